@@ -0,0 +1,387 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Hierarchical deterministic (HD) keys, derived from a BIP-39 mnemonic.
+//!
+//! The mnemonic is turned into a 64-byte seed, from which the keys of each scheme are derived
+//! along a BIP-44 path:
+//! * Ed25519 keys use SLIP-10, where all levels are hardened:
+//!   `m/44'/<LINERA_COIN_TYPE>'/0'/0'/<index>'`,
+//! * secp256k1 keys use BIP-32: `m/44'/<LINERA_COIN_TYPE>'/0'/0/<index>`,
+//! * EVM keys use BIP-32 on the Ethereum path `m/44'/60'/0'/0/<index>`, so that they match
+//!   the accounts of Ethereum wallets restored from the same mnemonic.
+
+use std::fmt;
+
+use ed25519_dalek as dalek;
+use hmac::{Hmac, Mac as _};
+use k256::elliptic_curve::{sec1::ToEncodedPoint as _, PrimeField as _};
+use serde::{Deserialize, Serialize};
+use sha2::Sha512;
+use thiserror::Error;
+
+#[cfg(with_getrandom)]
+use super::CryptoRng;
+use super::{AccountSecretKey, Ed25519SecretKey, EvmSecretKey, Secp256k1SecretKey};
+
+/// The BIP-44 coin type of Linera keys. It spells "LIN" in ASCII, and is not registered in
+/// SLIP-44.
+pub const LINERA_COIN_TYPE: u32 = 0x4c494e;
+/// The BIP-44 coin type of Ethereum.
+pub const ETHEREUM_COIN_TYPE: u32 = 60;
+
+/// The bit that marks a hardened derivation index.
+const HARDENED: u32 = 1 << 31;
+
+/// The number of key indices of each scheme: the last level of the path is hardened for
+/// Ed25519 keys, so only indices below 2^31 are available.
+pub const NUM_KEY_INDICES: u32 = HARDENED;
+
+/// An error when deriving HD keys.
+#[derive(Debug, Error)]
+pub enum HdError {
+    /// The mnemonic is not a valid BIP-39 English phrase.
+    #[error("invalid mnemonic: {0}")]
+    InvalidMnemonic(#[from] bip39::Error),
+    /// The derivation produced an invalid key. This happens with negligible probability.
+    #[error("the derivation path {0} does not lead to a valid key")]
+    InvalidKey(DerivationPath),
+    /// The key index is too high to be derived.
+    #[error("the key index {0} is out of range; indices must be lower than 2^31")]
+    IndexOutOfRange(u32),
+}
+
+/// The kinds of keys that can be derived from a seed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(any(web, not(target_arch = "wasm32")), derive(clap::ValueEnum))]
+pub enum HdKeyScheme {
+    /// Ed25519 keys.
+    Ed25519,
+    /// secp256k1 keys.
+    Secp256k1,
+    /// secp256k1 keys of EVM accounts.
+    EvmSecp256k1,
+}
+
+impl Default for HdKeyScheme {
+    /// Returns the scheme of the keys that signers generate, see
+    /// [`AccountSecretKey::generate_from`].
+    fn default() -> Self {
+        if cfg!(feature = "revm") {
+            HdKeyScheme::EvmSecp256k1
+        } else {
+            HdKeyScheme::Ed25519
+        }
+    }
+}
+
+impl HdKeyScheme {
+    /// All the schemes that keys can be derived for.
+    pub const ALL: [HdKeyScheme; 3] = [
+        HdKeyScheme::Ed25519,
+        HdKeyScheme::Secp256k1,
+        HdKeyScheme::EvmSecp256k1,
+    ];
+
+    /// Returns the path of the key with the given index. Only the low 31 bits of the index
+    /// are used; see [`NUM_KEY_INDICES`].
+    pub fn derivation_path(self, index: u32) -> DerivationPath {
+        let index = index & !HARDENED;
+        let indices = match self {
+            HdKeyScheme::Ed25519 => vec![
+                44 | HARDENED,
+                LINERA_COIN_TYPE | HARDENED,
+                HARDENED,
+                HARDENED,
+                index | HARDENED,
+            ],
+            HdKeyScheme::Secp256k1 => {
+                vec![
+                    44 | HARDENED,
+                    LINERA_COIN_TYPE | HARDENED,
+                    HARDENED,
+                    0,
+                    index,
+                ]
+            }
+            HdKeyScheme::EvmSecp256k1 => {
+                vec![
+                    44 | HARDENED,
+                    ETHEREUM_COIN_TYPE | HARDENED,
+                    HARDENED,
+                    0,
+                    index,
+                ]
+            }
+        };
+        DerivationPath(indices)
+    }
+}
+
+/// A BIP-32 derivation path, i.e. a sequence of child indices where hardened ones have the
+/// highest bit set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DerivationPath(pub Vec<u32>);
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+        for index in &self.0 {
+            if index & HARDENED != 0 {
+                write!(f, "/{}'", index & !HARDENED)?;
+            } else {
+                write!(f, "/{index}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The seed that HD keys are derived from.
+///
+/// The `Clone` trait is deliberately not implemented, to prevent accidental copies.
+#[derive(PartialEq, Eq)]
+pub struct HdSeed(Box<[u8; 64]>);
+
+impl fmt::Debug for HdSeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HdSeed(<redacted>)")
+    }
+}
+
+impl HdSeed {
+    /// Generates a new 24-word English mnemonic from the given RNG.
+    #[cfg(with_getrandom)]
+    pub fn generate_mnemonic<R: CryptoRng>(rng: &mut R) -> String {
+        let mut entropy = [0u8; 32];
+        rng.fill_bytes(&mut entropy);
+        bip39::Mnemonic::from_entropy(&entropy)
+            .expect("32 bytes are a valid entropy length")
+            .to_string()
+    }
+
+    /// Checks the mnemonic and computes its seed, using the optional BIP-39 passphrase.
+    pub fn from_mnemonic(mnemonic: &str, passphrase: &str) -> Result<Self, HdError> {
+        let mnemonic = bip39::Mnemonic::parse_in(bip39::Language::English, mnemonic)?;
+        Ok(Self(Box::new(mnemonic.to_seed(passphrase))))
+    }
+
+    /// Creates a seed from its raw bytes.
+    pub fn from_bytes(bytes: [u8; 64]) -> Self {
+        Self(Box::new(bytes))
+    }
+
+    /// Returns the raw bytes of the seed.
+    pub fn as_bytes(&self) -> &[u8; 64] {
+        &self.0
+    }
+
+    /// Copies the seed.
+    pub fn copy(&self) -> Self {
+        Self(self.0.clone())
+    }
+
+    /// Derives the key of the given scheme with the given index, which must be lower than
+    /// [`NUM_KEY_INDICES`].
+    pub fn derive(&self, scheme: HdKeyScheme, index: u32) -> Result<AccountSecretKey, HdError> {
+        if index >= NUM_KEY_INDICES {
+            return Err(HdError::IndexOutOfRange(index));
+        }
+        let seed = self.as_bytes();
+        let path = scheme.derivation_path(index);
+        Ok(match scheme {
+            HdKeyScheme::Ed25519 => {
+                let key = derive_ed25519(seed, &path);
+                AccountSecretKey::Ed25519(Ed25519SecretKey(dalek::SigningKey::from_bytes(&key)))
+            }
+            HdKeyScheme::Secp256k1 => {
+                AccountSecretKey::Secp256k1(Secp256k1SecretKey(derive_secp256k1(seed, &path)?))
+            }
+            HdKeyScheme::EvmSecp256k1 => {
+                AccountSecretKey::EvmSecp256k1(EvmSecretKey(derive_secp256k1(seed, &path)?))
+            }
+        })
+    }
+}
+
+/// Derives an Ed25519 secret key following SLIP-10, where every level is hardened.
+fn derive_ed25519(seed: &[u8], path: &DerivationPath) -> [u8; 32] {
+    let (mut key, mut chain_code) = hmac_sha512(b"ed25519 seed", &[seed]);
+    for index in &path.0 {
+        let index = index | HARDENED;
+        (key, chain_code) = hmac_sha512(&chain_code, &[&[0], &key, &index.to_be_bytes()]);
+    }
+    key
+}
+
+/// Derives a secp256k1 secret key following BIP-32.
+fn derive_secp256k1(
+    seed: &[u8],
+    path: &DerivationPath,
+) -> Result<k256::ecdsa::SigningKey, HdError> {
+    let invalid = || HdError::InvalidKey(path.clone());
+    let (key, mut chain_code) = hmac_sha512(b"Bitcoin seed", &[seed]);
+    let mut key = Option::<k256::NonZeroScalar>::from(k256::NonZeroScalar::from_repr(key.into()))
+        .ok_or_else(invalid)?;
+    for index in &path.0 {
+        let (tweak, next_chain_code) = if index & HARDENED != 0 {
+            hmac_sha512(&chain_code, &[&[0], &key.to_repr(), &index.to_be_bytes()])
+        } else {
+            let public_key = k256::PublicKey::from_secret_scalar(&key);
+            let public_key = public_key.to_encoded_point(true);
+            hmac_sha512(&chain_code, &[public_key.as_bytes(), &index.to_be_bytes()])
+        };
+        let tweak = Option::<k256::Scalar>::from(k256::Scalar::from_repr(tweak.into()))
+            .ok_or_else(invalid)?;
+        key = Option::from(k256::NonZeroScalar::new(*key + tweak)).ok_or_else(invalid)?;
+        chain_code = next_chain_code;
+    }
+    Ok(k256::ecdsa::SigningKey::from(key))
+}
+
+/// Computes HMAC-SHA512 of the concatenated `data`, and splits it into two halves.
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    for part in data {
+        mac.update(part);
+    }
+    let output = mac.finalize().into_bytes();
+    let mut left = [0; 32];
+    let mut right = [0; 32];
+    left.copy_from_slice(&output[..32]);
+    right.copy_from_slice(&output[32..]);
+    (left, right)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The seed of the first test vectors of BIP-32 and SLIP-10.
+    const TEST_VECTOR_SEED: [u8; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+
+    #[test]
+    fn slip10_ed25519_test_vector() {
+        let path = DerivationPath(vec![0, 1, 2, 2, 1_000_000_000]);
+        assert_eq!(
+            hex::encode(derive_ed25519(&TEST_VECTOR_SEED, &DerivationPath(vec![]))),
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7"
+        );
+        assert_eq!(
+            hex::encode(derive_ed25519(&TEST_VECTOR_SEED, &path)),
+            "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793"
+        );
+    }
+
+    #[test]
+    fn bip32_secp256k1_test_vector() {
+        let path = DerivationPath(vec![HARDENED, 1, 2 | HARDENED, 2, 1_000_000_000]);
+        let master = derive_secp256k1(&TEST_VECTOR_SEED, &DerivationPath(vec![])).unwrap();
+        assert_eq!(
+            hex::encode(master.to_bytes()),
+            "e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35"
+        );
+        let child = derive_secp256k1(&TEST_VECTOR_SEED, &path).unwrap();
+        assert_eq!(
+            hex::encode(child.to_bytes()),
+            "471b76e389e528d6de6d816857e012c5455051cad6660850e58372a6c3e6e7c8"
+        );
+    }
+
+    #[test]
+    fn derivation_paths() {
+        assert_eq!(
+            HdKeyScheme::EvmSecp256k1.derivation_path(3).to_string(),
+            "m/44'/60'/0'/0/3"
+        );
+        assert_eq!(
+            HdKeyScheme::Ed25519.derivation_path(3).to_string(),
+            format!("m/44'/{LINERA_COIN_TYPE}'/0'/0'/3'")
+        );
+    }
+
+    #[test]
+    fn indices_above_hardened_bit_are_rejected() {
+        let seed = HdSeed::from_bytes([7; 64]);
+        assert!(seed
+            .derive(HdKeyScheme::Ed25519, NUM_KEY_INDICES - 1)
+            .is_ok());
+        assert!(matches!(
+            seed.derive(HdKeyScheme::Ed25519, NUM_KEY_INDICES),
+            Err(HdError::IndexOutOfRange(NUM_KEY_INDICES))
+        ));
+    }
+
+    #[test]
+    fn anvil_test_mnemonic_matches_ethereum_wallets() {
+        let seed = HdSeed::from_mnemonic(
+            "test test test test test test test test test test test junk",
+            "",
+        )
+        .unwrap();
+        let AccountSecretKey::EvmSecp256k1(key) =
+            seed.derive(HdKeyScheme::EvmSecp256k1, 0).unwrap()
+        else {
+            panic!("expected an EVM key");
+        };
+        assert_eq!(
+            hex::encode(key.0.to_bytes()),
+            "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+        );
+    }
+
+    #[test]
+    fn invalid_mnemonics_are_rejected() {
+        assert!(HdSeed::from_mnemonic("test test test", "").is_err());
+        // The checksum of the last word is wrong.
+        assert!(HdSeed::from_mnemonic(
+            "test test test test test test test test test test test test",
+            ""
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn generated_mnemonics_are_valid() {
+        let mut rng: Box<dyn CryptoRng> = Some(42).into();
+        let mnemonic = HdSeed::generate_mnemonic(&mut rng);
+        assert_eq!(mnemonic.split_whitespace().count(), 24);
+        let seed = HdSeed::from_mnemonic(&mnemonic, "").unwrap();
+        let other = HdSeed::from_mnemonic(&mnemonic, "passphrase").unwrap();
+        assert_ne!(seed, other);
+    }
+
+    #[test]
+    fn signers_derive_keys_from_their_seed() {
+        let mnemonic = "test test test test test test test test test test test junk";
+        let seed = || HdSeed::from_mnemonic(mnemonic, "").unwrap();
+        let mut signer = crate::crypto::InMemorySigner::from_seed(seed());
+        assert!(signer.has_seed());
+        let first = signer.generate_new().unwrap();
+        assert_eq!(
+            first,
+            seed().derive(HdKeyScheme::default(), 0).unwrap().public()
+        );
+
+        // The next index survives serialization, and recovering a key moves it forward.
+        let json = serde_json::to_string(&signer).unwrap();
+        let mut signer: crate::crypto::InMemorySigner = serde_json::from_str(&json).unwrap();
+        signer.derive_key(HdKeyScheme::EvmSecp256k1, 5).unwrap();
+        let next = signer.derive_next(HdKeyScheme::EvmSecp256k1).unwrap();
+        assert_eq!(
+            next,
+            seed()
+                .derive(HdKeyScheme::EvmSecp256k1, 6)
+                .unwrap()
+                .public()
+        );
+        assert_ne!(signer.generate_new().unwrap(), first);
+        assert_eq!(signer.keys().len(), 4);
+
+        // Indices stop at 2^31 instead of wrapping around to the first keys.
+        signer
+            .derive_key(HdKeyScheme::EvmSecp256k1, NUM_KEY_INDICES - 1)
+            .unwrap();
+        assert!(signer.derive_next(HdKeyScheme::EvmSecp256k1).is_err());
+    }
+}
@@ -6,6 +6,7 @@
 
 mod ed25519;
 mod hash;
+pub mod hd;
 #[allow(dead_code)]
 mod secp256k1;
 pub mod signer;
@@ -76,6 +76,12 @@ impl Allocative for Secp256k1Signature {
     }
 }
 
+impl Hash for Secp256k1Signature {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_bytes().hash(state);
+    }
+}
+
 impl Secp256k1PublicKey {
     /// A fake public key used for testing.
     #[cfg(all(with_testing, not(target_arch = "wasm32")))]
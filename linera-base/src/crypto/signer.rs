@@ -64,9 +64,12 @@ mod in_mem {
     use serde::{Deserialize, Serialize};
 
     #[cfg(with_getrandom)]
-    use crate::crypto::{AccountPublicKey, CryptoRng};
+    use crate::crypto::CryptoRng;
     use crate::{
-        crypto::{AccountSecretKey, AccountSignature, CryptoHash, Signer},
+        crypto::{
+            hd::{HdError, HdKeyScheme, HdSeed, NUM_KEY_INDICES},
+            AccountPublicKey, AccountSecretKey, AccountSignature, CryptoHash, Signer,
+        },
         identifiers::AccountOwner,
     };
 
@@ -74,6 +77,10 @@ mod in_mem {
     pub enum Error {
         #[error("no key found for the given owner")]
         NoSuchOwner,
+        #[error("the signer has no seed to derive keys from")]
+        NoSeed,
+        #[error(transparent)]
+        Derivation(#[from] HdError),
     }
 
     /// In-memory signer.
@@ -101,10 +108,36 @@ mod in_mem {
             InMemorySigner(Arc::new(RwLock::new(InMemSignerInner::new())))
         }
 
-        /// Generates a new key pair from Signer's RNG. Use with care.
+        /// Creates a new [`InMemorySigner`] whose keys are derived from the given seed.
+        pub fn from_seed(seed: HdSeed) -> Self {
+            let inner = InMemSignerInner {
+                hd: Some(HdState {
+                    seed,
+                    next_indices: BTreeMap::new(),
+                }),
+                ..InMemSignerInner::default()
+            };
+            InMemorySigner(Arc::new(RwLock::new(inner)))
+        }
+
+        /// Returns whether the keys of this signer are derived from a seed.
+        pub fn has_seed(&self) -> bool {
+            self.0.read().unwrap().hd.is_some()
+        }
+
+        /// Generates a new key pair: the next derived key if the signer has a seed, or one from
+        /// the Signer's RNG otherwise. Use with care.
+        ///
+        /// Fails if the signer has a seed and all of its key indices are used.
         #[cfg(with_getrandom)]
-        pub fn generate_new(&mut self) -> AccountPublicKey {
+        pub fn generate_new(&mut self) -> Result<AccountPublicKey, Error> {
             let mut inner = self.0.write().unwrap();
+            if let Some(hd) = &mut inner.hd {
+                let secret = hd.derive_next(HdKeyScheme::default())?;
+                let public = secret.public();
+                inner.keys.insert(AccountOwner::from(public), secret);
+                return Ok(public);
+            }
             let secret = AccountSecretKey::generate_from(&mut inner.rng_state.prng);
             if inner.rng_state.testing_seed.is_some() {
                 // Generate a new testing seed for the case when we need to store the PRNG state.
@@ -116,6 +149,57 @@ mod in_mem {
             let public = secret.public();
             let owner = AccountOwner::from(public);
             inner.keys.insert(owner, secret);
+            Ok(public)
+        }
+
+        /// Derives the next key of the given scheme from the signer's seed.
+        pub fn derive_next(&mut self, scheme: HdKeyScheme) -> Result<AccountPublicKey, Error> {
+            let mut inner = self.0.write().unwrap();
+            let secret = inner
+                .hd
+                .as_mut()
+                .ok_or(Error::NoSeed)?
+                .derive_next(scheme)?;
+            let public = secret.public();
+            inner.keys.insert(AccountOwner::from(public), secret);
+            Ok(public)
+        }
+
+        /// Derives the key of the given scheme with the given index from the signer's seed,
+        /// e.g. to recover it. Later calls to [`Self::derive_next`] use higher indices.
+        pub fn derive_key(
+            &mut self,
+            scheme: HdKeyScheme,
+            index: u32,
+        ) -> Result<AccountPublicKey, Error> {
+            let mut inner = self.0.write().unwrap();
+            let hd = inner.hd.as_mut().ok_or(Error::NoSeed)?;
+            let secret = hd.seed.derive(scheme, index)?;
+            let next_index = hd.next_indices.entry(scheme).or_default();
+            *next_index = (*next_index).max(index.saturating_add(1));
+            let public = secret.public();
+            inner.keys.insert(AccountOwner::from(public), secret);
+            Ok(public)
+        }
+
+        /// Returns the public key of the given scheme with the given index, without adding
+        /// its secret key to the signer.
+        pub fn derived_public_key(
+            &self,
+            scheme: HdKeyScheme,
+            index: u32,
+        ) -> Result<AccountPublicKey, Error> {
+            let inner = self.0.read().unwrap();
+            let hd = inner.hd.as_ref().ok_or(Error::NoSeed)?;
+            Ok(hd.seed.derive(scheme, index)?.public())
+        }
+
+        /// Adds a secret key, e.g. one that was exported from another signer, and returns
+        /// its public key.
+        pub fn add_key(&mut self, secret: AccountSecretKey) -> AccountPublicKey {
+            let public = secret.public();
+            let mut inner = self.0.write().unwrap();
+            inner.keys.insert(AccountOwner::from(public), secret);
             public
         }
 
@@ -131,6 +215,14 @@ mod in_mem {
         keys: Vec<(AccountOwner, String)>,
         #[cfg(with_getrandom)]
         prng_seed: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        hd: Option<HdInner>,
+    }
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct HdInner {
+        seed: String,
+        next_indices: BTreeMap<HdKeyScheme, u32>,
     }
 
     /// In-memory signer.
@@ -138,6 +230,32 @@ mod in_mem {
         keys: BTreeMap<AccountOwner, AccountSecretKey>,
         #[cfg(with_getrandom)]
         rng_state: RngState,
+        hd: Option<HdState>,
+    }
+
+    /// The seed that keys are derived from, with the next index to use for each scheme.
+    struct HdState {
+        seed: HdSeed,
+        next_indices: BTreeMap<HdKeyScheme, u32>,
+    }
+
+    impl HdState {
+        /// Derives the key with the next index. Indices that don't lead to a valid key are
+        /// skipped, as specified by BIP-32. Fails once all the indices are used.
+        fn derive_next(&mut self, scheme: HdKeyScheme) -> Result<AccountSecretKey, HdError> {
+            let next_index = self.next_indices.entry(scheme).or_default();
+            loop {
+                let index = *next_index;
+                if index >= NUM_KEY_INDICES {
+                    return Err(HdError::IndexOutOfRange(index));
+                }
+                *next_index = index + 1;
+                match self.seed.derive(scheme, index) {
+                    Err(HdError::InvalidKey(_)) => continue,
+                    result => return result,
+                }
+            }
+        }
     }
 
     #[cfg(with_getrandom)]
@@ -167,6 +285,7 @@ mod in_mem {
             InMemSignerInner {
                 keys: BTreeMap::new(),
                 rng_state: RngState::new(prng_seed),
+                hd: None,
             }
         }
 
@@ -175,6 +294,7 @@ mod in_mem {
         pub fn new() -> Self {
             InMemSignerInner {
                 keys: BTreeMap::new(),
+                hd: None,
             }
         }
 
@@ -222,6 +342,7 @@ mod in_mem {
                 keys: BTreeMap::from_iter(input),
                 #[cfg(with_getrandom)]
                 rng_state: RngState::new(None),
+                hd: None,
             })))
         }
     }
@@ -270,10 +391,16 @@ mod in_mem {
                 .map(|(owner, bytes)| (owner, hex::encode(bytes)))
                 .collect::<Vec<_>>();
 
+            let hd = self.hd.as_ref().map(|hd| HdInner {
+                seed: hex::encode(hd.seed.as_bytes()),
+                next_indices: hd.next_indices.clone(),
+            });
+
             let inner = Inner {
                 keys: keys_as_strings,
                 #[cfg(with_getrandom)]
                 prng_seed,
+                hd,
             };
 
             Inner::serialize(&inner, serializer)
@@ -299,10 +426,23 @@ mod in_mem {
                 })
                 .collect::<Result<BTreeMap<_, _>, _>>()?;
 
+            let hd = inner
+                .hd
+                .map(|hd| {
+                    let mut seed = [0; 64];
+                    hex::decode_to_slice(&hd.seed, &mut seed).map_err(serde::de::Error::custom)?;
+                    Ok(HdState {
+                        seed: HdSeed::from_bytes(seed),
+                        next_indices: hd.next_indices,
+                    })
+                })
+                .transpose()?;
+
             let signer = InMemSignerInner {
                 keys,
                 #[cfg(with_getrandom)]
                 rng_state: RngState::new(inner.prng_seed),
+                hd,
             };
             Ok(signer)
         }
@@ -29,10 +29,11 @@ use tracing::instrument;
 #[cfg(with_metrics)]
 use crate::prometheus_util::MeasureLatency as _;
 use crate::{
-    crypto::{BcsHashable, CryptoError, CryptoHash},
+    crypto::{BcsHashable, CryptoError, CryptoHash, ValidatorPublicKey, ValidatorSignature},
     doc_scalar, hex_debug, http,
     identifiers::{
-        ApplicationId, BlobId, BlobType, ChainId, EventId, GenericApplicationId, ModuleId, StreamId,
+        AccountOwner, ApplicationId, BlobId, BlobType, ChainId, EventId, GenericApplicationId,
+        ModuleId, StreamId,
     },
     limited_writer::{LimitedWriter, LimitedWriterError},
     ownership::ChainOwnership,
@@ -1066,10 +1067,25 @@ pub enum OracleResponse {
     ),
     /// An event exists.
     EventExists(EventId),
+    /// The signatures that confirmed the previous block, from which random seeds are derived.
+    RandomSeed(ConfirmationSignatures),
 }
 
 impl BcsHashable<'_> for OracleResponse {}
 
+/// The validator signatures of a confirmed block certificate.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize, Allocative)]
+pub struct ConfirmationSignatures {
+    /// The epoch of the confirmed block.
+    pub epoch: Epoch,
+    /// The round in which the block was confirmed.
+    pub round: Round,
+    /// The signatures of the validators.
+    pub signatures: Vec<(ValidatorPublicKey, ValidatorSignature)>,
+}
+
+impl BcsHashable<'_> for ConfirmationSignatures {}
+
 /// Description of a user application.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Hash, Serialize, WitType, WitLoad, WitStore)]
 pub struct ApplicationDescription {
@@ -1087,6 +1103,17 @@ pub struct ApplicationDescription {
     pub parameters: Vec<u8>,
     /// Required dependencies.
     pub required_application_ids: Vec<ApplicationId>,
+    /// Whether the application accepts reentrant calls, i.e. calls made while it is already
+    /// executing. These are executed by a separate instance of its contract.
+    pub allow_reentrancy: bool,
+    /// The owner allowed to replace the application's module, if it is upgradable.
+    #[debug(skip_if = Option::is_none)]
+    pub upgrade_authority: Option<AccountOwner>,
+    /// The modules that the application used before `module_id`, oldest first. This is
+    /// empty in the description the application is created with, whose hash is the
+    /// application ID, and grows with every upgrade.
+    #[debug(skip_if = Vec::is_empty)]
+    pub module_history: Vec<ModuleId>,
 }
 
 impl From<&ApplicationDescription> for ApplicationId {
@@ -1107,6 +1134,29 @@ impl ApplicationDescription {
         bcs::to_bytes(self).expect("Serializing blob bytes should not fail!")
     }
 
+    /// Returns the description that the application was created with, before any upgrade.
+    pub fn original(&self) -> ApplicationDescription {
+        let Some(module_id) = self.module_history.first() else {
+            return self.clone();
+        };
+        ApplicationDescription {
+            module_id: *module_id,
+            module_history: Vec::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Returns the description after replacing the module with `module_id`.
+    pub fn upgraded(&self, module_id: ModuleId) -> ApplicationDescription {
+        let mut module_history = self.module_history.clone();
+        module_history.push(self.module_id);
+        ApplicationDescription {
+            module_id,
+            module_history,
+            ..self.clone()
+        }
+    }
+
     /// Gets the `BlobId` of the contract
     pub fn contract_bytecode_blob_id(&self) -> BlobId {
         self.module_id.contract_bytecode_blob_id()
@@ -21,3 +21,6 @@ pub const WRAPPED_FUNGIBLE_TYPES_SOURCE: &str =
 
 /// Solidity source for the FungibleBridge contract.
 pub const FUNGIBLE_BRIDGE_SOURCE: &str = include_str!("../solidity/FungibleBridge.sol");
+
+/// Solidity source for the ViewProof library, which verifies Merkle proofs of view entries.
+pub const VIEW_PROOF_SOURCE: &str = include_str!("../solidity/ViewProof.sol");
@@ -455,6 +455,45 @@ library BridgeTypes {
         return value;
     }
 
+    struct ApplicationOptions {
+        opt_AccountOwner upgrade_authority;
+        bool allow_reentrancy;
+    }
+
+    function bcs_serialize_ApplicationOptions(ApplicationOptions memory input)
+        internal
+        pure
+        returns (bytes memory)
+    {
+        bytes memory result = bcs_serialize_opt_AccountOwner(input.upgrade_authority);
+        return abi.encodePacked(result, bcs_serialize_bool(input.allow_reentrancy));
+    }
+
+    function bcs_deserialize_offset_ApplicationOptions(uint256 pos, bytes memory input)
+        internal
+        pure
+        returns (uint256, ApplicationOptions memory)
+    {
+        uint256 new_pos;
+        opt_AccountOwner memory upgrade_authority;
+        (new_pos, upgrade_authority) = bcs_deserialize_offset_opt_AccountOwner(pos, input);
+        bool allow_reentrancy;
+        (new_pos, allow_reentrancy) = bcs_deserialize_offset_bool(new_pos, input);
+        return (new_pos, ApplicationOptions(upgrade_authority, allow_reentrancy));
+    }
+
+    function bcs_deserialize_ApplicationOptions(bytes memory input)
+        internal
+        pure
+        returns (ApplicationOptions memory)
+    {
+        uint256 new_pos;
+        ApplicationOptions memory value;
+        (new_pos, value) = bcs_deserialize_offset_ApplicationOptions(0, input);
+        require(new_pos == input.length, "incomplete deserialization");
+        return value;
+    }
+
     struct ApplicationPermissions {
         opt_seq_ApplicationId execute_operations;
         ApplicationId[] mandatory_applications;
@@ -974,6 +1013,49 @@ library BridgeTypes {
         return value;
     }
 
+    struct ConfirmationSignatures {
+        Epoch epoch;
+        Round round;
+        tuple_Secp256k1PublicKey_Secp256k1Signature[] signatures;
+    }
+
+    function bcs_serialize_ConfirmationSignatures(ConfirmationSignatures memory input)
+        internal
+        pure
+        returns (bytes memory)
+    {
+        bytes memory result = bcs_serialize_Epoch(input.epoch);
+        result = abi.encodePacked(result, bcs_serialize_Round(input.round));
+        return abi.encodePacked(result, bcs_serialize_seq_tuple_Secp256k1PublicKey_Secp256k1Signature(input.signatures));
+    }
+
+    function bcs_deserialize_offset_ConfirmationSignatures(uint256 pos, bytes memory input)
+        internal
+        pure
+        returns (uint256, ConfirmationSignatures memory)
+    {
+        uint256 new_pos;
+        Epoch memory epoch;
+        (new_pos, epoch) = bcs_deserialize_offset_Epoch(pos, input);
+        Round memory round;
+        (new_pos, round) = bcs_deserialize_offset_Round(new_pos, input);
+        tuple_Secp256k1PublicKey_Secp256k1Signature[] memory signatures;
+        (new_pos, signatures) = bcs_deserialize_offset_seq_tuple_Secp256k1PublicKey_Secp256k1Signature(new_pos, input);
+        return (new_pos, ConfirmationSignatures(epoch, round, signatures));
+    }
+
+    function bcs_deserialize_ConfirmationSignatures(bytes memory input)
+        internal
+        pure
+        returns (ConfirmationSignatures memory)
+    {
+        uint256 new_pos;
+        ConfirmationSignatures memory value;
+        (new_pos, value) = bcs_deserialize_offset_ConfirmationSignatures(0, input);
+        require(new_pos == input.length, "incomplete deserialization");
+        return value;
+    }
+
     struct ConfirmedBlockCertificate {
         Block value;
         Round round;
@@ -1885,6 +1967,8 @@ library BridgeTypes {
         OracleResponse_Event event_;
         // choice=6 corresponds to EventExists
         EventId event_exists;
+        // choice=7 corresponds to RandomSeed
+        ConfirmationSignatures random_seed;
     }
 
     function OracleResponse_case_service(bytes memory service)
@@ -1897,7 +1981,8 @@ library BridgeTypes {
         opt_uint32 memory round;
         OracleResponse_Event memory event_;
         EventId memory event_exists;
-        return OracleResponse(uint8(0), service, http, blob, round, event_, event_exists);
+        ConfirmationSignatures memory random_seed;
+        return OracleResponse(uint8(0), service, http, blob, round, event_, event_exists, random_seed);
     }
 
     function OracleResponse_case_http(Response memory http)
@@ -1910,7 +1995,8 @@ library BridgeTypes {
         opt_uint32 memory round;
         OracleResponse_Event memory event_;
         EventId memory event_exists;
-        return OracleResponse(uint8(1), service, http, blob, round, event_, event_exists);
+        ConfirmationSignatures memory random_seed;
+        return OracleResponse(uint8(1), service, http, blob, round, event_, event_exists, random_seed);
     }
 
     function OracleResponse_case_blob(BlobId memory blob)
@@ -1923,7 +2009,8 @@ library BridgeTypes {
         opt_uint32 memory round;
         OracleResponse_Event memory event_;
         EventId memory event_exists;
-        return OracleResponse(uint8(2), service, http, blob, round, event_, event_exists);
+        ConfirmationSignatures memory random_seed;
+        return OracleResponse(uint8(2), service, http, blob, round, event_, event_exists, random_seed);
     }
 
     function OracleResponse_case_assert()
@@ -1937,7 +2024,8 @@ library BridgeTypes {
         opt_uint32 memory round;
         OracleResponse_Event memory event_;
         EventId memory event_exists;
-        return OracleResponse(uint8(3), service, http, blob, round, event_, event_exists);
+        ConfirmationSignatures memory random_seed;
+        return OracleResponse(uint8(3), service, http, blob, round, event_, event_exists, random_seed);
     }
 
     function OracleResponse_case_round(opt_uint32 memory round)
@@ -1950,7 +2038,8 @@ library BridgeTypes {
         BlobId memory blob;
         OracleResponse_Event memory event_;
         EventId memory event_exists;
-        return OracleResponse(uint8(4), service, http, blob, round, event_, event_exists);
+        ConfirmationSignatures memory random_seed;
+        return OracleResponse(uint8(4), service, http, blob, round, event_, event_exists, random_seed);
     }
 
     function OracleResponse_case_event(OracleResponse_Event memory event_)
@@ -1963,7 +2052,8 @@ library BridgeTypes {
         BlobId memory blob;
         opt_uint32 memory round;
         EventId memory event_exists;
-        return OracleResponse(uint8(5), service, http, blob, round, event_, event_exists);
+        ConfirmationSignatures memory random_seed;
+        return OracleResponse(uint8(5), service, http, blob, round, event_, event_exists, random_seed);
     }
 
     function OracleResponse_case_event_exists(EventId memory event_exists)
@@ -1976,7 +2066,22 @@ library BridgeTypes {
         BlobId memory blob;
         opt_uint32 memory round;
         OracleResponse_Event memory event_;
-        return OracleResponse(uint8(6), service, http, blob, round, event_, event_exists);
+        ConfirmationSignatures memory random_seed;
+        return OracleResponse(uint8(6), service, http, blob, round, event_, event_exists, random_seed);
+    }
+
+    function OracleResponse_case_random_seed(ConfirmationSignatures memory random_seed)
+        internal
+        pure
+        returns (OracleResponse memory)
+    {
+        bytes memory service;
+        Response memory http;
+        BlobId memory blob;
+        opt_uint32 memory round;
+        OracleResponse_Event memory event_;
+        EventId memory event_exists;
+        return OracleResponse(uint8(7), service, http, blob, round, event_, event_exists, random_seed);
     }
 
     function bcs_serialize_OracleResponse(OracleResponse memory input)
@@ -2002,6 +2107,9 @@ library BridgeTypes {
         if (input.choice == 6) {
             return abi.encodePacked(input.choice, bcs_serialize_EventId(input.event_exists));
         }
+        if (input.choice == 7) {
+            return abi.encodePacked(input.choice, bcs_serialize_ConfirmationSignatures(input.random_seed));
+        }
         return abi.encodePacked(input.choice);
     }
 
@@ -2037,8 +2145,12 @@ library BridgeTypes {
         if (choice == 6) {
             (new_pos, event_exists) = bcs_deserialize_offset_EventId(new_pos, input);
         }
-        require(choice < 7);
-        return (new_pos, OracleResponse(choice, service, http, blob, round, event_, event_exists));
+        ConfirmationSignatures memory random_seed;
+        if (choice == 7) {
+            (new_pos, random_seed) = bcs_deserialize_offset_ConfirmationSignatures(new_pos, input);
+        }
+        require(choice < 8);
+        return (new_pos, OracleResponse(choice, service, http, blob, round, event_, event_exists, random_seed));
     }
 
     function bcs_deserialize_OracleResponse(bytes memory input)
@@ -2420,6 +2532,45 @@ library BridgeTypes {
         return value;
     }
 
+    struct SponsorshipPolicy {
+        opt_seq_ApplicationId applications;
+        bool system_operations;
+    }
+
+    function bcs_serialize_SponsorshipPolicy(SponsorshipPolicy memory input)
+        internal
+        pure
+        returns (bytes memory)
+    {
+        bytes memory result = bcs_serialize_opt_seq_ApplicationId(input.applications);
+        return abi.encodePacked(result, bcs_serialize_bool(input.system_operations));
+    }
+
+    function bcs_deserialize_offset_SponsorshipPolicy(uint256 pos, bytes memory input)
+        internal
+        pure
+        returns (uint256, SponsorshipPolicy memory)
+    {
+        uint256 new_pos;
+        opt_seq_ApplicationId memory applications;
+        (new_pos, applications) = bcs_deserialize_offset_opt_seq_ApplicationId(pos, input);
+        bool system_operations;
+        (new_pos, system_operations) = bcs_deserialize_offset_bool(new_pos, input);
+        return (new_pos, SponsorshipPolicy(applications, system_operations));
+    }
+
+    function bcs_deserialize_SponsorshipPolicy(bytes memory input)
+        internal
+        pure
+        returns (SponsorshipPolicy memory)
+    {
+        uint256 new_pos;
+        SponsorshipPolicy memory value;
+        (new_pos, value) = bcs_deserialize_offset_SponsorshipPolicy(0, input);
+        require(new_pos == input.length, "incomplete deserialization");
+        return value;
+    }
+
     struct StreamId {
         GenericApplicationId application_id;
         StreamName stream_name;
@@ -2681,6 +2832,12 @@ library BridgeTypes {
         Epoch process_removed_epoch;
         // choice=13 corresponds to UpdateStream
         SystemOperation_UpdateStream update_stream;
+        // choice=14 corresponds to UpgradeApplication
+        SystemOperation_UpgradeApplication upgrade_application;
+        // choice=15 corresponds to Sponsor
+        SystemOperation_Sponsor sponsor;
+        // choice=16 corresponds to CreateApplicationWithOptions
+        SystemOperation_CreateApplicationWithOptions create_application_with_options;
     }
 
     function SystemOperation_case_transfer(SystemOperation_Transfer memory transfer_)
@@ -2700,7 +2857,10 @@ library BridgeTypes {
         Epoch memory process_new_epoch;
         Epoch memory process_removed_epoch;
         SystemOperation_UpdateStream memory update_stream;
-        return SystemOperation(uint8(0), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream);
+        SystemOperation_UpgradeApplication memory upgrade_application;
+        SystemOperation_Sponsor memory sponsor;
+        SystemOperation_CreateApplicationWithOptions memory create_application_with_options;
+        return SystemOperation(uint8(0), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream, upgrade_application, sponsor, create_application_with_options);
     }
 
     function SystemOperation_case_claim(SystemOperation_Claim memory claim)
@@ -2720,7 +2880,10 @@ library BridgeTypes {
         Epoch memory process_new_epoch;
         Epoch memory process_removed_epoch;
         SystemOperation_UpdateStream memory update_stream;
-        return SystemOperation(uint8(1), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream);
+        SystemOperation_UpgradeApplication memory upgrade_application;
+        SystemOperation_Sponsor memory sponsor;
+        SystemOperation_CreateApplicationWithOptions memory create_application_with_options;
+        return SystemOperation(uint8(1), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream, upgrade_application, sponsor, create_application_with_options);
     }
 
     function SystemOperation_case_open_chain(OpenChainConfig memory open_chain)
@@ -2740,7 +2903,10 @@ library BridgeTypes {
         Epoch memory process_new_epoch;
         Epoch memory process_removed_epoch;
         SystemOperation_UpdateStream memory update_stream;
-        return SystemOperation(uint8(2), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream);
+        SystemOperation_UpgradeApplication memory upgrade_application;
+        SystemOperation_Sponsor memory sponsor;
+        SystemOperation_CreateApplicationWithOptions memory create_application_with_options;
+        return SystemOperation(uint8(2), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream, upgrade_application, sponsor, create_application_with_options);
     }
 
     function SystemOperation_case_close_chain()
@@ -2761,7 +2927,10 @@ library BridgeTypes {
         Epoch memory process_new_epoch;
         Epoch memory process_removed_epoch;
         SystemOperation_UpdateStream memory update_stream;
-        return SystemOperation(uint8(3), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream);
+        SystemOperation_UpgradeApplication memory upgrade_application;
+        SystemOperation_Sponsor memory sponsor;
+        SystemOperation_CreateApplicationWithOptions memory create_application_with_options;
+        return SystemOperation(uint8(3), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream, upgrade_application, sponsor, create_application_with_options);
     }
 
     function SystemOperation_case_change_ownership(SystemOperation_ChangeOwnership memory change_ownership)
@@ -2781,7 +2950,10 @@ library BridgeTypes {
         Epoch memory process_new_epoch;
         Epoch memory process_removed_epoch;
         SystemOperation_UpdateStream memory update_stream;
-        return SystemOperation(uint8(4), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream);
+        SystemOperation_UpgradeApplication memory upgrade_application;
+        SystemOperation_Sponsor memory sponsor;
+        SystemOperation_CreateApplicationWithOptions memory create_application_with_options;
+        return SystemOperation(uint8(4), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream, upgrade_application, sponsor, create_application_with_options);
     }
 
     function SystemOperation_case_change_application_permissions(ApplicationPermissions memory change_application_permissions)
@@ -2801,7 +2973,10 @@ library BridgeTypes {
         Epoch memory process_new_epoch;
         Epoch memory process_removed_epoch;
         SystemOperation_UpdateStream memory update_stream;
-        return SystemOperation(uint8(5), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream);
+        SystemOperation_UpgradeApplication memory upgrade_application;
+        SystemOperation_Sponsor memory sponsor;
+        SystemOperation_CreateApplicationWithOptions memory create_application_with_options;
+        return SystemOperation(uint8(5), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream, upgrade_application, sponsor, create_application_with_options);
     }
 
     function SystemOperation_case_publish_module(SystemOperation_PublishModule memory publish_module)
@@ -2821,7 +2996,10 @@ library BridgeTypes {
         Epoch memory process_new_epoch;
         Epoch memory process_removed_epoch;
         SystemOperation_UpdateStream memory update_stream;
-        return SystemOperation(uint8(6), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream);
+        SystemOperation_UpgradeApplication memory upgrade_application;
+        SystemOperation_Sponsor memory sponsor;
+        SystemOperation_CreateApplicationWithOptions memory create_application_with_options;
+        return SystemOperation(uint8(6), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream, upgrade_application, sponsor, create_application_with_options);
     }
 
     function SystemOperation_case_publish_data_blob(SystemOperation_PublishDataBlob memory publish_data_blob)
@@ -2841,7 +3019,10 @@ library BridgeTypes {
         Epoch memory process_new_epoch;
         Epoch memory process_removed_epoch;
         SystemOperation_UpdateStream memory update_stream;
-        return SystemOperation(uint8(7), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream);
+        SystemOperation_UpgradeApplication memory upgrade_application;
+        SystemOperation_Sponsor memory sponsor;
+        SystemOperation_CreateApplicationWithOptions memory create_application_with_options;
+        return SystemOperation(uint8(7), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream, upgrade_application, sponsor, create_application_with_options);
     }
 
     function SystemOperation_case_verify_blob(SystemOperation_VerifyBlob memory verify_blob)
@@ -2861,7 +3042,10 @@ library BridgeTypes {
         Epoch memory process_new_epoch;
         Epoch memory process_removed_epoch;
         SystemOperation_UpdateStream memory update_stream;
-        return SystemOperation(uint8(8), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream);
+        SystemOperation_UpgradeApplication memory upgrade_application;
+        SystemOperation_Sponsor memory sponsor;
+        SystemOperation_CreateApplicationWithOptions memory create_application_with_options;
+        return SystemOperation(uint8(8), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream, upgrade_application, sponsor, create_application_with_options);
     }
 
     function SystemOperation_case_create_application(SystemOperation_CreateApplication memory create_application)
@@ -2881,7 +3065,10 @@ library BridgeTypes {
         Epoch memory process_new_epoch;
         Epoch memory process_removed_epoch;
         SystemOperation_UpdateStream memory update_stream;
-        return SystemOperation(uint8(9), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream);
+        SystemOperation_UpgradeApplication memory upgrade_application;
+        SystemOperation_Sponsor memory sponsor;
+        SystemOperation_CreateApplicationWithOptions memory create_application_with_options;
+        return SystemOperation(uint8(9), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream, upgrade_application, sponsor, create_application_with_options);
     }
 
     function SystemOperation_case_admin(AdminOperation memory admin)
@@ -2901,7 +3088,10 @@ library BridgeTypes {
         Epoch memory process_new_epoch;
         Epoch memory process_removed_epoch;
         SystemOperation_UpdateStream memory update_stream;
-        return SystemOperation(uint8(10), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream);
+        SystemOperation_UpgradeApplication memory upgrade_application;
+        SystemOperation_Sponsor memory sponsor;
+        SystemOperation_CreateApplicationWithOptions memory create_application_with_options;
+        return SystemOperation(uint8(10), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream, upgrade_application, sponsor, create_application_with_options);
     }
 
     function SystemOperation_case_process_new_epoch(Epoch memory process_new_epoch)
@@ -2921,7 +3111,10 @@ library BridgeTypes {
         AdminOperation memory admin;
         Epoch memory process_removed_epoch;
         SystemOperation_UpdateStream memory update_stream;
-        return SystemOperation(uint8(11), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream);
+        SystemOperation_UpgradeApplication memory upgrade_application;
+        SystemOperation_Sponsor memory sponsor;
+        SystemOperation_CreateApplicationWithOptions memory create_application_with_options;
+        return SystemOperation(uint8(11), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream, upgrade_application, sponsor, create_application_with_options);
     }
 
     function SystemOperation_case_process_removed_epoch(Epoch memory process_removed_epoch)
@@ -2941,7 +3134,10 @@ library BridgeTypes {
         AdminOperation memory admin;
         Epoch memory process_new_epoch;
         SystemOperation_UpdateStream memory update_stream;
-        return SystemOperation(uint8(12), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream);
+        SystemOperation_UpgradeApplication memory upgrade_application;
+        SystemOperation_Sponsor memory sponsor;
+        SystemOperation_CreateApplicationWithOptions memory create_application_with_options;
+        return SystemOperation(uint8(12), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream, upgrade_application, sponsor, create_application_with_options);
     }
 
     function SystemOperation_case_update_stream(SystemOperation_UpdateStream memory update_stream)
@@ -2961,7 +3157,79 @@ library BridgeTypes {
         AdminOperation memory admin;
         Epoch memory process_new_epoch;
         Epoch memory process_removed_epoch;
-        return SystemOperation(uint8(13), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream);
+        SystemOperation_UpgradeApplication memory upgrade_application;
+        SystemOperation_Sponsor memory sponsor;
+        SystemOperation_CreateApplicationWithOptions memory create_application_with_options;
+        return SystemOperation(uint8(13), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream, upgrade_application, sponsor, create_application_with_options);
+    }
+
+    function SystemOperation_case_upgrade_application(SystemOperation_UpgradeApplication memory upgrade_application)
+        internal
+        pure
+        returns (SystemOperation memory)
+    {
+        SystemOperation_Transfer memory transfer_;
+        SystemOperation_Claim memory claim;
+        OpenChainConfig memory open_chain;
+        SystemOperation_ChangeOwnership memory change_ownership;
+        ApplicationPermissions memory change_application_permissions;
+        SystemOperation_PublishModule memory publish_module;
+        SystemOperation_PublishDataBlob memory publish_data_blob;
+        SystemOperation_VerifyBlob memory verify_blob;
+        SystemOperation_CreateApplication memory create_application;
+        AdminOperation memory admin;
+        Epoch memory process_new_epoch;
+        Epoch memory process_removed_epoch;
+        SystemOperation_UpdateStream memory update_stream;
+        SystemOperation_Sponsor memory sponsor;
+        SystemOperation_CreateApplicationWithOptions memory create_application_with_options;
+        return SystemOperation(uint8(14), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream, upgrade_application, sponsor, create_application_with_options);
+    }
+
+    function SystemOperation_case_sponsor(SystemOperation_Sponsor memory sponsor)
+        internal
+        pure
+        returns (SystemOperation memory)
+    {
+        SystemOperation_Transfer memory transfer_;
+        SystemOperation_Claim memory claim;
+        OpenChainConfig memory open_chain;
+        SystemOperation_ChangeOwnership memory change_ownership;
+        ApplicationPermissions memory change_application_permissions;
+        SystemOperation_PublishModule memory publish_module;
+        SystemOperation_PublishDataBlob memory publish_data_blob;
+        SystemOperation_VerifyBlob memory verify_blob;
+        SystemOperation_CreateApplication memory create_application;
+        AdminOperation memory admin;
+        Epoch memory process_new_epoch;
+        Epoch memory process_removed_epoch;
+        SystemOperation_UpdateStream memory update_stream;
+        SystemOperation_UpgradeApplication memory upgrade_application;
+        SystemOperation_CreateApplicationWithOptions memory create_application_with_options;
+        return SystemOperation(uint8(15), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream, upgrade_application, sponsor, create_application_with_options);
+    }
+
+    function SystemOperation_case_create_application_with_options(SystemOperation_CreateApplicationWithOptions memory create_application_with_options)
+        internal
+        pure
+        returns (SystemOperation memory)
+    {
+        SystemOperation_Transfer memory transfer_;
+        SystemOperation_Claim memory claim;
+        OpenChainConfig memory open_chain;
+        SystemOperation_ChangeOwnership memory change_ownership;
+        ApplicationPermissions memory change_application_permissions;
+        SystemOperation_PublishModule memory publish_module;
+        SystemOperation_PublishDataBlob memory publish_data_blob;
+        SystemOperation_VerifyBlob memory verify_blob;
+        SystemOperation_CreateApplication memory create_application;
+        AdminOperation memory admin;
+        Epoch memory process_new_epoch;
+        Epoch memory process_removed_epoch;
+        SystemOperation_UpdateStream memory update_stream;
+        SystemOperation_UpgradeApplication memory upgrade_application;
+        SystemOperation_Sponsor memory sponsor;
+        return SystemOperation(uint8(16), transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream, upgrade_application, sponsor, create_application_with_options);
     }
 
     function bcs_serialize_SystemOperation(SystemOperation memory input)
@@ -3008,6 +3276,15 @@ library BridgeTypes {
         if (input.choice == 13) {
             return abi.encodePacked(input.choice, bcs_serialize_SystemOperation_UpdateStream(input.update_stream));
         }
+        if (input.choice == 14) {
+            return abi.encodePacked(input.choice, bcs_serialize_SystemOperation_UpgradeApplication(input.upgrade_application));
+        }
+        if (input.choice == 15) {
+            return abi.encodePacked(input.choice, bcs_serialize_SystemOperation_Sponsor(input.sponsor));
+        }
+        if (input.choice == 16) {
+            return abi.encodePacked(input.choice, bcs_serialize_SystemOperation_CreateApplicationWithOptions(input.create_application_with_options));
+        }
         return abi.encodePacked(input.choice);
     }
 
@@ -3071,8 +3348,20 @@ library BridgeTypes {
         if (choice == 13) {
             (new_pos, update_stream) = bcs_deserialize_offset_SystemOperation_UpdateStream(new_pos, input);
         }
-        require(choice < 14);
-        return (new_pos, SystemOperation(choice, transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream));
+        SystemOperation_UpgradeApplication memory upgrade_application;
+        if (choice == 14) {
+            (new_pos, upgrade_application) = bcs_deserialize_offset_SystemOperation_UpgradeApplication(new_pos, input);
+        }
+        SystemOperation_Sponsor memory sponsor;
+        if (choice == 15) {
+            (new_pos, sponsor) = bcs_deserialize_offset_SystemOperation_Sponsor(new_pos, input);
+        }
+        SystemOperation_CreateApplicationWithOptions memory create_application_with_options;
+        if (choice == 16) {
+            (new_pos, create_application_with_options) = bcs_deserialize_offset_SystemOperation_CreateApplicationWithOptions(new_pos, input);
+        }
+        require(choice < 17);
+        return (new_pos, SystemOperation(choice, transfer_, claim, open_chain, change_ownership, change_application_permissions, publish_module, publish_data_blob, verify_blob, create_application, admin, process_new_epoch, process_removed_epoch, update_stream, upgrade_application, sponsor, create_application_with_options));
     }
 
     function bcs_deserialize_SystemOperation(bytes memory input)
@@ -3236,6 +3525,57 @@ library BridgeTypes {
         return value;
     }
 
+    struct SystemOperation_CreateApplicationWithOptions {
+        ModuleId module_id;
+        bytes parameters;
+        bytes instantiation_argument;
+        ApplicationId[] required_application_ids;
+        ApplicationOptions options;
+    }
+
+    function bcs_serialize_SystemOperation_CreateApplicationWithOptions(SystemOperation_CreateApplicationWithOptions memory input)
+        internal
+        pure
+        returns (bytes memory)
+    {
+        bytes memory result = bcs_serialize_ModuleId(input.module_id);
+        result = abi.encodePacked(result, bcs_serialize_bytes(input.parameters));
+        result = abi.encodePacked(result, bcs_serialize_bytes(input.instantiation_argument));
+        result = abi.encodePacked(result, bcs_serialize_seq_ApplicationId(input.required_application_ids));
+        return abi.encodePacked(result, bcs_serialize_ApplicationOptions(input.options));
+    }
+
+    function bcs_deserialize_offset_SystemOperation_CreateApplicationWithOptions(uint256 pos, bytes memory input)
+        internal
+        pure
+        returns (uint256, SystemOperation_CreateApplicationWithOptions memory)
+    {
+        uint256 new_pos;
+        ModuleId memory module_id;
+        (new_pos, module_id) = bcs_deserialize_offset_ModuleId(pos, input);
+        bytes memory parameters;
+        (new_pos, parameters) = bcs_deserialize_offset_bytes(new_pos, input);
+        bytes memory instantiation_argument;
+        (new_pos, instantiation_argument) = bcs_deserialize_offset_bytes(new_pos, input);
+        ApplicationId[] memory required_application_ids;
+        (new_pos, required_application_ids) = bcs_deserialize_offset_seq_ApplicationId(new_pos, input);
+        ApplicationOptions memory options;
+        (new_pos, options) = bcs_deserialize_offset_ApplicationOptions(new_pos, input);
+        return (new_pos, SystemOperation_CreateApplicationWithOptions(module_id, parameters, instantiation_argument, required_application_ids, options));
+    }
+
+    function bcs_deserialize_SystemOperation_CreateApplicationWithOptions(bytes memory input)
+        internal
+        pure
+        returns (SystemOperation_CreateApplicationWithOptions memory)
+    {
+        uint256 new_pos;
+        SystemOperation_CreateApplicationWithOptions memory value;
+        (new_pos, value) = bcs_deserialize_offset_SystemOperation_CreateApplicationWithOptions(0, input);
+        require(new_pos == input.length, "incomplete deserialization");
+        return value;
+    }
+
     struct SystemOperation_PublishDataBlob {
         CryptoHash blob_hash;
     }
@@ -3306,6 +3646,53 @@ library BridgeTypes {
         return value;
     }
 
+    struct SystemOperation_Sponsor {
+        AccountOwner owner;
+        AccountOwner beneficiary;
+        Amount allowance;
+        SponsorshipPolicy policy;
+    }
+
+    function bcs_serialize_SystemOperation_Sponsor(SystemOperation_Sponsor memory input)
+        internal
+        pure
+        returns (bytes memory)
+    {
+        bytes memory result = bcs_serialize_AccountOwner(input.owner);
+        result = abi.encodePacked(result, bcs_serialize_AccountOwner(input.beneficiary));
+        result = abi.encodePacked(result, bcs_serialize_Amount(input.allowance));
+        return abi.encodePacked(result, bcs_serialize_SponsorshipPolicy(input.policy));
+    }
+
+    function bcs_deserialize_offset_SystemOperation_Sponsor(uint256 pos, bytes memory input)
+        internal
+        pure
+        returns (uint256, SystemOperation_Sponsor memory)
+    {
+        uint256 new_pos;
+        AccountOwner memory owner;
+        (new_pos, owner) = bcs_deserialize_offset_AccountOwner(pos, input);
+        AccountOwner memory beneficiary;
+        (new_pos, beneficiary) = bcs_deserialize_offset_AccountOwner(new_pos, input);
+        Amount memory allowance;
+        (new_pos, allowance) = bcs_deserialize_offset_Amount(new_pos, input);
+        SponsorshipPolicy memory policy;
+        (new_pos, policy) = bcs_deserialize_offset_SponsorshipPolicy(new_pos, input);
+        return (new_pos, SystemOperation_Sponsor(owner, beneficiary, allowance, policy));
+    }
+
+    function bcs_deserialize_SystemOperation_Sponsor(bytes memory input)
+        internal
+        pure
+        returns (SystemOperation_Sponsor memory)
+    {
+        uint256 new_pos;
+        SystemOperation_Sponsor memory value;
+        (new_pos, value) = bcs_deserialize_offset_SystemOperation_Sponsor(0, input);
+        require(new_pos == input.length, "incomplete deserialization");
+        return value;
+    }
+
     struct SystemOperation_Transfer {
         AccountOwner owner;
         Account recipient;
@@ -3396,6 +3783,45 @@ library BridgeTypes {
         return value;
     }
 
+    struct SystemOperation_UpgradeApplication {
+        ApplicationId application_id;
+        ModuleId module_id;
+    }
+
+    function bcs_serialize_SystemOperation_UpgradeApplication(SystemOperation_UpgradeApplication memory input)
+        internal
+        pure
+        returns (bytes memory)
+    {
+        bytes memory result = bcs_serialize_ApplicationId(input.application_id);
+        return abi.encodePacked(result, bcs_serialize_ModuleId(input.module_id));
+    }
+
+    function bcs_deserialize_offset_SystemOperation_UpgradeApplication(uint256 pos, bytes memory input)
+        internal
+        pure
+        returns (uint256, SystemOperation_UpgradeApplication memory)
+    {
+        uint256 new_pos;
+        ApplicationId memory application_id;
+        (new_pos, application_id) = bcs_deserialize_offset_ApplicationId(pos, input);
+        ModuleId memory module_id;
+        (new_pos, module_id) = bcs_deserialize_offset_ModuleId(new_pos, input);
+        return (new_pos, SystemOperation_UpgradeApplication(application_id, module_id));
+    }
+
+    function bcs_deserialize_SystemOperation_UpgradeApplication(bytes memory input)
+        internal
+        pure
+        returns (SystemOperation_UpgradeApplication memory)
+    {
+        uint256 new_pos;
+        SystemOperation_UpgradeApplication memory value;
+        (new_pos, value) = bcs_deserialize_offset_SystemOperation_UpgradeApplication(0, input);
+        require(new_pos == input.length, "incomplete deserialization");
+        return value;
+    }
+
     struct SystemOperation_VerifyBlob {
         BlobId blob_id;
     }
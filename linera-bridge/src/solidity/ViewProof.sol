@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.0;
+
+/// Verifies Merkle proofs of the entries of Linera views, as produced by
+/// `linera_views::proof::MerkleTree::prove` and `linera_views::proof::MerkleTreeView::prove`.
+///
+/// The path of an entry is given by the bits of keccak256(key), starting from the most
+/// significant one. A leaf is keccak256(0x00 ++ keccak256(key) ++ keccak256(value)) and an
+/// inner node is keccak256(0x01 ++ left ++ right). An empty subtree is zero, and a subtree
+/// with a single entry is the leaf of that entry.
+library ViewProof {
+    struct Proof {
+        /// Whether the key is present, with `value`.
+        bool present;
+        bytes value;
+        /// Whether the key is absent and its path ends with the leaf of another entry,
+        /// given by the hashes of its key and of its value.
+        bool hasOther;
+        bytes32 otherKeyHash;
+        bytes32 otherValueHash;
+        /// The siblings on the path of the key, starting from the root.
+        bytes32[] siblings;
+    }
+
+    /// Returns whether `key` is present in the tree with the given root, and its value,
+    /// reverting if the proof is invalid.
+    function verify(bytes32 root, bytes memory key, Proof memory proof)
+        internal
+        pure
+        returns (bool, bytes memory)
+    {
+        uint256 depth = proof.siblings.length;
+        require(depth <= 256, "malformed proof");
+        bytes32 keyHash = keccak256(key);
+        bytes32 hash;
+        if (proof.present) {
+            require(!proof.hasOther, "malformed proof");
+            hash = keccak256(abi.encodePacked(uint8(0), keyHash, keccak256(proof.value)));
+        } else if (proof.hasOther) {
+            require(proof.otherKeyHash != keyHash, "proof is about a different key");
+            if (depth > 0) {
+                // The other entry must be on the path of the key.
+                uint256 shift = 256 - depth;
+                require(
+                    uint256(proof.otherKeyHash) >> shift == uint256(keyHash) >> shift,
+                    "proof is about a different key"
+                );
+            }
+            hash = keccak256(abi.encodePacked(uint8(0), proof.otherKeyHash, proof.otherValueHash));
+        }
+        for (uint256 i = depth; i > 0; i--) {
+            bytes32 sibling = proof.siblings[i - 1];
+            if ((uint256(keyHash) >> (256 - i)) & 1 == 1) {
+                hash = keccak256(abi.encodePacked(uint8(1), sibling, hash));
+            } else {
+                hash = keccak256(abi.encodePacked(uint8(1), hash, sibling));
+            }
+        }
+        require(hash == root, "invalid proof");
+        return (proof.present, proof.value);
+    }
+
+    /// Returns the state hash of a block's header computed from its roots, as in
+    /// `linera_execution::StateRoots::state_hash`. The root of the balances and the root of
+    /// the application states can then be used to verify proofs of their entries.
+    function stateHash(bytes32 history, bytes32 balances, bytes32 applications) internal pure returns (bytes32) {
+        return keccak256(abi.encodePacked("ExecutionStateRootsHash::", history, balances, applications));
+    }
+}
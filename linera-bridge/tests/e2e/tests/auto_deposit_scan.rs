@@ -40,7 +40,7 @@ use linera_bridge_e2e::{
 };
 use linera_client::{chain_listener::ClientContext as _, client_context::ClientContext};
 use linera_core::environment::wallet::Memory;
-use linera_execution::{Operation, WasmRuntime};
+use linera_execution::{system::ApplicationOptions, Operation, WasmRuntime};
 use linera_faucet_client::Faucet;
 use linera_storage::{DbStorage, StorageCacheConfig};
 use linera_views::backends::memory::{MemoryDatabase, MemoryStoreConfig};
@@ -119,7 +119,7 @@ async fn test_auto_deposit_scan() -> anyhow::Result<()> {
 
     // Chain A: relay chain.
     tracing::info!("Claiming chain A (relay)...");
-    let owner_a = AccountOwner::from(signer.generate_new());
+    let owner_a = AccountOwner::from(signer.generate_new().unwrap());
     let chain_a_desc = faucet.claim(&owner_a).await?;
     let chain_a = chain_a_desc.id();
     ctx.extend_with_chain(chain_a_desc, Some(owner_a)).await?;
@@ -129,7 +129,7 @@ async fn test_auto_deposit_scan() -> anyhow::Result<()> {
 
     // Chain B: user chain.
     tracing::info!("Claiming chain B (user)...");
-    let owner_b = AccountOwner::from(signer.generate_new());
+    let owner_b = AccountOwner::from(signer.generate_new().unwrap());
     let chain_b_desc = faucet.claim(&owner_b).await?;
     let chain_b = chain_b_desc.id();
     ctx.extend_with_chain(chain_b_desc, Some(owner_b)).await?;
@@ -193,6 +193,7 @@ async fn test_auto_deposit_scan() -> anyhow::Result<()> {
             })?,
             serde_json::to_vec(&())?,
             vec![],
+            ApplicationOptions::default(),
         )
         .await?
         .expect("create evm-bridge app committed");
@@ -226,6 +227,7 @@ async fn test_auto_deposit_scan() -> anyhow::Result<()> {
                 accounts: BTreeMap::new(),
             })?,
             vec![],
+            ApplicationOptions::default(),
         )
         .await?
         .expect("create wrapped-fungible app committed");
@@ -346,9 +348,9 @@ async fn test_auto_deposit_scan() -> anyhow::Result<()> {
             None,
             relay_port,
             linera_storage_runtime::CommonStorageOptions::with_defaults().storage_cache_config(),
-            std::time::Duration::from_secs(5),  // monitor_scan_interval
-            0,  // monitor_start_block
-            5,  // max_retries
+            std::time::Duration::from_secs(5), // monitor_scan_interval
+            0,                                 // monitor_start_block
+            5,                                 // max_retries
             None,
         ))
         .await
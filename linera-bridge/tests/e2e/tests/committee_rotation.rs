@@ -57,7 +57,7 @@ async fn test_committee_rotation_updates_evm_light_client() -> anyhow::Result<()
     // Claim a chain for the relay so it can listen for admin chain notifications.
     let faucet = Faucet::new("http://localhost:8080".to_string());
     let mut signer = InMemorySigner::new(None);
-    let relay_owner = AccountOwner::from(signer.generate_new());
+    let relay_owner = AccountOwner::from(signer.generate_new().unwrap());
     let relay_chain_desc = faucet.claim(&relay_owner).await?;
     let relay_chain_id = relay_chain_desc.id();
     tracing::info!(%relay_chain_id, %relay_owner, "Relay chain claimed");
@@ -97,9 +97,9 @@ async fn test_committee_rotation_updates_evm_light_client() -> anyhow::Result<()
             Some(&light_client.to_string()),
             relay_port,
             linera_storage_runtime::CommonStorageOptions::with_defaults().storage_cache_config(),
-            std::time::Duration::from_secs(5),  // monitor_scan_interval
-            0,  // monitor_start_block
-            5,  // max_retries
+            std::time::Duration::from_secs(5), // monitor_scan_interval
+            0,                                 // monitor_start_block
+            5,                                 // max_retries
             None,
         ))
         .await
@@ -28,13 +28,12 @@ use linera_bridge::{
     proof::gen::{DepositProofClient as _, HttpDepositProofClient},
 };
 use linera_bridge_e2e::{
-    compose_file_path, exec_output, light_client_address, parse_deployed_address,
-    start_compose, wait_for_light_client,
-    ANVIL_PRIVATE_KEY,
+    compose_file_path, exec_output, light_client_address, parse_deployed_address, start_compose,
+    wait_for_light_client, ANVIL_PRIVATE_KEY,
 };
 use linera_client::{chain_listener::ClientContext as _, client_context::ClientContext};
 use linera_core::environment::wallet::Memory;
-use linera_execution::{Operation, Query, QueryResponse, WasmRuntime};
+use linera_execution::{system::ApplicationOptions, Operation, Query, QueryResponse, WasmRuntime};
 use linera_faucet_client::Faucet;
 use linera_storage::{DbStorage, StorageCacheConfig};
 use linera_views::backends::memory::{MemoryDatabase, MemoryStoreConfig};
@@ -113,7 +112,7 @@ async fn test_evm_to_linera_bridge() -> anyhow::Result<()> {
     .await?;
 
     tracing::info!("Claiming chain from faucet...");
-    let owner = AccountOwner::from(signer.generate_new());
+    let owner = AccountOwner::from(signer.generate_new().unwrap());
     let chain_desc = faucet.claim(&owner).await?;
     let chain_id = chain_desc.id();
     ctx.extend_with_chain(chain_desc, Some(owner)).await?;
@@ -156,8 +155,10 @@ async fn test_evm_to_linera_bridge() -> anyhow::Result<()> {
 
     // 4a. Publish and create wrapped-fungible app
     tracing::info!("Publishing wrapped-fungible module...");
-    let wf_contract = Bytecode::load_from_file(wasm_dir.join("wrapped_fungible_contract.wasm")).await?;
-    let wf_service = Bytecode::load_from_file(wasm_dir.join("wrapped_fungible_service.wasm")).await?;
+    let wf_contract =
+        Bytecode::load_from_file(wasm_dir.join("wrapped_fungible_contract.wasm")).await?;
+    let wf_service =
+        Bytecode::load_from_file(wasm_dir.join("wrapped_fungible_service.wasm")).await?;
 
     let (wf_module_id, _) = cc
         .publish_module(wf_contract, wf_service, VmRuntime::Wasm)
@@ -192,6 +193,7 @@ async fn test_evm_to_linera_bridge() -> anyhow::Result<()> {
             serde_json::to_vec(&bridge_params)?,
             serde_json::to_vec(&())?,
             vec![],
+            ApplicationOptions::default(),
         )
         .await?
         .expect("create evm-bridge app committed");
@@ -216,6 +218,7 @@ async fn test_evm_to_linera_bridge() -> anyhow::Result<()> {
             serde_json::to_vec(&wrapped_params)?,
             serde_json::to_vec(&wrapped_init)?,
             vec![],
+            ApplicationOptions::default(),
         )
         .await?
         .expect("create wrapped-fungible app committed");
@@ -17,6 +17,7 @@ use alloy::{
 };
 use anyhow::Context as _;
 use futures::StreamExt as _;
+use linera_base::identifiers::Account;
 use linera_base::{
     crypto::InMemorySigner,
     data_types::{Amount, Bytecode},
@@ -31,12 +32,11 @@ use linera_client::{chain_listener::ClientContext as _, client_context::ClientCo
 use linera_core::{environment::wallet::Memory, worker::Reason};
 use linera_execution::{Operation, WasmRuntime};
 use linera_faucet_client::Faucet;
-use linera_base::identifiers::Account;
+use linera_storage::{DbStorage, StorageCacheConfig};
+use linera_views::backends::memory::{MemoryDatabase, MemoryStoreConfig};
 use wrapped_fungible::{
     InitialState, WrappedFungibleOperation, WrappedFungibleTokenAbi, WrappedParameters,
 };
-use linera_storage::{DbStorage, StorageCacheConfig};
-use linera_views::backends::memory::{MemoryDatabase, MemoryStoreConfig};
 
 sol! {
     #[sol(rpc)]
@@ -105,7 +105,7 @@ async fn test_fungible_bridge_transfers_to_evm() -> anyhow::Result<()> {
 
     // ── 2. Claim chain A from faucet ──
     tracing::info!("Claiming chain A from faucet...");
-    let owner_a = AccountOwner::from(signer.generate_new());
+    let owner_a = AccountOwner::from(signer.generate_new().unwrap());
     let chain_a_desc = faucet.claim(&owner_a).await?;
     let chain_a = chain_a_desc.id();
     ctx.extend_with_chain(chain_a_desc, Some(owner_a)).await?;
@@ -175,7 +175,7 @@ async fn test_fungible_bridge_transfers_to_evm() -> anyhow::Result<()> {
 
     // ── 4. Claim chain B (user chain) from faucet and subscribe to notifications ──
     tracing::info!("Claiming chain B from faucet...");
-    let owner_b = AccountOwner::from(signer.generate_new());
+    let owner_b = AccountOwner::from(signer.generate_new().unwrap());
     let chain_b_desc = faucet.claim(&owner_b).await?;
     let chain_b = chain_b_desc.id();
     ctx.extend_with_chain(chain_b_desc, Some(owner_b)).await?;
@@ -7,14 +7,16 @@ use custom_debug_derive::Debug;
 #[cfg(with_metrics)]
 use linera_base::prometheus_util::MeasureLatency;
 use linera_base::{
+    crypto::CryptoHash,
     data_types::{Amount, Blob, BlockHeight, Event, OracleResponse, Timestamp},
     ensure,
     identifiers::{AccountOwner, BlobId, ChainId, StreamId},
 };
 use linera_execution::{
-    execution_state_actor::ExecutionStateActor, ExecutionRuntimeContext, ExecutionStateView,
-    Message, MessageContext, MessageKind, OperationContext, OutgoingMessage, ResourceController,
-    ResourceTracker, SystemExecutionStateView, TransactionOutcome, TransactionTracker,
+    execution_state_actor::ExecutionStateActor, ApplicationLog, ExecutionRuntimeContext,
+    ExecutionStateView, FeeSponsor, Message, MessageContext, MessageKind, OperationContext,
+    OutgoingMessage, ResourceController, ResourceTracker, SystemExecutionStateView,
+    TransactionOutcome, TransactionTracker,
 };
 use linera_views::context::Context;
 use tracing::instrument;
@@ -35,6 +37,8 @@ use crate::{
 pub struct BlockExecutionTracker<'resources, 'blobs> {
     chain_id: ChainId,
     block_height: BlockHeight,
+    #[debug(skip_if = Option::is_none)]
+    previous_block_hash: Option<CryptoHash>,
     timestamp: Timestamp,
     authenticated_owner: Option<AccountOwner>,
     resource_controller: &'resources mut ResourceController<Option<AccountOwner>, ResourceTracker>,
@@ -53,6 +57,10 @@ pub struct BlockExecutionTracker<'resources, 'blobs> {
     messages: Vec<Vec<OutgoingMessage>>,
     #[debug(skip_if = Vec::is_empty)]
     operation_results: Vec<OperationResult>,
+    #[debug(skip_if = Vec::is_empty)]
+    logs: Vec<ApplicationLog>,
+    #[debug(skip_if = BTreeMap::is_empty)]
+    http_requests_per_host: BTreeMap<String, u32>,
     // Index of the currently executed transaction in a block.
     transaction_index: u32,
 
@@ -79,6 +87,7 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
         Ok(Self {
             chain_id: proposal.chain_id,
             block_height: proposal.height,
+            previous_block_hash: proposal.previous_block_hash,
             timestamp: proposal.timestamp,
             authenticated_owner: proposal.authenticated_owner,
             resource_controller,
@@ -91,6 +100,8 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
             blobs: Vec::new(),
             messages: Vec::new(),
             operation_results: Vec::new(),
+            logs: Vec::new(),
+            http_requests_per_host: BTreeMap::new(),
             transaction_index: 0,
             published_blobs,
         })
@@ -177,7 +188,9 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
             self.next_chain_index,
             self.oracle_responses()?,
             &self.blobs,
-        ))
+        )
+        .with_previous_block_hash(self.previous_block_hash)
+        .with_http_requests_per_host(self.http_requests_per_host.clone()))
     }
 
     /// Executes a message as part of an incoming bundle in a block.
@@ -355,6 +368,8 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
         self.events.push(txn_outcome.events);
         self.blobs.push(txn_outcome.blobs);
         self.messages.push(txn_outcome.outgoing_messages);
+        self.logs.extend(txn_outcome.logs);
+        self.http_requests_per_host = txn_outcome.http_requests_per_host;
         if matches!(context, ChainExecutionContext::Operation(_)) {
             self.operation_results
                 .push(OperationResult(txn_outcome.operation_result));
@@ -395,6 +410,7 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
     pub fn create_checkpoint(&self) -> TrackerCheckpoint {
         TrackerCheckpoint {
             resource_tracker: self.resource_controller.tracker,
+            sponsor: self.resource_controller.sponsor,
             next_application_index: self.next_application_index,
             next_chain_index: self.next_chain_index,
             transaction_index: self.transaction_index,
@@ -403,6 +419,8 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
             blobs_len: self.blobs.len(),
             messages_len: self.messages.len(),
             operation_results_len: self.operation_results.len(),
+            logs_len: self.logs.len(),
+            http_requests_per_host: self.http_requests_per_host.clone(),
         }
     }
 
@@ -414,6 +432,7 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
         // Destructure to ensure all fields are handled (compiler will warn on new fields).
         let TrackerCheckpoint {
             resource_tracker,
+            sponsor,
             next_application_index,
             next_chain_index,
             transaction_index,
@@ -422,9 +441,12 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
             blobs_len,
             messages_len,
             operation_results_len,
+            logs_len,
+            http_requests_per_host,
         } = checkpoint;
 
         self.resource_controller.tracker = *resource_tracker;
+        self.resource_controller.sponsor = *sponsor;
         self.next_application_index = *next_application_index;
         self.next_chain_index = *next_chain_index;
         self.transaction_index = *transaction_index;
@@ -433,6 +455,8 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
         self.blobs.truncate(*blobs_len);
         self.messages.truncate(*messages_len);
         self.operation_results.truncate(*operation_results_len);
+        self.logs.truncate(*logs_len);
+        self.http_requests_per_host = http_requests_per_host.clone();
     }
 
     /// Finalizes the execution and returns the collected results.
@@ -459,6 +483,7 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
             self.events,
             self.blobs,
             self.operation_results,
+            self.logs,
             resource_tracker,
         )
     }
@@ -482,6 +507,7 @@ pub(crate) type FinalizeExecutionResult = (
     Vec<Vec<Event>>,
     Vec<Vec<Blob>>,
     Vec<OperationResult>,
+    Vec<ApplicationLog>,
     ResourceTracker,
 );
 
@@ -489,6 +515,7 @@ pub(crate) type FinalizeExecutionResult = (
 #[derive(Clone)]
 pub struct TrackerCheckpoint {
     pub(crate) resource_tracker: ResourceTracker,
+    pub(crate) sponsor: Option<FeeSponsor>,
     pub(crate) next_application_index: u32,
     pub(crate) next_chain_index: u32,
     pub(crate) transaction_index: u32,
@@ -497,4 +524,6 @@ pub struct TrackerCheckpoint {
     pub(crate) blobs_len: usize,
     pub(crate) messages_len: usize,
     pub(crate) operation_results_len: usize,
+    pub(crate) logs_len: usize,
+    pub(crate) http_requests_per_host: BTreeMap<String, u32>,
 }
@@ -19,9 +19,10 @@ use linera_base::{
     time::{Duration, Instant},
 };
 use linera_execution::{
-    committee::Committee, system::EPOCH_STREAM_NAME, ExecutionRuntimeContext, ExecutionStateView,
-    Message, Operation, OutgoingMessage, Query, QueryContext, QueryOutcome, ResourceController,
-    ResourceTracker, ServiceRuntimeEndpoint, TransactionTracker,
+    committee::Committee, system::EPOCH_STREAM_NAME, ApplicationLog, ApplicationStateProof,
+    BalanceProof, ExecutionRuntimeContext, ExecutionStateView, Message, Operation, OutgoingMessage,
+    Query, QueryContext, QueryOutcome, ResourceController, ResourceTracker, ServiceRuntimeEndpoint,
+    TransactionTracker,
 };
 use linera_views::{
     context::Context,
@@ -38,13 +39,14 @@ use crate::{
     block::{Block, ConfirmedBlock},
     block_tracker::BlockExecutionTracker,
     data_types::{
-        BlockExecutionOutcome, BundleExecutionPolicy, BundleFailurePolicy, ChainAndHeight,
-        IncomingBundle, MessageAction, MessageBundle, ProposedBlock, Transaction,
+        check_signatures, BlockExecutionOutcome, BundleExecutionPolicy, BundleFailurePolicy,
+        ChainAndHeight, IncomingBundle, MessageAction, MessageBundle, ProposedBlock, Transaction,
     },
     inbox::{InboxError, InboxStateView},
     manager::ChainManager,
     outbox::OutboxStateView,
     pending_blobs::PendingBlobsView,
+    types::CertificateKind,
     ChainError, ChainExecutionContext, ExecutionError, ExecutionResultExt,
 };
 
@@ -443,6 +445,24 @@ where
         Ok(self.execution_state.system.is_active().await?)
     }
 
+    /// Returns a proof of the balance of `owner` under the state hash of the latest block.
+    pub async fn balance_proof(&self, owner: AccountOwner) -> Result<BalanceProof, ChainError> {
+        Ok(self.execution_state.balance_proof(owner).await?)
+    }
+
+    /// Returns a proof that `key` has its value in the state of the application, or that it
+    /// is absent, under the state hash of the latest block.
+    pub async fn application_state_proof(
+        &self,
+        application_id: ApplicationId,
+        key: &[u8],
+    ) -> Result<ApplicationStateProof, ChainError> {
+        Ok(self
+            .execution_state
+            .application_state_proof(application_id, key)
+            .await?)
+    }
+
     /// Initializes the chain if it is not active yet.
     pub async fn initialize_if_needed(&mut self, local_time: Timestamp) -> Result<(), ChainError> {
         let chain_id = self.chain_id();
@@ -458,7 +478,11 @@ where
             return Ok(());
         }
         // Recompute the state hash.
-        let hash = self.execution_state.crypto_hash_mut().await?;
+        let hash = self
+            .execution_state
+            .crypto_hash_mut()
+            .await
+            .with_execution_context(ChainExecutionContext::Block)?;
         self.execution_state_hash.set(Some(hash));
         let maybe_committee = self
             .execution_state
@@ -681,7 +705,15 @@ where
         published_blobs: &[Blob],
         replaying_oracle_responses: Option<Vec<Vec<OracleResponse>>>,
         exec_policy: BundleExecutionPolicy,
-    ) -> Result<(BlockExecutionOutcome, ResourceTracker, HashSet<ChainId>), ChainError> {
+    ) -> Result<
+        (
+            BlockExecutionOutcome,
+            ResourceTracker,
+            HashSet<ChainId>,
+            Vec<ApplicationLog>,
+        ),
+        ChainError,
+    > {
         // AutoRetry is incompatible with replaying oracle responses because discarding or
         // rejecting bundles would change which transactions execute.
         if !matches!(&exec_policy.on_failure, BundleFailurePolicy::Abort) {
@@ -710,6 +742,14 @@ where
             ResourceTracker::default(),
             block.authenticated_owner,
         );
+        if let Some(owner) = block.authenticated_owner {
+            let operations = block.operations().collect::<Vec<_>>();
+            resource_controller.sponsor = chain
+                .system
+                .reserve_sponsored_fees(owner, &operations)
+                .await
+                .with_execution_context(ChainExecutionContext::Block)?;
+        }
 
         for blob in published_blobs {
             let blob_id = blob.id();
@@ -911,6 +951,20 @@ where
         // due to resource limit errors. This is unlikely in practice but theoretically possible.
         ensure!(!block.transactions.is_empty(), ChainError::EmptyBlock);
 
+        if let (Some(owner), Some(sponsor)) = (
+            block.authenticated_owner,
+            block_execution_tracker
+                .resource_controller_mut()
+                .sponsor
+                .take(),
+        ) {
+            chain
+                .system
+                .refund_sponsored_fees(owner, sponsor)
+                .await
+                .with_execution_context(ChainExecutionContext::Block)?;
+        }
+
         let recipients = block_execution_tracker.recipients();
         let mut recipient_heights = Vec::new();
         let mut indices = Vec::new();
@@ -960,11 +1014,15 @@ where
         let state_hash = {
             #[cfg(with_metrics)]
             let _hash_latency = metrics::STATE_HASH_COMPUTATION_LATENCY.measure_latency_us();
-            chain.crypto_hash_mut().await?
+            chain
+                .crypto_hash_mut()
+                .await
+                .with_execution_context(ChainExecutionContext::Block)?
         };
 
-        let (messages, oracle_responses, events, blobs, operation_results, resource_tracker) =
+        let (messages, oracle_responses, events, blobs, operation_results, logs, resource_tracker) =
             block_execution_tracker.finalize(block.transactions.len());
+        Self::check_random_seed_signatures(chain, block, &oracle_responses).await?;
 
         Ok((
             BlockExecutionOutcome {
@@ -979,9 +1037,62 @@ where
             },
             resource_tracker,
             never_reject_discarded_origins,
+            logs,
         ))
     }
 
+    /// Checks that the signatures recorded to derive random seeds from are a quorum of
+    /// signatures confirming the previous block, so that a proposer cannot substitute
+    /// signatures of its choosing when validators replay the block.
+    async fn check_random_seed_signatures(
+        chain: &ExecutionStateView<C>,
+        block: &ProposedBlock,
+        oracle_responses: &[Vec<OracleResponse>],
+    ) -> Result<(), ChainError> {
+        let mut all_signatures =
+            oracle_responses
+                .iter()
+                .flatten()
+                .filter_map(|response| match response {
+                    OracleResponse::RandomSeed(signatures) => Some(signatures),
+                    _ => None,
+                });
+        let Some(signatures) = all_signatures.next() else {
+            return Ok(());
+        };
+        ensure!(
+            all_signatures.all(|other| other == signatures),
+            ChainError::InvalidRandomSeedSignatures
+        );
+        let previous_block_hash = block
+            .previous_block_hash
+            .ok_or(ChainError::InvalidRandomSeedSignatures)?;
+        ensure!(
+            signatures.epoch <= block.epoch,
+            ChainError::InvalidRandomSeedSignatures
+        );
+        let context = chain.context();
+        let extra = context.extra();
+        let committee_hashes = extra
+            .get_committee_hashes(signatures.epoch..=signatures.epoch)
+            .await
+            .with_execution_context(ChainExecutionContext::Block)?;
+        let committee_hash = committee_hashes
+            .get(&signatures.epoch)
+            .ok_or(ChainError::InvalidRandomSeedSignatures)?;
+        let committee = extra
+            .get_or_load_committee_by_hash(*committee_hash)
+            .await
+            .with_execution_context(ChainExecutionContext::Block)?;
+        check_signatures(
+            previous_block_hash,
+            CertificateKind::Confirmed,
+            signatures.round,
+            &signatures.signatures,
+            &committee,
+        )
+    }
+
     fn discard_remaining_stream_updates(block: &mut ProposedBlock, mut index: usize) {
         while index < block.transactions.len() {
             if block.transactions[index].is_update_stream() {
@@ -1019,7 +1130,10 @@ where
     /// - For non-limit errors: the bundle is rejected (triggering bounced messages).
     /// - After `max_failures` failed bundles, all remaining message bundles are discarded.
     ///
-    /// The block may be modified to reflect the actual executed transactions.
+    /// The block may be modified to reflect the actual executed transactions. The log
+    /// messages emitted by applications are returned separately, since they are not part of
+    /// the block.
+    #[expect(clippy::type_complexity)]
     #[instrument(skip_all, fields(
         chain_id = %self.chain_id(),
         block_height = %block.height
@@ -1038,6 +1152,7 @@ where
             BlockExecutionOutcome,
             ResourceTracker,
             HashSet<ChainId>,
+            Vec<ApplicationLog>,
         ),
         ChainError,
     > {
@@ -1091,8 +1206,8 @@ where
             policy,
         )
         .await
-        .map(|(outcome, tracker, never_reject_origins)| {
-            (block, outcome, tracker, never_reject_origins)
+        .map(|(outcome, tracker, never_reject_origins, logs)| {
+            (block, outcome, tracker, never_reject_origins, logs)
         })
     }
 
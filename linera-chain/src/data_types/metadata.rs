@@ -106,6 +106,10 @@ pub struct SystemOperationMetadata {
     pub epoch: Option<i32>,
     /// `UpdateStream` operation details
     pub update_stream: Option<UpdateStreamMetadata>,
+    /// Upgrade application operation details
+    pub upgrade_application: Option<UpgradeApplicationMetadata>,
+    /// Sponsor operation details
+    pub sponsor: Option<SponsorOperationMetadata>,
 }
 
 impl SystemOperationMetadata {
@@ -125,6 +129,8 @@ impl SystemOperationMetadata {
             publish_module: None,
             epoch: None,
             update_stream: None,
+            upgrade_application: None,
+            sponsor: None,
         }
     }
 }
@@ -193,6 +199,8 @@ pub struct CreateApplicationOperationMetadata {
     pub parameters_hex: String,
     pub instantiation_argument_hex: String,
     pub required_application_ids: Vec<ApplicationId>,
+    pub upgrade_authority: Option<AccountOwner>,
+    pub allow_reentrancy: bool,
 }
 
 /// Publish data blob operation metadata.
@@ -222,6 +230,23 @@ pub struct UpdateStreamMetadata {
     pub next_index: i32,
 }
 
+/// Upgrade application operation metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, SimpleObject)]
+pub struct UpgradeApplicationMetadata {
+    pub application_id: String,
+    pub module_id: String,
+}
+
+/// Sponsor operation metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, SimpleObject)]
+pub struct SponsorOperationMetadata {
+    pub owner: AccountOwner,
+    pub beneficiary: AccountOwner,
+    pub allowance: Amount,
+    pub applications: Option<Vec<ApplicationId>>,
+    pub system_operations: bool,
+}
+
 /// Structured representation of a system message for GraphQL.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, SimpleObject)]
 pub struct SystemMessageMetadata {
@@ -347,6 +372,8 @@ impl From<&SystemOperation> for SystemOperationMetadata {
                     parameters_hex: hex::encode(parameters),
                     instantiation_argument_hex: hex::encode(instantiation_argument),
                     required_application_ids: required_application_ids.clone(),
+                    upgrade_authority: None,
+                    allow_reentrancy: false,
                 }),
                 ..SystemOperationMetadata::new("CreateApplication")
             },
@@ -390,6 +417,48 @@ impl From<&SystemOperation> for SystemOperationMetadata {
                 }),
                 ..SystemOperationMetadata::new("UpdateStream")
             },
+            SystemOperation::UpgradeApplication {
+                application_id,
+                module_id,
+            } => SystemOperationMetadata {
+                upgrade_application: Some(UpgradeApplicationMetadata {
+                    application_id: application_id.to_string(),
+                    module_id: module_id.to_string(),
+                }),
+                ..SystemOperationMetadata::new("UpgradeApplication")
+            },
+            SystemOperation::Sponsor {
+                owner,
+                beneficiary,
+                allowance,
+                policy,
+            } => SystemOperationMetadata {
+                sponsor: Some(SponsorOperationMetadata {
+                    owner: *owner,
+                    beneficiary: *beneficiary,
+                    allowance: *allowance,
+                    applications: policy.applications.clone(),
+                    system_operations: policy.system_operations,
+                }),
+                ..SystemOperationMetadata::new("Sponsor")
+            },
+            SystemOperation::CreateApplicationWithOptions {
+                module_id,
+                parameters,
+                instantiation_argument,
+                required_application_ids,
+                options,
+            } => SystemOperationMetadata {
+                create_application: Some(CreateApplicationOperationMetadata {
+                    module_id: module_id.to_string(),
+                    parameters_hex: hex::encode(parameters),
+                    instantiation_argument_hex: hex::encode(instantiation_argument),
+                    required_application_ids: required_application_ids.clone(),
+                    upgrade_authority: options.upgrade_authority,
+                    allow_reentrancy: options.allow_reentrancy,
+                }),
+                ..SystemOperationMetadata::new("CreateApplicationWithOptions")
+            },
         }
     }
 }
@@ -165,6 +165,8 @@ pub enum ChainError {
     MissingMandatoryApplications(Vec<ApplicationId>),
     #[error("Executed block contains fewer oracle responses than requests")]
     MissingOracleResponseList,
+    #[error("Random seeds must be derived from the signatures that confirmed the previous block")]
+    InvalidRandomSeedSignatures,
     #[error("Not signing timeout certificate; current round does not time out")]
     RoundDoesNotTimeOut,
     #[error("Not signing timeout certificate; current round times out at time {0}")]
@@ -204,6 +206,7 @@ impl ChainError {
             | ChainError::AuthorizedApplications(_)
             | ChainError::MissingMandatoryApplications(_)
             | ChainError::MissingOracleResponseList
+            | ChainError::InvalidRandomSeedSignatures
             | ChainError::RoundDoesNotTimeOut
             | ChainError::NotTimedOutYet(_)
             | ChainError::MissingCrossChainUpdate { .. } => false,
@@ -11,19 +11,21 @@ use std::{
 use assert_matches::assert_matches;
 use axum::{routing::get, Router};
 use linera_base::{
-    crypto::{AccountPublicKey, CryptoHash, ValidatorPublicKey},
+    crypto::{AccountPublicKey, CryptoHash, ValidatorKeypair, ValidatorPublicKey},
     data_types::{
         Amount, ApplicationDescription, ApplicationPermissions, Blob, BlockHeight, Bytecode,
-        ChainDescription, ChainOrigin, Epoch, InitialChainConfig, Timestamp,
+        ChainDescription, ChainOrigin, ConfirmationSignatures, Epoch, InitialChainConfig,
+        OracleResponse, Round, Timestamp,
     },
     http,
-    identifiers::{Account, AccountOwner, ApplicationId, ChainId, ModuleId},
+    identifiers::{Account, AccountOwner, ApplicationId, ChainId, ModuleId, OwnerSpender},
     ownership::ChainOwnership,
     time::{Duration, Instant},
     vm::VmRuntime,
 };
 use linera_execution::{
     committee::{Committee, ValidatorState},
+    system::SponsorshipPolicy,
     test_utils::{ExpectedCall, MockApplication},
     BaseRuntime, ContractRuntime, ExecutionError, ExecutionRuntimeConfig, ExecutionRuntimeContext,
     Message, MessageKind, Operation, ResourceControlPolicy, ResourceTracker, ServiceRuntime,
@@ -39,10 +41,11 @@ use test_case::test_case;
 use crate::{
     block::{Block, ConfirmedBlock},
     data_types::{
-        BlockExecutionOutcome, BundleExecutionPolicy, IncomingBundle, MessageAction, MessageBundle,
-        PostedMessage, ProposedBlock,
+        BlockExecutionOutcome, BundleExecutionPolicy, IncomingBundle, LiteValue, LiteVote,
+        MessageAction, MessageBundle, PostedMessage, ProposedBlock,
     },
     test::{make_child_block, make_first_block, BlockTestExt, HttpServer},
+    types::CertificateKind,
     ChainError, ChainExecutionContext, ChainStateView,
 };
 
@@ -65,7 +68,7 @@ impl ChainStateView<MemoryContext<TestExecutionRuntimeContext>> {
         local_time: Timestamp,
         published_blobs: &[Blob],
     ) -> Result<(ProposedBlock, BlockExecutionOutcome, ResourceTracker), ChainError> {
-        let (block, outcome, tracker, _) = self
+        let (block, outcome, tracker, _, _) = self
             .execute_block(
                 block,
                 local_time,
@@ -143,6 +146,9 @@ impl TestEnvironment {
                 application_index: 0,
                 required_application_ids: vec![],
                 parameters: vec![],
+                allow_reentrancy: false,
+                upgrade_authority: None,
+                module_history: vec![],
             },
             contract_blob,
             service_blob,
@@ -167,11 +173,18 @@ impl TestEnvironment {
 }
 
 fn committee_blob(policy: ResourceControlPolicy) -> Blob {
+    committee_blob_with_validator(ValidatorPublicKey::test_key(1), policy)
+}
+
+fn committee_blob_with_validator(
+    validator: ValidatorPublicKey,
+    policy: ResourceControlPolicy,
+) -> Blob {
     let committee = Committee::new(
         BTreeMap::from([(
-            ValidatorPublicKey::test_key(1),
+            validator,
             ValidatorState {
-                network_address: ValidatorPublicKey::test_key(1).to_string(),
+                network_address: validator.to_string(),
                 votes: 1,
                 account_public_key: AccountPublicKey::test_key(1),
             },
@@ -395,6 +408,138 @@ async fn test_application_permissions() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_sponsored_fees() -> anyhow::Result<()> {
+    let mut env = TestEnvironment::new();
+    let time = Timestamp::from(0);
+    let config = env.make_open_chain_config();
+    let chain_desc = env.make_child_chain_description_with_config(3, config);
+    let chain_id = chain_desc.id();
+    let owner = AccountOwner::from(AccountPublicKey::test_key(0));
+    let sponsor = AccountOwner::from(AccountPublicKey::test_key(1));
+
+    let mut chain = ChainStateView::new(chain_id).await;
+    let fee = Amount::from_millis(10);
+    let policy = ResourceControlPolicy {
+        operation: fee,
+        ..ResourceControlPolicy::default()
+    };
+    let extra = chain.context().extra().clone();
+    extra.add_blobs([committee_blob(policy)]).await?;
+    extra.add_blobs(env.description_blobs()).await?;
+    chain.initialize_if_needed(time).await?;
+
+    // The owner funds the sponsor's account, and pays the fees.
+    let block = make_first_block(chain_id)
+        .with_authenticated_owner(Some(owner))
+        .with_transfer(
+            AccountOwner::CHAIN,
+            Account::new(chain_id, sponsor),
+            Amount::from_tokens(2),
+        );
+    let (block, outcome, tracker) = chain.execute_test_block_simple(block, time, &[]).await?;
+    assert_eq!(tracker.fees, fee);
+    let value = ConfirmedBlock::new(outcome.with(block));
+    chain.apply_confirmed_block(&value, time).await?;
+    let system = &chain.execution_state.system;
+    assert_eq!(*system.balance.get(), Amount::from_millis(7_990));
+
+    // The sponsor covers the owner's system operations, up to one token.
+    let sponsorship = SystemOperation::Sponsor {
+        owner: sponsor,
+        beneficiary: owner,
+        allowance: Amount::ONE,
+        policy: SponsorshipPolicy {
+            applications: Some(Vec::new()),
+            system_operations: true,
+        },
+    };
+    let block = make_child_block(&value)
+        .with_authenticated_owner(Some(sponsor))
+        .with_operation(sponsorship);
+    let (block, outcome, _) = chain.execute_test_block_simple(block, time, &[]).await?;
+    let value = ConfirmedBlock::new(outcome.with(block));
+    chain.apply_confirmed_block(&value, time).await?;
+    let system = &mut chain.execution_state.system;
+    assert_eq!(*system.balance.get(), Amount::from_millis(7_980));
+
+    // The fee budget is not an allowance: the owner can't transfer the sponsor's tokens.
+    assert_matches!(
+        system
+            .transfer_from(
+                Some(owner),
+                None,
+                sponsor,
+                owner,
+                Account::new(chain_id, owner),
+                Amount::from_millis(1),
+            )
+            .await,
+        Err(ExecutionError::InsufficientAllowance { .. })
+    );
+    assert_eq!(
+        system.balances.get(&sponsor).await?,
+        Some(Amount::from_tokens(2))
+    );
+
+    // The owner's next transfer is paid for by the sponsor.
+    let block = make_child_block(&value)
+        .with_authenticated_owner(Some(owner))
+        .with_simple_transfer(env.admin_chain_id(), Amount::ONE);
+    let (block, outcome, tracker) = chain.execute_test_block_simple(block, time, &[]).await?;
+    assert_eq!(tracker.fees, fee);
+    let value = ConfirmedBlock::new(outcome.with(block));
+    chain.apply_confirmed_block(&value, time).await?;
+    let system = &chain.execution_state.system;
+    assert_eq!(*system.balance.get(), Amount::from_millis(6_980));
+    assert_eq!(
+        system.balances.get(&sponsor).await?,
+        Some(Amount::from_millis(1_990))
+    );
+    assert_eq!(
+        system
+            .sponsor_budgets
+            .get(&OwnerSpender::new(sponsor, owner))
+            .await?,
+        Some(Amount::from_millis(990))
+    );
+
+    // Operations that are not covered are paid for by the chain.
+    let (app_description, contract_blob, service_blob) = env.make_app_description();
+    let application_id = ApplicationId::from(&app_description);
+    let application = MockApplication::default();
+    extra
+        .user_contracts()
+        .pin()
+        .insert(application_id, application.clone().into());
+    extra
+        .add_blobs([
+            contract_blob,
+            service_blob,
+            Blob::new_application_description(&app_description),
+        ])
+        .await?;
+    application.expect_call(ExpectedCall::execute_operation(|_, _| Ok(vec![])));
+    application.expect_call(ExpectedCall::default_finalize());
+    let block = make_child_block(&value)
+        .with_authenticated_owner(Some(owner))
+        .with_operation(Operation::User {
+            application_id,
+            bytes: vec![],
+        });
+    let (block, outcome, _) = chain.execute_test_block_simple(block, time, &[]).await?;
+    let value = ConfirmedBlock::new(outcome.with(block));
+    chain.apply_confirmed_block(&value, time).await?;
+    let system = &chain.execution_state.system;
+    assert_eq!(*system.balance.get(), Amount::from_millis(6_970));
+    assert_eq!(
+        system.balances.get(&sponsor).await?,
+        Some(Amount::from_millis(1_990))
+    );
+
+    Ok(())
+}
+
 /// Tests that mandatory applications can be satisfied by accepted messages but not rejected ones.
 #[tokio::test]
 async fn test_mandatory_applications_with_messages() -> anyhow::Result<()> {
@@ -828,14 +973,24 @@ async fn prepare_test_with_dummy_mock_application(
     ChainStateView<ViewContext<TestExecutionRuntimeContext, MemoryStore>>,
     ProposedBlock,
     Timestamp,
+)> {
+    prepare_test_with_dummy_mock_application_and_committee(committee_blob(policy)).await
+}
+
+async fn prepare_test_with_dummy_mock_application_and_committee(
+    committee_blob: Blob,
+) -> anyhow::Result<(
+    MockApplication,
+    ApplicationId,
+    ChainStateView<ViewContext<TestExecutionRuntimeContext, MemoryStore>>,
+    ProposedBlock,
+    Timestamp,
 )> {
     let mut env = TestEnvironment::new();
     let time = Timestamp::from(0);
 
     let config = env.make_open_chain_config();
 
-    let committee_blob = committee_blob(policy);
-
     let chain_desc = env.make_child_chain_description_with_config(3, config);
     let chain_id = chain_desc.id();
     let mut chain = ChainStateView::new(chain_id).await;
@@ -884,3 +1039,95 @@ async fn prepare_test_with_dummy_mock_application(
 
     Ok((application, application_id, chain, block, time))
 }
+
+/// Tests that random seeds are derived from the signatures that confirmed the previous block,
+/// and that blocks recording other signatures are rejected.
+#[tokio::test]
+async fn test_random_seed_signatures() -> anyhow::Result<()> {
+    let validator = ValidatorKeypair::generate();
+    let committee_blob =
+        committee_blob_with_validator(validator.public_key, ResourceControlPolicy::default());
+    let (application, _, mut chain, mut block, time) =
+        prepare_test_with_dummy_mock_application_and_committee(committee_blob).await?;
+    let previous_block_hash = CryptoHash::test_hash("previous block");
+    block.previous_block_hash = Some(previous_block_hash);
+
+    let round = Round::SingleLeader(0);
+    let sign = |value_hash| {
+        let value = LiteValue {
+            value_hash,
+            chain_id: block.chain_id,
+            kind: CertificateKind::Confirmed,
+        };
+        let vote = LiteVote::new(value, round, &validator.secret_key);
+        ConfirmationSignatures {
+            epoch: Epoch::ZERO,
+            round,
+            signatures: vec![(validator.public_key, vote.signature)],
+        }
+    };
+    let signatures = sign(previous_block_hash);
+    chain
+        .context()
+        .extra()
+        .add_confirmation_signatures(previous_block_hash, signatures.clone());
+
+    let expect_seed = || {
+        application.expect_call(ExpectedCall::execute_operation(|runtime, _| {
+            runtime.random_seed()?;
+            Ok(vec![])
+        }));
+        application.expect_call(ExpectedCall::default_finalize());
+    };
+
+    expect_seed();
+    let (block, outcome, _) = chain
+        .execute_test_block_simple(block.clone(), time, &[])
+        .await?;
+    let recorded_signatures = outcome.oracle_responses[0]
+        .iter()
+        .filter(|response| matches!(response, OracleResponse::RandomSeed(_)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        recorded_signatures,
+        [&OracleResponse::RandomSeed(signatures)]
+    );
+    chain.rollback();
+
+    // Replaying the recorded signatures succeeds.
+    expect_seed();
+    chain
+        .execute_block(
+            block.clone(),
+            time,
+            None,
+            &[],
+            Some(outcome.oracle_responses.clone()),
+            BundleExecutionPolicy::committed(),
+        )
+        .await?;
+    chain.rollback();
+
+    // Signatures of a different block are rejected.
+    expect_seed();
+    let forged_signatures = sign(CryptoHash::test_hash("other block"));
+    let mut forged_responses = outcome.oracle_responses;
+    for response in &mut forged_responses[0] {
+        if let OracleResponse::RandomSeed(signatures) = response {
+            *signatures = forged_signatures.clone();
+        }
+    }
+    let result = chain
+        .execute_block(
+            block,
+            time,
+            None,
+            &[],
+            Some(forged_responses),
+            BundleExecutionPolicy::committed(),
+        )
+        .await;
+    assert_matches!(result, Err(ChainError::CryptoError(_)));
+
+    Ok(())
+}
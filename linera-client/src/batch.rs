@@ -0,0 +1,336 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Batches of operations, e.g. for airdrops or payroll, that are executed in as few blocks as
+//! the resource control policy allows.
+//!
+//! A batch is read from a CSV or JSON manifest with one row per operation. Consecutive rows
+//! are packed into blocks, and a [`BatchProgress`] records which rows were committed in which
+//! block, so that an interrupted batch can be resumed without executing any row twice.
+
+use std::{ops::Range, path::Path};
+
+use linera_base::{
+    crypto::{BcsHashable, CryptoHash},
+    data_types::{Amount, BlockHeight},
+    hex,
+    identifiers::{Account, AccountOwner, ApplicationId, ChainId},
+};
+use linera_execution::{system::SystemOperation, Operation, ResourceControlPolicy};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, thiserror::Error)]
+pub enum BatchError {
+    #[error("unsupported manifest {0}: expected a .csv or .json file")]
+    UnsupportedFormat(String),
+    #[error("invalid row {row}: {message}")]
+    InvalidRow { row: usize, message: String },
+    #[error("invalid JSON manifest: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("the manifest has no rows")]
+    EmptyManifest,
+    #[error("the progress file belongs to a different batch")]
+    ProgressMismatch,
+    #[error("row {0} exceeds the block limits on its own")]
+    RowTooLarge(usize),
+}
+
+/// The format of a batch manifest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ManifestFormat {
+    /// Comma-separated values, with an optional header line. Empty lines and lines starting
+    /// with `#` are ignored.
+    Csv,
+    /// An array of objects, with one field per column.
+    Json,
+}
+
+impl ManifestFormat {
+    /// Returns the format of a manifest, based on its file extension.
+    pub fn from_path(path: &Path) -> Result<Self, BatchError> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("csv") => Ok(Self::Csv),
+            Some(extension) if extension.eq_ignore_ascii_case("json") => Ok(Self::Json),
+            _ => Err(BatchError::UnsupportedFormat(path.display().to_string())),
+        }
+    }
+}
+
+/// Operations to execute on a chain, in order.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Batch {
+    /// The chain to execute the operations on.
+    pub chain_id: ChainId,
+    /// The operations, one per row of the manifest.
+    pub operations: Vec<Operation>,
+}
+
+impl BcsHashable<'_> for Batch {}
+
+impl Batch {
+    /// Reads a manifest of transfers from `owner` on `chain_id`, with the columns `recipient`
+    /// and `amount`.
+    pub fn transfers(
+        chain_id: ChainId,
+        owner: AccountOwner,
+        manifest: &str,
+        format: ManifestFormat,
+    ) -> Result<Self, BatchError> {
+        let operations = read_rows(manifest, format, &["recipient", "amount"])?
+            .into_iter()
+            .enumerate()
+            .map(|(index, values)| {
+                let row = index + 1;
+                let recipient = values[0]
+                    .parse::<Account>()
+                    .map_err(|error| invalid_row(row, error))?;
+                let amount = values[1]
+                    .parse::<Amount>()
+                    .map_err(|error| invalid_row(row, error))?;
+                Ok(Operation::system(SystemOperation::Transfer {
+                    owner,
+                    recipient,
+                    amount,
+                }))
+            })
+            .collect::<Result<_, BatchError>>()?;
+        Ok(Self {
+            chain_id,
+            operations,
+        })
+    }
+
+    /// Reads a manifest of user operations on `chain_id`, with the columns `application_id`
+    /// and `operation`, the latter containing the BCS-encoded operation as a hex string.
+    pub fn user_operations(
+        chain_id: ChainId,
+        manifest: &str,
+        format: ManifestFormat,
+    ) -> Result<Self, BatchError> {
+        let operations = read_rows(manifest, format, &["application_id", "operation"])?
+            .into_iter()
+            .enumerate()
+            .map(|(index, values)| {
+                let row = index + 1;
+                let application_id = values[0]
+                    .parse::<ApplicationId>()
+                    .map_err(|error| invalid_row(row, error))?;
+                let bytes = hex::decode(&values[1]).map_err(|error| invalid_row(row, error))?;
+                Ok(Operation::User {
+                    application_id,
+                    bytes,
+                })
+            })
+            .collect::<Result<_, BatchError>>()?;
+        Ok(Self {
+            chain_id,
+            operations,
+        })
+    }
+
+    /// Returns the hash identifying the batch.
+    pub fn id(&self) -> CryptoHash {
+        CryptoHash::new(self)
+    }
+}
+
+fn invalid_row(row: usize, error: impl ToString) -> BatchError {
+    BatchError::InvalidRow {
+        row,
+        message: error.to_string(),
+    }
+}
+
+/// Returns the values of the given columns in each row of the manifest.
+fn read_rows(
+    manifest: &str,
+    format: ManifestFormat,
+    columns: &[&str],
+) -> Result<Vec<Vec<String>>, BatchError> {
+    let rows = match format {
+        ManifestFormat::Csv => {
+            let mut lines = manifest
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .peekable();
+            let is_header = lines
+                .peek()
+                .is_some_and(|line| line.split(',').map(str::trim).eq(columns.iter().copied()));
+            if is_header {
+                lines.next();
+            }
+            lines
+                .enumerate()
+                .map(|(index, line)| {
+                    let values = line
+                        .split(',')
+                        .map(|value| value.trim().to_string())
+                        .collect::<Vec<_>>();
+                    if values.len() != columns.len() {
+                        return Err(invalid_row(
+                            index + 1,
+                            format!("expected the columns {}", columns.join(",")),
+                        ));
+                    }
+                    Ok(values)
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        }
+        ManifestFormat::Json => {
+            let objects: Vec<serde_json::Map<String, serde_json::Value>> =
+                serde_json::from_str(manifest)?;
+            objects
+                .into_iter()
+                .enumerate()
+                .map(|(index, object)| {
+                    columns
+                        .iter()
+                        .map(|column| match object.get(*column) {
+                            Some(serde_json::Value::String(value)) => Ok(value.clone()),
+                            Some(serde_json::Value::Number(value)) => Ok(value.to_string()),
+                            _ => Err(invalid_row(index + 1, format!("missing field `{column}`"))),
+                        })
+                        .collect()
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        }
+    };
+    if rows.is_empty() {
+        return Err(BatchError::EmptyManifest);
+    }
+    Ok(rows)
+}
+
+/// Limits on the rows that are packed into a single block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockLimits {
+    /// The maximum total size of the serialized operations.
+    pub max_bytes: u64,
+    /// The maximum number of operations.
+    pub max_operations: usize,
+}
+
+impl BlockLimits {
+    /// Returns the limits for blocks under the given policy.
+    ///
+    /// Half of the block is left for the execution outcome, e.g. the messages sent by
+    /// transfers, and a quarter of the proposal for the incoming messages that the block also
+    /// receives.
+    pub fn new(policy: &ResourceControlPolicy, max_operations: usize) -> Self {
+        let max_bytes =
+            (policy.maximum_block_size / 2).min(policy.maximum_block_proposal_size / 4 * 3);
+        Self {
+            max_bytes,
+            max_operations: max_operations.max(1),
+        }
+    }
+
+    /// Returns the end of the longest run of operations starting at `start` that fits in a
+    /// block. The run contains at least one operation.
+    pub fn next_chunk(&self, operations: &[Operation], start: usize) -> usize {
+        let mut bytes = 0u64;
+        let mut end = start;
+        for operation in operations.iter().skip(start).take(self.max_operations) {
+            let size = bcs::serialized_size(operation).map_or(u64::MAX, |size| size as u64);
+            bytes = bytes.saturating_add(size);
+            if end > start && bytes > self.max_bytes {
+                break;
+            }
+            end += 1;
+        }
+        end
+    }
+}
+
+/// A block that executed consecutive rows of a batch.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchBlock {
+    /// The indices of the rows, starting from 0.
+    pub rows: Range<usize>,
+    /// The height of the block.
+    pub height: BlockHeight,
+    /// The hash of the block's certificate.
+    pub certificate_hash: CryptoHash,
+}
+
+/// Rows that were proposed in a block, but not yet known to be committed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingBatchBlock {
+    /// The indices of the rows, starting from 0.
+    pub rows: Range<usize>,
+    /// The next block height of the chain when the rows were proposed.
+    pub height: BlockHeight,
+}
+
+/// The rows of a batch that were executed so far.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchProgress {
+    /// The ID of the batch.
+    pub batch_id: CryptoHash,
+    /// The number of rows in the batch.
+    pub row_count: usize,
+    /// The committed blocks, in order.
+    pub blocks: Vec<BatchBlock>,
+    /// The rows that are being proposed. If the client stops before the outcome is recorded,
+    /// the chain has to be checked for a block with these rows when resuming.
+    pub pending: Option<PendingBatchBlock>,
+}
+
+impl BatchProgress {
+    /// Creates the progress of a batch where no row was executed yet.
+    pub fn new(batch: &Batch) -> Self {
+        Self {
+            batch_id: batch.id(),
+            row_count: batch.operations.len(),
+            blocks: Vec::new(),
+            pending: None,
+        }
+    }
+
+    /// Checks that the progress was recorded for the given batch.
+    pub fn check(&self, batch: &Batch) -> Result<(), BatchError> {
+        if self.batch_id != batch.id() || self.row_count != batch.operations.len() {
+            return Err(BatchError::ProgressMismatch);
+        }
+        Ok(())
+    }
+
+    /// Returns the index of the first row that was not executed yet.
+    pub fn next_row(&self) -> usize {
+        self.blocks.last().map_or(0, |block| block.rows.end)
+    }
+
+    /// Returns whether all rows were executed.
+    pub fn is_complete(&self) -> bool {
+        self.next_row() >= self.row_count
+    }
+
+    /// Records that the pending rows were committed in the given block.
+    pub fn commit_pending(&mut self, height: BlockHeight, certificate_hash: CryptoHash) {
+        if let Some(pending) = self.pending.take() {
+            self.blocks.push(BatchBlock {
+                rows: pending.rows,
+                height,
+                certificate_hash,
+            });
+        }
+    }
+
+    /// Returns the report of the batch: the row number, starting from 1, with the height and
+    /// the certificate hash of the block that executed it, as CSV.
+    pub fn report(&self) -> String {
+        let mut report = "row,height,certificate_hash\n".to_string();
+        for block in &self.blocks {
+            for row in block.rows.clone() {
+                report.push_str(&format!(
+                    "{},{},{}\n",
+                    row + 1,
+                    block.height,
+                    block.certificate_hash
+                ));
+            }
+        }
+        report
+    }
+}
@@ -0,0 +1,326 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The history of a chain, as a list of the transactions, transfers, messages and events in
+//! its confirmed blocks.
+
+use std::fmt;
+
+use linera_base::{
+    crypto::CryptoHash,
+    data_types::{Amount, BlockHeight, Event, Timestamp},
+    identifiers::{Account, AccountOwner, ApplicationId, ChainId, GenericApplicationId, StreamId},
+};
+use linera_chain::{
+    block::Block,
+    data_types::{IncomingBundle, MessageAction, SystemOperationMetadata, Transaction},
+};
+use linera_execution::{system::SystemMessage, Message, MessageKind, Operation, OutgoingMessage};
+use serde::Serialize;
+
+/// Restricts the history to the entries that involve an application or an owner.
+#[derive(Clone, Debug, Default)]
+pub struct HistoryFilter {
+    /// Only keep the operations, messages and events of this application.
+    pub application_id: Option<ApplicationId>,
+    /// Only keep the entries that this owner authenticated, or that transfer tokens from or
+    /// to this owner.
+    pub owner: Option<AccountOwner>,
+}
+
+impl HistoryFilter {
+    fn matches(&self, entry: &HistoryEntry) -> bool {
+        if let Some(application_id) = self.application_id {
+            if entry.details.application_id() != GenericApplicationId::User(application_id) {
+                return false;
+            }
+        }
+        if let Some(owner) = self.owner {
+            if entry.authenticated_owner != Some(owner) && !entry.details.involves(owner) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Something that happened in a transaction of a confirmed block.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct HistoryEntry {
+    /// The height of the block.
+    pub height: BlockHeight,
+    /// The hash of the block.
+    pub block_hash: CryptoHash,
+    /// The timestamp of the block.
+    pub timestamp: Timestamp,
+    /// The index of the transaction in the block.
+    pub transaction_index: u32,
+    /// The owner who signed the block, for operations, or the message, for incoming
+    /// messages.
+    pub authenticated_owner: Option<AccountOwner>,
+    /// What happened.
+    #[serde(flatten)]
+    pub details: HistoryDetails,
+}
+
+/// The kinds of [`HistoryEntry`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HistoryDetails {
+    /// An operation that was executed.
+    Operation {
+        application_id: GenericApplicationId,
+        /// The type of system operation, or the size of the user operation.
+        summary: String,
+    },
+    /// Tokens that were received from another chain.
+    TransferIn {
+        source: Account,
+        target: AccountOwner,
+        amount: Amount,
+        /// Whether the transfer was accepted or bounced back.
+        accepted: bool,
+    },
+    /// Tokens that were sent to another chain.
+    TransferOut {
+        source: AccountOwner,
+        target: Account,
+        amount: Amount,
+    },
+    /// A message that was received from another chain, other than a transfer.
+    MessageIn {
+        origin: ChainId,
+        application_id: GenericApplicationId,
+        message_kind: MessageKind,
+        accepted: bool,
+    },
+    /// A message that was sent to another chain, other than a transfer.
+    MessageOut {
+        destination: ChainId,
+        application_id: GenericApplicationId,
+        message_kind: MessageKind,
+    },
+    /// An event that was published.
+    Event {
+        stream_id: StreamId,
+        index: u32,
+        size: usize,
+    },
+}
+
+impl HistoryDetails {
+    fn application_id(&self) -> GenericApplicationId {
+        match self {
+            HistoryDetails::Operation { application_id, .. }
+            | HistoryDetails::MessageIn { application_id, .. }
+            | HistoryDetails::MessageOut { application_id, .. } => *application_id,
+            HistoryDetails::TransferIn { .. } | HistoryDetails::TransferOut { .. } => {
+                GenericApplicationId::System
+            }
+            HistoryDetails::Event { stream_id, .. } => stream_id.application_id,
+        }
+    }
+
+    fn involves(&self, owner: AccountOwner) -> bool {
+        match self {
+            HistoryDetails::TransferIn { source, target, .. } => {
+                source.owner == owner || *target == owner
+            }
+            HistoryDetails::TransferOut { source, target, .. } => {
+                *source == owner || target.owner == owner
+            }
+            HistoryDetails::Operation { .. }
+            | HistoryDetails::MessageIn { .. }
+            | HistoryDetails::MessageOut { .. }
+            | HistoryDetails::Event { .. } => false,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            HistoryDetails::Operation { .. } => "operation",
+            HistoryDetails::TransferIn { .. } => "transfer in",
+            HistoryDetails::TransferOut { .. } => "transfer out",
+            HistoryDetails::MessageIn { .. } => "message in",
+            HistoryDetails::MessageOut { .. } => "message out",
+            HistoryDetails::Event { .. } => "event",
+        }
+    }
+}
+
+impl fmt::Display for HistoryDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoryDetails::Operation {
+                application_id,
+                summary,
+            } => write!(f, "{application_id}: {summary}"),
+            HistoryDetails::TransferIn {
+                source,
+                target,
+                amount,
+                accepted,
+            } => {
+                write!(f, "{amount} from {source} to {target}")?;
+                if !accepted {
+                    write!(f, " (bounced)")?;
+                }
+                Ok(())
+            }
+            HistoryDetails::TransferOut {
+                source,
+                target,
+                amount,
+            } => write!(f, "{amount} from {source} to {target}"),
+            HistoryDetails::MessageIn {
+                origin,
+                application_id,
+                message_kind,
+                accepted,
+            } => {
+                write!(f, "{message_kind:?} {application_id} message from {origin}")?;
+                if !accepted {
+                    write!(f, " (rejected)")?;
+                }
+                Ok(())
+            }
+            HistoryDetails::MessageOut {
+                destination,
+                application_id,
+                message_kind,
+            } => write!(
+                f,
+                "{message_kind:?} {application_id} message to {destination}"
+            ),
+            HistoryDetails::Event {
+                stream_id,
+                index,
+                size,
+            } => write!(f, "#{index} in {stream_id}, {size} bytes"),
+        }
+    }
+}
+
+impl fmt::Display for HistoryEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>8}  {:>4}  {:<12}  {}",
+            self.height,
+            self.transaction_index,
+            self.details.kind(),
+            self.details
+        )
+    }
+}
+
+/// Returns the entries of a confirmed block that pass the filter, in the order of the
+/// transactions.
+pub fn block_history(
+    block_hash: CryptoHash,
+    block: &Block,
+    filter: &HistoryFilter,
+) -> Vec<HistoryEntry> {
+    let mut entries = Vec::new();
+    for (index, transaction) in block.body.transactions.iter().enumerate() {
+        let mut push = |authenticated_owner, details| {
+            entries.push(HistoryEntry {
+                height: block.header.height,
+                block_hash,
+                timestamp: block.header.timestamp,
+                transaction_index: index as u32,
+                authenticated_owner,
+                details,
+            })
+        };
+        match transaction {
+            Transaction::ExecuteOperation(operation) => {
+                push(
+                    block.header.authenticated_owner,
+                    operation_details(operation),
+                );
+            }
+            Transaction::ReceiveMessages(bundle) => {
+                for (owner, details) in incoming_details(bundle) {
+                    push(owner, details);
+                }
+            }
+        }
+        let authenticated_owner = block.header.authenticated_owner;
+        for message in block.body.messages.get(index).into_iter().flatten() {
+            push(authenticated_owner, outgoing_details(message));
+        }
+        for event in block.body.events.get(index).into_iter().flatten() {
+            push(authenticated_owner, event_details(event));
+        }
+    }
+    entries.retain(|entry| filter.matches(entry));
+    entries
+}
+
+fn operation_details(operation: &Operation) -> HistoryDetails {
+    let summary = match operation {
+        Operation::System(operation) => {
+            SystemOperationMetadata::from(operation.as_ref()).system_operation_type
+        }
+        Operation::User { bytes, .. } => format!("{} bytes", bytes.len()),
+    };
+    HistoryDetails::Operation {
+        application_id: operation.application_id(),
+        summary,
+    }
+}
+
+fn incoming_details(
+    bundle: &IncomingBundle,
+) -> impl Iterator<Item = (Option<AccountOwner>, HistoryDetails)> + '_ {
+    let accepted = bundle.action == MessageAction::Accept;
+    bundle.messages().map(move |posted| {
+        let details = match &posted.message {
+            Message::System(SystemMessage::Credit {
+                target,
+                amount,
+                source,
+            }) => HistoryDetails::TransferIn {
+                source: Account::new(bundle.origin, *source),
+                target: *target,
+                amount: *amount,
+                accepted,
+            },
+            message => HistoryDetails::MessageIn {
+                origin: bundle.origin,
+                application_id: message.application_id(),
+                message_kind: posted.kind,
+                accepted,
+            },
+        };
+        (posted.authenticated_owner, details)
+    })
+}
+
+fn outgoing_details(message: &OutgoingMessage) -> HistoryDetails {
+    match &message.message {
+        Message::System(SystemMessage::Credit {
+            target,
+            amount,
+            source,
+        }) => HistoryDetails::TransferOut {
+            source: *source,
+            target: Account::new(message.destination, *target),
+            amount: *amount,
+        },
+        other => HistoryDetails::MessageOut {
+            destination: message.destination,
+            application_id: other.application_id(),
+            message_kind: message.kind,
+        },
+    }
+}
+
+fn event_details(event: &Event) -> HistoryDetails {
+    HistoryDetails::Event {
+        stream_id: event.stream_id.clone(),
+        index: event.index,
+        size: event.value.len(),
+    }
+}
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
-    collections::{btree_map::Entry, BTreeMap, BTreeSet},
+    collections::{btree_map::Entry, BTreeMap, BTreeSet, VecDeque},
     sync::Arc,
     time::Duration,
 };
@@ -10,7 +10,7 @@ use std::{
 use futures::{future, lock::Mutex, Future, FutureExt as _, StreamExt};
 use linera_base::{
     crypto::{CryptoHash, Signer},
-    data_types::{ChainDescription, Epoch, MessagePolicy, TimeDelta, Timestamp},
+    data_types::{Amount, ChainDescription, Epoch, MessagePolicy, TimeDelta, Timestamp},
     identifiers::{AccountOwner, BlobType, ChainId},
     util::future::FutureSyncExt as _,
     Task,
@@ -20,7 +20,9 @@ use linera_core::{
         chain_client::{self, ChainClient},
         AbortOnDrop, ListeningMode,
     },
+    data_types::ClientOutcome,
     node::NotificationStream,
+    wallet::InboxPolicy,
     worker::{Notification, Reason},
     Environment, Wallet,
 };
@@ -29,7 +31,10 @@ use tokio::sync::{mpsc::UnboundedReceiver, Notify};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, instrument, warn, Instrument as _};
 
-use crate::error::{self, Error};
+use crate::{
+    error::{self, Error},
+    scheduler::{self, Scheduler},
+};
 
 #[derive(Default, Debug, Clone, clap::Args, serde::Serialize, serde::Deserialize, tsify::Tsify)]
 #[serde(rename_all = "camelCase")]
@@ -60,6 +65,45 @@ pub struct ChainListenerConfig {
         env = "LINERA_LISTENER_DELAY_AFTER"
     )]
     pub delay_after_ms: u64,
+
+    /// Wait until at least this many incoming messages are pending before processing a chain's
+    /// inbox. Chains can override this in the wallet.
+    #[serde(default)]
+    #[arg(
+        long = "listener-min-batch-messages",
+        env = "LINERA_LISTENER_MIN_BATCH_MESSAGES"
+    )]
+    pub min_batch_messages: Option<usize>,
+
+    /// The longest time to wait for a batch of incoming messages to fill up before processing
+    /// a chain's inbox anyway, in milliseconds. Chains can override this in the wallet.
+    #[serde(default)]
+    #[arg(
+        long = "listener-max-batch-delay-ms",
+        env = "LINERA_LISTENER_MAX_BATCH_DELAY"
+    )]
+    pub max_batch_delay_ms: Option<u64>,
+
+    /// The most tokens that the blocks processing a chain's inbox may spend in any hour.
+    /// Processing pauses once the cap is reached. Chains can override this in the wallet.
+    #[serde(default)]
+    #[arg(
+        long = "listener-max-fees-per-hour",
+        env = "LINERA_LISTENER_MAX_FEES_PER_HOUR"
+    )]
+    pub max_fees_per_hour: Option<Amount>,
+}
+
+impl ChainListenerConfig {
+    /// Returns the inbox policy for chains that don't configure their own.
+    pub fn default_inbox_policy(&self) -> InboxPolicy {
+        InboxPolicy {
+            message_policy: None,
+            min_batch_messages: self.min_batch_messages,
+            max_batch_delay_ms: self.max_batch_delay_ms,
+            max_fees_per_hour: self.max_fees_per_hour,
+        }
+    }
 }
 
 type ContextChainClient<C> = ChainClient<<C as ClientContext>::Environment>;
@@ -270,6 +314,8 @@ pub struct ChainListener<C: ClientContext> {
     command_receiver: UnboundedReceiver<ListenerCommand>,
     /// Whether to fully sync chains in the background.
     enable_background_sync: bool,
+    /// The schedules to run, if any.
+    scheduler: Option<Scheduler>,
 }
 
 impl<C: ClientContext + 'static> ChainListener<C> {
@@ -291,9 +337,16 @@ impl<C: ClientContext + 'static> ChainListener<C> {
             event_subscribers: Default::default(),
             command_receiver,
             enable_background_sync,
+            scheduler: None,
         }
     }
 
+    /// Runs the schedules of the given scheduler when they are due.
+    pub fn with_scheduler(mut self, scheduler: Scheduler) -> Self {
+        self.scheduler = Some(scheduler);
+        self
+    }
+
     /// Runs the chain listener.
     #[instrument(skip(self))]
     pub async fn run(mut self) -> Result<impl Future<Output = Result<(), Error>>, Error> {
@@ -342,6 +395,7 @@ impl<C: ClientContext + 'static> ChainListener<C> {
                     Action::Notification(notification) => {
                         self.process_notification(notification).await?
                     }
+                    Action::RunSchedules => self.run_due_schedules().await,
                 }
             }
             future::join_all(self.listening.into_values().map(|client| client.stop())).await;
@@ -533,12 +587,16 @@ impl<C: ClientContext + 'static> ChainListener<C> {
 
         // Start background tasks to sync received certificates, if enabled.
         let background_sync_task = self.start_background_sync(chain_id).await;
-        let client = self
+        let mut client = self
             .context
             .lock()
             .await
             .make_chain_client(chain_id)
             .await?;
+        let inbox_policy = inbox_policy(&self.context, &self.config, chain_id).await?;
+        if let Some(message_policy) = &inbox_policy.message_policy {
+            client.options_mut().message_policy = message_policy.clone();
+        }
         let (listener, abort_handle, notification_stream) = client.listen().await?;
         let listening_client = ListeningClient::new(
             client,
@@ -644,6 +702,9 @@ impl<C: ClientContext + 'static> ChainListener<C> {
                 () = self.cancellation_token.cancelled().fuse() => {
                     return Ok(Action::Stop);
                 }
+                () = Self::next_schedule(self.scheduler.as_ref()).fuse() => {
+                    return Ok(Action::RunSchedules);
+                }
                 command = self.command_receiver.recv().then(async |maybe_command| {
                     if let Some(command) = maybe_command {
                         command
@@ -711,6 +772,69 @@ impl<C: ClientContext + 'static> ChainListener<C> {
         }
     }
 
+    /// Waits until a schedule may be due, or until the schedules change.
+    async fn next_schedule(scheduler: Option<&Scheduler>) {
+        let Some(scheduler) = scheduler else {
+            return future::pending().await;
+        };
+        let sleep = match scheduler.next_due() {
+            None => future::pending().left_future(),
+            Some(next_due) => {
+                let delta = next_due.delta_since(Timestamp::now());
+                linera_base::time::timer::sleep(delta.as_duration()).right_future()
+            }
+        };
+        futures::select! {
+            () = sleep.fuse() => {},
+            () = scheduler.changed().fuse() => {},
+        }
+    }
+
+    /// Runs the schedules that are due, one after the other, and records their outcomes.
+    async fn run_due_schedules(&mut self) {
+        let Some(scheduler) = &self.scheduler else {
+            return;
+        };
+        for schedule in scheduler.due(Timestamp::now()) {
+            // Advance the schedule first, so that a failure to record the outcome, or a crash,
+            // doesn't make the action run again.
+            let Some(schedule) = scheduler.start_run(schedule.id, Timestamp::now()) else {
+                continue;
+            };
+            let chain_id = schedule.chain_id;
+            info!(id = schedule.id, %chain_id, action = schedule.action.summary(), "Running schedule");
+            let client = match self.context.lock().await.make_chain_client(chain_id).await {
+                Ok(client) => client,
+                Err(error) => {
+                    warn!(%error, id = schedule.id, "Failed to run schedule");
+                    scheduler.record_run(schedule.id, Timestamp::now(), Err(error.to_string()));
+                    continue;
+                }
+            };
+            let result = scheduler::run_action(&client, &schedule.action).await;
+            if let Err(error) = self.context.lock().await.update_wallet(&client).await {
+                warn!(%error, "Failed to update wallet after running schedule");
+            }
+            match result {
+                Ok(ClientOutcome::Committed(certificate_hashes)) => {
+                    scheduler.record_run(schedule.id, Timestamp::now(), Ok(certificate_hashes));
+                }
+                Ok(ClientOutcome::WaitForTimeout(timeout)) => {
+                    debug!(id = schedule.id, %timeout, "Waiting for round timeout to run schedule");
+                    scheduler.postpone(schedule.id, timeout.timestamp);
+                }
+                Ok(ClientOutcome::Conflict(certificate)) => {
+                    let error = chain_client::Error::Conflict(certificate.hash());
+                    scheduler.record_run(schedule.id, Timestamp::now(), Err(error.to_string()));
+                }
+                Err(error) => {
+                    warn!(%error, id = schedule.id, "Failed to run schedule");
+                    scheduler.record_run(schedule.id, Timestamp::now(), Err(error.to_string()));
+                }
+            }
+        }
+    }
+
     /// Updates the validators about the chain.
     async fn update_validators(&self, notification: &Notification) -> Result<(), Error> {
         let chain_id = notification.chain_id;
@@ -818,8 +942,11 @@ impl<C: ClientContext + 'static> ChainListener<C> {
 /// Per-chain inbox processing loop. Runs as a long-lived tokio task. Wakes on
 /// `inbox_notify` signals and processes the inbox, handling round-leader timeouts
 /// internally. Multiple notifications while busy collapse into a single permit.
+///
+/// The chain's inbox policy is read from the wallet on every wake-up. Before processing,
+/// waits for a batch of messages, and before each block, for the fee cap.
 async fn inbox_processing_loop<C: ClientContext>(
-    client: ContextChainClient<C>,
+    mut client: ContextChainClient<C>,
     context: Arc<Mutex<C>>,
     config: Arc<ChainListenerConfig>,
     inbox_notify: Arc<Notify>,
@@ -842,50 +969,39 @@ async fn inbox_processing_loop<C: ClientContext>(
                     debug!("Not processing inbox for follow-only chain {chain_id:.8}");
                     continue;
                 }
-                debug!("Processing inbox for {chain_id:.8}");
-
-                // Inner loop handles round-leader timeouts: if we can't produce a block
-                // because we're not the leader, sleep until the timeout then retry.
-                // A new notification or cancellation can interrupt the sleep.
-                loop {
-                    match client.process_inbox_without_prepare().await {
-                        Err(chain_client::Error::CannotFindKeyForChain(chain_id)) => {
-                            debug!(%chain_id, "Cannot find key for chain");
-                            break;
-                        }
-                        Err(error) => {
-                            warn!(%error, "Failed to process inbox");
-                            break;
-                        }
-                        Ok((certs, None)) => {
-                            if certs.is_empty() {
-                                debug!(%chain_id, "done processing inbox: no blocks created");
-                            } else {
-                                info!(
-                                    %chain_id,
-                                    created_block_count = %certs.len(),
-                                    "done processing inbox",
-                                );
-                            }
-                            break;
-                        }
-                        Ok((certs, Some(new_timeout))) => {
-                            info!(
-                                %chain_id,
-                                created_block_count = %certs.len(),
-                                timeout = %new_timeout,
-                                "waiting for round timeout before continuing to process the inbox",
-                            );
-                            let delta = new_timeout.timestamp.delta_since(Timestamp::now());
-                            if delta > TimeDelta::ZERO {
-                                futures::select! {
-                                    () = cancellation_token.cancelled().fuse() => return,
-                                    () = linera_base::time::timer::sleep(delta.as_duration()).fuse() => {},
-                                    () = inbox_notify.notified().fuse() => {},
-                                }
-                            }
-                        }
+                if client.is_light() {
+                    debug!("Not processing inbox for light chain {chain_id:.8}");
+                    continue;
+                }
+                let inbox_policy = match inbox_policy(&context, &config, chain_id).await {
+                    Ok(inbox_policy) => inbox_policy,
+                    Err(error) => {
+                        warn!(%error, "Failed to read the inbox policy from the wallet");
+                        continue;
                     }
+                };
+                if let Some(message_policy) = &inbox_policy.message_policy {
+                    client.options_mut().message_policy = message_policy.clone();
+                }
+                if !wait_for_batch(&client, &inbox_policy, &inbox_notify, &cancellation_token).await {
+                    break;
+                }
+                debug!("Processing inbox for {chain_id:.8}");
+                let Some(created_block_count) = process_inbox(
+                    &client,
+                    &context,
+                    &inbox_policy,
+                    &inbox_notify,
+                    &cancellation_token,
+                )
+                .await
+                else {
+                    return;
+                };
+                if created_block_count == 0 {
+                    debug!(%chain_id, "done processing inbox: no blocks created");
+                } else {
+                    info!(%chain_id, %created_block_count, "done processing inbox");
                 }
 
                 if let Err(error) = context.lock().await.update_wallet(&client).await {
@@ -896,7 +1012,241 @@ async fn inbox_processing_loop<C: ClientContext>(
     }
 }
 
+/// Creates blocks to process the chain's inbox, one at a time, until it is empty. Before each
+/// block, waits until the fees of the inbox blocks in the last hour are below the cap; a new
+/// notification ends that wait and the round. Waits for round timeouts when we can't produce
+/// a block because we're not the leader; a new notification interrupts that wait.
+///
+/// Returns the number of blocks created, or `None` if the task was cancelled.
+async fn process_inbox<C: ClientContext>(
+    client: &ContextChainClient<C>,
+    context: &Mutex<C>,
+    inbox_policy: &InboxPolicy,
+    inbox_notify: &Notify,
+    cancellation_token: &CancellationToken,
+) -> Option<usize> {
+    let chain_id = client.chain_id();
+    let mut inbox_fees = match inbox_policy.max_fees_per_hour {
+        Some(_) => match InboxFees::load(context, chain_id).await {
+            Ok(inbox_fees) => Some(inbox_fees),
+            Err(error) => {
+                warn!(%error, "Failed to read the inbox fees from the wallet");
+                return Some(0);
+            }
+        },
+        None => None,
+    };
+    let mut created_block_count = 0;
+    loop {
+        if let (Some(max_fees), Some(inbox_fees)) =
+            (inbox_policy.max_fees_per_hour, &mut inbox_fees)
+        {
+            if let Some(paused_until) = inbox_fees.paused_until(Timestamp::now(), max_fees) {
+                info!(
+                    %chain_id,
+                    %max_fees,
+                    %paused_until,
+                    "hourly fee cap reached: pausing inbox processing",
+                );
+                let delta = paused_until.delta_since(Timestamp::now());
+                futures::select! {
+                    () = cancellation_token.cancelled().fuse() => return None,
+                    () = linera_base::time::timer::sleep(delta.as_duration()).fuse() => {},
+                    () = inbox_notify.notified().fuse() => {
+                        // Start a new round, so that a changed inbox policy takes effect.
+                        inbox_notify.notify_one();
+                        return Some(created_block_count);
+                    }
+                }
+                continue;
+            }
+        }
+        match client.process_inbox_block().await {
+            Err(chain_client::Error::CannotFindKeyForChain(chain_id)) => {
+                debug!(%chain_id, "Cannot find key for chain");
+                return Some(created_block_count);
+            }
+            Err(error) => {
+                warn!(%error, "Failed to process inbox");
+                return Some(created_block_count);
+            }
+            Ok(ClientOutcome::Committed(None)) => return Some(created_block_count),
+            Ok(ClientOutcome::Committed(Some((_, fees)))) => {
+                created_block_count += 1;
+                if let Some(inbox_fees) = &mut inbox_fees {
+                    inbox_fees.record(Timestamp::now(), fees);
+                    if let Err(error) = inbox_fees.save(context, chain_id).await {
+                        warn!(%error, "Failed to save the inbox fees in the wallet");
+                    }
+                }
+            }
+            // Another block was committed at this height; it wasn't one of our inbox blocks.
+            Ok(ClientOutcome::Conflict(_)) => {}
+            Ok(ClientOutcome::WaitForTimeout(timeout)) => {
+                info!(
+                    %chain_id,
+                    %created_block_count,
+                    %timeout,
+                    "waiting for round timeout before continuing to process the inbox",
+                );
+                let delta = timeout.timestamp.delta_since(Timestamp::now());
+                if delta > TimeDelta::ZERO {
+                    futures::select! {
+                        () = cancellation_token.cancelled().fuse() => return None,
+                        () = linera_base::time::timer::sleep(delta.as_duration()).fuse() => {},
+                        () = inbox_notify.notified().fuse() => {},
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the chain's inbox policy from the wallet, completed with the listener's
+/// configuration.
+async fn inbox_policy<C: ClientContext>(
+    context: &Mutex<C>,
+    config: &ChainListenerConfig,
+    chain_id: ChainId,
+) -> Result<InboxPolicy, Error> {
+    let chain = context
+        .lock()
+        .await
+        .wallet()
+        .get(chain_id)
+        .await
+        .map_err(error::Inner::wallet)?;
+    Ok(chain
+        .and_then(|chain| chain.inbox_policy)
+        .unwrap_or_default()
+        .with_defaults(&config.default_inbox_policy()))
+}
+
+/// Waits until enough incoming messages are pending, or until the batch delay has passed,
+/// according to the inbox policy. Returns `false` if the task was cancelled.
+async fn wait_for_batch<Env: Environment>(
+    client: &ChainClient<Env>,
+    inbox_policy: &InboxPolicy,
+    inbox_notify: &Notify,
+    cancellation_token: &CancellationToken,
+) -> bool {
+    let deadline = inbox_policy
+        .max_batch_delay_ms
+        .map(|delay_ms| Timestamp::now().saturating_add(TimeDelta::from_millis(delay_ms)));
+    loop {
+        match inbox_policy.min_batch_messages {
+            Some(min_messages) => match client.pending_message_count().await {
+                Ok(count) if count >= min_messages => return true,
+                Ok(count) => debug!(
+                    chain_id = %client.chain_id(),
+                    count,
+                    min_messages,
+                    "waiting for more incoming messages before processing the inbox",
+                ),
+                Err(error) => {
+                    warn!(%error, "Failed to count the pending messages");
+                    return true;
+                }
+            },
+            None if deadline.is_none() => return true,
+            None => {}
+        }
+        let sleep = match deadline {
+            Some(deadline) => {
+                let delta = deadline.delta_since(Timestamp::now());
+                if delta == TimeDelta::ZERO {
+                    return true;
+                }
+                linera_base::time::timer::sleep(delta.as_duration()).left_future()
+            }
+            None => future::pending().right_future(),
+        };
+        futures::select! {
+            () = cancellation_token.cancelled().fuse() => return false,
+            () = sleep.fuse() => return true,
+            () = inbox_notify.notified().fuse() => {},
+        }
+    }
+}
+
+/// The fees charged for the inbox blocks of a chain in the last hour, as computed when
+/// staging each block. They are kept in the wallet.
+struct InboxFees {
+    /// When the fees were spent, and how much, in chronological order.
+    spent: VecDeque<(Timestamp, Amount)>,
+}
+
+impl InboxFees {
+    /// The period over which fees are capped.
+    const WINDOW: TimeDelta = TimeDelta::from_secs(3600);
+
+    /// Reads the fees recorded in the wallet for the given chain.
+    async fn load<C: ClientContext>(context: &Mutex<C>, chain_id: ChainId) -> Result<Self, Error> {
+        let chain = context
+            .lock()
+            .await
+            .wallet()
+            .get(chain_id)
+            .await
+            .map_err(error::Inner::wallet)?;
+        Ok(Self {
+            spent: chain.map(|chain| chain.inbox_fees).unwrap_or_default(),
+        })
+    }
+
+    /// Records the fees in the wallet for the given chain.
+    async fn save<C: ClientContext>(
+        &self,
+        context: &Mutex<C>,
+        chain_id: ChainId,
+    ) -> Result<(), Error> {
+        context
+            .lock()
+            .await
+            .wallet()
+            .modify(chain_id, |chain| chain.inbox_fees = self.spent.clone())
+            .await
+            .map_err(error::Inner::wallet)?;
+        Ok(())
+    }
+
+    /// Records fees spent at the given time.
+    fn record(&mut self, now: Timestamp, fees: Amount) {
+        if fees > Amount::ZERO {
+            self.spent.push_back((now, fees));
+        }
+    }
+
+    /// Returns when the fees spent in the last hour will be below `max_fees` again, or `None`
+    /// if they already are.
+    fn paused_until(&mut self, now: Timestamp, max_fees: Amount) -> Option<Timestamp> {
+        while self
+            .spent
+            .front()
+            .is_some_and(|(time, _)| now.delta_since(*time) >= Self::WINDOW)
+        {
+            self.spent.pop_front();
+        }
+        let mut total = self
+            .spent
+            .iter()
+            .fold(Amount::ZERO, |total, (_, fees)| total.saturating_add(*fees));
+        if total < max_fees {
+            return None;
+        }
+        for (time, fees) in &self.spent {
+            total = total.saturating_sub(*fees);
+            if total < max_fees {
+                return Some(time.saturating_add(Self::WINDOW));
+            }
+        }
+        // With a zero cap, processing stays paused: check again in an hour.
+        Some(now.saturating_add(Self::WINDOW))
+    }
+}
+
 enum Action {
     Notification(Notification),
+    RunSchedules,
     Stop,
 }
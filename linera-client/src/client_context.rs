@@ -8,8 +8,8 @@ use futures::StreamExt as _;
 use futures::{Future, TryStreamExt as _};
 use linera_base::{
     crypto::{CryptoHash, ValidatorPublicKey},
-    data_types::{ChainDescription, Epoch, Timestamp},
-    identifiers::{Account, AccountOwner, ChainId},
+    data_types::{BlockHeight, ChainDescription, Epoch, Timestamp},
+    identifiers::{Account, AccountOwner, BlobType, ChainId},
     ownership::ChainOwnership,
     time::{Duration, Instant},
     util::future::FutureSyncExt as _,
@@ -22,7 +22,10 @@ use linera_core::{
     node::ValidatorNode,
     wallet, Environment, JoinSetExt as _, Wallet as _,
 };
-use linera_rpc::node_provider::{NodeOptions, NodeProvider};
+use linera_rpc::{
+    compression::Compression,
+    node_provider::{NodeOptions, NodeProvider},
+};
 use linera_storage::Storage as _;
 use linera_version::VersionInfo;
 use thiserror_context::Context;
@@ -34,11 +37,7 @@ use {
         client_metrics::ClientMetrics,
     },
     futures::stream,
-    linera_base::{
-        crypto::AccountPublicKey,
-        data_types::{Amount, BlockHeight},
-        identifiers::{ApplicationId, BlobType},
-    },
+    linera_base::{crypto::AccountPublicKey, data_types::Amount, identifiers::ApplicationId},
     linera_execution::{
         system::{OpenChainConfig, SystemOperation},
         Operation,
@@ -53,17 +52,36 @@ use {
         identifiers::ModuleId,
         vm::VmRuntime,
     },
-    linera_core::client::create_bytecode_blobs,
-    std::{fs, path::PathBuf},
+    linera_chain::ChainError,
+    linera_core::{
+        client::{create_bytecode_blobs, requests_scheduler::NodeStatistics},
+        worker::WorkerError,
+        LocalNodeError,
+    },
+    std::{
+        collections::{btree_map::Entry, BTreeMap},
+        fs,
+        path::PathBuf,
+    },
 };
 
+#[cfg(feature = "fs")]
+use crate::batch::{Batch, BatchError, BatchProgress, BlockLimits, PendingBatchBlock};
 use crate::{
+    chain_history::{self, HistoryEntry, HistoryFilter},
     chain_listener::{self, ClientContext as _},
     client_options::{ChainOwnershipConfig, Options},
     config::GenesisConfig,
     error, util, Error,
 };
 
+/// How often the validator statistics are saved while the client context is in use.
+#[cfg(feature = "fs")]
+const VALIDATOR_SCORES_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The number of blocks read from storage at once when collecting a chain's history.
+const HISTORY_BLOCKS_PER_READ: usize = 100;
+
 /// Results from querying a validator about version, network description, and chain info.
 pub struct ValidatorQueryResults {
     /// The validator's version information.
@@ -214,11 +232,19 @@ pub struct ClientContext<Env: Environment> {
     pub retry_delay: Duration,
     pub max_retries: u32,
     pub max_backoff: Duration,
+    pub compression: Option<Compression>,
     pub chain_listeners: JoinSet,
     // TODO(#5082): move this into the upstream UI layers (maybe just the CLI)
     pub default_chain: Option<ChainId>,
     #[cfg(not(web))]
     pub client_metrics: Option<ClientMetrics>,
+    /// Where the validator statistics are saved, periodically and when the context is
+    /// dropped.
+    #[cfg(feature = "fs")]
+    pub validator_scores_path: Option<PathBuf>,
+    /// The task saving the validator statistics periodically, if they are saved.
+    #[cfg(feature = "fs")]
+    pub validator_scores_saver: Option<linera_base::task::Task<()>>,
 }
 
 impl<Env: Environment> chain_listener::ClientContext for ClientContext<Env> {
@@ -290,6 +316,7 @@ where
             retry_delay: options.retry_delay,
             max_retries: options.max_retries,
             max_backoff: options.max_backoff,
+            compression: options.compression,
         });
         let chain_modes: Vec<_> = wallet
             .items()
@@ -350,13 +377,31 @@ where
             retry_delay: options.retry_delay,
             max_retries: options.max_retries,
             max_backoff: options.max_backoff,
+            compression: options.compression,
             chain_listeners: JoinSet::default(),
             #[cfg(not(web))]
             client_metrics,
+            #[cfg(feature = "fs")]
+            validator_scores_path: None,
+            #[cfg(feature = "fs")]
+            validator_scores_saver: None,
         })
     }
 }
 
+#[cfg(feature = "fs")]
+impl<Env: Environment> Drop for ClientContext<Env> {
+    fn drop(&mut self) {
+        let Some(path) = &self.validator_scores_path else {
+            return;
+        };
+        match self.client.try_validator_statistics() {
+            Some(statistics) => write_validator_scores(path, statistics),
+            None => debug!("Not saving validator scores while they are being updated"),
+        }
+    }
+}
+
 impl<Env: Environment> ClientContext<Env> {
     // TODO(#5084) this (and other injected dependencies) should not be re-exposed by the
     // client interface
@@ -409,6 +454,7 @@ impl<Env: Environment> ClientContext<Env> {
             retry_delay: self.retry_delay,
             max_retries: self.max_retries,
             max_backoff: self.max_backoff,
+            compression: self.compression,
         }
     }
 
@@ -421,14 +467,18 @@ impl<Env: Environment> ClientContext<Env> {
         &self,
         client: &ChainClient<Env_>,
     ) -> Result<(), Error> {
-        let info = client.chain_info().await?;
+        // Light chains are not executed locally, so their verified state is newer.
+        let info = match client.light_chain_info() {
+            Some(info) => info,
+            None => client.chain_info().await?,
+        };
         let chain_id = info.chain_id;
-        let existing_owner = self
+        let existing = self
             .wallet()
             .get(chain_id)
             .await
             .map_err(error::Inner::wallet)?
-            .and_then(|chain| chain.owner);
+            .unwrap_or_default();
 
         // Only persist proposals that were made in the fast round: they need to be
         // remembered across sessions to make sure there are no conflicting fast proposals.
@@ -438,7 +488,9 @@ impl<Env: Environment> ClientContext<Env> {
             .filter(|p| p.round.is_some_and(|r| r.is_fast()));
         let new_chain = wallet::Chain {
             pending_fast_proposal,
-            owner: existing_owner,
+            owner: existing.owner,
+            inbox_policy: existing.inbox_policy,
+            inbox_fees: existing.inbox_fees,
             ..info.as_ref().into()
         };
 
@@ -616,6 +668,73 @@ impl<Env: Environment> ClientContext<Env> {
         }
     }
 
+    /// Returns the history of a chain from the height `from` to the height `to`, both
+    /// included, or to the latest block.
+    ///
+    /// The blocks are read from local storage. If the latest blocks are needed, the chain is
+    /// synchronized with the validators first, and any other block missing locally is
+    /// downloaded from them. No block is skipped: if one can't be obtained, this fails.
+    pub async fn chain_history(
+        &self,
+        chain_id: ChainId,
+        from: BlockHeight,
+        to: Option<BlockHeight>,
+        filter: &HistoryFilter,
+    ) -> Result<Vec<HistoryEntry>, Error> {
+        let certificates = self.read_certificates(chain_id, from, to).await?;
+        Ok(certificates
+            .iter()
+            .flat_map(|certificate| {
+                chain_history::block_history(certificate.hash(), certificate.block(), filter)
+            })
+            .collect())
+    }
+
+    /// Returns the descriptions of the chains that the blocks of the given chain created.
+    pub async fn created_chains(&self, chain_id: ChainId) -> Result<Vec<ChainDescription>, Error> {
+        let certificates = self
+            .read_certificates(chain_id, BlockHeight::ZERO, None)
+            .await?;
+        let mut descriptions = Vec::new();
+        for certificate in &certificates {
+            for blob in certificate.block().body.blobs.iter().flatten() {
+                if blob.id().blob_type == BlobType::ChainDescription {
+                    descriptions.push(bcs::from_bytes(blob.content().bytes())?);
+                }
+            }
+        }
+        Ok(descriptions)
+    }
+
+    /// Reads the certificates of a chain from the height `from` to the height `to`, both
+    /// included, or to the latest block, synchronizing the chain with the validators if the
+    /// latest blocks are needed. Certificates missing from local storage are downloaded.
+    async fn read_certificates(
+        &self,
+        chain_id: ChainId,
+        from: BlockHeight,
+        to: Option<BlockHeight>,
+    ) -> Result<Vec<Arc<ConfirmedBlockCertificate>>, Error> {
+        let chain_client = self.make_chain_client(chain_id).await?;
+        let mut next_height = chain_client.chain_info().await?.next_block_height;
+        if to.is_none_or(|to| to >= next_height) {
+            next_height = chain_client
+                .synchronize_chain_state(chain_id)
+                .await?
+                .next_block_height;
+        }
+        let end = match to {
+            Some(to) => to.try_add_one()?.min(next_height),
+            None => next_height,
+        };
+        let heights = (from.0..end.0).map(BlockHeight).collect::<Vec<_>>();
+        let mut result = Vec::new();
+        for heights in heights.chunks(HISTORY_BLOCKS_PER_READ) {
+            result.extend(chain_client.read_certificates_by_heights(heights).await?);
+        }
+        Ok(result)
+    }
+
     pub async fn ownership(&mut self, chain_id: Option<ChainId>) -> Result<ChainOwnership, Error> {
         let chain_id = chain_id.unwrap_or_else(|| self.default_chain());
         let client = self.make_chain_client(chain_id).await?;
@@ -819,6 +938,29 @@ impl<Env: Environment> ClientContext<Env> {
 
 #[cfg(feature = "fs")]
 impl<Env: Environment> ClientContext<Env> {
+    /// Restores the validator statistics saved at `path` by an earlier session, if any, so
+    /// that requests go to the best validators right away. They are saved there again every
+    /// [`VALIDATOR_SCORES_SAVE_INTERVAL`] and when the context is dropped.
+    pub async fn load_validator_scores(&mut self, path: PathBuf) {
+        match fs::read(&path) {
+            Ok(bytes) => match serde_json::from_slice(&bytes) {
+                Ok(statistics) => self.client.restore_validator_statistics(statistics).await,
+                Err(error) => warn!("Ignoring invalid validator scores in {path:?}: {error}"),
+            },
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => warn!("Failed to read validator scores from {path:?}: {error}"),
+        }
+        let client = self.client.clone();
+        let saved_path = path.clone();
+        self.validator_scores_saver = Some(linera_base::task::Task::spawn(async move {
+            loop {
+                linera_base::time::timer::sleep(VALIDATOR_SCORES_SAVE_INTERVAL).await;
+                write_validator_scores(&saved_path, client.validator_statistics().await);
+            }
+        }));
+        self.validator_scores_path = Some(path);
+    }
+
     pub async fn publish_module(
         &mut self,
         chain_client: &ChainClient<Env>,
@@ -914,6 +1056,197 @@ impl<Env: Environment> ClientContext<Env> {
         info!("{}", "Data blob verified successfully!");
         Ok(())
     }
+
+    /// Executes the operations of a batch in as few blocks as the chain's resource control
+    /// policy allows, with at most `max_operations` operations per block.
+    ///
+    /// The progress is written to `progress_path` before and after each block. If the file
+    /// already exists, the batch is resumed: rows that were committed are not executed again.
+    pub async fn execute_batch(
+        &mut self,
+        batch: &Batch,
+        max_operations: usize,
+        progress_path: &std::path::Path,
+    ) -> Result<BatchProgress, Error> {
+        let mut progress = match fs::read(progress_path) {
+            Ok(bytes) => {
+                let progress: BatchProgress = serde_json::from_slice(&bytes)
+                    .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))?;
+                progress.check(batch)?;
+                progress
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => BatchProgress::new(batch),
+            Err(error) => return Err(error.into()),
+        };
+        let chain_client = self.make_chain_client(batch.chain_id).await?;
+        chain_client.synchronize_from_validators().await?;
+        if progress.pending.is_some() {
+            self.resolve_pending_batch_block(&chain_client, batch, &mut progress)
+                .await?;
+            save_batch_progress(progress_path, &progress)?;
+        }
+
+        let committee = chain_client.local_committee().await?;
+        let mut limits = BlockLimits::new(committee.policy(), max_operations);
+        while !progress.is_complete() {
+            let start = progress.next_row();
+            let end = limits.next_chunk(&batch.operations, start);
+            let height = chain_client.chain_info().await?.next_block_height;
+            progress.pending = Some(PendingBatchBlock {
+                rows: start..end,
+                height,
+            });
+            save_batch_progress(progress_path, &progress)?;
+
+            let operations = batch.operations[start..end].to_vec();
+            let certificate = self
+                .apply_client_command(&chain_client, |chain_client| {
+                    let chain_client = chain_client.clone();
+                    let operations = operations.clone();
+                    async move {
+                        // Blocks that exceed a limit fail locally, before they are proposed.
+                        match chain_client.execute_operations(operations, vec![]).await {
+                            Err(error) if is_block_limit_error(&error) => {
+                                Ok(ClientOutcome::Committed(None))
+                            }
+                            Ok(ClientOutcome::Committed(certificate)) => {
+                                Ok(ClientOutcome::Committed(Some(certificate)))
+                            }
+                            Ok(ClientOutcome::WaitForTimeout(timeout)) => {
+                                Ok(ClientOutcome::WaitForTimeout(timeout))
+                            }
+                            Ok(ClientOutcome::Conflict(certificate)) => {
+                                Ok(ClientOutcome::Conflict(certificate))
+                            }
+                            Err(error) => Err(error),
+                        }
+                    }
+                })
+                .await?;
+            match certificate {
+                Some(certificate) => {
+                    let height = certificate.block().header.height;
+                    info!(
+                        "Rows {} to {} of the batch committed in block {height}",
+                        start + 1,
+                        end
+                    );
+                    progress.commit_pending(height, certificate.hash());
+                }
+                None => {
+                    progress.pending = None;
+                    save_batch_progress(progress_path, &progress)?;
+                    if end - start == 1 {
+                        return Err(BatchError::RowTooLarge(start + 1).into());
+                    }
+                    limits.max_operations = (end - start) / 2;
+                    info!(
+                        "Rows {} to {} exceed the block limits; trying with at most {} rows",
+                        start + 1,
+                        end,
+                        limits.max_operations
+                    );
+                }
+            }
+            save_batch_progress(progress_path, &progress)?;
+        }
+        Ok(progress)
+    }
+
+    /// Checks whether the pending rows of a batch were committed before the client stopped,
+    /// and records the block if they were.
+    async fn resolve_pending_batch_block(
+        &mut self,
+        chain_client: &ChainClient<Env>,
+        batch: &Batch,
+        progress: &mut BatchProgress,
+    ) -> Result<(), Error> {
+        let Some(pending) = progress.pending.clone() else {
+            return Ok(());
+        };
+        let operations = &batch.operations[pending.rows.clone()];
+        let next_height = chain_client.chain_info().await?.next_block_height;
+        let heights = (pending.height.0..next_height.0)
+            .map(BlockHeight)
+            .collect::<Vec<_>>();
+        let certificates = chain_client
+            .storage_client()
+            .read_certificates_by_heights(batch.chain_id, &heights)
+            .await?;
+        for certificate in certificates.into_iter().flatten() {
+            let block_operations = certificate.block().body.operations().collect::<Vec<_>>();
+            if block_operations.ends_with(&operations.iter().collect::<Vec<_>>()) {
+                info!(
+                    "Rows {} to {} of the batch were already committed in block {}",
+                    pending.rows.start + 1,
+                    pending.rows.end,
+                    certificate.block().header.height
+                );
+                progress.commit_pending(certificate.block().header.height, certificate.hash());
+                return Ok(());
+            }
+        }
+        progress.pending = None;
+        Ok(())
+    }
+}
+
+/// Writes the progress of a batch, replacing the file atomically.
+#[cfg(feature = "fs")]
+fn save_batch_progress(path: &std::path::Path, progress: &BatchProgress) -> Result<(), Error> {
+    let bytes = serde_json::to_vec_pretty(progress)
+        .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))?;
+    let mut temporary_path = path.as_os_str().to_owned();
+    temporary_path.push(".tmp");
+    fs::write(&temporary_path, bytes)?;
+    fs::rename(&temporary_path, path)?;
+    Ok(())
+}
+
+/// Saves validator statistics to `path`. Statistics saved in the meantime by other clients
+/// are kept where they are more recent. The file is replaced atomically.
+#[cfg(feature = "fs")]
+fn write_validator_scores(
+    path: &std::path::Path,
+    mut statistics: BTreeMap<ValidatorPublicKey, NodeStatistics>,
+) {
+    if let Ok(bytes) = fs::read(path) {
+        let saved: BTreeMap<ValidatorPublicKey, NodeStatistics> =
+            serde_json::from_slice(&bytes).unwrap_or_default();
+        for (public_key, saved) in saved {
+            match statistics.entry(public_key) {
+                Entry::Vacant(entry) => {
+                    entry.insert(saved);
+                }
+                Entry::Occupied(mut entry) if entry.get().updated_at < saved.updated_at => {
+                    entry.insert(saved);
+                }
+                Entry::Occupied(_) => {}
+            }
+        }
+    }
+    // Each process uses its own temporary file, so that concurrent saves don't mix.
+    let mut temporary_path = path.as_os_str().to_owned();
+    temporary_path.push(format!(".{}.tmp", std::process::id()));
+    let result = serde_json::to_vec_pretty(&statistics)
+        .map_err(std::io::Error::from)
+        .and_then(|bytes| fs::write(&temporary_path, bytes))
+        .and_then(|()| fs::rename(&temporary_path, path));
+    if let Err(error) = result {
+        warn!("Failed to save validator scores to {path:?}: {error}");
+    }
+}
+
+/// Returns whether executing a block failed locally because it exceeded a per-block limit.
+#[cfg(feature = "fs")]
+fn is_block_limit_error(error: &chain_client::Error) -> bool {
+    let chain_client::Error::LocalNodeError(LocalNodeError::WorkerError(WorkerError::ChainError(
+        error,
+    ))) = error
+    else {
+        return false;
+    };
+    matches!(&**error, ChainError::ExecutionError(error, _) if error.is_limit_error())
 }
 
 #[cfg(not(web))]
@@ -7,7 +7,7 @@ use std::{
 };
 
 use linera_base::{
-    data_types::{ApplicationPermissions, BlanketMessagePolicy, MessagePolicy, TimeDelta},
+    data_types::{Amount, ApplicationPermissions, BlanketMessagePolicy, MessagePolicy, TimeDelta},
     identifiers::{AccountOwner, ApplicationId, ChainId, GenericApplicationId},
     ownership::ChainOwnership,
     time::Duration,
@@ -19,9 +19,11 @@ use linera_core::{
         DEFAULT_SENDER_CERTIFICATE_DOWNLOAD_BATCH_SIZE,
     },
     node::CrossChainMessageDelivery,
+    wallet::InboxPolicy,
     DEFAULT_QUORUM_GRACE_PERIOD,
 };
 use linera_execution::ResourceControlPolicy;
+use linera_rpc::compression::Compression;
 
 #[cfg(not(web))]
 use crate::client_metrics::TimingConfig;
@@ -122,6 +124,11 @@ pub struct Options {
     )]
     pub max_backoff: Duration,
 
+    /// Compression that validators are asked to use for large responses, such as
+    /// certificates and blobs.
+    #[arg(long)]
+    pub compression: Option<Compression>,
+
     /// Initial probe interval (ms) for the notification circuit breaker. When a validator's
     /// notification stream exhausts retries, the circuit breaker waits this long before
     /// probing again. Doubles on each failed probe.
@@ -254,6 +261,27 @@ pub struct Options {
     #[arg(long, default_value_t = DEFAULT_MAX_EVENT_STREAM_QUERIES)]
     pub max_event_stream_queries: usize,
 
+    /// How long to wait (ms) for more chain summary queries to the same validator before
+    /// sending them in one batch.
+    #[arg(
+        long = "chain-summary-batch-delay-ms",
+        default_value = "10",
+        value_parser = util::parse_millis
+    )]
+    pub chain_summary_batch_delay: Duration,
+
+    /// Follow chains other than the admin chain as a light client: check the certificate of
+    /// each chain's latest block against the committee of its epoch, and balances against
+    /// proofs under the block's state hash, without executing blocks. Such chains cannot
+    /// propose blocks. This requires the committee's policy to commit to the state roots.
+    #[arg(long)]
+    pub light_client: bool,
+
+    /// A chain owned by the signer to claim tokens from when a block proposal would exceed
+    /// the funding available in the signer's account and the chain balance.
+    #[arg(long = "funding-chain")]
+    pub funding_chain_id: Option<ChainId>,
+
     /// Maximum expected latency in milliseconds for score normalization.
     #[arg(
         long,
@@ -307,6 +335,23 @@ pub struct Options {
     )]
     pub alternative_peers_retry_delay_ms: u64,
 
+    /// How the latency and success rate of validators are combined into a score.
+    #[arg(
+        long,
+        default_value_t,
+        env = "LINERA_REQUESTS_SCHEDULER_SCORING_STRATEGY"
+    )]
+    pub scoring_strategy: linera_core::client::requests_scheduler::ScoringStrategy,
+
+    /// The age in milliseconds after which the persisted statistics of a validator count
+    /// half as much.
+    #[arg(
+        long,
+        default_value_t = linera_core::client::requests_scheduler::STATISTICS_HALF_LIFE_MS,
+        env = "LINERA_REQUESTS_SCHEDULER_STATISTICS_HALF_LIFE_MS"
+    )]
+    pub statistics_half_life_ms: u64,
+
     #[serde(flatten)]
     #[clap(flatten)]
     pub chain_listener_config: crate::chain_listener::ChainListenerConfig,
@@ -364,11 +409,15 @@ impl Options {
             sender_certificate_download_batch_size: self.sender_certificate_download_batch_size,
             max_joined_tasks: self.max_joined_tasks,
             allow_fast_blocks: self.allow_fast_blocks,
+            single_leader_rounds_only: false,
             notification_circuit_breaker_initial_probe_interval: self
                 .notification_circuit_breaker_initial_probe_interval,
             notification_circuit_breaker_max_probe_interval: self
                 .notification_circuit_breaker_max_probe_interval,
             max_event_stream_queries: self.max_event_stream_queries,
+            chain_summary_batch_delay: self.chain_summary_batch_delay,
+            light_client: self.light_client,
+            funding_chain_id: self.funding_chain_id,
         }
     }
 
@@ -392,6 +441,11 @@ impl Options {
             max_request_ttl_ms: self.max_request_ttl_ms,
             alpha: self.alpha,
             retry_delay_ms: self.alternative_peers_retry_delay_ms,
+            scoring_weights: linera_core::client::ScoringWeights {
+                strategy: self.scoring_strategy,
+                ..Default::default()
+            },
+            statistics_half_life_ms: self.statistics_half_life_ms,
         }
     }
 }
@@ -574,6 +628,88 @@ impl ApplicationPermissionsConfig {
     }
 }
 
+#[derive(Debug, Clone, clap::Args)]
+pub struct InboxPolicyConfig {
+    /// The policy for handling incoming messages on this chain.
+    #[arg(long, value_enum)]
+    pub blanket_message_policy: Option<BlanketMessagePolicy>,
+
+    /// A set of chains to restrict incoming messages from. To reject messages from all chains,
+    /// specify an empty string.
+    #[arg(long, value_parser = util::parse_chain_set)]
+    pub restrict_chain_ids_to: Option<HashSet<ChainId>>,
+
+    /// A set of chains whose incoming messages are ignored.
+    #[arg(long, value_parser = util::parse_chain_set)]
+    pub ignore_bundles_from: Option<HashSet<ChainId>>,
+
+    /// A set of application IDs. If specified, only bundles with at least one message from one of
+    /// these applications will be accepted.
+    #[arg(long, value_parser = util::parse_app_set)]
+    pub reject_message_bundles_without_application_ids: Option<HashSet<GenericApplicationId>>,
+
+    /// A set of application IDs. If specified, only bundles where all messages are from one of
+    /// these applications will be accepted.
+    #[arg(long, value_parser = util::parse_app_set)]
+    pub reject_message_bundles_with_other_application_ids: Option<HashSet<GenericApplicationId>>,
+
+    /// A set of application IDs. If specified, only events coming from streams created by
+    /// applications from this set will be processed.
+    #[arg(long, value_parser = util::parse_app_set)]
+    pub process_events_from_application_ids: Option<HashSet<GenericApplicationId>>,
+
+    /// A set of application IDs whose messages must never be rejected.
+    #[arg(long, value_parser = util::parse_app_set)]
+    pub never_reject_application_ids: Option<HashSet<GenericApplicationId>>,
+
+    /// Wait until at least this many incoming messages are pending before processing the
+    /// inbox.
+    #[arg(long)]
+    pub min_batch_messages: Option<usize>,
+
+    /// The longest time to wait for a batch of incoming messages to fill up before processing
+    /// the inbox anyway, in milliseconds.
+    #[arg(long)]
+    pub max_batch_delay_ms: Option<u64>,
+
+    /// The most tokens that the blocks processing the inbox may spend in any hour.
+    #[arg(long)]
+    pub max_fees_per_hour: Option<Amount>,
+}
+
+impl From<InboxPolicyConfig> for InboxPolicy {
+    fn from(config: InboxPolicyConfig) -> InboxPolicy {
+        let has_message_policy = config.blanket_message_policy.is_some()
+            || config.restrict_chain_ids_to.is_some()
+            || config.ignore_bundles_from.is_some()
+            || config
+                .reject_message_bundles_without_application_ids
+                .is_some()
+            || config
+                .reject_message_bundles_with_other_application_ids
+                .is_some()
+            || config.process_events_from_application_ids.is_some()
+            || config.never_reject_application_ids.is_some();
+        let message_policy = has_message_policy.then(|| MessagePolicy {
+            blanket: config.blanket_message_policy.unwrap_or_default(),
+            restrict_chain_ids_to: config.restrict_chain_ids_to,
+            ignore_chain_ids: config.ignore_bundles_from.unwrap_or_default(),
+            reject_message_bundles_without_application_ids: config
+                .reject_message_bundles_without_application_ids,
+            reject_message_bundles_with_other_application_ids: config
+                .reject_message_bundles_with_other_application_ids,
+            process_events_from_application_ids: config.process_events_from_application_ids,
+            never_reject_application_ids: config.never_reject_application_ids.unwrap_or_default(),
+        });
+        InboxPolicy {
+            message_policy,
+            min_batch_messages: config.min_batch_messages,
+            max_batch_delay_ms: config.max_batch_delay_ms,
+            max_fees_per_hour: config.max_fees_per_hour,
+        }
+    }
+}
+
 #[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ResourceControlPolicyConfig {
     NoFees,
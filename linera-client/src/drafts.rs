@@ -0,0 +1,653 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Coordination of the blocks proposed on chains with several owners.
+//!
+//! An owner publishes a [`Draft`] of the operations they want to execute. The other owners
+//! review it and sign a [`DraftVote`] to approve or reject it. Once owners holding a strict
+//! majority of the weights in the chain's [`ChainOwnership`] have approved the draft, one of
+//! them proposes it as a block.
+//!
+//! A coordinator, usually a node service, collects the drafts and votes in a [`DraftBook`],
+//! shared and saved through a [`Drafts`] handle. Owners talk to it with a
+//! [`DraftCoordinator`].
+
+use std::{
+    collections::{btree_map, BTreeMap},
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use linera_base::{
+    bcs_scalar,
+    crypto::{AccountSignature, BcsHashable, BcsSignable, CryptoError, CryptoHash, Signer},
+    data_types::Timestamp,
+    ensure, hex,
+    identifiers::{AccountOwner, ChainId},
+    ownership::ChainOwnership,
+};
+use linera_chain::types::ConfirmedBlockCertificate;
+use linera_execution::Operation;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("unknown draft {0}")]
+    UnknownDraft(CryptoHash),
+    #[error("{0} is not an owner of chain {1}")]
+    NotAnOwner(AccountOwner, ChainId),
+    #[error("a draft must be published together with its author's approval")]
+    MissingAuthorApproval,
+    #[error("draft {0} was already committed")]
+    AlreadyCommitted(CryptoHash),
+    #[error("the block does not execute exactly the operations of draft {0}")]
+    NotExecuted(CryptoHash),
+    #[error("the block executing draft {0} was not proposed by an owner who approved it")]
+    UnapprovedProposer(CryptoHash),
+    #[error("the vote of {0} is signed with a different key")]
+    WrongSigner(AccountOwner),
+    #[error("invalid vote signature: {0}")]
+    InvalidSignature(#[from] CryptoError),
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("BCS error: {0}")]
+    Bcs(#[from] bcs::Error),
+    #[error("hex error: {0}")]
+    Hex(#[from] hex::FromHexError),
+    #[error("GraphQL error: {0}")]
+    GraphQl(String),
+    #[error("failed to save the drafts: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// The operations that an owner of a multi-owner chain wants to execute.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Draft {
+    /// The chain to execute the operations on.
+    pub chain_id: ChainId,
+    /// The owner who published the draft.
+    pub author: AccountOwner,
+    /// The operations, in the order in which they will be executed.
+    pub operations: Vec<Operation>,
+    /// A description of the draft's purpose, for the reviewers.
+    pub description: String,
+    /// When the draft was created.
+    pub created_at: Timestamp,
+}
+
+impl BcsHashable<'_> for Draft {}
+
+bcs_scalar!(Draft, "A draft block on a multi-owner chain");
+
+impl Draft {
+    /// Returns the ID of the draft, i.e. its hash.
+    pub fn id(&self) -> CryptoHash {
+        CryptoHash::new(self)
+    }
+}
+
+/// An owner's decision about a draft.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DraftVote {
+    /// The ID of the draft.
+    pub draft_id: CryptoHash,
+    /// Whether the owner approves the draft.
+    pub approve: bool,
+}
+
+impl BcsSignable<'_> for DraftVote {}
+
+/// A [`DraftVote`] signed with the key of a chain owner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedDraftVote {
+    pub vote: DraftVote,
+    pub signature: AccountSignature,
+}
+
+bcs_scalar!(SignedDraftVote, "A signed approval or rejection of a draft");
+
+impl SignedDraftVote {
+    /// Signs the vote with the owner's key.
+    pub async fn new<S: Signer + ?Sized>(
+        vote: DraftVote,
+        owner: &AccountOwner,
+        signer: &S,
+    ) -> Result<Self, S::Error> {
+        let signature = signer.sign(owner, &CryptoHash::new(&vote)).await?;
+        Ok(Self { vote, signature })
+    }
+
+    /// Returns the owner who signed the vote.
+    pub fn owner(&self) -> AccountOwner {
+        self.signature.owner()
+    }
+
+    /// Checks the signature of the vote.
+    pub fn check(&self) -> Result<(), CryptoError> {
+        self.signature.verify(&self.vote)
+    }
+}
+
+/// The state of a draft.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DraftStatus {
+    /// The draft is waiting for more votes.
+    Pending,
+    /// Owners with a majority of the weights approved the draft; it can be proposed.
+    Approved,
+    /// Owners with at least half of the weights rejected the draft, so it cannot be approved.
+    Rejected,
+    /// The draft was executed in a block.
+    Committed,
+}
+
+impl fmt::Display for DraftStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            DraftStatus::Pending => "pending",
+            DraftStatus::Approved => "approved",
+            DraftStatus::Rejected => "rejected",
+            DraftStatus::Committed => "committed",
+        };
+        f.write_str(status)
+    }
+}
+
+/// The weights of the owners who voted on a draft, according to the chain's ownership.
+///
+/// Super owners can propose blocks on their own, so a super owner's approval approves the
+/// draft. Otherwise a super owner's rejection rejects it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DraftTally {
+    /// The total weight of the owners who approved the draft.
+    pub approved_weight: u64,
+    /// The total weight of the owners who rejected the draft.
+    pub rejected_weight: u64,
+    /// The total weight of all owners.
+    pub total_weight: u64,
+    /// Whether a super owner approved the draft.
+    pub approved_by_super_owner: bool,
+    /// Whether a super owner rejected the draft.
+    pub rejected_by_super_owner: bool,
+}
+
+impl DraftTally {
+    /// Returns whether a super owner, or owners with a strict majority of the weights,
+    /// approved the draft.
+    pub fn is_approved(&self) -> bool {
+        if self.approved_by_super_owner || self.rejected_by_super_owner {
+            return self.approved_by_super_owner;
+        }
+        u128::from(self.approved_weight) * 2 > u128::from(self.total_weight)
+    }
+
+    /// Returns whether a super owner rejected the draft, or it can no longer be approved by a
+    /// majority.
+    pub fn is_rejected(&self) -> bool {
+        if self.approved_by_super_owner || self.rejected_by_super_owner {
+            return !self.approved_by_super_owner;
+        }
+        u128::from(self.rejected_weight) * 2 >= u128::from(self.total_weight)
+    }
+}
+
+/// A draft together with the owners' votes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DraftRecord {
+    pub draft: Draft,
+    /// The signatures of the owners who approved the draft.
+    pub approvals: BTreeMap<AccountOwner, AccountSignature>,
+    /// The signatures of the owners who rejected the draft.
+    pub rejections: BTreeMap<AccountOwner, AccountSignature>,
+    /// The hash of the certificate of the block that executed the draft, if any.
+    pub certificate_hash: Option<CryptoHash>,
+}
+
+bcs_scalar!(
+    DraftRecord,
+    "A draft block on a multi-owner chain, with the owners' votes"
+);
+
+impl DraftRecord {
+    fn new(draft: Draft) -> Self {
+        Self {
+            draft,
+            approvals: BTreeMap::new(),
+            rejections: BTreeMap::new(),
+            certificate_hash: None,
+        }
+    }
+
+    /// Returns the ID of the draft.
+    pub fn id(&self) -> CryptoHash {
+        self.draft.id()
+    }
+
+    /// Records an owner's vote, replacing any earlier vote of the same owner.
+    pub fn add_vote(
+        &mut self,
+        vote: SignedDraftVote,
+        ownership: &ChainOwnership,
+    ) -> Result<(), Error> {
+        let id = self.id();
+        ensure!(
+            vote.vote.draft_id == id,
+            Error::UnknownDraft(vote.vote.draft_id)
+        );
+        ensure!(self.certificate_hash.is_none(), Error::AlreadyCommitted(id));
+        vote.check()?;
+        let owner = vote.owner();
+        ensure!(
+            ownership.owners.contains_key(&owner) || ownership.super_owners.contains(&owner),
+            Error::NotAnOwner(owner, self.draft.chain_id)
+        );
+        if vote.vote.approve {
+            self.rejections.remove(&owner);
+            self.approvals.insert(owner, vote.signature);
+        } else {
+            self.approvals.remove(&owner);
+            self.rejections.insert(owner, vote.signature);
+        }
+        Ok(())
+    }
+
+    /// Checks the signatures of all votes, e.g. for a record received from a coordinator.
+    pub fn check_votes(&self) -> Result<(), Error> {
+        let draft_id = self.id();
+        for (votes, approve) in [(&self.approvals, true), (&self.rejections, false)] {
+            for (owner, signature) in votes {
+                ensure!(signature.owner() == *owner, Error::WrongSigner(*owner));
+                signature.verify(&DraftVote { draft_id, approve })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds up the weights of the owners who voted, according to the given ownership.
+    pub fn tally(&self, ownership: &ChainOwnership) -> DraftTally {
+        let weight = |owners: &BTreeMap<AccountOwner, AccountSignature>| {
+            owners
+                .keys()
+                .filter_map(|owner| ownership.owners.get(owner))
+                .fold(0u64, |total, weight| total.saturating_add(*weight))
+        };
+        let any_super_owner = |owners: &BTreeMap<AccountOwner, AccountSignature>| {
+            owners
+                .keys()
+                .any(|owner| ownership.super_owners.contains(owner))
+        };
+        DraftTally {
+            approved_weight: weight(&self.approvals),
+            rejected_weight: weight(&self.rejections),
+            total_weight: ownership
+                .owners
+                .values()
+                .fold(0u64, |total, weight| total.saturating_add(*weight)),
+            approved_by_super_owner: any_super_owner(&self.approvals),
+            rejected_by_super_owner: any_super_owner(&self.rejections),
+        }
+    }
+
+    /// Checks that the certificate's block executed the draft: it is on the draft's chain,
+    /// executes exactly the draft's operations, and was proposed by an owner who approved
+    /// the draft.
+    pub fn check_executed_by(&self, certificate: &ConfirmedBlockCertificate) -> Result<(), Error> {
+        let id = self.id();
+        let block = certificate.block();
+        ensure!(
+            block.header.chain_id == self.draft.chain_id
+                && block.header.timestamp >= self.draft.created_at
+                && block.body.operations().eq(&self.draft.operations),
+            Error::NotExecuted(id)
+        );
+        ensure!(
+            block
+                .header
+                .authenticated_owner
+                .is_some_and(|owner| self.approvals.contains_key(&owner)),
+            Error::UnapprovedProposer(id)
+        );
+        Ok(())
+    }
+
+    /// Returns the state of the draft, given the chain's current ownership.
+    pub fn status(&self, ownership: &ChainOwnership) -> DraftStatus {
+        if self.certificate_hash.is_some() {
+            return DraftStatus::Committed;
+        }
+        let tally = self.tally(ownership);
+        if tally.is_approved() {
+            DraftStatus::Approved
+        } else if tally.is_rejected() {
+            DraftStatus::Rejected
+        } else {
+            DraftStatus::Pending
+        }
+    }
+}
+
+/// The drafts known to a coordinator, for all chains.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DraftBook {
+    drafts: BTreeMap<CryptoHash, DraftRecord>,
+}
+
+impl DraftBook {
+    /// Adds a draft, together with its author's approval, and returns its ID.
+    ///
+    /// Publishing the same draft again does not reset its votes.
+    pub fn publish(
+        &mut self,
+        draft: Draft,
+        vote: SignedDraftVote,
+        ownership: &ChainOwnership,
+    ) -> Result<CryptoHash, Error> {
+        let id = draft.id();
+        ensure!(
+            vote.owner() == draft.author && vote.vote.approve,
+            Error::MissingAuthorApproval
+        );
+        if let btree_map::Entry::Vacant(entry) = self.drafts.entry(id) {
+            let mut record = DraftRecord::new(draft);
+            record.add_vote(vote, ownership)?;
+            entry.insert(record);
+        }
+        Ok(id)
+    }
+
+    /// Records an owner's vote on a draft.
+    pub fn vote(
+        &mut self,
+        vote: SignedDraftVote,
+        ownership: &ChainOwnership,
+    ) -> Result<&DraftRecord, Error> {
+        let draft_id = vote.vote.draft_id;
+        let record = self
+            .drafts
+            .get_mut(&draft_id)
+            .ok_or(Error::UnknownDraft(draft_id))?;
+        record.add_vote(vote, ownership)?;
+        Ok(record)
+    }
+
+    /// Returns the draft with the given ID.
+    pub fn get(&self, draft_id: &CryptoHash) -> Option<&DraftRecord> {
+        self.drafts.get(draft_id)
+    }
+
+    /// Returns the drafts for the given chain.
+    pub fn drafts(&self, chain_id: ChainId) -> impl Iterator<Item = &DraftRecord> {
+        self.drafts
+            .values()
+            .filter(move |record| record.draft.chain_id == chain_id)
+    }
+
+    /// Records that the draft was executed in the block with the given certificate, after
+    /// checking the block.
+    pub fn mark_committed(
+        &mut self,
+        draft_id: CryptoHash,
+        certificate: &ConfirmedBlockCertificate,
+    ) -> Result<(), Error> {
+        let record = self
+            .drafts
+            .get_mut(&draft_id)
+            .ok_or(Error::UnknownDraft(draft_id))?;
+        ensure!(
+            record.certificate_hash.is_none(),
+            Error::AlreadyCommitted(draft_id)
+        );
+        record.check_executed_by(certificate)?;
+        record.certificate_hash = Some(certificate.hash());
+        Ok(())
+    }
+}
+
+/// A shared handle to the drafts of a coordinator, optionally saved in a file.
+#[derive(Clone, Default)]
+pub struct Drafts {
+    book: Arc<Mutex<DraftBook>>,
+    #[cfg(feature = "fs")]
+    path: Option<Arc<std::path::PathBuf>>,
+}
+
+impl Drafts {
+    /// Loads the drafts saved at `path`, if any. Changes are saved there as well.
+    #[cfg(feature = "fs")]
+    pub fn load(path: std::path::PathBuf) -> Result<Self, Error> {
+        let book = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => DraftBook::default(),
+            Err(error) => return Err(error.into()),
+        };
+        Ok(Self {
+            book: Arc::new(Mutex::new(book)),
+            path: Some(Arc::new(path)),
+        })
+    }
+
+    /// Adds a draft, together with its author's approval, and returns its ID.
+    pub fn publish(
+        &self,
+        draft: Draft,
+        vote: SignedDraftVote,
+        ownership: &ChainOwnership,
+    ) -> Result<CryptoHash, Error> {
+        self.update(|book| book.publish(draft, vote, ownership))
+    }
+
+    /// Records an owner's vote on a draft, and returns the updated draft.
+    pub fn vote(
+        &self,
+        vote: SignedDraftVote,
+        ownership: &ChainOwnership,
+    ) -> Result<DraftRecord, Error> {
+        self.update(|book| book.vote(vote, ownership).cloned())
+    }
+
+    /// Returns the draft with the given ID.
+    pub fn get(&self, draft_id: &CryptoHash) -> Option<DraftRecord> {
+        self.book.lock().unwrap().get(draft_id).cloned()
+    }
+
+    /// Returns the drafts for the given chain.
+    pub fn drafts(&self, chain_id: ChainId) -> Vec<DraftRecord> {
+        self.book
+            .lock()
+            .unwrap()
+            .drafts(chain_id)
+            .cloned()
+            .collect()
+    }
+
+    /// Records that the draft was executed in the block with the given certificate, after
+    /// checking the block.
+    pub fn mark_committed(
+        &self,
+        draft_id: CryptoHash,
+        certificate: &ConfirmedBlockCertificate,
+    ) -> Result<(), Error> {
+        self.update(|book| book.mark_committed(draft_id, certificate))
+    }
+
+    /// Applies `f` to a copy of the drafts, and keeps the result if it is saved
+    /// successfully.
+    fn update<T>(&self, f: impl FnOnce(&mut DraftBook) -> Result<T, Error>) -> Result<T, Error> {
+        let mut book = self.book.lock().unwrap();
+        let mut updated = book.clone();
+        let result = f(&mut updated)?;
+        self.save(&updated)?;
+        *book = updated;
+        Ok(result)
+    }
+
+    #[cfg(feature = "fs")]
+    fn save(&self, book: &DraftBook) -> Result<(), Error> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let bytes = serde_json::to_vec_pretty(book)?;
+        let mut temporary_path = path.as_os_str().to_owned();
+        temporary_path.push(".tmp");
+        std::fs::write(&temporary_path, bytes)?;
+        std::fs::rename(&temporary_path, path.as_path())?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "fs"))]
+    fn save(&self, _book: &DraftBook) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// A client of the GraphQL API of a draft coordinator, e.g. a node service.
+#[derive(Clone, Debug)]
+pub struct DraftCoordinator {
+    url: String,
+}
+
+impl DraftCoordinator {
+    pub fn new(url: String) -> Self {
+        Self { url }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    async fn query<Response: DeserializeOwned>(&self, query: String) -> Result<Response, Error> {
+        #[derive(Deserialize)]
+        struct GraphQlResponse<T> {
+            data: Option<T>,
+            errors: Option<Vec<serde_json::Value>>,
+        }
+
+        let builder = reqwest::ClientBuilder::new();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let builder = builder.timeout(linera_base::time::Duration::from_secs(30));
+
+        let body = serde_json::to_vec(&serde_json::json!({ "query": query }))?;
+        let bytes = builder
+            .build()?
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        let response: GraphQlResponse<Response> = serde_json::from_slice(&bytes)?;
+        if let Some(errors) = response.errors {
+            let messages = errors
+                .iter()
+                .map(
+                    |error| match error.get("message").and_then(|msg| msg.as_str()) {
+                        Some(message) => message.to_string(),
+                        None => error.to_string(),
+                    },
+                )
+                .collect::<Vec<_>>();
+            return Err(Error::GraphQl(messages.join("; ")));
+        }
+        response
+            .data
+            .ok_or_else(|| Error::GraphQl("no data returned".to_string()))
+    }
+
+    /// Returns the drafts for the given chain.
+    pub async fn drafts(&self, chain_id: ChainId) -> Result<Vec<DraftRecord>, Error> {
+        #[derive(Deserialize)]
+        struct Response {
+            drafts: Vec<RecordResponse>,
+        }
+
+        let response: Response = self
+            .query(format!(
+                r#"query {{ drafts(chainId: "{chain_id}") {{ record }} }}"#
+            ))
+            .await?;
+        response
+            .drafts
+            .into_iter()
+            .map(|draft| from_bcs_hex(&draft.record))
+            .collect()
+    }
+
+    /// Returns the draft with the given ID.
+    pub async fn draft(&self, draft_id: CryptoHash) -> Result<DraftRecord, Error> {
+        #[derive(Deserialize)]
+        struct Response {
+            draft: Option<RecordResponse>,
+        }
+
+        let response: Response = self
+            .query(format!(
+                r#"query {{ draft(id: "{draft_id}") {{ record }} }}"#
+            ))
+            .await?;
+        let draft = response.draft.ok_or(Error::UnknownDraft(draft_id))?;
+        from_bcs_hex(&draft.record)
+    }
+
+    /// Publishes a draft, together with its author's approval.
+    pub async fn publish(
+        &self,
+        draft: &Draft,
+        vote: &SignedDraftVote,
+    ) -> Result<CryptoHash, Error> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Response {
+            publish_draft: CryptoHash,
+        }
+
+        let response: Response = self
+            .query(format!(
+                r#"mutation {{ publishDraft(draft: "{}", vote: "{}") }}"#,
+                to_bcs_hex(draft)?,
+                to_bcs_hex(vote)?
+            ))
+            .await?;
+        Ok(response.publish_draft)
+    }
+
+    /// Sends an owner's vote on a draft.
+    pub async fn vote(&self, vote: &SignedDraftVote) -> Result<(), Error> {
+        self.query::<serde_json::Value>(format!(
+            r#"mutation {{ voteOnDraft(vote: "{}") {{ status }} }}"#,
+            to_bcs_hex(vote)?
+        ))
+        .await?;
+        Ok(())
+    }
+
+    /// Reports that the draft was executed in the block with the given certificate.
+    pub async fn mark_committed(
+        &self,
+        draft_id: CryptoHash,
+        certificate_hash: CryptoHash,
+    ) -> Result<(), Error> {
+        self.query::<serde_json::Value>(format!(
+            r#"mutation {{ markDraftCommitted(draftId: "{draft_id}", certificateHash: "{certificate_hash}") }}"#
+        ))
+        .await?;
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct RecordResponse {
+    record: String,
+}
+
+fn to_bcs_hex<T: Serialize>(value: &T) -> Result<String, Error> {
+    Ok(hex::encode(bcs::to_bytes(value)?))
+}
+
+fn from_bcs_hex<T: DeserializeOwned>(value: &str) -> Result<T, Error> {
+    Ok(bcs::from_bytes(&hex::decode(value)?)?)
+}
@@ -35,6 +35,8 @@ pub(crate) enum Inner {
     RemoteNode(#[from] linera_core::node::NodeError),
     #[error("arithmetic error: {0}")]
     Arithmetic(#[from] linera_base::data_types::ArithmeticError),
+    #[error("batch error: {0}")]
+    Batch(#[from] crate::batch::BatchError),
     #[error("incorrect chain ownership")]
     ChainOwnership,
     #[cfg(not(web))]
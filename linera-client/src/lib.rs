@@ -6,6 +6,8 @@
 #![recursion_limit = "256"]
 #![allow(async_fn_in_trait)]
 
+pub mod batch;
+pub mod chain_history;
 pub mod chain_listener;
 pub mod client_context;
 pub use client_context::ClientContext;
@@ -14,7 +16,9 @@ pub mod client_metrics;
 pub mod client_options;
 pub use client_options::Options;
 pub mod config;
+pub mod drafts;
 mod error;
+pub mod scheduler;
 pub mod util;
 
 #[cfg(not(web))]
@@ -0,0 +1,702 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Operations that a client submits on a schedule, e.g. a transfer every day, or processing
+//! the inbox at 02:00.
+//!
+//! A [`Schedule`] combines a [`ScheduledAction`] with a [`Trigger`]. The schedules are kept in
+//! a [`ScheduleBook`], shared through a [`Scheduler`] handle, and the
+//! [`ChainListener`](crate::chain_listener::ChainListener) runs them when they are due.
+//!
+//! A schedule that is overdue, e.g. because the client was not running, runs once, and then
+//! continues with the next time its trigger fires after the current time.
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Datelike as _, NaiveDate, Timelike as _};
+use linera_base::{
+    bcs_scalar,
+    crypto::CryptoHash,
+    data_types::{TimeDelta, Timestamp},
+    identifiers::ChainId,
+};
+use linera_core::{
+    client::chain_client::{self, ChainClient},
+    data_types::ClientOutcome,
+    Environment,
+};
+use linera_execution::Operation;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use tracing::warn;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("unknown schedule {0}")]
+    UnknownSchedule(u64),
+    #[error(
+        "invalid trigger {0:?}: expected `at <time>`, `every <interval> [from <time>]` or \
+        `cron <minute> <hour> <day of month> <month> <day of week>`"
+    )]
+    InvalidTrigger(String),
+    #[error("invalid time {0:?}: expected e.g. `2025-01-31 02:00:00`, in UTC")]
+    InvalidTime(String),
+    #[error("invalid interval {0:?}: expected e.g. `1d`, `12h` or `1h30m`")]
+    InvalidInterval(String),
+    #[error("invalid cron expression {expression:?}: {message}")]
+    InvalidCron { expression: String, message: String },
+    #[error("the trigger {0} never fires")]
+    NeverFires(Trigger),
+    #[error("failed to save the schedules: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid schedules file: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// What a schedule does when it is due.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScheduledAction {
+    /// Creates blocks to receive the incoming messages.
+    ProcessInbox,
+    /// Executes the operations in a block.
+    ExecuteOperations(Vec<Operation>),
+}
+
+bcs_scalar!(ScheduledAction, "What a schedule does when it is due");
+
+impl ScheduledAction {
+    /// Returns a short description of the action.
+    pub fn summary(&self) -> String {
+        match self {
+            ScheduledAction::ProcessInbox => "process inbox".to_string(),
+            ScheduledAction::ExecuteOperations(operations) => {
+                format!("execute {} operation(s)", operations.len())
+            }
+        }
+    }
+}
+
+/// When a schedule is due.
+///
+/// Triggers are written as `at <time>`, `every <interval> [from <time>]` or
+/// `cron <expression>`. Times are in UTC, e.g. `2025-01-31 02:00:00`, and intervals combine
+/// days, hours, minutes and seconds, e.g. `1d` or `1h30m`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Trigger {
+    /// Once, at the given time.
+    At(Timestamp),
+    /// Repeatedly, with the given interval. The first run is at `start`, or one interval
+    /// after the schedule was created.
+    Every {
+        interval: TimeDelta,
+        start: Option<Timestamp>,
+    },
+    /// At the times matching a cron expression.
+    Cron(CronSchedule),
+}
+
+impl Trigger {
+    /// Returns when a schedule created at `now` is due first.
+    pub fn first_run(&self, now: Timestamp) -> Option<Timestamp> {
+        match self {
+            Trigger::At(time) => Some(*time),
+            Trigger::Every {
+                interval,
+                start: Some(start),
+            } if *start < now => Some(next_interval(*start, *interval, now)),
+            Trigger::Every {
+                start: Some(start), ..
+            } => Some(*start),
+            Trigger::Every {
+                interval,
+                start: None,
+            } => Some(now.saturating_add(*interval)),
+            Trigger::Cron(cron) => cron.next_after(now),
+        }
+    }
+
+    /// Returns when a schedule that was due at `previous` and ran at `now` is due next.
+    pub fn next_run(&self, previous: Timestamp, now: Timestamp) -> Option<Timestamp> {
+        match self {
+            Trigger::At(_) => None,
+            Trigger::Every { interval, .. } => Some(next_interval(previous, *interval, now)),
+            Trigger::Cron(cron) => cron.next_after(now),
+        }
+    }
+}
+
+/// Returns the first `anchor + k * interval`, with `k >= 1`, that is later than `after`.
+fn next_interval(anchor: Timestamp, interval: TimeDelta, after: Timestamp) -> Timestamp {
+    let interval = interval.as_micros().max(1);
+    let periods = after.delta_since(anchor).as_micros() / interval + 1;
+    anchor.saturating_add(TimeDelta::from_micros(periods.saturating_mul(interval)))
+}
+
+impl fmt::Display for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Trigger::At(time) => write!(f, "at {time}"),
+            Trigger::Every { interval, start } => {
+                write!(f, "every {}", Interval(*interval))?;
+                if let Some(start) = start {
+                    write!(f, " from {start}")?;
+                }
+                Ok(())
+            }
+            Trigger::Cron(cron) => write!(f, "cron {cron}"),
+        }
+    }
+}
+
+impl FromStr for Trigger {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (kind, argument) = s
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| Error::InvalidTrigger(s.to_string()))?;
+        let argument = argument.trim();
+        match kind {
+            "at" => Ok(Trigger::At(parse_time(argument)?)),
+            "every" => {
+                let (interval, start) = match argument.split_once(" from ") {
+                    Some((interval, start)) => (interval, Some(parse_time(start.trim())?)),
+                    None => (argument, None),
+                };
+                Ok(Trigger::Every {
+                    interval: parse_interval(interval.trim())?,
+                    start,
+                })
+            }
+            "cron" => Ok(Trigger::Cron(argument.parse()?)),
+            _ => Err(Error::InvalidTrigger(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<String> for Trigger {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Trigger> for String {
+    fn from(trigger: Trigger) -> Self {
+        trigger.to_string()
+    }
+}
+
+fn parse_time(s: &str) -> Result<Timestamp, Error> {
+    s.parse().map_err(|_| Error::InvalidTime(s.to_string()))
+}
+
+/// The units of intervals, in seconds, from the largest to the smallest.
+const INTERVAL_UNITS: [(char, u64); 4] = [('d', 86_400), ('h', 3_600), ('m', 60), ('s', 1)];
+
+fn parse_interval(s: &str) -> Result<TimeDelta, Error> {
+    let invalid = || Error::InvalidInterval(s.to_string());
+    let mut seconds = 0u64;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let value = rest[..digits].parse::<u64>().map_err(|_| invalid())?;
+        let unit = rest[digits..].chars().next().ok_or_else(invalid)?;
+        let (_, factor) = INTERVAL_UNITS
+            .iter()
+            .find(|(name, _)| *name == unit)
+            .ok_or_else(invalid)?;
+        seconds = value
+            .checked_mul(*factor)
+            .and_then(|value| seconds.checked_add(value))
+            .ok_or_else(invalid)?;
+        rest = &rest[digits + unit.len_utf8()..];
+    }
+    if seconds == 0 {
+        return Err(invalid());
+    }
+    Ok(TimeDelta::from_secs(seconds))
+}
+
+/// Displays an interval with the units of [`parse_interval`].
+struct Interval(TimeDelta);
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut seconds = self.0.as_micros() / 1_000_000;
+        if seconds == 0 {
+            return write!(f, "0s");
+        }
+        for (unit, factor) in INTERVAL_UNITS {
+            if seconds >= factor {
+                write!(f, "{}{unit}", seconds / factor)?;
+                seconds %= factor;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A cron expression with the five fields `minute hour day-of-month month day-of-week`,
+/// evaluated in UTC.
+///
+/// Each field is `*` or a comma-separated list of values `a`, ranges `a-b` and steps `*/n`,
+/// `a-b/n` or `a/n`. Days of the week go from 0 (Sunday) to 6, and 7 is Sunday as well. As
+/// in the usual cron implementations, if both the day of the month and the day of the week
+/// are restricted, a day matching either of them matches.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+/// How far ahead to look for a matching day: long enough to find any 29th of February.
+const MAX_CRON_DAYS: u32 = 8 * 366;
+
+impl CronSchedule {
+    /// Returns the first minute matching the expression that is later than `time`.
+    pub fn next_after(&self, time: Timestamp) -> Option<Timestamp> {
+        let seconds = i64::try_from(time.micros() / 1_000_000).ok()?;
+        let start = DateTime::from_timestamp(seconds - seconds % 60 + 60, 0)?.naive_utc();
+        let mut date = start.date();
+        for _ in 0..MAX_CRON_DAYS {
+            if self.matches_day(date) {
+                let first_day = date == start.date();
+                let first_hour = if first_day { start.hour() } else { 0 };
+                for hour in (first_hour..24).filter(|hour| has(self.hours, *hour)) {
+                    let first_minute = if first_day && hour == first_hour {
+                        start.minute()
+                    } else {
+                        0
+                    };
+                    if let Some(minute) = (first_minute..60).find(|min| has(self.minutes, *min)) {
+                        let micros = date
+                            .and_hms_opt(hour, minute, 0)?
+                            .and_utc()
+                            .timestamp_micros();
+                        return Some(Timestamp::from(u64::try_from(micros).ok()?));
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        if !has(self.months, date.month()) {
+            return false;
+        }
+        let day_of_month = has(self.days_of_month, date.day());
+        let day_of_week = has(self.days_of_week, date.weekday().num_days_from_sunday());
+        if self.day_of_month_restricted && self.day_of_week_restricted {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+}
+
+fn has(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+/// Parses a field of a cron expression into the set of its values.
+fn parse_cron_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut set = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<u32>() {
+                Ok(step) if step > 0 => (range, step),
+                _ => return Err(format!("invalid step in {part:?}")),
+            },
+            None => (part, 1),
+        };
+        let parse = |value: &str| {
+            value
+                .parse::<u32>()
+                .map_err(|_| format!("invalid value {value:?}"))
+        };
+        let (low, high) = if range == "*" {
+            (min, max)
+        } else if let Some((low, high)) = range.split_once('-') {
+            (parse(low)?, parse(high)?)
+        } else {
+            let value = parse(range)?;
+            (value, if step > 1 { max } else { value })
+        };
+        if low < min || high > max || low > high {
+            return Err(format!("{part:?} is not within {min}-{max}"));
+        }
+        for value in (low..=high).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+impl FromStr for CronSchedule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |message: String| Error::InvalidCron {
+            expression: s.to_string(),
+            message,
+        };
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(invalid(format!(
+                "expected 5 fields, found {}",
+                fields.len()
+            )));
+        };
+        let mut days_of_week = parse_cron_field(day_of_week, 0, 7).map_err(invalid)?;
+        if has(days_of_week, 7) {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+        let schedule = CronSchedule {
+            expression: fields.join(" "),
+            minutes: parse_cron_field(minute, 0, 59).map_err(invalid)?,
+            hours: parse_cron_field(hour, 0, 23).map_err(invalid)?,
+            days_of_month: parse_cron_field(day_of_month, 1, 31).map_err(invalid)?,
+            months: parse_cron_field(month, 1, 12).map_err(invalid)?,
+            days_of_week,
+            day_of_month_restricted: !day_of_month.starts_with('*'),
+            day_of_week_restricted: !day_of_week.starts_with('*'),
+        };
+        Ok(schedule)
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.expression.fmt(f)
+    }
+}
+
+/// The outcome of the latest run of a schedule.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduleRun {
+    /// When the action ran.
+    pub time: Timestamp,
+    /// The certificates of the blocks that were created.
+    pub certificate_hashes: Vec<CryptoHash>,
+    /// The error, if the action failed.
+    pub error: Option<String>,
+}
+
+/// An action that runs on a chain whenever its trigger fires.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Schedule {
+    /// The ID of the schedule.
+    pub id: u64,
+    /// The chain to run the action on.
+    pub chain_id: ChainId,
+    /// A description of the schedule's purpose.
+    pub description: String,
+    /// What to do.
+    pub action: ScheduledAction,
+    /// When to do it.
+    pub trigger: Trigger,
+    /// When the schedule is due next, or `None` if it will not run again.
+    pub next_run: Option<Timestamp>,
+    /// How many times the schedule ran.
+    pub run_count: u64,
+    /// The outcome of the latest run, if any.
+    pub last_run: Option<ScheduleRun>,
+}
+
+/// The schedules of a client.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ScheduleBook {
+    next_id: u64,
+    schedules: BTreeMap<u64, Schedule>,
+}
+
+impl ScheduleBook {
+    /// Adds a schedule, created at `now`.
+    pub fn create(
+        &mut self,
+        chain_id: ChainId,
+        description: String,
+        action: ScheduledAction,
+        trigger: Trigger,
+        now: Timestamp,
+    ) -> Result<&Schedule, Error> {
+        let next_run = trigger
+            .first_run(now)
+            .ok_or_else(|| Error::NeverFires(trigger.clone()))?;
+        let id = self.next_id;
+        self.next_id += 1;
+        let schedule = Schedule {
+            id,
+            chain_id,
+            description,
+            action,
+            trigger,
+            next_run: Some(next_run),
+            run_count: 0,
+            last_run: None,
+        };
+        Ok(self.schedules.entry(id).or_insert(schedule))
+    }
+
+    /// Removes a schedule.
+    pub fn cancel(&mut self, id: u64) -> Result<Schedule, Error> {
+        self.schedules.remove(&id).ok_or(Error::UnknownSchedule(id))
+    }
+
+    /// Returns a schedule.
+    pub fn get(&self, id: u64) -> Option<&Schedule> {
+        self.schedules.get(&id)
+    }
+
+    /// Returns the schedules, optionally only those of the given chain.
+    pub fn schedules(&self, chain_id: Option<ChainId>) -> impl Iterator<Item = &Schedule> {
+        self.schedules
+            .values()
+            .filter(move |schedule| chain_id.is_none_or(|chain_id| schedule.chain_id == chain_id))
+    }
+
+    /// Returns when the next schedule is due, if any.
+    pub fn next_due(&self) -> Option<Timestamp> {
+        self.schedules
+            .values()
+            .filter_map(|schedule| schedule.next_run)
+            .min()
+    }
+
+    /// Returns the schedules that are due at `now`.
+    pub fn due(&self, now: Timestamp) -> Vec<Schedule> {
+        self.schedules
+            .values()
+            .filter(|schedule| schedule.next_run.is_some_and(|next_run| next_run <= now))
+            .cloned()
+            .collect()
+    }
+
+    /// Advances a schedule that is about to run at `now` to its next run, and returns it.
+    ///
+    /// This is done before the action runs, so that it is not repeated if its outcome can't
+    /// be recorded.
+    pub fn start_run(&mut self, id: u64, now: Timestamp) -> Option<Schedule> {
+        let schedule = self.schedules.get_mut(&id)?;
+        let started = schedule.clone();
+        let previous = schedule.next_run.unwrap_or(now);
+        schedule.next_run = schedule.trigger.next_run(previous, now);
+        Some(started)
+    }
+
+    /// Records the outcome of a run of a schedule that finished at `now`.
+    pub fn record_run(
+        &mut self,
+        id: u64,
+        now: Timestamp,
+        outcome: Result<Vec<CryptoHash>, String>,
+    ) {
+        let Some(schedule) = self.schedules.get_mut(&id) else {
+            // The schedule was cancelled while it ran.
+            return;
+        };
+        let (certificate_hashes, error) = match outcome {
+            Ok(certificate_hashes) => (certificate_hashes, None),
+            Err(error) => (Vec::new(), Some(error)),
+        };
+        schedule.run_count += 1;
+        schedule.last_run = Some(ScheduleRun {
+            time: now,
+            certificate_hashes,
+            error,
+        });
+    }
+
+    /// Retries a schedule at a later time, e.g. after a round timeout.
+    pub fn postpone(&mut self, id: u64, until: Timestamp) {
+        if let Some(schedule) = self.schedules.get_mut(&id) {
+            schedule.next_run = Some(until);
+        }
+    }
+}
+
+/// A shared handle to the schedules of a client, optionally saved in a file.
+#[derive(Clone, Default)]
+pub struct Scheduler {
+    book: Arc<Mutex<ScheduleBook>>,
+    changed: Arc<Notify>,
+    #[cfg(feature = "fs")]
+    path: Option<Arc<std::path::PathBuf>>,
+}
+
+impl Scheduler {
+    /// Loads the schedules saved at `path`, if any. Changes are saved there as well.
+    #[cfg(feature = "fs")]
+    pub fn load(path: std::path::PathBuf) -> Result<Self, Error> {
+        let book = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => ScheduleBook::default(),
+            Err(error) => return Err(error.into()),
+        };
+        Ok(Self {
+            book: Arc::new(Mutex::new(book)),
+            changed: Arc::default(),
+            path: Some(Arc::new(path)),
+        })
+    }
+
+    /// Adds a schedule and returns it.
+    pub fn create(
+        &self,
+        chain_id: ChainId,
+        description: String,
+        action: ScheduledAction,
+        trigger: Trigger,
+    ) -> Result<Schedule, Error> {
+        let schedule = self.update(|book| {
+            book.create(chain_id, description, action, trigger, Timestamp::now())
+                .cloned()
+        })?;
+        self.changed.notify_one();
+        Ok(schedule)
+    }
+
+    /// Removes a schedule and returns it.
+    pub fn cancel(&self, id: u64) -> Result<Schedule, Error> {
+        let schedule = self.update(|book| book.cancel(id))?;
+        self.changed.notify_one();
+        Ok(schedule)
+    }
+
+    /// Returns the schedules, optionally only those of the given chain.
+    pub fn schedules(&self, chain_id: Option<ChainId>) -> Vec<Schedule> {
+        let book = self.book.lock().unwrap();
+        book.schedules(chain_id).cloned().collect()
+    }
+
+    /// Returns a schedule.
+    pub fn get(&self, id: u64) -> Option<Schedule> {
+        self.book.lock().unwrap().get(id).cloned()
+    }
+
+    /// Returns when the next schedule is due, if any.
+    pub fn next_due(&self) -> Option<Timestamp> {
+        self.book.lock().unwrap().next_due()
+    }
+
+    /// Returns the schedules that are due at `now`.
+    pub fn due(&self, now: Timestamp) -> Vec<Schedule> {
+        self.book.lock().unwrap().due(now)
+    }
+
+    /// Advances a schedule that is about to run at `now` to its next run, and returns it.
+    /// Returns `None` if the schedule was cancelled.
+    ///
+    /// The change is kept even if it can't be saved, so that the action is not repeated.
+    pub fn start_run(&self, id: u64, now: Timestamp) -> Option<Schedule> {
+        let (schedule, result) = self.apply(|book| book.start_run(id, now));
+        if let Err(error) = result {
+            warn!(%error, "Failed to save the next run of schedule {id}");
+        }
+        schedule
+    }
+
+    /// Records the outcome of a run of a schedule that finished at `now`.
+    pub fn record_run(&self, id: u64, now: Timestamp, outcome: Result<Vec<CryptoHash>, String>) {
+        let ((), result) = self.apply(|book| book.record_run(id, now, outcome));
+        if let Err(error) = result {
+            warn!(%error, "Failed to record the run of schedule {id}");
+        }
+    }
+
+    /// Retries a schedule at a later time.
+    pub fn postpone(&self, id: u64, until: Timestamp) {
+        let ((), result) = self.apply(|book| book.postpone(id, until));
+        if let Err(error) = result {
+            warn!(%error, "Failed to postpone schedule {id}");
+        }
+    }
+
+    /// Waits until a schedule was created or cancelled.
+    pub async fn changed(&self) {
+        self.changed.notified().await
+    }
+
+    /// Applies `f` to a copy of the schedules, and keeps the result if it is saved
+    /// successfully.
+    fn update<T>(&self, f: impl FnOnce(&mut ScheduleBook) -> Result<T, Error>) -> Result<T, Error> {
+        let mut book = self.book.lock().unwrap();
+        let mut updated = book.clone();
+        let result = f(&mut updated)?;
+        self.save(&updated)?;
+        *book = updated;
+        Ok(result)
+    }
+
+    /// Applies `f` to the schedules and saves them. Unlike `update`, the change is kept
+    /// even if it can't be saved.
+    fn apply<T>(&self, f: impl FnOnce(&mut ScheduleBook) -> T) -> (T, Result<(), Error>) {
+        let mut book = self.book.lock().unwrap();
+        let value = f(&mut book);
+        (value, self.save(&book))
+    }
+
+    #[cfg(feature = "fs")]
+    fn save(&self, book: &ScheduleBook) -> Result<(), Error> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let bytes = serde_json::to_vec_pretty(book)?;
+        let mut temporary_path = path.as_os_str().to_owned();
+        temporary_path.push(".tmp");
+        std::fs::write(&temporary_path, bytes)?;
+        std::fs::rename(&temporary_path, path.as_path())?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "fs"))]
+    fn save(&self, _book: &ScheduleBook) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Runs a scheduled action on a chain. Returns the certificates of the created blocks.
+pub(crate) async fn run_action<Env: Environment>(
+    client: &ChainClient<Env>,
+    action: &ScheduledAction,
+) -> Result<ClientOutcome<Vec<CryptoHash>>, chain_client::Error> {
+    match action {
+        ScheduledAction::ProcessInbox => {
+            let (certificates, timeout) = client.process_inbox().await?;
+            match timeout {
+                Some(timeout) if certificates.is_empty() => {
+                    Ok(ClientOutcome::WaitForTimeout(timeout))
+                }
+                _ => Ok(ClientOutcome::Committed(
+                    certificates
+                        .iter()
+                        .map(|certificate| certificate.hash())
+                        .collect(),
+                )),
+            }
+        }
+        ScheduledAction::ExecuteOperations(operations) => Ok(client
+            .execute_operations(operations.clone(), vec![])
+            .await?
+            .map(|certificate| vec![certificate.hash()])),
+    }
+}
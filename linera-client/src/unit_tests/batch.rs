@@ -0,0 +1,169 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests for reading, packing and resuming batches of operations.
+
+use std::path::Path;
+
+use assert_matches::assert_matches;
+use linera_base::{
+    crypto::CryptoHash,
+    data_types::{Amount, BlockHeight},
+    identifiers::{Account, AccountOwner, ApplicationId, ChainId},
+};
+use linera_execution::{system::SystemOperation, Operation, ResourceControlPolicy};
+
+use crate::batch::{
+    Batch, BatchError, BatchProgress, BlockLimits, ManifestFormat, PendingBatchBlock,
+};
+
+fn chain_id(name: &str) -> ChainId {
+    ChainId(CryptoHash::test_hash(name))
+}
+
+#[test]
+fn test_read_transfer_manifest() -> anyhow::Result<()> {
+    let sender = chain_id("treasury");
+    let alice = Account::chain(chain_id("alice"));
+    let owner = AccountOwner::from(CryptoHash::test_hash("bob"));
+    let bob = Account::new(chain_id("bob"), owner);
+    let csv = format!("# Payroll\nrecipient,amount\n{alice},1.5\n\n{bob}, 2\n");
+    let json = format!(
+        r#"[{{"recipient": "{alice}", "amount": "1.5"}}, {{"recipient": "{bob}", "amount": 2}}]"#
+    );
+
+    let batch = Batch::transfers(sender, AccountOwner::CHAIN, &csv, ManifestFormat::Csv)?;
+    let expected = [(alice, "1.5"), (bob, "2")]
+        .into_iter()
+        .map(|(recipient, amount)| {
+            Operation::system(SystemOperation::Transfer {
+                owner: AccountOwner::CHAIN,
+                recipient,
+                amount: amount.parse().unwrap(),
+            })
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(batch.chain_id, sender);
+    assert_eq!(batch.operations, expected);
+    assert_eq!(
+        Batch::transfers(sender, AccountOwner::CHAIN, &json, ManifestFormat::Json)?,
+        batch
+    );
+
+    // The header line is optional, and errors refer to the data rows.
+    let csv = format!("{alice},1\n{bob},lots\n");
+    assert_matches!(
+        Batch::transfers(sender, AccountOwner::CHAIN, &csv, ManifestFormat::Csv),
+        Err(BatchError::InvalidRow { row: 2, .. })
+    );
+    assert_matches!(
+        Batch::transfers(
+            sender,
+            AccountOwner::CHAIN,
+            "recipient,amount\n",
+            ManifestFormat::Csv
+        ),
+        Err(BatchError::EmptyManifest)
+    );
+    assert_eq!(
+        ManifestFormat::from_path(Path::new("payroll.CSV"))?,
+        ManifestFormat::Csv
+    );
+    assert_matches!(
+        ManifestFormat::from_path(Path::new("payroll.txt")),
+        Err(BatchError::UnsupportedFormat(_))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_read_operation_manifest() -> anyhow::Result<()> {
+    let application_id = ApplicationId::new(CryptoHash::test_hash("app"));
+    let csv = format!("application_id,operation\n{application_id},01ff\n");
+    let batch = Batch::user_operations(chain_id("chain"), &csv, ManifestFormat::Csv)?;
+    assert_eq!(
+        batch.operations,
+        [Operation::User {
+            application_id,
+            bytes: vec![1, 255],
+        }]
+    );
+    let csv = format!("{application_id},not-hex\n");
+    assert_matches!(
+        Batch::user_operations(chain_id("chain"), &csv, ManifestFormat::Csv),
+        Err(BatchError::InvalidRow { row: 1, .. })
+    );
+    Ok(())
+}
+
+#[test]
+fn test_block_limits() {
+    let operation = |bytes: usize| Operation::User {
+        application_id: ApplicationId::new(CryptoHash::test_hash("app")),
+        bytes: vec![0; bytes],
+    };
+    let size = bcs::serialized_size(&operation(100)).unwrap() as u64;
+    let policy = ResourceControlPolicy {
+        maximum_block_proposal_size: (3 * size + 1) * 4 / 3 + 4,
+        ..ResourceControlPolicy::default()
+    };
+    let limits = BlockLimits::new(&policy, 100);
+    assert!(limits.max_bytes >= 3 * size && limits.max_bytes < 4 * size);
+
+    let operations = vec![operation(100); 7];
+    assert_eq!(limits.next_chunk(&operations, 0), 3);
+    assert_eq!(limits.next_chunk(&operations, 3), 6);
+    assert_eq!(limits.next_chunk(&operations, 6), 7);
+
+    // The number of operations is limited as well.
+    let limits = BlockLimits::new(&policy, 2);
+    assert_eq!(limits.next_chunk(&operations, 0), 2);
+
+    // An operation that is too large on its own gets a block of its own.
+    let operations = vec![operation(1000), operation(100)];
+    assert_eq!(limits.next_chunk(&operations, 0), 1);
+}
+
+#[test]
+fn test_batch_progress() {
+    let batch = Batch {
+        chain_id: chain_id("chain"),
+        operations: vec![
+            Operation::system(SystemOperation::Transfer {
+                owner: AccountOwner::CHAIN,
+                recipient: Account::chain(chain_id("recipient")),
+                amount: Amount::ONE,
+            });
+            3
+        ],
+    };
+    let mut progress = BatchProgress::new(&batch);
+    assert_eq!(progress.next_row(), 0);
+    progress.pending = Some(PendingBatchBlock {
+        rows: 0..2,
+        height: BlockHeight(4),
+    });
+    progress.commit_pending(BlockHeight(5), CryptoHash::test_hash("first"));
+    progress.pending = Some(PendingBatchBlock {
+        rows: 2..3,
+        height: BlockHeight(6),
+    });
+    assert_eq!(progress.next_row(), 2);
+    assert!(!progress.is_complete());
+    progress.commit_pending(BlockHeight(6), CryptoHash::test_hash("second"));
+    assert!(progress.is_complete());
+    assert!(progress.pending.is_none());
+    assert_eq!(
+        progress.report(),
+        format!(
+            "row,height,certificate_hash\n1,5,{first}\n2,5,{first}\n3,6,{second}\n",
+            first = CryptoHash::test_hash("first"),
+            second = CryptoHash::test_hash("second"),
+        )
+    );
+
+    assert!(progress.check(&batch).is_ok());
+    let mut other = batch.clone();
+    other.operations.pop();
+    assert_matches!(progress.check(&other), Err(BatchError::ProgressMismatch));
+}
@@ -17,12 +17,14 @@ use linera_core::{
     wallet,
     worker::{DEFAULT_BLOCK_CACHE_SIZE, DEFAULT_EXECUTION_STATE_CACHE_SIZE},
 };
+use linera_execution::{system::SystemOperation, Operation};
 use linera_storage::Storage;
 use tokio_util::sync::CancellationToken;
 
 use crate::{
     chain_listener::{self, ChainListener, ChainListenerConfig, ClientContext as _},
     config::GenesisConfig,
+    scheduler::{ScheduledAction, Scheduler, Trigger},
     Error,
 };
 
@@ -69,7 +71,7 @@ impl chain_listener::ClientContext for ClientContext {
         client: &ChainClient<environment::Test>,
     ) -> Result<(), Error> {
         let info = client.chain_info().await?;
-        let existing_owner = self.wallet().get(info.chain_id).and_then(|c| c.owner);
+        let existing = self.wallet().get(info.chain_id).unwrap_or_default();
         let pending_fast_proposal = client
             .pending_proposal()
             .await
@@ -78,7 +80,9 @@ impl chain_listener::ClientContext for ClientContext {
             info.chain_id,
             wallet::Chain {
                 pending_fast_proposal,
-                owner: existing_owner,
+                owner: existing.owner,
+                inbox_policy: existing.inbox_policy,
+                inbox_fees: existing.inbox_fees,
                 ..info.as_ref().into()
             },
         );
@@ -92,7 +96,7 @@ impl chain_listener::ClientContext for ClientContext {
 async fn test_chain_listener() -> anyhow::Result<()> {
     // Create two chains.
     let mut signer = InMemorySigner::new(Some(42));
-    let key_pair = signer.generate_new();
+    let key_pair = signer.generate_new().unwrap();
     let owner: AccountOwner = key_pair.into();
     let config = ChainListenerConfig::default();
     let storage_builder = MemoryStorageBuilder::default();
@@ -254,6 +258,8 @@ async fn test_chain_listener_follow_only() -> anyhow::Result<()> {
             timestamp: clock.current_time(),
             pending_fast_proposal: None,
             epoch: Some(chain_a_info.epoch),
+            inbox_policy: None,
+            inbox_fees: Default::default(),
         },
     );
 
@@ -267,6 +273,8 @@ async fn test_chain_listener_follow_only() -> anyhow::Result<()> {
             timestamp: clock.current_time(),
             pending_fast_proposal: None,
             epoch: Some(chain_b_info.epoch),
+            inbox_policy: None,
+            inbox_fees: Default::default(),
         },
     );
 
@@ -366,6 +374,343 @@ async fn test_chain_listener_follow_only() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Tests that the chain listener waits for a batch of messages before processing the inbox of a
+/// chain whose wallet entry has an inbox policy.
+#[test_log::test(tokio::test)]
+async fn test_chain_listener_batches_messages() -> anyhow::Result<()> {
+    let signer = InMemorySigner::new(Some(42));
+    let config = ChainListenerConfig::default();
+    let storage_builder = MemoryStorageBuilder::default();
+    let clock = storage_builder.clock().clone();
+    let mut builder = TestBuilder::new(storage_builder, 4, 1, signer.clone()).await?;
+
+    let sender = builder.add_root_chain(0, Amount::from_tokens(10)).await?;
+    let receiver = builder.add_root_chain(1, Amount::ZERO).await?;
+    let receiver_id = receiver.chain_id();
+
+    let genesis_config = GenesisConfig::new_for_testing(&builder);
+    let admin_chain_id = genesis_config.admin_chain_id();
+    let storage = builder.make_storage().await?;
+    let receiver_info = receiver.chain_info().await?;
+
+    let context = ClientContext {
+        client: Arc::new(Client::new(
+            environment::Impl {
+                storage: storage.clone(),
+                network: builder.make_node_provider(),
+                signer,
+                wallet: environment::TestWallet::default(),
+            },
+            admin_chain_id,
+            false,
+            [(receiver_id, ListeningMode::FullChain)],
+            "Client node with a batching inbox policy".to_string(),
+            Some(Duration::from_secs(30)),
+            Some(Duration::from_secs(1)),
+            1000,
+            chain_client::Options::test_default(),
+            DEFAULT_BLOCK_CACHE_SIZE,
+            DEFAULT_EXECUTION_STATE_CACHE_SIZE,
+            &linera_core::client::RequestsSchedulerConfig::default(),
+        )),
+    };
+    context.wallet().insert(
+        receiver_id,
+        wallet::Chain {
+            owner: receiver.preferred_owner(),
+            block_hash: receiver_info.block_hash,
+            next_block_height: receiver_info.next_block_height,
+            timestamp: clock.current_time(),
+            pending_fast_proposal: None,
+            epoch: Some(receiver_info.epoch),
+            inbox_policy: Some(wallet::InboxPolicy {
+                min_batch_messages: Some(2),
+                ..wallet::InboxPolicy::default()
+            }),
+            inbox_fees: Default::default(),
+        },
+    );
+
+    let context = Arc::new(Mutex::new(context));
+    let cancellation_token = CancellationToken::new();
+    let child_token = cancellation_token.child_token();
+    let chain_listener = ChainListener::new(
+        config,
+        context,
+        storage,
+        child_token,
+        tokio::sync::mpsc::unbounded_channel().1,
+        false, // Unit test doesn't need background sync
+    )
+    .run()
+    .await
+    .unwrap();
+
+    let handle = linera_base::Task::spawn(async move { chain_listener.await.unwrap() });
+
+    // A single message is not enough to process the inbox.
+    let recipient = Account::chain(receiver_id);
+    sender
+        .transfer(AccountOwner::CHAIN, Amount::ONE, recipient)
+        .await?;
+    for _ in 0..20 {
+        tokio::task::yield_now().await;
+        receiver.synchronize_from_validators().await?;
+    }
+    assert_eq!(
+        receiver.chain_info().await?.next_block_height,
+        BlockHeight::ZERO
+    );
+
+    // With a second message, both are received in a single block.
+    sender
+        .transfer(AccountOwner::CHAIN, Amount::ONE, recipient)
+        .await?;
+    for i in 0.. {
+        tokio::task::yield_now().await;
+        receiver.synchronize_from_validators().await?;
+        if receiver.chain_info().await?.next_block_height >= BlockHeight::from(1) {
+            break;
+        }
+        if i >= 50 {
+            panic!("The receiver's inbox was not processed by the listener.");
+        }
+    }
+    let receiver_info = receiver.chain_info().await?;
+    assert_eq!(receiver_info.next_block_height, BlockHeight::from(1));
+    assert_eq!(receiver.local_balance().await?, Amount::from_tokens(2));
+
+    cancellation_token.cancel();
+    handle.await;
+
+    Ok(())
+}
+
+/// Tests that the chain listener doesn't process the inbox while the fees recorded in the
+/// wallet for the last hour reach the cap, and that it picks up a new inbox policy from the
+/// wallet without a restart.
+#[test_log::test(tokio::test)]
+async fn test_chain_listener_pauses_at_fee_cap() -> anyhow::Result<()> {
+    let signer = InMemorySigner::new(Some(42));
+    let config = ChainListenerConfig::default();
+    let storage_builder = MemoryStorageBuilder::default();
+    let clock = storage_builder.clock().clone();
+    let mut builder = TestBuilder::new(storage_builder, 4, 1, signer.clone()).await?;
+
+    let sender = builder.add_root_chain(0, Amount::from_tokens(10)).await?;
+    let receiver = builder.add_root_chain(1, Amount::ZERO).await?;
+    let receiver_id = receiver.chain_id();
+
+    let genesis_config = GenesisConfig::new_for_testing(&builder);
+    let admin_chain_id = genesis_config.admin_chain_id();
+    let storage = builder.make_storage().await?;
+    let receiver_info = receiver.chain_info().await?;
+
+    let context = ClientContext {
+        client: Arc::new(Client::new(
+            environment::Impl {
+                storage: storage.clone(),
+                network: builder.make_node_provider(),
+                signer,
+                wallet: environment::TestWallet::default(),
+            },
+            admin_chain_id,
+            false,
+            [(receiver_id, ListeningMode::FullChain)],
+            "Client node with a fee cap".to_string(),
+            Some(Duration::from_secs(30)),
+            Some(Duration::from_secs(1)),
+            1000,
+            chain_client::Options::test_default(),
+            DEFAULT_BLOCK_CACHE_SIZE,
+            DEFAULT_EXECUTION_STATE_CACHE_SIZE,
+            &linera_core::client::RequestsSchedulerConfig::default(),
+        )),
+    };
+    // The cap was already reached by a previous run of the listener.
+    context.wallet().insert(
+        receiver_id,
+        wallet::Chain {
+            owner: receiver.preferred_owner(),
+            block_hash: receiver_info.block_hash,
+            next_block_height: receiver_info.next_block_height,
+            timestamp: clock.current_time(),
+            pending_fast_proposal: None,
+            epoch: Some(receiver_info.epoch),
+            inbox_policy: Some(wallet::InboxPolicy {
+                max_fees_per_hour: Some(Amount::ONE),
+                ..wallet::InboxPolicy::default()
+            }),
+            inbox_fees: [(Timestamp::now(), Amount::ONE)].into(),
+        },
+    );
+
+    let context = Arc::new(Mutex::new(context));
+    let cancellation_token = CancellationToken::new();
+    let child_token = cancellation_token.child_token();
+    let chain_listener = ChainListener::new(
+        config,
+        context.clone(),
+        storage,
+        child_token,
+        tokio::sync::mpsc::unbounded_channel().1,
+        false, // Unit test doesn't need background sync
+    )
+    .run()
+    .await
+    .unwrap();
+
+    let handle = linera_base::Task::spawn(async move { chain_listener.await.unwrap() });
+
+    let recipient = Account::chain(receiver_id);
+    sender
+        .transfer(AccountOwner::CHAIN, Amount::ONE, recipient)
+        .await?;
+    for _ in 0..20 {
+        tokio::task::yield_now().await;
+        receiver.synchronize_from_validators().await?;
+    }
+    assert_eq!(
+        receiver.chain_info().await?.next_block_height,
+        BlockHeight::ZERO
+    );
+
+    // Lifting the cap in the wallet takes effect with the next notification.
+    context
+        .lock()
+        .await
+        .wallet()
+        .mutate(receiver_id, |chain| chain.inbox_policy = None);
+    sender
+        .transfer(AccountOwner::CHAIN, Amount::ONE, recipient)
+        .await?;
+    for i in 0.. {
+        tokio::task::yield_now().await;
+        receiver.synchronize_from_validators().await?;
+        if receiver.chain_info().await?.next_block_height >= BlockHeight::from(1) {
+            break;
+        }
+        if i >= 50 {
+            panic!("The receiver's inbox was not processed by the listener.");
+        }
+    }
+    assert_eq!(receiver.local_balance().await?, Amount::from_tokens(2));
+
+    cancellation_token.cancel();
+    handle.await;
+
+    Ok(())
+}
+
+/// Tests that the chain listener runs the schedules that are due, and records their outcome.
+#[test_log::test(tokio::test)]
+async fn test_chain_listener_runs_schedules() -> anyhow::Result<()> {
+    let signer = InMemorySigner::new(Some(42));
+    let config = ChainListenerConfig::default();
+    let storage_builder = MemoryStorageBuilder::default();
+    let clock = storage_builder.clock().clone();
+    let mut builder = TestBuilder::new(storage_builder, 4, 1, signer.clone()).await?;
+
+    let sender = builder.add_root_chain(0, Amount::from_tokens(10)).await?;
+    let sender_id = sender.chain_id();
+    let receiver = builder.add_root_chain(1, Amount::ZERO).await?;
+
+    let genesis_config = GenesisConfig::new_for_testing(&builder);
+    let admin_chain_id = genesis_config.admin_chain_id();
+    let storage = builder.make_storage().await?;
+    let sender_info = sender.chain_info().await?;
+
+    let context = ClientContext {
+        client: Arc::new(Client::new(
+            environment::Impl {
+                storage: storage.clone(),
+                network: builder.make_node_provider(),
+                signer,
+                wallet: environment::TestWallet::default(),
+            },
+            admin_chain_id,
+            false,
+            [(sender_id, ListeningMode::FullChain)],
+            "Client node with schedules".to_string(),
+            Some(Duration::from_secs(30)),
+            Some(Duration::from_secs(1)),
+            1000,
+            chain_client::Options::test_default(),
+            DEFAULT_BLOCK_CACHE_SIZE,
+            DEFAULT_EXECUTION_STATE_CACHE_SIZE,
+            &linera_core::client::RequestsSchedulerConfig::default(),
+        )),
+    };
+    context.wallet().insert(
+        sender_id,
+        wallet::Chain {
+            owner: sender.preferred_owner(),
+            block_hash: sender_info.block_hash,
+            next_block_height: sender_info.next_block_height,
+            timestamp: clock.current_time(),
+            pending_fast_proposal: None,
+            epoch: Some(sender_info.epoch),
+            inbox_policy: None,
+            inbox_fees: Default::default(),
+        },
+    );
+
+    // A transfer that was due at the start of the epoch runs right away, and only once.
+    let scheduler = Scheduler::default();
+    let transfer = Operation::system(SystemOperation::Transfer {
+        owner: AccountOwner::CHAIN,
+        recipient: Account::chain(receiver.chain_id()),
+        amount: Amount::ONE,
+    });
+    let schedule = scheduler.create(
+        sender_id,
+        "Pay the rent".to_string(),
+        ScheduledAction::ExecuteOperations(vec![transfer]),
+        Trigger::At(Timestamp::from(0)),
+    )?;
+
+    let context = Arc::new(Mutex::new(context));
+    let cancellation_token = CancellationToken::new();
+    let child_token = cancellation_token.child_token();
+    let chain_listener = ChainListener::new(
+        config,
+        context,
+        storage,
+        child_token,
+        tokio::sync::mpsc::unbounded_channel().1,
+        false, // Unit test doesn't need background sync
+    )
+    .with_scheduler(scheduler.clone())
+    .run()
+    .await
+    .unwrap();
+
+    let handle = linera_base::Task::spawn(async move { chain_listener.await.unwrap() });
+
+    for i in 0.. {
+        tokio::task::yield_now().await;
+        if scheduler.get(schedule.id).unwrap().run_count > 0 {
+            break;
+        }
+        if i >= 50 {
+            panic!("The schedule was not run by the listener.");
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let schedule = scheduler.get(schedule.id).unwrap();
+    let last_run = schedule.last_run.unwrap();
+    assert_eq!(last_run.error, None);
+    assert_eq!(last_run.certificate_hashes.len(), 1);
+    assert_eq!(schedule.next_run, None);
+    sender.synchronize_from_validators().await?;
+    assert_eq!(sender.local_balance().await?, Amount::from_tokens(9));
+
+    cancellation_token.cancel();
+    handle.await;
+
+    Ok(())
+}
+
 /// Tests that the chain listener always listens to the admin chain.
 #[test_log::test(tokio::test)]
 async fn test_chain_listener_admin_chain() -> anyhow::Result<()> {
@@ -537,9 +882,9 @@ async fn test_chain_listener_listen_command_adds_chains_to_wallet() -> anyhow::R
 #[test_log::test(tokio::test)]
 async fn test_listener_uses_autosigner_for_incoming_messages() -> anyhow::Result<()> {
     let mut signer = InMemorySigner::new(Some(42));
-    let autosigner_key = signer.generate_new();
+    let autosigner_key = signer.generate_new().unwrap();
     let autosigner_owner: AccountOwner = autosigner_key.into();
-    let dynamic_key = signer.generate_new();
+    let dynamic_key = signer.generate_new().unwrap();
     let dynamic_owner: AccountOwner = dynamic_key.into();
 
     let config = ChainListenerConfig::default();
@@ -605,6 +950,8 @@ async fn test_listener_uses_autosigner_for_incoming_messages() -> anyhow::Result
             timestamp: clock.current_time(),
             pending_fast_proposal: None,
             epoch: Some(chain0_info.epoch),
+            inbox_policy: None,
+            inbox_fees: Default::default(),
         },
     );
     context
@@ -1,7 +1,8 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-//! Tests for [`ClientContext::update_wallet_from_client`].
+//! Tests for [`ClientContext::update_wallet_from_client`] and
+//! [`ClientContext::execute_batch`].
 
 use std::{
     collections::{BTreeMap, BTreeSet},
@@ -63,9 +64,14 @@ async fn make_context(
         retry_delay: Duration::from_secs(1),
         max_retries: 10,
         max_backoff: DEFAULT_MAX_BACKOFF,
+        compression: None,
         chain_listeners: JoinSet::default(),
         default_chain: None,
         client_metrics: None,
+        #[cfg(feature = "fs")]
+        validator_scores_path: None,
+        #[cfg(feature = "fs")]
+        validator_scores_saver: None,
     })
 }
 
@@ -127,7 +133,7 @@ async fn test_wallet_drops_non_fast_pending_proposal() -> anyhow::Result<()> {
     let client = builder.add_root_chain(1, Amount::from_tokens(10)).await?;
     let chain_id = client.chain_id();
     let owner0 = client.identity().await?;
-    let owner1: AccountOwner = signer.generate_new().into();
+    let owner1: AccountOwner = signer.generate_new().unwrap().into();
 
     let timeout_config = TimeoutConfig {
         fast_round_duration: Some(TimeDelta::from_secs(5)),
@@ -157,3 +163,249 @@ async fn test_wallet_drops_non_fast_pending_proposal() -> anyhow::Result<()> {
     assert!(stored.pending_fast_proposal.is_none());
     Ok(())
 }
+
+/// A batch is packed into blocks, and resuming it does not execute any row twice.
+#[cfg(feature = "fs")]
+#[test_log::test(tokio::test)]
+async fn test_execute_batch_resumes() -> anyhow::Result<()> {
+    use linera_base::{data_types::BlockHeight, identifiers::Account};
+    use linera_execution::{system::SystemOperation, Operation};
+
+    use crate::{
+        batch::{Batch, PendingBatchBlock},
+        chain_listener::ClientContext as _,
+    };
+
+    let signer = InMemorySigner::new(None);
+    let mut builder =
+        TestBuilder::new(MemoryStorageBuilder::default(), 4, 0, signer.clone()).await?;
+    let client = builder.add_root_chain(1, Amount::from_tokens(10)).await?;
+    let recipient = builder.add_root_chain(2, Amount::ZERO).await?;
+    let chain_id = client.chain_id();
+    let owner = client.identity().await?;
+    let mut context = make_context(&mut builder, signer, chain_id).await?;
+    let info = client.chain_info().await?;
+    context
+        .update_wallet_for_new_chain(chain_id, Some(owner), info.timestamp, info.epoch)
+        .await?;
+    context.update_wallet_from_client(&client).await?;
+
+    let transfer = Operation::system(SystemOperation::Transfer {
+        owner: AccountOwner::CHAIN,
+        recipient: Account::chain(recipient.chain_id()),
+        amount: Amount::ONE,
+    });
+    let batch = Batch {
+        chain_id,
+        operations: vec![transfer; 5],
+    };
+    let directory = tempfile::tempdir()?;
+    let progress_path = directory.path().join("batch.progress.json");
+    let progress = context.execute_batch(&batch, 2, &progress_path).await?;
+    let rows = progress
+        .blocks
+        .iter()
+        .map(|block| block.rows.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(rows, [0..2, 2..4, 4..5]);
+    assert!(progress.pending.is_none());
+
+    // The client stopped after the last block was committed, but before it was recorded.
+    let mut interrupted = progress.clone();
+    let last = interrupted.blocks.pop().unwrap();
+    interrupted.pending = Some(PendingBatchBlock {
+        rows: last.rows,
+        height: last.height,
+    });
+    std::fs::write(&progress_path, serde_json::to_vec(&interrupted)?)?;
+    assert_eq!(
+        context.execute_batch(&batch, 2, &progress_path).await?,
+        progress
+    );
+    let chain_client = context.make_chain_client(chain_id).await?;
+    assert_eq!(chain_client.local_balance().await?, Amount::from_tokens(5));
+    assert_eq!(
+        chain_client.chain_info().await?.next_block_height,
+        BlockHeight(3)
+    );
+
+    // The progress file cannot be used for a different batch.
+    let other = Batch {
+        chain_id,
+        operations: batch.operations[..4].to_vec(),
+    };
+    assert!(context
+        .execute_batch(&other, 2, &progress_path)
+        .await
+        .is_err());
+    Ok(())
+}
+
+/// The history of a chain lists the transfers in and out of it, and can be filtered.
+#[test_log::test(tokio::test)]
+async fn test_chain_history() -> anyhow::Result<()> {
+    use linera_base::{
+        crypto::CryptoHash,
+        data_types::BlockHeight,
+        identifiers::{Account, ApplicationId, GenericApplicationId},
+    };
+
+    use crate::chain_history::{HistoryDetails, HistoryFilter};
+
+    let signer = InMemorySigner::new(None);
+    let mut builder =
+        TestBuilder::new(MemoryStorageBuilder::default(), 4, 0, signer.clone()).await?;
+    let sender = builder.add_root_chain(1, Amount::from_tokens(10)).await?;
+    let receiver = builder.add_root_chain(2, Amount::ZERO).await?;
+    let recipient = Account::chain(receiver.chain_id());
+    for tokens in [1, 2] {
+        sender
+            .transfer(AccountOwner::CHAIN, Amount::from_tokens(tokens), recipient)
+            .await?
+            .unwrap();
+    }
+    receiver.synchronize_from_validators().await?;
+    let (certificates, _) = receiver.process_inbox().await?;
+    assert_eq!(certificates.len(), 1);
+    let context = make_context(&mut builder, signer, sender.chain_id()).await?;
+
+    let entries = context
+        .chain_history(
+            sender.chain_id(),
+            BlockHeight(0),
+            None,
+            &HistoryFilter::default(),
+        )
+        .await?;
+    let details = entries
+        .iter()
+        .map(|entry| (entry.height, entry.details.clone()))
+        .collect::<Vec<_>>();
+    let transfer = HistoryDetails::Operation {
+        application_id: GenericApplicationId::System,
+        summary: "Transfer".to_string(),
+    };
+    let transfer_out = |tokens| HistoryDetails::TransferOut {
+        source: AccountOwner::CHAIN,
+        target: recipient,
+        amount: Amount::from_tokens(tokens),
+    };
+    assert_eq!(
+        details,
+        [
+            (BlockHeight(0), transfer.clone()),
+            (BlockHeight(0), transfer_out(1)),
+            (BlockHeight(1), transfer),
+            (BlockHeight(1), transfer_out(2)),
+        ]
+    );
+
+    // The history is limited to the given heights.
+    let entries = context
+        .chain_history(
+            sender.chain_id(),
+            BlockHeight(1),
+            Some(BlockHeight(1)),
+            &HistoryFilter::default(),
+        )
+        .await?;
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|entry| entry.height == BlockHeight(1)));
+
+    // The receiver's chain is synchronized from the validators.
+    let entries = context
+        .chain_history(
+            receiver.chain_id(),
+            BlockHeight(0),
+            None,
+            &HistoryFilter::default(),
+        )
+        .await?;
+    let amounts = entries
+        .iter()
+        .map(|entry| match &entry.details {
+            HistoryDetails::TransferIn {
+                source,
+                amount,
+                accepted: true,
+                ..
+            } if *source == Account::chain(sender.chain_id()) => *amount,
+            details => panic!("unexpected history entry: {details:?}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(amounts, [Amount::from_tokens(1), Amount::from_tokens(2)]);
+
+    // Transfers are not part of any user application, and involve the chain's account.
+    let filter = HistoryFilter {
+        application_id: Some(ApplicationId::new(CryptoHash::test_hash("app"))),
+        owner: None,
+    };
+    let entries = context
+        .chain_history(sender.chain_id(), BlockHeight(0), None, &filter)
+        .await?;
+    assert!(entries.is_empty());
+    let filter = HistoryFilter {
+        application_id: None,
+        owner: Some(AccountOwner::CHAIN),
+    };
+    let entries = context
+        .chain_history(sender.chain_id(), BlockHeight(0), None, &filter)
+        .await?;
+    assert_eq!(entries.len(), 2);
+    Ok(())
+}
+
+/// Dropping the context saves the validator scores, keeping the statistics that another
+/// client saved in the meantime where they are more recent, and replaces the file atomically.
+#[cfg(feature = "fs")]
+#[test_log::test(tokio::test)]
+async fn test_dropping_context_saves_validator_scores() -> anyhow::Result<()> {
+    use linera_base::{crypto::ValidatorPublicKey, data_types::Timestamp};
+    use linera_core::client::requests_scheduler::NodeStatistics;
+
+    let signer = InMemorySigner::new(None);
+    let mut builder =
+        TestBuilder::new(MemoryStorageBuilder::default(), 4, 0, signer.clone()).await?;
+    let client = builder.add_root_chain(1, Amount::from_tokens(10)).await?;
+    let validators = builder
+        .initial_committee
+        .validators()
+        .keys()
+        .copied()
+        .collect::<Vec<_>>();
+    let mut context = make_context(&mut builder, signer, client.chain_id()).await?;
+    let directory = tempfile::tempdir()?;
+    let path = directory.path().join("wallet.scores.json");
+    let statistics = NodeStatistics {
+        ema_latency_ms: 50.0,
+        ema_success_rate: 0.9,
+        total_requests: 100,
+        updated_at: Timestamp::now(),
+    };
+    std::fs::write(
+        &path,
+        serde_json::to_vec(&BTreeMap::from([(validators[1], statistics)]))?,
+    )?;
+    context.load_validator_scores(path.clone()).await;
+
+    // Another client saves newer statistics for a different validator.
+    let newer = NodeStatistics {
+        ema_latency_ms: 5.0,
+        ema_success_rate: 1.0,
+        total_requests: 1000,
+        updated_at: Timestamp::now().saturating_add(TimeDelta::from_secs(3600)),
+    };
+    std::fs::write(
+        &path,
+        serde_json::to_vec(&BTreeMap::from([(validators[0], newer)]))?,
+    )?;
+
+    drop(context);
+    let saved: BTreeMap<ValidatorPublicKey, NodeStatistics> =
+        serde_json::from_slice(&std::fs::read(&path)?)?;
+    assert_eq!(saved.len(), 2);
+    assert_eq!(saved[&validators[0]], newer);
+    assert_eq!(saved[&validators[1]].total_requests, 100);
+    assert_eq!(std::fs::read_dir(directory.path())?.count(), 1);
+    Ok(())
+}
@@ -0,0 +1,278 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests for the coordination of drafts on multi-owner chains.
+
+use assert_matches::assert_matches;
+use linera_base::{
+    crypto::{CryptoHash, InMemorySigner},
+    data_types::{Amount, Round, Timestamp},
+    identifiers::{Account, AccountOwner, ChainId},
+    ownership::{ChainOwnership, TimeoutConfig},
+};
+use linera_chain::{
+    data_types::BlockExecutionOutcome,
+    test::{make_first_block, BlockTestExt as _},
+    types::{ConfirmedBlock, ConfirmedBlockCertificate},
+};
+use linera_execution::{system::SystemOperation, Operation};
+
+use crate::drafts::{Draft, DraftBook, DraftStatus, DraftVote, Error, SignedDraftVote};
+
+struct Owners {
+    signer: InMemorySigner,
+    owners: Vec<AccountOwner>,
+    ownership: ChainOwnership,
+}
+
+/// Creates three owners with the weights 1, 1 and 2.
+fn owners() -> Owners {
+    let mut signer = InMemorySigner::new(Some(42));
+    let owners = (0..3)
+        .map(|_| AccountOwner::from(signer.generate_new().unwrap()))
+        .collect::<Vec<_>>();
+    let ownership = ChainOwnership::multiple(
+        owners.iter().copied().zip([1, 1, 2]),
+        2,
+        TimeoutConfig::default(),
+    );
+    Owners {
+        signer,
+        owners,
+        ownership,
+    }
+}
+
+fn draft(author: AccountOwner) -> Draft {
+    let chain_id = ChainId(CryptoHash::test_hash("treasury"));
+    Draft {
+        chain_id,
+        author,
+        operations: vec![Operation::system(SystemOperation::Transfer {
+            owner: AccountOwner::CHAIN,
+            recipient: Account::chain(ChainId(CryptoHash::test_hash("recipient"))),
+            amount: Amount::ONE,
+        })],
+        description: "Pay the auditors".to_string(),
+        created_at: Timestamp::from(1_000),
+    }
+}
+
+/// Returns a certificate for a block with the draft's operations, proposed by `proposer`.
+fn certificate(draft: &Draft, proposer: AccountOwner) -> ConfirmedBlockCertificate {
+    let block = draft
+        .operations
+        .iter()
+        .fold(make_first_block(draft.chain_id), |block, operation| {
+            block.with_operation(operation.clone())
+        })
+        .with_authenticated_owner(Some(proposer))
+        .with_timestamp(draft.created_at);
+    let value = ConfirmedBlock::new(BlockExecutionOutcome::default().with(block));
+    ConfirmedBlockCertificate::new(value, Round::SingleLeader(0), vec![])
+}
+
+async fn vote(
+    owners: &Owners,
+    owner: usize,
+    draft_id: CryptoHash,
+    approve: bool,
+) -> SignedDraftVote {
+    let vote = DraftVote { draft_id, approve };
+    SignedDraftVote::new(vote, &owners.owners[owner], &owners.signer)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_draft_approval_by_weight() -> anyhow::Result<()> {
+    let owners = owners();
+    let ownership = &owners.ownership;
+    let mut book = DraftBook::default();
+    let draft = draft(owners.owners[0]);
+    let id = draft.id();
+
+    let author_vote = vote(&owners, 0, id, true).await;
+    assert_eq!(book.publish(draft.clone(), author_vote, ownership)?, id);
+    assert_eq!(
+        book.get(&id).unwrap().status(ownership),
+        DraftStatus::Pending
+    );
+
+    // Half of the weight is not a majority.
+    let record = book.vote(vote(&owners, 1, id, true).await, ownership)?;
+    assert_eq!(record.tally(ownership).approved_weight, 2);
+    assert_eq!(record.status(ownership), DraftStatus::Pending);
+
+    let record = book.vote(vote(&owners, 2, id, true).await, ownership)?;
+    assert_eq!(record.status(ownership), DraftStatus::Approved);
+    record.check_votes()?;
+
+    // Owners can change their minds.
+    let record = book.vote(vote(&owners, 2, id, false).await, ownership)?;
+    assert_eq!(record.tally(ownership).approved_weight, 2);
+    assert_eq!(record.tally(ownership).rejected_weight, 2);
+    assert_eq!(record.status(ownership), DraftStatus::Rejected);
+    record.check_votes()?;
+
+    book.mark_committed(id, &certificate(&draft, owners.owners[0]))?;
+    assert_eq!(
+        book.get(&id).unwrap().status(ownership),
+        DraftStatus::Committed
+    );
+    assert_matches!(
+        book.vote(vote(&owners, 1, id, false).await, ownership),
+        Err(Error::AlreadyCommitted(_))
+    );
+    assert_eq!(book.drafts(draft.chain_id).count(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_draft_votes() -> anyhow::Result<()> {
+    let owners = owners();
+    let ownership = &owners.ownership;
+    let mut book = DraftBook::default();
+    let draft = draft(owners.owners[0]);
+    let id = draft.id();
+
+    // The author has to approve their own draft.
+    let other_vote = vote(&owners, 1, id, true).await;
+    assert_matches!(
+        book.publish(draft.clone(), other_vote, ownership),
+        Err(Error::MissingAuthorApproval)
+    );
+    book.publish(draft, vote(&owners, 0, id, true).await, ownership)?;
+
+    // Votes from keys that don't own the chain are rejected.
+    let mut stranger_signer = InMemorySigner::new(Some(7));
+    let stranger = AccountOwner::from(stranger_signer.generate_new().unwrap());
+    let stranger_vote = SignedDraftVote::new(
+        DraftVote {
+            draft_id: id,
+            approve: true,
+        },
+        &stranger,
+        &stranger_signer,
+    )
+    .await?;
+    assert_matches!(
+        book.vote(stranger_vote, ownership),
+        Err(Error::NotAnOwner(owner, _)) if owner == stranger
+    );
+
+    // A signature for a rejection cannot be used as an approval.
+    let mut forged_vote = vote(&owners, 2, id, false).await;
+    forged_vote.vote.approve = true;
+    assert_matches!(
+        book.vote(forged_vote, ownership),
+        Err(Error::InvalidSignature(_))
+    );
+
+    // A record whose votes were swapped by the coordinator fails the check.
+    let mut record = book.get(&id).unwrap().clone();
+    let signature = record.approvals.remove(&owners.owners[0]).unwrap();
+    record.rejections.insert(owners.owners[0], signature);
+    assert_matches!(record.check_votes(), Err(Error::InvalidSignature(_)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_super_owner_votes() -> anyhow::Result<()> {
+    let mut owners = owners();
+    owners.ownership.super_owners.insert(owners.owners[0]);
+    let ownership = &owners.ownership;
+    let mut book = DraftBook::default();
+    let draft = draft(owners.owners[1]);
+    let id = draft.id();
+    book.publish(draft, vote(&owners, 1, id, true).await, ownership)?;
+
+    // A super owner's rejection outweighs the other owners.
+    book.vote(vote(&owners, 2, id, true).await, ownership)?;
+    let record = book.vote(vote(&owners, 0, id, false).await, ownership)?;
+    assert!(record.tally(ownership).rejected_by_super_owner);
+    assert_eq!(record.status(ownership), DraftStatus::Rejected);
+
+    // A super owner can approve the draft on their own.
+    let record = book.vote(vote(&owners, 0, id, true).await, ownership)?;
+    assert!(record.tally(ownership).approved_by_super_owner);
+    assert_eq!(record.status(ownership), DraftStatus::Approved);
+    record.check_votes()?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mark_committed_checks_block() -> anyhow::Result<()> {
+    let owners = owners();
+    let ownership = &owners.ownership;
+    let mut book = DraftBook::default();
+    let draft = draft(owners.owners[0]);
+    let id = draft.id();
+    book.publish(draft.clone(), vote(&owners, 0, id, true).await, ownership)?;
+    book.vote(vote(&owners, 2, id, true).await, ownership)?;
+
+    // The block must contain exactly the draft's operations.
+    let mut other_draft = draft.clone();
+    other_draft
+        .operations
+        .push(other_draft.operations[0].clone());
+    assert_matches!(
+        book.mark_committed(id, &certificate(&other_draft, owners.owners[0])),
+        Err(Error::NotExecuted(_))
+    );
+
+    // The block must be on the draft's chain.
+    other_draft = draft.clone();
+    other_draft.chain_id = ChainId(CryptoHash::test_hash("other chain"));
+    assert_matches!(
+        book.mark_committed(id, &certificate(&other_draft, owners.owners[0])),
+        Err(Error::NotExecuted(_))
+    );
+
+    // The proposer must have approved the draft.
+    assert_matches!(
+        book.mark_committed(id, &certificate(&draft, owners.owners[1])),
+        Err(Error::UnapprovedProposer(_))
+    );
+    assert_eq!(
+        book.get(&id).unwrap().status(ownership),
+        DraftStatus::Approved
+    );
+
+    book.mark_committed(id, &certificate(&draft, owners.owners[2]))?;
+    assert_eq!(
+        book.get(&id).unwrap().status(ownership),
+        DraftStatus::Committed
+    );
+    Ok(())
+}
+
+#[cfg(feature = "fs")]
+#[tokio::test]
+async fn test_drafts_persistence() -> anyhow::Result<()> {
+    use crate::drafts::Drafts;
+
+    let directory = tempfile::tempdir()?;
+    let path = directory.path().join("wallet.drafts.json");
+    let owners = owners();
+    let ownership = &owners.ownership;
+    let draft = draft(owners.owners[0]);
+    let id = draft.id();
+
+    let drafts = Drafts::load(path.clone())?;
+    drafts.publish(draft.clone(), vote(&owners, 0, id, true).await, ownership)?;
+    let record = drafts.vote(vote(&owners, 1, id, false).await, ownership)?;
+    let reloaded = Drafts::load(path.clone())?;
+    assert_eq!(reloaded.get(&id), Some(record.clone()));
+    assert_eq!(reloaded.drafts(draft.chain_id), vec![record]);
+
+    // Failed updates are not saved.
+    assert_matches!(
+        reloaded.mark_committed(id, &certificate(&draft, owners.owners[1])),
+        Err(Error::UnapprovedProposer(_))
+    );
+    reloaded.mark_committed(id, &certificate(&draft, owners.owners[0]))?;
+    let record = Drafts::load(path)?.get(&id).unwrap();
+    assert_eq!(record.status(ownership), DraftStatus::Committed);
+    Ok(())
+}
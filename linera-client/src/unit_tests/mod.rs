@@ -1,5 +1,8 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+mod batch;
 mod chain_listener;
 mod client_context;
+mod drafts;
+mod scheduler;
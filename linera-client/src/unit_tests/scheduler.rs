@@ -0,0 +1,258 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests for the triggers and the book-keeping of schedules.
+
+use assert_matches::assert_matches;
+use linera_base::{
+    crypto::CryptoHash,
+    data_types::{TimeDelta, Timestamp},
+    identifiers::ChainId,
+};
+
+use crate::scheduler::{CronSchedule, Error, ScheduleBook, ScheduledAction, Trigger};
+
+fn time(s: &str) -> Timestamp {
+    s.parse().unwrap()
+}
+
+fn next_cron(expression: &str, after: &str) -> Option<Timestamp> {
+    expression
+        .parse::<CronSchedule>()
+        .unwrap()
+        .next_after(time(after))
+}
+
+#[test]
+fn test_cron_schedule() {
+    assert_eq!(
+        next_cron("0 2 * * *", "2025-01-31 01:30:00"),
+        Some(time("2025-01-31 02:00:00"))
+    );
+    assert_eq!(
+        next_cron("0 2 * * *", "2025-01-31 02:00:00"),
+        Some(time("2025-02-01 02:00:00"))
+    );
+    // Friday evening, so the next working hour is on Monday.
+    assert_eq!(
+        next_cron("*/15 9-17 * * 1-5", "2025-01-31 17:50:00"),
+        Some(time("2025-02-03 09:00:00"))
+    );
+    assert_eq!(
+        next_cron("0 0 29 2 *", "2025-01-01 00:00:00"),
+        Some(time("2028-02-29 00:00:00"))
+    );
+    // If both days are restricted, either of them matches.
+    assert_eq!(
+        next_cron("0 12 13 * 5", "2025-01-31 12:00:00"),
+        Some(time("2025-02-07 12:00:00"))
+    );
+    assert_eq!(
+        next_cron("0 0 * * 7", "2025-01-31 00:00:00"),
+        Some(time("2025-02-02 00:00:00"))
+    );
+    assert_eq!(next_cron("0 0 30 2 *", "2025-01-01 00:00:00"), None);
+
+    for invalid in [
+        "60 * * * *",
+        "* * * *",
+        "*/0 * * * *",
+        "5-1 * * * *",
+        "a * * * *",
+    ] {
+        assert_matches!(
+            invalid.parse::<CronSchedule>(),
+            Err(Error::InvalidCron { .. }),
+            "{invalid}"
+        );
+    }
+}
+
+#[test]
+fn test_parse_trigger() {
+    for trigger in [
+        "at 2025-01-31 02:00:00",
+        "every 1d12h from 2025-01-31 02:00:00",
+        "every 1h30m",
+        "cron 0 2 * * *",
+    ] {
+        assert_eq!(trigger.parse::<Trigger>().unwrap().to_string(), trigger);
+    }
+    assert_eq!(
+        "every 90m".parse::<Trigger>().unwrap(),
+        Trigger::Every {
+            interval: TimeDelta::from_secs(5400),
+            start: None,
+        }
+    );
+    assert_eq!(
+        "at 2025-01-31T02:00:00".parse::<Trigger>().unwrap(),
+        Trigger::At(time("2025-01-31 02:00:00"))
+    );
+    assert_matches!(
+        "every 0s".parse::<Trigger>(),
+        Err(Error::InvalidInterval(_))
+    );
+    assert_matches!(
+        "every 3w".parse::<Trigger>(),
+        Err(Error::InvalidInterval(_))
+    );
+    assert_matches!("at noon".parse::<Trigger>(), Err(Error::InvalidTime(_)));
+    assert_matches!(
+        "sometimes".parse::<Trigger>(),
+        Err(Error::InvalidTrigger(_))
+    );
+}
+
+#[test]
+fn test_trigger_runs() {
+    let start = time("2025-01-31 02:00:00");
+    let hour = TimeDelta::from_secs(3600);
+    let hourly = Trigger::Every {
+        interval: hour,
+        start: Some(start),
+    };
+    let later = |seconds| start.saturating_add(TimeDelta::from_secs(seconds));
+    assert_eq!(hourly.first_run(time("2025-01-31 01:59:59")), Some(start));
+    assert_eq!(hourly.first_run(later(5400)), Some(later(7200)));
+    // Missed runs are skipped, but the runs stay aligned with the start.
+    assert_eq!(
+        hourly.next_run(start, later(3 * 3600 + 300)),
+        Some(later(4 * 3600))
+    );
+
+    let now = time("2025-02-01 00:00:00");
+    let every_hour = Trigger::Every {
+        interval: hour,
+        start: None,
+    };
+    assert_eq!(every_hour.first_run(now), Some(now.saturating_add(hour)));
+    assert_eq!(Trigger::At(start).first_run(now), Some(start));
+    assert_eq!(Trigger::At(start).next_run(start, now), None);
+}
+
+#[test]
+fn test_schedule_book() -> anyhow::Result<()> {
+    let chain_a = ChainId(CryptoHash::test_hash("a"));
+    let chain_b = ChainId(CryptoHash::test_hash("b"));
+    let now = time("2025-01-31 01:00:00");
+    let mut book = ScheduleBook::default();
+    let once = book
+        .create(
+            chain_a,
+            "Once".to_string(),
+            ScheduledAction::ProcessInbox,
+            Trigger::At(time("2025-01-31 02:00:00")),
+            now,
+        )?
+        .id;
+    let nightly = book
+        .create(
+            chain_b,
+            "Nightly".to_string(),
+            ScheduledAction::ExecuteOperations(Vec::new()),
+            "cron 30 2 * * *".parse()?,
+            now,
+        )?
+        .id;
+    assert_matches!(
+        book.create(
+            chain_a,
+            String::new(),
+            ScheduledAction::ProcessInbox,
+            "cron 0 0 31 4 *".parse()?,
+            now,
+        ),
+        Err(Error::NeverFires(_))
+    );
+    assert_eq!(book.schedules(Some(chain_b)).count(), 1);
+    assert_eq!(book.next_due(), Some(time("2025-01-31 02:00:00")));
+    assert!(book.due(now).is_empty());
+
+    let now = time("2025-01-31 03:00:00");
+    let due = book.due(now);
+    assert_eq!(due.len(), 2);
+    assert_eq!(book.start_run(once, now), Some(due[0].clone()));
+    assert_eq!(book.start_run(nightly, now), Some(due[1].clone()));
+    assert!(book.due(now).is_empty());
+    book.record_run(once, now, Ok(vec![CryptoHash::test_hash("block")]));
+    book.record_run(nightly, now, Err("insufficient balance".to_string()));
+    assert_eq!(book.get(once).unwrap().next_run, None);
+    let schedule = book.get(nightly).unwrap();
+    assert_eq!(schedule.run_count, 1);
+    assert_eq!(schedule.next_run, Some(time("2025-02-01 02:30:00")));
+    assert_eq!(
+        schedule.last_run.as_ref().unwrap().error.as_deref(),
+        Some("insufficient balance")
+    );
+    assert_eq!(book.next_due(), Some(time("2025-02-01 02:30:00")));
+
+    // The schedules are saved as JSON, with the triggers in their text form.
+    let json = serde_json::to_string(&book)?;
+    assert!(json.contains("\"cron 30 2 * * *\""));
+    let mut book = serde_json::from_str::<ScheduleBook>(&json)?;
+    assert_eq!(book.get(nightly), Some(schedule));
+
+    book.cancel(once)?;
+    assert_matches!(book.cancel(once), Err(Error::UnknownSchedule(_)));
+    assert_eq!(book.schedules(None).count(), 1);
+    Ok(())
+}
+
+#[cfg(feature = "fs")]
+#[test]
+fn test_scheduler_persistence() -> anyhow::Result<()> {
+    use crate::scheduler::Scheduler;
+
+    let directory = tempfile::tempdir()?;
+    let path = directory.path().join("wallet.schedules.json");
+    let chain_id = ChainId(CryptoHash::test_hash("chain"));
+    let scheduler = Scheduler::load(path.clone())?;
+    let schedule = scheduler.create(
+        chain_id,
+        "Daily".to_string(),
+        ScheduledAction::ProcessInbox,
+        "every 1d".parse()?,
+    )?;
+    let reloaded = Scheduler::load(path.clone())?;
+    assert_eq!(reloaded.schedules(Some(chain_id)), vec![schedule.clone()]);
+
+    reloaded.cancel(schedule.id)?;
+    assert!(Scheduler::load(path)?.schedules(None).is_empty());
+    Ok(())
+}
+
+#[cfg(feature = "fs")]
+#[test]
+fn test_scheduler_runs_once_if_saving_fails() -> anyhow::Result<()> {
+    use crate::scheduler::Scheduler;
+
+    let directory = tempfile::tempdir()?;
+    let path = directory.path().join("wallet.schedules.json");
+    let chain_id = ChainId(CryptoHash::test_hash("chain"));
+    let scheduler = Scheduler::load(path.clone())?;
+    let schedule = scheduler.create(
+        chain_id,
+        "Pay rent".to_string(),
+        ScheduledAction::ExecuteOperations(Vec::new()),
+        "every 1d".parse()?,
+    )?;
+    let now = schedule.next_run.unwrap();
+    assert_eq!(scheduler.due(now), vec![schedule.clone()]);
+
+    // Saving fails from now on, since the temporary file can't be written.
+    std::fs::create_dir(directory.path().join("wallet.schedules.json.tmp"))?;
+    assert_eq!(
+        scheduler.start_run(schedule.id, now),
+        Some(schedule.clone())
+    );
+    scheduler.record_run(schedule.id, now, Ok(Vec::new()));
+    assert!(scheduler.due(now).is_empty());
+    let schedule = scheduler.get(schedule.id).unwrap();
+    assert_eq!(
+        schedule.next_run,
+        Some(now.saturating_add(TimeDelta::from_secs(86_400)))
+    );
+    assert_eq!(schedule.run_count, 1);
+    Ok(())
+}
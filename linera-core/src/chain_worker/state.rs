@@ -15,7 +15,7 @@ use linera_base::prometheus_util::MeasureLatency as _;
 use linera_base::{
     crypto::{CryptoHash, ValidatorPublicKey},
     data_types::{
-        ApplicationDescription, ArithmeticError, Blob, BlockHeight, Epoch, Round, Timestamp,
+        Amount, ApplicationDescription, ArithmeticError, Blob, BlockHeight, Epoch, Round, Timestamp,
     },
     ensure,
     identifiers::{AccountOwner, ApplicationId, BlobId, ChainId, EventId, StreamId},
@@ -35,8 +35,8 @@ use linera_chain::{
 };
 use linera_execution::{
     system::{EpochEventData, EventSubscriptions, EPOCH_STREAM_NAME},
-    ExecutionRuntimeContext as _, ExecutionStateView, Query, QueryContext, QueryOutcome,
-    ResourceTracker, ServiceRuntimeEndpoint,
+    ApplicationLog, ExecutionRuntimeContext as _, ExecutionStateView, Query, QueryContext,
+    QueryOutcome, ResourceTracker, ServiceRuntimeEndpoint,
 };
 use linera_storage::{Clock as _, Storage};
 use linera_views::{
@@ -51,10 +51,15 @@ use tracing::{debug, instrument, trace, warn};
 use crate::{
     chain_worker::{handle::AtomicTimestamp, ChainWorkerConfig, DeliveryNotifier},
     client::ListeningMode,
-    data_types::{ChainInfo, ChainInfoQuery, ChainInfoResponse, CrossChainRequest},
+    data_types::{ChainInfo, ChainInfoQuery, ChainInfoResponse, ChainSummary, CrossChainRequest},
     worker::{BatchRequest, NetworkActions, Notification, Reason, WorkerError},
 };
 
+/// The amount temporarily added to the chain balance to work out the fees of a block that
+/// the chain can't pay. It is large enough for any block's fees, and small enough that the
+/// block's credits don't overflow the balance.
+const FEE_ESTIMATION_CREDIT: Amount = Amount::from_tokens(u64::MAX as u128);
+
 /// Type alias for event subscriptions result.
 pub(crate) type EventSubscriptionsResult = Vec<((ChainId, StreamId), EventSubscriptions)>;
 
@@ -86,6 +91,10 @@ mod metrics {
     });
 }
 
+/// The execution state after executing a block that is not confirmed yet, and the log
+/// messages emitted by applications during that execution.
+pub(crate) type ExecutedBlockState = (ExecutionStateView<InactiveContext>, Vec<ApplicationLog>);
+
 /// The state of the chain worker.
 pub(crate) struct ChainWorkerState<StorageClient>
 where
@@ -106,8 +115,7 @@ where
     /// the `RwLock`.
     last_access: Arc<AtomicTimestamp>,
     block_values: Arc<ValueCache<CryptoHash, ConfirmedBlock>>,
-    execution_state_cache:
-        Option<Arc<UniqueValueCache<CryptoHash, ExecutionStateView<InactiveContext>>>>,
+    execution_state_cache: Option<Arc<UniqueValueCache<CryptoHash, ExecutedBlockState>>>,
     chain_modes: Option<Arc<sync::RwLock<BTreeMap<ChainId, ListeningMode>>>>,
     delivery_notifier: DeliveryNotifier,
     knows_chain_is_active: bool,
@@ -150,9 +158,7 @@ where
         config: ChainWorkerConfig,
         storage: StorageClient,
         block_values: Arc<ValueCache<CryptoHash, ConfirmedBlock>>,
-        execution_state_cache: Option<
-            Arc<UniqueValueCache<CryptoHash, ExecutionStateView<InactiveContext>>>,
-        >,
+        execution_state_cache: Option<Arc<UniqueValueCache<CryptoHash, ExecutedBlockState>>>,
         chain_modes: Option<Arc<sync::RwLock<BTreeMap<ChainId, ListeningMode>>>>,
         delivery_notifier: DeliveryNotifier,
         chain_id: ChainId,
@@ -896,7 +902,7 @@ where
                 block.body.incoming_bundles(),
             )
             .await?;
-        let confirmed_block = if let Some(mut execution_state) = self
+        let (confirmed_block, logs) = if let Some((mut execution_state, logs)) = self
             .execution_state_cache
             .as_ref()
             .and_then(|cache| cache.remove(&block_hash))
@@ -909,11 +915,11 @@ where
                         .clone_with_base_key(ctx.base_key().bytes.clone())
                 })
                 .await;
-            certificate.into_value()
+            (certificate.into_value(), logs)
         } else {
             let (proposed_block, outcome) = certificate.into_value().into_block().into_proposal();
             let oracle_responses = Some(outcome.oracle_responses.clone());
-            let (proposed_block, verified, _resource_tracker, _) = chain
+            let (proposed_block, verified, _resource_tracker, _, logs) = chain
                 .execute_block(
                     proposed_block,
                     local_time,
@@ -932,7 +938,10 @@ where
                 ))
                 .into());
             }
-            ConfirmedBlock::new(Block::new(proposed_block, verified))
+            (
+                ConfirmedBlock::new(Block::new(proposed_block, verified)),
+                logs,
+            )
         };
 
         // Update the rest of the chain state.
@@ -961,6 +970,19 @@ where
         // Persist chain.
         self.save().await?;
 
+        let allow_application_logs = self
+            .chain
+            .execution_state
+            .context()
+            .extra()
+            .execution_runtime_config()
+            .allow_application_logs;
+        if allow_application_logs && !logs.is_empty() {
+            self.storage
+                .write_application_logs(block_hash, &logs)
+                .await?;
+        }
+
         self.block_values
             .insert_hashed(Cow::Owned(confirmed_block.into_inner()));
 
@@ -1475,6 +1497,34 @@ where
         Ok(hashes)
     }
 
+    /// Returns a compact summary of the chain's state.
+    #[instrument(skip_all, fields(chain_id = %self.chain_id()))]
+    pub(crate) async fn chain_summary(&self) -> Result<ChainSummary, WorkerError> {
+        let chain = &self.chain;
+        let tip_state = chain.tip_state.get();
+        let nonempty_origins: Vec<ChainId> = chain.nonempty_inboxes.get().iter().copied().collect();
+        let inboxes = chain.inboxes.try_load_entries(&nonempty_origins).await?;
+        let mut num_pending_message_bundles = 0u64;
+        for (origin, inbox) in nonempty_origins.into_iter().zip(inboxes) {
+            let inbox = inbox.ok_or_else(|| {
+                ChainError::InternalError(format!("Missing inbox for origin {origin}"))
+            })?;
+            num_pending_message_bundles += inbox.added_bundles.count() as u64;
+        }
+        let manager = &chain.manager;
+        let has_pending_proposal = manager.signed_proposal.get().is_some()
+            || manager.proposed.get().is_some()
+            || manager.locking_block.get().is_some()
+            || manager.timeout.get().is_some();
+        Ok(ChainSummary {
+            chain_id: self.chain_id(),
+            next_block_height: tip_state.next_block_height,
+            block_hash: tip_state.block_hash,
+            num_pending_message_bundles,
+            has_pending_proposal,
+        })
+    }
+
     /// Returns the next block height to receive from an inbox.
     #[instrument(skip_all, fields(
         chain_id = %self.chain_id(),
@@ -1769,7 +1819,7 @@ where
                 .and_then(|cache| cache.remove(&h))
                 .map(|s| (h, s))
         });
-        if let Some((requested_block, mut state)) = cached_state {
+        if let Some((requested_block, (mut state, logs))) = cached_state {
             let next_block_height = next_block_height
                 .try_add_one()
                 .expect("block height to not overflow");
@@ -1790,7 +1840,7 @@ where
                 .await
                 .with_execution_context(ChainExecutionContext::Query)?;
             if let Some(cache) = &self.execution_state_cache {
-                cache.insert(&requested_block, state);
+                cache.insert(&requested_block, (state, logs));
             }
             Ok((outcome, next_block_height))
         } else {
@@ -1888,6 +1938,40 @@ where
         ))
     }
 
+    /// Returns the total fees of a block, even if the chain can't pay them.
+    ///
+    /// The block is executed without persisting any changes, with the chain balance
+    /// temporarily raised by [`FEE_ESTIMATION_CREDIT`]. The resulting state is not cached.
+    #[instrument(skip_all, fields(
+        chain_id = %self.chain_id(),
+        block_height = %block.height
+    ))]
+    pub(crate) async fn block_fees(
+        &mut self,
+        block: ProposedBlock,
+        round: Option<u32>,
+        published_blobs: &[Blob],
+        policy: BundleExecutionPolicy,
+    ) -> Result<Amount, WorkerError> {
+        self.initialize_and_save_if_needed().await?;
+        let local_time = self.storage.clock().current_time();
+        self.chain
+            .remove_bundles_from_inboxes(block.timestamp, true, block.incoming_bundles())
+            .await?;
+        let balance = self.chain.execution_state.system.balance.get_mut();
+        *balance = balance.saturating_add(FEE_ESTIMATION_CREDIT);
+        let (_, _, resource_tracker, _, _) = Box::pin(self.chain.execute_block(
+            block,
+            local_time,
+            round,
+            published_blobs,
+            None,
+            policy,
+        ))
+        .await?;
+        Ok(resource_tracker.fees)
+    }
+
     /// Validates and executes a block proposed to extend this chain.
     #[instrument(skip_all, fields(
         chain_id = %self.chain_id(),
@@ -2153,6 +2237,16 @@ where
                     .insert(stream_id, (height, hash));
             }
         }
+        if let Some(owner) = query.request_balance_proof {
+            // Without blocks there is no state hash to prove against: the balance is the one
+            // in the chain description. Unless the policy requires it, the state hash doesn't
+            // commit to the balances either.
+            if chain.tip_state.get().next_block_height > BlockHeight::ZERO
+                && chain.execution_state.has_state_roots()
+            {
+                info.requested_balance_proof = Some(chain.balance_proof(owner).await?);
+            }
+        }
         Ok(ChainInfoResponse::new(info, self.config.key_pair()))
     }
 
@@ -2171,7 +2265,7 @@ where
         published_blobs: &[Blob],
         policy: BundleExecutionPolicy,
     ) -> Result<(Block, ResourceTracker, HashSet<ChainId>), WorkerError> {
-        let (proposed_block, outcome, resource_tracker, never_reject_origins) = Box::pin(
+        let (proposed_block, outcome, resource_tracker, never_reject_origins, logs) = Box::pin(
             self.chain
                 .execute_block(block, local_time, round, published_blobs, None, policy),
         )
@@ -2179,15 +2273,13 @@ where
         let executed_block = Block::new(proposed_block, outcome);
         let block_hash = CryptoHash::new(&executed_block);
         if let Some(cache) = &self.execution_state_cache {
-            cache.insert(
-                &block_hash,
-                Box::pin(
-                    self.chain
-                        .execution_state
-                        .with_context(|ctx| InactiveContext(ctx.base_key().clone())),
-                )
-                .await,
-            );
+            let state = Box::pin(
+                self.chain
+                    .execution_state
+                    .with_context(|ctx| InactiveContext(ctx.base_key().clone())),
+            )
+            .await;
+            cache.insert(&block_hash, (state, logs));
         }
         Ok((executed_block, resource_tracker, never_reject_origins))
     }
@@ -1,6 +1,7 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+mod offline;
 mod state;
 use std::{
     collections::{hash_map, BTreeMap, BTreeSet, HashMap, HashSet},
@@ -48,13 +49,14 @@ use linera_chain::{
 use linera_execution::{
     committee::Committee,
     system::{
-        AdminOperation, OpenChainConfig, SystemOperation, EPOCH_STREAM_NAME,
-        REMOVED_EPOCH_STREAM_NAME,
+        application_upgrade_stream_id, AdminOperation, ApplicationOptions, OpenChainConfig,
+        SponsorshipPolicy, SystemOperation, EPOCH_STREAM_NAME, REMOVED_EPOCH_STREAM_NAME,
     },
     ExecutionError, Operation, Query, QueryOutcome,
 };
 use linera_storage::{Clock as _, Storage as _};
 use linera_views::ViewError;
+pub use offline::{SignedProposal, UnsignedProposal};
 use serde::Serialize;
 pub use state::State;
 use thiserror::Error;
@@ -64,7 +66,7 @@ use tracing::{debug, error, info, instrument, trace, warn, Instrument as _};
 
 use super::{
     received_log::ReceivedLogs, validator_trackers::ValidatorTrackers, AbortOnDrop, Client,
-    ListeningMode, PendingProposal, TimingType,
+    LightChainState, ListeningMode, PendingProposal, TimingType,
 };
 use crate::{
     data_types::{ChainInfo, ChainInfoQuery, ClientOutcome, RoundTimeout},
@@ -118,6 +120,10 @@ pub struct Options {
     /// Whether to allow creating blocks in the fast round. Fast blocks have lower latency but
     /// must be used carefully so that there are never any conflicting fast block proposals.
     pub allow_fast_blocks: bool,
+    /// Whether to propose blocks only in single-leader rounds, where the leader is picked
+    /// according to the owners' weights, and not in the fast or multi-leader rounds where
+    /// several owners can compete. This skips to the first single-leader round if needed.
+    pub single_leader_rounds_only: bool,
     /// Initial probe interval for the notification circuit breaker. When a validator's
     /// notification stream exhausts retries, the circuit breaker waits this long before
     /// probing again. Doubles on each failed probe.
@@ -128,6 +134,15 @@ pub struct Options {
     /// Maximum number of event stream IDs to include in a single `PreviousEventBlocks`
     /// request. Larger sets are split into multiple requests.
     pub max_event_stream_queries: usize,
+    /// How long to wait for more chain summary queries to the same validator before sending
+    /// them in one batch.
+    pub chain_summary_batch_delay: Duration,
+    /// Whether to follow chains other than the admin chain as a light client: certificates are
+    /// checked against the committee of their epoch, but blocks are not executed locally.
+    pub light_client: bool,
+    /// A chain owned by the signer to claim tokens from when the signer's account and the
+    /// chain balance cannot pay for the fees of a block proposal.
+    pub funding_chain_id: Option<ChainId>,
 }
 
 struct CircuitBreakerState {
@@ -159,9 +174,13 @@ impl Options {
             sender_certificate_download_batch_size: DEFAULT_SENDER_CERTIFICATE_DOWNLOAD_BATCH_SIZE,
             max_joined_tasks: 100,
             allow_fast_blocks: false,
+            single_leader_rounds_only: false,
             notification_circuit_breaker_initial_probe_interval: Duration::from_secs(300),
             notification_circuit_breaker_max_probe_interval: Duration::from_secs(3600),
             max_event_stream_queries: DEFAULT_MAX_EVENT_STREAM_QUERIES,
+            chain_summary_batch_delay: Duration::ZERO,
+            light_client: false,
+            funding_chain_id: None,
         }
     }
 }
@@ -320,6 +339,18 @@ pub enum Error {
     #[error("Epoch is already revoked")]
     EpochAlreadyRevoked,
 
+    #[error("No validator reported a verifiable tip for chain {0}")]
+    CannotVerifyChainTip(ChainId),
+
+    #[error("Chain {0} is followed as a light client and its blocks are not executed locally")]
+    LightChain(ChainId),
+
+    #[error("The balance of {owner} on chain {chain_id} has not been verified yet")]
+    UnverifiedLightBalance {
+        chain_id: ChainId,
+        owner: AccountOwner,
+    },
+
     #[error("Failed to download missing sender blocks from chain {chain_id} at height {height}")]
     CannotDownloadMissingSenderBlock {
         chain_id: ChainId,
@@ -337,6 +368,23 @@ pub enum Error {
          different outcomes for the same block"
     )]
     ExecutionOutcomeMismatch,
+
+    #[error("Invalid signed block proposal: {0}")]
+    InvalidSignedProposal(String),
+
+    #[error("The chain state changed since the proposal was prepared: {0}")]
+    OutdatedProposal(&'static str),
+
+    #[error(
+        "Insufficient funding to pay for the fees of a block on chain {chain_id}: \
+         {required} required, but only {available} available"
+    )]
+    InsufficientFunding {
+        chain_id: ChainId,
+        required: Amount,
+        available: Amount,
+        context: ChainExecutionContext,
+    },
 }
 
 impl From<Infallible> for Error {
@@ -378,6 +426,20 @@ impl<Env: Environment> ChainClient<Env> {
         self.client.is_chain_follow_only(self.chain_id)
     }
 
+    /// Returns whether this chain is followed as a light client, i.e. without executing its
+    /// blocks.
+    pub fn is_light(&self) -> bool {
+        self.client.is_light_chain(self.chain_id)
+    }
+
+    /// Returns the last verified chain info, if this chain is followed as a light client.
+    pub fn light_chain_info(&self) -> Option<Box<ChainInfo>> {
+        let state = self.client.light_chain_state(self.chain_id)?;
+        Some(Box::new(
+            state.chain_info(self.chain_id, self.preferred_owner),
+        ))
+    }
+
     /// Returns the proposal mutex for this chain.
     ///
     /// The mutex serializes block proposals and holds the pending proposal (if any).
@@ -586,6 +648,20 @@ impl<Env: Environment> ChainClient<Env> {
         Ok(bundles)
     }
 
+    /// Returns the number of incoming messages that the next block would receive, according to
+    /// the message policy.
+    ///
+    /// Like the block, this only counts up to `self.options.max_pending_message_bundles` bundles.
+    #[instrument(level = "trace")]
+    pub async fn pending_message_count(&self) -> Result<usize, Error> {
+        Ok(self
+            .pending_message_bundles()
+            .await?
+            .iter()
+            .map(|bundle| bundle.bundle.messages.len())
+            .sum())
+    }
+
     #[instrument(level = "trace")]
     async fn collect_stream_updates(&self) -> Result<Vec<Operation>, Error> {
         let subscription_map = self
@@ -625,14 +701,25 @@ impl<Env: Environment> ChainClient<Env> {
                         .applications
                         .into_iter()
                         .filter(|(_, app_index)| *app_index < next_index)
-                        .map(|(application_id, _)| {
-                            SystemOperation::UpdateStream {
-                                application_id,
-                                chain_id,
-                                stream_id: stream_id.clone(),
-                                next_index,
-                            }
-                            .into()
+                        .flat_map(|(application_id, app_index)| {
+                            // Application upgrades are adopted one at a time, so that each of
+                            // them migrates the application state.
+                            let next_indices =
+                                if stream_id == application_upgrade_stream_id(application_id) {
+                                    (app_index + 1)..=next_index
+                                } else {
+                                    next_index..=next_index
+                                };
+                            let stream_id = stream_id.clone();
+                            next_indices.map(move |next_index| {
+                                SystemOperation::UpdateStream {
+                                    application_id,
+                                    chain_id,
+                                    stream_id: stream_id.clone(),
+                                    next_index,
+                                }
+                                .into()
+                            })
                         })
                         .collect::<Vec<Operation>>())
                 }
@@ -711,6 +798,23 @@ impl<Env: Environment> ChainClient<Env> {
     /// Returns an error if we don't have the private key for the identity.
     #[instrument(level = "trace")]
     pub async fn identity(&self) -> Result<AccountOwner, Error> {
+        let preferred_owner = self.owner_identity().await?;
+        let has_signer = self.has_key_for(&preferred_owner).await?;
+
+        if !has_signer {
+            warn!(%self.chain_id, ?preferred_owner,
+                "Chain is one of the owners but its Signer instance doesn't contain the key",
+            );
+            return Err(Error::CannotFindKeyForChain(self.chain_id));
+        }
+
+        Ok(preferred_owner)
+    }
+
+    /// Obtains the identity of the current owner of the chain, without requiring the
+    /// private key, e.g. if proposals are signed on another machine.
+    #[instrument(level = "trace")]
+    async fn owner_identity(&self) -> Result<AccountOwner, Error> {
         let Some(preferred_owner) = self.preferred_owner else {
             return Err(Error::NoAccountKeyConfigured(self.chain_id));
         };
@@ -745,15 +849,6 @@ impl<Env: Environment> ChainClient<Env> {
             return Err(Error::NotAnOwner(self.chain_id));
         }
 
-        let has_signer = self.has_key_for(&preferred_owner).await?;
-
-        if !has_signer {
-            warn!(%self.chain_id, ?preferred_owner,
-                "Chain is one of the owners but its Signer instance doesn't contain the key",
-            );
-            return Err(Error::CannotFindKeyForChain(self.chain_id));
-        }
-
         Ok(preferred_owner)
     }
 
@@ -795,6 +890,7 @@ impl<Env: Environment> ChainClient<Env> {
     pub async fn prepare_chain(&self) -> Result<Box<ChainInfo>, Error> {
         #[cfg(with_metrics)]
         let _latency = super::metrics::PREPARE_CHAIN_LATENCY.measure_latency();
+        ensure!(!self.is_light(), Error::LightChain(self.chain_id));
 
         let mut info = self.synchronize_to_known_height().await?;
 
@@ -1217,7 +1313,6 @@ impl<Env: Environment> ChainClient<Env> {
         amount: Amount,
         recipient: Account,
     ) -> Result<ClientOutcome<ConfirmedBlockCertificate>, Error> {
-        // TODO(#467): check the balance of `owner` before signing any block proposal.
         self.execute_operation(SystemOperation::Transfer {
             owner,
             recipient,
@@ -1321,7 +1416,9 @@ impl<Env: Environment> ChainClient<Env> {
         operations: Vec<Operation>,
         blobs: Vec<Blob>,
     ) -> Result<ClientOutcome<ConfirmedBlockCertificate>, Error> {
+        ensure!(!self.is_light(), Error::LightChain(self.chain_id));
         let timing_start = linera_base::time::Instant::now();
+        let mut funding_claimed = false;
 
         let result = loop {
             let execute_block_start = linera_base::time::Instant::now();
@@ -1353,6 +1450,23 @@ impl<Env: Environment> ChainClient<Env> {
                     );
                     self.synchronize_chain_state(self.chain_id).await?;
                 }
+                Err(Error::InsufficientFunding {
+                    chain_id,
+                    required,
+                    available,
+                    context,
+                }) if !funding_claimed => {
+                    funding_claimed = true;
+                    let amount = required.try_sub(available)?;
+                    if !Box::pin(self.claim_funding(amount)).await? {
+                        return Err(Error::InsufficientFunding {
+                            chain_id,
+                            required,
+                            available,
+                            context,
+                        });
+                    }
+                }
                 Err(err) => return Err(err),
             };
         };
@@ -1380,6 +1494,22 @@ impl<Env: Environment> ChainClient<Env> {
         operations: Vec<Operation>,
         blobs: Vec<Blob>,
     ) -> Result<ClientOutcome<ConfirmedBlockCertificate>, Error> {
+        Ok(self
+            .execute_block_with_fees(operations, blobs)
+            .await?
+            .map(|(certificate, _)| certificate))
+    }
+
+    /// Executes a new block and returns its certificate together with the fees charged for
+    /// it, as computed when staging the block.
+    ///
+    /// This must be preceded by a call to `prepare_chain()`.
+    #[instrument(level = "trace", skip(operations, blobs))]
+    async fn execute_block_with_fees(
+        &self,
+        operations: Vec<Operation>,
+        blobs: Vec<Blob>,
+    ) -> Result<ClientOutcome<(ConfirmedBlockCertificate, Amount)>, Error> {
         #[cfg(with_metrics)]
         let _latency = super::metrics::EXECUTE_BLOCK_LATENCY.measure_latency();
 
@@ -1423,7 +1553,7 @@ impl<Env: Environment> ChainClient<Env> {
             )));
         }
 
-        let block = self
+        let (block, fees) = self
             .new_pending_block(transactions, blobs, &mut proposal_guard)
             .await?;
 
@@ -1432,7 +1562,7 @@ impl<Env: Environment> ChainClient<Env> {
             .await?
         {
             ClientOutcome::Committed(Some(certificate)) if certificate.block() == &block => {
-                Ok(ClientOutcome::Committed(certificate))
+                Ok(ClientOutcome::Committed((certificate, fees)))
             }
             ClientOutcome::Committed(Some(certificate)) => {
                 Ok(ClientOutcome::Conflict(Box::new(certificate)))
@@ -1477,7 +1607,8 @@ impl<Env: Environment> ChainClient<Env> {
             .collect::<Vec<_>>())
     }
 
-    /// Creates a new pending block and stores it in `proposal_guard`.
+    /// Creates a new pending block and stores it in `proposal_guard`. Returns the block and
+    /// the fees charged for it.
     ///
     /// The caller must hold the proposal mutex. The pending proposal is written directly
     /// into the guard so that it is always synchronized with the mutex.
@@ -1487,7 +1618,7 @@ impl<Env: Environment> ChainClient<Env> {
         transactions: Vec<Transaction>,
         blobs: Vec<Blob>,
         proposal_guard: &mut Option<PendingProposal>,
-    ) -> Result<Block, Error> {
+    ) -> Result<(Block, Amount), Error> {
         let identity = self.identity().await?;
 
         ensure!(
@@ -1511,16 +1642,26 @@ impl<Env: Environment> ChainClient<Env> {
 
         let round = self.round_for_oracle(&info, &identity).await?;
         // Make sure every incoming message succeeds and otherwise remove them.
-        // Also, compute the final certified hash while we're at it.
-        let (block, _, never_reject_origins) = self
+        // Also, compute the final certified hash while we're at it. This charges the fees
+        // against the signer's account and the chain balance, so we find out before signing
+        // whether validators would reject the proposal.
+        let (block, _, resource_tracker, never_reject_origins) = match self
             .client
             .stage_block_execution(
-                proposed_block,
+                proposed_block.clone(),
                 round,
                 blobs.clone(),
                 self.options.bundle_execution_policy(),
             )
-            .await?;
+            .await
+        {
+            Ok(staged) => staged,
+            Err(error) => {
+                return Err(self
+                    .insufficient_funding_error(error, proposed_block, round, blobs)
+                    .await)
+            }
+        };
         // Record origins whose bundles were discarded due to the never-reject policy so
         // that `process_inbox` stops retrying them until the client is restarted.
         if !never_reject_origins.is_empty() {
@@ -1536,7 +1677,102 @@ impl<Env: Environment> ChainClient<Env> {
             auto_retry_outcome: Some(auto_retry_outcome),
             round: None,
         });
-        Ok(block)
+        Ok((block, resource_tracker.fees))
+    }
+
+    /// Turns an error about fees exceeding the funding of a block into
+    /// [`Error::InsufficientFunding`], with the block's total fees and the amounts available
+    /// in the signer's account and the chain balance. Other errors are returned unchanged.
+    ///
+    /// The total fees are found by executing the block again with a raised chain balance,
+    /// since the error only reports the first fee that couldn't be paid.
+    async fn insufficient_funding_error(
+        &self,
+        error: Error,
+        block: ProposedBlock,
+        round: Option<u32>,
+        blobs: Vec<Blob>,
+    ) -> Error {
+        let Error::LocalNodeError(LocalNodeError::WorkerError(WorkerError::ChainError(
+            chain_error,
+        ))) = &error
+        else {
+            return error;
+        };
+        let ChainError::ExecutionError(execution_error, context) = &**chain_error else {
+            return error;
+        };
+        let ExecutionError::FeesExceedFunding { fees, balance } = **execution_error else {
+            return error;
+        };
+        let context = *context;
+        let Some(owner) = block.authenticated_owner else {
+            return error;
+        };
+        let Ok((chain_balance, owner_balance)) = self.local_balances_with_owner(owner).await else {
+            return error;
+        };
+        let available = chain_balance.saturating_add(owner_balance.unwrap_or_default());
+        let policy = self.options.bundle_execution_policy();
+        let total_fees = match self
+            .client
+            .local_node
+            .block_fees(block, round, blobs, policy)
+            .await
+        {
+            Ok(fees) => fees,
+            Err(error) => {
+                debug!(%error, "Failed to work out the total fees of the block");
+                Amount::ZERO
+            }
+        };
+        Error::InsufficientFunding {
+            chain_id: self.chain_id,
+            required: total_fees.max(available.saturating_add(fees.saturating_sub(balance))),
+            available,
+            context,
+        }
+    }
+
+    /// Transfers `amount` from the signer's account on the configured funding chain to the
+    /// signer's account on this chain, and delivers the transfer to the local inbox.
+    ///
+    /// Returns whether the transfer was committed.
+    async fn claim_funding(&self, amount: Amount) -> Result<bool, Error> {
+        let Some(funding_chain_id) = self
+            .options
+            .funding_chain_id
+            .filter(|chain_id| *chain_id != self.chain_id)
+        else {
+            return Ok(false);
+        };
+        let owner = self.identity().await?;
+        info!(
+            chain_id = %self.chain_id,
+            %funding_chain_id,
+            %amount,
+            "Insufficient funding; claiming tokens from the funding chain"
+        );
+        let funding_client = self.client.create_chain_client(
+            funding_chain_id,
+            None,
+            BlockHeight::ZERO,
+            &None,
+            Some(owner),
+            self.timing_sender.clone(),
+            false,
+        );
+        funding_client.prepare_chain().await?;
+        let outcome = funding_client
+            .transfer(owner, amount, Account::new(self.chain_id, owner))
+            .await?;
+        if !matches!(outcome, ClientOutcome::Committed(_)) {
+            return Ok(false);
+        }
+        self.client
+            .retry_pending_cross_chain_requests(funding_chain_id)
+            .await?;
+        Ok(true)
     }
 
     /// Returns a suitable timestamp for the next block.
@@ -1636,6 +1872,9 @@ impl<Env: Environment> ChainClient<Env> {
     /// Does not attempt to synchronize with validators. The result will reflect up to
     /// `max_pending_message_bundles` incoming message bundles and the execution fees for a single
     /// block.
+    ///
+    /// For chains followed as a light client, the balance after the latest block is obtained
+    /// from validators instead, and verified against the block's state hash.
     #[instrument(level = "trace")]
     pub async fn query_balance(&self) -> Result<Amount, Error> {
         let (balance, _) = self.query_balances_with_owner(AccountOwner::CHAIN).await?;
@@ -1672,6 +1911,17 @@ impl<Env: Environment> ChainClient<Env> {
         &self,
         owner: AccountOwner,
     ) -> Result<(Amount, Option<Amount>), Error> {
+        if self.is_light() {
+            let owners = Some(owner).filter(|owner| !owner.is_chain());
+            let state = self
+                .client
+                .synchronize_light_chain_state(self.chain_id, owners.as_slice())
+                .await?;
+            return Ok((
+                state.chain_balance,
+                state.owner_balances.get(&owner).copied(),
+            ));
+        }
         let incoming_bundles = self.pending_message_bundles().await?;
         // Since we disallow empty blocks, and there is no incoming messages,
         // that could change it, we query for the balance immediately.
@@ -1709,7 +1959,7 @@ impl<Env: Environment> ChainClient<Env> {
             )
             .await
         {
-            Ok((_, response, _)) => Ok((
+            Ok((_, response, _, _)) => Ok((
                 response.info.chain_balance,
                 response.info.requested_owner_balance,
             )),
@@ -1766,6 +2016,20 @@ impl<Env: Environment> ChainClient<Env> {
         &self,
         owner: AccountOwner,
     ) -> Result<(Amount, Option<Amount>), Error> {
+        if self.is_light() {
+            let state = self.client.light_chain_state(self.chain_id);
+            let chain_id = self.chain_id;
+            let state = state.ok_or(Error::UnverifiedLightBalance {
+                chain_id,
+                owner: AccountOwner::CHAIN,
+            })?;
+            if owner.is_chain() {
+                return Ok((state.chain_balance, Some(state.chain_balance)));
+            }
+            let balance = state.owner_balances.get(&owner).copied();
+            let balance = balance.ok_or(Error::UnverifiedLightBalance { chain_id, owner })?;
+            return Ok((state.chain_balance, Some(balance)));
+        }
         ensure!(
             self.chain_info().await?.next_block_height >= self.initial_next_block_height,
             Error::WalletSynchronizationError
@@ -1862,7 +2126,23 @@ impl<Env: Environment> ChainClient<Env> {
             .map_err(Into::into)
     }
 
+    /// Verifies the latest block of this chain as a light client, without executing it, and
+    /// updates the balances of the chain account and of the preferred owner.
+    #[instrument(level = "trace")]
+    pub async fn synchronize_light_state(&self) -> Result<LightChainState, Error> {
+        let owners = self.preferred_owner.into_iter().collect::<Vec<_>>();
+        self.client
+            .synchronize_light_chain_state(self.chain_id, &owners)
+            .await
+    }
+
     pub async fn synchronize_from_validators(&self) -> Result<Box<ChainInfo>, Error> {
+        if self.is_light() {
+            let state = self.synchronize_light_state().await?;
+            return Ok(Box::new(
+                state.chain_info(self.chain_id, self.preferred_owner),
+            ));
+        }
         if self.is_follow_only() {
             return self.client.synchronize_chain_state(self.chain_id).await;
         }
@@ -1936,7 +2216,7 @@ impl<Env: Environment> ChainClient<Env> {
                         .get_locking_blobs(&blob_ids, self.chain_id)
                         .await?
                         .ok_or_else(|| Error::InternalError("Missing local locking blobs"))?;
-                    let (block, _, _) = self
+                    let (block, _, _, _) = self
                         .client
                         .stage_block_execution(
                             proposed_block,
@@ -1955,7 +2235,7 @@ impl<Env: Environment> ChainClient<Env> {
             let blobs = pending.blobs.clone();
             let staging_outcome = pending.auto_retry_outcome.as_ref();
             let round = self.round_for_oracle(&info, &owner).await?;
-            let (block, _, _) = self
+            let (block, _, _, _) = self
                 .client
                 .stage_block_execution(
                     proposed_block,
@@ -2017,6 +2297,34 @@ impl<Env: Environment> ChainClient<Env> {
                     .map_err(Error::signer_failure)?,
             )
         };
+        let block = Block::new(proposed_block, outcome);
+        let certificate = self
+            .submit_signed_block_proposal(proposal, block, blobs, already_handled_locally)
+            .await?;
+        tracing::debug!(
+            total_process_ms = process_start.elapsed().as_millis(),
+            "process_pending_block_without_prepare completing"
+        );
+        // Clear the pending proposal now that the block has been committed.
+        *proposal_guard = None;
+        Ok(ClientOutcome::Committed(Some(Arc::unwrap_or_clone(
+            certificate,
+        ))))
+    }
+
+    /// Checks a signed block proposal against the local node, sends it to the validators
+    /// and finalizes the resulting certificate.
+    ///
+    /// The `block` must be the result of executing the proposed block.
+    async fn submit_signed_block_proposal(
+        &self,
+        proposal: Box<BlockProposal>,
+        block: Block,
+        blobs: Vec<Blob>,
+        already_handled_locally: bool,
+    ) -> Result<Arc<ConfirmedBlockCertificate>, Error> {
+        let local_node = &self.client.local_node;
+        let round = proposal.content.round;
         if !already_handled_locally {
             // Check the final block proposal. This will be cheaper after #1401.
             if let Err(err) = local_node.handle_block_proposal(*proposal.clone()).await {
@@ -2032,7 +2340,6 @@ impl<Env: Environment> ChainClient<Env> {
             }
         }
         let committee = self.local_committee().await?;
-        let block = Block::new(proposed_block, outcome);
         // Send the query to validators.
         let submit_block_proposal_start = linera_base::time::Instant::now();
         let certificate = if round.is_fast() {
@@ -2049,19 +2356,11 @@ impl<Env: Environment> ChainClient<Env> {
             self.client.finalize_block(&committee, certificate).await?
         };
         self.send_timing(submit_block_proposal_start, TimingType::SubmitBlockProposal);
-        tracing::debug!(
-            total_process_ms = process_start.elapsed().as_millis(),
-            "process_pending_block_without_prepare completing"
-        );
         debug!(round = %certificate.round, "Sending confirmed block to validators");
         let certificate = self.client.storage_client().cache_certificate(certificate);
         self.update_validators(Some(&committee), Some(certificate.clone()))
             .await?;
-        // Clear the pending proposal now that the block has been committed.
-        *proposal_guard = None;
-        Ok(ClientOutcome::Committed(Some(Arc::unwrap_or_clone(
-            certificate,
-        ))))
+        Ok(certificate)
     }
 
     fn send_timing(&self, start: Instant, timing_type: TimingType) {
@@ -2177,6 +2476,11 @@ impl<Env: Environment> ChainClient<Env> {
                 "Conflicting proposal in the current round",
             ));
         };
+        let round = if self.options.single_leader_rounds_only {
+            round.max(Round::SingleLeader(0))
+        } else {
+            round
+        };
         let current_committee = self
             .local_committee()
             .await?
@@ -2470,12 +2774,17 @@ impl<Env: Environment> ChainClient<Env> {
                 parameters,
                 instantiation_argument,
                 required_application_ids,
+                ApplicationOptions::default(),
             )
             .await?
             .map(|(app_id, cert)| (app_id.with_abi(), cert)))
     }
 
     /// Creates an application by instantiating some bytecode.
+    ///
+    /// If the `options` have an `upgrade_authority`, that owner can later replace the
+    /// application's module on this chain using [`Self::upgrade_application`]. Every chain
+    /// that uses the application, including this one, adopts the upgrade in its next block.
     #[instrument(
         level = "trace",
         skip(
@@ -2492,33 +2801,81 @@ impl<Env: Environment> ChainClient<Env> {
         parameters: Vec<u8>,
         instantiation_argument: Vec<u8>,
         required_application_ids: Vec<ApplicationId>,
+        options: ApplicationOptions,
     ) -> Result<ClientOutcome<(ApplicationId, ConfirmedBlockCertificate)>, Error> {
-        self.execute_operation(SystemOperation::CreateApplication {
+        let operation = if options == ApplicationOptions::default() {
+            SystemOperation::CreateApplication {
+                module_id,
+                parameters,
+                instantiation_argument,
+                required_application_ids,
+            }
+        } else {
+            SystemOperation::CreateApplicationWithOptions {
+                module_id,
+                parameters,
+                instantiation_argument,
+                required_application_ids,
+                options,
+            }
+        };
+        self.execute_operation(operation)
+            .await?
+            .try_map(|certificate| {
+                // The first message of the only operation created the application.
+                let mut creation: Vec<_> = certificate
+                    .block()
+                    .created_blob_ids()
+                    .into_iter()
+                    .filter(|blob_id| blob_id.blob_type == BlobType::ApplicationDescription)
+                    .collect();
+                if creation.len() > 1 {
+                    return Err(Error::InternalError(
+                        "Unexpected number of application descriptions published",
+                    ));
+                }
+                let blob_id = creation.pop().ok_or(Error::InternalError(
+                    "ApplicationDescription blob not found.",
+                ))?;
+                let id = ApplicationId::new(blob_id.hash);
+                Ok((id, certificate))
+            })
+    }
+
+    /// Publishes a new module for an upgradable application. This chain must be the
+    /// application's creator chain. Every chain that uses the application, including this
+    /// one, adopts the upgrade and runs the new contract's migration entrypoint in its next
+    /// block.
+    #[instrument(level = "trace")]
+    pub async fn upgrade_application(
+        &self,
+        application_id: ApplicationId,
+        module_id: ModuleId,
+    ) -> Result<ClientOutcome<ConfirmedBlockCertificate>, Error> {
+        self.execute_operation(SystemOperation::UpgradeApplication {
+            application_id,
             module_id,
-            parameters,
-            instantiation_argument,
-            required_application_ids,
         })
-        .await?
-        .try_map(|certificate| {
-            // The first message of the only operation created the application.
-            let mut creation: Vec<_> = certificate
-                .block()
-                .created_blob_ids()
-                .into_iter()
-                .filter(|blob_id| blob_id.blob_type == BlobType::ApplicationDescription)
-                .collect();
-            if creation.len() > 1 {
-                return Err(Error::InternalError(
-                    "Unexpected number of application descriptions published",
-                ));
-            }
-            let blob_id = creation.pop().ok_or(Error::InternalError(
-                "ApplicationDescription blob not found.",
-            ))?;
-            let id = ApplicationId::new(blob_id.hash);
-            Ok((id, certificate))
+        .await
+    }
+
+    /// Lets the `owner`'s account pay the fees of `beneficiary`'s blocks on this chain, up
+    /// to `allowance` in total, if the `policy` covers all their operations.
+    #[instrument(level = "trace")]
+    pub async fn sponsor(
+        &self,
+        owner: AccountOwner,
+        beneficiary: AccountOwner,
+        allowance: Amount,
+        policy: SponsorshipPolicy,
+    ) -> Result<ClientOutcome<ConfirmedBlockCertificate>, Error> {
+        self.execute_operation(SystemOperation::Sponsor {
+            owner,
+            beneficiary,
+            allowance,
+            policy,
         })
+        .await
     }
 
     /// Creates a new committee and starts using it (admin chains only).
@@ -2575,29 +2932,46 @@ impl<Env: Environment> ChainClient<Env> {
     ) -> Result<(Vec<ConfirmedBlockCertificate>, Option<RoundTimeout>), Error> {
         #[cfg(with_metrics)]
         let _latency = super::metrics::PROCESS_INBOX_WITHOUT_PREPARE_LATENCY.measure_latency();
+        ensure!(!self.is_light(), Error::LightChain(self.chain_id));
 
         let mut certificates = Vec::new();
         loop {
-            // We provide no operations - this means that the only operations executed
-            // will be epoch changes, receiving messages and processing event stream
-            // updates, if any are pending.
-            match self.execute_block(vec![], vec![]).await {
-                Ok(ClientOutcome::Committed(certificate)) => certificates.push(certificate),
-                Ok(ClientOutcome::Conflict(certificate)) => certificates.push(*certificate),
-                Ok(ClientOutcome::WaitForTimeout(timeout)) => {
+            match self.process_inbox_block().await? {
+                ClientOutcome::Committed(Some((certificate, _))) => certificates.push(certificate),
+                ClientOutcome::Conflict(certificate) => certificates.push(*certificate),
+                ClientOutcome::WaitForTimeout(timeout) => {
                     return Ok((certificates, Some(timeout)));
                 }
-                // Nothing in the inbox and no stream updates to be processed.
-                Err(Error::LocalNodeError(LocalNodeError::WorkerError(
-                    WorkerError::ChainError(chain_error),
-                ))) if matches!(*chain_error, ChainError::EmptyBlock) => {
-                    return Ok((certificates, None));
-                }
-                Err(error) => return Err(error),
+                ClientOutcome::Committed(None) => return Ok((certificates, None)),
             };
         }
     }
 
+    /// Creates a single block without any operations to process incoming messages. Returns
+    /// its certificate together with the fees charged for it, or `None` if there was nothing
+    /// to process.
+    ///
+    /// This must be preceded by a call to `prepare_chain()`.
+    #[instrument(level = "trace")]
+    pub async fn process_inbox_block(
+        &self,
+    ) -> Result<ClientOutcome<Option<(ConfirmedBlockCertificate, Amount)>>, Error> {
+        ensure!(!self.is_light(), Error::LightChain(self.chain_id));
+        // We provide no operations - this means that the only operations executed
+        // will be epoch changes, receiving messages and processing event stream
+        // updates, if any are pending.
+        match self.execute_block_with_fees(vec![], vec![]).await {
+            Ok(outcome) => Ok(outcome.map(Some)),
+            // Nothing in the inbox and no stream updates to be processed.
+            Err(Error::LocalNodeError(LocalNodeError::WorkerError(WorkerError::ChainError(
+                chain_error,
+            )))) if matches!(*chain_error, ChainError::EmptyBlock) => {
+                Ok(ClientOutcome::Committed(None))
+            }
+            Err(error) => Err(error),
+        }
+    }
+
     /// Returns operations to process all pending epoch changes: first the new epochs, in order,
     /// then the removed epochs, in order.
     async fn collect_epoch_changes(&self) -> Result<Vec<Operation>, Error> {
@@ -2734,6 +3108,18 @@ impl<Env: Environment> ChainClient<Env> {
             .ok_or(Error::ReadCertificatesError(vec![hash]))
     }
 
+    /// Returns the certificates of this chain at the given heights. The ones that are missing
+    /// from local storage are downloaded from the validators.
+    #[instrument(level = "trace", skip(heights))]
+    pub async fn read_certificates_by_heights(
+        &self,
+        heights: &[BlockHeight],
+    ) -> Result<Vec<Arc<ConfirmedBlockCertificate>>, Error> {
+        self.client
+            .read_or_download_certificates(self.chain_id, heights)
+            .await
+    }
+
     /// Handles any cross-chain requests for any pending outgoing messages.
     #[instrument(level = "trace")]
     pub async fn retry_pending_outgoing_messages(&self) -> Result<(), Error> {
@@ -2799,6 +3185,21 @@ impl<Env: Environment> ChainClient<Env> {
             );
             return Ok(());
         }
+        if self.client.is_light_chain(notification.chain_id) {
+            // Light clients don't receive messages or execute blocks; they only verify the
+            // latest block of the chains they follow.
+            if let Reason::NewBlock { .. } = notification.reason {
+                let owners = self
+                    .preferred_owner
+                    .filter(|_| notification.chain_id == self.chain_id)
+                    .into_iter()
+                    .collect::<Vec<_>>();
+                self.client
+                    .synchronize_light_chain_state(notification.chain_id, &owners)
+                    .await?;
+            }
+            return Ok(());
+        }
         match notification.reason {
             Reason::NewIncomingBundle { origin, height } => {
                 if self.options.message_policy.ignores_origin(&origin) {
@@ -3124,7 +3525,7 @@ impl<Env: Environment> ChainClient<Env> {
                         .is_some_and(|mode| mode.should_sync_chain_state())
                     {
                         this.client
-                            .synchronize_chain_state_from(&remote_node, this.chain_id)
+                            .synchronize_chain_state_from_if_behind(&remote_node, this.chain_id)
                             .await?;
                     } else {
                         // For EventsOnly chains, do a lightweight initial sync:
@@ -0,0 +1,278 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Block proposals that are signed on another, possibly air-gapped, machine.
+//!
+//! An online client prepares an [`UnsignedProposal`]: it executes the block locally and
+//! records the hash of the resulting block. A host that only holds the owner's key signs it,
+//! and an online client submits the resulting [`SignedProposal`] after checking that the
+//! chain did not move in the meantime.
+
+use std::sync::Arc;
+
+use futures::future::Either;
+use linera_base::{
+    crypto::{CryptoHash, Signer},
+    data_types::{Blob, BlockHeight, Round},
+    ensure,
+    identifiers::{AccountOwner, ChainId},
+};
+use linera_chain::{
+    data_types::{BlockProposal, BundleExecutionPolicy, ProposalContent, ProposedBlock},
+    types::ConfirmedBlockCertificate,
+    ChainError,
+};
+use linera_execution::Operation;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+
+use super::{ChainClient, Error};
+use crate::{environment::Environment, local_node::LocalNodeError, worker::WorkerError};
+
+/// A block proposal that was prepared by an online client but not signed yet.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UnsignedProposal {
+    /// The owner who is expected to sign the proposal.
+    pub owner: AccountOwner,
+    /// The data to sign: the proposed block, with its operations and incoming bundles, and
+    /// the consensus round.
+    pub content: ProposalContent,
+    /// The hash of the block, including its execution outcome, that the proposal produced
+    /// when it was prepared.
+    pub expected_block_hash: CryptoHash,
+    /// The blobs published by the block.
+    pub blobs: Vec<Blob>,
+}
+
+/// A block proposal that was signed by the chain owner and can be submitted by an online
+/// client.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignedProposal {
+    /// The signed proposal.
+    pub proposal: BlockProposal,
+    /// The hash of the block that the proposal produced when it was prepared.
+    pub expected_block_hash: CryptoHash,
+    /// The blobs published by the block.
+    pub blobs: Vec<Blob>,
+}
+
+impl UnsignedProposal {
+    /// Returns the chain the block is proposed on.
+    pub fn chain_id(&self) -> ChainId {
+        self.content.block.chain_id
+    }
+
+    /// Returns the height of the proposed block.
+    pub fn height(&self) -> BlockHeight {
+        self.content.block.height
+    }
+
+    /// Returns the round the block is proposed in.
+    pub fn round(&self) -> Round {
+        self.content.round
+    }
+
+    /// Signs the proposal with the owner's key. This does not require any network access.
+    pub async fn sign<S: Signer + ?Sized>(self, signer: &S) -> Result<SignedProposal, S::Error> {
+        let proposal =
+            BlockProposal::new_initial(self.owner, self.content.round, self.content.block, signer)
+                .await?;
+        Ok(SignedProposal {
+            proposal,
+            expected_block_hash: self.expected_block_hash,
+            blobs: self.blobs,
+        })
+    }
+}
+
+impl<Env: Environment> ChainClient<Env> {
+    /// Prepares a block with the given operations, to be signed without access to the
+    /// network.
+    ///
+    /// As with [`ChainClient::execute_operations`], the block also receives the pending
+    /// message bundles. Nothing is stored locally: if another block is committed before the
+    /// signed proposal is submitted, the proposal has to be prepared again.
+    ///
+    /// This must be preceded by a call to `prepare_chain()`.
+    #[instrument(level = "trace", skip(operations, blobs))]
+    pub async fn prepare_unsigned_proposal(
+        &self,
+        operations: Vec<Operation>,
+        blobs: Vec<Blob>,
+    ) -> Result<UnsignedProposal, Error> {
+        let mutex = self.proposal_mutex();
+        let proposal_guard = mutex.lock_owned().await;
+        ensure!(
+            proposal_guard.is_none(),
+            Error::BlockProposalError(
+                "Client state already has a pending block; \
+                use the `linera retry-pending-block` command to commit that first"
+            )
+        );
+        let owner = self.owner_identity().await?;
+        let info = self.request_leader_timeout_if_needed().await?;
+        ensure!(
+            info.manager.requested_locking.is_none(),
+            Error::BlockProposalError("Another block is locked and has to be committed first")
+        );
+
+        let transactions = self.prepend_epochs_messages_and_events(operations).await?;
+        if transactions.is_empty() {
+            return Err(Error::LocalNodeError(LocalNodeError::WorkerError(
+                WorkerError::ChainError(Box::new(ChainError::EmptyBlock)),
+            )));
+        }
+        let timestamp = self.next_timestamp(&transactions, info.timestamp);
+        let proposed_block = ProposedBlock {
+            epoch: info.epoch,
+            chain_id: self.chain_id,
+            transactions,
+            previous_block_hash: info.block_hash,
+            height: info.next_block_height,
+            authenticated_owner: Some(owner),
+            timestamp,
+        };
+
+        // Remove the incoming messages that fail, as for a block proposed by this client.
+        let oracle_round = self.round_for_oracle(&info, &owner).await?;
+        let (block, _, _, _) = self
+            .client
+            .stage_block_execution(
+                proposed_block,
+                oracle_round,
+                blobs.clone(),
+                self.options.bundle_execution_policy(),
+            )
+            .await?;
+        let round = match self
+            .round_for_new_proposal(&info, &owner, block.has_oracle_responses())
+            .await?
+        {
+            Either::Left(round) => round,
+            Either::Right(_) => {
+                return Err(Error::BlockProposalError(
+                    "Not a leader in the current round; try again after it times out",
+                ))
+            }
+        };
+        // Execute the block the way the validators will, to obtain the expected outcome.
+        let (proposed_block, _) = block.into_proposal();
+        let (block, _, _, _) = self
+            .client
+            .stage_block_execution(
+                proposed_block,
+                round.multi_leader(),
+                blobs.clone(),
+                BundleExecutionPolicy::committed(),
+            )
+            .await?;
+        let expected_block_hash = CryptoHash::new(&block);
+        let (proposed_block, _) = block.into_proposal();
+        debug!(%expected_block_hash, "Prepared an unsigned proposal for round {round}");
+        Ok(UnsignedProposal {
+            owner,
+            content: ProposalContent {
+                block: proposed_block,
+                round,
+                outcome: None,
+            },
+            expected_block_hash,
+            blobs,
+        })
+    }
+
+    /// Submits a block proposal that was signed on another machine.
+    ///
+    /// The proposal is only sent to the validators if the chain is still at the height and
+    /// in the round that the proposal was prepared for, and if executing the block again
+    /// produces the expected outcome.
+    ///
+    /// This must be preceded by a call to `prepare_chain()`.
+    #[instrument(level = "trace", skip(signed))]
+    pub async fn submit_signed_proposal(
+        &self,
+        signed: SignedProposal,
+    ) -> Result<ConfirmedBlockCertificate, Error> {
+        let SignedProposal {
+            proposal,
+            expected_block_hash,
+            blobs,
+        } = signed;
+        proposal
+            .check_signature()
+            .map_err(|error| Error::InvalidSignedProposal(error.to_string()))?;
+        let owner = proposal.owner();
+        let ProposalContent { block, round, .. } = &proposal.content;
+        ensure!(
+            block.chain_id == self.chain_id,
+            Error::InvalidSignedProposal(format!(
+                "the block is proposed on chain {}",
+                block.chain_id
+            ))
+        );
+        ensure!(
+            block.authenticated_owner == Some(owner),
+            Error::InvalidSignedProposal(format!(
+                "the proposal is signed by {owner} instead of the block's authenticated owner"
+            ))
+        );
+
+        let mutex = self.proposal_mutex();
+        let proposal_guard = mutex.lock_owned().await;
+        ensure!(
+            proposal_guard.is_none(),
+            Error::BlockProposalError(
+                "Client state already has a pending block; \
+                use the `linera retry-pending-block` command to commit that first"
+            )
+        );
+        let info = self.request_leader_timeout_if_needed().await?;
+        ensure!(
+            info.next_block_height == block.height && info.block_hash == block.previous_block_hash,
+            Error::OutdatedProposal("a different block was committed at this height")
+        );
+        ensure!(
+            info.manager.requested_locking.is_none(),
+            Error::OutdatedProposal("another block is locked on the chain")
+        );
+
+        let (executed_block, _, _, _) = self
+            .client
+            .stage_block_execution(
+                block.clone(),
+                round.multi_leader(),
+                blobs.clone(),
+                BundleExecutionPolicy::committed(),
+            )
+            .await?;
+        ensure!(
+            CryptoHash::new(&executed_block) == expected_block_hash,
+            Error::OutdatedProposal("executing the block no longer produces the expected outcome")
+        );
+
+        // If the local node already accepted this proposal, e.g. in an earlier attempt to
+        // submit it, the round has already moved on.
+        let already_handled_locally = info.manager.already_handled_proposal(*round, block);
+        if !already_handled_locally {
+            let current_round = self
+                .round_for_new_proposal(&info, &owner, executed_block.has_oracle_responses())
+                .await?;
+            ensure!(
+                matches!(current_round, Either::Left(current_round) if current_round == *round),
+                Error::OutdatedProposal("the proposal's round is not open for new proposals")
+            );
+        }
+
+        debug!("Submitting the signed proposal for round {round}");
+        let certificate = self
+            .submit_signed_block_proposal(
+                Box::new(proposal),
+                executed_block,
+                blobs,
+                already_handled_locally,
+            )
+            .await?;
+        drop(proposal_guard);
+        Ok(Arc::unwrap_or_clone(certificate))
+    }
+}
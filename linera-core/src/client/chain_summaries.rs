@@ -0,0 +1,139 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Coalesces the chain summary queries sent to each validator into batched requests.
+//!
+//! When a client with many chains starts up, it synchronizes every chain with every
+//! validator. Most of these chains are already up to date, and a single batched query per
+//! validator is enough to find out which ones are not.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use linera_base::{crypto::ValidatorPublicKey, identifiers::ChainId, time::Duration};
+use tokio::sync::oneshot;
+
+use crate::{
+    data_types::{ChainSummary, MAX_CHAIN_SUMMARIES_PER_QUERY},
+    node::{NodeError, ValidatorNode},
+    remote_node::RemoteNode,
+};
+
+type Waiter = oneshot::Sender<Result<ChainSummary, NodeError>>;
+
+/// The chain summary queries that have not been sent yet, by validator.
+type PendingQueries = HashMap<ValidatorPublicKey, Vec<(ChainId, Waiter)>>;
+
+/// Collects the chain summary queries for each validator, and sends them in batches.
+pub(crate) struct ChainSummaryBatcher {
+    /// How long to wait for more queries before sending a batch.
+    delay: Duration,
+    pending: Mutex<PendingQueries>,
+}
+
+impl ChainSummaryBatcher {
+    pub(crate) fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            pending: Mutex::default(),
+        }
+    }
+
+    /// Returns the validator's summary of the chain.
+    ///
+    /// The query is sent together with the other queries for the same validator that are made
+    /// within the batching delay.
+    pub(crate) async fn chain_summary<N>(
+        &self,
+        remote_node: &RemoteNode<N>,
+        chain_id: ChainId,
+    ) -> Result<ChainSummary, NodeError>
+    where
+        N: ValidatorNode,
+    {
+        let (sender, receiver) = oneshot::channel();
+        let starts_batch = {
+            let mut pending = self.pending.lock().unwrap();
+            let queries = pending.entry(remote_node.public_key).or_default();
+            queries.push((chain_id, sender));
+            queries.len() == 1
+        };
+        if starts_batch {
+            // The first query of a batch sends it after the delay, so that no task outlives
+            // the client. If it is cancelled, the queries of the batch are dropped and their
+            // callers get an error.
+            let mut batch = PendingBatch {
+                pending: &self.pending,
+                public_key: remote_node.public_key,
+                is_taken: false,
+            };
+            linera_base::time::timer::sleep(self.delay).await;
+            let queries = batch.take();
+            Self::send_batches(&remote_node.node, queries).await;
+        }
+        receiver.await.unwrap_or_else(|_| {
+            Err(NodeError::ResponseHandlingError {
+                error: "chain summary query was dropped".to_string(),
+            })
+        })
+    }
+
+    /// Sends the queries in batches of at most [`MAX_CHAIN_SUMMARIES_PER_QUERY`] chains and
+    /// hands out the results.
+    async fn send_batches<N: ValidatorNode>(node: &N, mut queries: Vec<(ChainId, Waiter)>) {
+        while !queries.is_empty() {
+            let rest = queries.split_off(queries.len().min(MAX_CHAIN_SUMMARIES_PER_QUERY));
+            let chain_ids = queries.iter().map(|(chain_id, _)| *chain_id).collect();
+            match node.handle_chain_summaries_query(chain_ids).await {
+                Ok(results) if results.len() == queries.len() => {
+                    for ((chain_id, waiter), result) in queries.into_iter().zip(results) {
+                        let result = result.and_then(|summary| {
+                            if summary.chain_id == chain_id {
+                                Ok(summary)
+                            } else {
+                                Err(NodeError::InvalidChainInfoResponse)
+                            }
+                        });
+                        // The caller may have given up waiting.
+                        waiter.send(result).ok();
+                    }
+                }
+                result => {
+                    let error = result.err().unwrap_or(NodeError::InvalidChainInfoResponse);
+                    for (_, waiter) in queries {
+                        waiter.send(Err(error.clone())).ok();
+                    }
+                }
+            }
+            queries = rest;
+        }
+    }
+}
+
+/// The queries for a validator that are waiting for the batching delay to elapse.
+struct PendingBatch<'a> {
+    pending: &'a Mutex<PendingQueries>,
+    public_key: ValidatorPublicKey,
+    is_taken: bool,
+}
+
+impl PendingBatch<'_> {
+    /// Takes the queries of the batch. Later queries start a new batch.
+    fn take(&mut self) -> Vec<(ChainId, Waiter)> {
+        self.is_taken = true;
+        self.pending
+            .lock()
+            .unwrap()
+            .remove(&self.public_key)
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for PendingBatch<'_> {
+    fn drop(&mut self) {
+        // If the batch was cancelled before it was sent, drop its queries rather than leave
+        // them without anyone to send them.
+        if !self.is_taken {
+            self.pending.lock().unwrap().remove(&self.public_key);
+        }
+    }
+}
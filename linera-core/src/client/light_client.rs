@@ -0,0 +1,255 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Following chains as a light client.
+//!
+//! A light client does not execute the blocks of the chains it follows. Instead, it checks
+//! that the certificate of the latest block is signed by the committee of the block's epoch,
+//! and only tracks the chain's tip and the balances it was asked about. Each balance comes with
+//! a Merkle proof under the state hash in the header of that block, so a single validator with
+//! a valid certificate is enough and no validator can make us accept a wrong balance. This
+//! requires the committee's policy to set `commit_state_roots`.
+
+use std::{cmp::Reverse, collections::BTreeMap, sync::Arc};
+
+use futures::future;
+use linera_base::{
+    crypto::CryptoHash,
+    data_types::{Amount, BlockHeight, Epoch, Timestamp},
+    ensure,
+    identifiers::{AccountOwner, ChainId},
+};
+use linera_chain::manager::ChainManagerInfo;
+use linera_execution::committee::Committee;
+use tracing::{debug, instrument, warn};
+
+use super::{chain_client, Client};
+use crate::{
+    data_types::{ChainInfo, ChainInfoQuery},
+    environment::Environment,
+    node::{NodeError, ValidatorNode as _},
+    remote_node::RemoteNode,
+};
+
+/// The verified state of a chain that is followed as a light client.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LightChainState {
+    /// The height of the next block, i.e. the number of verified blocks.
+    pub next_block_height: BlockHeight,
+    /// The hash of the latest block, if any.
+    pub block_hash: Option<CryptoHash>,
+    /// The hash of the execution state after the latest block, if any.
+    pub state_hash: Option<CryptoHash>,
+    /// The epoch of the latest block, or of the chain's creation if it has no blocks.
+    pub epoch: Epoch,
+    /// The timestamp of the latest block, or of the chain's creation if it has no blocks.
+    pub timestamp: Timestamp,
+    /// The balance of the chain account.
+    pub chain_balance: Amount,
+    /// The balances of the requested owners.
+    pub owner_balances: BTreeMap<AccountOwner, Amount>,
+}
+
+impl LightChainState {
+    /// Returns the chain info with the verified fields of this state, and the balance of
+    /// `owner` if it was verified. The other fields, e.g. the chain manager's, are left empty.
+    pub fn chain_info(&self, chain_id: ChainId, owner: Option<AccountOwner>) -> ChainInfo {
+        ChainInfo {
+            chain_id,
+            epoch: self.epoch,
+            description: None,
+            manager: Box::new(ChainManagerInfo::default()),
+            chain_balance: self.chain_balance,
+            block_hash: self.block_hash,
+            timestamp: self.timestamp,
+            next_block_height: self.next_block_height,
+            state_hash: self.state_hash,
+            requested_owner_balance: owner
+                .and_then(|owner| self.owner_balances.get(&owner))
+                .copied(),
+            requested_committees: None,
+            requested_pending_message_bundles: Vec::new(),
+            requested_sent_certificate_hashes: Vec::new(),
+            count_received_log: 0,
+            requested_received_log: Vec::new(),
+            requested_previous_event_blocks: BTreeMap::new(),
+            requested_balance_proof: None,
+        }
+    }
+}
+
+impl<Env: Environment> Client<Env> {
+    /// Returns whether the chain is followed as a light client.
+    ///
+    /// The admin chain is always executed, since it defines the committees that certificates are
+    /// checked against.
+    pub fn is_light_chain(&self, chain_id: ChainId) -> bool {
+        self.options.light_client && chain_id != self.admin_chain_id
+    }
+
+    /// Returns the last verified state of a chain that is followed as a light client.
+    pub fn light_chain_state(&self, chain_id: ChainId) -> Option<LightChainState> {
+        self.light_chains.read().unwrap().get(&chain_id).cloned()
+    }
+
+    /// Asks the validators for the chain's tip, verifies the certificate of the latest block,
+    /// and updates the balances of the chain account and of the given owners.
+    ///
+    /// No block is executed locally.
+    #[instrument(level = "trace", skip(self, owners))]
+    pub async fn synchronize_light_chain_state(
+        &self,
+        chain_id: ChainId,
+        owners: &[AccountOwner],
+    ) -> Result<LightChainState, chain_client::Error> {
+        let (max_epoch, committees) = self.admin_committees().await?;
+        let committee = committees
+            .get(&max_epoch)
+            .ok_or(chain_client::Error::InternalError(
+                "missing the committee of the latest epoch",
+            ))?;
+        let validators = self.make_nodes(committee)?;
+        let mut reports = future::join_all(validators.iter().map(|remote_node| async move {
+            let result = Self::query_light_chain_infos(remote_node, chain_id, owners).await;
+            (remote_node, result)
+        }))
+        .await
+        .into_iter()
+        .filter_map(|(remote_node, result)| match result {
+            Ok(infos) => Some((remote_node, infos)),
+            Err(error) => {
+                debug!(
+                    remote_node = remote_node.address(),
+                    %chain_id,
+                    %error,
+                    "failed to query chain info from validator",
+                );
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+        // Try the highest tips first: a validator cannot make us accept a block without a valid
+        // certificate, nor a balance without a valid proof.
+        reports.sort_by_key(|(_, infos)| Reverse(infos[0].next_block_height));
+        let mut verified = None;
+        for (remote_node, infos) in &reports {
+            match self
+                .verify_light_chain_report(remote_node, infos, owners, max_epoch, &committees)
+                .await
+            {
+                Ok(state) => {
+                    verified = Some(state);
+                    break;
+                }
+                Err(error) => warn!(
+                    remote_node = remote_node.address(),
+                    %chain_id,
+                    %error,
+                    "failed to verify the chain state reported by validator",
+                ),
+            }
+        }
+        let state = verified.ok_or(chain_client::Error::CannotVerifyChainTip(chain_id))?;
+
+        let mut light_chains = self.light_chains.write().unwrap();
+        let known = light_chains.entry(chain_id).or_default();
+        // Certificates are final, so a lower verified tip only means that some validators are
+        // lagging behind.
+        if known.next_block_height <= state.next_block_height {
+            *known = state;
+        }
+        Ok(known.clone())
+    }
+
+    /// Queries the validator for the chain info with a balance proof, once for the chain account
+    /// and then once for each owner.
+    async fn query_light_chain_infos(
+        remote_node: &RemoteNode<Env::ValidatorNode>,
+        chain_id: ChainId,
+        owners: &[AccountOwner],
+    ) -> Result<Vec<Box<ChainInfo>>, NodeError> {
+        let queries = std::iter::once(AccountOwner::CHAIN)
+            .chain(owners.iter().copied())
+            .map(|owner| {
+                let query = ChainInfoQuery::new(chain_id).with_balance_proof(owner);
+                async move {
+                    let response = remote_node.node.handle_chain_info_query(query).await?;
+                    ensure!(
+                        response.info.chain_id == chain_id,
+                        NodeError::InvalidChainInfoResponse
+                    );
+                    Ok(response.info)
+                }
+            });
+        future::try_join_all(queries).await
+    }
+
+    /// Downloads the certificate of the block before the reported next height, checks it
+    /// against the committee of its epoch, and checks the reported balances against the state
+    /// hash in the block's header.
+    ///
+    /// If the chain has no blocks, the balances are the ones in the chain description instead.
+    async fn verify_light_chain_report(
+        &self,
+        remote_node: &RemoteNode<Env::ValidatorNode>,
+        infos: &[Box<ChainInfo>],
+        owners: &[AccountOwner],
+        max_epoch: Epoch,
+        committees: &BTreeMap<Epoch, Arc<Committee>>,
+    ) -> Result<LightChainState, chain_client::Error> {
+        let info = &infos[0];
+        let Ok(height) = info.next_block_height.try_sub_one() else {
+            let description = self.get_chain_description(info.chain_id).await?;
+            return Ok(LightChainState {
+                epoch: description.config().epoch,
+                timestamp: description.timestamp(),
+                chain_balance: description.config().balance,
+                owner_balances: owners.iter().map(|owner| (*owner, Amount::ZERO)).collect(),
+                ..LightChainState::default()
+            });
+        };
+        let certificate = self
+            .requests_scheduler
+            .download_certificates(remote_node, info.chain_id, height, 1)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(NodeError::MissingCertificateValue)?;
+        Self::check_certificate(max_epoch, committees, &certificate)?.into_result()?;
+        let header = &certificate.block().header;
+        ensure!(
+            header.chain_id == info.chain_id
+                && header.height == height
+                && info.block_hash == Some(certificate.hash()),
+            NodeError::InvalidChainInfoResponse
+        );
+        let mut balances = Vec::new();
+        for (info, owner) in infos
+            .iter()
+            .zip(std::iter::once(AccountOwner::CHAIN).chain(owners.iter().copied()))
+        {
+            let proof = info
+                .requested_balance_proof
+                .as_ref()
+                .ok_or(NodeError::InvalidChainInfoResponse)?;
+            let balance = proof
+                .verify(&header.state_hash, owner)
+                .map_err(|_| NodeError::InvalidChainInfoResponse)?;
+            balances.push(balance);
+        }
+        Ok(LightChainState {
+            next_block_height: info.next_block_height,
+            block_hash: Some(certificate.hash()),
+            state_hash: Some(header.state_hash),
+            epoch: header.epoch,
+            timestamp: header.timestamp,
+            chain_balance: balances[0],
+            owner_balances: owners
+                .iter()
+                .copied()
+                .zip(balances[1..].iter().copied())
+                .collect(),
+        })
+    }
+}
@@ -9,6 +9,7 @@ use std::{
     sync::{Arc, RwLock},
 };
 
+use chain_summaries::ChainSummaryBatcher;
 use custom_debug_derive::Debug;
 use futures::{
     future::Future,
@@ -39,7 +40,7 @@ use linera_chain::{
     },
     ChainError,
 };
-use linera_execution::committee::Committee;
+use linera_execution::{committee::Committee, ResourceTracker};
 use linera_storage::{Clock as _, ResultReadCertificates, Storage as _};
 use rand::seq::SliceRandom;
 use received_log::ReceivedLogs;
@@ -48,7 +49,7 @@ use tokio::sync::mpsc;
 use tracing::{debug, error, info, instrument, trace, warn};
 
 use crate::{
-    data_types::{ChainInfo, ChainInfoQuery, ChainInfoResponse},
+    data_types::{ChainInfo, ChainInfoQuery, ChainInfoResponse, ChainSummary},
     environment::Environment,
     local_node::{LocalNodeClient, LocalNodeError},
     node::{CrossChainMessageDelivery, NodeError, ValidatorNode as _, ValidatorNodeProvider as _},
@@ -69,7 +70,10 @@ pub use crate::data_types::ClientOutcome;
 mod client_tests;
 pub mod requests_scheduler;
 
+pub use light_client::LightChainState;
 pub use requests_scheduler::{RequestsScheduler, RequestsSchedulerConfig, ScoringWeights};
+mod chain_summaries;
+mod light_client;
 mod received_log;
 mod validator_trackers;
 
@@ -253,6 +257,8 @@ pub struct Client<Env: Environment> {
     pub local_node: LocalNodeClient<Env::Storage>,
     /// Manages the requests sent to validator nodes.
     requests_scheduler: RequestsScheduler<Env>,
+    /// Batches the chain summary queries sent to validator nodes.
+    chain_summaries: ChainSummaryBatcher,
     /// The admin chain ID.
     admin_chain_id: ChainId,
     /// Chains that should be tracked by the client, along with their listening mode.
@@ -262,6 +268,8 @@ pub struct Client<Env: Environment> {
     notifier: Arc<ChannelNotifier<Notification>>,
     /// Chain state for the managed chains.
     chains: papaya::HashMap<ChainId, chain_client::State>,
+    /// The verified tips and balances of the chains followed as a light client.
+    light_chains: Arc<RwLock<BTreeMap<ChainId, LightChainState>>>,
     /// Configuration options.
     options: chain_client::Options,
 }
@@ -304,12 +312,15 @@ impl<Env: Environment> Client<Env> {
         );
         let local_node = LocalNodeClient::new(state);
         let requests_scheduler = RequestsScheduler::new(vec![], requests_scheduler_config);
+        let chain_summaries = ChainSummaryBatcher::new(options.chain_summary_batch_delay);
 
         Self {
             environment,
             local_node,
             requests_scheduler,
+            chain_summaries,
             chains: papaya::HashMap::new(),
+            light_chains: Arc::default(),
             admin_chain_id,
             chain_modes,
             notifier: Arc::new(ChannelNotifier::default()),
@@ -322,6 +333,36 @@ impl<Env: Environment> Client<Env> {
         self.admin_chain_id
     }
 
+    /// Returns the performance statistics of the validators, e.g. to persist them.
+    pub async fn validator_statistics(
+        &self,
+    ) -> BTreeMap<ValidatorPublicKey, requests_scheduler::NodeStatistics> {
+        self.requests_scheduler.statistics().await
+    }
+
+    /// Returns the performance statistics of the validators, or `None` if they are being
+    /// updated. Unlike [`Self::validator_statistics`], this never blocks.
+    pub fn try_validator_statistics(
+        &self,
+    ) -> Option<BTreeMap<ValidatorPublicKey, requests_scheduler::NodeStatistics>> {
+        self.requests_scheduler.try_statistics()
+    }
+
+    /// Returns the score of each validator with known statistics, together with the statistics.
+    pub async fn validator_scores(
+        &self,
+    ) -> BTreeMap<ValidatorPublicKey, (f64, requests_scheduler::NodeStatistics)> {
+        self.requests_scheduler.scores().await
+    }
+
+    /// Uses the validator statistics from an earlier session, decayed according to their age.
+    pub async fn restore_validator_statistics(
+        &self,
+        statistics: BTreeMap<ValidatorPublicKey, requests_scheduler::NodeStatistics>,
+    ) {
+        self.requests_scheduler.restore_statistics(statistics).await
+    }
+
     /// Subscribes to notifications for the given chain IDs.
     pub fn subscribe(
         &self,
@@ -362,6 +403,69 @@ impl<Env: Environment> Client<Env> {
         Ok(results.into_iter().next().flatten())
     }
 
+    /// Reads the certificates of a chain at the given heights from local storage. The ones
+    /// that are missing are downloaded from the validators and checked against the block
+    /// hashes in the local chain state.
+    #[instrument(level = "trace", skip(self, heights))]
+    pub async fn read_or_download_certificates(
+        &self,
+        chain_id: ChainId,
+        heights: &[BlockHeight],
+    ) -> Result<Vec<Arc<ConfirmedBlockCertificate>>, chain_client::Error> {
+        let certificates = self
+            .storage_client()
+            .read_certificates_by_heights(chain_id, heights)
+            .await?;
+        let missing_heights = heights
+            .iter()
+            .zip(&certificates)
+            .filter(|(_, certificate)| certificate.is_none())
+            .map(|(height, _)| *height)
+            .collect::<Vec<_>>();
+        if missing_heights.is_empty() {
+            return Ok(certificates.into_iter().flatten().collect());
+        }
+        let hashes = self
+            .local_node
+            .get_block_hashes(chain_id, missing_heights.clone())
+            .await?;
+        let validators = self.validator_nodes().await?;
+        let (hashes, missing_heights) = (&hashes, &missing_heights);
+        let downloaded = communicate_concurrently(
+            &validators,
+            async move |remote_node| {
+                let certificates = self
+                    .requests_scheduler
+                    .download_certificates_by_heights(
+                        &remote_node,
+                        chain_id,
+                        missing_heights.clone(),
+                    )
+                    .await?;
+                if !certificates
+                    .iter()
+                    .map(ConfirmedBlockCertificate::hash)
+                    .eq(hashes.iter().copied())
+                {
+                    return Err(NodeError::UnexpectedCertificateValue);
+                }
+                Ok(certificates)
+            },
+            |errors| match errors.into_iter().next_back() {
+                Some((_validator, error)) => chain_client::Error::from(error),
+                None => chain_client::Error::ReadCertificatesError(hashes.clone()),
+            },
+            self.options.certificate_batch_download_timeout,
+        )
+        .await?;
+        let mut downloaded = downloaded.into_iter().map(Arc::new);
+        Ok(certificates
+            .into_iter()
+            .map(|certificate| certificate.or_else(|| downloaded.next()))
+            .collect::<Option<Vec<_>>>()
+            .expect("a certificate was downloaded for each missing height"))
+    }
+
     pub fn validator_node_provider(&self) -> &Env::Network {
         self.environment.network()
     }
@@ -1685,7 +1789,7 @@ impl<Env: Environment> Client<Env> {
             &committee,
             |_: &()| (),
             |remote_node| async move {
-                self.synchronize_chain_state_from(&remote_node, chain_id)
+                self.synchronize_chain_state_from_if_behind(&remote_node, chain_id)
                     .await
             },
             self.options.quorum_grace_period,
@@ -1698,6 +1802,52 @@ impl<Env: Environment> Client<Env> {
             .map_err(Into::into)
     }
 
+    /// Like [`Self::synchronize_chain_state_from`], but first asks the validator for a summary
+    /// of the chain, and skips the synchronization if the validator has nothing we are missing.
+    ///
+    /// The summary queries of concurrent synchronizations are sent to each validator in
+    /// batches, so that clients with many chains can synchronize them quickly.
+    #[instrument(level = "trace", skip(self, remote_node, chain_id))]
+    pub(crate) async fn synchronize_chain_state_from_if_behind(
+        &self,
+        remote_node: &RemoteNode<Env::ValidatorNode>,
+        chain_id: ChainId,
+    ) -> Result<(), chain_client::Error> {
+        match self
+            .chain_summaries
+            .chain_summary(remote_node, chain_id)
+            .await
+        {
+            Ok(summary) => {
+                if let Ok(local_info) = self.local_node.chain_info(chain_id).await {
+                    if Self::is_up_to_date_with(&local_info, &summary) {
+                        return Ok(());
+                    }
+                }
+            }
+            Err(error) => {
+                debug!(
+                    remote_node = remote_node.address(),
+                    %chain_id,
+                    %error,
+                    "failed to get chain summary from validator",
+                );
+            }
+        }
+        self.synchronize_chain_state_from(remote_node, chain_id)
+            .await
+    }
+
+    /// Returns whether synchronizing the chain from a validator with the given summary
+    /// would not change our local chain state.
+    fn is_up_to_date_with(local_info: &ChainInfo, summary: &ChainSummary) -> bool {
+        // The chain manager is only updated if we are at the same height as the validator.
+        summary.next_block_height < local_info.next_block_height
+            || (summary.next_block_height == local_info.next_block_height
+                && summary.block_hash == local_info.block_hash
+                && !summary.has_pending_proposal)
+    }
+
     /// Downloads any certificates from the specified validator that we are missing for the given
     /// chain.
     ///
@@ -1957,7 +2107,8 @@ impl<Env: Environment> Client<Env> {
         round: Option<u32>,
         published_blobs: Vec<Blob>,
         policy: BundleExecutionPolicy,
-    ) -> Result<(Block, ChainInfoResponse, HashSet<ChainId>), chain_client::Error> {
+    ) -> Result<(Block, ChainInfoResponse, ResourceTracker, HashSet<ChainId>), chain_client::Error>
+    {
         let mut downloaded_events = HashSet::<EventId>::new();
         loop {
             let result = self
@@ -1999,14 +2150,14 @@ impl<Env: Environment> Client<Env> {
                 };
                 self.notifier.notify(&[notification]);
             }
-            let (
-                _modified_block,
+            let (_modified_block, executed_block, response, resource_tracker, never_reject_origins) =
+                result?;
+            return Ok((
                 executed_block,
                 response,
-                _resource_tracker,
+                resource_tracker,
                 never_reject_origins,
-            ) = result?;
-            return Ok((executed_block, response, never_reject_origins));
+            ));
         }
     }
 }
@@ -12,7 +12,7 @@ mod scheduler;
 mod scoring;
 
 pub use scheduler::RequestsScheduler;
-pub use scoring::ScoringWeights;
+pub use scoring::{NodeStatistics, ScoringStrategy, ScoringWeights};
 
 // Module constants - default values for RequestsSchedulerConfig
 pub const MAX_IN_FLIGHT_REQUESTS: usize = 100;
@@ -22,6 +22,7 @@ pub const CACHE_MAX_SIZE: usize = 1000;
 pub const MAX_REQUEST_TTL_MS: u64 = 200;
 pub const ALPHA_SMOOTHING_FACTOR: f64 = 0.1;
 pub const STAGGERED_DELAY_MS: u64 = 150;
+pub const STATISTICS_HALF_LIFE_MS: u64 = 3_600_000;
 
 /// Configuration for the `RequestsScheduler`.
 #[derive(Debug, Clone)]
@@ -38,6 +39,10 @@ pub struct RequestsSchedulerConfig {
     pub alpha: f64,
     /// Delay in milliseconds between starting requests to different peers.
     pub retry_delay_ms: u64,
+    /// The weights and strategy used to score validators.
+    pub scoring_weights: ScoringWeights,
+    /// The time in milliseconds after which restored statistics count half as much.
+    pub statistics_half_life_ms: u64,
 }
 
 impl Default for RequestsSchedulerConfig {
@@ -49,6 +54,8 @@ impl Default for RequestsSchedulerConfig {
             max_request_ttl_ms: MAX_REQUEST_TTL_MS,
             alpha: ALPHA_SMOOTHING_FACTOR,
             retry_delay_ms: STAGGERED_DELAY_MS,
+            scoring_weights: ScoringWeights::default(),
+            statistics_half_life_ms: STATISTICS_HALF_LIFE_MS,
         }
     }
 }
@@ -2,8 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use custom_debug_derive::Debug;
+use linera_base::{
+    data_types::Timestamp,
+    time::{Duration, Instant},
+};
 
-use super::scoring::ScoringWeights;
+use super::scoring::{NodeStatistics, ScoringWeights};
 use crate::{environment::Environment, remote_node::RemoteNode};
 
 /// Tracks performance metrics and request capacity for a validator node using
@@ -17,16 +21,8 @@ pub(super) struct NodeInfo<Env: Environment> {
     /// The underlying validator node connection
     pub(super) node: RemoteNode<Env::ValidatorNode>,
 
-    /// Exponential Moving Average of latency in milliseconds
-    /// Adapts quickly to changes in response time
-    ema_latency_ms: f64,
-
-    /// Exponential Moving Average of success rate (0.0 to 1.0)
-    /// Tracks recent success/failure patterns
-    ema_success_rate: f64,
-
-    /// Total number of requests processed (for monitoring and cold-start handling)
-    total_requests: u64,
+    /// The Exponential Moving Averages of latency and success rate, and the number of requests
+    statistics: NodeStatistics,
 
     /// Configuration for scoring weights
     weights: ScoringWeights,
@@ -37,6 +33,9 @@ pub(super) struct NodeInfo<Env: Environment> {
 
     /// Maximum expected latency in milliseconds for score normalization
     max_expected_latency_ms: f64,
+
+    /// The time until which the node asked us not to send further requests, if any
+    rate_limited_until: Option<Instant>,
 }
 
 impl<Env: Environment> NodeInfo<Env> {
@@ -50,40 +49,33 @@ impl<Env: Environment> NodeInfo<Env> {
         assert!(alpha > 0.0 && alpha < 1.0, "Alpha must be in (0, 1) range");
         Self {
             node,
-            ema_latency_ms: 100.0, // Start with reasonable latency expectation
-            ema_success_rate: 1.0, // Start optimistically with 100% success
-            total_requests: 0,
+            // Start with reasonable latency expectation, and optimistically with 100% success
+            statistics: NodeStatistics::default(),
             weights,
             alpha,
             max_expected_latency_ms,
+            rate_limited_until: None,
         }
     }
 
+    /// Starts from previously recorded statistics instead of the initial assumptions.
+    pub(super) fn with_statistics(mut self, statistics: NodeStatistics) -> Self {
+        self.set_statistics(statistics);
+        self
+    }
+
+    /// Replaces the statistics, e.g. with ones restored from an earlier session.
+    pub(super) fn set_statistics(&mut self, statistics: NodeStatistics) {
+        self.statistics = statistics;
+    }
+
     /// Calculates a normalized performance score (0.0 to 1.0) using weighted metrics.
     ///
-    /// The score combines three normalized components:
-    /// - **Latency score**: Inversely proportional to EMA latency
-    /// - **Success score**: Directly proportional to EMA success rate
-    ///
     /// Returns a score from 0.0 to 1.0, where higher values indicate better performance.
+    /// See [`ScoringWeights::score`].
     pub(super) async fn calculate_score(&self) -> f64 {
-        // 1. Normalize latency (lower is better, so we invert)
-        let latency_score = 1.0
-            - (self.ema_latency_ms.min(self.max_expected_latency_ms)
-                / self.max_expected_latency_ms);
-
-        // 2. Success rate is already normalized [0, 1]
-        let success_score = self.ema_success_rate;
-
-        // 3. Apply cold-start penalty for nodes with very few requests
-        let confidence_factor = (self.total_requests as f64 / 10.0).min(1.0);
-
-        // 4. Combine with weights
-        let raw_score =
-            (self.weights.latency * latency_score) + (self.weights.success * success_score);
-
-        // Apply confidence factor to penalize nodes with too few samples
-        raw_score * (0.5 + 0.5 * confidence_factor)
+        self.weights
+            .score(&self.statistics, self.max_expected_latency_ms)
     }
 
     /// Updates performance metrics using Exponential Moving Average.
@@ -96,21 +88,39 @@ impl<Env: Environment> NodeInfo<Env> {
     /// This gives more weight to recent observations while maintaining some history.
     pub(super) fn update_metrics(&mut self, success: bool, response_time_ms: u64) {
         let response_time_f64 = response_time_ms as f64;
+        let statistics = &mut self.statistics;
 
         // Update latency EMA
-        self.ema_latency_ms =
-            (self.alpha * response_time_f64) + ((1.0 - self.alpha) * self.ema_latency_ms);
+        statistics.ema_latency_ms =
+            (self.alpha * response_time_f64) + ((1.0 - self.alpha) * statistics.ema_latency_ms);
 
         // Update success rate EMA
         let success_value = if success { 1.0 } else { 0.0 };
-        self.ema_success_rate =
-            (self.alpha * success_value) + ((1.0 - self.alpha) * self.ema_success_rate);
+        statistics.ema_success_rate =
+            (self.alpha * success_value) + ((1.0 - self.alpha) * statistics.ema_success_rate);
+
+        statistics.total_requests += 1;
+        statistics.updated_at = Timestamp::now();
+    }
 
-        self.total_requests += 1;
+    /// Records that the node rate-limited a request and asked us to retry after `retry_after`.
+    pub(super) fn set_rate_limited(&mut self, retry_after: Duration) {
+        self.rate_limited_until = Some(Instant::now() + retry_after);
+    }
+
+    /// Returns whether the node asked us to wait before sending further requests.
+    pub(super) fn is_rate_limited(&self) -> bool {
+        self.rate_limited_until
+            .is_some_and(|until| Instant::now() < until)
     }
 
     /// Returns the total number of requests processed.
     pub(super) fn total_requests(&self) -> u64 {
-        self.total_requests
+        self.statistics.total_requests
+    }
+
+    /// Returns the node's performance statistics.
+    pub(super) fn statistics(&self) -> NodeStatistics {
+        self.statistics
     }
 }
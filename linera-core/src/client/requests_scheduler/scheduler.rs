@@ -6,7 +6,7 @@ use std::{
     future::Future,
     sync::{
         atomic::{AtomicU32, Ordering},
-        Arc,
+        Arc, Mutex,
     },
 };
 
@@ -14,7 +14,7 @@ use custom_debug_derive::Debug;
 use futures::stream::{FuturesUnordered, StreamExt};
 use linera_base::{
     crypto::ValidatorPublicKey,
-    data_types::{Blob, BlobContent, BlockHeight},
+    data_types::{Blob, BlobContent, BlockHeight, TimeDelta, Timestamp},
     identifiers::{BlobId, ChainId},
     time::{Duration, Instant},
 };
@@ -27,7 +27,7 @@ use super::{
     in_flight_tracker::{InFlightMatch, InFlightTracker},
     node_info::NodeInfo,
     request::{RequestKey, RequestResult},
-    scoring::ScoringWeights,
+    scoring::{NodeStatistics, ScoringWeights},
 };
 use crate::{
     client::{
@@ -138,6 +138,10 @@ pub struct RequestsScheduler<Env: Environment> {
     max_expected_latency: f64,
     /// Delay between starting requests to alternative peers.
     retry_delay: Duration,
+    /// Statistics from earlier sessions, used for nodes when they are added.
+    restored_statistics: Arc<Mutex<BTreeMap<ValidatorPublicKey, NodeStatistics>>>,
+    /// The age after which restored statistics count half as much.
+    statistics_half_life: TimeDelta,
     /// Tracks in-flight requests to deduplicate concurrent requests for the same data.
     in_flight_tracker: InFlightTracker<RemoteNode<Env::ValidatorNode>>,
     /// Cache of recently completed requests with their results and timestamps.
@@ -150,16 +154,19 @@ impl<Env: Environment> RequestsScheduler<Env> {
         nodes: impl IntoIterator<Item = RemoteNode<Env::ValidatorNode>>,
         config: &RequestsSchedulerConfig,
     ) -> Self {
-        Self::with_config(
-            nodes,
-            ScoringWeights::default(),
-            config.alpha,
-            config.max_accepted_latency_ms,
-            Duration::from_millis(config.cache_ttl_ms),
-            config.cache_max_size,
-            Duration::from_millis(config.max_request_ttl_ms),
-            Duration::from_millis(config.retry_delay_ms),
-        )
+        Self {
+            statistics_half_life: TimeDelta::from_millis(config.statistics_half_life_ms),
+            ..Self::with_config(
+                nodes,
+                config.scoring_weights,
+                config.alpha,
+                config.max_accepted_latency_ms,
+                Duration::from_millis(config.cache_ttl_ms),
+                config.cache_max_size,
+                Duration::from_millis(config.max_request_ttl_ms),
+                Duration::from_millis(config.retry_delay_ms),
+            )
+        }
     }
 
     /// Creates a new `RequestsScheduler` with custom configuration.
@@ -202,6 +209,8 @@ impl<Env: Environment> RequestsScheduler<Env> {
             alpha,
             max_expected_latency: max_expected_latency_ms,
             retry_delay,
+            restored_statistics: Arc::default(),
+            statistics_half_life: TimeDelta::from_millis(super::STATISTICS_HALF_LIFE_MS),
             in_flight_tracker: InFlightTracker::new(max_request_ttl),
             cache: RequestsCache::new(cache_ttl, max_cache_size),
         }
@@ -501,6 +510,17 @@ impl<Env: Environment> RequestsScheduler<Env> {
         {
             let mut nodes_guard = nodes.write().await;
             if let Some(info) = nodes_guard.get_mut(&public_key) {
+                if let Err(NodeError::RateLimited { retry_after_ms }) = &result {
+                    // The node is healthy but overloaded: avoid it until it lets us retry,
+                    // without penalizing its score.
+                    tracing::debug!(
+                        node = %public_key,
+                        %retry_after_ms,
+                        "Validator rate-limited the request"
+                    );
+                    info.set_rate_limited(Duration::from_millis(*retry_after_ms));
+                    return result;
+                }
                 info.update_metrics(is_success, response_time_ms);
                 let score = info.calculate_score().await;
                 tracing::trace!(
@@ -840,16 +860,22 @@ impl<Env: Environment> RequestsScheduler<Env> {
     ///
     /// Only includes peers that can currently accept requests. Each peer is paired
     /// with its calculated score based on latency, success rate, and availability.
+    /// Peers that rate-limited us are skipped until their retry-after hint expires, unless
+    /// all peers are rate-limited.
     ///
     /// # Returns
     /// A vector of `(score, peer)` tuples sorted by score in descending order.
     /// Returns an empty vector if no peers can accept requests.
     async fn peers_by_score(&self) -> Vec<(f64, RemoteNode<Env::ValidatorNode>)> {
         let nodes = self.nodes.read().await;
+        let all_rate_limited = nodes.values().all(NodeInfo::is_rate_limited);
 
         // Filter nodes that can accept requests and calculate their scores
         let mut scored_nodes = Vec::new();
         for info in nodes.values() {
+            if info.is_rate_limited() && !all_rate_limited {
+                continue;
+            }
             let score = info.calculate_score().await;
             scored_nodes.push((score, info.node.clone()));
         }
@@ -901,9 +927,74 @@ impl<Env: Environment> RequestsScheduler<Env> {
         let mut nodes = self.nodes.write().await;
         let public_key = node.public_key;
         nodes.entry(public_key).or_insert_with(|| {
-            NodeInfo::with_config(node, self.weights, self.alpha, self.max_expected_latency)
+            let info =
+                NodeInfo::with_config(node, self.weights, self.alpha, self.max_expected_latency);
+            match self.restored_statistics.lock().unwrap().remove(&public_key) {
+                Some(statistics) => info.with_statistics(statistics),
+                None => info,
+            }
         });
     }
+
+    /// Uses statistics from an earlier session for the nodes that have not been contacted yet,
+    /// so that good validators are picked right away. The statistics decay with their age.
+    pub async fn restore_statistics(
+        &self,
+        statistics: impl IntoIterator<Item = (ValidatorPublicKey, NodeStatistics)>,
+    ) {
+        let now = Timestamp::now();
+        let mut nodes = self.nodes.write().await;
+        let mut restored = self.restored_statistics.lock().unwrap();
+        for (public_key, statistics) in statistics {
+            let statistics = statistics.decayed(now, self.statistics_half_life);
+            match nodes.get_mut(&public_key) {
+                Some(info) if info.total_requests() == 0 => {
+                    info.set_statistics(statistics);
+                }
+                Some(_) => {}
+                None => {
+                    restored.insert(public_key, statistics);
+                }
+            }
+        }
+    }
+
+    /// Returns the current statistics of all nodes, including restored ones that have not been
+    /// contacted in this session, e.g. to persist them.
+    pub async fn statistics(&self) -> BTreeMap<ValidatorPublicKey, NodeStatistics> {
+        self.collect_statistics(&*self.nodes.read().await)
+    }
+
+    /// Like [`Self::statistics`], but returns `None` instead of waiting if the nodes are
+    /// being updated. This can be used where blocking is not an option, e.g. when dropping.
+    pub fn try_statistics(&self) -> Option<BTreeMap<ValidatorPublicKey, NodeStatistics>> {
+        Some(self.collect_statistics(&*self.nodes.try_read().ok()?))
+    }
+
+    fn collect_statistics(
+        &self,
+        nodes: &BTreeMap<ValidatorPublicKey, NodeInfo<Env>>,
+    ) -> BTreeMap<ValidatorPublicKey, NodeStatistics> {
+        let mut statistics = self.restored_statistics.lock().unwrap().clone();
+        statistics.extend(
+            nodes
+                .iter()
+                .map(|(public_key, info)| (*public_key, info.statistics())),
+        );
+        statistics
+    }
+
+    /// Returns the score of each node with known statistics, together with the statistics.
+    pub async fn scores(&self) -> BTreeMap<ValidatorPublicKey, (f64, NodeStatistics)> {
+        self.statistics()
+            .await
+            .into_iter()
+            .map(|(public_key, statistics)| {
+                let score = self.weights.score(&statistics, self.max_expected_latency);
+                (public_key, (score, statistics))
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -1481,4 +1572,116 @@ mod tests {
             "Total time should be less than 500ms (sequential would be ~650ms), got {total_time}ms"
         );
     }
+
+    #[tokio::test]
+    async fn test_rate_limited_peer_is_avoided() {
+        use crate::test_utils::{MemoryStorageBuilder, TestBuilder};
+
+        let mut builder = TestBuilder::new(
+            MemoryStorageBuilder::default(),
+            2,
+            0,
+            InMemorySigner::new(None),
+        )
+        .await
+        .unwrap();
+        let nodes: Vec<_> = (0..2)
+            .map(|i| {
+                let node = builder.node(i);
+                let public_key = node.name();
+                RemoteNode { public_key, node }
+            })
+            .collect();
+        let limited_key = nodes[0].public_key;
+        let manager = RequestsScheduler::<TestEnvironment>::new(
+            nodes.clone(),
+            &RequestsSchedulerConfig::default(),
+        );
+
+        let result: Result<(), NodeError> = RequestsScheduler::<TestEnvironment>::track_request(
+            manager.nodes.clone(),
+            nodes[0].clone(),
+            async {
+                Err(NodeError::RateLimited {
+                    retry_after_ms: 60_000,
+                })
+            },
+        )
+        .await;
+        assert!(matches!(result, Err(NodeError::RateLimited { .. })));
+
+        // The rate-limited peer is skipped while another one is available.
+        let peers = manager.peers_by_score().await;
+        assert_eq!(peers.len(), 1);
+        assert_ne!(peers[0].1.public_key, limited_key);
+
+        // If all peers are rate-limited, they are all candidates again.
+        let result: Result<(), NodeError> = RequestsScheduler::<TestEnvironment>::track_request(
+            manager.nodes.clone(),
+            nodes[1].clone(),
+            async {
+                Err(NodeError::RateLimited {
+                    retry_after_ms: 60_000,
+                })
+            },
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(manager.peers_by_score().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_restored_statistics_rank_peers() {
+        use crate::test_utils::{MemoryStorageBuilder, TestBuilder};
+
+        let mut builder = TestBuilder::new(
+            MemoryStorageBuilder::default(),
+            2,
+            0,
+            InMemorySigner::new(None),
+        )
+        .await
+        .unwrap();
+        let nodes: Vec<_> = (0..2)
+            .map(|i| {
+                let node = builder.node(i);
+                let public_key = node.name();
+                RemoteNode { public_key, node }
+            })
+            .collect();
+        let manager = RequestsScheduler::<TestEnvironment>::new(
+            vec![nodes[0].clone()],
+            &RequestsSchedulerConfig::default(),
+        );
+        let now = Timestamp::now();
+        let unreliable = NodeStatistics {
+            ema_latency_ms: 500.0,
+            ema_success_rate: 0.2,
+            total_requests: 50,
+            updated_at: now,
+        };
+        let reliable = NodeStatistics {
+            ema_latency_ms: 50.0,
+            ema_success_rate: 1.0,
+            total_requests: 50,
+            updated_at: now,
+        };
+        manager
+            .restore_statistics([
+                (nodes[0].public_key, unreliable),
+                (nodes[1].public_key, reliable),
+            ])
+            .await;
+
+        // Statistics apply both to existing nodes and to nodes that are added later.
+        manager.add_peer(nodes[1].clone()).await;
+        let peers = manager.peers_by_score().await;
+        assert_eq!(peers.len(), 2);
+        assert_eq!(peers[0].1.public_key, nodes[1].public_key);
+
+        let statistics = manager.statistics().await;
+        assert_eq!(statistics.len(), 2);
+        assert_eq!(statistics[&nodes[0].public_key].total_requests, 50);
+        assert_eq!(manager.try_statistics(), Some(statistics));
+    }
 }
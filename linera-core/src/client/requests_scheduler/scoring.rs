@@ -1,6 +1,14 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use linera_base::data_types::{TimeDelta, Timestamp};
+use serde::{Deserialize, Serialize};
+
+/// The latency that nodes without statistics are assumed to have, in milliseconds.
+pub(super) const INITIAL_LATENCY_MS: f64 = 100.0;
+/// The success rate that nodes without statistics are assumed to have.
+pub(super) const INITIAL_SUCCESS_RATE: f64 = 1.0;
+
 /// Configurable weights for the scoring algorithm.
 ///
 /// These weights determine the relative importance of different metrics
@@ -14,6 +22,7 @@
 ///     latency: 0.4,
 ///     success: 0.4,
 ///     load: 0.2,
+///     strategy: ScoringStrategy::WeightedSum,
 /// };
 ///
 /// // Prioritize low latency above all else
@@ -21,6 +30,7 @@
 ///     latency: 0.7,
 ///     success: 0.2,
 ///     load: 0.1,
+///     strategy: ScoringStrategy::WeightedSum,
 /// };
 /// ```
 #[derive(Debug, Clone, Copy)]
@@ -32,6 +42,8 @@ pub struct ScoringWeights {
     /// Weight for load metric (lower load = higher score).
     /// NOTE: Not yet used in scoring; reserved for future use.
     pub load: f64,
+    /// How the weighted metrics are combined into a score.
+    pub strategy: ScoringStrategy,
 }
 
 impl Default for ScoringWeights {
@@ -40,6 +52,168 @@ impl Default for ScoringWeights {
             latency: 0.4, // 40% weight on response time
             success: 0.4, // 40% weight on success rate
             load: 0.2,    // 20% weight on current load (reserved)
+            strategy: ScoringStrategy::default(),
+        }
+    }
+}
+
+/// How the normalized metrics of a node are combined into a score.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumString,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum ScoringStrategy {
+    /// The weighted sum of the metrics: a node that is good at one metric can make up for
+    /// being bad at another.
+    #[default]
+    WeightedSum,
+    /// The weighted geometric mean of the metrics: a node that is bad at any metric gets a low
+    /// score, e.g. a fast validator that often fails.
+    WeightedProduct,
+}
+
+impl ScoringWeights {
+    /// Calculates a normalized performance score (0.0 to 1.0) from the node's statistics.
+    ///
+    /// The score combines two normalized components, according to the strategy:
+    /// - **Latency score**: Inversely proportional to EMA latency
+    /// - **Success score**: Directly proportional to EMA success rate
+    ///
+    /// Nodes with few requests are penalized, since their statistics are less reliable.
+    pub fn score(&self, statistics: &NodeStatistics, max_expected_latency_ms: f64) -> f64 {
+        // 1. Normalize latency (lower is better, so we invert)
+        let latency_score = 1.0
+            - (statistics.ema_latency_ms.min(max_expected_latency_ms) / max_expected_latency_ms);
+
+        // 2. Success rate is already normalized [0, 1]
+        let success_score = statistics.ema_success_rate;
+
+        // 3. Apply cold-start penalty for nodes with very few requests
+        let confidence_factor = (statistics.total_requests as f64 / 10.0).min(1.0);
+
+        // 4. Combine with weights
+        let raw_score = match self.strategy {
+            ScoringStrategy::WeightedSum => {
+                (self.latency * latency_score) + (self.success * success_score)
+            }
+            ScoringStrategy::WeightedProduct => {
+                let total_weight = self.latency + self.success;
+                if total_weight <= 0.0 {
+                    return 0.0;
+                }
+                // Scale to the same range as the weighted sum.
+                latency_score.powf(self.latency / total_weight)
+                    * success_score.powf(self.success / total_weight)
+                    * total_weight
+            }
+        };
+
+        // Apply confidence factor to penalize nodes with too few samples
+        raw_score * (0.5 + 0.5 * confidence_factor)
+    }
+}
+
+/// The performance statistics of a validator node, which can be persisted across client
+/// restarts.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NodeStatistics {
+    /// Exponential Moving Average of latency in milliseconds.
+    pub ema_latency_ms: f64,
+    /// Exponential Moving Average of success rate (0.0 to 1.0).
+    pub ema_success_rate: f64,
+    /// Total number of requests processed (for monitoring and cold-start handling).
+    pub total_requests: u64,
+    /// When the statistics were last updated.
+    pub updated_at: Timestamp,
+}
+
+impl Default for NodeStatistics {
+    fn default() -> Self {
+        Self {
+            ema_latency_ms: INITIAL_LATENCY_MS,
+            ema_success_rate: INITIAL_SUCCESS_RATE,
+            total_requests: 0,
+            updated_at: Timestamp::from(0),
         }
     }
 }
+
+impl NodeStatistics {
+    /// Returns the statistics as of `now`, taking their age into account: the averages move
+    /// back towards their initial values and the number of requests decreases, both by half
+    /// after each `half_life`.
+    pub fn decayed(&self, now: Timestamp, half_life: TimeDelta) -> Self {
+        let age = now.delta_since(self.updated_at).as_micros() as f64;
+        let half_life = half_life.as_micros().max(1) as f64;
+        let factor = 0.5f64.powf(age / half_life);
+        Self {
+            ema_latency_ms: INITIAL_LATENCY_MS
+                + (self.ema_latency_ms - INITIAL_LATENCY_MS) * factor,
+            ema_success_rate: INITIAL_SUCCESS_RATE
+                + (self.ema_success_rate - INITIAL_SUCCESS_RATE) * factor,
+            total_requests: (self.total_requests as f64 * factor).round() as u64,
+            updated_at: now.max(self.updated_at),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statistics_decay_towards_initial_values() {
+        let statistics = NodeStatistics {
+            ema_latency_ms: 900.0,
+            ema_success_rate: 0.5,
+            total_requests: 40,
+            updated_at: Timestamp::from(0),
+        };
+        let half_life = TimeDelta::from_secs(60);
+        assert_eq!(
+            statistics.decayed(Timestamp::from(0), half_life),
+            statistics
+        );
+        let decayed = statistics.decayed(Timestamp::from(60_000_000), half_life);
+        assert_eq!(decayed.ema_latency_ms, 500.0);
+        assert_eq!(decayed.ema_success_rate, 0.75);
+        assert_eq!(decayed.total_requests, 20);
+        let forgotten = statistics.decayed(Timestamp::from(u64::MAX), half_life);
+        assert_eq!(forgotten.ema_latency_ms, INITIAL_LATENCY_MS);
+        assert_eq!(forgotten.total_requests, 0);
+    }
+
+    #[test]
+    fn weighted_product_penalizes_unreliable_nodes() {
+        let fast_but_unreliable = NodeStatistics {
+            ema_latency_ms: 0.0,
+            ema_success_rate: 0.1,
+            total_requests: 10,
+            ..NodeStatistics::default()
+        };
+        let balanced = NodeStatistics {
+            ema_latency_ms: 2500.0,
+            ema_success_rate: 0.55,
+            total_requests: 10,
+            ..NodeStatistics::default()
+        };
+        let sum = ScoringWeights::default();
+        let product = ScoringWeights {
+            strategy: ScoringStrategy::WeightedProduct,
+            ..sum
+        };
+        assert!(sum.score(&fast_but_unreliable, 5000.0) > sum.score(&balanced, 5000.0));
+        assert!(product.score(&fast_but_unreliable, 5000.0) < product.score(&balanced, 5000.0));
+    }
+}
@@ -19,7 +19,7 @@ use linera_chain::{
     types::ConfirmedBlockCertificate,
     ChainStateView,
 };
-use linera_execution::ExecutionRuntimeContext;
+use linera_execution::{BalanceProof, ExecutionRuntimeContext};
 use linera_storage::ChainRuntimeContext;
 use linera_views::{context::Context, ViewError};
 use serde::{Deserialize, Serialize};
@@ -62,6 +62,10 @@ pub struct ChainInfoQuery {
     #[debug(skip_if = Vec::is_empty)]
     #[cfg_attr(with_testing, strategy(proptest::strategy::Just(Vec::new())))]
     pub request_previous_event_blocks: Vec<StreamId>,
+    /// Request a proof of the balance of a given [`AccountOwner`] under the state hash of the
+    /// latest block.
+    #[debug(skip_if = Option::is_none)]
+    pub request_balance_proof: Option<AccountOwner>,
 }
 
 impl ChainInfoQuery {
@@ -78,6 +82,7 @@ impl ChainInfoQuery {
             request_fallback: false,
             request_sent_certificate_hashes_by_heights: Vec::new(),
             request_previous_event_blocks: Vec::new(),
+            request_balance_proof: None,
         }
     }
 
@@ -101,6 +106,11 @@ impl ChainInfoQuery {
         self
     }
 
+    pub fn with_balance_proof(mut self, owner: AccountOwner) -> Self {
+        self.request_balance_proof = Some(owner);
+        self
+    }
+
     pub fn with_received_log_excluding_first_n(mut self, n: u64) -> Self {
         self.request_received_log_excluding_first_n = Some(n);
         self
@@ -167,6 +177,9 @@ pub struct ChainInfo {
     /// The response to `request_previous_event_blocks`.
     #[debug(skip_if = BTreeMap::is_empty)]
     pub requested_previous_event_blocks: BTreeMap<StreamId, (BlockHeight, CryptoHash)>,
+    /// The response to `request_balance_proof`, if the chain has any blocks.
+    #[debug(skip_if = Option::is_none)]
+    pub requested_balance_proof: Option<BalanceProof>,
 }
 
 impl ChainInfo {
@@ -200,6 +213,27 @@ pub struct ShardInfo {
     pub total_shards: usize,
 }
 
+/// The maximum number of chains in a single chain summaries query.
+pub const MAX_CHAIN_SUMMARIES_PER_QUERY: usize = 1000;
+
+/// A compact summary of a chain's state, as returned by batched chain queries.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(with_testing, derive(Eq, PartialEq))]
+pub struct ChainSummary {
+    /// The chain ID.
+    pub chain_id: ChainId,
+    /// The height after the latest block in the chain.
+    pub next_block_height: BlockHeight,
+    /// The last block hash, if any.
+    #[debug(skip_if = Option::is_none)]
+    pub block_hash: Option<CryptoHash>,
+    /// The number of received message bundles that are waiting to be picked in the next block.
+    pub num_pending_message_bundles: u64,
+    /// Whether the chain manager holds a proposal, a locking block or a timeout certificate
+    /// for the next block.
+    pub has_pending_proposal: bool,
+}
+
 /// An internal request between chains within a validator.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[cfg_attr(with_testing, derive(Eq, PartialEq))]
@@ -280,6 +314,7 @@ impl ChainInfo {
             count_received_log: view.received_log.count(),
             requested_received_log: Vec::new(),
             requested_previous_event_blocks: BTreeMap::new(),
+            requested_balance_proof: None,
         })
     }
 }
@@ -142,6 +142,8 @@ mod tests {
             timestamp: Timestamp::from(0),
             pending_fast_proposal: None,
             epoch: None,
+            inbox_policy: None,
+            inbox_fees: Default::default(),
         }
     }
 
@@ -1,12 +1,12 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::ops::Deref;
+use std::{collections::VecDeque, ops::Deref};
 
 use futures::{Stream, StreamExt as _, TryStreamExt as _};
 use linera_base::{
     crypto::CryptoHash,
-    data_types::{BlockHeight, ChainDescription, Epoch, Timestamp},
+    data_types::{Amount, BlockHeight, ChainDescription, Epoch, MessagePolicy, Timestamp},
     identifiers::{AccountOwner, ChainId},
 };
 
@@ -23,6 +23,49 @@ pub struct Chain {
     pub timestamp: Timestamp,
     pub pending_fast_proposal: Option<PendingProposal>,
     pub epoch: Option<Epoch>,
+    /// How the chain listener processes this chain's inbox, if it differs from the
+    /// listener's configuration.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inbox_policy: Option<InboxPolicy>,
+    /// When the chain listener's recent inbox blocks were created, and the fees charged for
+    /// each, in chronological order. This keeps the hourly fee cap across restarts.
+    #[serde(default, skip_serializing_if = "VecDeque::is_empty")]
+    pub inbox_fees: VecDeque<(Timestamp, Amount)>,
+}
+
+/// How the chain listener automatically processes a chain's inbox.
+///
+/// Unset fields fall back to the listener's configuration.
+#[derive(Default, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct InboxPolicy {
+    /// Which incoming messages to accept, reject or ignore, instead of the client's policy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_policy: Option<MessagePolicy>,
+    /// Wait until at least this many incoming messages are pending before creating a block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_batch_messages: Option<usize>,
+    /// The longest time, in milliseconds, to wait for a batch to fill up after being notified
+    /// of new messages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_batch_delay_ms: Option<u64>,
+    /// The most fees that inbox blocks may be charged in any hour. Processing pauses once the
+    /// cap is reached.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fees_per_hour: Option<Amount>,
+}
+
+impl InboxPolicy {
+    /// Returns this policy, with unset fields taken from `defaults`.
+    pub fn with_defaults(self, defaults: &InboxPolicy) -> Self {
+        Self {
+            message_policy: self
+                .message_policy
+                .or_else(|| defaults.message_policy.clone()),
+            min_batch_messages: self.min_batch_messages.or(defaults.min_batch_messages),
+            max_batch_delay_ms: self.max_batch_delay_ms.or(defaults.max_batch_delay_ms),
+            max_fees_per_hour: self.max_fees_per_hour.or(defaults.max_fees_per_hour),
+        }
+    }
 }
 
 impl From<&ChainInfo> for Chain {
@@ -34,6 +77,8 @@ impl From<&ChainInfo> for Chain {
             timestamp: info.timestamp,
             pending_fast_proposal: None,
             epoch: Some(info.epoch),
+            inbox_policy: None,
+            inbox_fees: VecDeque::new(),
         }
     }
 }
@@ -66,6 +111,8 @@ impl Chain {
             next_block_height: BlockHeight::ZERO,
             pending_fast_proposal: None,
             epoch: Some(current_epoch),
+            inbox_policy: None,
+            inbox_fees: VecDeque::new(),
         }
     }
 
@@ -10,7 +10,7 @@ use std::{
 use futures::{stream::FuturesUnordered, TryStreamExt as _};
 use linera_base::{
     crypto::{CryptoHash, ValidatorPublicKey},
-    data_types::{ArithmeticError, Blob, BlockHeight},
+    data_types::{Amount, ArithmeticError, Blob, BlockHeight},
     identifiers::{BlobId, ChainId, EventId, StreamId},
 };
 use linera_chain::{
@@ -176,6 +176,22 @@ where
             .await?)
     }
 
+    /// Returns the total fees of a block, even if its chain can't pay them.
+    #[instrument(level = "trace", skip_all)]
+    pub async fn block_fees(
+        &self,
+        block: ProposedBlock,
+        round: Option<u32>,
+        published_blobs: Vec<Blob>,
+        policy: BundleExecutionPolicy,
+    ) -> Result<Amount, LocalNodeError> {
+        Ok(self
+            .node
+            .state
+            .block_fees(block, round, published_blobs, policy)
+            .await?)
+    }
+
     /// Reads blobs from storage.
     pub async fn read_blobs_from_storage(
         &self,
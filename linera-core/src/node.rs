@@ -32,7 +32,7 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::{
-    data_types::{ChainInfoQuery, ChainInfoResponse},
+    data_types::{ChainInfoQuery, ChainInfoResponse, ChainSummary, MAX_CHAIN_SUMMARIES_PER_QUERY},
     worker::{Notification, WorkerError},
 };
 
@@ -96,6 +96,15 @@ pub trait ValidatorNode {
         query: ChainInfoQuery,
     ) -> Result<ChainInfoResponse, NodeError>;
 
+    /// Returns a summary of each of the given chains, in the same order, in a single round
+    /// trip. At most [`MAX_CHAIN_SUMMARIES_PER_QUERY`] chains can be queried at once. A chain
+    /// that cannot be summarized, e.g. because the validator doesn't know it, only fails its
+    /// own entry.
+    async fn handle_chain_summaries_query(
+        &self,
+        chain_ids: Vec<ChainId>,
+    ) -> Result<Vec<Result<ChainSummary, NodeError>>, NodeError>;
+
     /// Gets the version info for this validator node.
     async fn get_version_info(&self) -> Result<VersionInfo, NodeError>;
 
@@ -353,6 +362,12 @@ pub enum NodeError {
 
     #[error("No validators available to handle the request")]
     NoValidators,
+
+    #[error("Validator is rate limiting requests; retry after {retry_after_ms} ms")]
+    RateLimited { retry_after_ms: u64 },
+
+    #[error("Too many chains queried at once: {requested} (the maximum is {max})")]
+    TooManyChainsQueried { requested: usize, max: usize },
 }
 
 impl NodeError {
@@ -373,6 +388,7 @@ impl NodeError {
             | NodeError::UnexpectedBlockHeight { .. }
             | NodeError::InactiveChain(_)
             | NodeError::InvalidTimestamp { .. }
+            | NodeError::RateLimited { .. }
             | NodeError::MissingCertificateValue => true,
 
             // Unexpected: network issues, validator misbehavior, or internal problems.
@@ -400,11 +416,25 @@ impl NodeError {
             | NodeError::ResponseHandlingError { .. }
             | NodeError::MissingCertificatesByHeights { .. }
             | NodeError::TooManyCertificatesReturned { .. }
+            | NodeError::TooManyChainsQueried { .. }
             | NodeError::NoValidators => false,
         }
     }
 }
 
+impl NodeError {
+    /// Returns an error if more than [`MAX_CHAIN_SUMMARIES_PER_QUERY`] chains are queried.
+    pub fn check_chain_summaries_query(chain_ids: &[ChainId]) -> Result<(), NodeError> {
+        if chain_ids.len() > MAX_CHAIN_SUMMARIES_PER_QUERY {
+            return Err(NodeError::TooManyChainsQueried {
+                requested: chain_ids.len(),
+                max: MAX_CHAIN_SUMMARIES_PER_QUERY,
+            });
+        }
+        Ok(())
+    }
+}
+
 impl From<tonic::Status> for NodeError {
     fn from(status: tonic::Status) -> Self {
         Self::GrpcError {
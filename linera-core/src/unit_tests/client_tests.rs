@@ -13,7 +13,9 @@ use futures::StreamExt;
 use linera_base::{
     crypto::{AccountSecretKey, CryptoHash, InMemorySigner},
     data_types::*,
-    identifiers::{Account, AccountOwner, ApplicationId, BlobId, BlobType, GenericApplicationId},
+    identifiers::{
+        Account, AccountOwner, ApplicationId, BlobId, BlobType, ChainId, GenericApplicationId,
+    },
     ownership::{ChainOwnership, TimeoutConfig},
 };
 use linera_chain::{
@@ -44,9 +46,10 @@ use crate::test_utils::ScyllaDbStorageBuilder;
 use crate::test_utils::ServiceStorageBuilder;
 use crate::{
     client::{
-        chain_client::{self, ChainClient},
+        chain_client::{self, ChainClient, SignedProposal, UnsignedProposal},
         ClientOutcome, ListeningMode,
     },
+    data_types::MAX_CHAIN_SUMMARIES_PER_QUERY,
     local_node::LocalNodeError,
     node::{
         NodeError::{self, ClientIoError},
@@ -304,7 +307,7 @@ where
     B: StorageBuilder,
 {
     let mut signer = InMemorySigner::new(None);
-    let new_public_key = signer.generate_new();
+    let new_public_key = signer.generate_new().unwrap();
     let new_owner = AccountOwner::from(new_public_key);
     let mut builder = TestBuilder::new(storage_builder, 4, 1, signer)
         .await?
@@ -340,6 +343,88 @@ where
     Ok(())
 }
 
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new(); "storage_service"))]
+#[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
+#[cfg_attr(feature = "dynamodb", test_case(DynamoDbStorageBuilder::default(); "dynamo_db"))]
+#[cfg_attr(feature = "scylladb", test_case(ScyllaDbStorageBuilder::default(); "scylla_db"))]
+#[test_log::test(tokio::test)]
+async fn test_offline_signed_proposal<B>(storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let signer = InMemorySigner::new(None);
+    let mut builder = TestBuilder::new(storage_builder, 4, 1, signer)
+        .await?
+        .with_policy(ResourceControlPolicy::only_fuel());
+    let mut sender = builder.add_root_chain(1, Amount::from_tokens(4)).await?;
+    let receiver = builder.add_root_chain(2, Amount::ZERO).await?;
+
+    // The owner's key is only known to the offline signer.
+    let mut offline_signer = InMemorySigner::new(None);
+    let offline_owner = AccountOwner::from(offline_signer.generate_new().unwrap());
+    sender
+        .transfer_ownership(offline_owner)
+        .await
+        .unwrap_ok_committed();
+    sender.set_preferred_owner(offline_owner);
+    assert_matches!(
+        sender.identity().await,
+        Err(chain_client::Error::CannotFindKeyForChain(_))
+    );
+
+    let transfer = |amount| {
+        vec![Operation::system(SystemOperation::Transfer {
+            owner: AccountOwner::CHAIN,
+            recipient: Account::chain(receiver.chain_id()),
+            amount,
+        })]
+    };
+    let unsigned = sender
+        .prepare_unsigned_proposal(transfer(Amount::ONE), vec![])
+        .await?;
+    let stale_unsigned = sender
+        .prepare_unsigned_proposal(transfer(Amount::from_tokens(2)), vec![])
+        .await?;
+    assert_eq!(unsigned.height(), BlockHeight::from(1));
+    assert_eq!(unsigned.owner, offline_owner);
+    // Nothing was stored locally.
+    assert!(sender.pending_proposal().await.is_none());
+
+    // Proposals are passed between hosts as JSON files.
+    let unsigned: UnsignedProposal = serde_json::from_str(&serde_json::to_string(&unsigned)?)?;
+    let signed = unsigned.sign(&offline_signer).await?;
+    let signed: SignedProposal = serde_json::from_str(&serde_json::to_string(&signed)?)?;
+    let certificate = sender.submit_signed_proposal(signed.clone()).await?;
+    assert_eq!(certificate.hash(), signed.expected_block_hash);
+    assert_eq!(sender.local_balance().await?, Amount::from_millis(3000));
+    assert_eq!(
+        builder
+            .check_that_validators_have_certificate(sender.chain_id(), BlockHeight::from(1), 3)
+            .await,
+        Some(certificate)
+    );
+
+    // The second proposal was prepared for the same height, so it is now outdated.
+    let stale_signed = stale_unsigned.sign(&offline_signer).await?;
+    assert_matches!(
+        sender.submit_signed_proposal(stale_signed).await,
+        Err(chain_client::Error::OutdatedProposal(_))
+    );
+    // A proposal signed by a different key is rejected.
+    let mut signed = sender
+        .prepare_unsigned_proposal(transfer(Amount::ONE), vec![])
+        .await?
+        .sign(&offline_signer)
+        .await?;
+    signed.proposal.content.round = Round::Validator(0);
+    assert_matches!(
+        sender.submit_signed_proposal(signed).await,
+        Err(chain_client::Error::InvalidSignedProposal(_))
+    );
+    Ok(())
+}
+
 #[test_case(MemoryStorageBuilder::default(); "memory")]
 #[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new(); "storage_service"))]
 #[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
@@ -356,7 +441,7 @@ where
         .with_policy(ResourceControlPolicy::only_fuel());
     let sender = builder.add_root_chain(1, Amount::from_tokens(4)).await?;
 
-    let new_owner: AccountOwner = builder.signer.generate_new().into();
+    let new_owner: AccountOwner = builder.signer.generate_new().unwrap().into();
     let certificate = sender.transfer_ownership(new_owner).await.unwrap().unwrap();
     assert_eq!(
         sender.chain_info().await?.next_block_height,
@@ -399,7 +484,7 @@ where
     B: StorageBuilder,
 {
     let mut signer = InMemorySigner::new(None);
-    let new_owner = signer.generate_new().into();
+    let new_owner = signer.generate_new().unwrap().into();
     let mut builder = TestBuilder::new(storage_builder, 4, 0, signer).await?;
     let sender = builder.add_root_chain(1, Amount::from_tokens(4)).await?;
     let certificate = sender
@@ -515,7 +600,7 @@ where
     B: StorageBuilder,
 {
     let mut signer = InMemorySigner::new(None);
-    let regular_owner = signer.generate_new().into();
+    let regular_owner = signer.generate_new().unwrap().into();
     let mut builder = TestBuilder::new(storage_builder, 4, 0, signer).await?;
     let mut sender = builder.add_root_chain(1, Amount::from_tokens(4)).await?;
     let super_owner = sender.identity().await?;
@@ -557,7 +642,7 @@ where
     B: StorageBuilder,
 {
     let mut signer = InMemorySigner::new(None);
-    let new_public_key = signer.generate_new();
+    let new_public_key = signer.generate_new().unwrap();
     let mut builder = TestBuilder::new(storage_builder, 4, 1, signer).await?;
     // New chains use the admin chain to verify their creation certificate.
     let _admin = builder.add_root_chain(0, Amount::ZERO).await?;
@@ -605,7 +690,7 @@ where
     let _admin = builder.add_root_chain(0, Amount::ZERO).await?;
     let sender = builder.add_root_chain(1, Amount::from_tokens(4)).await?;
     let parent = builder.add_root_chain(2, Amount::ZERO).await?;
-    let new_public_key = builder.signer.generate_new();
+    let new_public_key = builder.signer.generate_new().unwrap();
 
     let admin_config = builder.admin_description().unwrap().config();
 
@@ -719,7 +804,7 @@ where
     // New chains use the admin chain to verify their creation certificate.
     let _admin = builder.add_root_chain(0, Amount::ZERO).await?;
     let sender = builder.add_root_chain(1, Amount::from_tokens(4)).await?;
-    let new_public_key = builder.signer.generate_new();
+    let new_public_key = builder.signer.generate_new().unwrap();
     // Open the new chain. We are both regular and super owner.
     let ownership = ChainOwnership::single(new_public_key.into())
         .with_regular_owner(new_public_key.into(), 100);
@@ -1088,6 +1173,226 @@ where
     Ok(())
 }
 
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new(); "storage_service"))]
+#[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
+#[cfg_attr(feature = "dynamodb", test_case(DynamoDbStorageBuilder::default(); "dynamo_db"))]
+#[cfg_attr(feature = "scylladb", test_case(ScyllaDbStorageBuilder::default(); "scylla_db"))]
+#[test_log::test(tokio::test)]
+async fn test_chain_summaries_query<B>(storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let signer = InMemorySigner::new(None);
+    let mut builder = TestBuilder::new(storage_builder, 4, 0, signer)
+        .await?
+        .with_policy(ResourceControlPolicy::only_fuel());
+    let client1 = builder.add_root_chain(1, Amount::from_tokens(3)).await?;
+    let client2 = builder.add_root_chain(2, Amount::ZERO).await?;
+    let cert = client1
+        .transfer_to_account(
+            AccountOwner::CHAIN,
+            Amount::from_tokens(2),
+            Account::chain(client2.chain_id()),
+        )
+        .await
+        .unwrap_ok_committed();
+
+    // The summaries are returned in the order of the query, and an unknown chain only fails
+    // its own entry.
+    let unknown_chain_id = ChainId(CryptoHash::test_hash("unknown chain"));
+    let chain_ids = vec![client2.chain_id(), client1.chain_id(), unknown_chain_id];
+    let mut results = builder
+        .node(0)
+        .handle_chain_summaries_query(chain_ids)
+        .await?;
+    assert_eq!(results.len(), 3);
+    assert_matches!(
+        results.pop(),
+        Some(Err(NodeError::InactiveChain(chain_id))) if chain_id == unknown_chain_id
+    );
+    let summaries = results.into_iter().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(summaries[0].chain_id, client2.chain_id());
+    assert_eq!(summaries[0].next_block_height, BlockHeight::ZERO);
+    assert_eq!(summaries[0].num_pending_message_bundles, 1);
+    assert_eq!(summaries[1].chain_id, client1.chain_id());
+    assert_eq!(summaries[1].next_block_height, BlockHeight::from(1));
+    assert_eq!(summaries[1].block_hash, Some(cert.hash()));
+    assert_eq!(summaries[1].num_pending_message_bundles, 0);
+    assert!(!summaries[1].has_pending_proposal);
+
+    // Once the transfer is received, the recipient has no pending message bundles left.
+    client2.synchronize_from_validators().await?;
+    client2.process_inbox().await?;
+    let summaries = builder
+        .node(0)
+        .handle_chain_summaries_query(vec![client2.chain_id()])
+        .await?
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(summaries[0].next_block_height, BlockHeight::from(1));
+    assert_eq!(summaries[0].num_pending_message_bundles, 0);
+    // Synchronizing a chain that is up to date leaves it unchanged.
+    client1.synchronize_from_validators().await?;
+    assert_eq!(
+        client1.chain_info().await?.next_block_height,
+        BlockHeight::from(1)
+    );
+
+    // Too large batches are rejected.
+    let chain_ids = vec![client1.chain_id(); MAX_CHAIN_SUMMARIES_PER_QUERY + 1];
+    assert_matches!(
+        builder
+            .node(0)
+            .handle_chain_summaries_query(chain_ids)
+            .await,
+        Err(NodeError::TooManyChainsQueried { .. })
+    );
+    Ok(())
+}
+
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new(); "storage_service"))]
+#[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
+#[cfg_attr(feature = "dynamodb", test_case(DynamoDbStorageBuilder::default(); "dynamo_db"))]
+#[cfg_attr(feature = "scylladb", test_case(ScyllaDbStorageBuilder::default(); "scylla_db"))]
+#[test_log::test(tokio::test)]
+async fn test_balance_proof_under_state_hash<B>(storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let signer = InMemorySigner::new(None);
+    let mut builder = TestBuilder::new(storage_builder, 4, 0, signer)
+        .await?
+        .with_policy(ResourceControlPolicy {
+            commit_state_roots: true,
+            ..ResourceControlPolicy::only_fuel()
+        });
+    let client1 = builder.add_root_chain(1, Amount::from_tokens(3)).await?;
+    let client2 = builder.add_root_chain(2, Amount::ZERO).await?;
+    for balance in [Amount::from_tokens(2), Amount::ONE] {
+        let certificate = client1
+            .transfer_to_account(
+                AccountOwner::CHAIN,
+                Amount::ONE,
+                Account::chain(client2.chain_id()),
+            )
+            .await
+            .unwrap_ok_committed();
+        // The state after the latest block is proven under the block's state hash.
+        let chain = client1.chain_state_view().await?;
+        let proof = chain.balance_proof(AccountOwner::CHAIN).await?;
+        let state_hash = certificate.block().header.state_hash;
+        assert_eq!(proof.verify(&state_hash, AccountOwner::CHAIN)?, balance);
+        // Owners without a balance are proven to be absent.
+        let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+        let proof = chain.balance_proof(owner).await?;
+        assert_eq!(proof.verify(&state_hash, owner)?, Amount::ZERO);
+    }
+    Ok(())
+}
+
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new(); "storage_service"))]
+#[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
+#[cfg_attr(feature = "dynamodb", test_case(DynamoDbStorageBuilder::default(); "dynamo_db"))]
+#[cfg_attr(feature = "scylladb", test_case(ScyllaDbStorageBuilder::default(); "scylla_db"))]
+#[test_log::test(tokio::test)]
+async fn test_light_client_follows_chain<B>(storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let signer = InMemorySigner::new(None);
+    // One of the four validators is faulty and reports wrong balances.
+    let mut builder = TestBuilder::new(storage_builder, 4, 1, signer)
+        .await?
+        .with_policy(ResourceControlPolicy {
+            commit_state_roots: true,
+            ..ResourceControlPolicy::default()
+        });
+    let sender = builder.add_root_chain(1, Amount::from_tokens(3)).await?;
+    let receiver = builder.add_root_chain(2, Amount::ZERO).await?;
+    let cert = sender
+        .transfer_to_account(
+            AccountOwner::CHAIN,
+            Amount::from_tokens(2),
+            Account::chain(receiver.chain_id()),
+        )
+        .await
+        .unwrap_ok_committed();
+
+    let light = builder
+        .make_client_with_options(
+            sender.chain_id(),
+            None,
+            BlockHeight::ZERO,
+            chain_client::Options {
+                light_client: true,
+                ..chain_client::Options::test_default()
+            },
+            true,
+        )
+        .await?;
+    let info = light.synchronize_from_validators().await?;
+    assert_eq!(info.next_block_height, BlockHeight::from(1));
+    assert_eq!(info.block_hash, Some(cert.hash()));
+    assert_eq!(info.chain_balance, sender.local_balance().await?);
+    let state = light
+        .client
+        .light_chain_state(sender.chain_id())
+        .expect("the chain state was verified");
+    assert_eq!(state.state_hash, Some(cert.block().header.state_hash));
+    assert_eq!(state.chain_balance, Amount::ONE);
+    // The block was verified, but not executed locally.
+    assert_eq!(
+        light.chain_info().await?.next_block_height,
+        BlockHeight::ZERO
+    );
+    // Light chains don't propose blocks.
+    assert_matches!(
+        light.prepare_chain().await,
+        Err(chain_client::Error::LightChain(chain_id)) if chain_id == sender.chain_id()
+    );
+    assert_matches!(
+        light.execute_operations(Vec::new(), Vec::new()).await,
+        Err(chain_client::Error::LightChain(_))
+    );
+
+    let cert = sender
+        .transfer_to_account(
+            AccountOwner::CHAIN,
+            Amount::ONE,
+            Account::chain(receiver.chain_id()),
+        )
+        .await
+        .unwrap_ok_committed();
+    assert_eq!(light.query_balance().await?, Amount::ZERO);
+    let state = light.synchronize_light_state().await?;
+    assert_eq!(state.next_block_height, BlockHeight::from(2));
+    assert_eq!(state.block_hash, Some(cert.hash()));
+    assert_eq!(state.chain_balance, sender.local_balance().await?);
+
+    // A chain without blocks has the balance of its description.
+    let new_chain = builder.add_root_chain(3, Amount::from_tokens(5)).await?;
+    let light_receiver = builder
+        .make_client_with_options(
+            new_chain.chain_id(),
+            None,
+            BlockHeight::ZERO,
+            chain_client::Options {
+                light_client: true,
+                ..chain_client::Options::test_default()
+            },
+            true,
+        )
+        .await?;
+    assert_eq!(
+        light_receiver.query_balance().await?,
+        Amount::from_tokens(5)
+    );
+    Ok(())
+}
+
 #[test_case(MemoryStorageBuilder::default(); "memory")]
 #[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new(); "storage_service"))]
 #[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
@@ -1382,7 +1687,185 @@ where
         .await;
     // We have balance=3, we try to burn 3 tokens but the operation itself
     // costs 1 microtoken so we don't have enough balance to pay for it.
-    assert_fees_exceed_funding(obtained_error);
+    assert_fees_exceed_funding(
+        obtained_error,
+        Amount::from_tokens(3).saturating_add(Amount::from_micros(1)),
+        Amount::from_tokens(3),
+    );
+    Ok(())
+}
+
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new(); "storage_service"))]
+#[test_log::test(tokio::test)]
+async fn test_process_inbox_block_reports_fees<B>(storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let signer = InMemorySigner::new(None);
+    let mut builder = TestBuilder::new(storage_builder, 4, 1, signer)
+        .await?
+        .with_policy(ResourceControlPolicy::all_categories());
+    let sender = builder.add_root_chain(1, Amount::from_tokens(4)).await?;
+    let receiver = builder.add_root_chain(2, Amount::ONE).await?;
+
+    sender
+        .transfer(
+            AccountOwner::CHAIN,
+            Amount::ONE,
+            Account::chain(receiver.chain_id()),
+        )
+        .await
+        .unwrap_ok_committed();
+    receiver.synchronize_from_validators().await?;
+
+    // The block receives the transfer and pays the reported fees.
+    let (certificate, fees) = receiver.process_inbox_block().await?.unwrap().unwrap();
+    assert_eq!(certificate.block().header.height, BlockHeight::ZERO);
+    assert_eq!(
+        receiver.local_balance().await?,
+        Amount::from_tokens(2).try_sub(fees)?
+    );
+    assert!(receiver.process_inbox_block().await?.unwrap().is_none());
+    Ok(())
+}
+
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new(); "storage_service"))]
+#[test_log::test(tokio::test)]
+async fn test_claim_from_funding_chain<B>(storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let signer = InMemorySigner::new(None);
+    let mut policy = ResourceControlPolicy::only_fuel();
+    policy.operation = Amount::from_micros(1);
+    let mut builder = TestBuilder::new(storage_builder, 4, 1, signer)
+        .await?
+        .with_policy(policy);
+    let funder = builder.add_root_chain(1, Amount::from_tokens(4)).await?;
+    let owner = funder.identity().await?;
+    let (description, _) = funder
+        .open_chain(
+            ChainOwnership::single(owner),
+            ApplicationPermissions::default(),
+            Amount::from_tokens(1),
+        )
+        .await
+        .unwrap_ok_committed();
+    let chain_id = description.id();
+    funder
+        .transfer_to_account(
+            AccountOwner::CHAIN,
+            Amount::ONE,
+            Account::new(funder.chain_id(), owner),
+        )
+        .await
+        .unwrap_ok_committed();
+
+    // Without a funding chain, the client refuses to propose a block it cannot pay for.
+    let mut client = builder
+        .make_client(chain_id, None, BlockHeight::ZERO)
+        .await?;
+    client.set_preferred_owner(owner);
+    client.synchronize_from_validators().await?;
+    let obtained_error = client
+        .burn(AccountOwner::CHAIN, Amount::from_tokens(1))
+        .await;
+    assert_fees_exceed_funding(
+        obtained_error,
+        Amount::from_tokens(1).saturating_add(Amount::from_micros(1)),
+        Amount::from_tokens(1),
+    );
+    assert_eq!(
+        client.chain_info().await?.next_block_height,
+        BlockHeight::ZERO
+    );
+
+    // With a funding chain, the missing amount is claimed from the signer's account there.
+    let mut client = builder
+        .make_client_with_options(
+            chain_id,
+            None,
+            BlockHeight::ZERO,
+            chain_client::Options {
+                funding_chain_id: Some(funder.chain_id()),
+                ..chain_client::Options::test_default()
+            },
+            false,
+        )
+        .await?;
+    client.set_preferred_owner(owner);
+    client.synchronize_from_validators().await?;
+    client
+        .burn(AccountOwner::CHAIN, Amount::from_tokens(1))
+        .await
+        .unwrap_ok_committed();
+    assert_eq!(client.local_balance().await?, Amount::ZERO);
+    assert_eq!(client.local_owner_balance(owner).await?, Amount::ZERO);
+    Ok(())
+}
+
+/// The claim from the funding chain covers all the fees of the block, not only the first fee
+/// that couldn't be paid.
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new(); "storage_service"))]
+#[test_log::test(tokio::test)]
+async fn test_claim_total_fees_from_funding_chain<B>(storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let signer = InMemorySigner::new(None);
+    let mut policy = ResourceControlPolicy::only_fuel();
+    policy.operation = Amount::ONE;
+    let mut builder = TestBuilder::new(storage_builder, 4, 1, signer)
+        .await?
+        .with_policy(policy);
+    let funder = builder.add_root_chain(1, Amount::from_tokens(10)).await?;
+    let owner = funder.identity().await?;
+    let (description, _) = funder
+        .open_chain(
+            ChainOwnership::single(owner),
+            ApplicationPermissions::default(),
+            Amount::from_millis(1_500),
+        )
+        .await
+        .unwrap_ok_committed();
+    let chain_id = description.id();
+    funder
+        .transfer_to_account(
+            AccountOwner::CHAIN,
+            Amount::from_tokens(5),
+            Account::new(funder.chain_id(), owner),
+        )
+        .await
+        .unwrap_ok_committed();
+
+    let mut client = builder
+        .make_client_with_options(
+            chain_id,
+            None,
+            BlockHeight::ZERO,
+            chain_client::Options {
+                funding_chain_id: Some(funder.chain_id()),
+                ..chain_client::Options::test_default()
+            },
+            false,
+        )
+        .await?;
+    client.set_preferred_owner(owner);
+    client.synchronize_from_validators().await?;
+
+    // The block costs 3 tokens, but the second operation's fee is the first that fails.
+    let operation = Operation::system(SystemOperation::ChangeApplicationPermissions(
+        ApplicationPermissions::default(),
+    ));
+    client
+        .execute_operations(vec![operation; 3], vec![])
+        .await
+        .unwrap_ok_committed();
+    assert_eq!(client.local_balance().await?, Amount::ZERO);
+    assert_eq!(client.local_owner_balance(owner).await?, Amount::ZERO);
     Ok(())
 }
 
@@ -1412,7 +1895,7 @@ where
     // first block a burn rather than a transfer to the receiver: that keeps the
     // message-sending blocks at height >= 1, so preprocessing them never requires
     // the sender's `ChainDescription` (only height-0 blocks do).
-    let sender_public_key = builder.signer.generate_new();
+    let sender_public_key = builder.signer.generate_new().unwrap();
     let sender_ownership = ChainOwnership::single(sender_public_key.into())
         .with_regular_owner(sender_public_key.into(), 100);
     let (sender_description, _creation_certificate) = Box::pin(owner.open_chain(
@@ -1522,7 +2005,7 @@ where
     let client_1a = builder.add_root_chain(1, Amount::ZERO).await?;
     let owner_1a = client_1a.identity().await.unwrap();
     let chain_1 = client_1a.chain_id();
-    let pk_1b = builder.signer.generate_new();
+    let pk_1b = builder.signer.generate_new().unwrap();
     let owner_1b = pk_1b.into();
 
     let owners = [(owner_1a, 50), (owner_1b, 50)];
@@ -1540,7 +2023,7 @@ where
     let client_2a = builder.add_root_chain(2, Amount::from_tokens(10)).await?;
     let owner_2a = client_2a.identity().await.unwrap();
     let chain_2 = client_2a.chain_id();
-    let pk_2b = builder.signer.generate_new();
+    let pk_2b = builder.signer.generate_new().unwrap();
     let owner_2b = pk_2b.into();
 
     let owners = [(owner_2a, 50), (owner_2b, 50)];
@@ -1701,7 +2184,7 @@ where
     let chain_id2 = client2_a.chain_id();
 
     let owner2_a = client2_a.identity().await.unwrap();
-    let owner2_b = builder.signer.generate_new().into();
+    let owner2_b = builder.signer.generate_new().unwrap().into();
 
     let owner_change_op = Operation::system(SystemOperation::ChangeOwnership {
         super_owners: Vec::new(),
@@ -1822,7 +2305,7 @@ where
     B: StorageBuilder,
 {
     let mut signer = InMemorySigner::new(None);
-    let owner2 = signer.generate_new().into();
+    let owner2 = signer.generate_new().unwrap().into();
     let mut builder = TestBuilder::new(storage_builder, 4, 0, signer).await?;
     let client1 = builder.add_root_chain(1, Amount::ONE).await?;
     let chain_id = client1.chain_id();
@@ -1915,8 +2398,8 @@ where
     let chain_id3 = client3_a.chain_id();
 
     let owner3_a = client3_a.identity().await.unwrap();
-    let owner3_b = builder.signer.generate_new().into();
-    let owner3_c = builder.signer.generate_new().into();
+    let owner3_b = builder.signer.generate_new().unwrap().into();
+    let owner3_c = builder.signer.generate_new().unwrap().into();
 
     let owner_change_op = Operation::system(SystemOperation::ChangeOwnership {
         super_owners: Vec::new(),
@@ -2377,7 +2860,7 @@ where
 {
     // Configure a chain with two regular and no super owners.
     let mut signer = InMemorySigner::new(None);
-    let owner1 = signer.generate_new().into();
+    let owner1 = signer.generate_new().unwrap().into();
     let mut builder = TestBuilder::new(storage_builder, 4, 1, signer).await?;
     let client0 = builder.add_root_chain(1, Amount::from_tokens(10)).await?;
     let chain_id = client0.chain_id();
@@ -2526,7 +3009,7 @@ where
     let client0 = builder.add_root_chain(1, Amount::from_tokens(10)).await?;
     let chain_id = client0.chain_id();
     let owner0 = client0.identity().await.unwrap();
-    let owner1 = builder.signer.generate_new().into();
+    let owner1 = builder.signer.generate_new().unwrap().into();
 
     let owners = [(owner0, 100), (owner1, 100)];
     let timeout_config = TimeoutConfig {
@@ -2641,7 +3124,7 @@ where
     client0.options_mut().allow_fast_blocks = true;
     let chain_id = client0.chain_id();
     let owner0 = client0.identity().await.unwrap();
-    let owner1 = builder.signer.generate_new().into();
+    let owner1 = builder.signer.generate_new().unwrap().into();
 
     let timeout_config = TimeoutConfig {
         fast_round_duration: Some(TimeDelta::from_secs(5)),
@@ -3190,7 +3673,7 @@ where
     B: StorageBuilder,
 {
     let mut signer = InMemorySigner::new(None);
-    let new_public_key = signer.generate_new();
+    let new_public_key = signer.generate_new().unwrap();
     let mut builder = TestBuilder::new(storage_builder, 4, 0, signer).await?;
 
     let admin_client = builder.add_root_chain(0, Amount::from_tokens(1000)).await?;
@@ -3296,7 +3779,7 @@ where
     B: StorageBuilder,
 {
     let mut signer = InMemorySigner::new(None);
-    let new_public_key = signer.generate_new();
+    let new_public_key = signer.generate_new().unwrap();
     let mut builder = TestBuilder::new(storage_builder, 4, 0, signer).await?;
 
     let admin_client = builder.add_root_chain(0, Amount::from_tokens(1000)).await?;
@@ -3650,7 +4133,7 @@ where
     let sender = builder.add_root_chain(2, Amount::from_tokens(10)).await?;
 
     // Generate a new key in the same signer that the parent uses.
-    let new_public_key = builder.signer.generate_new();
+    let new_public_key = builder.signer.generate_new().unwrap();
 
     // Open a new chain for the key we own.
     let (new_description, _certificate) = parent
@@ -3757,6 +4240,70 @@ where
     Ok(())
 }
 
+/// Tests the `single_leader_rounds_only` option: blocks skip the multi-leader rounds, and only
+/// the leader of the first single-leader round proposes them.
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new(); "storage_service"))]
+#[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
+#[cfg_attr(feature = "dynamodb", test_case(DynamoDbStorageBuilder::default(); "dynamo_db"))]
+#[cfg_attr(feature = "scylladb", test_case(ScyllaDbStorageBuilder::default(); "scylla_db"))]
+#[test_log::test(tokio::test)]
+async fn test_single_leader_rounds_only<B>(storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let mut signer = InMemorySigner::new(None);
+    let other_owner = AccountOwner::from(signer.generate_new().unwrap());
+    let mut builder = TestBuilder::new(storage_builder, 4, 0, signer).await?;
+    let mut client = builder.add_root_chain(1, Amount::from_tokens(4)).await?;
+    let owner = client.identity().await?;
+    let change_ownership = |first_leader| {
+        Operation::system(SystemOperation::ChangeOwnership {
+            super_owners: vec![],
+            owners: vec![(owner, 100), (other_owner, 100)],
+            first_leader: Some(first_leader),
+            multi_leader_rounds: 10,
+            open_multi_leader_rounds: false,
+            timeout_config: TimeoutConfig::default(),
+        })
+    };
+    client
+        .execute_operation(change_ownership(owner))
+        .await
+        .unwrap_ok_committed();
+
+    // Without the option, the block is proposed in the first multi-leader round.
+    let certificate = client
+        .burn(AccountOwner::CHAIN, Amount::from_tokens(1))
+        .await
+        .unwrap_ok_committed();
+    assert_eq!(certificate.round, Round::MultiLeader(0));
+
+    // With the option, the leader proposes it in the first single-leader round.
+    client.options_mut().single_leader_rounds_only = true;
+    let certificate = client
+        .burn(AccountOwner::CHAIN, Amount::from_tokens(1))
+        .await
+        .unwrap_ok_committed();
+    assert_eq!(certificate.round, Round::SingleLeader(0));
+
+    // Another owner leads the first single-leader round now, so the client cannot propose.
+    client.options_mut().single_leader_rounds_only = false;
+    client
+        .execute_operation(change_ownership(other_owner))
+        .await
+        .unwrap_ok_committed();
+    client.options_mut().single_leader_rounds_only = true;
+    assert_matches!(
+        client
+            .burn(AccountOwner::CHAIN, Amount::from_tokens(1))
+            .await,
+        Err(chain_client::Error::BlockProposalError(_))
+    );
+
+    Ok(())
+}
+
 /// Tests that message bundles exceeding block limits are removed (not rejected) when
 /// they're not the first transaction, allowing retry in a later block.
 #[test_case(MemoryStorageBuilder::default(); "memory")]
@@ -3859,7 +4406,7 @@ where
     // Create a new client for the same chain with a non-owner key.
     // This simulates a client that was assigned the chain (e.g., via a faucet with
     // open_multi_leader_rounds) without having the owner key.
-    let non_owner: AccountOwner = builder.signer.generate_new().into();
+    let non_owner: AccountOwner = builder.signer.generate_new().unwrap().into();
     let mut non_owner_client = builder
         .make_client(chain_id, info.block_hash, info.next_block_height)
         .await?;
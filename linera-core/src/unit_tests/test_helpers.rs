@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use assert_matches::assert_matches;
+use linera_base::data_types::Amount;
 use linera_chain::{ChainError, ChainExecutionContext};
 use linera_execution::ExecutionError;
 
@@ -37,23 +38,22 @@ pub fn assert_insufficient_balance_during_operation<T>(
 }
 
 /// Asserts that an error is due to insufficient funding for fees.
-pub fn assert_fees_exceed_funding<T>(obtained_error: Result<T, chain_client::Error>) {
-    let chain_client::Error::LocalNodeError(LocalNodeError::WorkerError(WorkerError::ChainError(
-        error,
-    ))) = obtained_error.err().unwrap()
+pub fn assert_fees_exceed_funding<T>(
+    obtained_error: Result<T, chain_client::Error>,
+    expected_required: Amount,
+    expected_available: Amount,
+) {
+    let error = obtained_error.err().unwrap();
+    let chain_client::Error::InsufficientFunding {
+        required,
+        available,
+        ..
+    } = error
     else {
-        panic!("Expected a chain_client::Error::LocalNodeError with a WorkerError::ChainError");
+        panic!("Expected a chain_client::Error::InsufficientFunding, found: {error:#?}");
     };
-
-    let ChainError::ExecutionError(execution_error, _context) = *error else {
-        panic!("Expected a ChainError::ExecutionError, found: {error:#?}");
-    };
-
-    assert_matches!(
-        *execution_error,
-        ExecutionError::FeesExceedFunding { .. },
-        "Expected ExecutionError::FeesExceedFunding, found: {execution_error:#?}"
-    );
+    assert_eq!(required, expected_required);
+    assert_eq!(available, expected_available);
 }
 
 /// Asserts that an error is due to insufficient funding with a generic execution context.
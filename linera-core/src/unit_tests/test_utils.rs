@@ -171,6 +171,16 @@ where
         .await
     }
 
+    async fn handle_chain_summaries_query(
+        &self,
+        chain_ids: Vec<ChainId>,
+    ) -> Result<Vec<Result<ChainSummary, NodeError>>, NodeError> {
+        self.spawn_and_receive(move |validator, sender| {
+            validator.do_handle_chain_summaries_query(chain_ids, sender)
+        })
+        .await
+    }
+
     async fn subscribe(&self, chains: Vec<ChainId>) -> Result<NotificationStream, NodeError> {
         self.spawn_and_receive(move |validator, sender| validator.do_subscribe(chains, sender))
             .await
@@ -507,6 +517,44 @@ where
         sender.send(result)
     }
 
+    async fn do_handle_chain_summaries_query(
+        self,
+        chain_ids: Vec<ChainId>,
+        sender: oneshot::Sender<Result<Vec<Result<ChainSummary, NodeError>>, NodeError>>,
+    ) -> Result<(), Result<Vec<Result<ChainSummary, NodeError>>, NodeError>> {
+        let validator = self.client.lock().await;
+        let result = match self.fault_type {
+            FaultType::Offline => Err(NodeError::ClientIoError {
+                error: "offline".to_string(),
+            }),
+            FaultType::NoChains => Ok(chain_ids
+                .into_iter()
+                .map(|chain_id| Err(NodeError::InactiveChain(chain_id)))
+                .collect()),
+            FaultType::Honest
+            | FaultType::DontSendConfirmVote
+            | FaultType::DontProcessValidated
+            | FaultType::DontSendValidateVote
+            | FaultType::OfflineWithInfo => {
+                match NodeError::check_chain_summaries_query(&chain_ids) {
+                    Ok(()) => match validator
+                        .state
+                        .handle_chain_summaries_query(chain_ids)
+                        .await
+                    {
+                        Ok(results) => Ok(results
+                            .into_iter()
+                            .map(|result| result.map_err(Into::into))
+                            .collect()),
+                        Err(error) => Err(error.into()),
+                    },
+                    Err(error) => Err(error),
+                }
+            }
+        };
+        sender.send(result)
+    }
+
     async fn do_subscribe(
         self,
         chains: Vec<ChainId>,
@@ -906,7 +954,7 @@ where
         let mut validators = Vec::new();
         for _ in 0..count {
             let validator_keypair = ValidatorKeypair::generate();
-            let account_public_key = signer.generate_new();
+            let account_public_key = signer.generate_new().unwrap();
             validators.push((validator_keypair, account_public_key));
         }
         let for_committee = validators
@@ -1010,7 +1058,7 @@ where
             Box::pin(self.add_root_chain(0, Amount::ZERO)).await?;
         }
         let origin = ChainOrigin::Root(index);
-        let public_key = self.signer.generate_new();
+        let public_key = self.signer.generate_new().unwrap();
         let open_chain_config = InitialChainConfig {
             ownership: ChainOwnership::single(public_key.into()),
             epoch: Epoch(0),
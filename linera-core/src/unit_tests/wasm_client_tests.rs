@@ -1441,8 +1441,8 @@ where
     B: StorageBuilder,
 {
     let mut keys = InMemorySigner::new(None);
-    let owner_a = keys.generate_new().into();
-    let owner_b = keys.generate_new().into();
+    let owner_a = keys.generate_new().unwrap().into();
+    let owner_b = keys.generate_new().unwrap().into();
     let clock = storage_builder.clock().clone();
     let mut builder = TestBuilder::new(storage_builder, 4, 0, keys).await?;
 
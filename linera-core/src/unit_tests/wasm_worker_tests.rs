@@ -185,6 +185,9 @@ where
         application_index: 0,
         required_application_ids: vec![],
         parameters: parameters_bytes,
+        allow_reentrancy: false,
+        upgrade_authority: None,
+        module_history: vec![],
     };
     let application_description_blob = Blob::new_application_description(&application_description);
     let application_id = From::from(&application_description);
@@ -365,6 +368,9 @@ where
         application_index: 0,
         required_application_ids: vec![],
         parameters: counter_params,
+        allow_reentrancy: false,
+        upgrade_authority: None,
+        module_history: vec![],
     };
     let counter_app_id: ApplicationId = From::from(&counter_app_desc);
     let create_counter_block = make_child_block(&publish_cert.into_value())
@@ -391,6 +397,9 @@ where
         application_index: 0,
         required_application_ids: vec![counter_app_id],
         parameters: meta_params,
+        allow_reentrancy: false,
+        upgrade_authority: None,
+        module_history: vec![],
     };
     let meta_app_id: ApplicationId = From::from(&meta_app_desc);
     let create_meta_block = make_child_block(&create_counter_cert.into_value())
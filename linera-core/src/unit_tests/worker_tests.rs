@@ -645,7 +645,7 @@ fn direct_credit_message(recipient: ChainId, amount: Amount) -> OutgoingMessage
 
 /// Creates `count` key pairs and returns them, sorted by the `AccountOwner` created from their public key.
 fn generate_key_pairs(signer: &mut InMemorySigner, count: usize) -> Vec<AccountPublicKey> {
-    let mut public_keys = iter::repeat_with(|| signer.generate_new())
+    let mut public_keys = iter::repeat_with(|| signer.generate_new().unwrap())
         .take(count)
         .collect::<Vec<_>>();
     public_keys.sort_by_key(|pk| AccountOwner::from(*pk));
@@ -684,7 +684,7 @@ where
     B: StorageBuilder,
 {
     let mut signer = InMemorySigner::new(None);
-    let sender_public_key = signer.generate_new();
+    let sender_public_key = signer.generate_new().unwrap();
     let sender_owner = sender_public_key.into();
     let mut env = TestEnvironment::new(&mut storage_builder, false, false).await?;
     let chain_1_desc = env
@@ -743,7 +743,7 @@ where
     B: StorageBuilder,
 {
     let mut signer = InMemorySigner::new(None);
-    let sender_owner = signer.generate_new().into();
+    let sender_owner = signer.generate_new().unwrap().into();
     let mut env = TestEnvironment::new(&mut storage_builder, false, false).await?;
     let chain_1_desc = env
         .add_root_chain(1, sender_owner, Amount::from_tokens(5))
@@ -789,7 +789,7 @@ where
     B: StorageBuilder,
 {
     let mut signer = InMemorySigner::new(None);
-    let public_key = signer.generate_new();
+    let public_key = signer.generate_new().unwrap();
     let owner = public_key.into();
     let balance = Amount::from_tokens(5);
     let small_transfer = Amount::from_micros(1);
@@ -877,7 +877,7 @@ where
     use tokio::task::yield_now;
 
     let mut signer = InMemorySigner::new(None);
-    let public_key = signer.generate_new();
+    let public_key = signer.generate_new().unwrap();
     let owner = public_key.into();
     let balance = Amount::from_tokens(5);
     let small_transfer = Amount::from_micros(1);
@@ -1038,7 +1038,7 @@ where
     B: StorageBuilder,
 {
     let mut signer = InMemorySigner::new(None);
-    let sender_public_key = signer.generate_new();
+    let sender_public_key = signer.generate_new().unwrap();
     let mut env = TestEnvironment::new(&mut storage_builder, false, false).await?;
     let chain_1_desc = env
         .add_root_chain(1, sender_public_key.into(), Amount::from_tokens(5))
@@ -1079,7 +1079,7 @@ where
     B: StorageBuilder,
 {
     let mut signer = InMemorySigner::new(None);
-    let sender_public_key = signer.generate_new();
+    let sender_public_key = signer.generate_new().unwrap();
     let sender_owner = sender_public_key.into();
     let mut env = TestEnvironment::new(&mut storage_builder, false, false).await?;
     let chain_desc = env
@@ -1184,7 +1184,7 @@ where
     B: StorageBuilder,
 {
     let mut signer = InMemorySigner::new(None);
-    let sender_public_key = signer.generate_new();
+    let sender_public_key = signer.generate_new().unwrap();
     let sender_owner = sender_public_key.into();
     let mut env = TestEnvironment::new(&mut storage_builder, false, false).await?;
     let chain_1_desc = env
@@ -1310,9 +1310,9 @@ where
     B: StorageBuilder,
 {
     let mut signer = InMemorySigner::new(None);
-    let sender_public_key = signer.generate_new();
+    let sender_public_key = signer.generate_new().unwrap();
     let sender_owner = sender_public_key.into();
-    let recipient_public_key = signer.generate_new();
+    let recipient_public_key = signer.generate_new().unwrap();
     let recipient_owner = recipient_public_key.into();
     let mut env = TestEnvironment::new(&mut storage_builder, false, false).await?;
     let chain_1_desc = env
@@ -1650,7 +1650,7 @@ where
     B: StorageBuilder,
 {
     let mut signer = InMemorySigner::new(None);
-    let sender_owner = signer.generate_new().into();
+    let sender_owner = signer.generate_new().unwrap().into();
     let mut env = TestEnvironment::new(&mut storage_builder, false, false).await?;
     let chain_1_desc = env
         .add_root_chain(1, sender_owner, Amount::from_tokens(5))
@@ -1691,7 +1691,7 @@ where
     B: StorageBuilder,
 {
     let mut signer = InMemorySigner::new(None);
-    let sender_owner = signer.generate_new().into();
+    let sender_owner = signer.generate_new().unwrap().into();
     let mut env = TestEnvironment::new(&mut storage_builder, false, false).await?;
     let chain_1_desc = env
         .add_root_chain(1, sender_owner, Amount::from_tokens(5))
@@ -1747,7 +1747,7 @@ where
     B: StorageBuilder,
 {
     let mut signer = InMemorySigner::new(None);
-    let sender_owner = signer.generate_new().into();
+    let sender_owner = signer.generate_new().unwrap().into();
     let mut env = TestEnvironment::new(&mut storage_builder, false, false).await?;
     let chain_1_desc = env
         .add_root_chain(1, sender_owner, Amount::from_tokens(5))
@@ -1791,8 +1791,8 @@ where
     B: StorageBuilder,
 {
     let mut signer = InMemorySigner::new(None);
-    let sender_pubkey = signer.generate_new();
-    let test_pubkey = signer.generate_new();
+    let sender_pubkey = signer.generate_new().unwrap();
+    let test_pubkey = signer.generate_new().unwrap();
     let mut env = TestEnvironment::new(&mut storage_builder, false, false).await?;
     let chain_2_desc = env
         .add_root_chain(2, test_pubkey.into(), Amount::ZERO)
@@ -4046,7 +4046,7 @@ where
     B: StorageBuilder,
 {
     let mut signer = InMemorySigner::new(None);
-    let public_key = signer.generate_new();
+    let public_key = signer.generate_new().unwrap();
     let mut env = TestEnvironment::new(&mut storage_builder, true, false).await?;
     let clock = storage_builder.clock();
     let committee = env.committee().clone();
@@ -4240,7 +4240,7 @@ where
     const BLOCK_TIMESTAMP: u64 = 10;
 
     let mut signer = InMemorySigner::new(None);
-    let public_key = signer.generate_new();
+    let public_key = signer.generate_new().unwrap();
     let owner = public_key.into();
     let balance = Amount::from_tokens(1);
     let small_transfer = Amount::from_micros(1);
@@ -4402,7 +4402,7 @@ where
     B: StorageBuilder,
 {
     let mut signer = InMemorySigner::new(None);
-    let receiver_public_key = signer.generate_new();
+    let receiver_public_key = signer.generate_new().unwrap();
     let owner = receiver_public_key.into();
     let mut env = TestEnvironment::new(&mut storage_builder, false, false).await?;
     let chain_1_desc = env.add_root_chain(1, owner, Amount::from_tokens(10)).await;
@@ -17,11 +17,11 @@ use futures::{
 use linera_base::{
     crypto::{CryptoError, CryptoHash, ValidatorPublicKey},
     data_types::{
-        ApplicationDescription, ArithmeticError, Blob, BlockHeight, Epoch, Round, TimeDelta,
-        Timestamp,
+        Amount, ApplicationDescription, ArithmeticError, Blob, BlockHeight, Epoch, Round,
+        TimeDelta, Timestamp,
     },
     doc_scalar,
-    identifiers::{AccountOwner, ApplicationId, BlobId, ChainId, EventId, StreamId},
+    identifiers::{AccountOwner, ApplicationId, BlobId, BlobType, ChainId, EventId, StreamId},
 };
 use linera_cache::{UniqueValueCache, ValueCache, DEFAULT_CLEANUP_INTERVAL_SECS};
 #[cfg(with_testing)]
@@ -34,9 +34,9 @@ use linera_chain::{
     },
     ChainError, ChainStateView,
 };
-use linera_execution::{ExecutionError, ExecutionStateView, Query, QueryOutcome, ResourceTracker};
+use linera_execution::{ExecutionError, Query, QueryOutcome, ResourceTracker};
 use linera_storage::{Clock as _, Storage};
-use linera_views::{context::InactiveContext, ViewError};
+use linera_views::ViewError;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio::sync::{mpsc, oneshot, OwnedRwLockReadGuard};
@@ -65,11 +65,11 @@ pub(crate) use crate::chain_worker::EventSubscriptionsResult;
 use crate::{
     chain_worker::{
         handle,
-        state::{send_result, ChainWorkerState},
+        state::{send_result, ChainWorkerState, ExecutedBlockState},
         BlockOutcome, ChainWorkerConfig, CrossChainUpdateResult, DeliveryNotifier,
     },
     client::ListeningMode,
-    data_types::{ChainInfoQuery, ChainInfoResponse, CrossChainRequest},
+    data_types::{ChainInfoQuery, ChainInfoResponse, ChainSummary, CrossChainRequest},
     notifier::Notifier,
 };
 
@@ -686,8 +686,7 @@ pub struct WorkerState<StorageClient: Storage> {
     /// Configuration options for chain workers.
     chain_worker_config: ChainWorkerConfig,
     block_cache: Arc<ValueCache<CryptoHash, ConfirmedBlock>>,
-    execution_state_cache:
-        Option<Arc<UniqueValueCache<CryptoHash, ExecutionStateView<InactiveContext>>>>,
+    execution_state_cache: Option<Arc<UniqueValueCache<CryptoHash, ExecutedBlockState>>>,
     /// Chains tracked by a worker, along with their listening modes.
     chain_modes: Option<Arc<RwLock<BTreeMap<ChainId, ListeningMode>>>>,
     /// One-shot channels to notify callers when messages of a particular chain have been
@@ -1260,6 +1259,24 @@ where
         .await
     }
 
+    /// Returns the total fees of a block, even if its chain can't pay them.
+    #[instrument(level = "trace", skip(self, block))]
+    pub async fn block_fees(
+        &self,
+        block: ProposedBlock,
+        round: Option<u32>,
+        published_blobs: Vec<Blob>,
+        policy: BundleExecutionPolicy,
+    ) -> Result<Amount, WorkerError> {
+        let chain_id = block.chain_id;
+        self.chain_write(chain_id, move |mut guard| async move {
+            guard
+                .block_fees(block, round, &published_blobs, policy)
+                .await
+        })
+        .await
+    }
+
     /// Executes a [`Query`] for an application's state on a specific chain.
     ///
     /// If `block_hash` is specified, system will query the application's state
@@ -1790,6 +1807,44 @@ where
         .await
     }
 
+    /// Returns a summary of each of the given chains, in the same order. A chain that
+    /// cannot be summarized only fails its own entry.
+    ///
+    /// Chains whose description is not in storage are reported as inactive without
+    /// loading a chain worker for them.
+    #[instrument(skip_all, fields(
+        nickname = %self.nickname(),
+        num_chains = %chain_ids.len()
+    ))]
+    pub async fn handle_chain_summaries_query(
+        &self,
+        chain_ids: Vec<ChainId>,
+    ) -> Result<Vec<Result<ChainSummary, WorkerError>>, WorkerError> {
+        let description_ids = chain_ids
+            .iter()
+            .map(|chain_id| BlobId::new(chain_id.0, BlobType::ChainDescription))
+            .collect::<Vec<_>>();
+        let unknown = self.storage.missing_blobs(&description_ids).await?;
+        Ok(
+            future::join_all(chain_ids.into_iter().zip(description_ids).map(
+                |(chain_id, description_id)| {
+                    let is_known = !unknown.contains(&description_id);
+                    async move {
+                        if !is_known {
+                            return Err(ChainError::InactiveChain(chain_id).into());
+                        }
+                        self.chain_read(
+                            chain_id,
+                            |guard| async move { guard.chain_summary().await },
+                        )
+                        .await
+                    }
+                },
+            ))
+            .await,
+        )
+    }
+
     /// Gets the next block height to receive from an inbox.
     #[instrument(skip_all, fields(
         nickname = %self.nickname(),
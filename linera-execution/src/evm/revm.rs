@@ -908,6 +908,9 @@ impl<Runtime: ContractRuntime> CallInterceptorContract<Runtime> {
             application_index,
             parameters: parameters.clone(),
             required_application_ids,
+            allow_reentrancy: false,
+            upgrade_authority: None,
+            module_history: vec![],
         };
         Ok(ApplicationId::from(&application_description))
     }
@@ -1325,6 +1328,11 @@ where
         self.execute_no_return_operation(operation, "process_streams", value, caller)
     }
 
+    fn migrate(&mut self) -> Result<(), ExecutionError> {
+        // EVM contracts keep their storage layout across upgrades and have no migration hook.
+        Ok(())
+    }
+
     fn finalize(&mut self) -> Result<(), ExecutionError> {
         Ok(())
     }
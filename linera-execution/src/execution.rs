@@ -11,7 +11,8 @@ use allocative::Allocative;
 use futures::{FutureExt, StreamExt};
 use linera_base::{
     crypto::{BcsHashable, CryptoHash},
-    data_types::{BlobContent, BlockHeight, StreamUpdate},
+    data_types::{Amount, BlobContent, BlockHeight, StreamUpdate},
+    ensure,
     identifiers::{AccountOwner, BlobId, ChainId, StreamId},
     time::Instant,
 };
@@ -20,7 +21,12 @@ use linera_views::{
     historical_hash_wrapper::HistoricallyHashableView,
     key_value_store_view::KeyValueStoreView,
     map_view::MapView,
+    proof::{
+        empty_root, IncrementallyProvableView as _, KeyProof, MerkleTree, MerkleTreeView,
+        ProofError, ProvableView as _,
+    },
     reentrant_collection_view::ReentrantCollectionView,
+    register_view::RegisterView,
     views::{ClonableView, ReplaceContext, View},
     ViewError,
 };
@@ -73,11 +79,46 @@ impl<C: Context, C2: Context> ReplaceContext<C2> for ExecutionStateViewInner<C>
     }
 }
 
+/// The Merkle trees of the balances and of the application states, which the state hash
+/// commits to if the policy requires it.
+#[derive(Debug, ClonableView, View, Allocative)]
+#[allocative(bound = "C")]
+pub struct StateTreesView<C> {
+    /// Whether the trees are up to date with the execution state. They are only maintained
+    /// while the policy requires the state hash to commit to their roots.
+    pub active: RegisterView<C, bool>,
+    /// The tree of the balances of the chain account and of the owners.
+    pub balances: MerkleTreeView<C>,
+    /// The tree of the roots of the non-empty application states.
+    pub applications: MerkleTreeView<C>,
+    /// The tree of each application's state.
+    pub application_states: ReentrantCollectionView<C, ApplicationId, MerkleTreeView<C>>,
+}
+
+impl<C: Context, C2: Context> ReplaceContext<C2> for StateTreesView<C> {
+    type Target = StateTreesView<C2>;
+
+    async fn with_context(
+        &mut self,
+        ctx: impl FnOnce(&Self::Context) -> C2 + Clone,
+    ) -> Self::Target {
+        StateTreesView {
+            active: self.active.with_context(ctx.clone()).await,
+            balances: self.balances.with_context(ctx.clone()).await,
+            applications: self.applications.with_context(ctx.clone()).await,
+            application_states: self.application_states.with_context(ctx.clone()).await,
+        }
+    }
+}
+
 /// A view accessing the execution state of a chain.
 #[derive(Debug, ClonableView, View, Allocative)]
 #[allocative(bound = "C")]
 pub struct ExecutionStateView<C> {
     inner: HistoricallyHashableView<C, ExecutionStateViewInner<C>>,
+    /// The Merkle trees that entries of the state can be proven with. They are not part of
+    /// the history of the changes.
+    state_trees: StateTreesView<C>,
 }
 
 impl<C> Deref for ExecutionStateView<C> {
@@ -99,12 +140,315 @@ where
     C: Context + Clone + 'static,
     C::Extra: ExecutionRuntimeContext,
 {
-    pub async fn crypto_hash_mut(&mut self) -> Result<CryptoHash, ViewError> {
-        #[derive(Serialize, Deserialize)]
-        struct ExecutionStateViewHash([u8; 32]);
-        impl BcsHashable<'_> for ExecutionStateViewHash {}
-        let hash = self.inner.historical_hash().await?;
-        Ok(CryptoHash::new(&ExecutionStateViewHash(hash.into())))
+    /// Returns the state hash, which commits to the history of the changes of the execution
+    /// state.
+    ///
+    /// If the policy of the current committee sets `commit_state_roots`, it also commits to
+    /// the Merkle roots of the balances and of the application states, so that entries can be
+    /// proven under a block's `state_hash`. Only the entries that changed are updated in the
+    /// trees, except when they are built for the first time.
+    pub async fn crypto_hash_mut(&mut self) -> Result<CryptoHash, ExecutionError> {
+        let history = self.history_root().await?;
+        if !self.commits_to_state_roots().await? {
+            if *self.state_trees.active.get() {
+                self.state_trees.clear();
+            }
+            return Ok(CryptoHash::new(&ExecutionStateViewHash(history.into())));
+        }
+        if *self.state_trees.active.get() {
+            self.update_state_trees().await?;
+        } else {
+            self.build_state_trees().await?;
+        }
+        let roots = StateRoots {
+            history,
+            balances: self.state_trees.balances.root().await?,
+            applications: self.state_trees.applications.root().await?,
+        };
+        Ok(roots.state_hash())
+    }
+
+    /// Returns whether the policy of the current committee requires the state hash to commit
+    /// to the roots of the state trees.
+    async fn commits_to_state_roots(&self) -> Result<bool, ExecutionError> {
+        let committee = self.system.current_committee().await?;
+        Ok(committee.is_some_and(|(_, committee)| committee.policy().commit_state_roots))
+    }
+
+    /// Updates the state trees with the balances and the application states that changed.
+    async fn update_state_trees(&mut self) -> Result<(), ViewError> {
+        let Some(application_ids) = self.inner.users.updated_indices()? else {
+            return self.build_state_trees().await;
+        };
+        let mut changes = self.inner.system.balances.merkle_changes().await?;
+        if changes.cleared || self.inner.system.balance.has_pending_changes().await {
+            changes.entries.push((
+                bcs::to_bytes(&AccountOwner::CHAIN)?,
+                Some(bcs::to_bytes(self.inner.system.balance.get())?),
+            ));
+        }
+        self.state_trees.balances.apply(changes).await?;
+        for application_id in application_ids {
+            let key = bcs::to_bytes(&application_id)?;
+            let Some(state) = self.inner.users.try_load_entry(&application_id).await? else {
+                self.state_trees
+                    .application_states
+                    .remove_entry(&application_id)?;
+                self.state_trees.applications.set(&key, None).await?;
+                continue;
+            };
+            if !state.has_pending_changes().await {
+                continue;
+            }
+            let mut tree = self
+                .state_trees
+                .application_states
+                .try_load_entry_mut(&application_id)
+                .await?;
+            tree.apply(state.merkle_changes().await?).await?;
+            let root = tree.root().await?;
+            drop(tree);
+            self.state_trees
+                .applications
+                .set(
+                    &key,
+                    (root != empty_root()).then_some(root.as_bytes().as_slice()),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Builds the state trees from all the balances and application states.
+    async fn build_state_trees(&mut self) -> Result<(), ViewError> {
+        self.state_trees.clear();
+        for (key, value) in self.balance_entries().await? {
+            self.state_trees.balances.set(&key, Some(&value)).await?;
+        }
+        for (application_id, state) in self.inner.users.try_load_all_entries().await? {
+            let mut tree = self
+                .state_trees
+                .application_states
+                .try_load_entry_mut(&application_id)
+                .await?;
+            for (key, value) in state.merkle_entries().await? {
+                tree.set(&key, Some(&value)).await?;
+            }
+            let root = tree.root().await?;
+            drop(tree);
+            if root != empty_root() {
+                let key = bcs::to_bytes(&application_id)?;
+                self.state_trees
+                    .applications
+                    .set(&key, Some(root.as_bytes().as_slice()))
+                    .await?;
+            }
+        }
+        self.state_trees.active.set(true);
+        Ok(())
+    }
+
+    /// Returns the balances of the chain account and of the owners, as tree entries.
+    async fn balance_entries(&self) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ViewError> {
+        let mut entries = self.system.balances.merkle_entries().await?;
+        entries.push((
+            bcs::to_bytes(&AccountOwner::CHAIN)?,
+            bcs::to_bytes(self.system.balance.get())?,
+        ));
+        Ok(entries)
+    }
+
+    /// Returns the hash of the history of the changes of the execution state.
+    async fn history_root(&self) -> Result<CryptoHash, ViewError> {
+        let history = self.inner.historical_hash().await?;
+        Ok(CryptoHash::from(<[u8; 32]>::from(history)))
+    }
+
+    /// Returns whether the state hash of the saved state commits to the state roots, so that
+    /// its entries can be proven.
+    pub fn has_state_roots(&self) -> bool {
+        *self.state_trees.active.get()
+    }
+
+    /// Returns the roots that the state hash of the saved state commits to.
+    pub async fn state_roots(&self) -> Result<StateRoots, ViewError> {
+        // The trees are only there if the state hash of the last block committed to them.
+        ensure!(
+            self.has_state_roots(),
+            ViewError::MissingEntries("ExecutionStateView::state_trees".into())
+        );
+        Ok(StateRoots {
+            history: self.history_root().await?,
+            balances: self.state_trees.balances.root().await?,
+            applications: self.state_trees.applications.root().await?,
+        })
+    }
+
+    /// Returns a proof of the balance of `owner`, or of the chain if `owner` is
+    /// [`AccountOwner::CHAIN`], under the state hash of the saved state.
+    pub async fn balance_proof(&self, owner: AccountOwner) -> Result<BalanceProof, ViewError> {
+        let roots = self.state_roots().await?;
+        let balance = if owner == AccountOwner::CHAIN {
+            Some(*self.system.balance.get())
+        } else {
+            self.system.balances.get(&owner).await?
+        };
+        let value = balance.map(|balance| bcs::to_bytes(&balance)).transpose()?;
+        Ok(BalanceProof {
+            roots,
+            balance: self
+                .state_trees
+                .balances
+                .prove(&bcs::to_bytes(&owner)?, value)
+                .await?,
+        })
+    }
+
+    /// Returns a proof that `key` has its value in the state of the application, or that it
+    /// is absent, under the state hash of the saved state.
+    pub async fn application_state_proof(
+        &self,
+        application_id: ApplicationId,
+        key: &[u8],
+    ) -> Result<ApplicationStateProof, ViewError> {
+        let roots = self.state_roots().await?;
+        let value = match self.users.try_load_entry(&application_id).await? {
+            Some(state) => state.get(key).await?,
+            None => None,
+        };
+        let tree = self
+            .state_trees
+            .application_states
+            .try_load_entry(&application_id)
+            .await?;
+        let (root, key) = match tree {
+            Some(tree) => (tree.root().await?, tree.prove(key, value).await?),
+            None => {
+                ensure!(value.is_none(), ViewError::InconsistentEntries);
+                (empty_root(), MerkleTree::new(Vec::new()).prove(key))
+            }
+        };
+        let application = self
+            .state_trees
+            .applications
+            .prove(
+                &bcs::to_bytes(&application_id)?,
+                (root != empty_root()).then(|| root.as_bytes().to_vec()),
+            )
+            .await?;
+        Ok(ApplicationStateProof {
+            roots,
+            application,
+            key,
+        })
+    }
+}
+
+/// The state hash of an execution state that does not commit to the state roots.
+#[derive(Serialize, Deserialize)]
+struct ExecutionStateViewHash([u8; 32]);
+
+impl BcsHashable<'_> for ExecutionStateViewHash {}
+
+/// What the state hash of an execution state commits to, with the state roots.
+#[derive(Serialize, Deserialize)]
+struct ExecutionStateRootsHash {
+    history: [u8; 32],
+    balances: [u8; 32],
+    applications: [u8; 32],
+}
+
+impl BcsHashable<'_> for ExecutionStateRootsHash {}
+
+/// The hashes that the state hash of an execution state is computed from, if the policy
+/// requires it to commit to the state roots.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, async_graphql::SimpleObject,
+)]
+pub struct StateRoots {
+    /// The hash of the history of the changes of the execution state.
+    pub history: CryptoHash,
+    /// The Merkle root of the balances of the chain account and of the owners.
+    pub balances: CryptoHash,
+    /// The Merkle root of the states of all user applications.
+    pub applications: CryptoHash,
+}
+
+impl StateRoots {
+    /// Returns the state hash computed from these roots.
+    pub fn state_hash(&self) -> CryptoHash {
+        CryptoHash::new(&ExecutionStateRootsHash {
+            history: self.history.into(),
+            balances: self.balances.into(),
+            applications: self.applications.into(),
+        })
+    }
+
+    /// Checks that these are the roots of the given state hash, e.g. of a certified block.
+    pub fn verify(&self, state_hash: &CryptoHash) -> Result<(), ProofError> {
+        ensure!(self.state_hash() == *state_hash, ProofError::WrongRoot);
+        Ok(())
+    }
+}
+
+/// A proof of the balance of an account under a state hash.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct BalanceProof {
+    /// The roots of the state hash.
+    pub roots: StateRoots,
+    /// The proof of the balance in the tree of balances.
+    pub balance: KeyProof,
+}
+
+impl BalanceProof {
+    /// Checks the proof against the `state_hash` of a trusted block, and returns the balance
+    /// of `owner`.
+    pub fn verify(
+        &self,
+        state_hash: &CryptoHash,
+        owner: AccountOwner,
+    ) -> Result<Amount, ProofError> {
+        self.roots.verify(state_hash)?;
+        let key = bcs::to_bytes(&owner).expect("serializing an account owner cannot fail");
+        match self.balance.verify(&self.roots.balances, &key)? {
+            Some(bytes) => bcs::from_bytes(bytes).map_err(|_| ProofError::Malformed),
+            None => Ok(Amount::ZERO),
+        }
+    }
+}
+
+/// A proof that a key of an application's state has a given value, or that it is absent,
+/// under a state hash.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct ApplicationStateProof {
+    /// The roots of the state hash.
+    pub roots: StateRoots,
+    /// The proof of the root of the application's state.
+    pub application: KeyProof,
+    /// The proof of the key in the application's state.
+    pub key: KeyProof,
+}
+
+impl ApplicationStateProof {
+    /// Checks the proof against the `state_hash` of a trusted block, and returns the value of
+    /// `key` in the application's state, or `None` if it is absent.
+    pub fn verify(
+        &self,
+        state_hash: &CryptoHash,
+        application_id: ApplicationId,
+        key: &[u8],
+    ) -> Result<Option<&[u8]>, ProofError> {
+        self.roots.verify(state_hash)?;
+        let application_key =
+            bcs::to_bytes(&application_id).expect("serializing an application ID cannot fail");
+        let state_root = match self
+            .application
+            .verify(&self.roots.applications, &application_key)?
+        {
+            Some(bytes) => CryptoHash::try_from(bytes).map_err(|_| ProofError::Malformed)?,
+            None => empty_root(),
+        };
+        self.key.verify(&state_root, key)
     }
 }
 
@@ -117,6 +461,7 @@ impl<C: Context, C2: Context> ReplaceContext<C2> for ExecutionStateView<C> {
     ) -> Self::Target {
         ExecutionStateView {
             inner: self.inner.with_context(ctx.clone()).await,
+            state_trees: self.state_trees.with_context(ctx.clone()).await,
         }
     }
 }
@@ -207,6 +552,7 @@ pub enum UserAction {
     Operation(OperationContext, Vec<u8>),
     Message(MessageContext, Vec<u8>),
     ProcessStreams(ProcessStreamsContext, Vec<StreamUpdate>),
+    Migrate(OperationContext),
 }
 
 impl UserAction {
@@ -216,6 +562,7 @@ impl UserAction {
             UserAction::Operation(context, _) => context.authenticated_owner,
             UserAction::ProcessStreams(_, _) => None,
             UserAction::Message(context, _) => context.authenticated_owner,
+            UserAction::Migrate(context) => context.authenticated_owner,
         }
     }
 
@@ -225,6 +572,7 @@ impl UserAction {
             UserAction::Operation(context, _) => context.height,
             UserAction::ProcessStreams(context, _) => context.height,
             UserAction::Message(context, _) => context.height,
+            UserAction::Migrate(context) => context.height,
         }
     }
 
@@ -234,6 +582,7 @@ impl UserAction {
             UserAction::Operation(context, _) => context.round,
             UserAction::ProcessStreams(context, _) => context.round,
             UserAction::Message(context, _) => context.round,
+            UserAction::Migrate(context) => context.round,
         }
     }
 
@@ -243,6 +592,7 @@ impl UserAction {
             UserAction::Operation(context, _) => context.timestamp,
             UserAction::ProcessStreams(context, _) => context.timestamp,
             UserAction::Message(context, _) => context.timestamp,
+            UserAction::Migrate(context) => context.timestamp,
         }
     }
 }
@@ -331,7 +681,11 @@ where
                 );
 
                 for (code, description) in codes.0.into_iter().zip(descriptions) {
-                    runtime.preload_service(ApplicationId::from(&description), code, description);
+                    runtime.preload_service(
+                        ApplicationId::from(&description.original()),
+                        code,
+                        description,
+                    );
                 }
 
                 runtime.run_query(application_id, query)
@@ -13,6 +13,7 @@ use futures::{channel::mpsc, StreamExt as _};
 #[cfg(with_metrics)]
 use linera_base::prometheus_util::MeasureLatency as _;
 use linera_base::{
+    crypto::CryptoHash,
     data_types::{
         Amount, ApplicationPermissions, ArithmeticError, BlobContent, BlockHeight, OracleResponse,
         Timestamp,
@@ -32,7 +33,7 @@ use tracing::{info_span, instrument, Instrument as _};
 use crate::{
     execution::UserAction,
     runtime::ContractSyncRuntime,
-    system::{CreateApplicationResult, OpenChainConfig},
+    system::{ApplicationAction, ApplicationOptions, CreateApplicationResult, OpenChainConfig},
     util::{OracleResponseExt as _, RespondExt as _},
     ApplicationDescription, ApplicationId, ExecutionError, ExecutionRuntimeContext,
     ExecutionStateView, JsVec, Message, MessageContext, MessageKind, ModuleId, Operation,
@@ -112,6 +113,11 @@ where
                     .await?
             }
         };
+        let description = self
+            .state
+            .system
+            .current_description(id, description)
+            .await?;
         let code = self
             .state
             .context()
@@ -137,6 +143,11 @@ where
                     .await?
             }
         };
+        let description = self
+            .state
+            .system
+            .current_description(id, description)
+            .await?;
         let code = self
             .state
             .context()
@@ -503,6 +514,7 @@ where
                         module_id,
                         parameters,
                         required_application_ids,
+                        ApplicationOptions::default(),
                         self.txn_tracker,
                     )
                     .await?;
@@ -515,6 +527,26 @@ where
                 callback,
             } => {
                 let system = &mut self.state.system;
+                // The host's quotas are also checked when replaying, so that validators
+                // enforce them.
+                let host = Url::parse(&request.url)
+                    .ok()
+                    .and_then(|url| url.host_str().map(str::to_owned));
+                let mut maximum_response_bytes = None;
+                if let Some(host) = host {
+                    let host_rule = system
+                        .current_committee()
+                        .await?
+                        .and_then(|(_, committee)| {
+                            committee.policy().http_request_rules.get(&host).cloned()
+                        });
+                    let maximum_requests_per_block = host_rule
+                        .as_ref()
+                        .and_then(|rule| rule.maximum_requests_per_block);
+                    maximum_response_bytes = host_rule.and_then(|rule| rule.maximum_response_bytes);
+                    self.txn_tracker
+                        .track_http_request_to_host(&host, maximum_requests_per_block)?;
+                }
                 let response = self
                     .txn_tracker
                     .oracle(|| async {
@@ -527,20 +559,12 @@ where
                             .collect::<Result<HeaderMap, ExecutionError>>()?;
 
                         let url = Url::parse(&request.url)?;
-                        let host = url
-                            .host_str()
-                            .ok_or_else(|| ExecutionError::UnauthorizedHttpRequest(url.clone()))?;
-
                         let (_epoch, committee) = system
                             .current_committee()
                             .await?
                             .ok_or_else(|| ExecutionError::UnauthorizedHttpRequest(url.clone()))?;
-                        let allowed_hosts = &committee.policy().http_request_allow_list;
-
-                        ensure!(
-                            allowed_hosts.contains(host),
-                            ExecutionError::UnauthorizedHttpRequest(url)
-                        );
+                        let policy = committee.policy();
+                        let rule = policy.check_http_request(&url, request.method)?;
 
                         let request = Client::new()
                             .request(request.method.into(), url)
@@ -548,17 +572,19 @@ where
                             .headers(headers);
                         #[cfg(not(web))]
                         let request = request.timeout(linera_base::time::Duration::from_millis(
-                            committee.policy().http_request_timeout_ms,
+                            policy.http_request_timeout_ms,
                         ));
 
                         let response = request.send().await?;
 
-                        let mut response_size_limit =
-                            committee.policy().maximum_http_response_bytes;
+                        let mut response_size_limit = policy.maximum_http_response_bytes;
+                        if let Some(maximum) = rule.and_then(|rule| rule.maximum_response_bytes) {
+                            response_size_limit = response_size_limit.min(maximum);
+                        }
 
                         if http_responses_are_oracle_responses {
-                            response_size_limit = response_size_limit
-                                .min(committee.policy().maximum_oracle_response_bytes);
+                            response_size_limit =
+                                response_size_limit.min(policy.maximum_oracle_response_bytes);
                         }
                         Ok(OracleResponse::Http(
                             Self::receive_http_response(response, response_size_limit).await?,
@@ -566,6 +592,18 @@ where
                     })
                     .await?
                     .to_http_response()?;
+                if let Some(limit) = maximum_response_bytes {
+                    let size = response
+                        .headers
+                        .iter()
+                        .map(|header| (header.name.len() + header.value.len()) as u64)
+                        .sum::<u64>()
+                        + response.body.len() as u64;
+                    ensure!(
+                        size <= limit,
+                        ExecutionError::HttpResponseSizeLimitExceeded { limit, size }
+                    );
+                }
                 callback.respond(response);
             }
 
@@ -810,6 +848,48 @@ where
                 callback.respond(validation_round);
             }
 
+            RandomSeed {
+                application_id,
+                height,
+                callback,
+            } => {
+                let context = self.state.context();
+                let extra = context.extra();
+                // The signatures are recorded once per transaction, with the first seed.
+                let signatures_hash = match self.txn_tracker.confirmation_signatures_hash() {
+                    Some(hash) => hash,
+                    None => {
+                        let previous_block_hash = self
+                            .txn_tracker
+                            .previous_block_hash()
+                            .ok_or(ExecutionError::RandomSeedInFirstBlock)?;
+                        let signatures = self
+                            .txn_tracker
+                            .oracle(|| async {
+                                let signatures = extra
+                                    .get_confirmation_signatures(previous_block_hash)
+                                    .await?
+                                    .ok_or(ExecutionError::PreviousBlockCertificateNotFound(
+                                        previous_block_hash,
+                                    ))?;
+                                Ok(OracleResponse::RandomSeed(signatures))
+                            })
+                            .await?
+                            .to_confirmation_signatures()?;
+                        let hash = CryptoHash::new(signatures);
+                        self.txn_tracker.set_confirmation_signatures_hash(hash);
+                        hash
+                    }
+                };
+                let seed = self.txn_tracker.next_random_seed(
+                    signatures_hash,
+                    extra.chain_id(),
+                    height,
+                    application_id,
+                );
+                callback.respond(seed);
+            }
+
             TotalStorageSize {
                 application,
                 callback,
@@ -835,21 +915,29 @@ where
                 callback.respond(allow);
             }
 
-            #[cfg(web)]
-            Log { message, level } => match level {
-                tracing::log::Level::Trace | tracing::log::Level::Debug => {
-                    tracing::debug!(target: "user_application_log", message = %message);
-                }
-                tracing::log::Level::Info => {
-                    tracing::info!(target: "user_application_log", message = %message);
-                }
-                tracing::log::Level::Warn => {
-                    tracing::warn!(target: "user_application_log", message = %message);
-                }
-                tracing::log::Level::Error => {
-                    tracing::error!(target: "user_application_log", message = %message);
+            Log {
+                application_id,
+                message,
+                level,
+            } => {
+                #[cfg(web)]
+                match level {
+                    tracing::log::Level::Trace | tracing::log::Level::Debug => {
+                        tracing::debug!(target: "user_application_log", message = %message);
+                    }
+                    tracing::log::Level::Info => {
+                        tracing::info!(target: "user_application_log", message = %message);
+                    }
+                    tracing::log::Level::Warn => {
+                        tracing::warn!(target: "user_application_log", message = %message);
+                    }
+                    tracing::log::Level::Error => {
+                        tracing::error!(target: "user_application_log", message = %message);
+                    }
                 }
-            },
+                self.txn_tracker
+                    .add_log(application_id, level.into(), message);
+            }
         }
 
         Ok(())
@@ -1023,7 +1111,11 @@ where
                 );
 
                 for (code, description) in codes.0.into_iter().zip(descriptions) {
-                    runtime.preload_contract(ApplicationId::from(&description), code, description);
+                    runtime.preload_contract(
+                        ApplicationId::from(&description.original()),
+                        code,
+                        description,
+                    );
                 }
 
                 runtime.run_action(application_id, chain_id, action)
@@ -1067,13 +1159,20 @@ where
         assert_eq!(context.chain_id, self.state.context().extra().chain_id());
         match operation {
             Operation::System(op) => {
-                let new_application = self
+                let application_action = self
                     .state
                     .system
                     .execute_operation(context, *op, self.txn_tracker, self.resource_controller)
                     .await?;
-                if let Some((application_id, argument)) = new_application {
-                    let user_action = UserAction::Instantiate(context, argument);
+                if let Some(application_action) = application_action {
+                    let (application_id, user_action) = match application_action {
+                        ApplicationAction::Instantiate(application_id, argument) => {
+                            (application_id, UserAction::Instantiate(context, argument))
+                        }
+                        ApplicationAction::Migrate(application_id) => {
+                            (application_id, UserAction::Migrate(context))
+                        }
+                    };
                     self.run_user_action(
                         application_id,
                         user_action,
@@ -1546,6 +1645,13 @@ pub enum ExecutionRequest {
         callback: Sender<Option<u32>>,
     },
 
+    RandomSeed {
+        application_id: ApplicationId,
+        height: BlockHeight,
+        #[debug(skip)]
+        callback: Sender<CryptoHash>,
+    },
+
     TotalStorageSize {
         application: ApplicationId,
         #[debug(skip)]
@@ -1557,9 +1663,9 @@ pub enum ExecutionRequest {
         callback: Sender<bool>,
     },
 
-    /// Log message from contract execution (fire-and-forget, no callback needed).
-    #[cfg(web)]
+    /// Log message from application execution (fire-and-forget, no callback needed).
     Log {
+        application_id: ApplicationId,
         message: String,
         level: tracing::log::Level,
     },
@@ -34,8 +34,8 @@ use linera_base::{
     crypto::{BcsHashable, CryptoHash},
     data_types::{
         Amount, ApplicationDescription, ApplicationPermissions, ArithmeticError, Blob, BlockHeight,
-        Bytecode, DecompressionError, Epoch, NetworkDescription, SendMessageRequest, StreamUpdate,
-        Timestamp,
+        Bytecode, ConfirmationSignatures, DecompressionError, Epoch, NetworkDescription,
+        SendMessageRequest, StreamUpdate, Timestamp,
     },
     doc_scalar, ensure, hex_debug, http,
     identifiers::{
@@ -66,10 +66,12 @@ pub use crate::wasm::{
 };
 pub use crate::{
     committee::{Committee, SharedCommittees},
-    execution::{ExecutionStateView, ServiceRuntimeEndpoint},
+    execution::{
+        ApplicationStateProof, BalanceProof, ExecutionStateView, ServiceRuntimeEndpoint, StateRoots,
+    },
     execution_state_actor::{ExecutionRequest, ExecutionStateActor},
-    policy::ResourceControlPolicy,
-    resources::{BalanceHolder, ResourceController, ResourceTracker},
+    policy::{HttpRequestRule, ResourceControlPolicy},
+    resources::{BalanceHolder, FeeSponsor, ResourceController, ResourceTracker},
     runtime::{
         ContractSyncRuntimeHandle, ServiceRuntimeRequest, ServiceSyncRuntime,
         ServiceSyncRuntimeHandle,
@@ -248,8 +250,18 @@ pub enum ExecutionError {
     #[error("The given promise is invalid or was polled once already")]
     InvalidPromise,
 
-    #[error("Attempted to perform a reentrant call to application {0}")]
+    #[error(
+        "Attempted to perform a reentrant call to application {0}, which does not allow \
+        reentrancy"
+    )]
     ReentrantCall(ApplicationId),
+    #[error(
+        "Application {0} attempted to overwrite storage that a reentrant call to it has \
+        changed"
+    )]
+    ReentrantWriteConflict(ApplicationId),
+    #[error("Exceeded the maximum depth of {0} nested contract calls")]
+    MaximumCallDepthExceeded(u32),
     #[error(
         "Application {caller_id} attempted to perform a cross-application to {callee_id} call \
         from `finalize`"
@@ -294,6 +306,10 @@ pub enum ExecutionError {
     BcsError(#[from] bcs::Error),
     #[error("Recorded response for oracle query has the wrong type")]
     OracleResponseMismatch,
+    #[error("Random seeds are not available in the first block of a chain")]
+    RandomSeedInFirstBlock,
+    #[error("No certificate of the previous block {0} to derive a random seed from")]
+    PreviousBlockCertificateNotFound(CryptoHash),
     #[error("Service oracle query tried to create operations: {0:?}")]
     ServiceOracleQueryOperations(Vec<Operation>),
     #[error("Assertion failed: local time {local_time} is not earlier than {timestamp}")]
@@ -310,6 +326,8 @@ pub enum ExecutionError {
     BytecodeTooLarge,
     #[error("Attempt to perform an HTTP request to an unauthorized host: {0:?}")]
     UnauthorizedHttpRequest(reqwest::Url),
+    #[error("Too many HTTP requests to {0} in this block")]
+    HttpRequestQuotaExceeded(String),
     #[error("Attempt to perform an HTTP request to an invalid URL")]
     InvalidUrlForHttpRequest(#[from] url::ParseError),
     #[error("Worker thread failure: {0:?}")]
@@ -367,6 +385,25 @@ pub enum ExecutionError {
     OutdatedUpdateStream,
     #[error("UpdateStream references an application that is not subscribed")]
     UnsubscribedUpdateStream,
+    #[error("Application {0} has no upgrade authority")]
+    ApplicationNotUpgradable(ApplicationId),
+    #[error("Application {0} can only be upgraded on its creator chain")]
+    ApplicationUpgradeOutsideCreatorChain(ApplicationId),
+    #[error("The previous upgrade of application {0} was not adopted by its creator chain yet")]
+    PendingApplicationUpgrade(ApplicationId),
+    #[error(
+        "Expected to process upgrade {expected} of application {application_id}, \
+        but got {provided}"
+    )]
+    InvalidApplicationUpgradeIndex {
+        application_id: ApplicationId,
+        expected: u32,
+        provided: u32,
+    },
+    #[error("Only the upgrade authority of application {0} can upgrade it")]
+    UnauthorizedApplicationUpgrade(ApplicationId),
+    #[error("Account {0} cannot sponsor its own fees")]
+    SelfSponsorship(AccountOwner),
 }
 
 impl ExecutionError {
@@ -381,6 +418,8 @@ impl ExecutionError {
             | ExecutionError::InvalidPromise
             | ExecutionError::CrossApplicationCallInFinalize { .. }
             | ExecutionError::ReentrantCall(_)
+            | ExecutionError::ReentrantWriteConflict(_)
+            | ExecutionError::MaximumCallDepthExceeded(_)
             | ExecutionError::ApplicationBytecodeNotFound(_)
             | ExecutionError::UnsupportedDynamicApplicationLoad(_)
             | ExecutionError::ExcessiveRead
@@ -395,12 +434,14 @@ impl ExecutionError {
             | ExecutionError::JsonError(_)
             | ExecutionError::BcsError(_)
             | ExecutionError::OracleResponseMismatch
+            | ExecutionError::RandomSeedInFirstBlock
             | ExecutionError::ServiceOracleQueryOperations(_)
             | ExecutionError::AssertBefore { .. }
             | ExecutionError::StreamNameTooLong
             | ExecutionError::BlobTooLarge
             | ExecutionError::BytecodeTooLarge
             | ExecutionError::UnauthorizedHttpRequest(_)
+            | ExecutionError::HttpRequestQuotaExceeded(_)
             | ExecutionError::InvalidUrlForHttpRequest(_)
             | ExecutionError::InactiveChain(_)
             | ExecutionError::BlobsNotFound(_)
@@ -422,6 +463,12 @@ impl ExecutionError {
             | ExecutionError::UnprocessedStreams
             | ExecutionError::OutdatedUpdateStream
             | ExecutionError::UnsubscribedUpdateStream
+            | ExecutionError::ApplicationNotUpgradable(_)
+            | ExecutionError::ApplicationUpgradeOutsideCreatorChain(_)
+            | ExecutionError::PendingApplicationUpgrade(_)
+            | ExecutionError::InvalidApplicationUpgradeIndex { .. }
+            | ExecutionError::UnauthorizedApplicationUpgrade(_)
+            | ExecutionError::SelfSponsorship(_)
             | ExecutionError::ViewError(ViewError::NotFound(_)) => false,
             #[cfg(with_wasm_runtime)]
             ExecutionError::WasmError(_) => false,
@@ -432,6 +479,7 @@ impl ExecutionError {
             | ExecutionError::ReqwestError(_)
             | ExecutionError::Thread(_)
             | ExecutionError::NoNetworkDescriptionFound
+            | ExecutionError::PreviousBlockCertificateNotFound(_)
             | ExecutionError::InternalError(_)
             | ExecutionError::IoError(_) => true,
         }
@@ -456,6 +504,7 @@ impl ExecutionError {
                 | ExecutionError::MaximumFuelExceeded(_)
                 | ExecutionError::MaximumServiceOracleExecutionTimeExceeded
                 | ExecutionError::BlockTooLarge
+                | ExecutionError::HttpRequestQuotaExceeded(_)
         )
     }
 
@@ -486,6 +535,9 @@ pub trait UserContract {
     /// Reacts to new events on streams this application subscribes to.
     fn process_streams(&mut self, updates: Vec<StreamUpdate>) -> Result<(), ExecutionError>;
 
+    /// Migrates the existing application state after the application's module was upgraded.
+    fn migrate(&mut self) -> Result<(), ExecutionError>;
+
     /// Finishes execution of the current transaction.
     fn finalize(&mut self) -> Result<(), ExecutionError>;
 }
@@ -512,6 +564,41 @@ impl Default for ExecutionRuntimeConfig {
     }
 }
 
+/// The severity of a log message emitted by an application.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize, async_graphql::Enum)]
+pub enum ApplicationLogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl From<tracing::log::Level> for ApplicationLogLevel {
+    fn from(level: tracing::log::Level) -> Self {
+        match level {
+            tracing::log::Level::Trace => ApplicationLogLevel::Trace,
+            tracing::log::Level::Debug => ApplicationLogLevel::Debug,
+            tracing::log::Level::Info => ApplicationLogLevel::Info,
+            tracing::log::Level::Warn => ApplicationLogLevel::Warn,
+            tracing::log::Level::Error => ApplicationLogLevel::Error,
+        }
+    }
+}
+
+/// A log message emitted by an application while executing a transaction.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, SimpleObject)]
+pub struct ApplicationLog {
+    /// The index of the transaction in the block.
+    pub transaction_index: u32,
+    /// The application that emitted the message.
+    pub application_id: ApplicationId,
+    /// The severity of the message.
+    pub level: ApplicationLogLevel,
+    /// The message.
+    pub message: String,
+}
+
 /// Requirements for the `extra` field in our state views (and notably the
 /// [`ExecutionStateView`]).
 #[cfg_attr(not(web), async_trait)]
@@ -545,6 +632,13 @@ pub trait ExecutionRuntimeContext {
 
     async fn get_network_description(&self) -> Result<Option<NetworkDescription>, ViewError>;
 
+    /// Returns the validator signatures of the locally stored certificate that confirmed the
+    /// block with the given hash, if any.
+    async fn get_confirmation_signatures(
+        &self,
+        block_hash: CryptoHash,
+    ) -> Result<Option<ConfirmationSignatures>, ViewError>;
+
     /// Returns the committee whose serialized form hashes to `hash`. Returns
     /// `ExecutionError::BlobsNotFound` if the committee blob is missing from
     /// storage.
@@ -917,9 +1011,9 @@ pub trait BaseRuntime {
     /// This is typically enabled for clients but disabled for validators.
     fn allow_application_logs(&mut self) -> Result<bool, ExecutionError>;
 
-    /// Sends a log message (used for forwarding logs from web workers to the main thread).
+    /// Sends a log message to the execution state, where it is recorded with the current
+    /// transaction (and, on the Web, forwarded from the worker to the main thread).
     /// This is a fire-and-forget operation - errors are silently ignored.
-    #[cfg(web)]
     fn send_log(&mut self, message: String, level: tracing::log::Level);
 }
 
@@ -1090,6 +1184,11 @@ pub trait ContractRuntime: BaseRuntime {
     /// Returns the multi-leader round in which this block was validated.
     fn validation_round(&mut self) -> Result<Option<u32>, ExecutionError>;
 
+    /// Returns a fresh random seed, derived deterministically from the signatures that
+    /// confirmed the previous block and the current application. Successive calls return
+    /// different seeds.
+    fn random_seed(&mut self) -> Result<CryptoHash, ExecutionError>;
+
     /// Writes a batch of changes.
     fn write_batch(&mut self, batch: Batch) -> Result<(), ExecutionError>;
 }
@@ -1285,6 +1384,7 @@ pub struct TestExecutionRuntimeContext {
     user_services: Arc<papaya::HashMap<ApplicationId, UserServiceCode>>,
     blobs: Arc<papaya::HashMap<BlobId, Blob>>,
     events: Arc<papaya::HashMap<EventId, Vec<u8>>>,
+    confirmation_signatures: Arc<papaya::HashMap<CryptoHash, ConfirmationSignatures>>,
 }
 
 #[cfg(with_testing)]
@@ -1298,8 +1398,20 @@ impl TestExecutionRuntimeContext {
             user_services: Arc::default(),
             blobs: Arc::default(),
             events: Arc::default(),
+            confirmation_signatures: Arc::default(),
         }
     }
+
+    /// Stores the validator signatures of the certificate that confirmed the given block.
+    pub fn add_confirmation_signatures(
+        &self,
+        block_hash: CryptoHash,
+        signatures: ConfirmationSignatures,
+    ) {
+        self.confirmation_signatures
+            .pin()
+            .insert(block_hash, signatures);
+    }
 }
 
 #[cfg(with_testing)]
@@ -1382,6 +1494,13 @@ impl ExecutionRuntimeContext for TestExecutionRuntimeContext {
         }))
     }
 
+    async fn get_confirmation_signatures(
+        &self,
+        block_hash: CryptoHash,
+    ) -> Result<Option<ConfirmationSignatures>, ViewError> {
+        Ok(self.confirmation_signatures.pin().get(&block_hash).cloned())
+    }
+
     async fn get_or_load_committee_by_hash(
         &self,
         hash: CryptoHash,
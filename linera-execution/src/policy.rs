@@ -9,19 +9,26 @@
 //! It also sets overarching limits such as the maximum fuel allowed per block,
 //! the maximum block size, and limits on concurrent operations.
 
-use std::{collections::BTreeSet, fmt};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
 
 use allocative::Allocative;
 use linera_base::{
     data_types::{Amount, ArithmeticError, BlobContent, CompressedBytecode, Resources},
-    ensure,
+    ensure, http,
     identifiers::{ApplicationId, BlobType},
     vm::VmRuntime,
 };
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
 
 use crate::ExecutionError;
 
+/// The default maximum number of nested contract calls.
+pub const DEFAULT_MAXIMUM_CALL_DEPTH: u32 = 64;
+
 /// A collection of prices and limits associated with block execution.
 #[derive(Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize, Allocative)]
 pub struct ResourceControlPolicy {
@@ -96,6 +103,15 @@ pub struct ResourceControlPolicy {
     pub http_request_allow_list: BTreeSet<String>,
     /// The list of application IDs for which all message- and event-related fees are waived.
     pub free_application_ids: BTreeSet<ApplicationId>,
+    /// Hosts that contracts and services can send HTTP requests to, subject to the given
+    /// restrictions. These rules take precedence over `http_request_allow_list`.
+    pub http_request_rules: BTreeMap<String, HttpRequestRule>,
+    /// The maximum number of contract calls on the call stack, including the application
+    /// executing the operation or message.
+    pub maximum_call_depth: u32,
+    /// Whether the state hash of a block also commits to the Merkle roots of the balances
+    /// and of the application states, so that their entries can be proven under it.
+    pub commit_state_roots: bool,
 }
 
 impl fmt::Display for ResourceControlPolicy {
@@ -132,8 +148,11 @@ impl fmt::Display for ResourceControlPolicy {
             maximum_oracle_response_bytes,
             maximum_http_response_bytes,
             http_request_allow_list,
+            http_request_rules,
             http_request_timeout_ms,
             free_application_ids,
+            maximum_call_depth,
+            commit_state_roots,
         } = self;
         write!(
             f,
@@ -170,9 +189,16 @@ impl fmt::Display for ResourceControlPolicy {
             {maximum_oracle_response_bytes} maximum number of bytes of an oracle response\n\
             {maximum_http_response_bytes} maximum number of bytes of an HTTP response\n\
             {http_request_timeout_ms} ms timeout for HTTP requests\n\
-            HTTP hosts allowed for contracts and services: {http_request_allow_list:#?}\n\
-            Free application IDs: {free_application_ids:#?}\n",
+            {maximum_call_depth} maximum depth of contract calls\n\
+            state hashes commit to the roots of balances and application states: \
+                {commit_state_roots}\n\
+            HTTP hosts allowed for contracts and services: {http_request_allow_list:#?}\n",
         )?;
+        writeln!(f, "HTTP request rules for contracts and services:")?;
+        for (host, rule) in http_request_rules {
+            writeln!(f, "  {host}: {rule}")?;
+        }
+        writeln!(f, "Free application IDs: {free_application_ids:#?}")?;
         Ok(())
     }
 }
@@ -222,9 +248,36 @@ impl ResourceControlPolicy {
             http_request_timeout_ms: u64::MAX,
             http_request_allow_list: BTreeSet::new(),
             free_application_ids: BTreeSet::new(),
+            http_request_rules: BTreeMap::new(),
+            maximum_call_depth: DEFAULT_MAXIMUM_CALL_DEPTH,
+            commit_state_roots: false,
         }
     }
 
+    /// Checks that contracts and services are allowed to send an HTTP request with the given
+    /// method to the given URL, and returns the rule that applies to its host, if any.
+    pub fn check_http_request(
+        &self,
+        url: &Url,
+        method: http::Method,
+    ) -> Result<Option<&HttpRequestRule>, ExecutionError> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| ExecutionError::UnauthorizedHttpRequest(url.clone()))?;
+        if let Some(rule) = self.http_request_rules.get(host) {
+            ensure!(
+                rule.allows(url, method),
+                ExecutionError::UnauthorizedHttpRequest(url.clone())
+            );
+            return Ok(Some(rule));
+        }
+        ensure!(
+            self.http_request_allow_list.contains(host),
+            ExecutionError::UnauthorizedHttpRequest(url.clone())
+        );
+        Ok(None)
+    }
+
     /// Returns whether the given application has its message- and event-related fees waived.
     pub fn is_free_app(&self, app_id: &ApplicationId) -> bool {
         self.free_application_ids.contains(app_id)
@@ -307,6 +360,9 @@ impl ResourceControlPolicy {
             http_request_timeout_ms: 20_000,
             http_request_allow_list: BTreeSet::new(),
             free_application_ids: BTreeSet::new(),
+            http_request_rules: BTreeMap::new(),
+            maximum_call_depth: DEFAULT_MAXIMUM_CALL_DEPTH,
+            commit_state_roots: false,
         }
     }
 
@@ -446,3 +502,135 @@ impl ResourceControlPolicy {
         Ok(())
     }
 }
+
+/// Restrictions on the HTTP requests that contracts and services can send to a host.
+///
+/// Empty sets and missing values mean that there is no restriction.
+#[derive(Eq, PartialEq, Hash, Clone, Debug, Default, Serialize, Deserialize, Allocative)]
+#[serde(default)]
+pub struct HttpRequestRule {
+    /// The allowed URL schemes, e.g. `https`.
+    pub schemes: BTreeSet<String>,
+    /// The allowed port.
+    pub port: Option<u16>,
+    /// The allowed prefixes of the URL path.
+    pub path_prefixes: BTreeSet<String>,
+    /// The allowed HTTP methods, e.g. `GET`.
+    pub methods: BTreeSet<String>,
+    /// The maximum size in bytes of a response, if lower than `maximum_http_response_bytes`.
+    pub maximum_response_bytes: Option<u64>,
+    /// The maximum number of requests to this host in a block.
+    pub maximum_requests_per_block: Option<u32>,
+}
+
+impl HttpRequestRule {
+    /// Returns whether a request with the given method to the given URL matches this rule.
+    pub fn allows(&self, url: &Url, method: http::Method) -> bool {
+        let method = reqwest::Method::from(method);
+        (self.schemes.is_empty() || self.schemes.contains(url.scheme()))
+            && self
+                .port
+                .is_none_or(|port| url.port_or_known_default() == Some(port))
+            && (self.path_prefixes.is_empty()
+                || self
+                    .path_prefixes
+                    .iter()
+                    .any(|prefix| url.path().starts_with(prefix)))
+            && (self.methods.is_empty()
+                || self
+                    .methods
+                    .iter()
+                    .any(|allowed| allowed.eq_ignore_ascii_case(method.as_str())))
+    }
+}
+
+impl fmt::Display for HttpRequestRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let HttpRequestRule {
+            schemes,
+            port,
+            path_prefixes,
+            methods,
+            maximum_response_bytes,
+            maximum_requests_per_block,
+        } = self;
+        let any = || "any".to_owned();
+        let join = |set: &BTreeSet<String>| {
+            if set.is_empty() {
+                any()
+            } else {
+                set.iter().cloned().collect::<Vec<_>>().join(", ")
+            }
+        };
+        write!(
+            f,
+            "schemes: {}; port: {}; path prefixes: {}; methods: {}; \
+            maximum response bytes: {}; maximum requests per block: {}",
+            join(schemes),
+            port.map_or_else(any, |port| port.to_string()),
+            join(path_prefixes),
+            join(methods),
+            maximum_response_bytes.map_or_else(any, |bytes| bytes.to_string()),
+            maximum_requests_per_block.map_or_else(any, |count| count.to_string()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy_with_rule(rule: HttpRequestRule) -> ResourceControlPolicy {
+        ResourceControlPolicy {
+            http_request_allow_list: BTreeSet::from(["example.com".to_owned()]),
+            http_request_rules: BTreeMap::from([("api.example.com".to_owned(), rule)]),
+            ..ResourceControlPolicy::no_fees()
+        }
+    }
+
+    #[test]
+    fn test_http_request_rules() {
+        let rule = HttpRequestRule {
+            schemes: BTreeSet::from(["https".to_owned()]),
+            port: Some(443),
+            path_prefixes: BTreeSet::from(["/v1/".to_owned()]),
+            methods: BTreeSet::from(["GET".to_owned()]),
+            maximum_response_bytes: Some(100),
+            maximum_requests_per_block: Some(2),
+        };
+        let policy = policy_with_rule(rule.clone());
+        let check =
+            |url: &str, method| policy.check_http_request(&Url::parse(url).unwrap(), method);
+
+        assert_eq!(
+            check("https://api.example.com/v1/price", http::Method::Get).unwrap(),
+            Some(&rule)
+        );
+        assert_eq!(
+            check("http://example.com:8080/anything", http::Method::Post).unwrap(),
+            None
+        );
+        for (url, method) in [
+            ("http://api.example.com/v1/price", http::Method::Get),
+            ("https://api.example.com:8443/v1/price", http::Method::Get),
+            ("https://api.example.com/v2/price", http::Method::Get),
+            ("https://api.example.com/v1/price", http::Method::Post),
+            ("https://other.example.com/v1/price", http::Method::Get),
+        ] {
+            assert!(matches!(
+                check(url, method),
+                Err(ExecutionError::UnauthorizedHttpRequest(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_empty_http_request_rule_allows_everything() {
+        let policy = policy_with_rule(HttpRequestRule::default());
+        let url = Url::parse("ftp://api.example.com:21/any/path").unwrap();
+        assert!(policy
+            .check_http_request(&url, http::Method::Delete)
+            .unwrap()
+            .is_some());
+    }
+}
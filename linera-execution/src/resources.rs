@@ -28,6 +28,18 @@ pub struct ResourceController<Account = Amount, Tracker = ResourceTracker> {
     pub account: Account,
     /// When true, balance deductions are skipped (fees waived for free apps).
     pub is_free: bool,
+    /// The sponsor paying the fees of the current block, if any, with what is left of the
+    /// budget reserved from its balance.
+    pub sponsor: Option<FeeSponsor>,
+}
+
+/// An account that pays the fees of a block on behalf of the block's owner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeSponsor {
+    /// The sponsor's account.
+    pub owner: AccountOwner,
+    /// The amount that is still available to pay fees.
+    pub budget: Amount,
 }
 
 impl<Account, Tracker> ResourceController<Account, Tracker> {
@@ -38,6 +50,7 @@ impl<Account, Tracker> ResourceController<Account, Tracker> {
             tracker,
             account,
             is_free: false,
+            sponsor: None,
         }
     }
 
@@ -82,13 +95,20 @@ pub const RUNTIME_CRYPTO_HASH_SIZE: u32 = 32;
 /// The runtime size of a `VmRuntime` enum.
 pub const RUNTIME_VM_RUNTIME_SIZE: u32 = 1;
 
+/// The runtime size of a `ModuleId`: 2 hashes + `VmRuntime`.
+pub const RUNTIME_MODULE_ID_SIZE: u32 = 2 * RUNTIME_CRYPTO_HASH_SIZE + RUNTIME_VM_RUNTIME_SIZE;
+
 /// The runtime constant part size of an `ApplicationDescription`.
-/// This includes: `ModuleId` (2 hashes + VmRuntime) + `ChainId` + `BlockHeight` + `u32`.
-/// Variable parts (`parameters` and `required_application_ids`) are calculated separately.
-pub const RUNTIME_CONSTANT_APPLICATION_DESCRIPTION_SIZE: u32 = 2 * RUNTIME_CRYPTO_HASH_SIZE + RUNTIME_VM_RUNTIME_SIZE  // ModuleId
+/// This includes: `ModuleId` + `ChainId` + `BlockHeight` + `u32` + `bool` + the tag of the
+/// `Option` of the upgrade authority.
+/// Variable parts (`parameters`, `required_application_ids`, the upgrade authority and
+/// `module_history`) are calculated separately.
+pub const RUNTIME_CONSTANT_APPLICATION_DESCRIPTION_SIZE: u32 = RUNTIME_MODULE_ID_SIZE // module_id
     + RUNTIME_CHAIN_ID_SIZE                                  // creator_chain_id
     + RUNTIME_BLOCK_HEIGHT_SIZE                              // block_height
-    + 4; // application_index (u32)
+    + 4                                                      // application_index (u32)
+    + 1                                                      // allow_reentrancy (bool)
+    + 1; // upgrade_authority (Option tag)
 
 #[cfg(test)]
 mod tests {
@@ -132,12 +152,15 @@ mod tests {
             application_index: 0,
             parameters: vec![],
             required_application_ids: vec![],
+            allow_reentrancy: false,
+            upgrade_authority: None,
+            module_history: vec![],
         };
         let serialized = bcs::to_bytes(&description).expect("serialization should succeed");
-        // Serialized size = fixed fields + 2 bytes for empty vectors (1 byte each for ULEB128 length).
+        // Serialized size = fixed fields + 3 bytes for empty vectors (1 byte each for ULEB128 length).
         assert_eq!(
             serialized.len(),
-            RUNTIME_CONSTANT_APPLICATION_DESCRIPTION_SIZE as usize + 2
+            RUNTIME_CONSTANT_APPLICATION_DESCRIPTION_SIZE as usize + 3
         );
     }
 }
@@ -199,6 +222,8 @@ pub struct ResourceTracker {
     pub service_oracle_execution: Duration,
     /// The amount allocated to message grants.
     pub grants: Amount,
+    /// The total fees charged so far, including grants.
+    pub fees: Amount,
 }
 
 impl ResourceTracker {
@@ -327,6 +352,10 @@ impl fmt::Display for ResourceTracker {
             lines.push(format!("http/service: {}", http_service_parts.join(", ")));
         }
 
+        if self.fees != Amount::ZERO {
+            lines.push(format!("fees: {}", self.fees));
+        }
+
         let mut lines_iter = lines.into_iter();
         if let Some(first) = lines_iter.next() {
             write!(f, "{first}")?;
@@ -390,6 +419,7 @@ where
                 fees,
                 balance: self.balance().unwrap_or(Amount::MAX),
             })?;
+        self.tracker.as_mut().fees.saturating_add_assign(fees);
         Ok(())
     }
 
@@ -590,8 +620,16 @@ where
         let parameters_size = description.parameters.len() as u32;
         let required_apps_size =
             description.required_application_ids.len() as u32 * RUNTIME_APPLICATION_ID_SIZE;
-        let size =
-            RUNTIME_CONSTANT_APPLICATION_DESCRIPTION_SIZE + parameters_size + required_apps_size;
+        let authority_size = description
+            .upgrade_authority
+            .as_ref()
+            .map_or(0, AccountOwner::size);
+        let module_history_size = description.module_history.len() as u32 * RUNTIME_MODULE_ID_SIZE;
+        let size = RUNTIME_CONSTANT_APPLICATION_DESCRIPTION_SIZE
+            + parameters_size
+            + required_apps_size
+            + authority_size
+            + module_history_size;
         self.track_size_runtime_operations(size)
     }
 
@@ -833,9 +871,9 @@ impl ResourceController<Option<AccountOwner>, ResourceTracker> {
     /// Provides a reference to the current execution state and obtains a temporary object
     /// where the accounting functions of [`ResourceController`] are available.
     pub async fn with_state<'a, C>(
-        &mut self,
+        &'a mut self,
         view: &'a mut SystemExecutionStateView<C>,
-    ) -> Result<ResourceController<Sources<'a>, &mut ResourceTracker>, ViewError>
+    ) -> Result<ResourceController<Sources<'a>, &'a mut ResourceTracker>, ViewError>
     where
         C: Context + Clone + 'static,
     {
@@ -846,19 +884,22 @@ impl ResourceController<Option<AccountOwner>, ResourceTracker> {
     /// and obtains a temporary object where the accounting functions of
     /// [`ResourceController`] are available.
     pub async fn with_state_and_grant<'a, C>(
-        &mut self,
+        &'a mut self,
         view: &'a mut SystemExecutionStateView<C>,
         grant: Option<&'a mut Amount>,
-    ) -> Result<ResourceController<Sources<'a>, &mut ResourceTracker>, ViewError>
+    ) -> Result<ResourceController<Sources<'a>, &'a mut ResourceTracker>, ViewError>
     where
         C: Context + Clone + 'static,
     {
         let mut sources = Vec::new();
-        // First, use the grant (e.g. for messages) and otherwise use the chain account
-        // (e.g. for blocks and operations).
+        // First, use the grant (e.g. for messages) and otherwise the budget of the block's
+        // sponsor, if any, then the chain account (e.g. for blocks and operations).
         if let Some(grant) = grant {
             sources.push(grant);
         } else {
+            if let Some(sponsor) = &mut self.sponsor {
+                sources.push(&mut sponsor.budget);
+            }
             sources.push(view.balance.get_mut());
         }
         // Then the local account, if any. Currently, any negative fee (e.g. storage
@@ -874,6 +915,7 @@ impl ResourceController<Option<AccountOwner>, ResourceTracker> {
             tracker: &mut self.tracker,
             account: Sources { sources },
             is_free: self.is_free,
+            sponsor: None,
         })
     }
 }
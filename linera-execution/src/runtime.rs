@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
-    collections::{hash_map, BTreeMap, HashMap, HashSet},
+    collections::{hash_map, BTreeMap, BTreeSet, HashMap, HashSet},
     mem,
     ops::{Deref, DerefMut},
     sync::{Arc, Mutex},
@@ -10,6 +10,7 @@ use std::{
 
 use custom_debug_derive::Debug;
 use linera_base::{
+    crypto::CryptoHash,
     data_types::{
         Amount, ApplicationPermissions, ArithmeticError, Blob, BlockHeight, Bytecode,
         SendMessageRequest, Timestamp,
@@ -22,7 +23,7 @@ use linera_base::{
     time::Instant,
     vm::VmRuntime,
 };
-use linera_views::batch::Batch;
+use linera_views::batch::{Batch, WriteOperation};
 use oneshot::Receiver;
 use tracing::instrument;
 
@@ -113,6 +114,9 @@ pub struct SyncRuntimeInternal<UserInstance: WithContext> {
     call_stack: Vec<ApplicationStatus>,
     /// The set of the IDs of the applications that are in the `call_stack`.
     active_applications: HashSet<ApplicationId>,
+    /// The keys that reentrant calls have written, for each application whose instance in
+    /// `loaded_applications` may hold stale copies of them.
+    overwritten_keys: HashMap<ApplicationId, OverwrittenKeys>,
     /// The operations scheduled during this query.
     scheduled_operations: Vec<Operation>,
 
@@ -146,6 +150,58 @@ struct ApplicationStatus {
     description: ApplicationDescription,
     /// The authenticated owner for the execution thread, if any.
     signer: Option<AccountOwner>,
+    /// Whether this is a reentrant call, executed by a separate instance of the contract.
+    is_reentrant: bool,
+    /// If this is a reentrant call, the keys that reentrant calls nested in it have written.
+    overwritten_keys: OverwrittenKeys,
+}
+
+/// Storage keys of an application that were written by a reentrant call to it.
+///
+/// The other instances of the application that were running at that time may hold stale
+/// copies of these keys, so they must not write them anymore.
+#[derive(Debug, Default)]
+struct OverwrittenKeys {
+    keys: BTreeSet<Vec<u8>>,
+    key_prefixes: Vec<Vec<u8>>,
+}
+
+impl OverwrittenKeys {
+    /// Records the keys written by the `batch`.
+    fn insert(&mut self, batch: &Batch) {
+        for operation in &batch.operations {
+            match operation {
+                WriteOperation::Delete { key } | WriteOperation::Put { key, .. } => {
+                    self.keys.insert(key.clone());
+                }
+                WriteOperation::DeletePrefix { key_prefix } => {
+                    self.key_prefixes.push(key_prefix.clone());
+                }
+            }
+        }
+    }
+
+    /// Returns whether the `batch` writes any of the recorded keys.
+    fn conflict_with(&self, batch: &Batch) -> bool {
+        batch.operations.iter().any(|operation| match operation {
+            WriteOperation::Delete { key } | WriteOperation::Put { key, .. } => {
+                self.keys.contains(key)
+                    || self
+                        .key_prefixes
+                        .iter()
+                        .any(|prefix| key.starts_with(prefix))
+            }
+            WriteOperation::DeletePrefix { key_prefix } => {
+                self.keys
+                    .range(key_prefix.clone()..)
+                    .next()
+                    .is_some_and(|key| key.starts_with(key_prefix))
+                    || self.key_prefixes.iter().any(|prefix| {
+                        key_prefix.starts_with(prefix) || prefix.starts_with(key_prefix)
+                    })
+            }
+        })
+    }
 }
 
 /// A loaded application instance.
@@ -334,6 +390,7 @@ impl<UserInstance: WithContext> SyncRuntimeInternal<UserInstance> {
             loaded_applications: HashMap::new(),
             call_stack: Vec::new(),
             active_applications: HashSet::new(),
+            overwritten_keys: HashMap::new(),
             view_user_states: BTreeMap::new(),
             deadline,
             refund_grant_to,
@@ -377,18 +434,40 @@ impl<UserInstance: WithContext> SyncRuntimeInternal<UserInstance> {
             .call_stack
             .pop()
             .expect("Can't remove application from empty call stack");
-        assert!(self.active_applications.remove(&status.id));
+        if !status.is_reentrant {
+            assert!(self.active_applications.remove(&status.id));
+        }
         status
     }
 
-    /// Ensures that a call to `application_id` is not-reentrant.
+    /// Checks whether a call to `application_id` is reentrant, and if so, whether the
+    /// application allows it.
     ///
-    /// Returns an error if there already is an entry for `application_id` in the call stack.
-    fn check_for_reentrancy(&self, application_id: ApplicationId) -> Result<(), ExecutionError> {
+    /// Returns an error if there already is an entry for `application_id` in the call stack
+    /// and its description doesn't allow reentrant calls.
+    fn check_for_reentrancy(&self, application_id: ApplicationId) -> Result<bool, ExecutionError> {
+        if !self.active_applications.contains(&application_id) {
+            return Ok(false);
+        }
+        let allows_reentrancy = self
+            .call_stack
+            .iter()
+            .find(|status| status.id == application_id)
+            .is_some_and(|status| status.description.allow_reentrancy);
         ensure!(
-            !self.active_applications.contains(&application_id),
+            allows_reentrancy,
             ExecutionError::ReentrantCall(application_id)
         );
+        Ok(true)
+    }
+
+    /// Ensures that one more contract call does not exceed the maximum call depth.
+    fn check_call_depth(&self) -> Result<(), ExecutionError> {
+        let maximum = self.resource_controller.policy().maximum_call_depth;
+        ensure!(
+            self.call_stack.len() < maximum as usize,
+            ExecutionError::MaximumCallDepthExceeded(maximum)
+        );
         Ok(())
     }
 }
@@ -439,14 +518,34 @@ impl SyncRuntimeInternal<UserContractInstance> {
         }
     }
 
+    /// Creates a separate contract instance to execute a reentrant call to an application
+    /// that is already on the call stack.
+    fn instantiate_reentrant_contract(
+        &mut self,
+        this: SyncRuntimeHandle<UserContractInstance>,
+        id: ApplicationId,
+    ) -> Result<LoadedApplication<UserContractInstance>, ExecutionError> {
+        let (code, description) = self
+            .preloaded_applications
+            .get(&id)
+            .cloned()
+            .expect("Applications on the call stack should have been loaded");
+        let instance = code.instantiate(this)?;
+        Ok(LoadedApplication::new(instance, description))
+    }
+
     /// Configures the runtime for executing a call to a different contract.
+    ///
+    /// Reentrant calls are executed by a separate instance of the contract, which the caller
+    /// must finalize before [`Self::finish_call`].
     fn prepare_for_call(
         &mut self,
         this: ContractSyncRuntimeHandle,
         authenticated: bool,
         callee_id: ApplicationId,
     ) -> Result<Arc<Mutex<UserContractInstance>>, ExecutionError> {
-        self.check_for_reentrancy(callee_id)?;
+        let is_reentrant = self.check_for_reentrancy(callee_id)?;
+        self.check_call_depth()?;
 
         ensure!(
             !self.is_finalizing,
@@ -457,7 +556,11 @@ impl SyncRuntimeInternal<UserContractInstance> {
         );
 
         // Load the application.
-        let application = self.load_contract_instance(this, callee_id)?;
+        let application = if is_reentrant {
+            self.instantiate_reentrant_contract(this, callee_id)?
+        } else {
+            self.load_contract_instance(this, callee_id)?
+        };
 
         let caller = self.current_application();
         let caller_id = caller.id;
@@ -474,10 +577,44 @@ impl SyncRuntimeInternal<UserContractInstance> {
             description: application.description,
             // Allow further nested calls to be authenticated if this one is.
             signer: authenticated_owner,
+            is_reentrant,
+            overwritten_keys: OverwrittenKeys::default(),
         });
         Ok(application.instance)
     }
 
+    /// Ensures that the current application doesn't write storage that reentrant calls to it
+    /// have changed since its instance was created.
+    ///
+    /// If the current application executes a reentrant call, records the keys that the
+    /// `batch` writes for the other instances of the application.
+    fn track_reentrant_writes(&mut self, batch: &Batch) -> Result<(), ExecutionError> {
+        let (current, outer) = self
+            .call_stack
+            .split_last_mut()
+            .expect("Call stack is unexpectedly empty");
+        let id = current.id;
+        let overwritten_keys = if current.is_reentrant {
+            Some(&current.overwritten_keys)
+        } else {
+            self.overwritten_keys.get(&id)
+        };
+        ensure!(
+            !overwritten_keys.is_some_and(|keys| keys.conflict_with(batch)),
+            ExecutionError::ReentrantWriteConflict(id)
+        );
+        if current.is_reentrant {
+            self.overwritten_keys.entry(id).or_default().insert(batch);
+            for status in outer
+                .iter_mut()
+                .filter(|status| status.id == id && status.is_reentrant)
+            {
+                status.overwritten_keys.insert(batch);
+            }
+        }
+        Ok(())
+    }
+
     /// Cleans up the runtime after the execution of a call to a different contract.
     fn finish_call(&mut self) {
         self.pop_application();
@@ -1006,12 +1143,16 @@ where
         Ok(self.inner().allow_application_logs)
     }
 
-    #[cfg(web)]
     fn send_log(&mut self, message: String, level: tracing::log::Level) {
         let this = self.inner();
+        let application_id = this.current_application().id;
         // Fire-and-forget: ignore errors since logging shouldn't affect execution.
         this.execution_state_sender
-            .unbounded_send(ExecutionRequest::Log { message, level })
+            .unbounded_send(ExecutionRequest::Log {
+                application_id,
+                message,
+                level,
+            })
             .ok();
     }
 }
@@ -1139,6 +1280,7 @@ impl ContractSyncRuntimeHandle {
             UserAction::ProcessStreams(_context, updates) => {
                 code.process_streams(updates).map(|()| None)
             }
+            UserAction::Migrate(_context) => code.migrate().map(|()| None),
         };
 
         let result = self.execute(application_id, signer, closure)?;
@@ -1159,7 +1301,9 @@ impl ContractSyncRuntimeHandle {
             self.execute(application, context.authenticated_owner, |contract| {
                 contract.finalize().map(|_| None)
             })?;
-            self.inner().loaded_applications.remove(&application);
+            let mut runtime = self.inner();
+            runtime.loaded_applications.remove(&application);
+            runtime.overwritten_keys.remove(&application);
         }
 
         Ok(())
@@ -1182,6 +1326,8 @@ impl ContractSyncRuntimeHandle {
                 id: application_id,
                 description: application.description.clone(),
                 signer,
+                is_reentrant: false,
+                overwritten_keys: OverwrittenKeys::default(),
             };
 
             runtime.push_application(status);
@@ -1414,9 +1560,20 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
 
         let value = contract
             .try_lock()
-            .expect("Applications should not have reentrant calls")
+            .expect("Contract instances should not be called recursively")
             .execute_operation(argument)?;
 
+        if self.inner().current_application().is_reentrant {
+            // The separate instance must persist its state before the caller resumes.
+            self.inner().is_finalizing = true;
+            let result = contract
+                .try_lock()
+                .expect("Contract instances should not be called recursively")
+                .finalize();
+            self.inner().is_finalizing = false;
+            result?;
+        }
+
         self.inner().finish_call();
 
         Ok(value)
@@ -1700,9 +1857,23 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
             .recv_response()
     }
 
+    fn random_seed(&mut self) -> Result<CryptoHash, ExecutionError> {
+        let this = self.inner();
+        let application_id = this.current_application().id;
+        let height = this.height;
+        this.execution_state_sender
+            .send_request(|callback| ExecutionRequest::RandomSeed {
+                application_id,
+                height,
+                callback,
+            })?
+            .recv_response()
+    }
+
     fn write_batch(&mut self, batch: Batch) -> Result<(), ExecutionError> {
         let mut this = self.inner();
         let id = this.current_application().id;
+        this.track_reentrant_writes(&batch)?;
         let state = this.view_user_states.entry(id).or_default();
         state.force_all_pending_queries()?;
         this.resource_controller.track_write_operations(
@@ -1872,6 +2043,8 @@ impl ServiceRuntime for ServiceSyncRuntimeHandle {
                 id: queried_id,
                 description: application.description,
                 signer: None,
+                is_reentrant: false,
+                overwritten_keys: OverwrittenKeys::default(),
             });
             application.instance
         };
@@ -37,14 +37,31 @@ use serde::{Deserialize, Serialize};
 use crate::test_utils::SystemExecutionState;
 use crate::{
     committee::Committee, util::OracleResponseExt as _, ApplicationDescription, ApplicationId,
-    ExecutionError, ExecutionRuntimeContext, MessageContext, MessageKind, OperationContext,
-    OutgoingMessage, QueryContext, QueryOutcome, ResourceController, TransactionTracker,
+    ExecutionError, ExecutionRuntimeContext, FeeSponsor, MessageContext, MessageKind, Operation,
+    OperationContext, OutgoingMessage, QueryContext, QueryOutcome, ResourceController,
+    TransactionTracker,
 };
 
 /// The event stream name for new epochs and committees.
 pub static EPOCH_STREAM_NAME: &[u8] = &[0];
 /// The event stream name for removed epochs.
 pub static REMOVED_EPOCH_STREAM_NAME: &[u8] = &[1];
+/// The prefix of the event stream names for application upgrades. It is followed by the
+/// application ID, and the event with index `i` contains the application description after
+/// the `i + 1`-th upgrade.
+pub static APPLICATION_UPGRADE_STREAM_PREFIX: &[u8] = &[2];
+
+/// Returns the system stream on the creator chain with the upgrades of the given application.
+pub fn application_upgrade_stream_id(application_id: ApplicationId) -> StreamId {
+    let mut stream_name = APPLICATION_UPGRADE_STREAM_PREFIX.to_vec();
+    stream_name.extend_from_slice(
+        application_id
+            .application_description_hash
+            .as_bytes()
+            .as_slice(),
+    );
+    StreamId::system(stream_name)
+}
 
 /// The data stored in an epoch creation event.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -108,6 +125,12 @@ pub struct SystemExecutionStateView<C> {
     pub event_subscriptions: MapView<C, (ChainId, StreamId), EventSubscriptions>,
     /// The number of events in the streams that this chain is writing to.
     pub stream_event_counts: MapView<C, StreamId, u32>,
+    /// The accounts paying the fees of an owner's blocks, with what they cover, indexed by
+    /// the owner. What a sponsor pays is limited by its budget in `sponsor_budgets`.
+    pub sponsorships: MapView<C, AccountOwner, BTreeMap<AccountOwner, SponsorshipPolicy>>,
+    /// What sponsors may still pay for the fees of their beneficiaries' blocks. Unlike
+    /// `allowances`, these budgets can't be transferred.
+    pub sponsor_budgets: MapView<C, OwnerSpender, Amount>,
 }
 
 impl<C: Context, C2: Context> ReplaceContext<C2> for SystemExecutionStateView<C> {
@@ -132,6 +155,8 @@ impl<C: Context, C2: Context> ReplaceContext<C2> for SystemExecutionStateView<C>
             used_blobs: self.used_blobs.with_context(ctx.clone()).await,
             event_subscriptions: self.event_subscriptions.with_context(ctx.clone()).await,
             stream_event_counts: self.stream_event_counts.with_context(ctx.clone()).await,
+            sponsorships: self.sponsorships.with_context(ctx.clone()).await,
+            sponsor_budgets: self.sponsor_budgets.with_context(ctx.clone()).await,
         }
     }
 }
@@ -168,6 +193,50 @@ impl EventSubscriptions {
     }
 }
 
+/// The options of a new application.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Allocative)]
+pub struct ApplicationOptions {
+    /// The owner allowed to replace the application's module using
+    /// [`SystemOperation::UpgradeApplication`], if any.
+    #[debug(skip_if = Option::is_none)]
+    pub upgrade_authority: Option<AccountOwner>,
+    /// Whether the application accepts reentrant calls. See
+    /// [`ApplicationDescription::allow_reentrancy`].
+    pub allow_reentrancy: bool,
+}
+
+/// The blocks whose fees a sponsor pays: those where all operations are covered.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Allocative)]
+pub struct SponsorshipPolicy {
+    /// The applications whose operations are covered. If `None`, all user operations are.
+    #[debug(skip_if = Option::is_none)]
+    pub applications: Option<Vec<ApplicationId>>,
+    /// Whether system operations are covered.
+    pub system_operations: bool,
+}
+
+impl SponsorshipPolicy {
+    /// Returns whether the operation is covered.
+    pub fn covers(&self, operation: &Operation) -> bool {
+        match operation {
+            Operation::System(_) => self.system_operations,
+            Operation::User { application_id, .. } => self
+                .applications
+                .as_ref()
+                .is_none_or(|application_ids| application_ids.contains(application_id)),
+        }
+    }
+}
+
+/// An action on a user application that must follow a system operation.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ApplicationAction {
+    /// Instantiates a newly created application with the given argument.
+    Instantiate(ApplicationId, Vec<u8>),
+    /// Runs the migration entrypoint of an application whose module was replaced.
+    Migrate(ApplicationId),
+}
+
 /// The initial configuration for a new chain.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize, Allocative)]
 pub struct OpenChainConfig {
@@ -276,6 +345,40 @@ pub enum SystemOperation {
         stream_id: StreamId,
         next_index: u32,
     },
+    /// Replaces the module of an upgradable application. This is only allowed on the
+    /// creator chain, which publishes the upgraded description on the application's
+    /// [`application_upgrade_stream_id`]. Every chain that uses the application, including
+    /// the creator chain, is subscribed to that stream and adopts the upgrade with
+    /// [`SystemOperation::UpdateStream`], which runs the new contract's migration
+    /// entrypoint over the existing application state.
+    UpgradeApplication {
+        application_id: ApplicationId,
+        module_id: ModuleId,
+    },
+    /// Lets the `owner`'s account pay the fees of the blocks that `beneficiary` proposes on
+    /// this chain, up to `allowance` in total, if the `policy` covers all their operations.
+    /// The allowance replaces any previous one; zero ends the sponsorship. Unlike the
+    /// allowances that owners approve for spenders, the beneficiary can't transfer it.
+    Sponsor {
+        owner: AccountOwner,
+        beneficiary: AccountOwner,
+        allowance: Amount,
+        policy: SponsorshipPolicy,
+    },
+    /// Creates a new application, like [`SystemOperation::CreateApplication`], with
+    /// non-default options.
+    CreateApplicationWithOptions {
+        module_id: ModuleId,
+        #[serde(with = "serde_bytes")]
+        #[debug(with = "hex_debug")]
+        parameters: Vec<u8>,
+        #[serde(with = "serde_bytes")]
+        #[debug(with = "hex_debug", skip_if = Vec::is_empty)]
+        instantiation_argument: Vec<u8>,
+        #[debug(skip_if = Vec::is_empty)]
+        required_application_ids: Vec<ApplicationId>,
+        options: ApplicationOptions,
+    },
 }
 
 /// Operations that are only allowed on the admin chain.
@@ -377,9 +480,9 @@ where
         operation: SystemOperation,
         txn_tracker: &mut TransactionTracker,
         resource_controller: &mut ResourceController<Option<AccountOwner>>,
-    ) -> Result<Option<(ApplicationId, Vec<u8>)>, ExecutionError> {
+    ) -> Result<Option<ApplicationAction>, ExecutionError> {
         use SystemOperation::*;
-        let mut new_application = None;
+        let mut application_action = None;
         match operation {
             OpenChain(config) => {
                 let _chain_id = self
@@ -507,10 +610,94 @@ where
                         module_id,
                         parameters,
                         required_application_ids,
+                        ApplicationOptions::default(),
+                        txn_tracker,
+                    )
+                    .await?;
+                application_action = Some(ApplicationAction::Instantiate(
+                    app_id,
+                    instantiation_argument,
+                ));
+            }
+            CreateApplicationWithOptions {
+                module_id,
+                parameters,
+                instantiation_argument,
+                required_application_ids,
+                options,
+            } => {
+                let CreateApplicationResult { app_id } = self
+                    .create_application(
+                        context.chain_id,
+                        context.height,
+                        module_id,
+                        parameters,
+                        required_application_ids,
+                        options,
                         txn_tracker,
                     )
                     .await?;
-                new_application = Some((app_id, instantiation_argument));
+                application_action = Some(ApplicationAction::Instantiate(
+                    app_id,
+                    instantiation_argument,
+                ));
+            }
+            UpgradeApplication {
+                application_id,
+                module_id,
+            } => {
+                let original = self
+                    .describe_application(application_id, txn_tracker)
+                    .await?;
+                ensure!(
+                    original.upgrade_authority.is_some(),
+                    ExecutionError::ApplicationNotUpgradable(application_id)
+                );
+                ensure!(
+                    context.chain_id == original.creator_chain_id,
+                    ExecutionError::ApplicationUpgradeOutsideCreatorChain(application_id)
+                );
+                ensure!(
+                    context.authenticated_owner == original.upgrade_authority,
+                    ExecutionError::UnauthorizedApplicationUpgrade(application_id)
+                );
+                let stream_id = application_upgrade_stream_id(application_id);
+                let index = self
+                    .stream_event_counts
+                    .get(&stream_id)
+                    .await?
+                    .unwrap_or_default();
+                ensure!(
+                    self.adopted_upgrade_count(application_id, &original)
+                        .await?
+                        == index,
+                    ExecutionError::PendingApplicationUpgrade(application_id)
+                );
+                let current = self.current_description(application_id, original).await?;
+                let blob_ids = self.check_bytecode_blobs(&module_id, txn_tracker).await?;
+                for blob_id in blob_ids {
+                    self.blob_used(txn_tracker, blob_id).await?;
+                }
+                let upgraded = current.upgraded(module_id);
+                txn_tracker.add_event(stream_id.clone(), index, bcs::to_bytes(&upgraded)?);
+                let count = index.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+                self.stream_event_counts.insert(&stream_id, count)?;
+            }
+            Sponsor {
+                owner,
+                beneficiary,
+                allowance,
+                policy,
+            } => {
+                self.sponsor(
+                    context.authenticated_owner,
+                    None,
+                    owner,
+                    beneficiary,
+                    allowance,
+                    policy,
+                )
+                .await?;
             }
             PublishDataBlob { blob_hash } => {
                 self.blob_published(&BlobId::new(blob_hash, BlobType::Data), txn_tracker)?;
@@ -578,6 +765,16 @@ where
                     })
                     .await?;
             }
+            UpdateStream {
+                application_id,
+                chain_id,
+                stream_id,
+                next_index,
+            } if stream_id == application_upgrade_stream_id(application_id) => {
+                self.adopt_application_upgrade(application_id, chain_id, next_index, txn_tracker)
+                    .await?;
+                application_action = Some(ApplicationAction::Migrate(application_id));
+            }
             UpdateStream {
                 application_id,
                 chain_id,
@@ -633,7 +830,7 @@ where
             }
         }
 
-        Ok(new_application)
+        Ok(application_action)
     }
 
     /// Returns an error if the `provided` epoch is not exactly one higher than the chain's current
@@ -815,6 +1012,110 @@ where
         self.credit_or_send_message(owner, recipient, amount).await
     }
 
+    /// Sets the budget and the policy with which `owner` pays the fees of `beneficiary`.
+    pub async fn sponsor(
+        &mut self,
+        authenticated_owner: Option<AccountOwner>,
+        authenticated_application_id: Option<ApplicationId>,
+        owner: AccountOwner,
+        beneficiary: AccountOwner,
+        allowance: Amount,
+        policy: SponsorshipPolicy,
+    ) -> Result<(), ExecutionError> {
+        ensure!(
+            authenticated_owner == Some(owner)
+                || authenticated_application_id.map(AccountOwner::from) == Some(owner),
+            ExecutionError::UnauthenticatedTransferOwner
+        );
+        ensure!(owner != beneficiary, ExecutionError::SelfSponsorship(owner));
+        let owner_spender = OwnerSpender::new(owner, beneficiary);
+        if allowance == Amount::ZERO {
+            self.sponsor_budgets.remove(&owner_spender)?;
+        } else {
+            self.sponsor_budgets.insert(&owner_spender, allowance)?;
+        }
+        let mut sponsors = self
+            .sponsorships
+            .get(&beneficiary)
+            .await?
+            .unwrap_or_default();
+        if allowance == Amount::ZERO {
+            sponsors.remove(&owner);
+        } else {
+            sponsors.insert(owner, policy);
+        }
+        if sponsors.is_empty() {
+            self.sponsorships.remove(&beneficiary)?;
+        } else {
+            self.sponsorships.insert(&beneficiary, sponsors)?;
+        }
+        Ok(())
+    }
+
+    /// Finds a sponsor of the `beneficiary` that covers all the given operations, and
+    /// reserves as much of its balance for the block's fees as its budget permits.
+    ///
+    /// The reserved amount is taken from the sponsor's balance and budget. What is left
+    /// of it after the block must be returned with [`Self::refund_sponsored_fees`].
+    pub async fn reserve_sponsored_fees(
+        &mut self,
+        beneficiary: AccountOwner,
+        operations: &[&Operation],
+    ) -> Result<Option<FeeSponsor>, ExecutionError> {
+        if operations.is_empty() {
+            return Ok(None);
+        }
+        let Some(sponsors) = self.sponsorships.get(&beneficiary).await? else {
+            return Ok(None);
+        };
+        for (owner, policy) in sponsors {
+            if !operations.iter().all(|operation| policy.covers(operation)) {
+                continue;
+            }
+            let owner_spender = OwnerSpender::new(owner, beneficiary);
+            let Some(remaining) = self.sponsor_budgets.get(&owner_spender).await? else {
+                continue;
+            };
+            let Some(balance) = self.balances.get(&owner).await? else {
+                continue;
+            };
+            let budget = remaining.min(balance);
+            if budget == Amount::ZERO {
+                continue;
+            }
+            self.debit(&owner, budget).await?;
+            let remaining = remaining.saturating_sub(budget);
+            if remaining == Amount::ZERO {
+                self.sponsor_budgets.remove(&owner_spender)?;
+            } else {
+                self.sponsor_budgets.insert(&owner_spender, remaining)?;
+            }
+            return Ok(Some(FeeSponsor { owner, budget }));
+        }
+        Ok(None)
+    }
+
+    /// Returns what is left of a sponsor's reserved budget to its balance and its budget for
+    /// the beneficiary.
+    pub async fn refund_sponsored_fees(
+        &mut self,
+        beneficiary: AccountOwner,
+        sponsor: FeeSponsor,
+    ) -> Result<(), ExecutionError> {
+        let FeeSponsor { owner, budget } = sponsor;
+        if budget == Amount::ZERO {
+            return Ok(());
+        }
+        let balance = self.balances.get_mut_or_default(&owner).await?;
+        balance.try_add_assign(budget)?;
+        let remaining = self
+            .sponsor_budgets
+            .get_mut_or_default(&OwnerSpender::new(owner, beneficiary))
+            .await?;
+        remaining.try_add_assign(budget)?;
+        Ok(())
+    }
+
     /// Debits an [`Amount`] of tokens from an account's balance.
     async fn debit(
         &mut self,
@@ -973,6 +1274,7 @@ where
         self.closed.set(true);
     }
 
+    #[expect(clippy::too_many_arguments)]
     pub async fn create_application(
         &mut self,
         chain_id: ChainId,
@@ -980,6 +1282,7 @@ where
         module_id: ModuleId,
         parameters: Vec<u8>,
         required_application_ids: Vec<ApplicationId>,
+        options: ApplicationOptions,
         txn_tracker: &mut TransactionTracker,
     ) -> Result<CreateApplicationResult, ExecutionError> {
         let application_index = txn_tracker.next_application_index();
@@ -998,6 +1301,9 @@ where
             application_index,
             parameters,
             required_application_ids,
+            allow_reentrancy: options.allow_reentrancy,
+            upgrade_authority: options.upgrade_authority,
+            module_history: Vec::new(),
         };
         self.check_required_applications(&application_description, txn_tracker)
             .await?;
@@ -1006,9 +1312,10 @@ where
         self.used_blobs.insert(&blob.id())?;
         txn_tracker.add_created_blob(blob);
 
-        Ok(CreateApplicationResult {
-            app_id: ApplicationId::from(&application_description),
-        })
+        let app_id = ApplicationId::from(&application_description);
+        self.subscribe_to_upgrades(app_id, &application_description)
+            .await?;
+        Ok(CreateApplicationResult { app_id })
     }
 
     async fn check_required_applications(
@@ -1048,10 +1355,143 @@ where
 
         self.check_required_applications(&description, txn_tracker)
             .await?;
+        self.subscribe_to_upgrades(id, &description).await?;
 
         Ok(description)
     }
 
+    /// Subscribes this chain to the upgrades that the creator chain of an upgradable
+    /// application publishes, so that the client adopts them with
+    /// [`SystemOperation::UpdateStream`] before executing the chain's operations.
+    async fn subscribe_to_upgrades(
+        &mut self,
+        id: ApplicationId,
+        description: &ApplicationDescription,
+    ) -> Result<(), ExecutionError> {
+        if description.upgrade_authority.is_none() {
+            return Ok(());
+        }
+        let key = (
+            description.creator_chain_id,
+            application_upgrade_stream_id(id),
+        );
+        if self
+            .event_subscriptions
+            .get(&key)
+            .await?
+            .is_some_and(|subscriptions| subscriptions.applications.contains_key(&id))
+        {
+            return Ok(());
+        }
+        let subscriptions = self.event_subscriptions.get_mut_or_default(&key).await?;
+        subscriptions.applications.insert(id, 0);
+        subscriptions.recalculate_min();
+        Ok(())
+    }
+
+    /// Returns the number of upgrades of an application that this chain has adopted.
+    async fn adopted_upgrade_count(
+        &self,
+        id: ApplicationId,
+        original: &ApplicationDescription,
+    ) -> Result<u32, ExecutionError> {
+        if original.upgrade_authority.is_none() {
+            return Ok(0);
+        }
+        let key = (original.creator_chain_id, application_upgrade_stream_id(id));
+        Ok(self
+            .event_subscriptions
+            .get(&key)
+            .await?
+            .and_then(|subscriptions| subscriptions.applications.get(&id).copied())
+            .unwrap_or_default())
+    }
+
+    /// Returns the description of an application after the upgrades that this chain has
+    /// adopted, given its original description. Its module is the one to load the
+    /// application's code with.
+    ///
+    /// The bytecode blobs of the current module were already checked and recorded as used
+    /// when the upgrade was adopted.
+    pub(crate) async fn current_description(
+        &self,
+        id: ApplicationId,
+        original: ApplicationDescription,
+    ) -> Result<ApplicationDescription, ExecutionError> {
+        let count = self.adopted_upgrade_count(id, &original).await?;
+        let Some(index) = count.checked_sub(1) else {
+            return Ok(original);
+        };
+        let event_id = EventId {
+            chain_id: original.creator_chain_id,
+            stream_id: application_upgrade_stream_id(id),
+            index,
+        };
+        let bytes = self.get_event(event_id).await?;
+        Ok(bcs::from_bytes(&bytes)?)
+    }
+
+    /// Adopts the upgrades of an application that its creator chain published, up to the
+    /// event with index `next_index - 1`. Each upgrade is adopted with its own
+    /// [`SystemOperation::UpdateStream`], so that the new contract can migrate the
+    /// application state.
+    async fn adopt_application_upgrade(
+        &mut self,
+        application_id: ApplicationId,
+        chain_id: ChainId,
+        next_index: u32,
+        txn_tracker: &mut TransactionTracker,
+    ) -> Result<(), ExecutionError> {
+        let stream_id = application_upgrade_stream_id(application_id);
+        let subscriptions = self
+            .event_subscriptions
+            .get_mut(&(chain_id, stream_id.clone()))
+            .await?
+            .ok_or(ExecutionError::UnsubscribedUpdateStream)?;
+        let app_next_index = *subscriptions
+            .applications
+            .get(&application_id)
+            .ok_or(ExecutionError::UnsubscribedUpdateStream)?;
+        let index = next_index
+            .checked_sub(1)
+            .ok_or(ArithmeticError::Underflow)?;
+        ensure!(
+            index == app_next_index,
+            ExecutionError::InvalidApplicationUpgradeIndex {
+                application_id,
+                expected: app_next_index,
+                provided: index,
+            }
+        );
+        subscriptions
+            .applications
+            .insert(application_id, next_index);
+        subscriptions.recalculate_min();
+        let event_id = EventId {
+            chain_id,
+            stream_id,
+            index,
+        };
+        let bytes = txn_tracker
+            .oracle(|| async {
+                let bytes = self.get_event(event_id.clone()).await?;
+                Ok(OracleResponse::Event(
+                    event_id.clone(),
+                    Arc::unwrap_or_clone(bytes),
+                ))
+            })
+            .await?
+            .to_event(&event_id)?;
+        let description: ApplicationDescription = bcs::from_bytes(&bytes)?;
+        let blob_ids = self
+            .check_bytecode_blobs(&description.module_id, txn_tracker)
+            .await?;
+        for blob_id in blob_ids {
+            self.blob_used(txn_tracker, blob_id).await?;
+        }
+        Ok(())
+    }
+
     /// Records a blob that is used in this block. If this is the first use on this chain, creates
     /// an oracle response for it.
     pub(crate) async fn blob_used(
@@ -132,6 +132,8 @@ type ProcessStreamHandler = Box<
         + Send
         + Sync,
 >;
+type MigrateHandler =
+    Box<dyn FnOnce(&mut ContractSyncRuntimeHandle) -> Result<(), ExecutionError> + Send + Sync>;
 type FinalizeHandler =
     Box<dyn FnOnce(&mut ContractSyncRuntimeHandle) -> Result<(), ExecutionError> + Send + Sync>;
 type HandleQueryHandler = Box<
@@ -151,6 +153,8 @@ pub enum ExpectedCall {
     ExecuteMessage(#[debug(skip)] ExecuteMessageHandler),
     /// An expected call to [`UserContract::process_streams`].
     ProcessStreams(#[debug(skip)] ProcessStreamHandler),
+    /// An expected call to [`UserContract::migrate`].
+    Migrate(#[debug(skip)] MigrateHandler),
     /// An expected call to [`UserContract::finalize`].
     Finalize(#[debug(skip)] FinalizeHandler),
     /// An expected call to [`UserService::handle_query`].
@@ -164,6 +168,7 @@ impl Display for ExpectedCall {
             ExpectedCall::ExecuteOperation(_) => "execute_operation",
             ExpectedCall::ExecuteMessage(_) => "execute_message",
             ExpectedCall::ProcessStreams(_) => "process_streams",
+            ExpectedCall::Migrate(_) => "migrate",
             ExpectedCall::Finalize(_) => "finalize",
             ExpectedCall::HandleQuery(_) => "handle_query",
         };
@@ -220,6 +225,17 @@ impl ExpectedCall {
         ExpectedCall::ProcessStreams(Box::new(handler))
     }
 
+    /// Creates an [`ExpectedCall`] to the [`MockApplicationInstance`]'s [`UserContract::migrate`]
+    /// implementation, which is handled by the provided `handler`.
+    pub fn migrate(
+        handler: impl FnOnce(&mut ContractSyncRuntimeHandle) -> Result<(), ExecutionError>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        ExpectedCall::Migrate(Box::new(handler))
+    }
+
     /// Creates an [`ExpectedCall`] to the [`MockApplicationInstance`]'s [`UserContract::finalize`]
     /// implementation, which is handled by the provided `handler`.
     pub fn finalize(
@@ -318,6 +334,16 @@ impl UserContract for MockApplicationInstance<ContractSyncRuntimeHandle> {
         }
     }
 
+    fn migrate(&mut self) -> Result<(), ExecutionError> {
+        match self.next_expected_call() {
+            Some(ExpectedCall::Migrate(handler)) => handler(&mut self.runtime),
+            Some(unexpected_call) => {
+                panic!("Expected a call to `migrate`, got a call to `{unexpected_call}` instead.")
+            }
+            None => panic!("Unexpected call to `migrate`"),
+        }
+    }
+
     fn finalize(&mut self) -> Result<(), ExecutionError> {
         match self.next_expected_call() {
             Some(ExpectedCall::Finalize(handler)) => handler(&mut self.runtime),
@@ -99,6 +99,9 @@ pub fn create_dummy_user_application_description(
             application_index: index,
             required_application_ids: vec![],
             parameters: vec![],
+            allow_reentrancy: false,
+            upgrade_authority: None,
+            module_history: vec![],
         },
         contract_blob,
         service_blob,
@@ -9,12 +9,14 @@ use std::{
 
 use custom_debug_derive::Debug;
 use linera_base::{
-    data_types::{Blob, BlobContent, Event, OracleResponse, StreamUpdate, Timestamp},
+    crypto::{BcsHashable, CryptoHash},
+    data_types::{Blob, BlobContent, BlockHeight, Event, OracleResponse, StreamUpdate, Timestamp},
     ensure,
     identifiers::{ApplicationId, BlobId, ChainId, StreamId},
 };
+use serde::{Deserialize, Serialize};
 
-use crate::{ExecutionError, OutgoingMessage};
+use crate::{ApplicationLog, ApplicationLogLevel, ExecutionError, OutgoingMessage};
 
 type AppStreamUpdates = BTreeMap<(ChainId, StreamId), (u32, u32)>;
 
@@ -56,8 +58,37 @@ pub struct TransactionTracker {
     blobs_published: BTreeSet<BlobId>,
     /// Blob IDs created or published by free apps (fees waived).
     free_blob_ids: BTreeSet<BlobId>,
+    /// The hash of the previous block in the chain, whose certificate random seeds are
+    /// derived from.
+    #[debug(skip_if = Option::is_none)]
+    previous_block_hash: Option<CryptoHash>,
+    /// The hash of the signatures that confirmed the previous block, once they have been
+    /// recorded in this transaction.
+    #[debug(skip_if = Option::is_none)]
+    confirmation_signatures_hash: Option<CryptoHash>,
+    /// The number of random seeds requested so far in this transaction.
+    next_random_seed_index: u32,
+    /// Log messages emitted by applications, if application logs are allowed.
+    #[debug(skip_if = Vec::is_empty)]
+    logs: Vec<ApplicationLog>,
+    /// The number of HTTP requests performed per host in the block so far.
+    #[debug(skip_if = BTreeMap::is_empty)]
+    http_requests_per_host: BTreeMap<String, u32>,
+}
+
+/// The data from which the random seed returned to a contract is derived.
+#[derive(Serialize, Deserialize)]
+struct RandomSeedInput {
+    confirmation_signatures_hash: CryptoHash,
+    chain_id: ChainId,
+    height: BlockHeight,
+    transaction_index: u32,
+    application_id: ApplicationId,
+    index: u32,
 }
 
+impl BcsHashable<'_> for RandomSeedInput {}
+
 /// The [`TransactionTracker`] contents after a transaction has finished.
 #[derive(Debug, Default)]
 pub struct TransactionOutcome {
@@ -77,6 +108,11 @@ pub struct TransactionOutcome {
     pub blobs_published: BTreeSet<BlobId>,
     /// Blob IDs created or published by free apps (fees waived).
     pub free_blob_ids: BTreeSet<BlobId>,
+    /// Log messages emitted by applications, if application logs are allowed.
+    pub logs: Vec<ApplicationLog>,
+    /// The number of HTTP requests performed per host in the block, including this
+    /// transaction.
+    pub http_requests_per_host: BTreeMap<String, u32>,
 }
 
 impl TransactionTracker {
@@ -110,6 +146,22 @@ impl TransactionTracker {
         self
     }
 
+    /// Sets the hash of the previous block, whose certificate random seeds are derived from.
+    pub fn with_previous_block_hash(mut self, previous_block_hash: Option<CryptoHash>) -> Self {
+        self.previous_block_hash = previous_block_hash;
+        self
+    }
+
+    /// Sets the number of HTTP requests per host performed by the previous transactions in
+    /// the block.
+    pub fn with_http_requests_per_host(
+        mut self,
+        http_requests_per_host: BTreeMap<String, u32>,
+    ) -> Self {
+        self.http_requests_per_host = http_requests_per_host;
+        self
+    }
+
     pub fn local_time(&self) -> Timestamp {
         self.local_time
     }
@@ -138,6 +190,67 @@ impl TransactionTracker {
         index
     }
 
+    /// Returns the hash of the previous block, if any.
+    pub fn previous_block_hash(&self) -> Option<CryptoHash> {
+        self.previous_block_hash
+    }
+
+    /// Returns the hash of the signatures that confirmed the previous block, if they have
+    /// already been recorded in this transaction.
+    pub fn confirmation_signatures_hash(&self) -> Option<CryptoHash> {
+        self.confirmation_signatures_hash
+    }
+
+    /// Sets the hash of the signatures that confirmed the previous block.
+    pub fn set_confirmation_signatures_hash(&mut self, hash: CryptoHash) {
+        self.confirmation_signatures_hash = Some(hash);
+    }
+
+    /// Returns the next random seed for the given application, derived from the hash of the
+    /// signatures that confirmed the previous block.
+    ///
+    /// These signatures are only known once the previous block is confirmed, and the
+    /// proposer of this block cannot choose them other than among the quorums that signed
+    /// it. The seed also depends on the position of the transaction in the block and the
+    /// number of seeds requested before, so every validator computes the same value.
+    pub fn next_random_seed(
+        &mut self,
+        confirmation_signatures_hash: CryptoHash,
+        chain_id: ChainId,
+        height: BlockHeight,
+        application_id: ApplicationId,
+    ) -> CryptoHash {
+        let index = self.next_random_seed_index;
+        self.next_random_seed_index += 1;
+        CryptoHash::new(&RandomSeedInput {
+            confirmation_signatures_hash,
+            chain_id,
+            height,
+            transaction_index: self.transaction_index,
+            application_id,
+            index,
+        })
+    }
+
+    /// Counts an HTTP request to the given host, failing if this exceeds the given maximum
+    /// number of requests to that host per block.
+    pub fn track_http_request_to_host(
+        &mut self,
+        host: &str,
+        maximum_requests_per_block: Option<u32>,
+    ) -> Result<(), ExecutionError> {
+        let count = self
+            .http_requests_per_host
+            .entry(host.to_owned())
+            .or_default();
+        *count = count.saturating_add(1);
+        ensure!(
+            maximum_requests_per_block.is_none_or(|maximum| *count <= maximum),
+            ExecutionError::HttpRequestQuotaExceeded(host.to_owned())
+        );
+        Ok(())
+    }
+
     pub fn add_outgoing_message(&mut self, message: OutgoingMessage) {
         self.outgoing_messages.push(message);
     }
@@ -148,6 +261,20 @@ impl TransactionTracker {
         }
     }
 
+    pub fn add_log(
+        &mut self,
+        application_id: ApplicationId,
+        level: ApplicationLogLevel,
+        message: String,
+    ) {
+        self.logs.push(ApplicationLog {
+            transaction_index: self.transaction_index,
+            application_id,
+            level,
+            message,
+        });
+    }
+
     pub fn add_event(&mut self, stream_id: StreamId, index: u32, value: Vec<u8>) {
         self.events.push(Event {
             stream_id,
@@ -308,6 +435,11 @@ impl TransactionTracker {
             streams_to_process,
             blobs_published,
             free_blob_ids,
+            previous_block_hash: _,
+            confirmation_signatures_hash: _,
+            next_random_seed_index: _,
+            logs,
+            http_requests_per_host,
         } = self;
         ensure!(
             streams_to_process.is_empty(),
@@ -333,6 +465,8 @@ impl TransactionTracker {
             operation_result: operation_result.unwrap_or_default(),
             blobs_published,
             free_blob_ids,
+            logs,
+            http_requests_per_host,
         })
     }
 }
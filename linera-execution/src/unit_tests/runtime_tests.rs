@@ -14,7 +14,9 @@ use futures::{channel::mpsc, StreamExt};
 use linera_base::{crypto::CryptoHash, data_types::BlockHeight, identifiers::ApplicationId};
 use linera_views::batch::Batch;
 
-use super::{ApplicationStatus, SyncRuntimeHandle, SyncRuntimeInternal, WithContext};
+use super::{
+    ApplicationStatus, OverwrittenKeys, SyncRuntimeHandle, SyncRuntimeInternal, WithContext,
+};
 use crate::{
     execution_state_actor::ExecutionRequest,
     runtime::{LoadedApplication, ResourceController, SyncRuntime},
@@ -200,6 +202,8 @@ fn create_dummy_application() -> ApplicationStatus {
         id,
         description,
         signer: None,
+        is_reentrant: false,
+        overwritten_keys: OverwrittenKeys::default(),
     }
 }
 
@@ -48,6 +48,9 @@ fn expected_application_id(
         application_index,
         parameters,
         required_application_ids,
+        allow_reentrancy: false,
+        upgrade_authority: None,
+        module_history: vec![],
     };
     From::from(&description)
 }
@@ -74,12 +77,15 @@ async fn application_message_index() -> anyhow::Result<()> {
         .add_blobs([contract_blob, service_blob])
         .await?;
     let mut controller = ResourceController::default();
-    let new_application = view
+    let application_action = view
         .system
         .execute_operation(context, operation, &mut txn_tracker, &mut controller)
         .await?;
     let id = expected_application_id(&context, &module_id, vec![], vec![], 0);
-    assert_eq!(new_application, Some((id, vec![])));
+    assert_eq!(
+        application_action,
+        Some(ApplicationAction::Instantiate(id, vec![]))
+    );
 
     Ok(())
 }
@@ -97,12 +103,12 @@ async fn open_chain_message_index() {
     let mut txn_tracker = TransactionTracker::default();
     let operation = SystemOperation::OpenChain(config.clone());
     let mut controller = ResourceController::default();
-    let new_application = view
+    let application_action = view
         .system
         .execute_operation(context, operation, &mut txn_tracker, &mut controller)
         .await
         .unwrap();
-    assert_eq!(new_application, None);
+    assert_eq!(application_action, None);
     assert_eq!(
         txn_tracker.into_outcome().unwrap().blobs[0].id().blob_type,
         BlobType::ChainDescription,
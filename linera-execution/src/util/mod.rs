@@ -6,7 +6,11 @@
 mod sync_response;
 
 use futures::channel::mpsc;
-use linera_base::{data_types::OracleResponse, http::Response, identifiers::EventId};
+use linera_base::{
+    data_types::{ConfirmationSignatures, OracleResponse},
+    http::Response,
+    identifiers::EventId,
+};
 
 pub use self::sync_response::SyncSender;
 use crate::ExecutionError;
@@ -135,6 +139,8 @@ pub(crate) trait OracleResponseExt {
     fn to_http_response(&self) -> Result<Response, ExecutionError>;
 
     fn to_event(&self, event_id: &EventId) -> Result<Vec<u8>, ExecutionError>;
+
+    fn to_confirmation_signatures(&self) -> Result<&ConfirmationSignatures, ExecutionError>;
 }
 
 impl OracleResponseExt for OracleResponse {
@@ -167,4 +173,11 @@ impl OracleResponseExt for OracleResponse {
             _ => Err(ExecutionError::OracleResponseMismatch),
         }
     }
+
+    fn to_confirmation_signatures(&self) -> Result<&ConfirmationSignatures, ExecutionError> {
+        match self {
+            OracleResponse::RandomSeed(signatures) => Ok(signatures),
+            _ => Err(ExecutionError::OracleResponseMismatch),
+        }
+    }
 }
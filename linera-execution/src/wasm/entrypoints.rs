@@ -13,6 +13,7 @@ pub trait ContractEntrypoints {
     fn execute_operation(operation: Vec<u8>) -> Vec<u8>;
     fn execute_message(message: Vec<u8>);
     fn process_streams(streams: Vec<StreamUpdate>);
+    fn migrate();
     fn finalize();
 }
 
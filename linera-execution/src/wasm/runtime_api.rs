@@ -4,6 +4,7 @@
 use std::{any::Any, collections::HashMap, marker::PhantomData};
 
 use linera_base::{
+    crypto::CryptoHash,
     data_types::{
         Amount, ApplicationDescription, ApplicationPermissions, BlockHeight, Bytecode,
         SendMessageRequest, Timestamp,
@@ -291,14 +292,12 @@ where
             return Ok(());
         }
 
-        #[cfg(web)]
-        {
-            // Send log through the execution channel to the main thread
-            caller
-                .user_data_mut()
-                .runtime
-                .send_log(message.clone(), level);
-        }
+        // Record the log with the current transaction (and, on the Web, send it through the
+        // execution channel to the main thread).
+        caller
+            .user_data_mut()
+            .runtime
+            .send_log(message.clone(), level);
 
         // Also use tracing for native builds (and as a fallback on web)
         match level {
@@ -780,6 +779,16 @@ where
             .map_err(|error| RuntimeError::Custom(error.into()))
     }
 
+    /// Returns a fresh random seed, derived from the previous block and the current
+    /// application.
+    fn random_seed(caller: &mut Caller) -> Result<CryptoHash, RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime_mut()
+            .random_seed()
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
+
     /// Writes a batch of `operations` to storage.
     fn write_batch(
         caller: &mut Caller,
@@ -180,6 +180,14 @@ where
         Ok(())
     }
 
+    #[instrument(skip_all)]
+    fn migrate(&mut self) -> Result<(), ExecutionError> {
+        ContractEntrypoints::new(&mut self.instance)
+            .migrate()
+            .map_err(WasmExecutionError::from)?;
+        Ok(())
+    }
+
     #[instrument(skip_all)]
     fn finalize(&mut self) -> Result<(), ExecutionError> {
         ContractEntrypoints::new(&mut self.instance)
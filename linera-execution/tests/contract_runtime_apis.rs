@@ -3,7 +3,7 @@
 
 use std::{
     collections::{BTreeMap, BTreeSet},
-    sync::Arc,
+    sync::{Arc, Mutex},
     vec,
 };
 
@@ -12,24 +12,31 @@ use linera_base::{
     crypto::{AccountPublicKey, CryptoHash},
     data_types::{
         Amount, ApplicationDescription, ApplicationPermissions, Blob, BlockHeight, Bytecode,
-        CompressedBytecode, OracleResponse,
+        CompressedBytecode, ConfirmationSignatures, Epoch, OracleResponse, Round, Timestamp,
     },
     http,
-    identifiers::{Account, AccountOwner, ApplicationId, DataBlobHash, ModuleId},
+    identifiers::{Account, AccountOwner, ApplicationId, BlobId, DataBlobHash, ModuleId},
     ownership::ChainOwnership,
     vm::VmRuntime,
 };
 use linera_execution::{
     test_utils::{
-        create_dummy_message_context, create_dummy_operation_context, dummy_chain_description,
-        dummy_chain_description_with_ownership_and_balance, test_accounts_strategy, ExpectedCall,
-        RegisterMockApplication, SystemExecutionState,
+        create_dummy_message_context, create_dummy_operation_context,
+        create_dummy_user_application_description, dummy_chain_description,
+        dummy_chain_description_with_ownership_and_balance, dummy_committee,
+        test_accounts_strategy, ExpectedCall, MockApplication, RegisterMockApplication,
+        SystemExecutionState,
     },
-    BaseRuntime, ContractRuntime, ExecutionError, ExecutionStateActor, Message, MessageContext,
-    Operation, OperationContext, ResourceController, SystemExecutionStateView,
+    ApplicationLog, ApplicationLogLevel, BaseRuntime, ContractRuntime, ExecutionError,
+    ExecutionStateActor, HttpRequestRule, Message, MessageContext, Operation, OperationContext,
+    ResourceControlPolicy, ResourceController, ResourceTracker, SystemExecutionStateView,
     TestExecutionRuntimeContext, TransactionOutcome, TransactionTracker,
 };
-use linera_views::context::MemoryContext;
+use linera_views::{
+    batch::Batch,
+    context::{Context as _, MemoryContext},
+    views::View as _,
+};
 use test_case::{test_case, test_matrix};
 use test_strategy::proptest;
 
@@ -638,6 +645,9 @@ impl TransferTestEndpoint {
             application_index: 0,
             parameters: vec![],
             required_application_ids: vec![],
+            allow_reentrancy: false,
+            upgrade_authority: None,
+            module_history: vec![],
         }
     }
 
@@ -912,6 +922,117 @@ async fn test_perform_http_request(authorized_apps: Option<Vec<()>>) -> Result<(
     Ok(())
 }
 
+/// Tests that the number of HTTP requests to a host per block is limited by the policy,
+/// including when replaying recorded responses.
+#[test_log::test(tokio::test)]
+async fn test_http_request_quota() -> anyhow::Result<()> {
+    let description = dummy_chain_description(0);
+    let chain_id = description.id();
+    let mut committee = dummy_committee();
+    committee.policy_mut().http_request_rules = BTreeMap::from([(
+        "localhost".to_owned(),
+        HttpRequestRule {
+            maximum_requests_per_block: Some(2),
+            ..HttpRequestRule::default()
+        },
+    )]);
+    let mut view = SystemExecutionState {
+        committees: BTreeMap::from([(Epoch::ZERO, committee)]),
+        ..SystemExecutionState::new(description)
+    }
+    .into_view()
+    .await;
+    let (application_id, application, blobs) = view.register_mock_application(0).await?;
+
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            runtime.perform_http_request(http::Request::get("http://localhost/first"))?;
+            runtime.perform_http_request(http::Request::get("http://localhost/second"))?;
+            runtime.perform_http_request(http::Request::get("http://localhost/third"))?;
+            Ok(vec![])
+        },
+    ));
+
+    let context = create_dummy_operation_context(chain_id);
+    let mut controller = ResourceController::default();
+    let operation = Operation::User {
+        application_id,
+        bytes: vec![],
+    };
+    let mut oracle_responses = blobs
+        .iter()
+        .map(|blob_id| OracleResponse::Blob(*blob_id))
+        .collect::<Vec<_>>();
+    oracle_responses.extend(vec![OracleResponse::Http(http::Response::ok(vec![])); 3]);
+
+    let mut txn_tracker = TransactionTracker::new_replaying(oracle_responses);
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, operation)
+        .await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::HttpRequestQuotaExceeded(host)) if host == "localhost"
+    );
+
+    Ok(())
+}
+
+/// Tests that the maximum size of responses from a host is enforced when replaying recorded
+/// responses.
+#[test_log::test(tokio::test)]
+async fn test_http_response_size_rule_on_replay() -> anyhow::Result<()> {
+    let description = dummy_chain_description(0);
+    let chain_id = description.id();
+    let mut committee = dummy_committee();
+    committee.policy_mut().http_request_rules = BTreeMap::from([(
+        "localhost".to_owned(),
+        HttpRequestRule {
+            maximum_response_bytes: Some(10),
+            ..HttpRequestRule::default()
+        },
+    )]);
+    let mut view = SystemExecutionState {
+        committees: BTreeMap::from([(Epoch::ZERO, committee)]),
+        ..SystemExecutionState::new(description)
+    }
+    .into_view()
+    .await;
+    let (application_id, application, blobs) = view.register_mock_application(0).await?;
+
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            runtime.perform_http_request(http::Request::get("http://localhost"))?;
+            Ok(vec![])
+        },
+    ));
+
+    let context = create_dummy_operation_context(chain_id);
+    let mut controller = ResourceController::default();
+    let operation = Operation::User {
+        application_id,
+        bytes: vec![],
+    };
+    let mut oracle_responses = blobs
+        .iter()
+        .map(|blob_id| OracleResponse::Blob(*blob_id))
+        .collect::<Vec<_>>();
+    oracle_responses.push(OracleResponse::Http(http::Response::ok(vec![0; 11])));
+
+    let mut txn_tracker = TransactionTracker::new_replaying(oracle_responses);
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, operation)
+        .await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::HttpResponseSizeLimitExceeded {
+            limit: 10,
+            size: 11
+        })
+    );
+
+    Ok(())
+}
+
 /// Tests creating multiple data blobs in a single transaction.
 #[test_log::test(tokio::test)]
 async fn test_create_multiple_data_blobs() -> anyhow::Result<()> {
@@ -1092,6 +1213,185 @@ async fn test_callee_api_calls() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Tests that an application calling back into its caller fails, unless the caller allows
+/// reentrancy.
+#[test_case(false; "rejected by default")]
+#[test_case(true; "allowed by the application")]
+#[test_log::test(tokio::test)]
+async fn test_reentrant_call(allow_reentrancy: bool) -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let mut view = state.into_view().await;
+    let (caller_id, caller_application, caller_blobs) =
+        register_mock_application_with_reentrancy(&mut view, 0, allow_reentrancy).await?;
+    let (target_id, target_application, target_blobs) = view.register_mock_application(1).await?;
+
+    caller_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            let response =
+                runtime.try_call_application(/* authenticated */ false, target_id, vec![1])?;
+            assert_eq!(response, vec![2]);
+            Ok(vec![])
+        },
+    ));
+    target_application.expect_call(ExpectedCall::execute_operation(move |runtime, argument| {
+        assert_eq!(argument, vec![1]);
+        runtime.try_call_application(/* authenticated */ false, caller_id, vec![3])
+    }));
+    if allow_reentrancy {
+        // The callback is executed and finalized by a separate instance of the caller.
+        caller_application.expect_call(ExpectedCall::execute_operation(
+            move |runtime, argument| {
+                assert_eq!(argument, vec![3]);
+                assert_eq!(runtime.authenticated_caller_id().unwrap(), None);
+                Ok(vec![2])
+            },
+        ));
+        caller_application.expect_call(ExpectedCall::default_finalize());
+        target_application.expect_call(ExpectedCall::default_finalize());
+        caller_application.expect_call(ExpectedCall::default_finalize());
+    }
+
+    let context = create_dummy_operation_context(chain_id);
+    let mut controller = ResourceController::default();
+    let mut txn_tracker =
+        TransactionTracker::new_replaying_blobs(caller_blobs.iter().chain(&target_blobs));
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::User {
+                application_id: caller_id,
+                bytes: vec![],
+            },
+        )
+        .await;
+
+    if allow_reentrancy {
+        result?;
+    } else {
+        assert_matches!(result, Err(ExecutionError::ReentrantCall(id)) if id == caller_id);
+    }
+    Ok(())
+}
+
+/// Tests that an application can't overwrite the keys that a reentrant call to it has
+/// written, since its instance may hold stale copies of them.
+#[test_log::test(tokio::test)]
+async fn test_reentrant_call_write_conflict() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let mut view = state.into_view().await;
+    let (caller_id, caller_application, caller_blobs) =
+        register_mock_application_with_reentrancy(&mut view, 0, true).await?;
+    let (target_id, target_application, target_blobs) = view.register_mock_application(1).await?;
+
+    caller_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            runtime.try_call_application(/* authenticated */ false, target_id, vec![])?;
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(vec![1], vec![1]);
+            runtime
+                .write_batch(batch)
+                .expect("Keys that the reentrant call didn't write can be written");
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(vec![0], vec![1]);
+            runtime.write_batch(batch)?;
+            Ok(vec![])
+        },
+    ));
+    target_application.expect_call(ExpectedCall::execute_operation(move |runtime, _| {
+        runtime.try_call_application(/* authenticated */ false, caller_id, vec![])
+    }));
+    caller_application.expect_call(ExpectedCall::execute_operation(|runtime, _| {
+        let mut batch = Batch::new();
+        batch.put_key_value_bytes(vec![0], vec![2]);
+        runtime.write_batch(batch)?;
+        Ok(vec![])
+    }));
+    caller_application.expect_call(ExpectedCall::default_finalize());
+
+    let context = create_dummy_operation_context(chain_id);
+    let mut controller = ResourceController::default();
+    let mut txn_tracker =
+        TransactionTracker::new_replaying_blobs(caller_blobs.iter().chain(&target_blobs));
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::User {
+                application_id: caller_id,
+                bytes: vec![],
+            },
+        )
+        .await;
+
+    assert_matches!(
+        result,
+        Err(ExecutionError::ReentrantWriteConflict(id)) if id == caller_id
+    );
+    Ok(())
+}
+
+/// Registers a new [`MockApplication`] whose description does or doesn't allow reentrancy.
+async fn register_mock_application_with_reentrancy(
+    view: &mut impl RegisterMockApplication,
+    index: u32,
+    allow_reentrancy: bool,
+) -> anyhow::Result<(ApplicationId, MockApplication, [BlobId; 3])> {
+    let (mut description, contract, service) = create_dummy_user_application_description(index);
+    description.allow_reentrancy = allow_reentrancy;
+    let blob_ids = [
+        Blob::new_application_description(&description).id(),
+        contract.id(),
+        service.id(),
+    ];
+    let (application_id, application) = view
+        .register_mock_application_with(description, contract, service)
+        .await?;
+    Ok((application_id, application, blob_ids))
+}
+
+/// Tests that nested contract calls are limited by the maximum call depth of the policy.
+#[test_log::test(tokio::test)]
+async fn test_maximum_call_depth() -> anyhow::Result<()> {
+    let policy = ResourceControlPolicy {
+        maximum_call_depth: 2,
+        ..ResourceControlPolicy::default()
+    };
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let mut view = state.into_view().await;
+    let (first_id, first_application, first_blobs) = view.register_mock_application(0).await?;
+    let (second_id, second_application, second_blobs) = view.register_mock_application(1).await?;
+    let (third_id, _third_application, third_blobs) = view.register_mock_application(2).await?;
+
+    first_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            runtime.try_call_application(/* authenticated */ false, second_id, vec![])
+        },
+    ));
+    second_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _argument| {
+            runtime.try_call_application(/* authenticated */ false, third_id, vec![])
+        },
+    ));
+
+    let context = create_dummy_operation_context(chain_id);
+    let mut controller =
+        ResourceController::new(Arc::new(policy), ResourceTracker::default(), None);
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs(
+        first_blobs.iter().chain(&second_blobs).chain(&third_blobs),
+    );
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::User {
+                application_id: first_id,
+                bytes: vec![],
+            },
+        )
+        .await;
+
+    assert_matches!(result, Err(ExecutionError::MaximumCallDepthExceeded(2)));
+    Ok(())
+}
+
 /// Tests the contract system API to change the chain ownership.
 #[test_log::test(tokio::test)]
 async fn test_change_ownership_system_api() -> anyhow::Result<()> {
@@ -1181,3 +1481,160 @@ async fn test_unauthorized_change_ownership_system_api() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Tests that random seeds differ between calls, are derived from the signatures that
+/// confirmed the previous block, which are recorded as an oracle response, and are
+/// reproduced when replaying.
+#[test_log::test(tokio::test)]
+async fn test_random_seed() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let mut view = state.into_view().await;
+
+    let (application_id, application, _) = view.register_mock_application(0).await?;
+    let previous_block_hash = CryptoHash::test_hash("previous block");
+    let signatures = ConfirmationSignatures {
+        epoch: Epoch::ZERO,
+        round: Round::SingleLeader(0),
+        signatures: vec![],
+    };
+    view.context()
+        .extra()
+        .add_confirmation_signatures(previous_block_hash, signatures.clone());
+    let context = create_dummy_operation_context(chain_id);
+    let operation = Operation::User {
+        application_id,
+        bytes: vec![],
+    };
+
+    let seeds = Arc::new(Mutex::new(Vec::new()));
+    let expect_seeds = |application: &MockApplication| {
+        let seeds = seeds.clone();
+        application.expect_call(ExpectedCall::execute_operation(
+            move |runtime, _operation| {
+                let first_seed = runtime.random_seed()?;
+                let second_seed = runtime.random_seed()?;
+                assert_ne!(first_seed, second_seed);
+                seeds.lock().unwrap().push((first_seed, second_seed));
+                Ok(vec![])
+            },
+        ));
+        application.expect_call(ExpectedCall::default_finalize());
+    };
+
+    expect_seeds(&application);
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::new(Timestamp::from(0), 0, 0, 0, None, &[])
+        .with_previous_block_hash(Some(previous_block_hash));
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, operation.clone())
+        .await?;
+    let responses = txn_tracker
+        .into_outcome()?
+        .oracle_responses
+        .into_iter()
+        .filter(|response| matches!(response, OracleResponse::RandomSeed(_)))
+        .collect::<Vec<_>>();
+    assert_eq!(responses, [OracleResponse::RandomSeed(signatures)]);
+
+    // Replaying the recorded signatures yields the same seeds. The application is already
+    // loaded, so no blob responses are needed.
+    expect_seeds(&application);
+    let mut txn_tracker = TransactionTracker::new_replaying(responses.clone())
+        .with_previous_block_hash(Some(previous_block_hash));
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, operation.clone())
+        .await?;
+    assert_eq!(txn_tracker.into_outcome()?.oracle_responses, responses);
+    {
+        let seeds = seeds.lock().unwrap();
+        assert_eq!(seeds[0], seeds[1]);
+    }
+
+    // Other signatures yield other seeds.
+    expect_seeds(&application);
+    let other_responses = vec![OracleResponse::RandomSeed(ConfirmationSignatures {
+        epoch: Epoch::ZERO,
+        round: Round::SingleLeader(1),
+        signatures: vec![],
+    })];
+    let mut txn_tracker = TransactionTracker::new_replaying(other_responses)
+        .with_previous_block_hash(Some(previous_block_hash));
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, operation.clone())
+        .await?;
+    {
+        let seeds = seeds.lock().unwrap();
+        assert_ne!(seeds[0], seeds[2]);
+    }
+
+    // There are no random seeds in the first block of a chain.
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            runtime.random_seed()?;
+            Ok(vec![])
+        },
+    ));
+    let mut txn_tracker = TransactionTracker::new(Timestamp::from(0), 0, 0, 0, None, &[]);
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, operation)
+        .await;
+    assert_matches!(result, Err(ExecutionError::RandomSeedInFirstBlock));
+
+    Ok(())
+}
+
+/// Tests that log messages are recorded with the transaction that emitted them.
+#[test_log::test(tokio::test)]
+async fn test_application_logs() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let mut view = state.into_view().await;
+
+    let (application_id, application, blobs) = view.register_mock_application(0).await?;
+    let context = create_dummy_operation_context(chain_id);
+
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            runtime.send_log("first".to_owned(), tracing::log::Level::Info);
+            runtime.send_log("second".to_owned(), tracing::log::Level::Error);
+            Ok(vec![])
+        },
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::new(Timestamp::from(0), 3, 0, 0, None, &[]);
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::User {
+                application_id,
+                bytes: vec![],
+            },
+        )
+        .await?;
+    let TransactionOutcome {
+        logs,
+        oracle_responses,
+        ..
+    } = txn_tracker.into_outcome()?;
+    assert_eq!(oracle_responses.len(), blobs.len());
+    assert_eq!(
+        logs,
+        vec![
+            ApplicationLog {
+                transaction_index: 3,
+                application_id,
+                level: ApplicationLogLevel::Info,
+                message: "first".to_owned(),
+            },
+            ApplicationLog {
+                transaction_index: 3,
+                application_id,
+                level: ApplicationLogLevel::Error,
+                message: "second".to_owned(),
+            },
+        ]
+    );
+
+    Ok(())
+}
@@ -3,7 +3,11 @@
 
 //! Tests for how the runtime computes fees based on consumed resources.
 
-use std::{collections::BTreeSet, sync::Arc, vec};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+    vec,
+};
 
 use linera_base::{
     crypto::AccountPublicKey,
@@ -239,7 +243,10 @@ async fn test_fee_consumption(
         blob_byte_read: Amount::from_tokens(101),
         blob_byte_published: Amount::from_tokens(103),
         http_request_allow_list: BTreeSet::new(),
+        http_request_rules: BTreeMap::new(),
         free_application_ids: BTreeSet::new(),
+        maximum_call_depth: u32::MAX,
+        commit_state_roots: false,
     };
 
     let consumed_fees = spends
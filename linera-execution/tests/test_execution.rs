@@ -5,27 +5,32 @@ use std::{collections::BTreeMap, vec};
 
 use assert_matches::assert_matches;
 use linera_base::{
-    crypto::{AccountPublicKey, ValidatorPublicKey},
+    crypto::{AccountPublicKey, CryptoHash, ValidatorPublicKey},
     data_types::{
-        Amount, ApplicationPermissions, Blob, BlockHeight, ChainDescription, ChainOrigin, Epoch,
-        InitialChainConfig, Resources, SendMessageRequest, Timestamp,
+        Amount, ApplicationDescription, ApplicationPermissions, Blob, BlockHeight,
+        ChainDescription, ChainOrigin, Epoch, Event, InitialChainConfig, OracleResponse, Resources,
+        SendMessageRequest, Timestamp,
     },
-    identifiers::{Account, AccountOwner, BlobType},
+    identifiers::{Account, AccountOwner, ApplicationId, BlobId, BlobType, EventId},
     ownership::ChainOwnership,
 };
 use linera_execution::{
     committee::Committee,
+    system::application_upgrade_stream_id,
     test_utils::{
         blob_oracle_responses, create_dummy_message_context, create_dummy_operation_context,
-        create_dummy_user_application_registrations, dummy_chain_description,
-        dummy_chain_description_with_ownership_and_balance, ExpectedCall, RegisterMockApplication,
-        SystemExecutionState,
+        create_dummy_user_application_description, create_dummy_user_application_registrations,
+        dummy_chain_description, dummy_chain_description_with_ownership_and_balance, ExpectedCall,
+        MockApplication, RegisterMockApplication, SystemExecutionState,
     },
     BaseRuntime, ContractRuntime, ExecutionError, ExecutionRuntimeContext, ExecutionStateActor,
     Message, Operation, OperationContext, OutgoingMessage, Query, QueryContext, QueryOutcome,
     QueryResponse, ResourceController, SystemOperation, TransactionTracker,
 };
-use linera_views::{batch::Batch, context::Context, views::View};
+use linera_views::{
+    batch::Batch, context::Context, proof::ProofError, store::WritableKeyValueStore as _,
+    views::View,
+};
 use test_case::test_case;
 
 #[tokio::test]
@@ -1447,3 +1452,408 @@ async fn test_read_application_description() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Registers an application with an upgrade authority, created on chain 1, and the
+/// description it has after upgrading it to the module with index 1.
+async fn register_upgradable_application(
+    view: &mut impl RegisterMockApplication,
+    authority: AccountOwner,
+) -> anyhow::Result<(
+    ApplicationId,
+    ApplicationDescription,
+    MockApplication,
+    MockApplication,
+    [BlobId; 2],
+)> {
+    let (old_description, old_contract, old_service) = create_dummy_user_application_description(0);
+    let original = ApplicationDescription {
+        upgrade_authority: Some(authority),
+        ..old_description
+    };
+    let (application_id, old_application) = view
+        .register_mock_application_with(original.clone(), old_contract, old_service)
+        .await?;
+    let (new_module_description, new_contract, new_service) =
+        create_dummy_user_application_description(1);
+    let new_blob_ids = [new_contract.id(), new_service.id()];
+    let upgraded = original.upgraded(new_module_description.module_id);
+    let (_, new_application) = view
+        .register_mock_application_with(upgraded.clone(), new_contract, new_service)
+        .await?;
+    Ok((
+        application_id,
+        upgraded,
+        old_application,
+        new_application,
+        new_blob_ids,
+    ))
+}
+
+/// Tests upgrading the module of an upgradable application on its creator chain, and
+/// adopting the upgrade there by running its migration.
+#[tokio::test]
+async fn test_upgrade_application() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(1);
+    let mut view = state.into_view().await;
+    let authority = AccountOwner::from(AccountPublicKey::test_key(0));
+    let (application_id, upgraded, _old_application, new_application, new_blob_ids) =
+        register_upgradable_application(&mut view, authority).await?;
+    let new_module_id = upgraded.module_id;
+    let stream_id = application_upgrade_stream_id(application_id);
+    let operation = SystemOperation::UpgradeApplication {
+        application_id,
+        module_id: new_module_id,
+    };
+    let mut controller = ResourceController::default();
+
+    // Only the upgrade authority can replace the module.
+    let context = OperationContext {
+        authenticated_owner: Some(AccountOwner::from(AccountPublicKey::test_key(1))),
+        ..create_dummy_operation_context(chain_id)
+    };
+    let mut txn_tracker = TransactionTracker::default();
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, operation.clone().into())
+        .await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::UnauthorizedApplicationUpgrade(id)) if id == application_id
+    );
+
+    // The upgraded description is published, for all chains using the application.
+    let context = OperationContext {
+        authenticated_owner: Some(authority),
+        ..create_dummy_operation_context(chain_id)
+    };
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs(new_blob_ids);
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, operation.clone().into())
+        .await?;
+    let event_bytes = bcs::to_bytes(&upgraded)?;
+    assert_eq!(
+        txn_tracker.into_outcome()?.events,
+        vec![Event {
+            stream_id: stream_id.clone(),
+            index: 0,
+            value: event_bytes.clone(),
+        }]
+    );
+    assert_eq!(upgraded.module_history.len(), 1);
+    let event_id = EventId {
+        chain_id,
+        stream_id: stream_id.clone(),
+        index: 0,
+    };
+    view.context()
+        .extra()
+        .add_events([(event_id.clone(), event_bytes.clone())])
+        .await?;
+
+    // The next upgrade must wait until the creator chain adopted this one.
+    let mut txn_tracker = TransactionTracker::default();
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, operation.into())
+        .await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::PendingApplicationUpgrade(id)) if id == application_id
+    );
+
+    // The new contract migrates the existing state.
+    new_application.expect_call(ExpectedCall::migrate(|runtime| {
+        let mut batch = Batch::new();
+        batch.put_key_value_bytes(vec![], b"migrated".to_vec());
+        runtime.write_batch(batch)?;
+        Ok(())
+    }));
+    new_application.expect_call(ExpectedCall::default_finalize());
+    let adoption = SystemOperation::UpdateStream {
+        application_id,
+        chain_id,
+        stream_id: stream_id.clone(),
+        next_index: 1,
+    };
+    let mut txn_tracker =
+        TransactionTracker::new_replaying(vec![OracleResponse::Event(event_id, event_bytes)]);
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, adoption.clone().into())
+        .await?;
+    let subscriptions = view
+        .system
+        .event_subscriptions
+        .get(&(chain_id, stream_id))
+        .await?
+        .expect("the creator chain should be subscribed to the upgrades");
+    assert_eq!(subscriptions.applications.get(&application_id), Some(&1));
+
+    // Upgrades must be adopted in order.
+    let mut txn_tracker = TransactionTracker::default();
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, adoption.into())
+        .await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::InvalidApplicationUpgradeIndex {
+            expected: 1,
+            provided: 0,
+            ..
+        })
+    );
+
+    // Later operations are executed by the new contract, over the migrated state.
+    new_application.expect_call(ExpectedCall::execute_operation(|runtime, _operation| {
+        assert_eq!(
+            runtime.read_value_bytes(vec![])?,
+            Some(b"migrated".to_vec())
+        );
+        Ok(vec![])
+    }));
+    new_application.expect_call(ExpectedCall::default_finalize());
+
+    let mut txn_tracker = TransactionTracker::new_replaying(Vec::new());
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::User {
+                application_id,
+                bytes: vec![],
+            },
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Tests that a chain using an upgradable application subscribes to its upgrades and adopts
+/// them, but cannot publish upgrades itself.
+#[tokio::test]
+async fn test_adopt_application_upgrade() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let mut view = state.into_view().await;
+    let authority = AccountOwner::from(AccountPublicKey::test_key(0));
+    let (application_id, upgraded, old_application, new_application, new_blob_ids) =
+        register_upgradable_application(&mut view, authority).await?;
+    let creator_chain_id = upgraded.creator_chain_id;
+    let stream_id = application_upgrade_stream_id(application_id);
+    let event_id = EventId {
+        chain_id: creator_chain_id,
+        stream_id: stream_id.clone(),
+        index: 0,
+    };
+    let event_bytes = bcs::to_bytes(&upgraded)?;
+    view.context()
+        .extra()
+        .add_events([(event_id.clone(), event_bytes.clone())])
+        .await?;
+    let context = OperationContext {
+        authenticated_owner: Some(authority),
+        ..create_dummy_operation_context(chain_id)
+    };
+    let mut controller = ResourceController::default();
+
+    // Upgrades are only adopted by chains that use the application.
+    let adoption = SystemOperation::UpdateStream {
+        application_id,
+        chain_id: creator_chain_id,
+        stream_id: stream_id.clone(),
+        next_index: 1,
+    };
+    let mut txn_tracker = TransactionTracker::default();
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, adoption.clone().into())
+        .await;
+    assert_matches!(result, Err(ExecutionError::UnsubscribedUpdateStream));
+
+    // Using the application subscribes the chain to its upgrades.
+    old_application.expect_call(ExpectedCall::execute_operation(|_runtime, _operation| {
+        Ok(vec![])
+    }));
+    old_application.expect_call(ExpectedCall::default_finalize());
+    let mut txn_tracker = TransactionTracker::default();
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::User {
+                application_id,
+                bytes: vec![],
+            },
+        )
+        .await?;
+    let subscriptions = view
+        .system
+        .event_subscriptions
+        .get(&(creator_chain_id, stream_id.clone()))
+        .await?
+        .expect("the chain should be subscribed to the upgrades");
+    assert_eq!(subscriptions.applications.get(&application_id), Some(&0));
+
+    // Only the creator chain can upgrade the application.
+    let operation = SystemOperation::UpgradeApplication {
+        application_id,
+        module_id: upgraded.module_id,
+    };
+    let mut txn_tracker = TransactionTracker::default();
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, operation.into())
+        .await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::ApplicationUpgradeOutsideCreatorChain(id)) if id == application_id
+    );
+
+    new_application.expect_call(ExpectedCall::migrate(|_runtime| Ok(())));
+    new_application.expect_call(ExpectedCall::default_finalize());
+    let oracle_responses = [OracleResponse::Event(event_id, event_bytes)]
+        .into_iter()
+        .chain(
+            new_blob_ids
+                .iter()
+                .map(|blob_id| OracleResponse::Blob(*blob_id)),
+        )
+        .collect();
+    let mut txn_tracker = TransactionTracker::new_replaying(oracle_responses);
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, adoption.into())
+        .await?;
+    let subscriptions = view
+        .system
+        .event_subscriptions
+        .get(&(creator_chain_id, stream_id))
+        .await?
+        .expect("the chain should be subscribed to the upgrades");
+    assert_eq!(subscriptions.applications.get(&application_id), Some(&1));
+
+    Ok(())
+}
+
+/// Tests that applications not declared upgradable cannot be upgraded.
+#[tokio::test]
+async fn test_upgrade_non_upgradable_application() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let mut view = state.into_view().await;
+
+    let (application_id, _application, _blobs) = view.register_mock_application(0).await?;
+    let (new_module_description, _, _) = create_dummy_user_application_description(1);
+
+    let context = OperationContext {
+        authenticated_owner: Some(AccountOwner::from(AccountPublicKey::test_key(0))),
+        ..create_dummy_operation_context(chain_id)
+    };
+    let operation = SystemOperation::UpgradeApplication {
+        application_id,
+        module_id: new_module_description.module_id,
+    };
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::default();
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, operation.into())
+        .await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::ApplicationNotUpgradable(id)) if id == application_id
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_application_state_proof() -> anyhow::Result<()> {
+    let (mut state, _) = SystemExecutionState::dummy_chain_state(0);
+    for committee in state.committees.values_mut() {
+        committee.policy_mut().commit_state_roots = true;
+    }
+    let mut view = state.into_view().await;
+    let (application_id, _, _) = view.register_mock_application(0).await?;
+    let (other_id, _, _) = view.register_mock_application(1).await?;
+    view.users
+        .try_load_entry_mut(&application_id)
+        .await?
+        .insert(vec![1], vec![2])
+        .await?;
+    let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+    view.system.balances.insert(&owner, Amount::ONE)?;
+    let state_hash = view.crypto_hash_mut().await?;
+    let mut batch = Batch::new();
+    view.pre_save(&mut batch)?;
+    view.context().store().write_batch(batch).await?;
+    view.post_save();
+
+    let proof = view.application_state_proof(application_id, &[1]).await?;
+    assert_eq!(
+        proof.verify(&state_hash, application_id, &[1])?,
+        Some([2].as_slice())
+    );
+    assert!(proof.verify(&state_hash, other_id, &[1]).is_err());
+    let other_state_hash = CryptoHash::test_hash("other state");
+    assert_eq!(
+        proof.verify(&other_state_hash, application_id, &[1]),
+        Err(ProofError::WrongRoot)
+    );
+
+    let proof = view.application_state_proof(application_id, &[3]).await?;
+    assert_eq!(proof.verify(&state_hash, application_id, &[3])?, None);
+    let proof = view.application_state_proof(other_id, &[1]).await?;
+    assert_eq!(proof.verify(&state_hash, other_id, &[1])?, None);
+
+    let proof = view.balance_proof(owner).await?;
+    assert_eq!(proof.verify(&state_hash, owner)?, Amount::ONE);
+    let proof = view.balance_proof(AccountOwner::CHAIN).await?;
+    assert_eq!(
+        proof.verify(&state_hash, AccountOwner::CHAIN)?,
+        *view.system.balance.get()
+    );
+    assert!(proof.verify(&state_hash, owner).is_err());
+
+    // Changing an application's state changes the state hash, and only the changed entries
+    // are updated in the trees.
+    view.users
+        .try_load_entry_mut(&application_id)
+        .await?
+        .insert(vec![1], vec![3])
+        .await?;
+    view.system.balances.remove(&owner)?;
+    let new_state_hash = view.crypto_hash_mut().await?;
+    assert_ne!(new_state_hash, state_hash);
+    let mut batch = Batch::new();
+    view.pre_save(&mut batch)?;
+    view.context().store().write_batch(batch).await?;
+    view.post_save();
+    let proof = view.application_state_proof(application_id, &[1]).await?;
+    assert_eq!(
+        proof.verify(&new_state_hash, application_id, &[1])?,
+        Some([3].as_slice())
+    );
+    let proof = view.balance_proof(owner).await?;
+    assert_eq!(proof.verify(&new_state_hash, owner)?, Amount::ZERO);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_state_hash_without_state_roots() -> anyhow::Result<()> {
+    let (state, _) = SystemExecutionState::dummy_chain_state(0);
+    let mut view = state.into_view().await;
+    let (application_id, _, _) = view.register_mock_application(0).await?;
+    view.users
+        .try_load_entry_mut(&application_id)
+        .await?
+        .insert(vec![1], vec![2])
+        .await?;
+    let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+    view.system.balances.insert(&owner, Amount::ONE)?;
+    view.crypto_hash_mut().await?;
+    let mut batch = Batch::new();
+    view.pre_save(&mut batch)?;
+    view.context().store().write_batch(batch).await?;
+    view.post_save();
+
+    // Unless the policy requires it, the state hash doesn't commit to the state roots, and
+    // there is nothing to prove entries with.
+    assert!(!view.has_state_roots());
+    assert!(view.state_roots().await.is_err());
+    assert!(view.balance_proof(owner).await.is_err());
+    assert!(view
+        .application_state_proof(application_id, &[1])
+        .await
+        .is_err());
+    Ok(())
+}
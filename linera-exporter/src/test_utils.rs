@@ -228,6 +228,7 @@ impl ValidatorNode for DummyValidator {
             count_received_log: 0,
             requested_received_log: vec![],
             requested_previous_event_blocks: BTreeMap::new(),
+            requested_balance_proof: None,
         };
 
         let response = if missing_blobs.is_empty() {
@@ -301,6 +302,13 @@ impl ValidatorNode for DummyValidator {
         unimplemented!()
     }
 
+    async fn handle_chain_summaries_query(
+        &self,
+        _request: Request<linera_rpc::grpc::api::ChainSummariesQuery>,
+    ) -> Result<Response<linera_rpc::grpc::api::ChainSummariesResult>, Status> {
+        unimplemented!()
+    }
+
     async fn subscribe(
         &self,
         _request: Request<linera_rpc::grpc::api::SubscriptionRequest>,
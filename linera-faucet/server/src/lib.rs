@@ -841,6 +841,16 @@ where
         }
     }
 
+    /// Limits future batches to `max_batch_size` requests and puts the given requests back
+    /// into the queue.
+    async fn reduce_batch_size(&mut self, max_batch_size: usize, requests: Vec<PendingRequest>) {
+        self.config.max_batch_size = max_batch_size;
+        let mut pending_requests = self.pending_requests.lock().await;
+        for request in requests.into_iter().rev() {
+            pending_requests.push_front(request);
+        }
+    }
+
     /// Executes a batch of chain creation and/or token transfer requests.
     async fn execute_batch(&mut self, requests: Vec<PendingRequest>) -> anyhow::Result<()> {
         if let Err(err) = self.check_rate_limiting(&requests).await {
@@ -914,12 +924,7 @@ where
                                 .inc();
                         }
 
-                        self.config.max_batch_size = i as usize;
-                        // Put the valid requests back into the queue.
-                        let mut pending_requests = self.pending_requests.lock().await;
-                        for request in requests.into_iter().rev() {
-                            pending_requests.push_front(request);
-                        }
+                        self.reduce_batch_size(i as usize, requests).await;
                         return Ok(()); // Don't return an error, so we retry.
                     }
                     chain_err => {
@@ -931,6 +936,20 @@ where
                     }
                 }
             }
+            Err(chain_client::Error::InsufficientFunding {
+                context: ChainExecutionContext::Operation(i),
+                ..
+            }) if i > 0 => {
+                tracing::error!("Funding for operation {i} is insufficient; reducing batch size");
+
+                #[cfg(with_metrics)]
+                metrics::RETRYABLE_ERRORS
+                    .with_label_values(&["fees_exceed_funding"])
+                    .inc();
+
+                self.reduce_batch_size(i as usize, requests).await;
+                return Ok(()); // Don't return an error, so we retry.
+            }
             Err(err) => {
                 tracing::debug!("Error executing operations: {err}");
                 Self::send_err(requests, err.to_string());
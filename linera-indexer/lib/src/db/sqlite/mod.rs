@@ -280,6 +280,11 @@ impl SqliteDatabase {
                     SystemOperation::ProcessNewEpoch(_) => "ProcessNewEpoch",
                     SystemOperation::ProcessRemovedEpoch(_) => "ProcessRemovedEpoch",
                     SystemOperation::UpdateStream { .. } => "UpdateStream",
+                    SystemOperation::UpgradeApplication { .. } => "UpgradeApplication",
+                    SystemOperation::Sponsor { .. } => "Sponsor",
+                    SystemOperation::CreateApplicationWithOptions { .. } => {
+                        "CreateApplicationWithOptions"
+                    }
                     SystemOperation::ChangeOwnership { .. } => "ChangeOwnership",
                     SystemOperation::VerifyBlob { .. } => "VerifyBlob",
                 };
@@ -437,6 +442,14 @@ impl SqliteDatabase {
                     })?;
                     ("EventExists", None, Some(serialized))
                 }
+                OracleResponse::RandomSeed(signatures) => {
+                    let serialized = bincode::serialize(signatures).map_err(|e| {
+                        SqliteError::Serialization(format!(
+                            "Failed to serialize random seed signatures: {e}"
+                        ))
+                    })?;
+                    ("RandomSeed", None, Some(serialized))
+                }
             };
 
         sqlx::query(
@@ -0,0 +1,146 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compares the TCP and QUIC transports of the simple network protocol.
+
+use std::{path::Path, sync::Arc};
+
+use async_trait::async_trait;
+use criterion::{criterion_group, criterion_main, Criterion};
+use futures::{stream::FuturesUnordered, SinkExt as _, StreamExt as _};
+use linera_base::{
+    crypto::CryptoHash,
+    data_types::BlobContent,
+    identifiers::{BlobId, BlobType},
+    port::get_free_port,
+};
+use linera_rpc::{
+    simple::{
+        Connector, MessageHandler, TransportProtocol, QUIC_CA_CERTIFICATE_ENV_VAR,
+        QUIC_CERTIFICATE_ENV_VAR, QUIC_PRIVATE_KEY_ENV_VAR,
+    },
+    RpcMessage,
+};
+use tokio::{runtime::Runtime, task::JoinSet};
+use tokio_util::sync::CancellationToken;
+
+/// The size of the large responses.
+const LARGE_RESPONSE_BYTES: usize = 4 << 20;
+
+/// The number of large downloads sent concurrently with the small queries.
+const LARGE_REQUESTS: usize = 4;
+
+/// The number of small queries.
+const SMALL_REQUESTS: usize = 64;
+
+/// Answers version queries, and blob downloads with a large blob.
+#[derive(Clone)]
+struct Handler {
+    blob: Arc<BlobContent>,
+}
+
+#[async_trait]
+impl MessageHandler for Handler {
+    async fn handle_message(&mut self, message: RpcMessage) -> Option<RpcMessage> {
+        match message {
+            RpcMessage::VersionInfoQuery => Some(RpcMessage::VersionInfoResponse(Box::default())),
+            RpcMessage::DownloadBlob(_) => Some(RpcMessage::DownloadBlobResponse(Box::new(
+                (*self.blob).clone(),
+            ))),
+            _ => None,
+        }
+    }
+}
+
+/// Sends `message` on a new transport and waits for the response.
+async fn request(connector: &Connector, port: u16, message: RpcMessage) {
+    let mut transport = connector.connect("localhost", port).await.unwrap();
+    transport.send(message).await.unwrap();
+    transport.next().await.unwrap().unwrap();
+}
+
+/// Measures small queries sent while large responses are being downloaded, over each
+/// transport.
+fn small_requests_during_large_downloads(criterion: &mut Criterion) {
+    let directory = tempfile::tempdir().unwrap();
+    write_certificates(directory.path());
+    let runtime = Runtime::new().unwrap();
+    let blob_id = BlobId::new(CryptoHash::test_hash("blob"), BlobType::Data);
+
+    for protocol in [TransportProtocol::Tcp, TransportProtocol::Quic] {
+        let shutdown_signal = CancellationToken::new();
+        let mut join_set = JoinSet::new();
+        let port = runtime.block_on(get_free_port()).unwrap();
+        let handler = Handler {
+            blob: Arc::new(BlobContent::new_data(vec![0; LARGE_RESPONSE_BYTES])),
+        };
+        runtime.block_on(async {
+            protocol.spawn_server(
+                ("127.0.0.1", port),
+                handler,
+                shutdown_signal.clone(),
+                &mut join_set,
+            );
+            // Wait until the server accepts requests.
+            let connector = protocol.connector();
+            while connector.connect("localhost", port).await.is_err() {
+                tokio::task::yield_now().await;
+            }
+        });
+        let connector = protocol.connector();
+
+        criterion.bench_function(
+            &format!("{protocol}_small_requests_during_large_downloads"),
+            |bencher| {
+                bencher.to_async(&runtime).iter(|| async {
+                    let downloads = (0..LARGE_REQUESTS)
+                        .map(|_| {
+                            request(
+                                &connector,
+                                port,
+                                RpcMessage::DownloadBlob(Box::new(blob_id)),
+                            )
+                        })
+                        .collect::<FuturesUnordered<_>>();
+                    let queries = (0..SMALL_REQUESTS)
+                        .map(|_| request(&connector, port, RpcMessage::VersionInfoQuery))
+                        .collect::<FuturesUnordered<_>>();
+                    futures::join!(downloads.collect::<()>(), queries.collect::<()>());
+                })
+            },
+        );
+
+        shutdown_signal.cancel();
+        runtime.block_on(async { while join_set.join_next().await.is_some() {} });
+    }
+}
+
+/// Writes a certificate for `localhost`, and configures the QUIC servers and clients to use
+/// it.
+fn write_certificates(directory: &Path) {
+    let mut ca_params = rcgen::CertificateParams::new(vec![]);
+    ca_params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+    let ca = rcgen::Certificate::from_params(ca_params).unwrap();
+    let certificate =
+        rcgen::Certificate::from_params(rcgen::CertificateParams::new(
+            vec!["localhost".to_owned()],
+        ))
+        .unwrap();
+    std::fs::write(directory.join("ca.pem"), ca.serialize_pem().unwrap()).unwrap();
+    std::fs::write(
+        directory.join("cert.pem"),
+        certificate.serialize_pem_with_signer(&ca).unwrap(),
+    )
+    .unwrap();
+    std::fs::write(
+        directory.join("key.pem"),
+        certificate.serialize_private_key_pem(),
+    )
+    .unwrap();
+    std::env::set_var(QUIC_CA_CERTIFICATE_ENV_VAR, directory.join("ca.pem"));
+    std::env::set_var(QUIC_CERTIFICATE_ENV_VAR, directory.join("cert.pem"));
+    std::env::set_var(QUIC_PRIVATE_KEY_ENV_VAR, directory.join("key.pem"));
+}
+
+criterion_group!(benches, small_requests_during_large_downloads);
+criterion_main!(benches);
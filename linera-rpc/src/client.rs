@@ -15,7 +15,7 @@ use linera_chain::{
     },
 };
 use linera_core::{
-    data_types::{ChainInfoQuery, ChainInfoResponse},
+    data_types::{ChainInfoQuery, ChainInfoResponse, ChainSummary},
     node::{BlobStream, CrossChainMessageDelivery, NodeError, NotificationStream, ValidatorNode},
 };
 
@@ -298,6 +298,24 @@ impl ValidatorNode for Client {
         })
     }
 
+    async fn handle_chain_summaries_query(
+        &self,
+        chain_ids: Vec<ChainId>,
+    ) -> Result<Vec<Result<ChainSummary, NodeError>>, NodeError> {
+        Ok(match self {
+            Client::Grpc(grpc_client) => {
+                grpc_client.handle_chain_summaries_query(chain_ids).await?
+            }
+
+            #[cfg(with_simple_network)]
+            Client::Simple(simple_client) => {
+                simple_client
+                    .handle_chain_summaries_query(chain_ids)
+                    .await?
+            }
+        })
+    }
+
     async fn blob_last_used_by(&self, blob_id: BlobId) -> Result<CryptoHash, NodeError> {
         Ok(match self {
             Client::Grpc(grpc_client) => grpc_client.blob_last_used_by(blob_id).await?,
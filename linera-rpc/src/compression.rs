@@ -0,0 +1,125 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compression of large RPC messages, such as certificates and blobs.
+//!
+//! Clients choose whether they want compressed responses. Validators compress the responses
+//! that are at least [`COMPRESSION_THRESHOLD`] bytes long with the algorithm the client asked
+//! for.
+
+#[cfg(with_simple_network)]
+use std::io::{self, Read as _};
+
+/// Messages smaller than this, in bytes, are always sent uncompressed.
+pub const COMPRESSION_THRESHOLD: usize = 1024;
+
+/// A compression algorithm for RPC messages.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum, serde::Serialize, serde::Deserialize,
+)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// Returns the name of the algorithm, as used in metrics.
+    pub fn name(self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+        }
+    }
+
+    /// Returns the corresponding gRPC compression encoding.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn grpc_encoding(self) -> tonic::codec::CompressionEncoding {
+        match self {
+            Compression::Gzip => tonic::codec::CompressionEncoding::Gzip,
+            Compression::Zstd => tonic::codec::CompressionEncoding::Zstd,
+        }
+    }
+
+    /// Compresses the `payload`.
+    #[cfg(with_simple_network)]
+    pub(crate) fn compress(self, payload: &[u8]) -> Result<Vec<u8>, io::Error> {
+        let mut compressed = Vec::new();
+        match self {
+            Compression::Gzip => {
+                flate2::read::GzEncoder::new(payload, flate2::Compression::default())
+                    .read_to_end(&mut compressed)?;
+            }
+            Compression::Zstd => {
+                zstd::stream::read::Encoder::new(payload, zstd::DEFAULT_COMPRESSION_LEVEL)?
+                    .read_to_end(&mut compressed)?;
+            }
+        }
+        Ok(compressed)
+    }
+
+    /// Decompresses the `payload`, failing if the result is longer than `max_size` bytes.
+    #[cfg(with_simple_network)]
+    pub(crate) fn decompress(self, payload: &[u8], max_size: usize) -> Result<Vec<u8>, io::Error> {
+        let mut decompressed = Vec::new();
+        let limit = max_size as u64 + 1;
+        match self {
+            Compression::Gzip => {
+                flate2::read::GzDecoder::new(payload)
+                    .take(limit)
+                    .read_to_end(&mut decompressed)?;
+            }
+            Compression::Zstd => {
+                zstd::stream::read::Decoder::new(payload)?
+                    .take(limit)
+                    .read_to_end(&mut decompressed)?;
+            }
+        }
+        if decompressed.len() > max_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("decompressed message is larger than {max_size} bytes"),
+            ));
+        }
+        Ok(decompressed)
+    }
+}
+
+/// Records that a message of `uncompressed_size` bytes was sent in `compressed_size` bytes.
+#[cfg(all(with_simple_network, with_metrics))]
+pub(crate) fn record_compression(
+    compression: Compression,
+    uncompressed_size: usize,
+    compressed_size: usize,
+) {
+    let labels = [compression.name()];
+    metrics::COMPRESSED_MESSAGES
+        .with_label_values(&labels)
+        .inc();
+    metrics::COMPRESSION_SAVED_BYTES
+        .with_label_values(&labels)
+        .inc_by(uncompressed_size.saturating_sub(compressed_size) as u64);
+}
+
+#[cfg(all(with_simple_network, with_metrics))]
+mod metrics {
+    use std::sync::LazyLock;
+
+    use linera_base::prometheus_util::register_int_counter_vec;
+    use prometheus::IntCounterVec;
+
+    pub static COMPRESSED_MESSAGES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+        register_int_counter_vec(
+            "rpc_compressed_messages",
+            "Number of RPC messages sent compressed",
+            &["algorithm"],
+        )
+    });
+
+    pub static COMPRESSION_SAVED_BYTES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+        register_int_counter_vec(
+            "rpc_compression_saved_bytes",
+            "Number of bytes saved by compressing RPC messages",
+            &["algorithm"],
+        )
+    });
+}
@@ -1,7 +1,7 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::ffi::OsString;
+use std::{collections::BTreeMap, ffi::OsString, path::PathBuf};
 
 use clap::Parser;
 use linera_base::{crypto::ValidatorPublicKey, identifiers::ChainId};
@@ -152,6 +152,24 @@ impl NetworkProtocol {
     }
 }
 
+/// The certificates used for mutual TLS between the proxies and the shards of a validator.
+///
+/// Every proxy and shard presents its certificate, and rejects peers whose certificate is
+/// not signed by the certificate authority. The files are reloaded when they change, so
+/// that certificates can be rotated without restarting the validator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InternalTlsConfig {
+    /// The PEM file with the certificate authority that signs the internal certificates.
+    pub ca_certificate: PathBuf,
+    /// The PEM file with the certificate chain of this process.
+    pub certificate: PathBuf,
+    /// The PEM file with the private key of this process.
+    pub private_key: PathBuf,
+    /// The name to verify in the certificates of the servers, instead of their host names.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain_name: Option<String>,
+}
+
 /// The network configuration for all shards.
 pub type ValidatorInternalNetworkConfig = ValidatorInternalNetworkPreConfig<NetworkProtocol>;
 
@@ -174,6 +192,10 @@ pub struct ValidatorInternalNetworkPreConfig<P> {
     pub block_exporters: Vec<ExporterServiceConfig>,
     /// The available proxies.
     pub proxies: Vec<ProxyConfig>,
+    /// The certificates for mutual TLS between the proxies and the shards. Required if, and
+    /// only if, the internal protocol is `grpcs`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mutual_tls: Option<InternalTlsConfig>,
 }
 
 impl<P> ValidatorInternalNetworkPreConfig<P> {
@@ -184,11 +206,17 @@ impl<P> ValidatorInternalNetworkPreConfig<P> {
             shards: self.shards.clone(),
             block_exporters: self.block_exporters.clone(),
             proxies: self.proxies.clone(),
+            mutual_tls: self.mutual_tls.clone(),
         }
     }
 }
 
 impl ValidatorInternalNetworkConfig {
+    /// Returns the URL of the shard for the internal traffic.
+    pub fn shard_address(&self, shard: &ShardConfig) -> String {
+        format!("{}://{}", self.protocol.scheme(), shard.address())
+    }
+
     pub fn exporter_addresses(&self) -> Vec<String> {
         self.block_exporters
             .iter()
@@ -259,7 +287,7 @@ where
         let parts = s.split(':').collect::<Vec<_>>();
         anyhow::ensure!(
             parts.len() == 3,
-            "Expecting format `(tcp|udp|grpc|grpcs):host:port`"
+            "Expecting format `(tcp|udp|quic|grpc|grpcs):host:port`"
         );
         let protocol = parts[0].parse().map_err(|s| anyhow::anyhow!("{}", s))?;
         let host = parts[1].to_owned();
@@ -307,6 +335,22 @@ impl<P> ValidatorInternalNetworkPreConfig<P> {
     pub fn get_shard_for(&self, chain_id: ChainId) -> &ShardConfig {
         self.shard(self.get_shard_id(chain_id))
     }
+
+    /// Groups the `chain_ids` by the shard they are assigned to. Each chain is given with its
+    /// index in `chain_ids`.
+    pub fn group_by_shard(
+        &self,
+        chain_ids: &[ChainId],
+    ) -> BTreeMap<ShardId, Vec<(usize, ChainId)>> {
+        let mut groups = BTreeMap::<ShardId, Vec<_>>::new();
+        for (index, chain_id) in chain_ids.iter().enumerate() {
+            groups
+                .entry(self.get_shard_id(*chain_id))
+                .or_default()
+                .push((index, *chain_id));
+        }
+        groups
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -335,3 +379,50 @@ fn cross_chain_config_to_args() {
     assert_eq!(config, config2);
     assert_eq!(args, args2);
 }
+
+#[cfg(test)]
+mod tests {
+    use linera_base::crypto::CryptoHash;
+
+    use super::*;
+
+    fn shard(port: u16) -> ShardConfig {
+        ShardConfig {
+            host: "127.0.0.1".to_owned(),
+            port,
+            metrics_port: None,
+        }
+    }
+
+    fn network(shard_count: u16) -> ValidatorInternalNetworkPreConfig<()> {
+        ValidatorInternalNetworkPreConfig {
+            public_key: ValidatorPublicKey::test_key(0),
+            protocol: (),
+            shards: (0..shard_count).map(|i| shard(9100 + i)).collect(),
+            block_exporters: vec![],
+            proxies: vec![],
+            mutual_tls: None,
+        }
+    }
+
+    fn chain_ids() -> impl Iterator<Item = ChainId> {
+        (0..1000).map(|i| ChainId(CryptoHash::test_hash(format!("chain {i}"))))
+    }
+
+    #[test]
+    fn grouping_by_shard_keeps_every_chain_once() {
+        let config = network(4);
+        let chain_ids = chain_ids().collect::<Vec<_>>();
+        let groups = config.group_by_shard(&chain_ids);
+        let mut indices = Vec::new();
+        for (shard_id, group) in groups {
+            for (index, chain_id) in group {
+                assert_eq!(chain_ids[index], chain_id);
+                assert_eq!(config.get_shard_id(chain_id), shard_id);
+                indices.push(index);
+            }
+        }
+        indices.sort_unstable();
+        assert_eq!(indices, (0..chain_ids.len()).collect::<Vec<_>>());
+    }
+}
@@ -54,13 +54,13 @@ use tracing::{debug, instrument, trace, Level};
 
 use super::{
     api::{self, validator_node_client::ValidatorNodeClient, SubscriptionRequest},
-    transport, GRPC_MAX_MESSAGE_SIZE,
+    retry_after_ms, transport, GRPC_MAX_MESSAGE_SIZE,
 };
 #[cfg(feature = "opentelemetry")]
 use crate::propagation::{get_context_with_traffic_type, inject_context};
 use crate::{
-    grpc::api::RawCertificate, HandleConfirmedCertificateRequest, HandleLiteCertRequest,
-    HandleTimeoutCertificateRequest, HandleValidatedCertificateRequest,
+    compression::Compression, grpc::api::RawCertificate, HandleConfirmedCertificateRequest,
+    HandleLiteCertRequest, HandleTimeoutCertificateRequest, HandleValidatedCertificateRequest,
 };
 
 #[derive(Clone)]
@@ -84,10 +84,19 @@ impl GrpcClient {
         max_retries: u32,
         max_backoff: Duration,
         subscription_cooldowns: papaya::HashMap<String, Instant>,
+        #[cfg_attr(target_arch = "wasm32", expect(unused_variables))] compression: Option<
+            Compression,
+        >,
     ) -> Self {
         let client = ValidatorNodeClient::new(channel)
             .max_encoding_message_size(GRPC_MAX_MESSAGE_SIZE)
             .max_decoding_message_size(GRPC_MAX_MESSAGE_SIZE);
+        // Compressed responses are not supported in the browser.
+        #[cfg(not(target_arch = "wasm32"))]
+        let client = match compression {
+            Some(compression) => client.accept_compressed(compression.grpc_encoding()),
+            None => client,
+        };
         Self {
             address,
             client,
@@ -169,7 +178,12 @@ impl GrpcClient {
             // environment variable, allowing benchmark tools to mark their traffic as synthetic.
             #[cfg(feature = "opentelemetry")]
             inject_context(&get_context_with_traffic_type(), request.metadata_mut());
-            match f(self.client.clone(), request).await {
+            let result = f(self.client.clone(), request).await;
+            if let Some(retry_after_ms) = result.as_ref().err().and_then(retry_after_ms) {
+                // Let the caller decide whether to wait or to try another validator.
+                return Err(NodeError::RateLimited { retry_after_ms });
+            }
+            match result {
                 Err(s) if Self::is_retryable(&s) && retry_count < self.max_retries => {
                     let delay = crate::jittered_backoff_delay(
                         self.retry_delay,
@@ -329,6 +343,15 @@ impl ValidatorNode for GrpcClient {
         GrpcClient::try_into_chain_info(client_delegate!(self, handle_chain_info_query, query)?)
     }
 
+    #[instrument(target = "grpc_client", skip_all, err(level = Level::DEBUG), fields(address = self.address))]
+    async fn handle_chain_summaries_query(
+        &self,
+        chain_ids: Vec<ChainId>,
+    ) -> Result<Vec<Result<linera_core::data_types::ChainSummary, NodeError>>, NodeError> {
+        let request = api::ChainSummariesQuery::from(chain_ids);
+        client_delegate!(self, handle_chain_summaries_query, request)?.try_into()
+    }
+
     #[instrument(target = "grpc_client", skip_all, err(level = Level::DEBUG), fields(address = self.address))]
     async fn subscribe(&self, chains: Vec<ChainId>) -> Result<Self::NotificationStream, NodeError> {
         let retry_delay = self.retry_delay;
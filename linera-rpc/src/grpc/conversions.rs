@@ -19,7 +19,8 @@ use linera_chain::{
 };
 use linera_core::{
     data_types::{
-        CertificatesByHeightRequest, ChainInfoQuery, ChainInfoResponse, CrossChainRequest,
+        CertificatesByHeightRequest, ChainInfoQuery, ChainInfoResponse, ChainSummary,
+        CrossChainRequest,
     },
     node::NodeError,
     worker::Notification,
@@ -629,6 +630,10 @@ impl TryFrom<api::ChainInfoQuery> for ChainInfoQuery {
             .map(|stream_ids| bincode::deserialize(&stream_ids))
             .transpose()?
             .unwrap_or_default();
+        let request_balance_proof = chain_info_query
+            .request_balance_proof
+            .map(|owner| bincode::deserialize(&owner))
+            .transpose()?;
 
         Ok(Self {
             request_committees: chain_info_query.request_committees,
@@ -643,6 +648,7 @@ impl TryFrom<api::ChainInfoQuery> for ChainInfoQuery {
             request_fallback: chain_info_query.request_fallback,
             request_sent_certificate_hashes_by_heights,
             request_previous_event_blocks,
+            request_balance_proof,
         })
     }
 }
@@ -660,6 +666,10 @@ impl TryFrom<ChainInfoQuery> for api::ChainInfoQuery {
             .transpose()?;
         let request_previous_event_blocks =
             bincode::serialize(&chain_info_query.request_previous_event_blocks)?;
+        let request_balance_proof = chain_info_query
+            .request_balance_proof
+            .map(|owner| bincode::serialize(&owner))
+            .transpose()?;
 
         Ok(Self {
             chain_id: Some(chain_info_query.chain_id.into()),
@@ -676,6 +686,7 @@ impl TryFrom<ChainInfoQuery> for api::ChainInfoQuery {
             request_leader_timeout,
             request_fallback: chain_info_query.request_fallback,
             request_previous_event_blocks: Some(request_previous_event_blocks),
+            request_balance_proof,
         })
     }
 }
@@ -1097,6 +1108,68 @@ impl TryFrom<api::EventBlockHeightsResponse> for Vec<Option<BlockHeight>> {
     }
 }
 
+impl From<Vec<ChainId>> for api::ChainSummariesQuery {
+    fn from(chain_ids: Vec<ChainId>) -> Self {
+        Self {
+            chain_ids: bincode::serialize(&chain_ids).expect("serialize chain_ids"),
+        }
+    }
+}
+
+impl TryFrom<api::ChainSummariesQuery> for Vec<ChainId> {
+    type Error = GrpcProtoConversionError;
+
+    fn try_from(query: api::ChainSummariesQuery) -> Result<Self, Self::Error> {
+        Ok(bincode::deserialize(&query.chain_ids)?)
+    }
+}
+
+impl TryFrom<Vec<Result<ChainSummary, NodeError>>> for api::ChainSummariesResult {
+    type Error = GrpcProtoConversionError;
+
+    fn try_from(summaries: Vec<Result<ChainSummary, NodeError>>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            inner: Some(api::chain_summaries_result::Inner::Summaries(
+                bincode::serialize(&summaries)?,
+            )),
+        })
+    }
+}
+
+impl TryFrom<NodeError> for api::ChainSummariesResult {
+    type Error = GrpcProtoConversionError;
+
+    fn try_from(node_error: NodeError) -> Result<Self, Self::Error> {
+        let error = bincode::serialize(&node_error)?;
+        Ok(Self {
+            inner: Some(api::chain_summaries_result::Inner::Error(error)),
+        })
+    }
+}
+
+impl TryFrom<api::ChainSummariesResult> for Vec<Result<ChainSummary, NodeError>> {
+    type Error = NodeError;
+
+    fn try_from(result: api::ChainSummariesResult) -> Result<Self, Self::Error> {
+        let inner = result.inner.ok_or_else(|| NodeError::GrpcError {
+            error: "missing body from response".to_string(),
+        })?;
+        match inner {
+            api::chain_summaries_result::Inner::Summaries(summaries) => {
+                bincode::deserialize(&summaries).map_err(|err| NodeError::GrpcError {
+                    error: format!("failed to unmarshal response: {err}"),
+                })
+            }
+            api::chain_summaries_result::Inner::Error(error) => Err(bincode::deserialize(&error)
+                .map_err(|err| {
+                NodeError::GrpcError {
+                    error: format!("failed to unmarshal error message: {err}"),
+                }
+            })?),
+        }
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use std::{borrow::Cow, collections::BTreeMap, fmt::Debug};
@@ -1201,6 +1274,7 @@ pub mod tests {
             count_received_log: 0,
             requested_received_log: vec![],
             requested_previous_event_blocks: BTreeMap::new(),
+            requested_balance_proof: None,
         });
 
         let chain_info_response_none = ChainInfoResponse {
@@ -1238,6 +1312,7 @@ pub mod tests {
             request_fallback: true,
             request_sent_certificate_hashes_by_heights: (3..8).map(BlockHeight::from).collect(),
             request_previous_event_blocks: Vec::new(),
+            request_balance_proof: Some(AccountOwner::CHAIN),
         };
         round_trip_check::<_, api::ChainInfoQuery>(&chain_info_query_some);
     }
@@ -7,6 +7,8 @@ mod node_provider;
 pub mod pool;
 #[cfg(with_server)]
 mod server;
+#[cfg(with_server)]
+pub mod tls;
 pub mod transport;
 
 pub use client::*;
@@ -33,6 +35,14 @@ pub enum GrpcError {
     #[cfg(with_server)]
     #[error(transparent)]
     Reflection(#[from] tonic_reflection::server::Error),
+
+    #[cfg(with_server)]
+    #[error(transparent)]
+    Tls(#[from] tls::TlsError),
+
+    #[cfg(with_server)]
+    #[error("failed to bind to address: {0}")]
+    Bind(#[source] std::io::Error),
 }
 
 const MEBIBYTE: usize = 1024 * 1024;
@@ -51,6 +61,47 @@ pub const TRAFFIC_TYPE_LABEL: &str = "traffic_type";
 /// Prometheus label for the error variant name, e.g. `"WorkerError::UnexpectedBlockHeight"`.
 pub const ERROR_TYPE_LABEL: &str = "error_type";
 
+/// gRPC metadata key for the number of milliseconds after which a rate-limited request can
+/// be retried.
+pub const RETRY_AFTER_MS_METADATA: &str = "retry-after-ms";
+
+/// Returns a response with the `message`, which is left uncompressed if it is smaller than
+/// [`COMPRESSION_THRESHOLD`](crate::compression::COMPRESSION_THRESHOLD) bytes.
+pub fn compressible_response<T: prost::Message>(message: T) -> tonic::Response<T> {
+    let size = message.encoded_len();
+    let mut response = tonic::Response::new(message);
+    if size < crate::compression::COMPRESSION_THRESHOLD {
+        response.disable_compression();
+    }
+    response
+}
+
+/// Returns a `RESOURCE_EXHAUSTED` status telling the client to retry after `retry_after`.
+pub fn rate_limited_status(retry_after: std::time::Duration) -> tonic::Status {
+    let retry_after_ms = retry_after.as_millis().max(1) as u64;
+    let mut status = tonic::Status::resource_exhausted(format!(
+        "rate limit exceeded; retry after {retry_after_ms} ms"
+    ));
+    status
+        .metadata_mut()
+        .insert(RETRY_AFTER_MS_METADATA, retry_after_ms.into());
+    status
+}
+
+/// Returns the retry-after hint of a status returned by [`rate_limited_status`], if any.
+pub fn retry_after_ms(status: &tonic::Status) -> Option<u64> {
+    if status.code() != tonic::Code::ResourceExhausted {
+        return None;
+    }
+    status
+        .metadata()
+        .get(RETRY_AFTER_MS_METADATA)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+}
+
 /// Extracts the gRPC method name from a request URI path.
 ///
 /// gRPC paths have the form `/{package}.{Service}/{Method}` — the first segment
@@ -65,6 +116,21 @@ pub fn extract_grpc_method_name(path: &str) -> &str {
     }
 }
 
+#[cfg(test)]
+mod rate_limit_tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn retry_after_round_trips_through_status() {
+        let status = rate_limited_status(Duration::from_millis(1500));
+        assert_eq!(retry_after_ms(&status), Some(1500));
+        assert_eq!(retry_after_ms(&tonic::Status::resource_exhausted("")), None);
+        assert_eq!(retry_after_ms(&tonic::Status::unavailable("")), None);
+    }
+}
+
 #[cfg(test)]
 mod method_name_tests {
     use super::*;
@@ -8,6 +8,7 @@ use linera_core::node::{NodeError, ValidatorNodeProvider};
 
 use super::GrpcClient;
 use crate::{
+    compression::Compression,
     config::ValidatorPublicNetworkConfig,
     grpc::{pool::GrpcConnectionPool, transport},
     node_provider::NodeOptions,
@@ -19,6 +20,7 @@ pub struct GrpcNodeProvider {
     retry_delay: Duration,
     max_retries: u32,
     max_backoff: Duration,
+    compression: Option<Compression>,
     /// Shared across all `GrpcClient` instances. When a subscription to a validator
     /// fails, the failure time is recorded here so that other chains (which share the
     /// same provider) skip retrying the same dead validator.
@@ -37,6 +39,7 @@ impl GrpcNodeProvider {
             retry_delay,
             max_retries,
             max_backoff,
+            compression: options.compression,
             subscription_cooldowns: papaya::HashMap::new(),
         }
     }
@@ -66,6 +69,7 @@ impl ValidatorNodeProvider for GrpcNodeProvider {
             self.max_retries,
             self.max_backoff,
             self.subscription_cooldowns.clone(),
+            self.compression,
         ))
     }
 }
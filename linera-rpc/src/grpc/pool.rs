@@ -1,8 +1,13 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(with_server)]
+use std::sync::Arc;
+
 use linera_base::time::Duration;
 
+#[cfg(with_server)]
+use super::tls::MutualTls;
 use super::{transport, GrpcError};
 
 /// A pool of transport channels to be used by gRPC.
@@ -10,16 +15,30 @@ use super::{transport, GrpcError};
 pub struct GrpcConnectionPool {
     options: transport::Options,
     channels: papaya::HashMap<String, transport::Channel>,
+    /// The certificates to authenticate with, if the peers require mutual TLS.
+    #[cfg(with_server)]
+    mutual_tls: Option<Arc<MutualTls>>,
+    /// The channels using mutual TLS, with the generation of their certificates.
+    #[cfg(with_server)]
+    mutual_tls_channels: papaya::HashMap<String, (u64, transport::Channel)>,
 }
 
 impl GrpcConnectionPool {
     pub fn new(options: transport::Options) -> Self {
         Self {
             options,
-            channels: papaya::HashMap::default(),
+            ..Self::default()
         }
     }
 
+    /// Authenticates to the peers with the given certificates. Channels are recreated
+    /// when the certificates are rotated.
+    #[cfg(with_server)]
+    pub fn with_mutual_tls(mut self, mutual_tls: Option<Arc<MutualTls>>) -> Self {
+        self.mutual_tls = mutual_tls;
+        self
+    }
+
     pub fn with_connect_timeout(mut self, connect_timeout: impl Into<Option<Duration>>) -> Self {
         self.options.connect_timeout = connect_timeout.into();
         self
@@ -34,6 +53,10 @@ impl GrpcConnectionPool {
     /// reusing the connection), or creates one if needed. New channels do not create a
     /// connection immediately.
     pub fn channel(&self, address: String) -> Result<transport::Channel, GrpcError> {
+        #[cfg(with_server)]
+        if let Some(mutual_tls) = &self.mutual_tls {
+            return self.mutual_tls_channel(mutual_tls, address);
+        }
         let pinned = self.channels.pin();
         if let Some(channel) = pinned.get(&address) {
             return Ok(channel.clone());
@@ -41,4 +64,23 @@ impl GrpcConnectionPool {
         let channel = transport::create_channel(address.clone(), &self.options)?;
         Ok(pinned.get_or_insert(address, channel).clone())
     }
+
+    #[cfg(with_server)]
+    fn mutual_tls_channel(
+        &self,
+        mutual_tls: &MutualTls,
+        address: String,
+    ) -> Result<transport::Channel, GrpcError> {
+        let (generation, tls_config) = mutual_tls.client_config();
+        let pinned = self.mutual_tls_channels.pin();
+        if let Some((channel_generation, channel)) = pinned.get(&address) {
+            if *channel_generation == generation {
+                return Ok(channel.clone());
+            }
+        }
+        let channel =
+            transport::create_channel_with_tls(address.clone(), &self.options, tls_config)?;
+        pinned.insert(address, (generation, channel.clone()));
+        Ok(channel)
+    }
 }
@@ -4,6 +4,7 @@
 use std::{
     net::{IpAddr, SocketAddr},
     str::FromStr,
+    sync::Arc,
     task::{Context, Poll},
 };
 
@@ -22,7 +23,10 @@ use linera_core::{
 use linera_storage::Storage;
 use tokio::sync::{broadcast::error::RecvError, oneshot};
 use tokio_util::sync::CancellationToken;
-use tonic::{transport::Channel, Request, Response, Status};
+use tonic::{
+    transport::{server::TcpIncoming, Channel},
+    Request, Response, Status,
+};
 use tower::{builder::ServiceBuilder, Layer, Service};
 use tracing::{debug, error, info, instrument, trace, warn};
 
@@ -32,10 +36,12 @@ use super::{
         notifier_service_client::NotifierServiceClient,
         validator_worker_client::ValidatorWorkerClient,
         validator_worker_server::{ValidatorWorker as ValidatorWorkerRpc, ValidatorWorkerServer},
-        BlockProposal, ChainInfoQuery, ChainInfoResult, CrossChainRequest,
-        HandlePendingBlobRequest, LiteCertificate, PendingBlobRequest, PendingBlobResult,
+        BlockProposal, ChainInfoQuery, ChainInfoResult, ChainSummariesQuery, ChainSummariesResult,
+        CrossChainRequest, HandlePendingBlobRequest, LiteCertificate, PendingBlobRequest,
+        PendingBlobResult,
     },
     pool::GrpcConnectionPool,
+    tls::MutualTls,
     GrpcError, GRPC_MAX_MESSAGE_SIZE,
 };
 #[cfg(feature = "opentelemetry")]
@@ -147,8 +153,9 @@ mod metrics {
 /// Handles batched forwarding of notifications to proxy and exporters.
 struct BatchForwarder {
     nickname: String,
-    client: NotifierServiceClient<Channel>,
-    exporter_clients: Vec<NotifierServiceClient<Channel>>,
+    pool: GrpcConnectionPool,
+    proxy_address: String,
+    exporter_addresses: Vec<String>,
     pending_notifications: Vec<Notification>,
     futures: FuturesUnordered<BoxFuture<'static, ()>>,
     batch_limit: usize,
@@ -157,8 +164,20 @@ struct BatchForwarder {
 
 impl BatchForwarder {
     /// Spawns batch send tasks up to max_tasks limit.
-    fn spawn_batches(&mut self) {
+    ///
+    /// If the clients cannot be created, the notifications are kept pending and the error is
+    /// returned.
+    fn spawn_batches(&mut self) -> Result<(), GrpcError> {
         while !self.pending_notifications.is_empty() && self.futures.len() < self.max_tasks {
+            // The clients are obtained for each batch, so that rotated certificates are used.
+            let client = self.notifier_client(&self.proxy_address)?;
+            let exporter_clients = self
+                .exporter_addresses
+                .iter()
+                .map(|address| self.notifier_client(address))
+                .collect::<Result<_, _>>()?;
+            let nickname = self.nickname.clone();
+
             let chunk_size = std::cmp::min(self.batch_limit, self.pending_notifications.len());
             let batch: Vec<Notification> = self.pending_notifications.drain(..chunk_size).collect();
 
@@ -167,10 +186,6 @@ impl BatchForwarder {
                 .with_label_values(&[])
                 .observe(batch.len() as f64);
 
-            let client = self.client.clone();
-            let exporter_clients = self.exporter_clients.clone();
-            let nickname = self.nickname.clone();
-
             self.futures.push(
                 async move {
                     Self::send_batch(nickname, client, exporter_clients, batch).await;
@@ -178,6 +193,14 @@ impl BatchForwarder {
                 .boxed(),
             );
         }
+        Ok(())
+    }
+
+    fn notifier_client(&self, address: &str) -> Result<NotifierServiceClient<Channel>, GrpcError> {
+        let channel = self.pool.channel(address.to_owned())?;
+        Ok(NotifierServiceClient::new(channel)
+            .max_encoding_message_size(GRPC_MAX_MESSAGE_SIZE)
+            .max_decoding_message_size(GRPC_MAX_MESSAGE_SIZE))
     }
 
     /// Returns true if there are no pending notifications and no in-flight tasks.
@@ -392,6 +415,7 @@ where
         internal_network: ValidatorInternalNetworkConfig,
         cross_chain_config: &CrossChainConfig,
         notification_config: &NotificationConfig,
+        mutual_tls: Option<Arc<MutualTls>>,
         shutdown_signal: CancellationToken,
         join_set: &mut JoinSet,
     ) -> GrpcServerHandle {
@@ -435,6 +459,7 @@ where
                 cross_chain_config.sender_failure_rate,
                 shard_id,
                 cross_chain_receiver,
+                mutual_tls.clone(),
             )
         });
 
@@ -458,6 +483,7 @@ where
                     exporter_addresses,
                     receiver,
                     notification_config.clone(),
+                    mutual_tls.clone(),
                 )
             });
         }
@@ -500,12 +526,28 @@ where
                     .layer(GrpcPrometheusMetricsMiddlewareLayer)
                     .into_inner(),
             );
-            server
+            let router = server
                 .add_service(health_service)
                 .add_service(reflection_service)
-                .add_service(worker_node)
-                .serve_with_shutdown(server_address, shutdown_signal.cancelled_owned())
-                .await?;
+                .add_service(worker_node);
+            match mutual_tls {
+                Some(mutual_tls) => {
+                    let incoming = TcpIncoming::bind(server_address)
+                        .map_err(GrpcError::Bind)?
+                        .with_nodelay(Some(true));
+                    router
+                        .serve_with_incoming_shutdown(
+                            mutual_tls.incoming(incoming),
+                            shutdown_signal.cancelled_owned(),
+                        )
+                        .await?
+                }
+                None => {
+                    router
+                        .serve_with_shutdown(server_address, shutdown_signal.cancelled_owned())
+                        .await?
+                }
+            }
 
             Ok(())
         });
@@ -515,37 +557,28 @@ where
 
     /// Continuously waits for receiver to receive notifications and sends them to
     /// the proxy in batches for improved throughput.
-    #[instrument(skip(receiver, config))]
+    #[instrument(skip(receiver, config, mutual_tls))]
     async fn forward_notifications(
         nickname: String,
         proxy_address: String,
         exporter_addresses: Vec<String>,
         mut receiver: tokio::sync::broadcast::Receiver<Notification>,
         config: NotificationConfig,
+        mutual_tls: Option<Arc<MutualTls>>,
     ) {
-        let channel = tonic::transport::Channel::from_shared(proxy_address.clone())
-            .expect("Proxy URI should be valid")
-            .connect_lazy();
-        let client = NotifierServiceClient::new(channel)
-            .max_encoding_message_size(GRPC_MAX_MESSAGE_SIZE)
-            .max_decoding_message_size(GRPC_MAX_MESSAGE_SIZE);
-
-        let exporter_clients: Vec<NotifierServiceClient<Channel>> = exporter_addresses
-            .iter()
-            .map(|address| {
-                let channel = tonic::transport::Channel::from_shared(address.clone())
-                    .expect("Exporter URI should be valid")
-                    .connect_lazy();
-                NotifierServiceClient::new(channel)
-                    .max_encoding_message_size(GRPC_MAX_MESSAGE_SIZE)
-                    .max_decoding_message_size(GRPC_MAX_MESSAGE_SIZE)
-            })
-            .collect::<Vec<_>>();
+        let pool = GrpcConnectionPool::default().with_mutual_tls(mutual_tls);
+        pool.channel(proxy_address.clone())
+            .expect("Proxy URI should be valid");
+        for address in &exporter_addresses {
+            pool.channel(address.clone())
+                .expect("Exporter URI should be valid");
+        }
 
         let mut forwarder = BatchForwarder {
             nickname: nickname.clone(),
-            client,
-            exporter_clients,
+            pool,
+            proxy_address,
+            exporter_addresses,
             pending_notifications: Vec::new(),
             futures: FuturesUnordered::new(),
             batch_limit: config.notification_batch_size,
@@ -564,7 +597,9 @@ where
                             if forwarder.futures.is_empty()
                                || (forwarder.pending_notifications.len() >= forwarder.batch_limit
                                    && forwarder.futures.len() < forwarder.max_tasks) {
-                                forwarder.spawn_batches();
+                                if let Err(error) = forwarder.spawn_batches() {
+                                    error!(%error, nickname, "could not forward notifications");
+                                }
                             }
                         }
                         Err(RecvError::Lagged(skipped_count)) => {
@@ -584,7 +619,15 @@ where
                             );
                             // Drain all pending notifications before exiting
                             loop {
-                                forwarder.spawn_batches();
+                                if let Err(error) = forwarder.spawn_batches() {
+                                    error!(
+                                        %error,
+                                        nickname,
+                                        pending = forwarder.pending_notifications.len(),
+                                        "could not forward the remaining notifications"
+                                    );
+                                    forwarder.pending_notifications.clear();
+                                }
                                 if forwarder.is_fully_drained() {
                                     break;
                                 }
@@ -596,7 +639,9 @@ where
                 }
 
                 Some(()) = forwarder.futures.next() => {
-                    forwarder.spawn_batches();
+                    if let Err(error) = forwarder.spawn_batches() {
+                        error!(%error, nickname, "could not forward notifications");
+                    }
                 }
             }
         }
@@ -649,11 +694,12 @@ where
         cross_chain_sender_failure_rate: f32,
         this_shard: ShardId,
         receiver: mpsc::Receiver<(linera_core::data_types::CrossChainRequest, ShardId)>,
+        mutual_tls: Option<Arc<MutualTls>>,
     ) {
-        let pool = GrpcConnectionPool::default();
+        let pool = GrpcConnectionPool::default().with_mutual_tls(mutual_tls);
         let handle_request =
             move |shard_id: ShardId, request: linera_core::data_types::CrossChainRequest| {
-                let channel_result = pool.channel(network.shard(shard_id).http_address());
+                let channel_result = pool.channel(network.shard_address(network.shard(shard_id)));
                 async move {
                     let mut client = ValidatorWorkerClient::new(channel_result?)
                         .max_encoding_message_size(GRPC_MAX_MESSAGE_SIZE)
@@ -964,6 +1010,46 @@ where
         }
     }
 
+    #[instrument(
+        target = "grpc_server",
+        skip_all,
+        err,
+        fields(nickname = self.state.nickname())
+    )]
+    async fn handle_chain_summaries_query(
+        &self,
+        request: Request<ChainSummariesQuery>,
+    ) -> Result<Response<ChainSummariesResult>, Status> {
+        let traffic_type = Self::get_traffic_type(&request);
+        let chain_ids: Vec<ChainId> = request.into_inner().try_into()?;
+        trace!(
+            num_chains = chain_ids.len(),
+            "Handling chain summaries query"
+        );
+        if let Err(error) = NodeError::check_chain_summaries_query(&chain_ids) {
+            return Ok(Response::new(error.try_into()?));
+        }
+        match self.state.handle_chain_summaries_query(chain_ids).await {
+            Ok(results) => {
+                Self::log_request_success("handle_chain_summaries_query", traffic_type);
+                let results = results
+                    .into_iter()
+                    .map(|result| result.map_err(NodeError::from))
+                    .collect::<Vec<_>>();
+                Ok(Response::new(results.try_into()?))
+            }
+            Err(error) => {
+                Self::log_request_error(
+                    "handle_chain_summaries_query",
+                    traffic_type,
+                    &error.error_type(),
+                );
+                self.log_error(&error, "Failed to handle chain summaries query");
+                Ok(Response::new(NodeError::from(error).try_into()?))
+            }
+        }
+    }
+
     #[instrument(
         target = "grpc_server",
         skip_all,
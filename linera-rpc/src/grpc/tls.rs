@@ -0,0 +1,427 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Mutual TLS between the proxies and the shards of a validator.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use futures::{Stream, StreamExt as _};
+use linera_base::time::{Duration, Instant};
+use rustls::{
+    crypto::ring,
+    pki_types::{pem::PemObject as _, CertificateDer, PrivateKeyDer},
+    server::WebPkiClientVerifier,
+    RootCertStore, ServerConfig,
+};
+use tokio::net::TcpStream;
+use tokio_rustls::{server::TlsStream, TlsAcceptor};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::{server::TcpIncoming, Certificate, ClientTlsConfig, Identity};
+use tracing::{debug, info, warn};
+
+use crate::config::{
+    InternalTlsConfig, NetworkProtocol, TlsConfig, ValidatorInternalNetworkConfig,
+};
+
+/// How often the certificate files are checked for changes.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long a peer has to complete the TLS handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The number of connections that completed the handshake but were not served yet.
+const ACCEPTED_QUEUE_SIZE: usize = 128;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TlsError {
+    #[error("failed to read {path}: {error}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        error: io::Error,
+    },
+
+    #[error("invalid PEM file {path}: {error}")]
+    Pem {
+        path: PathBuf,
+        #[source]
+        error: rustls::pki_types::pem::Error,
+    },
+
+    #[error("no certificate found in {0}")]
+    NoCertificate(PathBuf),
+
+    #[error(transparent)]
+    Rustls(#[from] rustls::Error),
+
+    #[error(transparent)]
+    Verifier(#[from] rustls::server::VerifierBuilderError),
+
+    #[error(
+        "the internal protocol must be grpcs if, and only if, mutual TLS certificates are \
+        configured"
+    )]
+    ProtocolMismatch,
+}
+
+/// The certificates of a proxy or shard, reloaded when the files change.
+pub struct MutualTls {
+    config: InternalTlsConfig,
+    state: Mutex<State>,
+}
+
+struct State {
+    /// Incremented every time new certificates are loaded.
+    generation: u64,
+    modified: [Option<SystemTime>; 3],
+    last_check: Instant,
+    client_config: ClientTlsConfig,
+    server_config: Arc<ServerConfig>,
+}
+
+impl MutualTls {
+    /// Loads the certificates of the internal network, if it uses mutual TLS.
+    pub fn for_network(
+        network: &ValidatorInternalNetworkConfig,
+    ) -> Result<Option<Arc<Self>>, TlsError> {
+        match (network.protocol, &network.mutual_tls) {
+            (NetworkProtocol::Grpc(TlsConfig::Tls), Some(config)) => {
+                Ok(Some(Arc::new(Self::new(config.clone())?)))
+            }
+            (NetworkProtocol::Grpc(TlsConfig::Tls), None) | (_, Some(_)) => {
+                Err(TlsError::ProtocolMismatch)
+            }
+            (_, None) => Ok(None),
+        }
+    }
+
+    /// Loads the certificates of the configuration.
+    pub fn new(config: InternalTlsConfig) -> Result<Self, TlsError> {
+        let modified = Self::modification_times(&config);
+        let (client_config, server_config) = Self::load(&config)?;
+        Ok(Self {
+            config,
+            state: Mutex::new(State {
+                generation: 0,
+                modified,
+                last_check: Instant::now(),
+                client_config,
+                server_config,
+            }),
+        })
+    }
+
+    /// Returns the TLS configuration for connections to the other proxies and shards,
+    /// together with its generation.
+    pub fn client_config(&self) -> (u64, ClientTlsConfig) {
+        let state = self.refresh();
+        (state.generation, state.client_config.clone())
+    }
+
+    /// Returns the TLS configuration for connections from the other proxies and shards.
+    pub fn server_config(&self) -> Arc<ServerConfig> {
+        self.refresh().server_config.clone()
+    }
+
+    /// Accepts the connections that complete a TLS handshake with a certificate signed by
+    /// the certificate authority. Other peers are dropped.
+    pub fn incoming(
+        self: Arc<Self>,
+        mut tcp: TcpIncoming,
+    ) -> impl Stream<Item = Result<TlsStream<TcpStream>, io::Error>> {
+        let (sender, receiver) = tokio::sync::mpsc::channel(ACCEPTED_QUEUE_SIZE);
+        tokio::spawn(async move {
+            loop {
+                let stream = tokio::select! {
+                    stream = tcp.next() => match stream {
+                        Some(Ok(stream)) => stream,
+                        Some(Err(error)) => {
+                            if sender.send(Err(error)).await.is_err() {
+                                return;
+                            }
+                            continue;
+                        }
+                        None => return,
+                    },
+                    () = sender.closed() => return,
+                };
+                let acceptor = TlsAcceptor::from(self.server_config());
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    let peer = stream.peer_addr().ok();
+                    match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                        Ok(Ok(stream)) => {
+                            if sender.send(Ok(stream)).await.is_err() {
+                                debug!(?peer, "server shut down during TLS handshake");
+                            }
+                        }
+                        Ok(Err(error)) => {
+                            debug!(%error, ?peer, "rejected internal connection");
+                        }
+                        Err(_) => debug!(?peer, "internal TLS handshake timed out"),
+                    }
+                });
+            }
+        });
+        ReceiverStream::new(receiver)
+    }
+
+    /// Reloads the certificates if the files changed since they were last loaded. If the
+    /// new files are invalid, e.g. because they are being rotated, the previous
+    /// certificates are kept.
+    fn refresh(&self) -> std::sync::MutexGuard<'_, State> {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        if now.duration_since(state.last_check) < RELOAD_INTERVAL {
+            return state;
+        }
+        state.last_check = now;
+        let modified = Self::modification_times(&self.config);
+        if modified == state.modified {
+            return state;
+        }
+        match Self::load(&self.config) {
+            Ok((client_config, server_config)) => {
+                info!("reloaded the internal TLS certificates");
+                state.generation += 1;
+                state.modified = modified;
+                state.client_config = client_config;
+                state.server_config = server_config;
+            }
+            Err(error) => warn!(%error, "failed to reload the internal TLS certificates"),
+        }
+        state
+    }
+
+    fn modification_times(config: &InternalTlsConfig) -> [Option<SystemTime>; 3] {
+        [
+            &config.ca_certificate,
+            &config.certificate,
+            &config.private_key,
+        ]
+        .map(|path| {
+            std::fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+    }
+
+    fn load(config: &InternalTlsConfig) -> Result<(ClientTlsConfig, Arc<ServerConfig>), TlsError> {
+        let ca_certificate = read(&config.ca_certificate)?;
+        let certificate = read(&config.certificate)?;
+        let private_key = read(&config.private_key)?;
+
+        let mut roots = RootCertStore::empty();
+        for ca_certificate in parse_certificates(&config.ca_certificate, &ca_certificate)? {
+            roots.add(ca_certificate)?;
+        }
+        let certificates = parse_certificates(&config.certificate, &certificate)?;
+        let key = PrivateKeyDer::from_pem_slice(&private_key).map_err(|error| TlsError::Pem {
+            path: config.private_key.clone(),
+            error,
+        })?;
+
+        let provider = Arc::new(ring::default_provider());
+        let verifier =
+            WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+                .build()?;
+        let mut server_config = ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()?
+            .with_client_cert_verifier(verifier)
+            .with_single_cert(certificates, key)?;
+        server_config.alpn_protocols = vec![b"h2".to_vec()];
+
+        let mut client_config = ClientTlsConfig::new()
+            .ca_certificate(Certificate::from_pem(ca_certificate))
+            .identity(Identity::from_pem(certificate, private_key));
+        if let Some(domain_name) = &config.domain_name {
+            client_config = client_config.domain_name(domain_name);
+        }
+        Ok((client_config, Arc::new(server_config)))
+    }
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, TlsError> {
+    std::fs::read(path).map_err(|error| TlsError::Io {
+        path: path.to_owned(),
+        error,
+    })
+}
+
+fn parse_certificates(path: &Path, pem: &[u8]) -> Result<Vec<CertificateDer<'static>>, TlsError> {
+    let certificates = CertificateDer::pem_slice_iter(pem)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|error| TlsError::Pem {
+            path: path.to_owned(),
+            error,
+        })?;
+    if certificates.is_empty() {
+        return Err(TlsError::NoCertificate(path.to_owned()));
+    }
+    Ok(certificates)
+}
+
+#[cfg(test)]
+mod tests {
+    use tonic_health::pb::{health_client::HealthClient, HealthCheckRequest};
+
+    use super::*;
+    use crate::grpc::{pool::GrpcConnectionPool, transport};
+
+    /// Writes a new certificate authority, and a certificate signed by it, to `directory`.
+    fn write_certificates(directory: &Path) -> InternalTlsConfig {
+        let mut ca_params = rcgen::CertificateParams::new(vec![]);
+        ca_params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+        let ca = rcgen::Certificate::from_params(ca_params).unwrap();
+        let certificate = rcgen::Certificate::from_params(rcgen::CertificateParams::new(vec![
+            "localhost".to_owned(),
+        ]))
+        .unwrap();
+        let config = InternalTlsConfig {
+            ca_certificate: directory.join("ca.pem"),
+            certificate: directory.join("cert.pem"),
+            private_key: directory.join("key.pem"),
+            domain_name: None,
+        };
+        std::fs::write(&config.ca_certificate, ca.serialize_pem().unwrap()).unwrap();
+        std::fs::write(
+            &config.certificate,
+            certificate.serialize_pem_with_signer(&ca).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(&config.private_key, certificate.serialize_private_key_pem()).unwrap();
+        config
+    }
+
+    /// Starts a health service that requires mutual TLS, and returns its URL.
+    fn start_server(mutual_tls: Arc<MutualTls>) -> String {
+        let (_, health_service) = tonic_health::server::health_reporter();
+        let incoming = TcpIncoming::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let port = incoming.local_addr().unwrap().port();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(health_service)
+                .serve_with_incoming(mutual_tls.incoming(incoming)),
+        );
+        format!("https://localhost:{port}")
+    }
+
+    async fn check_health(channel: transport::Channel) -> Result<(), tonic::Status> {
+        HealthClient::new(channel)
+            .check(HealthCheckRequest::default())
+            .await
+            .map(|_| ())
+    }
+
+    fn expire_reload_interval(mutual_tls: &MutualTls) {
+        mutual_tls.state.lock().unwrap().last_check = Instant::now() - RELOAD_INTERVAL;
+    }
+
+    #[tokio::test]
+    async fn rejects_unauthenticated_peers() {
+        let directory = tempfile::tempdir().unwrap();
+        let config = write_certificates(directory.path());
+        let mutual_tls = Arc::new(MutualTls::new(config.clone()).unwrap());
+        let address = start_server(mutual_tls.clone());
+
+        let pool = GrpcConnectionPool::default().with_mutual_tls(Some(mutual_tls));
+        check_health(pool.channel(address.clone()).unwrap())
+            .await
+            .unwrap();
+
+        // A client that trusts the server but has no certificate is rejected.
+        let ca_certificate = std::fs::read(&config.ca_certificate).unwrap();
+        let tls_config =
+            ClientTlsConfig::new().ca_certificate(Certificate::from_pem(ca_certificate));
+        let channel = transport::create_channel_with_tls(
+            address.clone(),
+            &transport::Options::default(),
+            tls_config,
+        )
+        .unwrap();
+        assert!(check_health(channel).await.is_err());
+
+        // So is a client with a certificate signed by another certificate authority.
+        let other_directory = tempfile::tempdir().unwrap();
+        let other_config = InternalTlsConfig {
+            ca_certificate: config.ca_certificate.clone(),
+            ..write_certificates(other_directory.path())
+        };
+        let other_mutual_tls = Arc::new(MutualTls::new(other_config).unwrap());
+        let pool = GrpcConnectionPool::default().with_mutual_tls(Some(other_mutual_tls));
+        assert!(check_health(pool.channel(address).unwrap()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn reloads_rotated_certificates() {
+        let server_directory = tempfile::tempdir().unwrap();
+        let server_config = write_certificates(server_directory.path());
+        let server_tls = Arc::new(MutualTls::new(server_config.clone()).unwrap());
+        let address = start_server(server_tls.clone());
+        let client_tls = Arc::new(MutualTls::new(server_config.clone()).unwrap());
+        let pool = GrpcConnectionPool::default().with_mutual_tls(Some(client_tls.clone()));
+        check_health(pool.channel(address.clone()).unwrap())
+            .await
+            .unwrap();
+
+        // Rotate to a new certificate authority. Files are only checked periodically.
+        write_certificates(server_directory.path());
+        assert_eq!(client_tls.client_config().0, 0);
+        expire_reload_interval(&server_tls);
+        expire_reload_interval(&client_tls);
+        assert_eq!(client_tls.client_config().0, 1);
+
+        // The pool connects again with the new certificate, which the server accepts.
+        check_health(pool.channel(address.clone()).unwrap())
+            .await
+            .unwrap();
+
+        // Certificates signed by the previous authority are now rejected.
+        let old_directory = tempfile::tempdir().unwrap();
+        let old_tls = Arc::new(MutualTls::new(write_certificates(old_directory.path())).unwrap());
+        let pool = GrpcConnectionPool::default().with_mutual_tls(Some(old_tls));
+        assert!(check_health(pool.channel(address).unwrap()).await.is_err());
+    }
+
+    #[test]
+    fn requires_grpcs_with_mutual_tls() {
+        let network = |protocol, mutual_tls| ValidatorInternalNetworkConfig {
+            public_key: linera_base::crypto::ValidatorPublicKey::test_key(0),
+            protocol,
+            shards: vec![],
+            block_exporters: vec![],
+            proxies: vec![],
+            mutual_tls,
+        };
+        let config = InternalTlsConfig {
+            ca_certificate: "ca.pem".into(),
+            certificate: "cert.pem".into(),
+            private_key: "key.pem".into(),
+            domain_name: None,
+        };
+        let grpc = NetworkProtocol::Grpc(TlsConfig::ClearText);
+        let grpcs = NetworkProtocol::Grpc(TlsConfig::Tls);
+
+        assert!(matches!(
+            MutualTls::for_network(&network(grpc, None)),
+            Ok(None)
+        ));
+        assert!(matches!(
+            MutualTls::for_network(&network(grpcs, None)),
+            Err(TlsError::ProtocolMismatch)
+        ));
+        assert!(matches!(
+            MutualTls::for_network(&network(grpc, Some(config.clone()))),
+            Err(TlsError::ProtocolMismatch)
+        ));
+        assert!(matches!(
+            MutualTls::for_network(&network(grpcs, Some(config))),
+            Err(TlsError::Io { .. })
+        ));
+    }
+}
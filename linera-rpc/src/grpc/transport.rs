@@ -32,9 +32,19 @@ cfg_if::cfg_if! {
         pub fn create_channel(
             address: String,
             options: &Options,
+        ) -> Result<Channel, Error> {
+            let tls_config = tonic::transport::channel::ClientTlsConfig::default().with_webpki_roots();
+            create_channel_with_tls(address, options, tls_config)
+        }
+
+        /// Creates a channel that uses the given TLS configuration for `https` addresses.
+        pub fn create_channel_with_tls(
+            address: String,
+            options: &Options,
+            tls_config: tonic::transport::channel::ClientTlsConfig,
         ) -> Result<Channel, Error> {
             let mut endpoint = tonic::transport::Endpoint::from_shared(address)?
-                .tls_config(tonic::transport::channel::ClientTlsConfig::default().with_webpki_roots())?
+                .tls_config(tls_config)?
                 .tcp_keepalive(Some(std::time::Duration::from_secs(60)))
                 .http2_keep_alive_interval(std::time::Duration::from_secs(30))
                 .keep_alive_timeout(std::time::Duration::from_secs(10))
@@ -12,6 +12,7 @@ pub mod config;
 pub mod node_provider;
 
 pub mod client;
+pub mod compression;
 
 mod cross_chain_message_queue;
 mod message;
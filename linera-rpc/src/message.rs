@@ -12,7 +12,7 @@ use linera_chain::{
     types::{ConfirmedBlock, ConfirmedBlockCertificate},
 };
 use linera_core::{
-    data_types::{ChainInfoQuery, ChainInfoResponse, CrossChainRequest},
+    data_types::{ChainInfoQuery, ChainInfoResponse, ChainSummary, CrossChainRequest},
     node::NodeError,
     worker::Notification,
 };
@@ -85,6 +85,10 @@ pub enum RpcMessage {
     // Notification subscription
     SubscribeNotifications(Vec<ChainId>),
     Notification(Box<Notification>),
+
+    // Batched chain queries
+    ChainSummariesQuery(Vec<ChainId>),
+    ChainSummariesResponse(Vec<Result<ChainSummary, NodeError>>),
 }
 
 impl RpcMessage {
@@ -134,7 +138,9 @@ impl RpcMessage {
             | ShardInfoResponse(_)
             | DownloadCertificatesResponse(_)
             | SubscribeNotifications(_)
-            | Notification(_) => {
+            | Notification(_)
+            | ChainSummariesQuery(_)
+            | ChainSummariesResponse(_) => {
                 return None;
             }
         };
@@ -159,6 +165,7 @@ impl RpcMessage {
             | BlobLastUsedByCertificate(_)
             | MissingBlobIds(_)
             | EventBlockHeights(_)
+            | ChainSummariesQuery(_)
             | DownloadCertificates(_)
             | DownloadCertificatesByHeights(_, _) => true,
             BlockProposal(_)
@@ -187,7 +194,8 @@ impl RpcMessage {
             | DownloadCertificatesResponse(_)
             | DownloadCertificatesByHeightsResponse(_)
             | SubscribeNotifications(_)
-            | Notification(_) => false,
+            | Notification(_)
+            | ChainSummariesResponse(_) => false,
         }
     }
 }
@@ -292,6 +300,17 @@ impl TryFrom<RpcMessage> for Vec<Option<BlockHeight>> {
     }
 }
 
+impl TryFrom<RpcMessage> for Vec<Result<ChainSummary, NodeError>> {
+    type Error = NodeError;
+    fn try_from(message: RpcMessage) -> Result<Self, Self::Error> {
+        match message {
+            RpcMessage::ChainSummariesResponse(summaries) => Ok(summaries),
+            RpcMessage::Error(error) => Err(*error),
+            _ => Err(NodeError::UnexpectedMessage),
+        }
+    }
+}
+
 impl TryFrom<RpcMessage> for Vec<BlobId> {
     type Error = NodeError;
     fn try_from(message: RpcMessage) -> Result<Self, Self::Error> {
@@ -6,7 +6,7 @@ use linera_core::node::{NodeError, ValidatorNodeProvider};
 
 #[cfg(with_simple_network)]
 use crate::simple::SimpleNodeProvider;
-use crate::{client::Client, grpc::GrpcNodeProvider};
+use crate::{client::Client, compression::Compression, grpc::GrpcNodeProvider};
 
 /// A general node provider which delegates node provision to the underlying
 /// node provider according to the `ValidatorPublicNetworkConfig`.
@@ -34,7 +34,10 @@ impl ValidatorNodeProvider for NodeProvider {
         let address = address.to_lowercase();
 
         #[cfg(with_simple_network)]
-        if address.starts_with("tcp") || address.starts_with("udp") {
+        if ["tcp", "udp", "quic"]
+            .iter()
+            .any(|scheme| address.starts_with(scheme))
+        {
             return Ok(Client::Simple(self.simple.make_node(&address)?));
         }
 
@@ -60,6 +63,8 @@ pub struct NodeOptions {
     pub retry_delay: Duration,
     pub max_retries: u32,
     pub max_backoff: Duration,
+    /// The compression that validators are asked to use for large responses.
+    pub compression: Option<Compression>,
 }
 
 impl Default for NodeOptions {
@@ -70,6 +75,7 @@ impl Default for NodeOptions {
             retry_delay: Duration::ZERO,
             max_retries: 0,
             max_backoff: DEFAULT_MAX_BACKOFF,
+            compression: None,
         }
     }
 }
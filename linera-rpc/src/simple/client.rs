@@ -18,21 +18,25 @@ use linera_chain::{
     },
 };
 use linera_core::{
-    data_types::{ChainInfoQuery, ChainInfoResponse},
+    data_types::{ChainInfoQuery, ChainInfoResponse, ChainSummary},
     node::{BlobStream, CrossChainMessageDelivery, NodeError, NotificationStream, ValidatorNode},
 };
 use linera_version::VersionInfo;
 
-use super::{codec, transport::TransportProtocol};
+use super::{
+    codec,
+    transport::{Connector, TransportProtocol},
+};
 use crate::{
-    config::ValidatorPublicNetworkPreConfig, HandleConfirmedCertificateRequest,
-    HandleLiteCertRequest, HandleTimeoutCertificateRequest, HandleValidatedCertificateRequest,
-    RpcMessage,
+    compression::Compression, config::ValidatorPublicNetworkPreConfig,
+    HandleConfirmedCertificateRequest, HandleLiteCertRequest, HandleTimeoutCertificateRequest,
+    HandleValidatedCertificateRequest, RpcMessage,
 };
 
 #[derive(Clone)]
 pub struct SimpleClient {
     network: ValidatorPublicNetworkPreConfig<TransportProtocol>,
+    connector: Connector,
     send_timeout: Duration,
     recv_timeout: Duration,
 }
@@ -42,8 +46,10 @@ impl SimpleClient {
         network: ValidatorPublicNetworkPreConfig<TransportProtocol>,
         send_timeout: Duration,
         recv_timeout: Duration,
+        compression: Option<Compression>,
     ) -> Self {
         Self {
+            connector: network.protocol.connector().with_compression(compression),
             network,
             send_timeout,
             recv_timeout,
@@ -51,8 +57,10 @@ impl SimpleClient {
     }
 
     async fn send_recv_internal(&self, message: RpcMessage) -> Result<RpcMessage, codec::Error> {
-        let address = format!("{}:{}", self.network.host, self.network.port);
-        let mut stream = self.network.protocol.connect(address).await?;
+        let mut stream = self
+            .connector
+            .connect(&self.network.host, self.network.port)
+            .await?;
         // Send message
         timer::timeout(self.send_timeout, stream.send(message))
             .await
@@ -153,9 +161,8 @@ impl ValidatorNode for SimpleClient {
 
     async fn subscribe(&self, chains: Vec<ChainId>) -> Result<NotificationStream, NodeError> {
         let mut stream = self
-            .network
-            .protocol
-            .connect((self.network.host.clone(), self.network.port))
+            .connector
+            .connect(&self.network.host, self.network.port)
             .await
             .map_err(|e| NodeError::ClientIoError {
                 error: e.to_string(),
@@ -201,9 +208,8 @@ impl ValidatorNode for SimpleClient {
 
     async fn download_blobs(&self, blob_ids: Vec<BlobId>) -> Result<BlobStream, NodeError> {
         let mut stream = self
-            .network
-            .protocol
-            .connect((self.network.host.clone(), self.network.port))
+            .connector
+            .connect(&self.network.host, self.network.port)
             .await
             .map_err(|e| NodeError::ClientIoError {
                 error: e.to_string(),
@@ -330,6 +336,13 @@ impl ValidatorNode for SimpleClient {
         self.query(RpcMessage::EventBlockHeights(event_ids)).await
     }
 
+    async fn handle_chain_summaries_query(
+        &self,
+        chain_ids: Vec<ChainId>,
+    ) -> Result<Vec<Result<ChainSummary, NodeError>>, NodeError> {
+        self.query(RpcMessage::ChainSummariesQuery(chain_ids)).await
+    }
+
     async fn get_shard_info(
         &self,
         chain_id: ChainId,
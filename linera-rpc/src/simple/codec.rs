@@ -8,17 +8,83 @@ use linera_core::node::NodeError;
 use thiserror::Error;
 use tokio_util::codec::{Decoder, Encoder};
 
-use crate::RpcMessage;
+use crate::{
+    compression::{Compression, COMPRESSION_THRESHOLD},
+    grpc::GRPC_MAX_MESSAGE_SIZE,
+    RpcMessage,
+};
 
-/// The size of the frame prefix that contains the payload size.
+/// The size of the frame prefix that contains the size of the flags and the payload.
 const PREFIX_SIZE: u8 = mem::size_of::<u32>() as u8;
 
+/// The size of the flags that precede the payload in a frame.
+const FLAGS_SIZE: u8 = 1;
+
+/// The bits of the flags with the compression of the payload.
+const PAYLOAD_COMPRESSION_MASK: u8 = 0b0011;
+
+/// The offset of the bits of the flags with the compression that the sender asks for.
+const ACCEPTED_COMPRESSION_SHIFT: u8 = 2;
+
+/// The offset of the bits of the flags with the version of the frame format.
+const VERSION_SHIFT: u8 = 4;
+
+/// The version of the frame format, so that peers reject the frames they don't understand.
+const VERSION: u8 = 1;
+
+/// The maximum size of a payload, after decompression, in bytes. This is the same limit as
+/// for gRPC messages.
+const MAX_PAYLOAD_SIZE: usize = GRPC_MAX_MESSAGE_SIZE;
+
 /// An encoder/decoder of [`RpcMessage`]s for the RPC protocol.
 ///
-/// The frames are length-delimited by a [`u32`] prefix, and the payload is deserialized by
-/// [`bincode`].
+/// The frames are length-delimited by a [`u32`] prefix, followed by a byte of flags, and the
+/// payload is deserialized by [`bincode`]. Payloads are at most [`MAX_PAYLOAD_SIZE`] bytes
+/// long, also once decompressed.
+///
+/// The flags contain the version of the frame format, the compression of the payload and the
+/// compression that the sender wants to receive. Over a connection, the codec compresses the
+/// payloads that are at least [`COMPRESSION_THRESHOLD`] bytes long if the peer asked for it.
+/// Compressed payloads are only accepted with the compression that this side asked for.
 #[derive(Clone, Copy, Debug)]
-pub struct Codec;
+pub struct Codec {
+    /// The compression that this side asks its peer to use.
+    accepted_compression: Option<Compression>,
+    /// The compression that the peer asked for.
+    peer_compression: Option<Compression>,
+    /// Whether the codec exchanges messages with a single peer, so that it can follow the
+    /// compression that the peer asked for.
+    negotiate: bool,
+}
+
+impl Codec {
+    /// Creates a codec for a connection, asking the peer to use `accepted_compression`.
+    pub fn new(accepted_compression: Option<Compression>) -> Self {
+        Codec {
+            accepted_compression,
+            peer_compression: None,
+            negotiate: true,
+        }
+    }
+
+    /// Creates a codec for datagrams, which may come from different peers, so no
+    /// compression is negotiated.
+    pub fn for_datagrams() -> Self {
+        Codec {
+            accepted_compression: None,
+            peer_compression: None,
+            negotiate: false,
+        }
+    }
+
+    /// Returns the compression to use for a payload of `payload_size` bytes.
+    fn compression_for(&self, payload_size: usize) -> Option<Compression> {
+        if !self.negotiate || payload_size < COMPRESSION_THRESHOLD {
+            return None;
+        }
+        self.peer_compression
+    }
+}
 
 impl Encoder<RpcMessage> for Codec {
     type Error = Error;
@@ -27,6 +93,7 @@ impl Encoder<RpcMessage> for Codec {
         let mut frame_buffer = buffer.split_off(buffer.len());
 
         frame_buffer.put_u32_le(0);
+        frame_buffer.put_u8(0);
 
         let mut frame_writer = frame_buffer.writer();
 
@@ -34,15 +101,42 @@ impl Encoder<RpcMessage> for Codec {
             .map_err(|error| Error::Serialization(*error))?;
 
         let mut frame_buffer = frame_writer.into_inner();
+        let header_size = (PREFIX_SIZE + FLAGS_SIZE) as usize;
+        let mut payload_compression = None;
+
+        if let Some(compression) = self.compression_for(frame_buffer.len() - header_size) {
+            let payload = &frame_buffer[header_size..];
+            let compressed = compression.compress(payload).map_err(Error::Compression)?;
+            if compressed.len() < payload.len() {
+                #[cfg(with_metrics)]
+                crate::compression::record_compression(
+                    compression,
+                    payload.len(),
+                    compressed.len(),
+                );
+                frame_buffer.truncate(header_size);
+                frame_buffer.extend_from_slice(&compressed);
+                payload_compression = Some(compression);
+            }
+        }
+
         let frame_size = frame_buffer.len();
         let payload_size = frame_size - PREFIX_SIZE as usize;
+        if payload_size > MAX_PAYLOAD_SIZE + FLAGS_SIZE as usize {
+            return Err(Error::MessageTooBig { size: payload_size });
+        }
 
         let mut start_of_frame = frame_buffer.deref_mut();
 
         start_of_frame.put_u32_le(
             payload_size
                 .try_into()
-                .map_err(|_| Error::MessageTooBig { size: payload_size })?,
+                .expect("the payload size should be below the maximum"),
+        );
+        start_of_frame.put_u8(
+            compression_to_bits(payload_compression)
+                | compression_to_bits(self.accepted_compression) << ACCEPTED_COMPRESSION_SHIFT
+                | VERSION << VERSION_SHIFT,
         );
 
         buffer.unsplit(frame_buffer);
@@ -66,6 +160,10 @@ impl Decoder for Codec {
             .try_into()
             .expect("u32 should fit in a usize");
 
+        if payload_size > MAX_PAYLOAD_SIZE + FLAGS_SIZE as usize {
+            return Err(Error::MessageTooBig { size: payload_size });
+        }
+
         let frame_size = PREFIX_SIZE as usize + payload_size;
 
         if buffer.len() < frame_size {
@@ -74,15 +172,57 @@ impl Decoder for Codec {
         }
 
         let _prefix = buffer.split_to(PREFIX_SIZE.into());
-        let payload = buffer.split_to(payload_size);
+        let mut payload = buffer.split_to(payload_size);
+
+        if payload.is_empty() {
+            return Err(Error::MissingFlags);
+        }
+        let flags = payload.get_u8();
+        let version = flags >> VERSION_SHIFT;
+        if version != VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        if self.negotiate {
+            self.peer_compression = compression_from_bits(flags >> ACCEPTED_COMPRESSION_SHIFT)?;
+        }
 
-        let message =
-            bincode::deserialize(&payload).map_err(|error| Error::Deserialization(*error))?;
+        let message = match compression_from_bits(flags & PAYLOAD_COMPRESSION_MASK)? {
+            None => bincode::deserialize(&payload),
+            Some(compression) => {
+                if self.accepted_compression != Some(compression) {
+                    return Err(Error::UnexpectedCompression(compression));
+                }
+                let payload = compression
+                    .decompress(&payload, MAX_PAYLOAD_SIZE)
+                    .map_err(Error::Decompression)?;
+                bincode::deserialize(&payload)
+            }
+        }
+        .map_err(|error| Error::Deserialization(*error))?;
 
         Ok(Some(message))
     }
 }
 
+/// Returns the bits that represent the `compression` in the frame flags.
+fn compression_to_bits(compression: Option<Compression>) -> u8 {
+    match compression {
+        None => 0,
+        Some(Compression::Gzip) => 1,
+        Some(Compression::Zstd) => 2,
+    }
+}
+
+/// Returns the compression represented by the lowest two `bits` of the frame flags.
+fn compression_from_bits(bits: u8) -> Result<Option<Compression>, Error> {
+    match bits & PAYLOAD_COMPRESSION_MASK {
+        0 => Ok(None),
+        1 => Ok(Some(Compression::Gzip)),
+        2 => Ok(Some(Compression::Zstd)),
+        bits => Err(Error::UnknownCompression(bits)),
+    }
+}
+
 /// Errors that can arise during transmission or reception of [`RpcMessage`]s.
 #[derive(Debug, Error)]
 pub enum Error {
@@ -97,8 +237,26 @@ pub enum Error {
 
     #[error("RpcMessage is too big to fit in a protocol frame: \
         message is {size} bytes but can't be larger than {max} bytes.",
-        max = u32::MAX)]
+        max = MAX_PAYLOAD_SIZE)]
     MessageTooBig { size: usize },
+
+    #[error("Frame is missing its flags")]
+    MissingFlags,
+
+    #[error("Unsupported frame format version: {0}")]
+    UnsupportedVersion(u8),
+
+    #[error("Unknown compression in frame flags: {0}")]
+    UnknownCompression(u8),
+
+    #[error("Received a payload compressed with {0:?}, which was not asked for")]
+    UnexpectedCompression(Compression),
+
+    #[error("Failed to compress outgoing message: {0}")]
+    Compression(#[source] io::Error),
+
+    #[error("Failed to decompress incoming message: {0}")]
+    Decompression(#[source] io::Error),
 }
 
 impl From<Error> for NodeError {
@@ -118,16 +276,21 @@ impl From<Error> for NodeError {
 #[cfg(test)]
 mod tests {
     use bytes::{BufMut, BytesMut};
+    use linera_base::data_types::BlobContent;
     use linera_core::data_types::ChainInfoQuery;
     use test_strategy::proptest;
     use tokio_util::codec::{Decoder, Encoder};
 
-    use super::{Codec, RpcMessage, PREFIX_SIZE};
+    use super::{
+        Codec, Compression, Error, RpcMessage, ACCEPTED_COMPRESSION_SHIFT, MAX_PAYLOAD_SIZE,
+        PAYLOAD_COMPRESSION_MASK, PREFIX_SIZE, VERSION, VERSION_SHIFT,
+    };
 
     /// Test decoding of a frame from a buffer.
     ///
     /// The buffer may contain leading or trailing bytes around the frame. The frame contains the
-    /// size of the payload, and the payload is a serialized dummy [`RpcMessage`].
+    /// size of the flags and the payload, the flags, and the payload is a serialized dummy
+    /// [`RpcMessage`].
     ///
     /// The decoder should produce the exact same message as used as the test input, and it should
     /// ignore the leading and trailing bytes.
@@ -141,18 +304,19 @@ mod tests {
         let payload = bincode::serialize(&message).expect("RpcMessage is serializable");
 
         let mut buffer = BytesMut::with_capacity(
-            leading_bytes.len() + PREFIX_SIZE as usize + payload.len() + trailing_bytes.len(),
+            leading_bytes.len() + PREFIX_SIZE as usize + 1 + payload.len() + trailing_bytes.len(),
         );
 
         buffer.extend_from_slice(&leading_bytes);
 
         let start_of_buffer = buffer.split();
 
-        buffer.put_u32_le(payload.len() as u32);
+        buffer.put_u32_le(payload.len() as u32 + 1);
+        buffer.put_u8(VERSION << VERSION_SHIFT);
         buffer.extend_from_slice(&payload);
         buffer.extend_from_slice(&trailing_bytes);
 
-        let result = Codec.decode(&mut buffer);
+        let result = Codec::new(None).decode(&mut buffer);
 
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), Some(message));
@@ -166,9 +330,9 @@ mod tests {
     /// The buffer may already contain some leading bytes, but the cursor is set to where the frame
     /// should start.
     ///
-    /// The encoder should write a prefix with the size of the serialized message, followed by the
-    /// serialized message bytes. It should not touch the leading bytes nor append any trailing
-    /// bytes.
+    /// The encoder should write a prefix with the size of the flags and the serialized message,
+    /// followed by the flags and the serialized message bytes. It should not touch the leading
+    /// bytes nor append any trailing bytes.
     #[proptest]
     fn encodes_at_the_correct_buffer_offset(
         leading_bytes: Vec<u8>,
@@ -185,7 +349,7 @@ mod tests {
         let frame_start = buffer.len();
         let prefix_end = frame_start + PREFIX_SIZE as usize;
 
-        let result = Codec.encode(message, &mut buffer);
+        let result = Codec::new(None).encode(message, &mut buffer);
 
         assert!(matches!(result, Ok(())));
         assert_eq!(&buffer[..frame_start], &leading_bytes);
@@ -196,12 +360,138 @@ mod tests {
                 .expect("Incorrect prefix slice indices"),
         );
 
-        assert_eq!(prefix as usize, serialized_message.len());
+        assert_eq!(prefix as usize, serialized_message.len() + 1);
         assert_eq!(
             buffer.len(),
             leading_bytes.len() + PREFIX_SIZE as usize + prefix as usize
         );
 
-        assert_eq!(&buffer[prefix_end..], &serialized_message);
+        assert_eq!(buffer[prefix_end], VERSION << VERSION_SHIFT);
+        assert_eq!(&buffer[prefix_end + 1..], &serialized_message);
+    }
+
+    /// Tests that large responses are compressed with the algorithm the client asked for, and
+    /// small ones are not.
+    #[test]
+    fn compresses_large_messages_for_peers_that_ask_for_it() {
+        for compression in [Compression::Gzip, Compression::Zstd] {
+            let mut client = Codec::new(Some(compression));
+            let mut server = Codec::new(None);
+            let mut buffer = BytesMut::new();
+
+            client
+                .encode(RpcMessage::VersionInfoQuery, &mut buffer)
+                .unwrap();
+            let request = server.decode(&mut buffer).unwrap();
+            assert_eq!(request, Some(RpcMessage::VersionInfoQuery));
+
+            let small =
+                RpcMessage::DownloadBlobResponse(Box::new(BlobContent::new_data(vec![0; 10])));
+            server.encode(small.clone(), &mut buffer).unwrap();
+            assert_eq!(buffer[PREFIX_SIZE as usize] & PAYLOAD_COMPRESSION_MASK, 0);
+            assert_eq!(client.decode(&mut buffer).unwrap(), Some(small));
+
+            let large =
+                RpcMessage::DownloadBlobResponse(Box::new(BlobContent::new_data(vec![0; 100_000])));
+            server.encode(large.clone(), &mut buffer).unwrap();
+            assert!(buffer.len() < 10_000);
+            assert_eq!(
+                buffer[PREFIX_SIZE as usize] & PAYLOAD_COMPRESSION_MASK,
+                super::compression_to_bits(Some(compression))
+            );
+            assert_eq!(client.decode(&mut buffer).unwrap(), Some(large));
+        }
+    }
+
+    /// Tests that datagram codecs neither ask for nor use compression.
+    #[test]
+    fn does_not_compress_datagrams() {
+        let mut codec = Codec::for_datagrams();
+        let payload = bincode::serialize(&RpcMessage::VersionInfoQuery).unwrap();
+        let mut buffer = BytesMut::new();
+        buffer.put_u32_le(payload.len() as u32 + 1);
+        buffer.put_u8(
+            super::compression_to_bits(Some(Compression::Zstd)) << ACCEPTED_COMPRESSION_SHIFT
+                | VERSION << VERSION_SHIFT,
+        );
+        buffer.extend_from_slice(&payload);
+        assert_eq!(
+            codec.decode(&mut buffer).unwrap(),
+            Some(RpcMessage::VersionInfoQuery)
+        );
+
+        let large =
+            RpcMessage::DownloadBlobResponse(Box::new(BlobContent::new_data(vec![0; 100_000])));
+        codec.encode(large, &mut buffer).unwrap();
+        assert_eq!(buffer[PREFIX_SIZE as usize] & PAYLOAD_COMPRESSION_MASK, 0);
+    }
+
+    /// Tests that compressed payloads are rejected unless this side asked for that
+    /// compression.
+    #[test]
+    fn rejects_compression_that_was_not_asked_for() {
+        let large =
+            RpcMessage::DownloadBlobResponse(Box::new(BlobContent::new_data(vec![0; 100_000])));
+        let mut buffer = BytesMut::new();
+        let mut client = Codec::new(Some(Compression::Zstd));
+        let mut server = Codec::new(None);
+        client
+            .encode(RpcMessage::VersionInfoQuery, &mut buffer)
+            .unwrap();
+        server.decode(&mut buffer).unwrap();
+        server.encode(large, &mut buffer).unwrap();
+
+        let result = Codec::new(Some(Compression::Gzip)).decode(&mut buffer.clone());
+        assert!(matches!(
+            result,
+            Err(Error::UnexpectedCompression(Compression::Zstd))
+        ));
+        let result = Codec::new(None).decode(&mut buffer);
+        assert!(matches!(
+            result,
+            Err(Error::UnexpectedCompression(Compression::Zstd))
+        ));
+    }
+
+    /// Tests that payloads larger than the maximum are rejected, before they are received
+    /// and after they are decompressed.
+    #[test]
+    fn rejects_oversized_payloads() {
+        let mut buffer = BytesMut::new();
+        buffer.put_u32_le(MAX_PAYLOAD_SIZE as u32 + 2);
+        buffer.put_u8(VERSION << VERSION_SHIFT);
+        assert!(matches!(
+            Codec::new(None).decode(&mut buffer),
+            Err(Error::MessageTooBig { .. })
+        ));
+
+        let bomb = Compression::Zstd
+            .compress(&vec![0; MAX_PAYLOAD_SIZE + 1])
+            .unwrap();
+        let mut buffer = BytesMut::new();
+        buffer.put_u32_le(bomb.len() as u32 + 1);
+        buffer
+            .put_u8(super::compression_to_bits(Some(Compression::Zstd)) | VERSION << VERSION_SHIFT);
+        buffer.extend_from_slice(&bomb);
+        assert!(matches!(
+            Codec::new(Some(Compression::Zstd)).decode(&mut buffer),
+            Err(Error::Decompression(_))
+        ));
+    }
+
+    /// Tests that frames with another format version are rejected.
+    #[test]
+    fn rejects_unknown_versions() {
+        let payload = bincode::serialize(&RpcMessage::VersionInfoQuery).unwrap();
+        for version in [0, VERSION + 1] {
+            let mut buffer = BytesMut::new();
+            buffer.put_u32_le(payload.len() as u32 + 1);
+            buffer.put_u8(version << VERSION_SHIFT);
+            buffer.extend_from_slice(&payload);
+            assert!(matches!(
+                Codec::new(None).decode(&mut buffer),
+                Err(Error::UnsupportedVersion(v)) if v == version
+            ));
+        }
     }
 }
@@ -28,7 +28,12 @@ impl ValidatorNodeProvider for SimpleNodeProvider {
             }
         })?;
 
-        let client = SimpleClient::new(network, self.0.send_timeout, self.0.recv_timeout);
+        let client = SimpleClient::new(
+            network,
+            self.0.send_timeout,
+            self.0.recv_timeout,
+            self.0.compression,
+        );
 
         Ok(client)
     }
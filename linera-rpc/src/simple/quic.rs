@@ -0,0 +1,474 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! QUIC connections for the simple network protocol.
+//!
+//! Each request is sent on its own bidirectional stream, so that a large response doesn't
+//! delay the other requests on the same connection. Connections are kept open and reused.
+//!
+//! Servers present the certificate configured with [`QuicTlsConfig`], and clients verify
+//! it against the server's host name, like for gRPC over TLS.
+
+use std::{
+    collections::HashMap,
+    io,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use linera_core::JoinSetExt as _;
+use quinn::{
+    crypto::rustls::{QuicClientConfig, QuicServerConfig},
+    ClientConfig, Connection, ConnectionError, Endpoint, Incoming, RecvStream, SendStream,
+    ServerConfig,
+};
+use rustls::{
+    crypto::{ring, CryptoProvider},
+    pki_types::{pem::PemObject as _, CertificateDer, PrivateKeyDer},
+    RootCertStore,
+};
+use tokio::{io::Join, net::ToSocketAddrs, task::JoinSet};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+use super::transport::{MessageHandler, TcpServer, REAP_TASKS_THRESHOLD};
+
+/// The ALPN protocol identifier of the simple protocol over QUIC.
+const ALPN: &[u8] = b"linera-simple";
+
+/// The maximum number of concurrent requests on a connection.
+const MAX_CONCURRENT_STREAMS: u32 = 1024;
+
+/// The environment variable with the PEM file of the certificate chain of QUIC servers.
+pub const QUIC_CERTIFICATE_ENV_VAR: &str = "LINERA_QUIC_CERTIFICATE";
+
+/// The environment variable with the PEM file of the private key of QUIC servers.
+pub const QUIC_PRIVATE_KEY_ENV_VAR: &str = "LINERA_QUIC_PRIVATE_KEY";
+
+/// The environment variable with the PEM file of the certificate authorities that QUIC
+/// clients trust.
+pub const QUIC_CA_CERTIFICATE_ENV_VAR: &str = "LINERA_QUIC_CA_CERTIFICATE";
+
+/// A bidirectional QUIC stream, used for a single request.
+pub type QuicStream = Join<RecvStream, SendStream>;
+
+/// The certificates used by QUIC servers and clients.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QuicTlsConfig {
+    /// The PEM file with the certificate chain that servers present.
+    pub certificate: Option<PathBuf>,
+    /// The PEM file with the private key of servers.
+    pub private_key: Option<PathBuf>,
+    /// The PEM file with the certificate authorities that clients trust. If it is not set,
+    /// clients trust the web PKI roots.
+    pub ca_certificate: Option<PathBuf>,
+}
+
+impl QuicTlsConfig {
+    /// Reads the configuration from the `LINERA_QUIC_*` environment variables.
+    pub fn from_env() -> Self {
+        let path = |name| std::env::var_os(name).map(PathBuf::from);
+        Self {
+            certificate: path(QUIC_CERTIFICATE_ENV_VAR),
+            private_key: path(QUIC_PRIVATE_KEY_ENV_VAR),
+            ca_certificate: path(QUIC_CA_CERTIFICATE_ENV_VAR),
+        }
+    }
+
+    fn client_config(&self) -> Result<ClientConfig, io::Error> {
+        let mut roots = RootCertStore::empty();
+        match &self.ca_certificate {
+            Some(path) => {
+                for certificate in CertificateDer::pem_file_iter(path).map_err(io::Error::other)? {
+                    roots
+                        .add(certificate.map_err(io::Error::other)?)
+                        .map_err(io::Error::other)?;
+                }
+            }
+            None => roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned()),
+        }
+        let mut crypto = rustls::ClientConfig::builder_with_provider(crypto_provider())
+            .with_protocol_versions(&[&rustls::version::TLS13])
+            .map_err(io::Error::other)?
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        crypto.alpn_protocols = vec![ALPN.to_vec()];
+        let crypto = QuicClientConfig::try_from(crypto).map_err(io::Error::other)?;
+        Ok(ClientConfig::new(Arc::new(crypto)))
+    }
+
+    fn server_config(&self) -> Result<ServerConfig, io::Error> {
+        let (Some(certificate), Some(private_key)) = (&self.certificate, &self.private_key) else {
+            return Err(io::Error::other(format!(
+                "QUIC servers need a certificate: set {QUIC_CERTIFICATE_ENV_VAR} and \
+                 {QUIC_PRIVATE_KEY_ENV_VAR}"
+            )));
+        };
+        let certificates = CertificateDer::pem_file_iter(certificate)
+            .map_err(io::Error::other)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(io::Error::other)?;
+        let key = PrivateKeyDer::from_pem_file(private_key).map_err(io::Error::other)?;
+        let mut crypto = rustls::ServerConfig::builder_with_provider(crypto_provider())
+            .with_protocol_versions(&[&rustls::version::TLS13])
+            .map_err(io::Error::other)?
+            .with_no_client_auth()
+            .with_single_cert(certificates, key)
+            .map_err(io::Error::other)?;
+        crypto.alpn_protocols = vec![ALPN.to_vec()];
+        let crypto = QuicServerConfig::try_from(crypto).map_err(io::Error::other)?;
+        let mut config = ServerConfig::with_crypto(Arc::new(crypto));
+        Arc::get_mut(&mut config.transport)
+            .expect("The transport configuration should not be shared yet")
+            .max_concurrent_bidi_streams(MAX_CONCURRENT_STREAMS.into());
+        Ok(config)
+    }
+}
+
+/// Opens streams to QUIC servers, reusing a connection per server.
+#[derive(Clone, Debug)]
+pub struct QuicConnector {
+    tls: Arc<QuicTlsConfig>,
+    state: Arc<Mutex<ConnectorState>>,
+}
+
+#[derive(Debug, Default)]
+struct ConnectorState {
+    endpoint: Option<Endpoint>,
+    connections: HashMap<(String, SocketAddr), Connection>,
+}
+
+impl Default for QuicConnector {
+    /// Creates a connector configured by the environment variables.
+    fn default() -> Self {
+        Self::new(QuicTlsConfig::from_env())
+    }
+}
+
+impl QuicConnector {
+    /// Creates a connector that verifies servers with the given configuration.
+    pub fn new(tls: QuicTlsConfig) -> Self {
+        Self {
+            tls: Arc::new(tls),
+            state: Arc::default(),
+        }
+    }
+
+    /// Opens a new stream to the server `host` at `address`, connecting to it if needed.
+    /// The server's certificate must be valid for `host`.
+    pub async fn open_stream(
+        &self,
+        host: &str,
+        address: SocketAddr,
+    ) -> Result<QuicStream, io::Error> {
+        let key = (host.to_owned(), address);
+        let (endpoint, connection) = {
+            let mut state = self.state.lock().unwrap();
+            let endpoint = match &state.endpoint {
+                Some(endpoint) => endpoint.clone(),
+                None => {
+                    let mut endpoint = Endpoint::client(SocketAddr::from(([0, 0, 0, 0], 0)))?;
+                    endpoint.set_default_client_config(self.tls.client_config()?);
+                    state.endpoint.insert(endpoint).clone()
+                }
+            };
+            let connection = state
+                .connections
+                .get(&key)
+                .filter(|connection| connection.close_reason().is_none())
+                .cloned();
+            (endpoint, connection)
+        };
+        if let Some(connection) = connection {
+            match connection.open_bi().await {
+                Ok((send, recv)) => return Ok(tokio::io::join(recv, send)),
+                Err(error) => debug!(%error, %address, "QUIC connection lost, reconnecting"),
+            }
+        }
+        let connection = endpoint
+            .connect(address, host)
+            .map_err(io::Error::other)?
+            .await?;
+        let (send, recv) = connection.open_bi().await?;
+        self.state
+            .lock()
+            .unwrap()
+            .connections
+            .insert(key, connection);
+        Ok(tokio::io::join(recv, send))
+    }
+}
+
+/// Server implementation for QUIC.
+pub struct QuicServer;
+
+impl QuicServer {
+    /// Runs the QUIC server implementation.
+    ///
+    /// Accepts connections, and serves each of their streams like a TCP connection.
+    pub async fn run<State>(
+        address: impl ToSocketAddrs,
+        handler: State,
+        tls: QuicTlsConfig,
+        shutdown_signal: CancellationToken,
+    ) -> Result<(), io::Error>
+    where
+        State: MessageHandler + Send + 'static,
+    {
+        let address = tokio::net::lookup_host(address)
+            .await?
+            .next()
+            .ok_or_else(|| io::Error::other("Couldn't resolve address to listen on"))?;
+        let endpoint = Endpoint::server(tls.server_config()?, address)?;
+
+        let connection_shutdown_signal = shutdown_signal.child_token();
+        let mut join_set = JoinSet::new();
+        let mut reap_countdown = REAP_TASKS_THRESHOLD;
+
+        loop {
+            tokio::select! { biased;
+                _ = shutdown_signal.cancelled() => {
+                    join_set.await_all_tasks().await;
+                    endpoint.close(0u32.into(), b"shutdown");
+                    return Ok(());
+                }
+                incoming = endpoint.accept() => match incoming {
+                    Some(incoming) => {
+                        join_set.spawn_task(Self::serve_connection(
+                            incoming,
+                            handler.clone(),
+                            connection_shutdown_signal.clone(),
+                        ));
+                        reap_countdown -= 1;
+                    }
+                    None => return Ok(()),
+                },
+            }
+
+            if reap_countdown == 0 {
+                join_set.reap_finished_tasks();
+                reap_countdown = REAP_TASKS_THRESHOLD;
+            }
+        }
+    }
+
+    /// Serves the streams of a single connection.
+    async fn serve_connection<State>(
+        incoming: Incoming,
+        handler: State,
+        shutdown_signal: CancellationToken,
+    ) where
+        State: MessageHandler + Send + 'static,
+    {
+        let connection = match incoming.await {
+            Ok(connection) => connection,
+            Err(error) => {
+                debug!(%error, "Failed to accept QUIC connection");
+                return;
+            }
+        };
+        let peer = connection.remote_address();
+        let mut join_set = JoinSet::new();
+        let mut reap_countdown = REAP_TASKS_THRESHOLD;
+
+        loop {
+            tokio::select! { biased;
+                _ = shutdown_signal.cancelled() => break,
+                stream = connection.accept_bi() => match stream {
+                    Ok((send, recv)) => {
+                        let server = TcpServer::new_connection(
+                            tokio::io::join(recv, send),
+                            Some(peer),
+                            handler.clone(),
+                            shutdown_signal.clone(),
+                        );
+                        join_set.spawn_task(server.serve());
+                        reap_countdown -= 1;
+                    }
+                    Err(
+                        ConnectionError::ApplicationClosed(_)
+                        | ConnectionError::ConnectionClosed(_)
+                        | ConnectionError::LocallyClosed
+                        | ConnectionError::TimedOut,
+                    ) => break,
+                    Err(error) => {
+                        warn!(%error, %peer, "QUIC connection failed");
+                        break;
+                    }
+                },
+            }
+
+            if reap_countdown == 0 {
+                join_set.reap_finished_tasks();
+                reap_countdown = REAP_TASKS_THRESHOLD;
+            }
+        }
+
+        join_set.await_all_tasks().await;
+    }
+}
+
+fn crypto_provider() -> Arc<CryptoProvider> {
+    Arc::new(ring::default_provider())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, path::Path};
+
+    use async_trait::async_trait;
+    use futures::{SinkExt as _, StreamExt as _};
+    use linera_base::port::get_free_port;
+    use tokio_util::{codec::Framed, sync::CancellationToken};
+
+    use super::{QuicConnector, QuicServer, QuicTlsConfig};
+    use crate::{
+        simple::{codec::Codec, MessageHandler},
+        RpcMessage,
+    };
+
+    #[derive(Clone)]
+    struct VersionHandler;
+
+    #[async_trait]
+    impl MessageHandler for VersionHandler {
+        async fn handle_message(&mut self, message: RpcMessage) -> Option<RpcMessage> {
+            match message {
+                RpcMessage::VersionInfoQuery => {
+                    Some(RpcMessage::VersionInfoResponse(Box::default()))
+                }
+                _ => None,
+            }
+        }
+    }
+
+    /// Writes a certificate authority, and a certificate for `localhost` signed by it.
+    fn write_certificates(directory: &Path) -> QuicTlsConfig {
+        let mut ca_params = rcgen::CertificateParams::new(vec![]);
+        ca_params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+        let ca = rcgen::Certificate::from_params(ca_params).unwrap();
+        let certificate = rcgen::Certificate::from_params(rcgen::CertificateParams::new(vec![
+            "localhost".to_owned(),
+        ]))
+        .unwrap();
+        let config = QuicTlsConfig {
+            certificate: Some(directory.join("cert.pem")),
+            private_key: Some(directory.join("key.pem")),
+            ca_certificate: Some(directory.join("ca.pem")),
+        };
+        std::fs::write(directory.join("ca.pem"), ca.serialize_pem().unwrap()).unwrap();
+        std::fs::write(
+            directory.join("cert.pem"),
+            certificate.serialize_pem_with_signer(&ca).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(
+            directory.join("key.pem"),
+            certificate.serialize_private_key_pem(),
+        )
+        .unwrap();
+        config
+    }
+
+    /// Starts a server, and returns its address and the configuration to connect to it.
+    async fn start_server(
+        directory: &Path,
+        shutdown_signal: &CancellationToken,
+    ) -> anyhow::Result<(SocketAddr, QuicTlsConfig)> {
+        let address = SocketAddr::from(([127, 0, 0, 1], get_free_port().await?));
+        let config = write_certificates(directory);
+        tokio::spawn(QuicServer::run(
+            address,
+            VersionHandler,
+            config.clone(),
+            shutdown_signal.clone(),
+        ));
+        Ok((address, config))
+    }
+
+    async fn query_version(
+        connector: &QuicConnector,
+        host: &str,
+        address: SocketAddr,
+    ) -> anyhow::Result<()> {
+        let stream = connector.open_stream(host, address).await?;
+        let mut transport = Framed::new(stream, Codec::new(None));
+        transport.send(RpcMessage::VersionInfoQuery).await?;
+        let response = transport.next().await.expect("a response")?;
+        assert!(matches!(response, RpcMessage::VersionInfoResponse(_)));
+        Ok(())
+    }
+
+    /// Sends concurrent requests to a server, each on its own stream of a single connection.
+    #[tokio::test]
+    async fn serves_concurrent_streams_on_one_connection() -> anyhow::Result<()> {
+        let directory = tempfile::tempdir()?;
+        let shutdown_signal = CancellationToken::new();
+        let (address, config) = start_server(directory.path(), &shutdown_signal).await?;
+
+        let connector = QuicConnector::new(config);
+        let mut transports = Vec::new();
+        for _ in 0..10 {
+            let stream = connector.open_stream("localhost", address).await?;
+            let mut transport = Framed::new(stream, Codec::new(None));
+            transport.send(RpcMessage::VersionInfoQuery).await?;
+            transports.push(transport);
+        }
+        for mut transport in transports.into_iter().rev() {
+            let response = transport.next().await.expect("a response")?;
+            assert!(matches!(response, RpcMessage::VersionInfoResponse(_)));
+        }
+        assert_eq!(connector.state.lock().unwrap().connections.len(), 1);
+
+        shutdown_signal.cancel();
+        Ok(())
+    }
+
+    /// Clients reject servers whose certificate is not signed by a trusted authority, or
+    /// not valid for the server's host name.
+    #[tokio::test]
+    async fn verifies_server_certificates() -> anyhow::Result<()> {
+        let directory = tempfile::tempdir()?;
+        let other_directory = tempfile::tempdir()?;
+        let shutdown_signal = CancellationToken::new();
+        let (address, config) = start_server(directory.path(), &shutdown_signal).await?;
+        let other_config = write_certificates(other_directory.path());
+
+        query_version(&QuicConnector::new(config.clone()), "localhost", address).await?;
+        assert!(
+            query_version(&QuicConnector::new(other_config), "localhost", address)
+                .await
+                .is_err()
+        );
+        assert!(
+            query_version(&QuicConnector::new(config), "example.com", address)
+                .await
+                .is_err()
+        );
+        // Without an authority, clients trust the web PKI roots only.
+        assert!(query_version(
+            &QuicConnector::new(QuicTlsConfig::default()),
+            "localhost",
+            address
+        )
+        .await
+        .is_err());
+
+        shutdown_signal.cancel();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn servers_require_a_certificate() {
+        let shutdown_signal = CancellationToken::new();
+        let result = QuicServer::run(
+            "127.0.0.1:0",
+            VersionHandler,
+            QuicTlsConfig::default(),
+            shutdown_signal,
+        )
+        .await;
+        assert!(result.is_err());
+    }
+}
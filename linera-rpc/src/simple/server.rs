@@ -327,6 +327,29 @@ where
                     }
                 }
             }
+            RpcMessage::ChainSummariesQuery(chain_ids) => {
+                if let Err(error) = NodeError::check_chain_summaries_query(&chain_ids) {
+                    Err(error)
+                } else {
+                    match self
+                        .server
+                        .state
+                        .handle_chain_summaries_query(chain_ids)
+                        .await
+                    {
+                        Ok(results) => Ok(Some(RpcMessage::ChainSummariesResponse(
+                            results
+                                .into_iter()
+                                .map(|result| result.map_err(Into::into))
+                                .collect(),
+                        ))),
+                        Err(error) => {
+                            self.log_error(&error, "Failed to handle chain summaries query");
+                            Err(error.into())
+                        }
+                    }
+                }
+            }
             RpcMessage::CrossChainRequest(request) => {
                 match self.server.state.handle_cross_chain_request(*request).await {
                     Ok(actions) => {
@@ -408,9 +431,8 @@ where
             | RpcMessage::UploadBlob(_)
             | RpcMessage::UploadBlobResponse(_)
             | RpcMessage::DownloadCertificatesByHeights(_, _)
-            | RpcMessage::DownloadCertificatesByHeightsResponse(_) => {
-                Err(NodeError::UnexpectedMessage)
-            }
+            | RpcMessage::DownloadCertificatesByHeightsResponse(_)
+            | RpcMessage::ChainSummariesResponse(_) => Err(NodeError::UnexpectedMessage),
         };
 
         self.server.packets_processed += 1;
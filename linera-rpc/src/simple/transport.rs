@@ -20,7 +20,7 @@ use linera_base::identifiers::{BlobId, ChainId};
 use linera_core::{JoinSetExt as _, TaskHandle};
 use serde::{Deserialize, Serialize};
 use tokio::{
-    io::AsyncWriteExt,
+    io::{AsyncRead, AsyncWrite, AsyncWriteExt},
     net::{lookup_host, TcpListener, TcpStream, ToSocketAddrs, UdpSocket},
     sync::Mutex,
     task::JoinSet,
@@ -29,7 +29,12 @@ use tokio_util::{codec::Framed, sync::CancellationToken, udp::UdpFramed};
 use tracing::{error, warn};
 
 use crate::{
-    simple::{codec, codec::Codec},
+    compression::Compression,
+    simple::{
+        codec,
+        codec::Codec,
+        quic::{QuicConnector, QuicServer, QuicTlsConfig},
+    },
     RpcMessage,
 };
 
@@ -37,13 +42,14 @@ use crate::{
 pub const DEFAULT_MAX_DATAGRAM_SIZE: &str = "65507";
 
 /// Number of tasks to spawn before attempting to reap some finished tasks to prevent memory leaks.
-const REAP_TASKS_THRESHOLD: usize = 100;
+pub(super) const REAP_TASKS_THRESHOLD: usize = 100;
 
 // Supported transport protocols.
 #[derive(clap::ValueEnum, Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub enum TransportProtocol {
     Udp,
     Tcp,
+    Quic,
 }
 
 impl std::str::FromStr for TransportProtocol {
@@ -65,6 +71,7 @@ impl TransportProtocol {
         match self {
             TransportProtocol::Udp => "udp",
             TransportProtocol::Tcp => "tcp",
+            TransportProtocol::Quic => "quic",
         }
     }
 }
@@ -139,35 +146,18 @@ impl<T> Transport for T where
 }
 
 impl TransportProtocol {
-    /// Creates a transport for this protocol.
-    pub async fn connect(
-        self,
-        address: impl ToSocketAddrs,
-    ) -> Result<impl Transport, std::io::Error> {
-        let mut addresses = lookup_host(address)
-            .await
-            .expect("Invalid address to connect to");
-        let address = addresses
-            .next()
-            .expect("Couldn't resolve address to connect to");
-
-        let stream: futures::future::Either<_, _> = match self {
-            TransportProtocol::Udp => {
-                let socket = UdpSocket::bind(&"0.0.0.0:0").await?;
-
-                UdpFramed::new(socket, Codec)
-                    .with(move |message| future::ready(Ok((message, address))))
-                    .map_ok(|(message, _address)| message)
-                    .left_stream()
-            }
-            TransportProtocol::Tcp => {
-                let stream = TcpStream::connect(address).await?;
-
-                Framed::new(stream, Codec).right_stream()
-            }
-        };
+    /// Creates a transport for this protocol to the server `host` on `port`.
+    pub async fn connect(self, host: &str, port: u16) -> Result<impl Transport, std::io::Error> {
+        self.connector().connect(host, port).await
+    }
 
-        Ok(stream)
+    /// Creates a [`Connector`] for this protocol, to open several transports.
+    pub fn connector(self) -> Connector {
+        Connector {
+            protocol: self,
+            compression: None,
+            quic: QuicConnector::default(),
+        }
     }
 
     /// Creates a [`ConnectionPool`] for this protocol.
@@ -177,6 +167,7 @@ impl TransportProtocol {
         let pool: Box<dyn ConnectionPool> = match self {
             Self::Udp => Box::new(UdpConnectionPool::new().await?),
             Self::Tcp => Box::new(TcpConnectionPool::new()),
+            Self::Quic => Box::new(QuicConnectionPool::default()),
         };
         Ok(pool)
     }
@@ -195,11 +186,71 @@ impl TransportProtocol {
         let handle = match self {
             Self::Udp => join_set.spawn_task(UdpServer::run(address, state, shutdown_signal)),
             Self::Tcp => join_set.spawn_task(TcpServer::run(address, state, shutdown_signal)),
+            Self::Quic => join_set.spawn_task(QuicServer::run(
+                address,
+                state,
+                QuicTlsConfig::from_env(),
+                shutdown_signal,
+            )),
         };
         ServerHandle { handle }
     }
 }
 
+/// Opens transports for a protocol. With QUIC, the connection to each address is reused,
+/// and each transport is a new stream on that connection.
+#[derive(Clone, Debug)]
+pub struct Connector {
+    protocol: TransportProtocol,
+    compression: Option<Compression>,
+    quic: QuicConnector,
+}
+
+impl Connector {
+    /// Asks servers to compress large responses with `compression`. Compression is not
+    /// used over UDP.
+    pub fn with_compression(mut self, compression: Option<Compression>) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Creates a transport to the server `host` on `port`.
+    pub async fn connect(&self, host: &str, port: u16) -> Result<impl Transport, std::io::Error> {
+        let mut addresses = lookup_host((host, port))
+            .await
+            .expect("Invalid address to connect to");
+        let address = addresses
+            .next()
+            .expect("Couldn't resolve address to connect to");
+
+        let stream: futures::future::Either<_, _> = match self.protocol {
+            TransportProtocol::Udp => {
+                let socket = UdpSocket::bind(&"0.0.0.0:0").await?;
+
+                UdpFramed::new(socket, Codec::for_datagrams())
+                    .with(move |message| future::ready(Ok((message, address))))
+                    .map_ok(|(message, _address)| message)
+                    .left_stream()
+                    .left_stream()
+            }
+            TransportProtocol::Tcp => {
+                let stream = TcpStream::connect(address).await?;
+
+                Framed::new(stream, Codec::new(self.compression))
+                    .right_stream()
+                    .left_stream()
+            }
+            TransportProtocol::Quic => {
+                let stream = self.quic.open_stream(host, address).await?;
+
+                Framed::new(stream, Codec::new(self.compression)).right_stream()
+            }
+        };
+
+        Ok(stream)
+    }
+}
+
 /// An implementation of [`ConnectionPool`] based on UDP.
 struct UdpConnectionPool {
     transport: UdpFramed<Codec>,
@@ -208,7 +259,7 @@ struct UdpConnectionPool {
 impl UdpConnectionPool {
     async fn new() -> Result<Self, std::io::Error> {
         let socket = UdpSocket::bind(&"0.0.0.0:0").await?;
-        let transport = UdpFramed::new(socket, Codec);
+        let transport = UdpFramed::new(socket, Codec::for_datagrams());
         Ok(Self { transport })
     }
 }
@@ -269,7 +320,7 @@ where
     /// provided `handler`.
     async fn bind(address: impl ToSocketAddrs, handler: State) -> Result<Self, std::io::Error> {
         let socket = UdpSocket::bind(address).await?;
-        let (udp_sink, udp_stream) = UdpFramed::new(socket, Codec).split();
+        let (udp_sink, udp_stream) = UdpFramed::new(socket, Codec::for_datagrams()).split();
 
         Ok(UdpServer {
             handler,
@@ -358,7 +409,7 @@ impl TcpConnectionPool {
             match TcpStream::connect(address).await {
                 Ok(s) => {
                     self.streams
-                        .insert(address.to_string(), Framed::new(s, Codec));
+                        .insert(address.to_string(), Framed::new(s, Codec::new(None)));
                 }
                 Err(error) => {
                     error!("Failed to open connection to {}: {}", address, error);
@@ -387,9 +438,41 @@ impl ConnectionPool for TcpConnectionPool {
     }
 }
 
-/// Server implementation for TCP.
-pub struct TcpServer<State> {
-    connection: Framed<TcpStream, Codec>,
+/// An implementation of [`ConnectionPool`] based on QUIC, sending each message on a new
+/// stream.
+#[derive(Default)]
+struct QuicConnectionPool {
+    connector: QuicConnector,
+}
+
+impl ConnectionPool for QuicConnectionPool {
+    fn send_message_to<'a>(
+        &'a mut self,
+        message: RpcMessage,
+        address: &'a str,
+    ) -> future::BoxFuture<'a, Result<(), codec::Error>> {
+        Box::pin(async move {
+            let host = address.rsplit_once(':').map_or(address, |(host, _)| {
+                host.trim_start_matches('[').trim_end_matches(']')
+            });
+            let socket_address = lookup_host(address).await?.next().ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("Couldn't resolve {address}"),
+                )
+            })?;
+            let stream = self.connector.open_stream(host, socket_address).await?;
+            let mut transport = Framed::new(stream, Codec::new(None));
+            transport.send(message).await?;
+            transport.close().await
+        })
+    }
+}
+
+/// Server implementation for TCP. It also serves the streams of [`QuicServer`].
+pub struct TcpServer<State, Connection = TcpStream> {
+    connection: Framed<Connection, Codec>,
+    peer: Option<SocketAddr>,
     handler: State,
     shutdown_signal: CancellationToken,
 }
@@ -427,8 +510,10 @@ where
                 }
                 maybe_socket = accept_stream.next() => match maybe_socket {
                     Some(Ok(socket)) => {
+                        let peer = socket.peer_addr().ok();
                         let server = TcpServer::new_connection(
                             socket,
+                            peer,
                             handler.clone(),
                             connection_shutdown_signal.clone(),
                         );
@@ -451,31 +536,39 @@ where
             }
         }
     }
+}
 
+impl<State, Connection> TcpServer<State, Connection>
+where
+    State: MessageHandler + Send + 'static,
+    Connection: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
     /// Creates a new [`TcpServer`] to serve a single connection established on the provided
-    /// [`TcpStream`].
-    fn new_connection(
-        tcp_stream: TcpStream,
+    /// stream, e.g. a [`TcpStream`].
+    pub(super) fn new_connection(
+        stream: Connection,
+        peer: Option<SocketAddr>,
         handler: State,
         shutdown_signal: CancellationToken,
     ) -> Self {
         TcpServer {
-            connection: Framed::new(tcp_stream, Codec),
+            connection: Framed::new(stream, Codec::new(None)),
+            peer,
             handler,
             shutdown_signal,
         }
     }
 
     /// Serves a client through a single connection.
-    async fn serve(mut self) {
+    pub(super) async fn serve(mut self) {
         loop {
             tokio::select! { biased;
                 _ = self.shutdown_signal.cancelled() => {
-                    let mut tcp_stream = self.connection.into_inner();
-                    if let Err(error) = tcp_stream.shutdown().await {
-                        let peer = tcp_stream
-                            .peer_addr()
-                            .map_or_else(|_| "an unknown peer".to_owned(), |address| address.to_string());
+                    let mut stream = self.connection.into_inner();
+                    if let Err(error) = stream.shutdown().await {
+                        let peer = self
+                            .peer
+                            .map_or_else(|| "an unknown peer".to_owned(), |address| address.to_string());
                         warn!("Failed to close connection to {peer}: {error:?}");
                     }
                     return;
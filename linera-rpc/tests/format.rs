@@ -14,12 +14,17 @@ use linera_chain::{
     manager::{ChainManagerInfo, LockingBlock},
     types::{Certificate, CertificateKind, ConfirmedBlock, Timeout, ValidatedBlock},
 };
-use linera_core::{data_types::CrossChainRequest, node::NodeError, worker::Reason};
+use linera_core::{
+    data_types::{ChainSummary, CrossChainRequest},
+    node::NodeError,
+    worker::Reason,
+};
 use linera_execution::{
     system::{AdminOperation, SystemMessage, SystemOperation},
     Message, MessageKind, Operation,
 };
 use linera_rpc::RpcMessage;
+use linera_views::proof::KeyProof;
 use serde_reflection::{Registry, Result, Samples, Tracer, TracerConfig};
 
 fn get_registry() -> Result<Registry> {
@@ -81,11 +86,13 @@ fn get_registry() -> Result<Registry> {
     tracer.trace_type::<ChainManagerInfo>(&samples)?;
     tracer.trace_type::<CrossChainRequest>(&samples)?;
     tracer.trace_type::<NodeError>(&samples)?;
+    tracer.trace_type::<std::result::Result<ChainSummary, NodeError>>(&samples)?;
     tracer.trace_type::<Reason>(&samples)?;
     tracer.trace_type::<RpcMessage>(&samples)?;
     tracer.trace_type::<BlobType>(&samples)?;
     tracer.trace_type::<BlobContent>(&samples)?;
     tracer.trace_type::<AccountOwner>(&samples)?;
+    tracer.trace_type::<KeyProof>(&samples)?;
     tracer.registry()
 }
 
@@ -31,6 +31,7 @@ async fn client() {
         max_retries,
         linera_rpc::node_provider::DEFAULT_MAX_BACKOFF,
         papaya::HashMap::new(),
+        None,
     )
     .get_version_info()
     .await
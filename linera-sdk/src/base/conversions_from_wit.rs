@@ -218,6 +218,13 @@ macro_rules! impl_from_wit {
                         .into_iter()
                         .map(Into::into)
                         .collect(),
+                    allow_reentrancy: description.allow_reentrancy,
+                    upgrade_authority: description.upgrade_authority.map(Into::into),
+                    module_history: description
+                        .module_history
+                        .into_iter()
+                        .map(Into::into)
+                        .collect(),
                 }
             }
         }
@@ -98,6 +98,14 @@ macro_rules! contract {
                 )
             }
 
+            fn migrate() {
+                use $crate::util::BlockingWait as _;
+                $crate::contract::run_async_entrypoint::<$contract, _, _>(
+                    unsafe { &mut CONTRACT },
+                    move |contract| contract.migrate().blocking_wait(),
+                )
+            }
+
             fn finalize() {
                 use $crate::util::BlockingWait as _;
 
@@ -5,6 +5,7 @@
 
 use linera_base::{
     abi::{ContractAbi, ServiceAbi},
+    crypto::CryptoHash,
     data_types::{
         Amount, ApplicationDescription, ApplicationPermissions, BlockHeight, Bytecode, Resources,
         SendMessageRequest, Timestamp,
@@ -443,6 +444,22 @@ where
     pub fn validation_round(&mut self) -> Option<u32> {
         contract_wit::validation_round()
     }
+
+    /// Returns a fresh random seed.
+    ///
+    /// The seed is derived deterministically from the validator signatures that confirmed
+    /// the previous block, the current application and the number of seeds requested so far
+    /// in this transaction. The signatures are recorded as an oracle response of the block,
+    /// and checked by the validators. They cannot be known before the previous block is
+    /// confirmed, and whoever assembles its certificate can only choose among the quorums of
+    /// validators that signed it. Once they are known, the owner proposing the block can
+    /// still influence the seed by choosing which transactions to include and in which
+    /// order, so it must not be used to protect secrets.
+    ///
+    /// Random seeds are not available in the first block of a chain.
+    pub fn random_seed(&mut self) -> CryptoHash {
+        contract_wit::random_seed().into()
+    }
 }
 
 /// A helper type that uses the builder pattern to configure how a message is sent, and then
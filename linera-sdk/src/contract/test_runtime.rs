@@ -10,6 +10,7 @@ use std::{
 
 use linera_base::{
     abi::{ContractAbi, ServiceAbi},
+    crypto::CryptoHash,
     data_types::{
         Amount, ApplicationDescription, ApplicationPermissions, BlockHeight, Bytecode, Resources,
         SendMessageRequest, Timestamp,
@@ -59,6 +60,7 @@ where
     authenticated_owner: Option<Option<AccountOwner>>,
     block_height: Option<BlockHeight>,
     round: Option<u32>,
+    random_seeds: VecDeque<CryptoHash>,
     message_is_bouncing: Option<Option<bool>>,
     message_origin_chain_id: Option<Option<ChainId>>,
     authenticated_caller_id: Option<Option<ApplicationId>>,
@@ -112,6 +114,7 @@ where
             authenticated_owner: None,
             block_height: None,
             round: None,
+            random_seeds: VecDeque::new(),
             message_is_bouncing: None,
             message_origin_chain_id: None,
             authenticated_caller_id: None,
@@ -1202,6 +1205,28 @@ where
         self.round
     }
 
+    /// Adds a random seed to be returned by the next call to
+    /// [`MockContractRuntime::random_seed`].
+    pub fn with_random_seed(mut self, seed: CryptoHash) -> Self {
+        self.random_seeds.push_back(seed);
+        self
+    }
+
+    /// Adds a random seed to be returned by the next call to
+    /// [`MockContractRuntime::random_seed`].
+    pub fn add_random_seed(&mut self, seed: CryptoHash) -> &mut Self {
+        self.random_seeds.push_back(seed);
+        self
+    }
+
+    /// Returns the next mocked random seed.
+    pub fn random_seed(&mut self) -> CryptoHash {
+        self.random_seeds.pop_front().expect(
+            "Random seed has not been mocked, \
+            please call `MockContractRuntime::add_random_seed` first",
+        )
+    }
+
     /// Configures the remaining fuel to return during the test.
     pub fn with_remaining_fuel(mut self, remaining_fuel: u64) -> Self {
         self.remaining_fuel = Some(remaining_fuel);
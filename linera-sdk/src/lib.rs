@@ -129,6 +129,13 @@ pub trait Contract: WithContractAbi + ContractAbi + Sized {
     /// subscribes to.
     async fn process_streams(&mut self, _updates: Vec<StreamUpdate>) {}
 
+    /// Migrates the application state after the application's module was upgraded.
+    ///
+    /// This is called once, on the new contract, when an upgradable application's module is
+    /// replaced on a chain. The state stored by the previous module is available through the
+    /// runtime as usual.
+    async fn migrate(&mut self) {}
+
     /// Finishes the execution of the current transaction.
     ///
     /// This is called once at the end of the transaction, to allow all applications that
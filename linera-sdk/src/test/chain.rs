@@ -583,6 +583,9 @@ impl ActiveChain {
             application_index: 0,
             parameters,
             required_application_ids,
+            allow_reentrancy: false,
+            upgrade_authority: None,
+            module_history: vec![],
         };
 
         ApplicationId::<()>::from(&description).with_abi()
@@ -626,6 +629,9 @@ impl ActiveChain {
             application_index: 0,
             parameters,
             required_application_ids,
+            allow_reentrancy: false,
+            upgrade_authority: None,
+            module_history: vec![],
         };
 
         Ok(ApplicationId::<()>::from(&description).with_abi())
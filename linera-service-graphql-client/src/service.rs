@@ -162,7 +162,7 @@ mod from {
         types::ConfirmedBlock,
     };
     use linera_execution::{
-        system::{AdminOperation, OpenChainConfig},
+        system::{AdminOperation, ApplicationOptions, OpenChainConfig, SponsorshipPolicy},
         OutgoingMessage,
     };
 
@@ -356,7 +356,7 @@ mod from {
                 })?;
                 Ok(SystemOperation::VerifyBlob { blob_id })
             }
-            "CreateApplication" => {
+            "CreateApplication" | "CreateApplicationWithOptions" => {
                 let create_application = system_op.create_application.ok_or_else(|| {
                     ConversionError::UnexpectedCertificateType(
                         "Missing create_application metadata".to_string(),
@@ -394,12 +394,25 @@ mod from {
                     })
                     .collect::<Result<Vec<_>, _>>()?;
 
-                Ok(SystemOperation::CreateApplication {
-                    module_id,
-                    parameters,
-                    instantiation_argument,
-                    required_application_ids,
-                })
+                if system_op.system_operation_type == "CreateApplication" {
+                    Ok(SystemOperation::CreateApplication {
+                        module_id,
+                        parameters,
+                        instantiation_argument,
+                        required_application_ids,
+                    })
+                } else {
+                    Ok(SystemOperation::CreateApplicationWithOptions {
+                        module_id,
+                        parameters,
+                        instantiation_argument,
+                        required_application_ids,
+                        options: ApplicationOptions {
+                            upgrade_authority: create_application.upgrade_authority,
+                            allow_reentrancy: create_application.allow_reentrancy,
+                        },
+                    })
+                }
             }
             "Admin" => {
                 let admin = system_op.admin.ok_or_else(|| {
@@ -493,6 +506,62 @@ mod from {
                     next_index: stream.next_index as u32,
                 })
             }
+            "UpgradeApplication" => {
+                let upgrade = system_op.upgrade_application.ok_or_else(|| {
+                    ConversionError::UnexpectedCertificateType(
+                        "Missing upgrade_application metadata for UpgradeApplication operation"
+                            .to_string(),
+                    )
+                })?;
+                let application_id = upgrade
+                    .application_id
+                    .parse::<RealApplicationId>()
+                    .map_err(|e| {
+                        ConversionError::UnexpectedCertificateType(format!(
+                            "Invalid application_id: {e}"
+                        ))
+                    })?;
+                let module_id: ModuleId = upgrade.module_id.parse().map_err(|e| {
+                    ConversionError::UnexpectedCertificateType(format!(
+                        "Invalid module_id format: {e}"
+                    ))
+                })?;
+                Ok(SystemOperation::UpgradeApplication {
+                    application_id,
+                    module_id,
+                })
+            }
+            "Sponsor" => {
+                let sponsor = system_op.sponsor.ok_or_else(|| {
+                    ConversionError::UnexpectedCertificateType(
+                        "Missing sponsor metadata for Sponsor operation".to_string(),
+                    )
+                })?;
+                let applications = sponsor
+                    .applications
+                    .map(|application_ids| {
+                        application_ids
+                            .into_iter()
+                            .map(|id| {
+                                id.parse::<RealApplicationId>().map_err(|e| {
+                                    ConversionError::UnexpectedCertificateType(format!(
+                                        "Invalid application_id: {e}"
+                                    ))
+                                })
+                            })
+                            .collect::<Result<Vec<_>, _>>()
+                    })
+                    .transpose()?;
+                Ok(SystemOperation::Sponsor {
+                    owner: sponsor.owner,
+                    beneficiary: sponsor.beneficiary,
+                    allowance: sponsor.allowance,
+                    policy: SponsorshipPolicy {
+                        applications,
+                        system_operations: sponsor.system_operations,
+                    },
+                })
+            }
             _ => Err(ConversionError::UnexpectedCertificateType(format!(
                 "Unknown system operation type: {}",
                 system_op.system_operation_type
@@ -0,0 +1,319 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Names of the wallet's address book in the GraphQL requests of the node service.
+//!
+//! A string argument or variable of the form `@name`, where a chain ID, an account owner or
+//! an application ID is expected, is replaced with the ID that `name` stands for, before the
+//! request is executed. Strings of other types, e.g. descriptions, and strings with unknown
+//! names or names of another kind of ID, are left as they are.
+
+use std::sync::Arc;
+
+use async_graphql::{
+    extensions::{
+        Extension, ExtensionContext, ExtensionFactory, NextParseQuery, NextPrepareRequest,
+    },
+    parser::{
+        parse_query,
+        types::{DocumentOperations, ExecutableDocument, OperationType, Selection, SelectionSet},
+    },
+    registry::{MetaType, MetaTypeName, Registry},
+    Request, ServerResult, Variables,
+};
+use async_graphql_value::{ConstValue, Value};
+use linera_wallet_json::{address_book::AddressKind, AddressBook};
+
+/// A GraphQL extension that resolves the names of an address book.
+#[derive(Clone, Default)]
+pub struct ResolveNames(Arc<AddressBook>);
+
+impl ResolveNames {
+    pub fn new(address_book: AddressBook) -> Self {
+        Self(Arc::new(address_book))
+    }
+
+    /// Returns the ID for a string `@name` of the GraphQL scalar type `type_name`, if `name`
+    /// is in the address book and stands for an ID of that type.
+    fn resolve(&self, string: &str, type_name: &str) -> Option<String> {
+        let kind = match type_name {
+            "ChainId" => AddressKind::Chain,
+            "AccountOwner" => AddressKind::Owner,
+            "ApplicationId" => AddressKind::Application,
+            _ => return None,
+        };
+        let address = self.0.get(string.strip_prefix('@')?)?;
+        (address.kind() == kind).then(|| address.id())
+    }
+
+    fn resolve_const_value(&self, registry: &Registry, value: &mut ConstValue, ty: &str) {
+        match (MetaTypeName::create(ty), value) {
+            (MetaTypeName::NonNull(ty), value) => self.resolve_const_value(registry, value, ty),
+            (MetaTypeName::List(ty), ConstValue::List(values)) => {
+                for value in values {
+                    self.resolve_const_value(registry, value, ty);
+                }
+            }
+            // A single value is accepted where a list is expected.
+            (MetaTypeName::List(ty), value) => self.resolve_const_value(registry, value, ty),
+            (MetaTypeName::Named(type_name), ConstValue::String(string)) => {
+                if let Some(id) = self.resolve(string, type_name) {
+                    *string = id;
+                }
+            }
+            (MetaTypeName::Named(type_name), ConstValue::Object(fields)) => {
+                let Some(MetaType::InputObject { input_fields, .. }) =
+                    registry.types.get(type_name)
+                else {
+                    return;
+                };
+                for (name, value) in fields {
+                    if let Some(field) = input_fields.get(name.as_str()) {
+                        self.resolve_const_value(registry, value, &field.ty);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn resolve_value(&self, registry: &Registry, value: &mut Value, ty: &str) {
+        match (MetaTypeName::create(ty), value) {
+            (MetaTypeName::NonNull(ty), value) => self.resolve_value(registry, value, ty),
+            (MetaTypeName::List(ty), Value::List(values)) => {
+                for value in values {
+                    self.resolve_value(registry, value, ty);
+                }
+            }
+            (MetaTypeName::List(ty), value) => self.resolve_value(registry, value, ty),
+            (MetaTypeName::Named(type_name), Value::String(string)) => {
+                if let Some(id) = self.resolve(string, type_name) {
+                    *string = id;
+                }
+            }
+            (MetaTypeName::Named(type_name), Value::Object(fields)) => {
+                let Some(MetaType::InputObject { input_fields, .. }) =
+                    registry.types.get(type_name)
+                else {
+                    return;
+                };
+                for (name, value) in fields {
+                    if let Some(field) = input_fields.get(name.as_str()) {
+                        self.resolve_value(registry, value, &field.ty);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Resolves the names in the arguments of the fields selected on the type `type_name`.
+    fn resolve_selection_set(
+        &self,
+        registry: &Registry,
+        selection_set: &mut SelectionSet,
+        type_name: &str,
+    ) {
+        for selection in &mut selection_set.items {
+            match &mut selection.node {
+                Selection::Field(field) => {
+                    let Some(meta_field) = registry
+                        .types
+                        .get(type_name)
+                        .and_then(|ty| ty.field_by_name(&field.node.name.node))
+                    else {
+                        continue;
+                    };
+                    for (name, value) in &mut field.node.arguments {
+                        if let Some(argument) = meta_field.args.get(name.node.as_str()) {
+                            self.resolve_value(registry, &mut value.node, &argument.ty);
+                        }
+                    }
+                    self.resolve_selection_set(
+                        registry,
+                        &mut field.node.selection_set.node,
+                        MetaTypeName::concrete_typename(&meta_field.ty),
+                    );
+                }
+                Selection::InlineFragment(fragment) => {
+                    let type_name = match &fragment.node.type_condition {
+                        Some(condition) => condition.node.on.node.as_str(),
+                        None => type_name,
+                    };
+                    self.resolve_selection_set(
+                        registry,
+                        &mut fragment.node.selection_set.node,
+                        type_name,
+                    );
+                }
+                Selection::FragmentSpread(_) => {}
+            }
+        }
+    }
+}
+
+impl ExtensionFactory for ResolveNames {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(self.clone())
+    }
+}
+
+#[async_trait::async_trait]
+impl Extension for ResolveNames {
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        mut request: Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<Request> {
+        // The variables are resolved according to their declared types. If the query is
+        // invalid, the error is reported when it is parsed again.
+        if let Ok(document) = parse_query(&request.query) {
+            let registry = &ctx.schema_env.registry;
+            for (_, operation) in document.operations.iter() {
+                for variable in &operation.node.variable_definitions {
+                    let variable = &variable.node;
+                    if let Some(value) = request.variables.get_mut(&variable.name.node) {
+                        let ty = variable.var_type.node.to_string();
+                        self.resolve_const_value(registry, value, &ty);
+                    }
+                }
+            }
+        }
+        next.run(ctx, request).await
+    }
+
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let mut document = next.run(ctx, query, variables).await?;
+        let registry = &ctx.schema_env.registry;
+        let operations = match &mut document.operations {
+            DocumentOperations::Single(operation) => vec![operation],
+            DocumentOperations::Multiple(operations) => operations.values_mut().collect(),
+        };
+        for operation in operations {
+            for variable in &mut operation.node.variable_definitions {
+                let variable = &mut variable.node;
+                if let Some(value) = &mut variable.default_value {
+                    let ty = variable.var_type.node.to_string();
+                    self.resolve_const_value(registry, &mut value.node, &ty);
+                }
+            }
+            let root_type = match operation.node.ty {
+                OperationType::Query => Some(&registry.query_type),
+                OperationType::Mutation => registry.mutation_type.as_ref(),
+                OperationType::Subscription => registry.subscription_type.as_ref(),
+            };
+            if let Some(root_type) = root_type {
+                self.resolve_selection_set(
+                    registry,
+                    &mut operation.node.selection_set.node,
+                    root_type,
+                );
+            }
+        }
+        for fragment in document.fragments.values_mut() {
+            let fragment = &mut fragment.node;
+            self.resolve_selection_set(
+                registry,
+                &mut fragment.selection_set.node,
+                fragment.type_condition.node.on.node.as_str(),
+            );
+        }
+        Ok(document)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::{
+        EmptyMutation, EmptySubscription, InputObject, Object, Request, Schema, Variables,
+    };
+    use linera_base::{
+        crypto::CryptoHash,
+        identifiers::{AccountOwner, ChainId},
+    };
+    use linera_wallet_json::{address_book::Address, AddressBook};
+
+    use super::ResolveNames;
+
+    #[derive(InputObject)]
+    struct Payment {
+        recipient: ChainId,
+        memo: String,
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn chains(&self, chain_ids: Vec<ChainId>) -> Vec<String> {
+            chain_ids.iter().map(ChainId::to_string).collect()
+        }
+
+        async fn owner(&self, owner: AccountOwner) -> String {
+            owner.to_string()
+        }
+
+        async fn echo(&self, text: String) -> String {
+            text
+        }
+
+        async fn pay(&self, payment: Payment) -> String {
+            format!("{}: {}", payment.recipient, payment.memo)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_names() -> anyhow::Result<()> {
+        let treasury = ChainId(CryptoHash::test_hash("treasury"));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let mut address_book = AddressBook::default();
+        address_book.insert("treasury".to_string(), Address::Chain(treasury))?;
+        address_book.insert("alice".to_string(), Address::Owner(alice))?;
+        let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+            .extension(ResolveNames::new(address_book))
+            .finish();
+
+        let response = schema
+            .execute(
+                Request::new(
+                    r#"query($owner: AccountOwner!, $payment: Payment!) {
+                        chains(chainIds: ["@treasury"])
+                        owner(owner: $owner)
+                        echo(text: "@treasury")
+                        literal: pay(payment: { recipient: "@treasury", memo: "@alice" })
+                        variable: pay(payment: $payment)
+                    }"#,
+                )
+                .variables(Variables::from_json(serde_json::json!({
+                    "owner": "@alice",
+                    "payment": { "recipient": "@treasury", "memo": "@treasury" },
+                }))),
+            )
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json()?,
+            serde_json::json!({
+                "chains": [treasury.to_string()],
+                "owner": alice.to_string(),
+                "echo": "@treasury",
+                "literal": format!("{treasury}: @alice"),
+                "variable": format!("{treasury}: @treasury"),
+            })
+        );
+
+        // A name is only resolved where its kind of ID is expected.
+        let response = schema
+            .execute(Request::new(r#"{ owner(owner: "@treasury") }"#))
+            .await;
+        assert_eq!(response.errors.len(), 1);
+        Ok(())
+    }
+}
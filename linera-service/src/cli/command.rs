@@ -5,7 +5,7 @@ use std::{borrow::Cow, num::NonZeroU16, path::PathBuf};
 
 use chrono::{DateTime, Utc};
 use linera_base::{
-    crypto::{AccountPublicKey, CryptoHash, ValidatorPublicKey},
+    crypto::{hd::HdKeyScheme, AccountPublicKey, CryptoHash, ValidatorPublicKey},
     data_types::{Amount, BlockHeight, Epoch, Timestamp},
     identifiers::{Account, AccountOwner, ApplicationId, ChainId, ModuleId, StreamId},
     time::Duration,
@@ -14,11 +14,13 @@ use linera_base::{
 use linera_client::{
     chain_listener::ChainListenerConfig,
     client_options::{
-        ApplicationPermissionsConfig, ChainOwnershipConfig, ResourceControlPolicyConfig,
+        ApplicationPermissionsConfig, ChainOwnershipConfig, InboxPolicyConfig,
+        ResourceControlPolicyConfig,
     },
     util,
 };
 use linera_rpc::config::CrossChainConfig;
+use linera_wallet_json::address_book::AddressKind;
 
 use crate::{
     cli::validator, query_subscription::parse_subscription_ttl, task_processor::parse_operator,
@@ -337,9 +339,57 @@ pub struct ResourceControlPolicyOverrides {
     #[arg(long, value_delimiter = ',')]
     pub http_request_allow_list: Option<Vec<String>>,
 
+    /// Set the rules restricting HTTP requests per host, as a JSON object mapping hosts to
+    /// rules, e.g. `{"api.example.com": {"schemes": ["https"], "port": 443,
+    /// "path_prefixes": ["/v1/"], "methods": ["GET"], "maximum_response_bytes": 10000,
+    /// "maximum_requests_per_block": 5}}`. All rule fields are optional.
+    #[arg(long)]
+    pub http_request_rules: Option<String>,
+
     /// Set the list of application IDs for which message- and event-related fees are waived.
     #[arg(long, value_delimiter = ',')]
     pub free_application_ids: Option<Vec<String>>,
+
+    /// Set the maximum number of nested contract calls, including the application executing
+    /// the operation or message.
+    #[arg(long)]
+    pub maximum_call_depth: Option<u32>,
+    /// Set whether the state hash of a block commits to the Merkle roots of the balances and
+    /// of the application states, so that their entries can be proven under it.
+    #[arg(long)]
+    pub commit_state_roots: Option<bool>,
+}
+
+/// Options for executing the rows of a manifest in batches.
+#[derive(Clone, clap::Args)]
+pub struct BatchOptions {
+    /// The manifest, as a `.csv` file, optionally starting with a header line, or as a
+    /// `.json` file with an array of objects.
+    #[arg(long)]
+    pub input: PathBuf,
+
+    /// The file recording which rows were committed, used to resume an interrupted batch.
+    /// Defaults to the input path with the extension `.progress.json`.
+    #[arg(long)]
+    pub progress: Option<PathBuf>,
+
+    /// The file to write the report to, with the block height and certificate hash of each
+    /// row, as CSV. If not specified, the report is printed.
+    #[arg(long)]
+    pub report: Option<PathBuf>,
+
+    /// The maximum number of rows to execute in a single block.
+    #[arg(long, default_value = "100")]
+    pub max_operations_per_block: usize,
+}
+
+impl BatchOptions {
+    /// Returns the path of the progress file.
+    pub fn progress_path(&self) -> PathBuf {
+        self.progress
+            .clone()
+            .unwrap_or_else(|| self.input.with_extension("progress.json"))
+    }
 }
 
 #[derive(Clone, clap::Subcommand)]
@@ -358,6 +408,47 @@ pub enum ClientCommand {
         amount: Amount,
     },
 
+    /// Let an account pay the fees of another owner's blocks on the same chain.
+    ///
+    /// A block of the beneficiary is sponsored if all its operations are covered. The
+    /// sponsor pays at most the allowance in total, and must be the owner signing this
+    /// command's block.
+    Sponsor {
+        /// The sponsoring account, formatted as `owner@chain`
+        #[arg(long = "from")]
+        sponsor: Account,
+
+        /// The owner whose blocks are sponsored
+        #[arg(long)]
+        beneficiary: AccountOwner,
+
+        /// The most that the sponsor pays in total. Zero ends the sponsorship.
+        allowance: Amount,
+
+        /// The applications whose operations are covered. All applications are covered if
+        /// none is given.
+        #[arg(long = "application")]
+        applications: Option<Vec<ApplicationId>>,
+
+        /// Also cover system operations, such as transfers
+        #[arg(long)]
+        system_operations: bool,
+    },
+
+    /// Transfer funds to many recipients, packing the transfers into as few blocks as the
+    /// resource control policy allows.
+    ///
+    /// The manifest has the columns `recipient` and `amount`. An interrupted batch can be
+    /// resumed by running the same command again.
+    TransferBatch {
+        /// Sending account (must be one of our chains)
+        #[arg(long = "from")]
+        sender: Account,
+
+        #[command(flatten)]
+        batch: BatchOptions,
+    },
+
     /// Open (i.e. activate) a new chain deriving the UID from an existing one.
     OpenChain {
         /// Chain ID (must be one of our chains).
@@ -715,10 +806,25 @@ pub enum ClientCommand {
         #[arg(long, value_delimiter = ',')]
         http_request_allow_list: Option<Vec<String>>,
 
+        /// Set the rules restricting HTTP requests per host, as a JSON object mapping hosts
+        /// to rules. See `linera resource-control-policy --help` for the format.
+        #[arg(long)]
+        http_request_rules: Option<String>,
+
         /// Set the list of application IDs for which message- and event-related fees are waived.
         #[arg(long, value_delimiter = ',')]
         free_application_ids: Option<Vec<String>>,
 
+        /// Set the maximum number of nested contract calls, including the application
+        /// executing the operation or message.
+        #[arg(long)]
+        maximum_call_depth: Option<u32>,
+
+        /// Set whether the state hash of a block commits to the Merkle roots of the balances
+        /// and of the application states, so that their entries can be proven under it.
+        #[arg(long)]
+        commit_state_roots: Option<bool>,
+
         /// Force this wallet to generate keys using a PRNG and a given seed. USE FOR
         /// TESTING ONLY.
         #[arg(long)]
@@ -941,6 +1047,32 @@ pub enum ClientCommand {
         /// The list of required dependencies of application, if any.
         #[arg(long, num_args(0..))]
         required_application_ids: Option<Vec<ApplicationId>>,
+
+        /// Make the application upgradable by this owner, using `upgrade-application` on
+        /// the creator chain.
+        #[arg(long)]
+        upgrade_authority: Option<AccountOwner>,
+
+        /// Let the application be called while it is already executing. Each reentrant call
+        /// is executed by a separate instance of the contract.
+        #[arg(long)]
+        allow_reentrancy: bool,
+    },
+
+    /// Replace the module of an upgradable application.
+    ///
+    /// Every chain that uses the application adopts the new module and runs its migration
+    /// in its next block.
+    UpgradeApplication {
+        /// The application to upgrade.
+        application_id: ApplicationId,
+
+        /// The module ID of the new application code.
+        module_id: ModuleId,
+
+        /// The chain where the application was created. The default chain of the wallet
+        /// is used otherwise.
+        chain_id: Option<ChainId>,
     },
 
     /// Create an application, and publish the required module.
@@ -981,7 +1113,12 @@ pub enum ClientCommand {
     },
 
     /// Create an unassigned key pair.
-    Keygen,
+    Keygen {
+        /// Derive the next key of this scheme from the keystore's mnemonic. Without this
+        /// option, a key of the default scheme is created.
+        #[arg(long, value_enum)]
+        scheme: Option<HdKeyScheme>,
+    },
 
     /// Link the owner to the chain.
     /// Expects that the caller has a private key corresponding to the `public_key`,
@@ -1022,6 +1159,21 @@ pub enum ClientCommand {
         chain_id: Option<ChainId>,
     },
 
+    /// Execute many raw user operations, packing them into as few blocks as the resource
+    /// control policy allows.
+    ///
+    /// The manifest has the columns `application_id` and `operation`, the latter containing
+    /// the BCS-encoded operation as a hex string. An interrupted batch can be resumed by
+    /// running the same command again.
+    ExecuteOperationBatch {
+        /// Chain ID to submit the operations on. Defaults to the wallet's default chain.
+        #[arg(long)]
+        chain_id: Option<ChainId>,
+
+        #[command(flatten)]
+        batch: BatchOptions,
+    },
+
     /// Show the contents of the wallet.
     #[command(subcommand)]
     Wallet(WalletCommand),
@@ -1030,6 +1182,14 @@ pub enum ClientCommand {
     #[command(subcommand)]
     Chain(ChainCommand),
 
+    /// Prepare, sign and submit block proposals whose signing key is kept offline.
+    #[command(subcommand)]
+    Proposal(ProposalCommand),
+
+    /// Publish, review and propose draft blocks on chains with several owners.
+    #[command(subcommand)]
+    Draft(DraftCommand),
+
     /// Manage Linera projects.
     #[command(subcommand)]
     Project(ProjectCommand),
@@ -1079,6 +1239,7 @@ impl ClientCommand {
     pub fn log_file_name(&self) -> Cow<'static, str> {
         match self {
             ClientCommand::Transfer { .. }
+            | ClientCommand::Sponsor { .. }
             | ClientCommand::OpenChain { .. }
             | ClientCommand::OpenMultiOwnerChain { .. }
             | ClientCommand::ShowOwnership { .. }
@@ -1101,15 +1262,20 @@ impl ClientCommand {
             | ClientCommand::PublishDataBlob { .. }
             | ClientCommand::ReadDataBlob { .. }
             | ClientCommand::CreateApplication { .. }
+            | ClientCommand::UpgradeApplication { .. }
             | ClientCommand::PublishAndCreate { .. }
-            | ClientCommand::Keygen
+            | ClientCommand::Keygen { .. }
             | ClientCommand::Assign { .. }
             | ClientCommand::Wallet { .. }
             | ClientCommand::Chain { .. }
+            | ClientCommand::Proposal { .. }
+            | ClientCommand::Draft { .. }
             | ClientCommand::Validator { .. }
             | ClientCommand::RetryPendingBlock { .. }
             | ClientCommand::QueryApplication { .. } => "client".into(),
-            ClientCommand::ExecuteOperation { .. } => "client".into(),
+            ClientCommand::ExecuteOperation { .. }
+            | ClientCommand::TransferBatch { .. }
+            | ClientCommand::ExecuteOperationBatch { .. } => "client".into(),
             ClientCommand::Benchmark(BenchmarkCommand::Single { .. }) => "single-benchmark".into(),
             ClientCommand::Benchmark(BenchmarkCommand::Multi { .. }) => "multi-benchmark".into(),
             ClientCommand::Net { .. } => "net".into(),
@@ -1230,7 +1396,10 @@ pub enum NetCommand {
         #[arg(long)]
         path: Option<String>,
 
-        /// External protocol used, either `grpc` or `grpcs`.
+        /// External protocol used: `grpc`, `grpcs`, `tcp`, `udp` or `quic`.
+        ///
+        /// The simple protocols (`tcp`, `udp` and `quic`) are also used between the proxy and the
+        /// shards.
         #[arg(long, default_value = "grpc")]
         external_protocol: String,
 
@@ -1320,8 +1489,34 @@ pub enum WalletCommand {
 
         /// Force this wallet to generate keys using a PRNG and a given seed. USE FOR
         /// TESTING ONLY.
-        #[arg(long)]
+        #[arg(long, conflicts_with_all = ["with_mnemonic", "from_mnemonic"])]
         testing_prng_seed: Option<u64>,
+
+        /// Derive the keys of this wallet from a new BIP-39 mnemonic, which is printed once.
+        /// Backing up the mnemonic, and the passphrase if any, is enough to recover the keys
+        /// with `--from-mnemonic`.
+        #[arg(long, conflicts_with = "from_mnemonic", group = "mnemonic")]
+        with_mnemonic: bool,
+
+        /// Recover the keys of a wallet from its BIP-39 mnemonic, and re-discover the chains
+        /// that they own in the genesis configuration and among the faucet's claims, as well
+        /// as the chains that these opened later and that the keys now own. The
+        /// mnemonic is read from the `LINERA_MNEMONIC` environment variable if set, or else
+        /// from the standard input.
+        #[arg(long, group = "mnemonic")]
+        from_mnemonic: bool,
+
+        /// Protect the mnemonic with a BIP-39 passphrase, which is then needed together with
+        /// the mnemonic to recover the keys. The passphrase is read from the
+        /// `LINERA_MNEMONIC_PASSPHRASE` environment variable if set, or else from the standard
+        /// input.
+        #[arg(long, requires = "mnemonic")]
+        mnemonic_passphrase: bool,
+
+        /// When recovering from a mnemonic, the number of consecutive unused keys of each
+        /// scheme after which the search for owned chains stops.
+        #[arg(long, default_value = "20", requires = "from_mnemonic")]
+        recovery_gap: u32,
     },
 
     /// Request a new chain from a faucet and add it to the wallet.
@@ -1349,6 +1544,55 @@ pub enum WalletCommand {
         faucet: Option<String>,
     },
 
+    /// Export chains of the wallet to a portable bundle, e.g. to use them on another machine.
+    ///
+    /// The bundle references the genesis configuration of the network. With `--with-keys`, it
+    /// also contains the keys of the chains' owners, encrypted with a passphrase that is read
+    /// from the `LINERA_WALLET_PASSPHRASE` environment variable if set, or else from the
+    /// standard input.
+    Export {
+        /// Path to save the bundle.
+        output: PathBuf,
+
+        /// The chains to export. By default, all the chains of the wallet are exported.
+        #[arg(long = "chain")]
+        chain_ids: Vec<ChainId>,
+
+        /// Include the encrypted keys of the chains' owners.
+        #[arg(long)]
+        with_keys: bool,
+    },
+
+    /// Initialize a wallet from a bundle created with `linera wallet export`.
+    ///
+    /// The genesis configuration must be the one of the exported wallet. If the bundle
+    /// contains keys, the passphrase is read from the `LINERA_WALLET_PASSPHRASE` environment
+    /// variable if set, or else from the standard input.
+    Import {
+        /// Path to the bundle.
+        bundle: PathBuf,
+
+        /// The path to the genesis configuration for a Linera deployment. Either this or
+        /// `--faucet` must be specified.
+        ///
+        /// Overrides `--faucet` if provided.
+        #[arg(long = "genesis")]
+        genesis_config_path: Option<PathBuf>,
+
+        /// The address of a faucet.
+        #[arg(long, env = "LINERA_FAUCET_URL")]
+        faucet: Option<String>,
+    },
+
+    /// Merge a bundle created with `linera wallet export` into the wallet.
+    ///
+    /// Chains that the wallet already has keep their owner, and are updated if the bundle has
+    /// a more recent state. Chains whose owner's key is not available are followed only.
+    Merge {
+        /// Path to the bundle.
+        bundle: PathBuf,
+    },
+
     /// Add a new followed chain (i.e. a chain without keypair) to the wallet.
     FollowChain {
         /// The chain ID.
@@ -1364,6 +1608,69 @@ pub enum WalletCommand {
 
     /// Forgets the specified chain, including the associated key pair.
     ForgetChain { chain_id: ChainId },
+
+    /// Manage the names of chains, owners and applications in the wallet's address book.
+    ///
+    /// Names can be used instead of IDs in the arguments of all commands, and as `@name` in
+    /// the GraphQL arguments of `linera service`.
+    #[command(subcommand)]
+    Alias(AliasCommand),
+
+    /// Sets how the chain listener of `linera service` processes the specified chain's inbox.
+    ///
+    /// Options that are not given fall back to the listener's configuration. If any message
+    /// policy option is given, the resulting policy replaces the client's message policy for
+    /// this chain. Without any options, the chain's policy is removed.
+    SetInboxPolicy {
+        /// The chain ID.
+        chain_id: ChainId,
+
+        #[command(flatten)]
+        policy: Box<InboxPolicyConfig>,
+    },
+}
+
+#[derive(Clone, clap::Subcommand)]
+pub enum AliasCommand {
+    /// Give a name to a chain, an owner or an application. A previous meaning of the name
+    /// is replaced.
+    Set {
+        /// The name. It must start with a letter, and only contain letters, digits, `-`, `_`
+        /// and `.`.
+        name: String,
+
+        /// The ID that the name stands for.
+        id: String,
+
+        /// What kind of ID the name stands for: `chain`, `owner` or `application`. This is
+        /// required unless the ID can only be of one kind, e.g. an owner: chain and
+        /// application IDs have the same form.
+        #[arg(long)]
+        kind: Option<AddressKind>,
+    },
+
+    /// Remove a name from the address book.
+    Remove { name: String },
+
+    /// List the names of the address book, with the kinds and IDs they stand for.
+    List,
+
+    /// Add the names published by a registry application to the address book.
+    ///
+    /// The registry's service must answer the GraphQL query `entries { name kind id }`,
+    /// where `kind` is `chain`, `owner` or `application`.
+    Sync {
+        /// The registry application.
+        registry: ApplicationId,
+
+        /// The chain to query the registry on. Defaults to the default chain of the wallet.
+        #[arg(long)]
+        chain_id: Option<ChainId>,
+
+        /// Replace the names that already stand for something else in the address book.
+        #[arg(long)]
+        overwrite: bool,
+    },
 }
 
 #[derive(Clone, clap::Subcommand)]
@@ -1375,6 +1682,41 @@ pub enum ChainCommand {
         /// The chain to show the block (if not specified, the default chain from the
         /// wallet is used).
         chain_id: Option<ChainId>,
+        /// Also show the logs emitted by applications while executing the block.
+        #[arg(long)]
+        logs: bool,
+    },
+
+    /// Show the transactions, transfers, messages and events in the blocks of a chain.
+    ///
+    /// The blocks are read from local storage, after synchronizing the chain with the
+    /// validators if they are not all available locally.
+    History {
+        /// The chain to show the history of (if not specified, the default chain from the
+        /// wallet is used).
+        chain_id: Option<ChainId>,
+
+        /// The height of the first block to show.
+        #[arg(long, default_value = "0")]
+        from: BlockHeight,
+
+        /// The height of the last block to show. If not specified, the history goes up to
+        /// the latest block.
+        #[arg(long)]
+        to: Option<BlockHeight>,
+
+        /// Only show the operations, messages and events of this application.
+        #[arg(long)]
+        application: Option<ApplicationId>,
+
+        /// Only show the entries that this owner authenticated, or that transfer tokens
+        /// from or to this owner.
+        #[arg(long)]
+        owner: Option<AccountOwner>,
+
+        /// Print the entries as JSON instead of a table.
+        #[arg(long)]
+        json: bool,
     },
 
     /// Show the chain description of a chain.
@@ -1385,6 +1727,127 @@ pub enum ChainCommand {
     },
 }
 
+#[derive(Clone, clap::Subcommand)]
+pub enum ProposalCommand {
+    /// Prepare a block proposal and write it to a file, unsigned.
+    ///
+    /// The block receives the chain's pending incoming messages, followed by the given
+    /// operations. The proposal can be signed on a host without network access, using
+    /// `linera proposal sign`.
+    Prepare {
+        /// Path to a JSON file containing the list of operations to execute. If not
+        /// specified, the block only receives the pending incoming messages.
+        #[arg(long)]
+        operations: Option<PathBuf>,
+
+        /// The file to write the unsigned proposal to.
+        #[arg(long)]
+        output: PathBuf,
+
+        /// The chain to propose a block on. If not specified, the wallet's default chain is
+        /// used.
+        chain_id: Option<ChainId>,
+    },
+
+    /// Sign a prepared block proposal with a key from the keystore.
+    ///
+    /// The incoming bundles, the operations and the expected block hash are printed first.
+    /// This only reads the keystore and does not access the wallet, the storage or the
+    /// network.
+    Sign {
+        /// The file containing the unsigned proposal.
+        input: PathBuf,
+
+        /// The file to write the signed proposal to.
+        #[arg(long)]
+        output: PathBuf,
+    },
+
+    /// Submit a signed block proposal to the validators.
+    ///
+    /// The proposal is rejected if a block was committed on the chain, or the consensus round
+    /// changed, since the proposal was prepared.
+    Submit {
+        /// The file containing the signed proposal.
+        input: PathBuf,
+    },
+}
+
+#[derive(Clone, clap::Subcommand)]
+pub enum DraftCommand {
+    /// List the drafts published for a chain, with the owners' votes.
+    List {
+        /// The URL of the node service that coordinates the drafts.
+        #[arg(long, env = "LINERA_DRAFT_COORDINATOR")]
+        coordinator: String,
+
+        /// The chain whose drafts to list. If not specified, the wallet's default chain is
+        /// used.
+        chain_id: Option<ChainId>,
+    },
+
+    /// Publish a draft block for the other owners of a chain to review.
+    ///
+    /// The draft is signed with the chain's owner key from the wallet, which counts as the
+    /// author's approval. Prints the ID of the draft.
+    Publish {
+        /// The URL of the node service that coordinates the drafts.
+        #[arg(long, env = "LINERA_DRAFT_COORDINATOR")]
+        coordinator: String,
+
+        /// Path to a JSON file containing the list of operations to execute.
+        #[arg(long)]
+        operations: PathBuf,
+
+        /// A description of the draft's purpose, for the reviewers.
+        #[arg(long, default_value = "")]
+        description: String,
+
+        /// The chain to execute the operations on. If not specified, the wallet's default
+        /// chain is used.
+        chain_id: Option<ChainId>,
+    },
+
+    /// Approve a draft, signing the vote with the chain's owner key from the wallet.
+    ///
+    /// The draft's operations are printed before the vote is signed.
+    Approve {
+        /// The URL of the node service that coordinates the drafts.
+        #[arg(long, env = "LINERA_DRAFT_COORDINATOR")]
+        coordinator: String,
+
+        /// The ID of the draft.
+        draft_id: CryptoHash,
+    },
+
+    /// Reject a draft, signing the vote with the chain's owner key from the wallet.
+    ///
+    /// The draft's operations are printed before the vote is signed.
+    Reject {
+        /// The URL of the node service that coordinates the drafts.
+        #[arg(long, env = "LINERA_DRAFT_COORDINATOR")]
+        coordinator: String,
+
+        /// The ID of the draft.
+        draft_id: CryptoHash,
+    },
+
+    /// Propose an approved draft as a block on its chain.
+    ///
+    /// The owners' signatures and weights are checked against the chain's current ownership
+    /// before the operations are executed. The wallet's owner must have approved the draft,
+    /// and proposes it in a single-leader round that it leads, waiting for its turn if
+    /// needed. Prints the hash of the block's certificate.
+    Propose {
+        /// The URL of the node service that coordinates the drafts.
+        #[arg(long, env = "LINERA_DRAFT_COORDINATOR")]
+        coordinator: String,
+
+        /// The ID of the draft.
+        draft_id: CryptoHash,
+    },
+}
+
 #[derive(Clone, clap::Parser)]
 pub enum ProjectCommand {
     /// Create a new Linera project.
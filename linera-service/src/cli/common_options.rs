@@ -6,6 +6,7 @@
 use std::{env, path::PathBuf};
 
 use anyhow::{bail, Error};
+use linera_base::crypto::hd::HdSeed;
 use linera_client::config::GenesisConfig;
 use linera_execution::WasmRuntime;
 
@@ -133,4 +134,18 @@ impl CommonCliOptions {
             testing_prng_seed,
         )?)
     }
+
+    pub fn create_keystore_from_seed(
+        &self,
+        seed: HdSeed,
+    ) -> Result<linera_wallet_json::Keystore, Error> {
+        let keystore_path = self.keystore_path()?;
+        if keystore_path.exists() {
+            bail!("Keystore already exists: {}", keystore_path.display());
+        }
+        Ok(linera_wallet_json::Keystore::create_from_seed(
+            &keystore_path,
+            seed,
+        )?)
+    }
 }
@@ -22,8 +22,9 @@ pub static MALLOC_CONF: &[u8] = b"prof:true,prof_active:false,lg_prof_sample:19\
 
 mod options;
 use std::{
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, VecDeque},
     env,
+    io::IsTerminal as _,
     path::PathBuf,
     process,
     sync::Arc,
@@ -36,7 +37,10 @@ use clap_complete::generate;
 use colored::Colorize;
 use futures::{lock::Mutex, FutureExt as _, StreamExt as _};
 use linera_base::{
-    crypto::Signer,
+    crypto::{
+        hd::{HdKeyScheme, HdSeed, NUM_KEY_INDICES},
+        CryptoRng, Signer,
+    },
     data_types::{ApplicationPermissions, TimeDelta, Timestamp},
     identifiers::{AccountOwner, ChainId},
     listen_for_shutdown_signals,
@@ -44,22 +48,34 @@ use linera_base::{
     time::{Duration, Instant},
 };
 use linera_client::{
+    batch::{Batch, ManifestFormat},
     benchmark::{
         BenchmarkConfig, FungibleTransferGenerator, NativeFungibleTransferGenerator,
         OperationGenerator,
     },
+    chain_history::HistoryFilter,
     chain_listener::{ChainListener, ChainListenerConfig, ClientContext as _},
     config::{CommitteeConfig, GenesisConfig},
+    drafts::{self, DraftCoordinator, DraftVote, Drafts, SignedDraftVote},
+    scheduler::Scheduler,
+    ClientContext,
 };
 use linera_core::{
-    client::{chain_client, ListeningMode},
+    client::{
+        chain_client::{self, SignedProposal, UnsignedProposal},
+        ListeningMode,
+    },
     data_types::ClientOutcome,
     node::{ValidatorNode, ValidatorNodeProvider},
     wallet,
     worker::Reason,
     JoinSetExt as _, LocalNodeError,
 };
-use linera_execution::{committee::Committee, Operation};
+use linera_execution::{
+    committee::Committee,
+    system::{ApplicationOptions, SponsorshipPolicy},
+    HttpRequestRule, Operation,
+};
 use linera_faucet_server::{FaucetConfig, FaucetService};
 #[cfg(with_metrics)]
 use linera_metrics::monitoring_server;
@@ -67,8 +83,9 @@ use linera_persistent::{self as persistent, Persist as _};
 use linera_service::{
     cli::{
         command::{
-            BenchmarkCommand, BenchmarkOptions, ChainCommand, ClientCommand, DatabaseToolCommand,
-            NetCommand, ProjectCommand, ResourceControlPolicyOverrides, WalletCommand,
+            AliasCommand, BatchOptions, BenchmarkCommand, BenchmarkOptions, ChainCommand,
+            ClientCommand, DatabaseToolCommand, DraftCommand, NetCommand, ProjectCommand,
+            ProposalCommand, ResourceControlPolicyOverrides, WalletCommand,
         },
         net_up_utils,
     },
@@ -82,6 +99,9 @@ use linera_service::{
 };
 use linera_storage::{DbStorage, Storage};
 use linera_views::store::{KeyValueDatabase, KeyValueStore};
+use linera_wallet_json::{
+    address_book::Address, bundle::DEFAULT_KEY_DERIVATION_ITERATIONS, WalletBundle,
+};
 use options::Options;
 use serde_json::Value;
 use tempfile::NamedTempFile;
@@ -97,6 +117,36 @@ use tracing::{debug, error, info, warn, Instrument as _};
 
 struct Job(Options);
 
+/// Executes a batch, resuming from its progress file, and writes the report.
+async fn execute_batch<Env: linera_core::Environment>(
+    context: &mut ClientContext<Env>,
+    batch: &Batch,
+    options: &BatchOptions,
+) -> anyhow::Result<()> {
+    let start_time = Instant::now();
+    let progress_path = options.progress_path();
+    info!(
+        "Executing {} rows on chain {}, recording the progress in {}",
+        batch.operations.len(),
+        batch.chain_id,
+        progress_path.display()
+    );
+    let progress = context
+        .execute_batch(batch, options.max_operations_per_block, &progress_path)
+        .await
+        .context("Failed to execute the batch")?;
+    info!(
+        "Batch executed in {} block(s) in {} ms",
+        progress.blocks.len(),
+        start_time.elapsed().as_millis()
+    );
+    match &options.report {
+        Some(path) => fs_err::write(path, progress.report())?,
+        None => print!("{}", progress.report()),
+    }
+    Ok(())
+}
+
 /// Check if an error is retryable (HTTP 502, 503, 504, timeouts, connection errors)
 fn is_retryable_error(err: &anyhow::Error) -> bool {
     // Check for reqwest errors in the error chain
@@ -152,6 +202,353 @@ fn read_json(string: Option<String>, path: Option<PathBuf>) -> anyhow::Result<Ve
     Ok(serde_json::to_vec(&value)?)
 }
 
+/// Reads a BIP-39 mnemonic from the `LINERA_MNEMONIC` environment variable, or else from the
+/// standard input, so that it does not end up in the shell history.
+fn read_mnemonic() -> anyhow::Result<String> {
+    if let Ok(mnemonic) = env::var("LINERA_MNEMONIC") {
+        return Ok(mnemonic);
+    }
+    eprintln!("Enter the mnemonic of the wallet:");
+    let mut mnemonic = String::new();
+    std::io::stdin()
+        .read_line(&mut mnemonic)
+        .context("Failed to read the mnemonic")?;
+    Ok(mnemonic.trim().to_string())
+}
+
+/// Prints operations in full, so that they can be reviewed before they are signed.
+fn print_operations<'a>(operations: impl IntoIterator<Item = &'a Operation>) {
+    for (index, operation) in operations.into_iter().enumerate() {
+        println!("  Operation {index}: {operation:?}");
+    }
+}
+
+/// An entry of a registry application, as returned by its service.
+#[derive(serde::Deserialize)]
+struct RegistryEntry {
+    name: String,
+    kind: String,
+    id: String,
+}
+
+/// Reads the genesis configuration from a file, or else obtains it from a faucet.
+async fn read_genesis_config(
+    genesis_config_path: Option<&PathBuf>,
+    faucet: Option<&String>,
+) -> anyhow::Result<GenesisConfig> {
+    let genesis_config = match (genesis_config_path, faucet) {
+        (None, None) => bail!("please specify one of `--faucet` or `--genesis`."),
+        (Some(genesis_config_path), _) => util::read_json(genesis_config_path)?,
+        (None, Some(url)) => {
+            let faucet = cli_wrappers::Faucet::new(url.clone());
+            let version_info = faucet
+                .version_info()
+                .await
+                .context("Failed to obtain version information from the faucet")?;
+            if !version_info.is_compatible_with(&linera_version::VERSION_INFO) {
+                warn!(
+                    "\
+Make sure to use a Linera client compatible with this network.
+--- Faucet info ---\
+{}\
+-------------------
+--- This binary ---\
+{}\
+-------------------",
+                    version_info,
+                    linera_version::VERSION_INFO,
+                );
+            }
+            faucet
+                .genesis_config()
+                .await
+                .context("Failed to obtain the genesis configuration from the faucet")?
+        }
+    };
+    Ok(genesis_config)
+}
+
+/// Reads a passphrase from the given environment variable, or else from the terminal without
+/// echoing it, or from the standard input if it is not a terminal.
+fn read_secret(variable: &str, prompt: &str) -> anyhow::Result<String> {
+    if let Ok(passphrase) = env::var(variable) {
+        return Ok(passphrase);
+    }
+    eprintln!("{prompt}");
+    let terminal = console::Term::stderr();
+    if terminal.is_term() && std::io::stdin().is_terminal() {
+        return terminal
+            .read_secure_line()
+            .context("Failed to read the passphrase");
+    }
+    let mut passphrase = String::new();
+    std::io::stdin()
+        .read_line(&mut passphrase)
+        .context("Failed to read the passphrase")?;
+    Ok(passphrase.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Reads the passphrase of a wallet bundle from the `LINERA_WALLET_PASSPHRASE` environment
+/// variable, or else from the standard input.
+fn read_passphrase() -> anyhow::Result<String> {
+    read_secret(
+        "LINERA_WALLET_PASSPHRASE",
+        "Enter the passphrase of the wallet bundle:",
+    )
+}
+
+/// Reads the BIP-39 passphrase of a mnemonic from the `LINERA_MNEMONIC_PASSPHRASE`
+/// environment variable, or else from the standard input.
+fn read_mnemonic_passphrase() -> anyhow::Result<String> {
+    read_secret(
+        "LINERA_MNEMONIC_PASSPHRASE",
+        "Enter the BIP-39 passphrase of the mnemonic:",
+    )
+}
+
+/// Adds the keys of a wallet bundle to the keystore and merges its chains into the wallet.
+async fn merge_wallet_bundle(
+    bundle: &WalletBundle,
+    keystore: &mut linera_wallet_json::Keystore,
+    wallet: &linera_service::Wallet,
+) -> anyhow::Result<()> {
+    if bundle.keys.is_some() {
+        let passphrase = read_passphrase()?;
+        for secret in bundle.decrypt_keys(&passphrase)? {
+            keystore.add_key(secret);
+        }
+        keystore.save().await?;
+    }
+    let owners_with_keys = keystore
+        .secret_keys(&bundle.owners())
+        .iter()
+        .map(|secret| AccountOwner::from(secret.public()))
+        .collect();
+    let report = wallet.merge(bundle, &owners_with_keys)?;
+    for chain_id in &report.conflicts {
+        warn!("Chain {chain_id} has a different owner in the bundle; keeping the wallet's owner");
+    }
+    for chain_id in &report.without_keys {
+        warn!("The bundle's owner of chain {chain_id} has no key in the keystore");
+    }
+    info!(
+        "Added {} chain(s), updated {}, left {} unchanged",
+        report.added.len(),
+        report.updated.len(),
+        report.unchanged.len()
+    );
+    Ok(())
+}
+
+/// Derives the keys of each scheme from the seed, and adds the chains they own to the wallet:
+/// genesis chains, and chains that the faucet created for them. The search for each scheme
+/// stops after `gap` consecutive keys without chains. Only the keys that own a chain are added
+/// to the keystore. The chains that these opened later are found with
+/// [`discover_opened_chains`], once the storage is initialized.
+///
+/// Returns the number of chains found.
+async fn recover_chains_from_seed(
+    seed: &HdSeed,
+    keystore: &mut linera_wallet_json::Keystore,
+    wallet: &mut linera_service::Wallet,
+    faucet: Option<&cli_wrappers::Faucet>,
+    gap: u32,
+) -> anyhow::Result<usize> {
+    let genesis_chains = wallet.genesis_config().chains.clone();
+    let mut num_chains = 0;
+    for scheme in HdKeyScheme::ALL {
+        let mut unused = 0;
+        for index in 0..NUM_KEY_INDICES {
+            if unused >= gap {
+                break;
+            }
+            unused += 1;
+            let Ok(secret) = seed.derive(scheme, index) else {
+                continue;
+            };
+            let owner = AccountOwner::from(secret.public());
+            let mut chains = genesis_chains
+                .iter()
+                .filter(|description| description.config().ownership.is_owner(&owner))
+                .map(|description| (description.id(), wallet::Chain::from(description)))
+                .collect::<Vec<_>>();
+            if let Some(faucet) = faucet {
+                if let Some(claim) = faucet.initial_claim(&owner).await? {
+                    chains.push((
+                        claim.chain_id,
+                        wallet::Chain {
+                            timestamp: claim.timestamp,
+                            ..Default::default()
+                        },
+                    ));
+                }
+            }
+            if chains.is_empty() {
+                continue;
+            }
+            unused = 0;
+            keystore.derive_key(scheme, index)?;
+            for (chain_id, chain) in chains {
+                info!("Recovered chain {chain_id} owned by {owner} ({scheme:?} key {index})");
+                wallet.insert(
+                    chain_id,
+                    &wallet::Chain {
+                        owner: Some(owner),
+                        ..chain
+                    },
+                )?;
+                num_chains += 1;
+            }
+        }
+    }
+    Ok(num_chains)
+}
+
+/// The owners of the keys derived from the keystore's seed. As in
+/// [`recover_chains_from_seed`], the keys of each scheme are derived until `gap` consecutive
+/// keys are unused.
+struct DerivedOwners<'a> {
+    keystore: &'a linera_wallet_json::Keystore,
+    gap: u32,
+    /// The owners that are known to be used.
+    used: BTreeSet<AccountOwner>,
+    /// The scheme and index of each derived owner.
+    owners: BTreeMap<AccountOwner, (HdKeyScheme, u32)>,
+    /// The number of indices derived so far for each scheme.
+    num_derived: BTreeMap<HdKeyScheme, u32>,
+}
+
+impl<'a> DerivedOwners<'a> {
+    fn new(
+        keystore: &'a linera_wallet_json::Keystore,
+        gap: u32,
+        used: BTreeSet<AccountOwner>,
+    ) -> Self {
+        let mut derived = DerivedOwners {
+            keystore,
+            gap,
+            used,
+            owners: BTreeMap::new(),
+            num_derived: BTreeMap::new(),
+        };
+        for scheme in HdKeyScheme::ALL {
+            derived.derive_up_to(scheme, gap);
+        }
+        derived
+    }
+
+    /// Returns the scheme and index of the key of `owner`, if it was derived.
+    fn get(&self, owner: &AccountOwner) -> Option<(HdKeyScheme, u32)> {
+        self.owners.get(owner).copied()
+    }
+
+    /// Records that `owner` is used, and derives the next `gap` keys after it.
+    fn mark_used(&mut self, owner: AccountOwner) {
+        if let Some((scheme, index)) = self.get(&owner) {
+            self.used.insert(owner);
+            self.derive_up_to(scheme, index.saturating_add(1).saturating_add(self.gap));
+        }
+    }
+
+    /// Derives the keys of `scheme` with indices below `end`, and further if some of them
+    /// are used.
+    fn derive_up_to(&mut self, scheme: HdKeyScheme, end: u32) {
+        let mut index = self.num_derived.get(&scheme).copied().unwrap_or_default();
+        let mut end = end.min(NUM_KEY_INDICES);
+        while index < end {
+            if let Ok(public_key) = self.keystore.derived_public_key(scheme, index) {
+                let owner = AccountOwner::from(public_key);
+                if self.used.contains(&owner) {
+                    end = end.max(
+                        index
+                            .saturating_add(1)
+                            .saturating_add(self.gap)
+                            .min(NUM_KEY_INDICES),
+                    );
+                }
+                self.owners.insert(owner, (scheme, index));
+            }
+            index += 1;
+        }
+        self.num_derived.insert(scheme, index);
+    }
+}
+
+/// Finds the chains that the given chains opened, directly or through the chains they
+/// opened in turn, and that are now owned by keys derived from the keystore's seed. This
+/// includes chains that were opened for other owners and later transferred to one of these
+/// keys.
+///
+/// Returns each chain found with its owner and the scheme and index of the owner's key.
+async fn discover_opened_chains<S>(
+    context: &JobContext<S>,
+    chain_ids: BTreeMap<ChainId, AccountOwner>,
+    gap: u32,
+) -> anyhow::Result<Vec<(ChainId, AccountOwner, HdKeyScheme, u32)>>
+where
+    S: Storage + Clone + Send + Sync + 'static,
+{
+    let mut derived = DerivedOwners::new(
+        context.client.signer(),
+        gap,
+        chain_ids.values().copied().collect(),
+    );
+    let mut visited = chain_ids.keys().copied().collect::<BTreeSet<_>>();
+    let mut queue = chain_ids.into_keys().collect::<VecDeque<_>>();
+    // The opened chains that no derived key owns so far, with their current owners.
+    let mut unowned = Vec::new();
+    let mut found = Vec::new();
+    loop {
+        while let Some(chain_id) = queue.pop_front() {
+            for description in context.created_chains(chain_id).await? {
+                let new_chain_id = description.id();
+                if !visited.insert(new_chain_id) {
+                    continue;
+                }
+                let chain_client = context.make_chain_client(new_chain_id).await?;
+                match chain_client.synchronize_chain_state(new_chain_id).await {
+                    Ok(info) => {
+                        let owners = info.manager.ownership.all_owners().copied().collect();
+                        unowned.push((new_chain_id, owners));
+                    }
+                    Err(error) => warn!("Could not synchronize chain {new_chain_id}: {error}"),
+                }
+            }
+        }
+        let mut newly_used = Vec::new();
+        unowned.retain(|(chain_id, owners): &(ChainId, Vec<AccountOwner>)| {
+            let Some((owner, (scheme, index))) = owners
+                .iter()
+                .find_map(|owner| Some((*owner, derived.get(owner)?)))
+            else {
+                return true;
+            };
+            info!("Recovered chain {chain_id} owned by {owner} ({scheme:?} key {index})");
+            found.push((*chain_id, owner, scheme, index));
+            queue.push_back(*chain_id);
+            newly_used.push(owner);
+            false
+        });
+        if queue.is_empty() {
+            return Ok(found);
+        }
+        // More keys may be derived now, which may own some of the other chains.
+        for owner in newly_used {
+            derived.mark_used(owner);
+        }
+    }
+}
+
+/// The client context of a command.
+type JobContext<S> = ClientContext<
+    linera_core::environment::Impl<
+        S,
+        linera_rpc::NodeProvider,
+        linera_wallet_json::Keystore,
+        linera_service::Wallet,
+    >,
+>;
+
 #[async_trait]
 impl Runnable for Job {
     type Output = anyhow::Result<()>;
@@ -198,6 +595,37 @@ impl Runnable for Job {
                 debug!("{:?}", certificate);
             }
 
+            Sponsor {
+                sponsor,
+                beneficiary,
+                allowance,
+                applications,
+                system_operations,
+            } => {
+                let mut context = options
+                    .create_client_context(storage, wallet, keystore)
+                    .await?;
+                let chain_client = context.make_chain_client(sponsor.chain_id).await?;
+                info!("Letting {sponsor} pay up to {allowance} for the fees of {beneficiary}");
+                let policy = SponsorshipPolicy {
+                    applications,
+                    system_operations,
+                };
+                let certificate = context
+                    .apply_client_command(&chain_client, |chain_client| {
+                        let chain_client = chain_client.clone();
+                        let policy = policy.clone();
+                        async move {
+                            chain_client
+                                .sponsor(sponsor.owner, beneficiary, allowance, policy)
+                                .await
+                        }
+                    })
+                    .await
+                    .context("Failed to set the sponsorship")?;
+                debug!("{:?}", certificate);
+            }
+
             OpenChain {
                 chain_id,
                 owner,
@@ -458,6 +886,13 @@ impl Runnable for Job {
                 let chain_client = context.make_chain_client(chain_id).await?;
                 info!("Synchronizing chain information");
                 let time_start = Instant::now();
+                if chain_client.is_light() && (next_height.is_some() || until_block_time.is_some())
+                {
+                    anyhow::bail!(
+                        "Chain {chain_id} is followed as a light client: only its latest \
+                         block can be verified"
+                    );
+                }
                 if next_height.is_some() || until_block_time.is_some() {
                     chain_client
                         .synchronize_up_to(next_height, until_block_time)
@@ -538,6 +973,15 @@ impl Runnable for Job {
 
             command @ ResourceControlPolicy { .. } => {
                 info!("Starting operations to change resource control policy");
+                let ResourceControlPolicy { overrides } = &command else {
+                    unreachable!()
+                };
+                let http_request_rules: Option<BTreeMap<String, HttpRequestRule>> = overrides
+                    .http_request_rules
+                    .as_deref()
+                    .map(serde_json::from_str)
+                    .transpose()
+                    .context("Invalid HTTP request rules")?;
 
                 let time_start = Instant::now();
                 let mut context = options
@@ -554,6 +998,7 @@ impl Runnable for Job {
                     .apply_client_command(&chain_client, |chain_client| {
                         let chain_client = chain_client.clone();
                         let command = command.clone();
+                        let http_request_rules = http_request_rules.clone();
                         async move {
                             // Update resource control policy
                             let committee = chain_client.local_committee().await.unwrap();
@@ -595,7 +1040,10 @@ impl Runnable for Job {
                                             maximum_http_response_bytes,
                                             http_request_timeout_ms,
                                             http_request_allow_list,
+                                            http_request_rules: _,
                                             free_application_ids,
+                                            maximum_call_depth,
+                                            commit_state_roots,
                                         },
                                 } => {
                                     let existing_policy = policy.clone();
@@ -669,6 +1117,8 @@ impl Runnable for Job {
                                         http_request_allow_list: http_request_allow_list
                                             .map(BTreeSet::from_iter)
                                             .unwrap_or(existing_policy.http_request_allow_list),
+                                        http_request_rules: http_request_rules
+                                            .unwrap_or(existing_policy.http_request_rules),
                                         free_application_ids: free_application_ids
                                             .map(|ids| {
                                                 ids.into_iter().map(|s| s.parse()).collect::<Result<
@@ -680,6 +1130,10 @@ impl Runnable for Job {
                                             .transpose()
                                             .expect("Invalid application ID")
                                             .unwrap_or(existing_policy.free_application_ids),
+                                        maximum_call_depth: maximum_call_depth
+                                            .unwrap_or(existing_policy.maximum_call_depth),
+                                        commit_state_roots: commit_state_roots
+                                            .unwrap_or(existing_policy.commit_state_roots),
                                     };
                                     info!("{policy}");
                                     if committee.policy() == &policy {
@@ -1276,6 +1730,7 @@ impl Runnable for Job {
                     .await?;
 
                 let default_chain = context.wallet().default_chain();
+                let address_book = context.wallet().address_book();
                 let chain_id =
                     default_chain.expect("Service requires a default chain in the wallet");
 
@@ -1369,7 +1824,10 @@ impl Runnable for Job {
                     cancellation_token.clone(),
                     options.enable_memory_profiling(),
                     pause,
-                );
+                )
+                .with_scheduler(Scheduler::load(options.schedules_path()?)?)
+                .with_drafts(Drafts::load(options.drafts_path()?)?)
+                .with_address_book(address_book);
                 service.run(cancellation_token, command_receiver).await?;
             }
 
@@ -1507,6 +1965,8 @@ impl Runnable for Job {
                 json_argument,
                 json_argument_path,
                 required_application_ids,
+                upgrade_authority,
+                allow_reentrancy,
             } => {
                 let mut context = options
                     .create_client_context(storage, wallet, keystore)
@@ -1535,6 +1995,10 @@ impl Runnable for Job {
                                     parameters,
                                     argument,
                                     required_application_ids.unwrap_or_default(),
+                                    ApplicationOptions {
+                                        upgrade_authority,
+                                        allow_reentrancy,
+                                    },
                                 )
                                 .await
                         }
@@ -1549,6 +2013,42 @@ impl Runnable for Job {
                 println!("{application_id}");
             }
 
+            UpgradeApplication {
+                application_id,
+                module_id,
+                chain_id,
+            } => {
+                let mut context = options
+                    .create_client_context(storage, wallet, keystore)
+                    .await?;
+
+                let start_time = Instant::now();
+                let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
+                info!("Upgrading application {application_id} on chain {chain_id}");
+                let chain_client = context.make_chain_client(chain_id).await?;
+
+                info!("Synchronizing");
+                context.process_inbox(&chain_client).await?;
+
+                let certificate = context
+                    .apply_client_command(&chain_client, move |chain_client| {
+                        let chain_client = chain_client.clone();
+                        async move {
+                            chain_client
+                                .upgrade_application(application_id, module_id)
+                                .await
+                        }
+                    })
+                    .await
+                    .context("Failed to upgrade application")?;
+                info!("{}", "Application upgraded successfully!".green().bold());
+                info!(
+                    "Application upgraded in {} ms",
+                    start_time.elapsed().as_millis()
+                );
+                debug!("{:?}", certificate);
+            }
+
             PublishAndCreate {
                 contract,
                 service,
@@ -1587,6 +2087,7 @@ impl Runnable for Job {
                                     parameters,
                                     argument,
                                     required_application_ids.unwrap_or_default(),
+                                    ApplicationOptions::default(),
                                 )
                                 .await
                         }
@@ -1617,6 +2118,28 @@ impl Runnable for Job {
                 );
             }
 
+            TransferBatch { sender, batch } => {
+                let manifest = fs_err::read_to_string(&batch.input)?;
+                let format = ManifestFormat::from_path(&batch.input)?;
+                let operations =
+                    Batch::transfers(sender.chain_id, sender.owner, &manifest, format)?;
+                let mut context = options
+                    .create_client_context(storage, wallet, keystore)
+                    .await?;
+                execute_batch(&mut context, &operations, &batch).await?;
+            }
+
+            ExecuteOperationBatch { chain_id, batch } => {
+                let manifest = fs_err::read_to_string(&batch.input)?;
+                let format = ManifestFormat::from_path(&batch.input)?;
+                let mut context = options
+                    .create_client_context(storage, wallet, keystore)
+                    .await?;
+                let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
+                let operations = Batch::user_operations(chain_id, &manifest, format)?;
+                execute_batch(&mut context, &operations, &batch).await?;
+            }
+
             ExecuteOperation {
                 application_id,
                 operation,
@@ -1688,6 +2211,7 @@ impl Runnable for Job {
                                         parameters,
                                         argument,
                                         required_application_ids.unwrap_or_default(),
+                                        ApplicationOptions::default(),
                                     )
                                     .await
                             }
@@ -1784,26 +2308,132 @@ impl Runnable for Job {
             Wallet(WalletCommand::Init {
                 faucet,
                 genesis_config_path,
+                from_mnemonic,
+                recovery_gap,
                 ..
             }) => {
-                let (Some(faucet_url), None) = (faucet, genesis_config_path) else {
-                    return Ok(());
+                let faucet_url = match (faucet, genesis_config_path) {
+                    (Some(faucet_url), None) => Some(faucet_url),
+                    _ => None,
                 };
+                if faucet_url.is_none() && !from_mnemonic {
+                    return Ok(());
+                }
                 let Some(network_description) = storage.read_network_description().await? else {
                     anyhow::bail!("Missing network description");
                 };
-                let context = options
+                let mut context = options
                     .create_client_context(storage, wallet, keystore)
                     .await?;
-                let faucet = cli_wrappers::Faucet::new(faucet_url);
-                let committee = faucet.current_committee().await?;
-                let chain_client = context
-                    .make_chain_client(network_description.admin_chain_id)
-                    .await?;
-                chain_client
-                    .synchronize_chain_state_from_committee(Arc::new(committee))
+                if let Some(faucet_url) = faucet_url {
+                    let faucet = cli_wrappers::Faucet::new(faucet_url);
+                    let committee = faucet.current_committee().await?;
+                    let chain_client = context
+                        .make_chain_client(network_description.admin_chain_id)
+                        .await?;
+                    chain_client
+                        .synchronize_chain_state_from_committee(Arc::new(committee))
+                        .await?;
+                    context.update_wallet_from_client(&chain_client).await?;
+                }
+                if from_mnemonic {
+                    let mut recovered = context
+                        .wallet()
+                        .items()
+                        .into_iter()
+                        .filter_map(|(chain_id, chain)| Some((chain_id, chain.owner?)))
+                        .collect::<BTreeMap<_, _>>();
+                    // Look for the chains that the recovered chains opened later on.
+                    let opened =
+                        discover_opened_chains(&context, recovered.clone(), recovery_gap).await?;
+                    recovered.extend(
+                        opened
+                            .iter()
+                            .map(|(chain_id, owner, _, _)| (*chain_id, *owner)),
+                    );
+                    // Check with the validators that the recovered chains are still owned, and
+                    // update their state.
+                    for (chain_id, owner) in recovered {
+                        if let Err(error) = context.assign_new_chain_to_key(chain_id, owner).await {
+                            warn!(
+                                "Could not confirm that {owner} still owns chain {chain_id}: \
+                                 {error}"
+                            );
+                        }
+                    }
+                    if !opened.is_empty() {
+                        // Release the keystore, and add the keys of the chains found.
+                        drop(context);
+                        let mut keystore = options.keystore()?;
+                        for (_, _, scheme, index) in opened {
+                            keystore.derive_key(scheme, index)?;
+                        }
+                        keystore.save().await?;
+                    }
+                }
+            }
+
+            Wallet(WalletCommand::Alias(AliasCommand::Sync {
+                registry,
+                chain_id,
+                overwrite,
+            })) => {
+                let context = options
+                    .create_client_context(storage, wallet, keystore)
                     .await?;
-                context.update_wallet_from_client(&chain_client).await?;
+                let chain_id = chain_id
+                    .or_else(|| context.wallet().default_chain())
+                    .context("No chain ID specified and no default chain in wallet")?;
+                let chain_client = context.make_chain_client(chain_id).await?;
+                let json_query =
+                    serde_json::json!({ "query": "query { entries { name kind id } }" });
+                let query = linera_execution::Query::User {
+                    application_id: registry,
+                    bytes: serde_json::to_vec(&json_query)?,
+                };
+                let (outcome, _height) = chain_client.query_application(query, None).await?;
+                let linera_execution::QueryResponse::User(bytes) = outcome.response else {
+                    unreachable!("cannot get a system response for a user query")
+                };
+                let mut response: Value = serde_json::from_slice(&bytes)?;
+                let entries: Vec<RegistryEntry> =
+                    serde_json::from_value(response["data"]["entries"].take())
+                        .context("Unexpected response from the registry")?;
+                let (mut added, mut kept) = (0, 0);
+                context.wallet().update_address_book(|address_book| {
+                    for entry in entries {
+                        let address = entry
+                            .kind
+                            .parse()
+                            .and_then(|kind| Address::parse(kind, &entry.id));
+                        let address = match address {
+                            Ok(address) => address,
+                            Err(error) => {
+                                warn!("Skipping the registry's entry `{}`: {error}", entry.name);
+                                continue;
+                            }
+                        };
+                        match address_book.get(&entry.name) {
+                            Some(current) if current == address => continue,
+                            Some(current) if !overwrite => {
+                                warn!(
+                                    "Keeping `{}` for {current} instead of the registry's {address}",
+                                    entry.name
+                                );
+                                kept += 1;
+                                continue;
+                            }
+                            _ => {}
+                        }
+                        if let Err(error) = address_book.insert(entry.name.clone(), address) {
+                            warn!("Skipping the registry's entry `{}`: {error}", entry.name);
+                            continue;
+                        }
+                        added += 1;
+                    }
+                    Ok(())
+                })?;
+                info!("Added {added} name(s) from the registry, kept {kept} existing one(s)");
             }
 
             Wallet(WalletCommand::FollowChain { chain_id, sync }) => {
@@ -1829,7 +2459,41 @@ impl Runnable for Job {
                 );
             }
 
-            Chain(ChainCommand::ShowBlock { chain_id, height }) => {
+            Chain(ChainCommand::History {
+                chain_id,
+                from,
+                to,
+                application,
+                owner,
+                json,
+            }) => {
+                let context = options
+                    .create_client_context(storage, wallet, keystore)
+                    .await?;
+                let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
+                let filter = HistoryFilter {
+                    application_id: application,
+                    owner,
+                };
+                let entries = context
+                    .chain_history(chain_id, from, to, &filter)
+                    .await
+                    .context("Failed to read the history of the chain")?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&entries)?);
+                } else {
+                    println!("{:>8}  {:>4}  {:<12}  DETAILS", "HEIGHT", "TX", "KIND");
+                    for entry in &entries {
+                        println!("{entry}");
+                    }
+                }
+            }
+
+            Chain(ChainCommand::ShowBlock {
+                chain_id,
+                height,
+                logs,
+            }) => {
                 let context = options
                     .create_client_context(storage, wallet, keystore)
                     .await?;
@@ -1849,6 +2513,30 @@ impl Runnable for Job {
                     .await
                     .context("Failed to find the given block in storage")?;
                 println!("{block:#?}");
+                if logs {
+                    let logs = context
+                        .storage()
+                        .read_application_logs(block_hash)
+                        .await
+                        .context("Failed to read the application logs")?;
+                    match logs {
+                        Some(logs) => {
+                            for log in logs {
+                                println!(
+                                    "[{:?}] transaction {} {}: {}",
+                                    log.level,
+                                    log.transaction_index,
+                                    log.application_id,
+                                    log.message
+                                );
+                            }
+                        }
+                        None => println!(
+                            "No application logs were recorded for this block. \
+                            Run with `--with-application-logs` to record them."
+                        ),
+                    }
+                }
             }
 
             Chain(ChainCommand::ShowChainDescription { chain_id }) => {
@@ -1868,6 +2556,237 @@ impl Runnable for Job {
                 println!("{description:#?}");
             }
 
+            Proposal(ProposalCommand::Prepare {
+                operations,
+                output,
+                chain_id,
+            }) => {
+                let operations: Vec<Operation> = match operations {
+                    Some(path) => serde_json::from_str(&fs_err::read_to_string(path)?)
+                        .context("Failed to parse the list of operations")?,
+                    None => Vec::new(),
+                };
+                let context = options
+                    .create_client_context(storage, wallet, keystore)
+                    .await?;
+                let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
+                let chain_client = context.make_chain_client(chain_id).await?;
+                chain_client.synchronize_from_validators().await?;
+                let unsigned = chain_client
+                    .prepare_unsigned_proposal(operations, vec![])
+                    .await
+                    .context("Failed to prepare the block proposal")?;
+                context.update_wallet_from_client(&chain_client).await?;
+                fs_err::write(&output, serde_json::to_string_pretty(&unsigned)?)?;
+                info!(
+                    "Unsigned proposal for block {} of chain {} in round {} written to {}",
+                    unsigned.height(),
+                    chain_id,
+                    unsigned.round(),
+                    output.display()
+                );
+                println!("{}", unsigned.expected_block_hash);
+            }
+
+            Proposal(ProposalCommand::Submit { input }) => {
+                let signed: SignedProposal = serde_json::from_str(&fs_err::read_to_string(input)?)
+                    .context("Failed to parse the signed proposal")?;
+                let context = options
+                    .create_client_context(storage, wallet, keystore)
+                    .await?;
+                let start_time = Instant::now();
+                let chain_id = signed.proposal.content.block.chain_id;
+                let chain_client = context.make_chain_client(chain_id).await?;
+                chain_client.synchronize_from_validators().await?;
+                let certificate = chain_client
+                    .submit_signed_proposal(signed)
+                    .await
+                    .context("Failed to submit the signed proposal")?;
+                context.update_wallet_from_client(&chain_client).await?;
+                info!(
+                    "Block {} of chain {} committed in {} ms",
+                    certificate.block().header.height,
+                    chain_id,
+                    start_time.elapsed().as_millis()
+                );
+                println!("{}", certificate.hash());
+            }
+
+            Draft(DraftCommand::List {
+                coordinator,
+                chain_id,
+            }) => {
+                let context = options
+                    .create_client_context(storage, wallet, keystore)
+                    .await?;
+                let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
+                let chain_client = context.make_chain_client(chain_id).await?;
+                chain_client.synchronize_from_validators().await?;
+                let ownership = chain_client.query_chain_ownership().await?;
+                let records = DraftCoordinator::new(coordinator).drafts(chain_id).await?;
+                for record in records {
+                    record.check_votes()?;
+                    let tally = record.tally(&ownership);
+                    println!(
+                        "{}  {}  {}/{} approved, {}/{} rejected  by {}: {}",
+                        record.id(),
+                        record.status(&ownership),
+                        tally.approved_weight,
+                        tally.total_weight,
+                        tally.rejected_weight,
+                        tally.total_weight,
+                        record.draft.author,
+                        record.draft.description,
+                    );
+                }
+            }
+
+            Draft(DraftCommand::Publish {
+                coordinator,
+                operations,
+                description,
+                chain_id,
+            }) => {
+                let operations: Vec<Operation> =
+                    serde_json::from_str(&fs_err::read_to_string(operations)?)
+                        .context("Failed to parse the list of operations")?;
+                let context = options
+                    .create_client_context(storage, wallet, keystore)
+                    .await?;
+                let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
+                let chain_client = context.make_chain_client(chain_id).await?;
+                let author = chain_client
+                    .preferred_owner()
+                    .with_context(|| format!("The wallet has no owner key for chain {chain_id}"))?;
+                let draft = drafts::Draft {
+                    chain_id,
+                    author,
+                    operations,
+                    description,
+                    created_at: Timestamp::now(),
+                };
+                let vote = DraftVote {
+                    draft_id: draft.id(),
+                    approve: true,
+                };
+                let vote = SignedDraftVote::new(vote, &author, chain_client.signer()).await?;
+                let draft_id = DraftCoordinator::new(coordinator)
+                    .publish(&draft, &vote)
+                    .await
+                    .context("Failed to publish the draft")?;
+                info!("Published draft for chain {chain_id} as {author}");
+                println!("{draft_id}");
+            }
+
+            Draft(command @ (DraftCommand::Approve { .. } | DraftCommand::Reject { .. })) => {
+                let (coordinator, draft_id, approve) = match command {
+                    DraftCommand::Approve {
+                        coordinator,
+                        draft_id,
+                    } => (coordinator, draft_id, true),
+                    DraftCommand::Reject {
+                        coordinator,
+                        draft_id,
+                    } => (coordinator, draft_id, false),
+                    _ => unreachable!(),
+                };
+                let coordinator = DraftCoordinator::new(coordinator);
+                let record = coordinator.draft(draft_id).await?;
+                ensure!(
+                    record.id() == draft_id,
+                    "The coordinator returned a different draft"
+                );
+                let context = options
+                    .create_client_context(storage, wallet, keystore)
+                    .await?;
+                let chain_id = record.draft.chain_id;
+                let chain_client = context.make_chain_client(chain_id).await?;
+                let owner = chain_client
+                    .preferred_owner()
+                    .with_context(|| format!("The wallet has no owner key for chain {chain_id}"))?;
+                println!(
+                    "Draft {draft_id} for chain {chain_id} by {}: {}",
+                    record.draft.author, record.draft.description
+                );
+                print_operations(&record.draft.operations);
+                let vote = DraftVote { draft_id, approve };
+                let vote = SignedDraftVote::new(vote, &owner, chain_client.signer()).await?;
+                coordinator
+                    .vote(&vote)
+                    .await
+                    .context("Failed to send the vote")?;
+                info!(
+                    "{} draft {draft_id} as {owner}",
+                    if approve { "Approved" } else { "Rejected" }
+                );
+            }
+
+            Draft(DraftCommand::Propose {
+                coordinator,
+                draft_id,
+            }) => {
+                let coordinator = DraftCoordinator::new(coordinator);
+                let record = coordinator.draft(draft_id).await?;
+                ensure!(
+                    record.id() == draft_id,
+                    "The coordinator returned a different draft"
+                );
+                ensure!(
+                    record.certificate_hash.is_none(),
+                    "Draft {draft_id} was already committed"
+                );
+                record.check_votes()?;
+                let mut context = options
+                    .create_client_context(storage, wallet, keystore)
+                    .await?;
+                let start_time = Instant::now();
+                let chain_id = record.draft.chain_id;
+                let mut chain_client = context.make_chain_client(chain_id).await?;
+                chain_client.synchronize_from_validators().await?;
+                let ownership = chain_client.query_chain_ownership().await?;
+                let tally = record.tally(&ownership);
+                ensure!(
+                    tally.is_approved(),
+                    "Draft {draft_id} is approved by owners with a weight of {} out of {}",
+                    tally.approved_weight,
+                    tally.total_weight
+                );
+                let owner = chain_client
+                    .preferred_owner()
+                    .with_context(|| format!("The wallet has no owner key for chain {chain_id}"))?;
+                ensure!(
+                    record.approvals.contains_key(&owner),
+                    "Only an owner who approved draft {draft_id} can propose it, but {owner} did not"
+                );
+                // The draft is proposed by the leader of a single-leader round, chosen according
+                // to the owners' weights, so that other owners cannot propose a competing block
+                // in the same round. If another owner leads the current round, wait for the next
+                // one.
+                chain_client.options_mut().single_leader_rounds_only = true;
+                let operations = record.draft.operations;
+                let certificate = context
+                    .apply_client_command(&chain_client, |chain_client| {
+                        let chain_client = chain_client.clone();
+                        let operations = operations.clone();
+                        async move { chain_client.execute_operations(operations, vec![]).await }
+                    })
+                    .await
+                    .context(
+                        "Failed to propose the draft in a single-leader round led by this wallet's \
+                         owner",
+                    )?;
+                coordinator
+                    .mark_committed(draft_id, certificate.hash())
+                    .await
+                    .context("Failed to report the committed draft")?;
+                info!(
+                    "Draft {draft_id} committed in block {} of chain {chain_id} after {} ms",
+                    certificate.block().header.height,
+                    start_time.elapsed().as_millis()
+                );
+                println!("{}", certificate.hash());
+            }
+
             Validator(validator_command) => {
                 validator_command
                     .run(
@@ -1879,7 +2798,8 @@ impl Runnable for Job {
             }
 
             CreateGenesisConfig { .. }
-            | Keygen
+            | Keygen { .. }
+            | Proposal(ProposalCommand::Sign { .. })
             | Net(_)
             | Storage { .. }
             | Wallet(_)
@@ -2224,7 +3144,10 @@ async fn run(options: &Options) -> Result<i32, Error> {
             maximum_http_response_bytes,
             http_request_timeout_ms,
             http_request_allow_list,
+            http_request_rules,
             free_application_ids,
+            maximum_call_depth,
+            commit_state_roots,
             testing_prng_seed,
             network_name,
         } => {
@@ -2282,6 +3205,12 @@ async fn run(options: &Options) -> Result<i32, Error> {
                     .as_ref()
                     .map(|list| list.iter().cloned().collect())
                     .unwrap_or(existing_policy.http_request_allow_list),
+                http_request_rules: http_request_rules
+                    .as_deref()
+                    .map(serde_json::from_str)
+                    .transpose()
+                    .context("Invalid HTTP request rules")?
+                    .unwrap_or(existing_policy.http_request_rules),
                 free_application_ids: free_application_ids
                     .as_ref()
                     .map(|ids| {
@@ -2292,6 +3221,10 @@ async fn run(options: &Options) -> Result<i32, Error> {
                     .transpose()
                     .expect("Invalid application ID")
                     .unwrap_or(existing_policy.free_application_ids),
+                maximum_call_depth: maximum_call_depth
+                    .unwrap_or(existing_policy.maximum_call_depth),
+                commit_state_roots: commit_state_roots
+                    .unwrap_or(existing_policy.commit_state_roots),
             };
             let timestamp = start_timestamp.map_or_else(Timestamp::now, |st| {
                 let micros =
@@ -2395,16 +3328,57 @@ async fn run(options: &Options) -> Result<i32, Error> {
             }
         },
 
-        ClientCommand::Keygen => {
+        ClientCommand::Keygen { scheme } => {
             let start_time = Instant::now();
             let mut keystore = options.keystore()?;
-            let public_key = keystore.generate_key().await?;
+            let public_key = match scheme {
+                Some(scheme) => {
+                    ensure!(
+                        keystore.has_seed(),
+                        "The keystore has no mnemonic to derive keys from. \
+                         Use `linera wallet init --with-mnemonic` to create one."
+                    );
+                    let public_key = keystore.derive_next(*scheme)?;
+                    keystore.save().await?;
+                    public_key
+                }
+                None => keystore.generate_key().await?,
+            };
             let owner = AccountOwner::from(public_key);
             println!("{owner}");
             info!("Key generated in {} ms", start_time.elapsed().as_millis());
             Ok(0)
         }
 
+        ClientCommand::Proposal(ProposalCommand::Sign { input, output }) => {
+            let unsigned: UnsignedProposal = serde_json::from_str(&fs_err::read_to_string(input)?)
+                .context("Failed to parse the unsigned proposal")?;
+            let keystore = options.keystore()?;
+            let block = &unsigned.content.block;
+            println!(
+                "Block {} of chain {} in round {}, to be signed by {}",
+                block.height,
+                block.chain_id,
+                unsigned.round(),
+                unsigned.owner
+            );
+            for bundle in block.incoming_bundles() {
+                println!(
+                    "  Incoming bundle from chain {} at height {}: {:?} {} message(s)",
+                    bundle.origin,
+                    bundle.bundle.height,
+                    bundle.action,
+                    bundle.bundle.messages.len()
+                );
+            }
+            print_operations(block.operations());
+            println!("Expected block hash: {}", unsigned.expected_block_hash);
+            let signed = unsigned.sign(&keystore).await?;
+            fs_err::write(output, serde_json::to_string_pretty(&signed)?)?;
+            info!("Signed proposal written to {}", output.display());
+            Ok(0)
+        }
+
         ClientCommand::Net(net_command) => match net_command {
             #[cfg(feature = "kubernetes")]
             NetCommand::Up {
@@ -2579,46 +3553,74 @@ async fn run(options: &Options) -> Result<i32, Error> {
                 Ok(0)
             }
 
+            WalletCommand::SetInboxPolicy { chain_id, policy } => {
+                let start_time = Instant::now();
+                let policy = wallet::InboxPolicy::from((**policy).clone());
+                let policy = (policy != wallet::InboxPolicy::default()).then_some(policy);
+                options.wallet()?.set_inbox_policy(*chain_id, policy)?;
+                info!(
+                    "Inbox policy set in {} ms",
+                    start_time.elapsed().as_millis()
+                );
+                Ok(0)
+            }
+
             WalletCommand::Init {
                 genesis_config_path,
                 faucet,
                 testing_prng_seed,
+                with_mnemonic,
+                from_mnemonic,
+                mnemonic_passphrase,
+                recovery_gap,
             } => {
                 let start_time = Instant::now();
-                let genesis_config: GenesisConfig = match (genesis_config_path, faucet) {
-                    (None, None) => {
-                        anyhow::bail!("please specify one of `--faucet` or `--genesis`.")
-                    }
-                    (Some(genesis_config_path), _) => util::read_json(genesis_config_path)?,
-                    (None, Some(url)) => {
-                        let faucet = cli_wrappers::Faucet::new(url.clone());
-                        let version_info = faucet
-                            .version_info()
-                            .await
-                            .context("Failed to obtain version information from the faucet")?;
-                        if !version_info.is_compatible_with(&linera_version::VERSION_INFO) {
-                            warn!(
-                                "\
-Make sure to use a Linera client compatible with this network.
---- Faucet info ---\
-{}\
--------------------
---- This binary ---\
-{}\
--------------------",
-                                version_info,
-                                linera_version::VERSION_INFO,
-                            );
-                        }
-                        faucet
-                            .genesis_config()
-                            .await
-                            .context("Failed to obtain the genesis configuration from the faucet")?
+                let genesis_config =
+                    read_genesis_config(genesis_config_path.as_ref(), faucet.as_ref()).await?;
+                let passphrase = || -> anyhow::Result<String> {
+                    if *mnemonic_passphrase {
+                        read_mnemonic_passphrase()
+                    } else {
+                        Ok(String::new())
                     }
                 };
-                let mut keystore = options.create_keystore(*testing_prng_seed)?;
+                let seed = if *from_mnemonic {
+                    let mnemonic = read_mnemonic()?;
+                    Some(HdSeed::from_mnemonic(&mnemonic, &passphrase()?)?)
+                } else if *with_mnemonic {
+                    let mut rng: Box<dyn CryptoRng> = None.into();
+                    let mnemonic = HdSeed::generate_mnemonic(&mut rng);
+                    eprintln!(
+                        "Write down the following mnemonic and keep it safe. It is the only \
+                         way to recover the keys of this wallet:"
+                    );
+                    println!("{mnemonic}");
+                    Some(HdSeed::from_mnemonic(&mnemonic, &passphrase()?)?)
+                } else {
+                    None
+                };
+                let mut keystore = match &seed {
+                    Some(seed) => options.create_keystore_from_seed(seed.copy())?,
+                    None => options.create_keystore(*testing_prng_seed)?,
+                };
+                let mut wallet = options.create_wallet(genesis_config)?;
+                if let (true, Some(seed)) = (*from_mnemonic, &seed) {
+                    let faucet = match genesis_config_path {
+                        Some(_) => None,
+                        None => faucet.clone().map(cli_wrappers::Faucet::new),
+                    };
+                    let num_chains = recover_chains_from_seed(
+                        seed,
+                        &mut keystore,
+                        &mut wallet,
+                        faucet.as_ref(),
+                        *recovery_gap,
+                    )
+                    .await?;
+                    info!("Recovered {num_chains} chain(s) from the mnemonic");
+                }
                 keystore.save().await?;
-                options.create_wallet(genesis_config)?.save()?;
+                wallet.save()?;
                 options.initialize_storage().boxed().await?;
                 options.run_with_storage(Job(options.clone())).await??;
                 info!(
@@ -2646,7 +3648,101 @@ Make sure to use a Linera client compatible with this network.
                 Ok(0)
             }
 
-            WalletCommand::FollowChain { .. } | WalletCommand::RequestChain { .. } => {
+            WalletCommand::Export {
+                output,
+                chain_ids,
+                with_keys,
+            } => {
+                let wallet = options.wallet()?;
+                let chain_ids = (!chain_ids.is_empty()).then_some(chain_ids.as_slice());
+                let mut bundle = WalletBundle::new(&wallet, chain_ids)?;
+                if *with_keys {
+                    let owners = bundle.owners();
+                    let keys = options.keystore()?.secret_keys(&owners);
+                    if keys.len() < owners.len() {
+                        warn!(
+                            "The keystore has no key for {} of the chains' owners",
+                            owners.len() - keys.len()
+                        );
+                    }
+                    let passphrase = read_passphrase()?;
+                    ensure!(!passphrase.is_empty(), "The passphrase must not be empty");
+                    bundle.encrypt_keys(&keys, &passphrase, DEFAULT_KEY_DERIVATION_ITERATIONS)?;
+                }
+                std::fs::write(output, bundle.to_json()?)
+                    .context("Failed to write the wallet bundle to file")?;
+                info!(
+                    "{} chain(s) exported to {}",
+                    bundle.chains.len(),
+                    output.display()
+                );
+                Ok(0)
+            }
+
+            WalletCommand::Import {
+                bundle,
+                genesis_config_path,
+                faucet,
+            } => {
+                let start_time = Instant::now();
+                let bundle = WalletBundle::from_json(&std::fs::read_to_string(bundle)?)?;
+                let genesis_config =
+                    read_genesis_config(genesis_config_path.as_ref(), faucet.as_ref()).await?;
+                bundle.check_genesis(&genesis_config)?;
+                let mut keystore = options.create_keystore(None)?;
+                let wallet = options.create_wallet(genesis_config)?;
+                merge_wallet_bundle(&bundle, &mut keystore, &wallet).await?;
+                keystore.save().await?;
+                options.initialize_storage().boxed().await?;
+                info!("Wallet imported in {} ms", start_time.elapsed().as_millis());
+                Ok(0)
+            }
+
+            WalletCommand::Merge { bundle } => {
+                let bundle = WalletBundle::from_json(&std::fs::read_to_string(bundle)?)?;
+                let wallet = options.wallet()?;
+                bundle.check_genesis(wallet.genesis_config())?;
+                let mut keystore = options.keystore()?;
+                merge_wallet_bundle(&bundle, &mut keystore, &wallet).await?;
+                Ok(0)
+            }
+
+            WalletCommand::Alias(AliasCommand::Set { name, id, kind }) => {
+                let address = match kind {
+                    Some(kind) => Address::parse(*kind, id)?,
+                    None => Address::infer(id).context("Use `--kind` to give the kind of ID")?,
+                };
+                let previous = options.wallet()?.update_address_book(|address_book| {
+                    address_book.insert(name.clone(), address)
+                })?;
+                if let Some(previous) = previous.filter(|previous| *previous != address) {
+                    info!("`{name}` stood for {previous}");
+                }
+                info!("`{name}` stands for {address}");
+                Ok(0)
+            }
+
+            WalletCommand::Alias(AliasCommand::Remove { name }) => {
+                let removed = options
+                    .wallet()?
+                    .update_address_book(|address_book| Ok(address_book.remove(name)))?;
+                let Some(address) = removed else {
+                    bail!("`{name}` is not in the address book");
+                };
+                info!("`{name}` no longer stands for {address}");
+                Ok(0)
+            }
+
+            WalletCommand::Alias(AliasCommand::List) => {
+                for (name, address) in options.wallet()?.address_book().iter() {
+                    println!("{name}\t{}\t{}", address.kind(), address.id());
+                }
+                Ok(0)
+            }
+
+            WalletCommand::Alias(AliasCommand::Sync { .. })
+            | WalletCommand::FollowChain { .. }
+            | WalletCommand::RequestChain { .. } => {
                 options.run_with_storage(Job(options.clone())).await??;
                 Ok(0)
             }
@@ -2666,9 +3762,14 @@ Make sure to use a Linera client compatible with this network.
 
 #[cfg(test)]
 mod tests {
-    use std::ffi::OsStr;
+    use std::{collections::BTreeSet, ffi::OsStr};
+
+    use linera_base::{
+        crypto::hd::{HdKeyScheme, HdSeed},
+        identifiers::AccountOwner,
+    };
 
-    use super::color_override_from_env;
+    use super::{color_override_from_env, DerivedOwners};
 
     #[test]
     fn no_color_disables_colors() {
@@ -2710,4 +3811,28 @@ mod tests {
             "CLICOLOR=1 should not force or disable",
         );
     }
+
+    #[test]
+    fn derived_owners_extend_past_used_keys() {
+        let directory = tempfile::tempdir().unwrap();
+        let mnemonic = "test test test test test test test test test test test junk";
+        let seed = HdSeed::from_mnemonic(mnemonic, "").unwrap();
+        let owner =
+            |index| AccountOwner::from(seed.derive(HdKeyScheme::Ed25519, index).unwrap().public());
+        let keystore = linera_wallet_json::Keystore::create_from_seed(
+            &directory.path().join("keystore.json"),
+            seed.copy(),
+        )
+        .unwrap();
+
+        // Key 2 is used, so the search continues until key 5.
+        let mut derived = DerivedOwners::new(&keystore, 3, BTreeSet::from([owner(2)]));
+        assert_eq!(derived.get(&owner(5)), Some((HdKeyScheme::Ed25519, 5)));
+        assert_eq!(derived.get(&owner(6)), None);
+
+        // A chain owned by key 5 is found: the search continues until key 8.
+        derived.mark_used(owner(5));
+        assert_eq!(derived.get(&owner(8)), Some((HdKeyScheme::Ed25519, 8)));
+        assert_eq!(derived.get(&owner(9)), None);
+    }
 }
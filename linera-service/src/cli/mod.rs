@@ -5,5 +5,6 @@
 
 pub mod command;
 pub mod common_options;
+pub mod names;
 pub mod net_up_utils;
 pub mod validator;
@@ -0,0 +1,171 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of command-line arguments where the names of the wallet's address book can be used
+//! instead of IDs.
+//!
+//! All the arguments that take a chain ID, an owner, an application ID or an account accept
+//! names. The wallet is only known once the arguments are parsed, so they are parsed twice:
+//! first with these arguments taken as plain strings to find the wallet, then with the address
+//! book of that wallet.
+
+use std::{any::TypeId, ffi::OsString, sync::Arc};
+
+use clap::{builder::ValueParser, Arg, Command, FromArgMatches as _};
+use linera_base::identifiers::{Account, AccountOwner, ApplicationId, ChainId};
+use linera_wallet_json::AddressBook;
+
+use crate::cli::common_options::CommonCliOptions;
+
+/// Parses the command-line arguments, resolving names with the address book of the wallet
+/// that the arguments select. `P` must flatten [`CommonCliOptions`].
+pub fn parse_from<P, I, T>(args: I) -> Result<P, clap::Error>
+where
+    P: clap::Parser,
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let args = args.into_iter().collect::<Vec<_>>();
+    let address_book = Arc::new(read_address_book(P::command(), args.clone()));
+    let command = with_id_parsers(P::command(), &|arg| resolving_parser(arg, &address_book));
+    let mut matches = command.try_get_matches_from(args)?;
+    P::from_arg_matches_mut(&mut matches).map_err(|error| error.format(&mut P::command()))
+}
+
+/// Reads the address book of the wallet selected by the arguments. The address book is empty
+/// if the arguments or the wallet can't be read.
+fn read_address_book<T>(command: Command, args: Vec<T>) -> AddressBook
+where
+    T: Into<OsString> + Clone,
+{
+    let command = with_id_parsers(command, &|arg| {
+        is_id_argument(arg).then(ValueParser::string)
+    });
+    let Ok(matches) = command.ignore_errors(true).try_get_matches_from(args) else {
+        return AddressBook::default();
+    };
+    CommonCliOptions::from_arg_matches(&matches)
+        .map_err(anyhow::Error::from)
+        .and_then(|options| AddressBook::read_from_wallet(&options.wallet_path()?))
+        .unwrap_or_default()
+}
+
+/// Replaces the parsers of the arguments of the command and its subcommands, where
+/// `parser` returns a new one.
+fn with_id_parsers(command: Command, parser: &impl Fn(&Arg) -> Option<ValueParser>) -> Command {
+    command
+        .mut_args(|arg| match parser(&arg) {
+            Some(value_parser) => arg.value_parser(value_parser),
+            None => arg,
+        })
+        .mut_subcommands(|subcommand| with_id_parsers(subcommand, parser))
+}
+
+/// Returns whether the argument takes a chain ID, an owner, an application ID or an account.
+fn is_id_argument(arg: &Arg) -> bool {
+    let type_id = arg.get_value_parser().type_id();
+    type_id == TypeId::of::<ChainId>()
+        || type_id == TypeId::of::<AccountOwner>()
+        || type_id == TypeId::of::<ApplicationId>()
+        || type_id == TypeId::of::<Account>()
+}
+
+/// Returns a parser for the argument that also accepts names of the address book, if it takes
+/// an ID.
+fn resolving_parser(arg: &Arg, address_book: &Arc<AddressBook>) -> Option<ValueParser> {
+    let type_id = arg.get_value_parser().type_id();
+    let book = address_book.clone();
+    if type_id == TypeId::of::<ChainId>() {
+        Some(ValueParser::new(move |value: &str| {
+            book.resolve_chain_id(value)
+        }))
+    } else if type_id == TypeId::of::<AccountOwner>() {
+        Some(ValueParser::new(move |value: &str| {
+            book.resolve_owner(value)
+        }))
+    } else if type_id == TypeId::of::<ApplicationId>() {
+        Some(ValueParser::new(move |value: &str| {
+            book.resolve_application_id(value)
+        }))
+    } else if type_id == TypeId::of::<Account>() {
+        Some(ValueParser::new(move |value: &str| {
+            book.resolve_account(value)
+        }))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_base::identifiers::{Account, ChainId};
+    use linera_wallet_json::{address_book::Address, AddressBook};
+
+    use super::parse_from;
+    use crate::cli::common_options::CommonCliOptions;
+
+    #[derive(clap::Parser)]
+    struct TestOptions {
+        #[command(flatten)]
+        common: CommonCliOptions,
+
+        #[command(subcommand)]
+        command: TestCommand,
+    }
+
+    #[derive(clap::Subcommand)]
+    enum TestCommand {
+        Transfer {
+            #[arg(long)]
+            to: Account,
+        },
+        Sync {
+            chain_id: Option<ChainId>,
+        },
+    }
+
+    #[test]
+    fn test_parse_names_of_selected_wallet() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let chain_id = ChainId(linera_base::crypto::CryptoHash::test_hash("alice"));
+        let mut address_book = AddressBook::default();
+        address_book.insert("alice".to_string(), Address::Chain(chain_id))?;
+        let wallet_path = dir.path().join("wallet.json");
+        let wallet = serde_json::json!({ "address_book": address_book });
+        std::fs::write(&wallet_path, serde_json::to_string(&wallet)?)?;
+        let wallet_arg = wallet_path.to_str().unwrap();
+
+        let options: TestOptions = parse_from([
+            "linera", "--wallet", wallet_arg, "transfer", "--to", "alice",
+        ])?;
+        let TestCommand::Transfer { to } = options.command else {
+            panic!("unexpected command");
+        };
+        assert_eq!(to, Account::chain(chain_id));
+
+        let options: TestOptions = parse_from([
+            "linera",
+            &format!("--wallet={wallet_arg}"),
+            "sync",
+            &chain_id.to_string(),
+        ])?;
+        let TestCommand::Sync { chain_id: parsed } = options.command else {
+            panic!("unexpected command");
+        };
+        assert_eq!(parsed, Some(chain_id));
+
+        // Names are only resolved with the address book of the selected wallet.
+        let other_wallet_path = dir.path().join("other.json");
+        std::fs::write(&other_wallet_path, "{}")?;
+        let other_wallet_arg = other_wallet_path.to_str().unwrap();
+        assert!(parse_from::<TestOptions, _, _>([
+            "linera",
+            "--wallet",
+            other_wallet_arg,
+            "sync",
+            "alice"
+        ])
+        .is_err());
+        Ok(())
+    }
+}
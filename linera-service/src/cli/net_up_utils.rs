@@ -225,12 +225,19 @@ pub async fn handle_net_up_service(
     let namespace = storage.namespace().to_string();
     let database = storage.database()?;
     let storage_config_builder = InnerStorageConfigBuilder::ExistingConfig { storage_config };
-    let external = match external_protocol.as_str() {
-        "grpc" => Network::Grpc,
-        "grpcs" => Network::Grpcs,
-        _ => panic!("Only allowed options are grpc and grpcs"),
+    let (external, internal) = match external_protocol.as_str() {
+        "grpc" => (Network::Grpc, Network::Grpc),
+        "grpcs" => (Network::Grpcs, Network::Grpc),
+        // The simple protocol is used both between the proxy and the shards and outside.
+        "tcp" => (Network::Tcp, Network::Tcp),
+        "udp" => (Network::Udp, Network::Udp),
+        "quic" => (Network::Quic, Network::Quic),
+        _ => panic!("Only allowed options are grpc, grpcs, tcp, udp and quic"),
     };
-    let internal = Network::Grpc;
+    anyhow::ensure!(
+        !with_block_exporter || matches!(internal, Network::Grpc),
+        "Block exporters are only supported with grpc and grpcs"
+    );
     let network = NetworkConfig { external, internal };
     let path_provider = PathProvider::from_path_option(path)?;
     let num_proxies = 1; // Local networks currently support exactly 1 proxy.
@@ -317,6 +324,13 @@ async fn print_messages_and_create_faucet(
         client.keystore_path().display(),
     );
     println!("export LINERA_STORAGE=\"{}\"", client.storage_path(),);
+    if let Some(path) = std::env::var_os(linera_rpc::simple::QUIC_CA_CERTIFICATE_ENV_VAR) {
+        println!(
+            "export {}=\"{}\"",
+            linera_rpc::simple::QUIC_CA_CERTIFICATE_ENV_VAR,
+            std::path::Path::new(&path).display()
+        );
+    }
 
     // Run the faucet using a separate wallet so it doesn't lock the admin wallet.
     // Keep half the balance on the admin chain for fee payments (e.g. committee changes).
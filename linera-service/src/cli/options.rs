@@ -4,10 +4,11 @@
 use std::path::PathBuf;
 
 use anyhow::Error;
+use linera_base::crypto::hd::HdSeed;
 use linera_client::{client_context::ClientContext, config::GenesisConfig};
 use linera_execution::WithWasmDefault as _;
 use linera_service::{
-    cli::{command::ClientCommand, common_options::CommonCliOptions},
+    cli::{command::ClientCommand, common_options::CommonCliOptions, names},
     storage::{Runnable, RunnableWithStore, StorageConfig},
     Wallet,
 };
@@ -64,8 +65,10 @@ pub struct Options {
 }
 
 impl Options {
+    /// Parses the command-line arguments, where the names of the selected wallet's address
+    /// book can be used instead of IDs.
     pub fn init() -> Self {
-        <Options as clap::Parser>::parse()
+        names::parse_from(std::env::args_os()).unwrap_or_else(|error| error.exit())
     }
 
     pub fn enable_memory_profiling(&self) -> bool {
@@ -93,7 +96,7 @@ impl Options {
     {
         let genesis_config = wallet.genesis_config().clone();
         let default_chain = wallet.default_chain();
-        Ok(ClientContext::new(
+        let mut context = ClientContext::new(
             storage,
             wallet,
             signer,
@@ -103,7 +106,26 @@ impl Options {
             self.block_cache_size,
             self.execution_state_cache_size,
         )
-        .await?)
+        .await?;
+        context
+            .load_validator_scores(self.validator_scores_path()?)
+            .await;
+        Ok(context)
+    }
+
+    /// Returns the path of the validator statistics kept alongside the wallet.
+    pub fn validator_scores_path(&self) -> Result<PathBuf, Error> {
+        Ok(self.wallet_path()?.with_extension("scores.json"))
+    }
+
+    /// Returns the path of the schedules that the node service keeps alongside the wallet.
+    pub fn schedules_path(&self) -> Result<PathBuf, Error> {
+        Ok(self.wallet_path()?.with_extension("schedules.json"))
+    }
+
+    /// Returns the path of the drafts that the node service keeps alongside the wallet.
+    pub fn drafts_path(&self) -> Result<PathBuf, Error> {
+        Ok(self.wallet_path()?.with_extension("drafts.json"))
     }
 
     pub async fn run_with_storage<R: Runnable>(&self, job: R) -> Result<R::Output, Error> {
@@ -173,4 +195,11 @@ impl Options {
     ) -> Result<linera_wallet_json::Keystore, Error> {
         self.common.create_keystore(testing_prng_seed)
     }
+
+    pub fn create_keystore_from_seed(
+        &self,
+        seed: HdSeed,
+    ) -> Result<linera_wallet_json::Keystore, Error> {
+        self.common.create_keystore_from_seed(seed)
+    }
 }
@@ -159,6 +159,10 @@ pub struct List {
     /// Only show validators with at least this many votes
     #[arg(long)]
     min_votes: Option<u64>,
+    /// Also show the scores that the client uses to pick validators, based on the latency
+    /// and success rate of earlier requests
+    #[arg(long)]
+    scores: bool,
 }
 
 /// Query a single validator's state and connectivity.
@@ -669,6 +673,24 @@ impl List {
             );
         }
 
+        if self.scores {
+            let scores = context.client.validator_scores().await;
+            println!("\nValidator scores:");
+            for (name, address, _votes, _results) in &validator_results {
+                match scores.get(name) {
+                    Some((score, statistics)) => println!(
+                        "  {name} at {address}: score {score:.3}, latency {:.0} ms, \
+                        success rate {:.1}%, {} request(s), last updated {}",
+                        statistics.ema_latency_ms,
+                        statistics.ema_success_rate * 100.0,
+                        statistics.total_requests,
+                        statistics.updated_at,
+                    ),
+                    None => println!("  {name} at {address}: no statistics yet"),
+                }
+            }
+        }
+
         if !faulty_validators.is_empty() {
             println!("\nFaulty validators:");
             for ((name, address), errors) in faulty_validators {
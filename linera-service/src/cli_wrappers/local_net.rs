@@ -1,14 +1,12 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-#[cfg(with_testing)]
-use std::sync::LazyLock;
 use std::{
     collections::BTreeMap,
     env,
     num::NonZeroU16,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, LazyLock},
     time::Duration,
 };
 
@@ -358,11 +356,57 @@ impl LocalNetConfig {
     }
 }
 
+/// The certificates of the QUIC servers of local networks. They are created once per
+/// process, since they are configured through environment variables.
+static QUIC_CERTIFICATES: LazyLock<TempDir> = LazyLock::new(|| {
+    let directory = tempdir().expect("failed to create a temporary directory");
+    let mut ca_params = rcgen::CertificateParams::new(vec![]);
+    ca_params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+    let ca = rcgen::Certificate::from_params(ca_params).expect("failed to create a CA");
+    let certificate = rcgen::Certificate::from_params(rcgen::CertificateParams::new(vec![
+        "localhost".to_owned(),
+        "127.0.0.1".to_owned(),
+    ]))
+    .expect("failed to create a certificate");
+    let files = [
+        ("ca.pem", ca.serialize_pem()),
+        ("cert.pem", certificate.serialize_pem_with_signer(&ca)),
+        ("key.pem", Ok(certificate.serialize_private_key_pem())),
+    ];
+    for (name, contents) in files {
+        let contents = contents.expect("failed to serialize a certificate");
+        std::fs::write(directory.path().join(name), contents)
+            .expect("failed to write a certificate");
+    }
+    directory
+});
+
+/// Configures the QUIC servers and clients started by this process to use certificates for
+/// the local host, unless QUIC certificates are already configured.
+fn configure_quic_certificates() {
+    use linera_rpc::simple::{
+        QUIC_CA_CERTIFICATE_ENV_VAR, QUIC_CERTIFICATE_ENV_VAR, QUIC_PRIVATE_KEY_ENV_VAR,
+    };
+
+    if env::var_os(QUIC_CERTIFICATE_ENV_VAR).is_some() {
+        return;
+    }
+    let directory = QUIC_CERTIFICATES.path();
+    env::set_var(QUIC_CA_CERTIFICATE_ENV_VAR, directory.join("ca.pem"));
+    env::set_var(QUIC_CERTIFICATE_ENV_VAR, directory.join("cert.pem"));
+    env::set_var(QUIC_PRIVATE_KEY_ENV_VAR, directory.join("key.pem"));
+}
+
 #[async_trait]
 impl LineraNetConfig for LocalNetConfig {
     type Net = LocalNet;
 
     async fn instantiate(self) -> Result<(Self::Net, ClientWrapper)> {
+        if matches!(self.network.internal, Network::Quic)
+            || matches!(self.network.external, Network::Quic)
+        {
+            configure_quic_certificates();
+        }
         let storage_config = self.storage_config_builder.build(self.database).await?;
         let mut net = LocalNet::new(
             self.network,
@@ -777,6 +821,9 @@ impl LocalNet {
             Network::Udp => {
                 Self::ensure_simple_server_has_started(&nickname, port, "udp").await?;
             }
+            Network::Quic => {
+                Self::ensure_simple_server_has_started(&nickname, port, "quic").await?;
+            }
         }
         Ok(child)
     }
@@ -808,7 +855,7 @@ impl LocalNet {
                 let nickname = format!("block exporter  {validator}:{exporter_id}");
                 Self::ensure_grpc_server_has_started(&nickname, port, "https").await?;
             }
-            Network::Tcp | Network::Udp => {
+            Network::Tcp | Network::Udp | Network::Quic => {
                 unreachable!("Only allowed options are grpc and grpcs")
             }
         }
@@ -946,6 +993,9 @@ impl LocalNet {
             Network::Udp => {
                 Self::ensure_simple_server_has_started(&nickname, port, "udp").await?;
             }
+            Network::Quic => {
+                Self::ensure_simple_server_has_started(&nickname, port, "quic").await?;
+            }
         }
         Ok(child)
     }
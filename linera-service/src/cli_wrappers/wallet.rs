@@ -1304,7 +1304,10 @@ impl ClientWrapper {
             maximum_http_response_bytes,
             http_request_timeout_ms,
             http_request_allow_list,
+            http_request_rules,
             free_application_ids,
+            maximum_call_depth,
+            commit_state_roots,
         } = overrides;
         if let Some(value) = wasm_fuel_unit {
             command.args(["--wasm-fuel-unit", &value.to_string()]);
@@ -1402,9 +1405,18 @@ impl ClientWrapper {
         if let Some(values) = http_request_allow_list {
             command.args(["--http-request-allow-list", &values.join(",")]);
         }
+        if let Some(value) = http_request_rules {
+            command.args(["--http-request-rules", &value]);
+        }
         if let Some(values) = free_application_ids {
             command.args(["--free-application-ids", &values.join(",")]);
         }
+        if let Some(value) = maximum_call_depth {
+            command.args(["--maximum-call-depth", &value.to_string()]);
+        }
+        if let Some(value) = commit_state_roots {
+            command.args(["--commit-state-roots", &value.to_string()]);
+        }
         command.spawn_and_wait_for_stdout().await?;
         Ok(())
     }
@@ -6,6 +6,7 @@
 
 #![recursion_limit = "256"]
 
+pub mod address_book;
 pub mod cli;
 pub mod cli_wrappers;
 pub mod config;
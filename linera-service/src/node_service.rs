@@ -24,7 +24,7 @@ use linera_base::{
     crypto::{CryptoError, CryptoHash},
     data_types::{
         Amount, ApplicationDescription, ApplicationPermissions, BlockHeight, Bytecode, Epoch,
-        TimeDelta,
+        TimeDelta, Timestamp,
     },
     identifiers::{
         Account, AccountOwner, ApplicationId, ChainId, IndexAndEvent, ModuleId, StreamId,
@@ -34,11 +34,14 @@ use linera_base::{
     BcsHexParseError,
 };
 use linera_chain::{
+    data_types::OperationMetadata,
     types::{ConfirmedBlock, GenericCertificate},
     ChainStateView,
 };
-use linera_client::chain_listener::{
-    ChainListener, ChainListenerConfig, ClientContext, ListenerCommand,
+use linera_client::{
+    chain_listener::{ChainListener, ChainListenerConfig, ClientContext, ListenerCommand},
+    drafts::{self, Draft, DraftRecord, Drafts, SignedDraftVote},
+    scheduler::{Schedule, ScheduledAction, Scheduler, Trigger},
 };
 use linera_core::{
     client::chain_client::{self, ChainClient},
@@ -47,22 +50,25 @@ use linera_core::{
     worker::{ChainStateViewReadGuard, Notification, Reason},
 };
 use linera_execution::{
-    committee::Committee, system::AdminOperation, Operation, Query, QueryOutcome, QueryResponse,
+    committee::Committee,
+    system::{AdminOperation, ApplicationOptions},
+    ApplicationLog, ApplicationStateProof, Operation, Query, QueryOutcome, QueryResponse,
     SystemOperation,
 };
 #[cfg(with_metrics)]
 use linera_metrics::monitoring_server;
 use linera_sdk::linera_base_types::BlobContent;
 use linera_storage::Storage;
+use linera_wallet_json::AddressBook;
 use lru::LruCache;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tokio::sync::mpsc::UnboundedReceiver;
 use tokio_util::sync::CancellationToken;
 use tower_http::cors::CorsLayer;
-use tracing::{debug, info, instrument, trace};
+use tracing::{debug, info, instrument, trace, warn};
 
-use crate::util;
+use crate::{address_book::ResolveNames, util};
 
 /// A pre-serialized JSON string that implements [`OutputType`] as the `JSON` scalar.
 ///
@@ -116,11 +122,142 @@ pub struct Chains {
     pub default: Option<ChainId>,
 }
 
+/// A proof of an application's state after the latest block of a chain.
+#[derive(SimpleObject, Serialize, Deserialize, Clone)]
+pub struct ApplicationStateProofAtHeight {
+    /// The height of the next block of the chain.
+    pub next_block_height: BlockHeight,
+    /// The hash of the latest block of the chain, if any.
+    pub block_hash: Option<CryptoHash>,
+    /// The state hash in that block's header, which the proof verifies against.
+    pub state_hash: CryptoHash,
+    /// The proof, relative to the state hash.
+    pub proof: ApplicationStateProof,
+}
+
+/// A draft block on a multi-owner chain, with the state of the owners' votes.
+#[derive(SimpleObject)]
+pub struct DraftInfo {
+    /// The ID of the draft.
+    pub id: CryptoHash,
+    /// The chain to execute the operations on.
+    pub chain_id: ChainId,
+    /// The owner who published the draft.
+    pub author: AccountOwner,
+    /// The description of the draft's purpose.
+    pub description: String,
+    /// When the draft was created.
+    pub created_at: Timestamp,
+    /// The operations to execute.
+    pub operations: Vec<OperationMetadata>,
+    /// The owners who approved the draft.
+    pub approvals: Vec<AccountOwner>,
+    /// The owners who rejected the draft.
+    pub rejections: Vec<AccountOwner>,
+    /// The total weight of the owners who approved the draft.
+    pub approved_weight: u64,
+    /// The total weight of the owners who rejected the draft.
+    pub rejected_weight: u64,
+    /// The total weight of the chain's owners.
+    pub total_weight: u64,
+    /// Whether the draft is pending, approved, rejected or committed.
+    pub status: String,
+    /// The certificate of the block that executed the draft, if any.
+    pub certificate_hash: Option<CryptoHash>,
+    /// The draft with the owners' signatures, so that clients can check them.
+    pub record: DraftRecord,
+}
+
+impl DraftInfo {
+    fn new(record: &DraftRecord, ownership: &ChainOwnership) -> Self {
+        let tally = record.tally(ownership);
+        Self {
+            id: record.id(),
+            chain_id: record.draft.chain_id,
+            author: record.draft.author,
+            description: record.draft.description.clone(),
+            created_at: record.draft.created_at,
+            operations: record
+                .draft
+                .operations
+                .iter()
+                .map(OperationMetadata::from)
+                .collect(),
+            approvals: record.approvals.keys().copied().collect(),
+            rejections: record.rejections.keys().copied().collect(),
+            approved_weight: tally.approved_weight,
+            rejected_weight: tally.rejected_weight,
+            total_weight: tally.total_weight,
+            status: record.status(ownership).to_string(),
+            certificate_hash: record.certificate_hash,
+            record: record.clone(),
+        }
+    }
+}
+
+/// An action that the node service runs on a chain whenever its trigger fires.
+#[derive(SimpleObject)]
+pub struct ScheduleInfo {
+    /// The ID of the schedule.
+    pub id: u64,
+    /// The chain to run the action on.
+    pub chain_id: ChainId,
+    /// The description of the schedule's purpose.
+    pub description: String,
+    /// Whether the action processes the inbox.
+    pub process_inbox: bool,
+    /// The operations that the action executes.
+    pub operations: Vec<OperationMetadata>,
+    /// When the action runs, e.g. `every 1d` or `cron 0 2 * * *`.
+    pub trigger: String,
+    /// When the schedule is due next, if it will run again.
+    pub next_run: Option<Timestamp>,
+    /// How many times the schedule ran.
+    pub run_count: u64,
+    /// When the schedule ran last, if ever.
+    pub last_run: Option<Timestamp>,
+    /// The certificates of the blocks created by the latest run.
+    pub last_certificate_hashes: Vec<CryptoHash>,
+    /// The error of the latest run, if it failed.
+    pub last_error: Option<String>,
+}
+
+impl From<Schedule> for ScheduleInfo {
+    fn from(schedule: Schedule) -> Self {
+        let (process_inbox, operations) = match &schedule.action {
+            ScheduledAction::ProcessInbox => (true, Vec::new()),
+            ScheduledAction::ExecuteOperations(operations) => (
+                false,
+                operations.iter().map(OperationMetadata::from).collect(),
+            ),
+        };
+        let (last_run, last_certificate_hashes, last_error) = match schedule.last_run {
+            Some(run) => (Some(run.time), run.certificate_hashes, run.error),
+            None => (None, Vec::new(), None),
+        };
+        Self {
+            id: schedule.id,
+            chain_id: schedule.chain_id,
+            description: schedule.description,
+            process_inbox,
+            operations,
+            trigger: schedule.trigger.to_string(),
+            next_run: schedule.next_run,
+            run_count: schedule.run_count,
+            last_run,
+            last_certificate_hashes,
+            last_error,
+        }
+    }
+}
+
 /// Our root GraphQL query type.
 pub struct QueryRoot<C> {
     context: Arc<Mutex<C>>,
     port: NonZeroU16,
     default_chain: Option<ChainId>,
+    drafts: Drafts,
+    scheduler: Scheduler,
 }
 
 /// Our root GraphQL subscription type.
@@ -133,6 +270,21 @@ pub struct SubscriptionRoot<C> {
 /// Our root GraphQL mutation type.
 pub struct MutationRoot<C> {
     context: Arc<Mutex<C>>,
+    drafts: Drafts,
+    scheduler: Scheduler,
+}
+
+/// Returns the current ownership of the chain, according to the local node.
+async fn chain_ownership<C: ClientContext>(
+    context: &Mutex<C>,
+    chain_id: ChainId,
+) -> Result<ChainOwnership, Error> {
+    let client = context.lock().await.make_chain_client(chain_id).await?;
+    Ok(client
+        .chain_info_with_manager_values()
+        .await?
+        .manager
+        .ownership)
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -184,6 +336,46 @@ where
         Ok(client.subscribe()?)
     }
 
+    /// Subscribes to the logs emitted by applications in new blocks of the specified chain.
+    /// Logs are only available if the node service was started with application logs enabled.
+    async fn application_logs(
+        &self,
+        chain_id: ChainId,
+        #[graphql(desc = "Only return the logs of this application.")] application_id: Option<
+            ApplicationId,
+        >,
+    ) -> Result<impl Stream<Item = ApplicationLog>, Error> {
+        let client = self
+            .context
+            .lock()
+            .await
+            .make_chain_client(chain_id)
+            .await?;
+        let storage = client.storage_client().clone();
+        let notifications = client.subscribe()?;
+        Ok(notifications
+            .filter_map(move |notification| {
+                let storage = storage.clone();
+                async move {
+                    let Reason::NewBlock { height, hash } = notification.reason else {
+                        return None;
+                    };
+                    match storage.read_application_logs(hash).await {
+                        Ok(logs) => logs,
+                        Err(error) => {
+                            warn!(%chain_id, %height, %error, "Failed to read application logs");
+                            None
+                        }
+                    }
+                }
+            })
+            .flat_map(move |logs| {
+                futures::stream::iter(logs.into_iter().filter(move |log| {
+                    application_id.is_none_or(|application_id| log.application_id == application_id)
+                }))
+            }))
+    }
+
     /// Subscribes to the result of a pre-registered GraphQL query.
     /// Re-executes the query on every new block and pushes changed results.
     async fn query_result(
@@ -736,6 +928,7 @@ and no system operations."
                         parameters,
                         instantiation_argument,
                         required_application_ids,
+                        ApplicationOptions::default(),
                     )
                     .await
                     .map_err(Error::from)
@@ -745,6 +938,116 @@ and no system operations."
         })
         .await
     }
+
+    /// Publishes a draft block for a multi-owner chain, together with its author's signed
+    /// approval, so that the other owners can review it. Returns the ID of the draft.
+    async fn publish_draft(
+        &self,
+        #[graphql(desc = "The draft to publish")] draft: Draft,
+        #[graphql(desc = "The author's approval of the draft")] vote: SignedDraftVote,
+    ) -> Result<CryptoHash, Error> {
+        let ownership = chain_ownership(&self.context, draft.chain_id).await?;
+        let draft_id = self.drafts.publish(draft, vote, &ownership)?;
+        info!(%draft_id, "Draft published");
+        Ok(draft_id)
+    }
+
+    /// Records a chain owner's signed approval or rejection of a draft.
+    async fn vote_on_draft(
+        &self,
+        #[graphql(desc = "The signed vote")] vote: SignedDraftVote,
+    ) -> Result<DraftInfo, Error> {
+        let draft_id = vote.vote.draft_id;
+        let chain_id = self
+            .drafts
+            .get(&draft_id)
+            .ok_or_else(|| drafts::Error::UnknownDraft(draft_id))?
+            .draft
+            .chain_id;
+        let ownership = chain_ownership(&self.context, chain_id).await?;
+        let record = self.drafts.vote(vote, &ownership)?;
+        Ok(DraftInfo::new(&record, &ownership))
+    }
+
+    /// Records that a draft was executed. The block with the given certificate must be on the
+    /// draft's chain, execute exactly the draft's operations, and be proposed by an owner who
+    /// approved the draft.
+    async fn mark_draft_committed(
+        &self,
+        #[graphql(desc = "The ID of the draft")] draft_id: CryptoHash,
+        #[graphql(desc = "The certificate of the block that executed the draft")]
+        certificate_hash: CryptoHash,
+    ) -> Result<bool, Error> {
+        let chain_id = self
+            .drafts
+            .get(&draft_id)
+            .ok_or_else(|| drafts::Error::UnknownDraft(draft_id))?
+            .draft
+            .chain_id;
+        let client = self
+            .context
+            .lock()
+            .await
+            .make_chain_client(chain_id)
+            .await?;
+        let storage = client.storage_client();
+        let certificate = match storage.read_certificate(certificate_hash).await? {
+            Some(certificate) => certificate,
+            None => {
+                client.synchronize_from_validators().await?;
+                self.context.lock().await.update_wallet(&client).await?;
+                storage
+                    .read_certificate(certificate_hash)
+                    .await?
+                    .ok_or_else(|| Error::new(format!("unknown certificate {certificate_hash}")))?
+            }
+        };
+        self.drafts.mark_committed(draft_id, &certificate)?;
+        info!(%draft_id, %certificate_hash, "Draft committed");
+        Ok(true)
+    }
+
+    /// Schedules an action on a chain, e.g. a transfer every day or processing the inbox at
+    /// 02:00. The node service runs it whenever the trigger fires.
+    async fn create_schedule(
+        &self,
+        #[graphql(desc = "The chain to run the action on")] chain_id: ChainId,
+        #[graphql(desc = "What to do: the BCS-encoded `ScheduledAction`")] action: ScheduledAction,
+        #[graphql(
+            desc = "When to do it: `at <time>`, `every <interval> [from <time>]` or \
+            `cron <expression>`, in UTC"
+        )]
+        trigger: String,
+        #[graphql(desc = "The description of the schedule's purpose", default)] description: String,
+    ) -> Result<ScheduleInfo, Error> {
+        let trigger = trigger.parse::<Trigger>()?;
+        let client = self
+            .context
+            .lock()
+            .await
+            .make_chain_client(chain_id)
+            .await?;
+        if client.preferred_owner().is_none() {
+            return Err(Error::new(format!(
+                "cannot schedule actions on chain {chain_id}: it has no owner in the wallet"
+            )));
+        }
+        let schedule = self
+            .scheduler
+            .create(chain_id, description, action, trigger)?;
+        info!(id = schedule.id, %chain_id, trigger = %schedule.trigger, "Schedule created");
+        Ok(schedule.into())
+    }
+
+    /// Cancels a schedule, and returns it.
+    async fn cancel_schedule(
+        &self,
+        #[graphql(desc = "The ID of the schedule")] id: u64,
+    ) -> Result<ScheduleInfo, Error> {
+        let schedule = self.scheduler.cancel(id)?;
+        info!(id, "Schedule cancelled");
+        Ok(schedule.into())
+    }
 }
 
 #[async_graphql::Object(cache_control(no_cache))]
@@ -789,6 +1092,46 @@ where
         Ok(overviews)
     }
 
+    /// Returns a proof that a key of an application's state has its value, or that it is
+    /// absent, after the chain's latest block.
+    ///
+    /// The proof verifies against the state hash in the header of that block, if the policy
+    /// of the committee makes state hashes commit to the state roots. Only the latest state is
+    /// kept, so `height`, if given, must be the one of the latest block.
+    async fn application_state_proof(
+        &self,
+        chain_id: ChainId,
+        application_id: ApplicationId,
+        #[graphql(desc = "The key in the application's state")] key: Vec<u8>,
+        #[graphql(desc = "The height of the latest block, if the state must be after it")]
+        height: Option<BlockHeight>,
+    ) -> Result<ApplicationStateProofAtHeight, Error> {
+        let client = self
+            .context
+            .lock()
+            .await
+            .make_chain_client(chain_id)
+            .await?;
+        let view = client.chain_state_view().await?;
+        let tip_state = view.tip_state.get();
+        let next_block_height = tip_state.next_block_height;
+        if let Some(height) = height {
+            if height.try_add_one()? != next_block_height {
+                return Err(Error::new(format!(
+                    "only the state after the latest block of chain {chain_id}, at height \
+                     {next_block_height}, can be proven, not the state after height {height}"
+                )));
+            }
+        }
+        let proof = view.application_state_proof(application_id, &key).await?;
+        Ok(ApplicationStateProofAtHeight {
+            next_block_height,
+            block_hash: tip_state.block_hash,
+            state_hash: proof.roots.state_hash(),
+            proof,
+        })
+    }
+
     async fn chains(&self) -> Result<Chains, Error> {
         Ok(Chains {
             list: self
@@ -825,6 +1168,32 @@ where
         }
     }
 
+    /// Returns the logs emitted by applications while executing the block at the given
+    /// height, if they were recorded.
+    async fn application_logs(
+        &self,
+        chain_id: ChainId,
+        height: BlockHeight,
+    ) -> Result<Option<Vec<ApplicationLog>>, Error> {
+        let client = self
+            .context
+            .lock()
+            .await
+            .make_chain_client(chain_id)
+            .await?;
+        let storage = client.storage_client();
+        let Some(block_hash) = storage
+            .read_certificate_hashes_by_heights(chain_id, &[height])
+            .await?
+            .into_iter()
+            .next()
+            .flatten()
+        else {
+            return Ok(None);
+        };
+        Ok(storage.read_application_logs(block_hash).await?)
+    }
+
     async fn events_from_index(
         &self,
         chain_id: ChainId,
@@ -878,6 +1247,35 @@ where
     async fn version(&self) -> linera_version::VersionInfo {
         linera_version::VersionInfo::default()
     }
+
+    /// Returns the drafts published for a multi-owner chain.
+    async fn drafts(&self, chain_id: ChainId) -> Result<Vec<DraftInfo>, Error> {
+        let ownership = chain_ownership(&self.context, chain_id).await?;
+        Ok(self
+            .drafts
+            .drafts(chain_id)
+            .iter()
+            .map(|record| DraftInfo::new(record, &ownership))
+            .collect())
+    }
+
+    /// Returns the draft with the given ID, if it was published.
+    async fn draft(&self, id: CryptoHash) -> Result<Option<DraftInfo>, Error> {
+        let Some(record) = self.drafts.get(&id) else {
+            return Ok(None);
+        };
+        let ownership = chain_ownership(&self.context, record.draft.chain_id).await?;
+        Ok(Some(DraftInfo::new(&record, &ownership)))
+    }
+
+    /// Returns the scheduled actions, optionally only those of the given chain.
+    async fn schedules(&self, chain_id: Option<ChainId>) -> Vec<ScheduleInfo> {
+        self.scheduler
+            .schedules(chain_id)
+            .into_iter()
+            .map(ScheduleInfo::from)
+            .collect()
+    }
 }
 
 // What follows is a hack to add a chain_id field to `ChainStateView` based on
@@ -1244,6 +1642,12 @@ where
     enable_memory_profiling: bool,
     /// If true, do not start the chain listener; serve queries from local state only.
     pause: bool,
+    /// The drafts published for multi-owner chains.
+    drafts: Drafts,
+    /// The schedules that the chain listener runs.
+    scheduler: Scheduler,
+    /// Resolves the names of the wallet's address book in GraphQL arguments.
+    names: ResolveNames,
 }
 
 impl<C> Clone for NodeService<C>
@@ -1264,6 +1668,9 @@ where
             cancellation_token: self.cancellation_token.clone(),
             enable_memory_profiling: self.enable_memory_profiling,
             pause: self.pause,
+            drafts: self.drafts.clone(),
+            scheduler: self.scheduler.clone(),
+            names: self.names.clone(),
         }
     }
 }
@@ -1305,9 +1712,32 @@ where
             cancellation_token,
             enable_memory_profiling,
             pause,
+            drafts: Drafts::default(),
+            scheduler: Scheduler::default(),
+            names: ResolveNames::default(),
         }
     }
 
+    /// Uses the given schedules, e.g. the ones saved alongside the wallet, instead of
+    /// schedules that are only kept in memory.
+    pub fn with_scheduler(mut self, scheduler: Scheduler) -> Self {
+        self.scheduler = scheduler;
+        self
+    }
+
+    /// Uses the given drafts, e.g. the ones saved alongside the wallet, instead of drafts
+    /// that are only kept in memory.
+    pub fn with_drafts(mut self, drafts: Drafts) -> Self {
+        self.drafts = drafts;
+        self
+    }
+
+    /// Accepts the names of the given address book as `@name` in GraphQL arguments.
+    pub fn with_address_book(mut self, address_book: AddressBook) -> Self {
+        self.names = ResolveNames::new(address_book);
+        self
+    }
+
     #[cfg(with_metrics)]
     pub fn metrics_address(&self) -> SocketAddr {
         SocketAddr::from(([0, 0, 0, 0], self.metrics_port.get()))
@@ -1318,6 +1748,8 @@ where
             context: Arc::clone(&self.context),
             port: self.port,
             default_chain: self.default_chain,
+            drafts: self.drafts.clone(),
+            scheduler: self.scheduler.clone(),
         };
         let subscription = SubscriptionRoot {
             context: Arc::clone(&self.context),
@@ -1326,16 +1758,23 @@ where
         };
 
         if self.read_only {
-            NodeServiceSchema::ReadOnly(Schema::build(query, EmptyMutation, subscription).finish())
+            NodeServiceSchema::ReadOnly(
+                Schema::build(query, EmptyMutation, subscription)
+                    .extension(self.names.clone())
+                    .finish(),
+            )
         } else {
             NodeServiceSchema::Full(
                 Schema::build(
                     query,
                     MutationRoot {
                         context: Arc::clone(&self.context),
+                        drafts: self.drafts.clone(),
+                        scheduler: self.scheduler.clone(),
                     },
                     subscription,
                 )
+                .extension(self.names.clone())
                 .finish(),
             )
         }
@@ -1425,6 +1864,7 @@ where
                 command_receiver,
                 true,
             )
+            .with_scheduler(self.scheduler.clone())
             .run()
             .await?;
             let mut chain_listener = Box::pin(chain_listener).fuse();
@@ -15,10 +15,16 @@ use std::{
 
 use anyhow::Result;
 use async_trait::async_trait;
-use futures::{future::BoxFuture, FutureExt as _};
+use futures::{
+    future::{self, BoxFuture},
+    FutureExt as _,
+};
 use linera_base::identifiers::ChainId;
 use linera_core::{
-    data_types::CertificatesByHeightRequest, notifier::ChannelNotifier, JoinSetExt as _,
+    data_types::{CertificatesByHeightRequest, ChainSummary},
+    node::NodeError,
+    notifier::ChannelNotifier,
+    JoinSetExt as _,
 };
 #[cfg(with_metrics)]
 use linera_metrics::monitoring_server;
@@ -27,6 +33,7 @@ use linera_rpc::propagation::get_traffic_type_from_request;
 #[cfg(feature = "opentelemetry")]
 use linera_rpc::propagation::OtelContextLayer;
 use linera_rpc::{
+    compression::Compression,
     config::{ProxyConfig, ShardConfig, TlsConfig, ValidatorInternalNetworkConfig},
     grpc::{
         api::{
@@ -40,7 +47,10 @@ use linera_rpc::{
             PendingBlobRequest, PendingBlobResult, RawCertificate, RawCertificatesBatch,
             SubscriptionRequest, VersionInfo,
         },
+        compressible_response,
         pool::GrpcConnectionPool,
+        rate_limited_status,
+        tls::MutualTls,
         GrpcProtoConversionError, GrpcProxyable, GRPC_CHUNKED_MESSAGE_FILL_LIMIT,
         GRPC_MAX_MESSAGE_SIZE,
     },
@@ -52,13 +62,15 @@ use tokio::{select, task::JoinSet};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tokio_util::sync::CancellationToken;
 use tonic::{
-    transport::{Channel, Identity, Server, ServerTlsConfig},
+    transport::{server::TcpIncoming, Channel, Identity, Server, ServerTlsConfig},
     Request, Response, Status,
 };
 use tonic_web::GrpcWebLayer;
 use tower::{builder::ServiceBuilder, Layer, Service};
 use tracing::{debug, info, instrument, Instrument as _, Level};
 
+use crate::rate_limit::{RateLimiters, RequestKind};
+
 #[cfg(with_metrics)]
 mod metrics {
     use std::sync::LazyLock;
@@ -209,12 +221,15 @@ struct GrpcProxyInner<S> {
     tls: TlsConfig,
     storage: S,
     id: usize,
+    rate_limiters: RateLimiters,
+    mutual_tls: Option<Arc<MutualTls>>,
 }
 
 impl<S> GrpcProxy<S>
 where
     S: Storage + Clone + Send + Sync + 'static,
 {
+    #[expect(clippy::too_many_arguments)]
     pub fn new(
         internal_config: ValidatorInternalNetworkConfig,
         connect_timeout: Duration,
@@ -222,16 +237,21 @@ where
         tls: TlsConfig,
         storage: S,
         id: usize,
+        rate_limiters: RateLimiters,
+        mutual_tls: Option<Arc<MutualTls>>,
     ) -> Self {
         Self(Arc::new(GrpcProxyInner {
             internal_config,
             worker_connection_pool: GrpcConnectionPool::default()
                 .with_connect_timeout(connect_timeout)
-                .with_timeout(timeout),
+                .with_timeout(timeout)
+                .with_mutual_tls(mutual_tls.clone()),
             notifier: ChannelNotifier::default(),
             tls,
             storage,
             id,
+            rate_limiters,
+            mutual_tls,
         }))
     }
 
@@ -239,6 +259,8 @@ where
         ValidatorNodeServer::new(self.clone())
             .max_encoding_message_size(GRPC_MAX_MESSAGE_SIZE)
             .max_decoding_message_size(GRPC_MAX_MESSAGE_SIZE)
+            .send_compressed(Compression::Gzip.grpc_encoding())
+            .send_compressed(Compression::Zstd.grpc_encoding())
     }
 
     fn config(&self) -> &ProxyConfig {
@@ -278,7 +300,7 @@ where
         &self,
         shard: &ShardConfig,
     ) -> Result<ValidatorWorkerClient<Channel>> {
-        let address = shard.http_address();
+        let address = self.0.internal_config.shard_address(shard);
         let channel = self.0.worker_connection_pool.channel(address)?;
         let client = ValidatorWorkerClient::new(channel)
             .max_encoding_message_size(GRPC_MAX_MESSAGE_SIZE)
@@ -320,12 +342,22 @@ where
         health_reporter
             .set_serving::<ValidatorNodeServer<GrpcProxy<S>>>()
             .await;
-        let internal_server = join_set.spawn_task(
-            Server::builder()
-                .add_service(self.as_notifier_service())
-                .serve(self.internal_address())
-                .in_current_span(),
-        );
+        let internal_router = Server::builder().add_service(self.as_notifier_service());
+        let internal_server = match &self.0.mutual_tls {
+            Some(mutual_tls) => {
+                let incoming = TcpIncoming::bind(self.internal_address())?.with_nodelay(Some(true));
+                join_set.spawn_task(
+                    internal_router
+                        .serve_with_incoming(mutual_tls.clone().incoming(incoming))
+                        .in_current_span(),
+                )
+            }
+            None => join_set.spawn_task(
+                internal_router
+                    .serve(self.internal_address())
+                    .in_current_span(),
+            ),
+        };
         let reflection_service = tonic_reflection::server::Builder::configure()
             .register_encoded_file_descriptor_set(linera_rpc::FILE_DESCRIPTOR_SET)
             .build_v1()?;
@@ -386,6 +418,50 @@ where
         }
     }
 
+    /// Rejects the request if its client, or the chain it is about, exceeded the rate limit
+    /// for this kind of request. Only the client's quota is consumed here.
+    fn check_rate_limit<R>(
+        &self,
+        kind: RequestKind,
+        request: &Request<R>,
+        chain_id: Option<ChainId>,
+    ) -> Result<(), Status> {
+        let client = request.remote_addr().map(|address| address.ip());
+        self.0
+            .rate_limiters
+            .check(kind, client, chain_id)
+            .map_err(rate_limited_status)
+    }
+
+    /// Like [`Self::check_rate_limit`], for requests forwarded to the shard of their chain.
+    fn check_proxyable_rate_limit<R: GrpcProxyable>(
+        &self,
+        kind: RequestKind,
+        request: &Request<R>,
+    ) -> Result<(), Status> {
+        self.check_rate_limit(kind, request, request.get_ref().chain_id())
+    }
+
+    /// Consumes the chain's quota for a request, if its shard accepted it. Invalid requests
+    /// are not charged, so that other clients cannot exhaust the quota of a chain.
+    fn charge_chain_rate_limit(
+        &self,
+        kind: RequestKind,
+        chain_id: Option<ChainId>,
+        result: &Result<Response<ChainInfoResult>, Status>,
+    ) {
+        let Some(chain_id) = chain_id else {
+            return;
+        };
+        if let Ok(response) = result {
+            if let Some(api::chain_info_result::Inner::ChainInfoResponse(_)) =
+                &response.get_ref().inner
+            {
+                self.0.rate_limiters.charge_chain(kind, chain_id);
+            }
+        }
+    }
+
     #[instrument(skip_all, fields(remote_addr = ?request.remote_addr(), chain_id = ?request.get_ref().chain_id()))]
     fn worker_client<R>(
         &self,
@@ -457,10 +533,14 @@ where
         &self,
         request: Request<BlockProposal>,
     ) -> Result<Response<ChainInfoResult>, Status> {
+        self.check_proxyable_rate_limit(RequestKind::Proposal, &request)?;
+        let chain_id = request.get_ref().chain_id();
         let (mut client, inner) = self.worker_client(request)?;
-        client
+        let result = client
             .handle_block_proposal(Self::create_forwarding_request(inner))
-            .await
+            .await;
+        self.charge_chain_rate_limit(RequestKind::Proposal, chain_id, &result);
+        result
     }
 
     #[instrument(skip_all, err(Display), fields(method = "handle_lite_certificate"))]
@@ -520,12 +600,65 @@ where
         &self,
         request: Request<api::ChainInfoQuery>,
     ) -> Result<Response<ChainInfoResult>, Status> {
+        self.check_rate_limit(RequestKind::ChainInfo, &request, None)?;
         let (mut client, inner) = self.worker_client(request)?;
         client
             .handle_chain_info_query(Self::create_forwarding_request(inner))
             .await
     }
 
+    #[instrument(
+        skip_all,
+        err(Display),
+        fields(method = "handle_chain_summaries_query")
+    )]
+    async fn handle_chain_summaries_query(
+        &self,
+        request: Request<api::ChainSummariesQuery>,
+    ) -> Result<Response<api::ChainSummariesResult>, Status> {
+        let client = request.remote_addr().map(|address| address.ip());
+        let chain_ids: Vec<ChainId> = request.into_inner().try_into()?;
+        if let Err(error) = NodeError::check_chain_summaries_query(&chain_ids) {
+            return Ok(Response::new(error.try_into()?));
+        }
+        // Each chain counts as a chain info query.
+        self.0
+            .rate_limiters
+            .check_batch(RequestKind::ChainInfo, client, chain_ids.len())
+            .map_err(rate_limited_status)?;
+        let groups = self.0.internal_config.group_by_shard(&chain_ids);
+        let responses =
+            future::try_join_all(groups.into_iter().map(|(shard_id, group)| async move {
+                let (indices, shard_chain_ids): (Vec<_>, Vec<_>) = group.into_iter().unzip();
+                let mut client = self
+                    .worker_client_for_shard(self.0.internal_config.shard(shard_id))
+                    .map_err(|_| Status::internal("could not connect to shard"))?;
+                let query = api::ChainSummariesQuery::from(shard_chain_ids);
+                let response = client
+                    .handle_chain_summaries_query(Self::create_forwarding_request(query))
+                    .await?;
+                Ok::<_, Status>((indices, response.into_inner()))
+            }))
+            .await?;
+        let mut summaries = vec![None; chain_ids.len()];
+        for (indices, result) in responses {
+            let shard_summaries = match Vec::<Result<ChainSummary, NodeError>>::try_from(result) {
+                Ok(shard_summaries) => shard_summaries,
+                Err(error) => return Ok(Response::new(error.try_into()?)),
+            };
+            if shard_summaries.len() != indices.len() {
+                return Err(Status::internal(
+                    "shard sent the wrong number of chain summaries",
+                ));
+            }
+            for (index, summary) in indices.into_iter().zip(shard_summaries) {
+                summaries[index] = Some(summary);
+            }
+        }
+        let summaries = summaries.into_iter().flatten().collect::<Vec<_>>();
+        Ok(Response::new(summaries.try_into()?))
+    }
+
     #[instrument(skip_all, err(Display), fields(method = "subscribe"))]
     async fn subscribe(
         &self,
@@ -589,6 +722,7 @@ where
 
     #[instrument(skip_all, err(Display), fields(method = "upload_blob"))]
     async fn upload_blob(&self, request: Request<BlobContent>) -> Result<Response<BlobId>, Status> {
+        self.check_rate_limit(RequestKind::BlobUpload, &request, None)?;
         let content: linera_sdk::linera_base_types::BlobContent =
             request.into_inner().try_into()?;
         let blob = Blob::new(content);
@@ -605,6 +739,7 @@ where
         &self,
         request: Request<BlobId>,
     ) -> Result<Response<BlobContent>, Status> {
+        self.check_rate_limit(RequestKind::Download, &request, None)?;
         let blob_id = request.into_inner().try_into()?;
         let blob = self
             .0
@@ -615,7 +750,7 @@ where
         let blob = blob
             .map(Arc::unwrap_or_clone)
             .ok_or_else(|| Status::not_found(format!("Blob not found {blob_id}")))?;
-        Ok(Response::new(blob.into_content().try_into()?))
+        Ok(compressible_response(blob.into_content().try_into()?))
     }
 
     #[instrument(skip_all, err(Display), fields(method = "download_blobs"))]
@@ -623,6 +758,7 @@ where
         &self,
         request: Request<BlobIds>,
     ) -> Result<Response<Self::DownloadBlobsStream>, Status> {
+        self.check_rate_limit(RequestKind::Download, &request, None)?;
         let blob_ids = Vec::<linera_base::identifiers::BlobId>::try_from(request.into_inner())?;
         let blobs = self
             .0
@@ -647,6 +783,7 @@ where
         &self,
         request: Request<PendingBlobRequest>,
     ) -> Result<Response<PendingBlobResult>, Status> {
+        self.check_rate_limit(RequestKind::Download, &request, None)?;
         let (mut client, inner) = self.worker_client(request)?;
         client.download_pending_blob(inner).await
     }
@@ -656,8 +793,12 @@ where
         &self,
         request: Request<HandlePendingBlobRequest>,
     ) -> Result<Response<ChainInfoResult>, Status> {
+        self.check_proxyable_rate_limit(RequestKind::BlobUpload, &request)?;
+        let chain_id = request.get_ref().chain_id();
         let (mut client, inner) = self.worker_client(request)?;
-        client.handle_pending_blob(inner).await
+        let result = client.handle_pending_blob(inner).await;
+        self.charge_chain_rate_limit(RequestKind::BlobUpload, chain_id, &result);
+        result
     }
 
     #[instrument(skip_all, err(Display), fields(method = "download_certificate"))]
@@ -665,6 +806,7 @@ where
         &self,
         request: Request<CryptoHash>,
     ) -> Result<Response<Certificate>, Status> {
+        self.check_rate_limit(RequestKind::Download, &request, None)?;
         let hash = request.into_inner().try_into()?;
         let certificate: linera_chain::types::Certificate = self
             .0
@@ -675,7 +817,7 @@ where
             .ok_or_else(|| Status::not_found(hash.to_string()))?
             .as_ref()
             .into();
-        Ok(Response::new(certificate.try_into()?))
+        Ok(compressible_response(certificate.try_into()?))
     }
 
     #[instrument(skip_all, err(Display), fields(method = "download_certificates"))]
@@ -683,6 +825,7 @@ where
         &self,
         request: Request<CertificatesBatchRequest>,
     ) -> Result<Response<CertificatesBatchResponse>, Status> {
+        self.check_rate_limit(RequestKind::Download, &request, None)?;
         let hashes: Vec<linera_base::crypto::CryptoHash> = request
             .into_inner()
             .hashes
@@ -717,7 +860,7 @@ where
             }
         }
 
-        Ok(Response::new(CertificatesBatchResponse::try_from(
+        Ok(compressible_response(CertificatesBatchResponse::try_from(
             returned_certificates,
         )?))
     }
@@ -731,6 +874,7 @@ where
         &self,
         request: Request<api::DownloadCertificatesByHeightsRequest>,
     ) -> Result<Response<CertificatesBatchResponse>, Status> {
+        self.check_rate_limit(RequestKind::Download, &request, None)?;
         let original_request: CertificatesByHeightRequest = request.into_inner().try_into()?;
         let chain_id = original_request.chain_id;
         let heights = original_request.heights;
@@ -754,7 +898,7 @@ where
                 Ok(lim.fits::<Certificate>(cert.clone())?.then_some(cert))
             })?;
 
-        Ok(Response::new(CertificatesBatchResponse::try_from(
+        Ok(compressible_response(CertificatesBatchResponse::try_from(
             returned_certificates,
         )?))
     }
@@ -764,6 +908,7 @@ where
         &self,
         request: Request<api::DownloadCertificatesByHeightsRequest>,
     ) -> Result<Response<api::RawCertificatesBatch>, Status> {
+        self.check_rate_limit(RequestKind::Download, &request, None)?;
         let original_request: CertificatesByHeightRequest = request.into_inner().try_into()?;
         let chain_id = original_request.chain_id;
         let heights = original_request.heights;
@@ -791,7 +936,7 @@ where
                 }))
         })?;
 
-        Ok(Response::new(RawCertificatesBatch { certificates }))
+        Ok(compressible_response(RawCertificatesBatch { certificates }))
     }
 
     #[instrument(skip_all, err(level = Level::WARN), fields(
@@ -15,13 +15,13 @@ use std::{net::SocketAddr, path::PathBuf, pin::Pin, sync::Arc, time::Duration};
 
 use anyhow::{anyhow, bail, ensure, Result};
 use async_trait::async_trait;
-use futures::{stream::SelectAll, FutureExt as _, SinkExt, Stream, StreamExt};
+use futures::{future, stream::SelectAll, FutureExt as _, SinkExt, Stream, StreamExt};
 use linera_base::{
     identifiers::{BlobId, ChainId},
     listen_for_shutdown_signals,
 };
 use linera_client::config::ValidatorServerConfig;
-use linera_core::{node::NodeError, JoinSetExt as _};
+use linera_core::{data_types::ChainSummary, node::NodeError, JoinSetExt as _};
 #[cfg(with_metrics)]
 use linera_metrics::monitoring_server;
 use linera_rpc::{
@@ -29,7 +29,8 @@ use linera_rpc::{
         NetworkProtocol, ShardConfig, ValidatorInternalNetworkPreConfig,
         ValidatorPublicNetworkPreConfig,
     },
-    simple::{MessageHandler, TransportProtocol},
+    grpc::tls::MutualTls,
+    simple::{Connector, MessageHandler, TransportProtocol},
     RpcMessage,
 };
 use linera_sdk::linera_base_types::Blob;
@@ -43,7 +44,9 @@ use tokio_util::sync::CancellationToken;
 use tracing::{error, info, instrument};
 
 mod grpc;
+mod rate_limit;
 use grpc::GrpcProxy;
+use rate_limit::{RateLimitOptions, RateLimiters};
 
 /// Options for running the proxy.
 #[derive(clap::Parser, Debug, Clone)]
@@ -86,6 +89,10 @@ pub struct ProxyOptions {
     #[command(flatten)]
     common_storage_options: CommonStorageOptions,
 
+    /// Rate limits on the requests of clients.
+    #[command(flatten)]
+    rate_limits: RateLimitOptions,
+
     /// Runs a specific proxy instance.
     #[arg(long)]
     id: Option<usize>,
@@ -130,6 +137,7 @@ struct ProxyContext {
     recv_timeout: Duration,
     id: usize,
     enable_memory_profiling: bool,
+    rate_limits: RateLimitOptions,
 }
 
 impl ProxyContext {
@@ -142,6 +150,7 @@ impl ProxyContext {
             recv_timeout: options.recv_timeout,
             id: options.id.unwrap_or(0),
             enable_memory_profiling: options.enable_memory_profiling(),
+            rate_limits: options.rate_limits.clone(),
         })
     }
 }
@@ -182,6 +191,7 @@ where
     fn from_context(context: ProxyContext, storage: S) -> Result<Self> {
         let internal_protocol = context.config.internal_network.protocol;
         let external_protocol = context.config.validator.network.protocol;
+        let mutual_tls = MutualTls::for_network(&context.config.internal_network)?;
         let proxy = match (internal_protocol, external_protocol) {
             (NetworkProtocol::Grpc { .. }, NetworkProtocol::Grpc(tls)) => {
                 Self::Grpc(GrpcProxy::new(
@@ -191,6 +201,8 @@ where
                     tls,
                     storage,
                     context.id,
+                    RateLimiters::new(context.rate_limits),
+                    mutual_tls,
                 ))
             }
             (
@@ -201,6 +213,7 @@ where
                     .config
                     .internal_network
                     .clone_with_protocol(internal_transport),
+                connector: internal_transport.connector(),
                 public_config: context
                     .config
                     .validator
@@ -231,6 +244,7 @@ where
 {
     public_config: ValidatorPublicNetworkPreConfig<TransportProtocol>,
     internal_config: ValidatorInternalNetworkPreConfig<TransportProtocol>,
+    connector: Connector,
     send_timeout: Duration,
     recv_timeout: Duration,
     storage: S,
@@ -262,12 +276,11 @@ where
         };
 
         let shard = self.internal_config.get_shard_for(chain_id).clone();
-        let protocol = self.internal_config.protocol;
 
         match Self::try_proxy_message(
             message,
             shard.clone(),
-            protocol,
+            &self.connector,
             self.send_timeout,
             self.recv_timeout,
         )
@@ -375,7 +388,6 @@ where
         &self,
         chains: Vec<ChainId>,
     ) -> Option<Pin<Box<dyn Stream<Item = RpcMessage> + Send>>> {
-        let protocol = self.internal_config.protocol;
         let mut select_all = SelectAll::new();
         // Group chains by their owning shard.
         let mut chains_by_shard = std::collections::HashMap::<usize, Vec<ChainId>>::new();
@@ -386,8 +398,7 @@ where
         // Only connect to shards that own at least one requested chain.
         for (shard_id, shard_chains) in chains_by_shard {
             let shard = &self.internal_config.shards[shard_id];
-            let address = (shard.host.clone(), shard.port);
-            match protocol.connect(address).await {
+            match self.connector.connect(&shard.host, shard.port).await {
                 Ok(mut connection) => {
                     let subscribe_msg = RpcMessage::SubscribeNotifications(shard_chains);
                     if let Err(error) = connection.send(subscribe_msg).await {
@@ -423,11 +434,11 @@ where
     async fn try_proxy_message(
         message: RpcMessage,
         shard: ShardConfig,
-        protocol: TransportProtocol,
+        connector: &Connector,
         send_timeout: Duration,
         recv_timeout: Duration,
     ) -> Result<Option<RpcMessage>> {
-        let mut connection = protocol.connect((shard.host, shard.port)).await?;
+        let mut connection = connector.connect(&shard.host, shard.port).await?;
         linera_base::time::timer::timeout(send_timeout, connection.send(message)).await??;
         let message = linera_base::time::timer::timeout(recv_timeout, connection.next())
             .await?
@@ -500,7 +511,6 @@ where
             }
             DownloadCertificatesByHeights(chain_id, heights) => {
                 let shard = self.internal_config.get_shard_for(chain_id).clone();
-                let protocol = self.internal_config.protocol;
 
                 let chain_info_query = RpcMessage::ChainInfoQuery(Box::new(
                     linera_core::data_types::ChainInfoQuery::new(chain_id)
@@ -510,7 +520,7 @@ where
                 let hashes = match Self::try_proxy_message(
                     chain_info_query,
                     shard.clone(),
-                    protocol,
+                    &self.connector,
                     self.send_timeout,
                     self.recv_timeout,
                 )
@@ -565,6 +575,48 @@ where
             EventBlockHeights(event_ids) => Ok(Some(RpcMessage::EventBlockHeightsResponse(
                 self.storage.read_event_block_heights(&event_ids).await?,
             ))),
+            ChainSummariesQuery(chain_ids) => {
+                if let Err(error) = NodeError::check_chain_summaries_query(&chain_ids) {
+                    return Ok(Some(error.into()));
+                }
+                let groups = self.internal_config.group_by_shard(&chain_ids);
+                let responses =
+                    future::try_join_all(groups.into_iter().map(|(shard_id, group)| async move {
+                        let (indices, shard_chain_ids): (Vec<_>, Vec<_>) =
+                            group.into_iter().unzip();
+                        let response = Self::try_proxy_message(
+                            RpcMessage::ChainSummariesQuery(shard_chain_ids),
+                            self.internal_config.shard(shard_id).clone(),
+                            &self.connector,
+                            self.send_timeout,
+                            self.recv_timeout,
+                        )
+                        .await?
+                        .ok_or_else(|| anyhow!("Shard {shard_id} did not send chain summaries"))?;
+                        Ok::<_, anyhow::Error>((indices, response))
+                    }))
+                    .await?;
+                let mut summaries = vec![None; chain_ids.len()];
+                for (indices, response) in responses {
+                    let shard_summaries =
+                        match Vec::<Result<ChainSummary, NodeError>>::try_from(response) {
+                            Ok(shard_summaries) => shard_summaries,
+                            Err(error) => return Ok(Some(error.into())),
+                        };
+                    ensure!(
+                        shard_summaries.len() == indices.len(),
+                        "Shard sent {} chain summaries instead of {}",
+                        shard_summaries.len(),
+                        indices.len()
+                    );
+                    for (index, summary) in indices.into_iter().zip(shard_summaries) {
+                        summaries[index] = Some(summary);
+                    }
+                }
+                Ok(Some(ChainSummariesResponse(
+                    summaries.into_iter().flatten().collect(),
+                )))
+            }
             BlockProposal(_)
             | LiteCertificate(_)
             | TimeoutCertificate(_)
@@ -592,7 +644,8 @@ where
             | UploadBlobResponse(_)
             | DownloadCertificatesByHeightsResponse(_)
             | SubscribeNotifications(_)
-            | Notification(_) => Err(anyhow::Error::from(NodeError::UnexpectedMessage)),
+            | Notification(_)
+            | ChainSummariesResponse(_) => Err(anyhow::Error::from(NodeError::UnexpectedMessage)),
         }
     }
 }
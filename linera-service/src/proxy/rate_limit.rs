@@ -0,0 +1,366 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Token-bucket rate limiting of the requests received by the proxy.
+
+use std::{
+    hash::Hash,
+    net::IpAddr,
+    num::NonZeroUsize,
+    str::FromStr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::{ensure, Context as _};
+use linera_base::identifiers::ChainId;
+use lru::LruCache;
+
+/// The maximum number of buckets. The least recently used ones are dropped beyond that.
+const MAX_BUCKETS: NonZeroUsize = NonZeroUsize::new(100_000).unwrap();
+
+/// A token-bucket limit: a sustained number of requests per second, and a burst size.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    /// The number of tokens added to a bucket every second.
+    requests_per_second: f64,
+    /// The capacity of a bucket.
+    burst: f64,
+}
+
+impl FromStr for RateLimit {
+    type Err = anyhow::Error;
+
+    /// Parses `<REQUESTS_PER_SECOND>` or `<REQUESTS_PER_SECOND>:<BURST>`. The burst
+    /// defaults to one second's worth of requests.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (rate, burst) = match s.split_once(':') {
+            Some((rate, burst)) => (rate, Some(burst)),
+            None => (s, None),
+        };
+        let requests_per_second: f64 = rate.parse().context("Invalid request rate")?;
+        let burst = match burst {
+            Some(burst) => burst.parse().context("Invalid burst size")?,
+            None => requests_per_second.max(1.0),
+        };
+        ensure!(
+            requests_per_second > 0.0 && burst >= 1.0,
+            "The request rate must be positive and the burst size at least 1"
+        );
+        Ok(Self {
+            requests_per_second,
+            burst,
+        })
+    }
+}
+
+/// The rate limits applied by the proxy, per client IP address and per chain. They are only
+/// enforced by the gRPC proxy.
+///
+/// Anyone can send a request about any chain, so a chain's quota is only consumed by the
+/// requests that its shard accepted: invalid requests only count against their client.
+#[derive(Clone, Debug, Default, clap::Parser)]
+pub struct RateLimitOptions {
+    /// Limit on block proposals, per client and per chain, as
+    /// `<REQUESTS_PER_SECOND>[:<BURST>]`.
+    #[arg(long, env = "LINERA_PROXY_PROPOSAL_RATE_LIMIT")]
+    pub proposal_rate_limit: Option<RateLimit>,
+
+    /// Limit on chain info queries, per client, as
+    /// `<REQUESTS_PER_SECOND>[:<BURST>]`. A chain summaries query counts once per chain.
+    #[arg(long, env = "LINERA_PROXY_CHAIN_INFO_RATE_LIMIT")]
+    pub chain_info_rate_limit: Option<RateLimit>,
+
+    /// Limit on blob uploads, per client and per chain, as
+    /// `<REQUESTS_PER_SECOND>[:<BURST>]`.
+    #[arg(long, env = "LINERA_PROXY_BLOB_UPLOAD_RATE_LIMIT")]
+    pub blob_upload_rate_limit: Option<RateLimit>,
+
+    /// Limit on certificate and blob downloads, per client, as
+    /// `<REQUESTS_PER_SECOND>[:<BURST>]`.
+    #[arg(long, env = "LINERA_PROXY_DOWNLOAD_RATE_LIMIT")]
+    pub download_rate_limit: Option<RateLimit>,
+}
+
+/// The kinds of requests with separate rate limits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RequestKind {
+    Proposal,
+    ChainInfo,
+    BlobUpload,
+    Download,
+}
+
+/// Who a token bucket is for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Subject {
+    Client(IpAddr),
+    Chain(ChainId),
+}
+
+/// The rate limiters of the proxy.
+pub struct RateLimiters {
+    options: RateLimitOptions,
+    buckets: Mutex<Buckets<(RequestKind, Subject)>>,
+}
+
+impl RateLimiters {
+    pub fn new(options: RateLimitOptions) -> Self {
+        Self {
+            options,
+            buckets: Mutex::default(),
+        }
+    }
+
+    fn limit(&self, kind: RequestKind) -> Option<RateLimit> {
+        match kind {
+            RequestKind::Proposal => self.options.proposal_rate_limit,
+            RequestKind::ChainInfo => self.options.chain_info_rate_limit,
+            RequestKind::BlobUpload => self.options.blob_upload_rate_limit,
+            RequestKind::Download => self.options.download_rate_limit,
+        }
+    }
+
+    /// Takes a token for a request of the given kind from the client, if the client and the
+    /// chain, if known, are both within their limit. Otherwise returns how long to wait
+    /// until the request would be accepted.
+    ///
+    /// The chain's token is only taken by [`Self::charge_chain`], once the request has been
+    /// accepted.
+    pub fn check(
+        &self,
+        kind: RequestKind,
+        client: Option<IpAddr>,
+        chain_id: Option<ChainId>,
+    ) -> Result<(), Duration> {
+        let Some(limit) = self.limit(kind) else {
+            return Ok(());
+        };
+        let mut buckets = self.buckets.lock().unwrap();
+        let now = Instant::now();
+        // Check all buckets before taking any token, so that a rejected request doesn't
+        // consume the quota of the client.
+        if let Some(retry_after) = client
+            .map(Subject::Client)
+            .into_iter()
+            .chain(chain_id.map(Subject::Chain))
+            .filter_map(|subject| buckets.wait_time(&(kind, subject), 1.0, now))
+            .max()
+        {
+            return Err(retry_after);
+        }
+        if let Some(client) = client {
+            buckets.take((kind, Subject::Client(client)), &limit, 1.0, now);
+        }
+        Ok(())
+    }
+
+    /// Takes one token per request from the client, for a batch of `count` requests of the
+    /// given kind. A batch larger than the burst size is accepted when the client's bucket is
+    /// full, and leaves it in debt.
+    pub fn check_batch(
+        &self,
+        kind: RequestKind,
+        client: Option<IpAddr>,
+        count: usize,
+    ) -> Result<(), Duration> {
+        let (Some(limit), Some(client)) = (self.limit(kind), client) else {
+            return Ok(());
+        };
+        let key = (kind, Subject::Client(client));
+        let cost = count as f64;
+        let mut buckets = self.buckets.lock().unwrap();
+        let now = Instant::now();
+        if let Some(retry_after) = buckets.wait_time(&key, cost.min(limit.burst), now) {
+            return Err(retry_after);
+        }
+        buckets.take(key, &limit, cost, now);
+        Ok(())
+    }
+
+    /// Takes a token for a request of the given kind that was accepted for the chain.
+    pub fn charge_chain(&self, kind: RequestKind, chain_id: ChainId) {
+        let Some(limit) = self.limit(kind) else {
+            return;
+        };
+        let mut buckets = self.buckets.lock().unwrap();
+        buckets.take(
+            (kind, Subject::Chain(chain_id)),
+            &limit,
+            1.0,
+            Instant::now(),
+        );
+    }
+}
+
+/// A bounded set of token buckets. Dropping a bucket only forgets the requests it counted.
+struct Buckets<K: Hash + Eq> {
+    buckets: LruCache<K, TokenBucket>,
+}
+
+impl<K: Hash + Eq> Default for Buckets<K> {
+    fn default() -> Self {
+        Self {
+            buckets: LruCache::new(MAX_BUCKETS),
+        }
+    }
+}
+
+impl<K: Eq + Hash> Buckets<K> {
+    /// Returns how long to wait until the bucket for `key` has `tokens` tokens, if it has
+    /// fewer.
+    fn wait_time(&mut self, key: &K, tokens: f64, now: Instant) -> Option<Duration> {
+        let bucket = self.buckets.get_mut(key)?;
+        bucket.refill(now);
+        (bucket.tokens < tokens).then(|| {
+            Duration::from_secs_f64((tokens - bucket.tokens) / bucket.limit.requests_per_second)
+        })
+    }
+
+    /// Takes `tokens` tokens from the bucket for `key`. Concurrent requests that were all
+    /// checked before being charged can leave the bucket in debt, which delays the next ones.
+    fn take(&mut self, key: K, limit: &RateLimit, tokens: f64, now: Instant) {
+        let bucket = self.buckets.get_or_insert_mut(key, || TokenBucket {
+            limit: *limit,
+            tokens: limit.burst,
+            last_refill: now,
+        });
+        bucket.refill(now);
+        bucket.tokens -= tokens;
+    }
+}
+
+/// A bucket of tokens, refilled continuously at the rate of its limit.
+struct TokenBucket {
+    limit: RateLimit,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.limit.requests_per_second)
+            .min(self.limit.burst);
+        self.last_refill = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use linera_base::crypto::CryptoHash;
+
+    use super::*;
+
+    fn limiters(proposal_rate_limit: &str) -> RateLimiters {
+        RateLimiters::new(RateLimitOptions {
+            proposal_rate_limit: Some(proposal_rate_limit.parse().unwrap()),
+            ..RateLimitOptions::default()
+        })
+    }
+
+    #[test]
+    fn parses_rate_limits() {
+        let limit: RateLimit = "2.5:10".parse().unwrap();
+        assert_eq!(
+            limit,
+            RateLimit {
+                requests_per_second: 2.5,
+                burst: 10.0
+            }
+        );
+        let limit: RateLimit = "0.5".parse().unwrap();
+        assert_eq!(limit.burst, 1.0);
+        assert!("0".parse::<RateLimit>().is_err());
+        assert!("1:0".parse::<RateLimit>().is_err());
+    }
+
+    #[test]
+    fn limits_each_client_and_chain() {
+        let limiters = limiters("1:2");
+        let client = Some(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let other_client = Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+        let chain_id = ChainId(CryptoHash::test_hash("chain"));
+
+        assert!(limiters.check(RequestKind::Proposal, client, None).is_ok());
+        assert!(limiters
+            .check(RequestKind::Proposal, client, Some(chain_id))
+            .is_ok());
+        limiters.charge_chain(RequestKind::Proposal, chain_id);
+        let retry_after = limiters
+            .check(RequestKind::Proposal, client, None)
+            .unwrap_err();
+        assert!(retry_after > Duration::ZERO && retry_after <= Duration::from_secs(1));
+
+        // Another client can still send a request, but the chain's quota is exhausted.
+        assert!(limiters
+            .check(RequestKind::Proposal, other_client, Some(chain_id))
+            .is_ok());
+        limiters.charge_chain(RequestKind::Proposal, chain_id);
+        assert!(limiters
+            .check(RequestKind::Proposal, other_client, Some(chain_id))
+            .is_err());
+
+        // Other kinds of requests are not limited.
+        assert!(limiters.check(RequestKind::Download, client, None).is_ok());
+    }
+
+    #[test]
+    fn only_accepted_requests_consume_the_chain_quota() {
+        let limiters = limiters("1:1");
+        let chain_id = Some(ChainId(CryptoHash::test_hash("chain")));
+        let attacker = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let client = Some(IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+        // The attacker's invalid requests are never charged to the chain.
+        assert!(limiters
+            .check(RequestKind::Proposal, Some(attacker), chain_id)
+            .is_ok());
+        assert!(limiters
+            .check(RequestKind::Proposal, Some(attacker), chain_id)
+            .is_err());
+        assert!(limiters
+            .check(RequestKind::Proposal, client, chain_id)
+            .is_ok());
+    }
+
+    #[test]
+    fn batches_cost_one_token_per_request() {
+        let limiters = RateLimiters::new(RateLimitOptions {
+            chain_info_rate_limit: Some("1:10".parse().unwrap()),
+            ..RateLimitOptions::default()
+        });
+        let client = Some(IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+        assert!(limiters
+            .check_batch(RequestKind::ChainInfo, client, 6)
+            .is_ok());
+        // Four tokens are left: not enough for another batch of six.
+        let retry_after = limiters
+            .check_batch(RequestKind::ChainInfo, client, 6)
+            .unwrap_err();
+        assert!(retry_after > Duration::from_secs(1) && retry_after <= Duration::from_secs(2));
+        assert!(limiters
+            .check_batch(RequestKind::ChainInfo, client, 4)
+            .is_ok());
+        assert!(limiters
+            .check(RequestKind::ChainInfo, client, None)
+            .is_err());
+    }
+
+    #[test]
+    fn rejected_requests_do_not_consume_tokens() {
+        let limiters = limiters("1:1");
+        let client = Some(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let chain_id = ChainId(CryptoHash::test_hash("chain"));
+
+        limiters.charge_chain(RequestKind::Proposal, chain_id);
+        // The chain is limited, so the client's token is not taken.
+        assert!(limiters
+            .check(RequestKind::Proposal, client, Some(chain_id))
+            .is_err());
+        assert!(limiters.check(RequestKind::Proposal, client, None).is_ok());
+    }
+}
@@ -178,6 +178,13 @@ impl ValidatorNode for DummyValidatorNode {
     ) -> Result<linera_core::data_types::ShardInfo, NodeError> {
         Err(NodeError::UnexpectedMessage)
     }
+
+    async fn handle_chain_summaries_query(
+        &self,
+        _: Vec<ChainId>,
+    ) -> Result<Vec<Result<linera_core::data_types::ChainSummary, NodeError>>, NodeError> {
+        Err(NodeError::UnexpectedMessage)
+    }
 }
 
 struct DummyValidatorNodeProvider;
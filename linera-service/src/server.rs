@@ -23,7 +23,7 @@ use std::{
     time::Duration,
 };
 
-use anyhow::{bail, Context};
+use anyhow::Context;
 use async_trait::async_trait;
 use futures::{stream::FuturesUnordered, FutureExt as _, StreamExt, TryFutureExt as _};
 use linera_base::{
@@ -41,11 +41,12 @@ use linera_metrics::monitoring_server;
 use linera_persistent::{self as persistent, Persist};
 use linera_rpc::{
     config::{
-        CrossChainConfig, ExporterServiceConfig, NetworkProtocol, NotificationConfig, ProxyConfig,
-        ShardConfig, ShardId, TlsConfig, ValidatorInternalNetworkConfig,
+        CrossChainConfig, ExporterServiceConfig, InternalTlsConfig, NetworkProtocol,
+        NotificationConfig, ProxyConfig, ShardConfig, ShardId, ValidatorInternalNetworkConfig,
         ValidatorPublicNetworkConfig,
     },
-    grpc, simple,
+    grpc::{self, tls::MutualTls},
+    simple,
 };
 use linera_sdk::linera_base_types::{AccountSecretKey, ValidatorKeypair};
 use linera_service::{
@@ -180,6 +181,7 @@ impl ServerContext {
         &self,
         listen_address: &str,
         states: Vec<(WorkerState<S>, ShardId, ShardConfig)>,
+        mutual_tls: Option<Arc<MutualTls>>,
         shutdown_signal: &CancellationToken,
         enable_memory_profiling: bool,
     ) -> JoinSet<()>
@@ -207,6 +209,7 @@ impl ServerContext {
                 self.server_config.internal_network.clone(),
                 &self.cross_chain_config,
                 &self.notification_config,
+                mutual_tls.clone(),
                 shutdown_signal.clone(),
                 &mut join_set,
             );
@@ -271,6 +274,7 @@ impl Runnable for ServerContext {
             }
         };
 
+        let mutual_tls = MutualTls::for_network(&self.server_config.internal_network)?;
         let mut join_set = match self.server_config.internal_network.protocol {
             NetworkProtocol::Simple(protocol) => self.spawn_simple(
                 &listen_address,
@@ -279,15 +283,13 @@ impl Runnable for ServerContext {
                 &shutdown_notifier,
                 enable_memory_profiling,
             ),
-            NetworkProtocol::Grpc(tls_config) => match tls_config {
-                TlsConfig::ClearText => self.spawn_grpc(
-                    &listen_address,
-                    states,
-                    &shutdown_notifier,
-                    enable_memory_profiling,
-                ),
-                TlsConfig::Tls => bail!("TLS not supported between proxy and shards."),
-            },
+            NetworkProtocol::Grpc(_) => self.spawn_grpc(
+                &listen_address,
+                states,
+                mutual_tls,
+                &shutdown_notifier,
+                enable_memory_profiling,
+            ),
         };
 
         join_set.await_all_tasks().await;
@@ -373,6 +375,10 @@ struct ValidatorOptions {
 
     /// The name and the port of the proxies
     proxies: Vec<ProxyConfig>,
+
+    /// The certificates for mutual TLS between the proxies and the shards.
+    #[serde(default)]
+    mutual_tls: Option<InternalTlsConfig>,
 }
 
 fn make_server_config<R: CryptoRng>(
@@ -394,6 +400,7 @@ fn make_server_config<R: CryptoRng>(
         shards: options.shards,
         block_exporters: options.block_exporters,
         proxies: options.proxies,
+        mutual_tls: options.mutual_tls,
     };
     let validator = ValidatorConfig {
         network,
@@ -841,6 +848,7 @@ mod test {
                         metrics_port: Some(5002),
                     },
                 ],
+                mutual_tls: None,
             }
         );
     }
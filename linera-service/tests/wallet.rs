@@ -1,11 +1,13 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::BTreeSet;
 use std::time::Duration;
 
 use linera_base::{
-    crypto::InMemorySigner,
+    crypto::{AccountSecretKey, CryptoHash, Ed25519SecretKey, InMemorySigner},
     data_types::{Amount, Blob, BlockHeight, Epoch},
+    identifiers::{Account, AccountOwner, ApplicationId, ChainId},
 };
 use linera_chain::data_types::ProposedBlock;
 use linera_client::client_context::ClientContext;
@@ -17,6 +19,7 @@ use linera_core::{
 };
 use linera_rpc::{NodeOptions, NodeProvider};
 use linera_service::Wallet;
+use linera_wallet_json::{address_book::Address, AddressBook, WalletBundle};
 
 pub async fn new_test_client_context(
     storage: impl linera_core::environment::Storage,
@@ -80,8 +83,11 @@ pub async fn new_test_client_context(
         retry_delay,
         max_retries,
         max_backoff: linera_rpc::node_provider::DEFAULT_MAX_BACKOFF,
+        compression: None,
         chain_listeners: JoinSet::default(),
         client_metrics: None,
+        validator_scores_path: None,
+        validator_scores_saver: None,
     })
 }
 
@@ -90,7 +96,7 @@ pub async fn new_test_client_context(
 async fn test_save_wallet_with_pending_blobs() -> anyhow::Result<()> {
     let storage_builder = MemoryStorageBuilder::default();
     let mut signer = InMemorySigner::new(Some(42));
-    let new_pubkey = signer.generate_new();
+    let new_pubkey = signer.generate_new().unwrap();
     let clock = storage_builder.clock().clone();
     let mut builder = TestBuilder::new(storage_builder, 4, 1, signer.clone()).await?;
     builder.add_root_chain(0, Amount::ONE).await?;
@@ -139,3 +145,137 @@ async fn test_save_wallet_with_pending_blobs() -> anyhow::Result<()> {
     wallet.save()?;
     Ok(())
 }
+
+/// Tests exporting chains with encrypted keys, and merging them into another wallet.
+#[test_log::test(tokio::test)]
+async fn test_merge_wallet_bundle() -> anyhow::Result<()> {
+    let signer = InMemorySigner::new(Some(42));
+    let mut builder = TestBuilder::new(MemoryStorageBuilder::default(), 4, 1, signer).await?;
+    builder.add_root_chain(0, Amount::ONE).await?;
+    let genesis_config = linera_core::GenesisConfig::new_for_testing(&builder);
+    let template = wallet::Chain::from(builder.admin_description().unwrap().clone());
+    let secret = AccountSecretKey::Ed25519(Ed25519SecretKey::generate());
+    let owner = AccountOwner::from(secret.public());
+    let other_owner = AccountOwner::from(CryptoHash::test_hash("other owner"));
+    let [chain_1, chain_2, chain_3] =
+        ["chain 1", "chain 2", "chain 3"].map(|name| ChainId(CryptoHash::test_hash(name)));
+
+    let tmp_dir = tempfile::tempdir()?;
+    let source = Wallet::create(&tmp_dir.path().join("source.json"), genesis_config.clone())?;
+    for (chain_id, owner, height) in [
+        (chain_1, owner, 5),
+        (chain_2, owner, 1),
+        (chain_3, other_owner, 0),
+    ] {
+        source.insert(
+            chain_id,
+            &wallet::Chain {
+                owner: Some(owner),
+                next_block_height: BlockHeight(height),
+                ..template.clone()
+            },
+        )?;
+    }
+    let mut bundle = WalletBundle::new(&source, None)?;
+    bundle.encrypt_keys(&[secret.copy()], "passphrase", 1000)?;
+    let bundle = WalletBundle::from_json(&bundle.to_json()?)?;
+    assert_eq!(bundle.default, Some(chain_1));
+    assert!(bundle.decrypt_keys("wrong passphrase").is_err());
+    let owners_with_keys = bundle
+        .decrypt_keys("passphrase")?
+        .iter()
+        .map(|secret| AccountOwner::from(secret.public()))
+        .collect::<BTreeSet<_>>();
+    assert_eq!(owners_with_keys, BTreeSet::from([owner]));
+
+    // The target wallet follows the first chain at an older height, and has the second one
+    // with a different owner.
+    let target = Wallet::create(&tmp_dir.path().join("target.json"), genesis_config)?;
+    target.insert(
+        chain_1,
+        &wallet::Chain {
+            next_block_height: BlockHeight(2),
+            ..template.clone()
+        },
+    )?;
+    target.insert(
+        chain_2,
+        &wallet::Chain {
+            owner: Some(other_owner),
+            next_block_height: BlockHeight(3),
+            ..template.clone()
+        },
+    )?;
+    bundle.check_genesis(target.genesis_config())?;
+    let report = target.merge(&bundle, &owners_with_keys)?;
+    assert_eq!(report.added, vec![chain_3]);
+    assert_eq!(report.updated, vec![chain_1]);
+    assert_eq!(report.unchanged, vec![chain_2]);
+    assert_eq!(report.conflicts, vec![chain_2]);
+    assert_eq!(report.without_keys, vec![chain_3]);
+
+    let chain = target.get(chain_1).unwrap();
+    assert_eq!(chain.owner, Some(owner));
+    assert_eq!(chain.next_block_height, BlockHeight(5));
+    let chain = target.get(chain_2).unwrap();
+    assert_eq!(chain.owner, Some(other_owner));
+    assert_eq!(chain.next_block_height, BlockHeight(3));
+    assert_eq!(target.get(chain_3).unwrap().owner, None);
+    assert_eq!(target.default_chain(), Some(chain_2));
+    Ok(())
+}
+
+/// Tests the names of the wallet's address book, and their resolution.
+#[test_log::test(tokio::test)]
+async fn test_address_book() -> anyhow::Result<()> {
+    let signer = InMemorySigner::new(Some(42));
+    let mut builder = TestBuilder::new(MemoryStorageBuilder::default(), 4, 1, signer).await?;
+    builder.add_root_chain(0, Amount::ONE).await?;
+    let genesis_config = linera_core::GenesisConfig::new_for_testing(&builder);
+    let treasury = ChainId(CryptoHash::test_hash("treasury"));
+    let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+    let counter = ApplicationId::new(CryptoHash::test_hash("counter"));
+
+    let tmp_dir = tempfile::tempdir()?;
+    let wallet_path = tmp_dir.path().join("wallet.json");
+    let wallet = Wallet::create(&wallet_path, genesis_config)?;
+    wallet.update_address_book(|address_book| {
+        address_book.insert("treasury".to_string(), Address::Chain(treasury))?;
+        address_book.insert("alice".to_string(), Address::Owner(alice))?;
+        address_book.insert("counter".to_string(), Address::Application(counter))?;
+        Ok(())
+    })?;
+    assert!(wallet
+        .update_address_book(|address_book| {
+            address_book.insert("0xalice".to_string(), Address::Owner(alice))
+        })
+        .is_err());
+    assert!(wallet
+        .update_address_book(|address_book| {
+            address_book.insert(treasury.to_string(), Address::Chain(treasury))
+        })
+        .is_err());
+    drop(wallet);
+
+    let address_book = AddressBook::read_from_wallet(&wallet_path)?;
+    assert_eq!(address_book, Wallet::read(&wallet_path)?.address_book());
+    assert_eq!(address_book.len(), 3);
+    assert_eq!(address_book.resolve_chain_id("treasury")?, treasury);
+    assert_eq!(
+        address_book.resolve_chain_id(&treasury.to_string())?,
+        treasury
+    );
+    assert_eq!(address_book.resolve_owner("alice")?, alice);
+    assert_eq!(address_book.resolve_application_id("counter")?, counter);
+    assert_eq!(
+        address_book.resolve_account("alice@treasury")?,
+        Account::new(treasury, alice)
+    );
+    assert_eq!(
+        address_book.resolve_account("treasury")?,
+        Account::chain(treasury)
+    );
+    assert!(address_book.resolve_chain_id("alice").is_err());
+    assert!(address_book.resolve_chain_id("bob").is_err());
+    Ok(())
+}
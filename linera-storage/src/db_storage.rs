@@ -21,8 +21,8 @@ use linera_chain::{
     ChainStateView,
 };
 use linera_execution::{
-    BlobState, ExecutionRuntimeConfig, SharedCommittees, UserContractCode, UserServiceCode,
-    WasmRuntime,
+    ApplicationLog, BlobState, ExecutionRuntimeConfig, SharedCommittees, UserContractCode,
+    UserServiceCode, WasmRuntime,
 };
 use linera_views::{
     backends::dual::{DualStoreRootKeyAssignment, StoreInUse},
@@ -286,6 +286,10 @@ const BLOCK_KEY: &[u8] = &[3];
 /// The key used for the network description.
 const NETWORK_DESCRIPTION_KEY: &[u8] = &[4];
 
+/// The key used for the application logs of confirmed blocks. The cryptohash itself is
+/// contained in the root key.
+const APPLICATION_LOGS_KEY: &[u8] = &[5];
+
 fn get_block_keys() -> Vec<Vec<u8>> {
     vec![LITE_CERTIFICATE_KEY.to_vec(), BLOCK_KEY.to_vec()]
 }
@@ -407,6 +411,17 @@ impl MultiPartitionBatch {
         self.put_key_value(root_key, key, value);
         Ok(())
     }
+
+    fn add_application_logs(
+        &mut self,
+        block_hash: CryptoHash,
+        logs: &[ApplicationLog],
+    ) -> Result<(), ViewError> {
+        let root_key = RootKey::BlockHash(block_hash).bytes();
+        let value = bcs::to_bytes(logs)?;
+        self.put_key_value(root_key, APPLICATION_LOGS_KEY.to_vec(), value);
+        Ok(())
+    }
 }
 
 /// Individual cache sizes for each `ValueCache` in `DbStorage`.
@@ -1385,6 +1400,28 @@ where
         Ok(())
     }
 
+    #[instrument(skip_all, fields(%block_hash))]
+    async fn write_application_logs(
+        &self,
+        block_hash: CryptoHash,
+        logs: &[ApplicationLog],
+    ) -> Result<(), ViewError> {
+        let mut batch = MultiPartitionBatch::new();
+        batch.add_application_logs(block_hash, logs)?;
+        self.write_batch(batch).await?;
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(%block_hash))]
+    async fn read_application_logs(
+        &self,
+        block_hash: CryptoHash,
+    ) -> Result<Option<Vec<ApplicationLog>>, ViewError> {
+        let root_key = RootKey::BlockHash(block_hash).bytes();
+        let store = self.database.open_shared(&root_key)?;
+        Ok(store.read_value(APPLICATION_LOGS_KEY).await?)
+    }
+
     fn wasm_runtime(&self) -> Option<WasmRuntime> {
         self.wasm_runtime
     }
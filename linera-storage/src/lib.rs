@@ -13,7 +13,7 @@ use linera_base::{
     crypto::CryptoHash,
     data_types::{
         ApplicationDescription, Blob, BlockHeight, ChainDescription, CompressedBytecode,
-        NetworkDescription, Timestamp,
+        ConfirmationSignatures, NetworkDescription, Timestamp,
     },
     identifiers::{ApplicationId, BlobId, BlobType, ChainId, EventId, IndexAndEvent, StreamId},
     vm::VmRuntime,
@@ -24,7 +24,7 @@ use linera_chain::{
     ChainError, ChainStateView,
 };
 use linera_execution::{
-    committee::Committee, BlobState, ExecutionError, ExecutionRuntimeConfig,
+    committee::Committee, ApplicationLog, BlobState, ExecutionError, ExecutionRuntimeConfig,
     ExecutionRuntimeContext, SharedCommittees, TransactionTracker, UserContractCode,
     UserServiceCode, WasmRuntime,
 };
@@ -251,6 +251,21 @@ pub trait Storage: linera_base::util::traits::AutoTraits + Sized {
         information: &NetworkDescription,
     ) -> Result<(), ViewError>;
 
+    /// Writes the application logs emitted while executing the confirmed block with the
+    /// given hash.
+    async fn write_application_logs(
+        &self,
+        block_hash: CryptoHash,
+        logs: &[ApplicationLog],
+    ) -> Result<(), ViewError>;
+
+    /// Reads the application logs emitted while executing the confirmed block with the given
+    /// hash, if they were recorded.
+    async fn read_application_logs(
+        &self,
+        block_hash: CryptoHash,
+    ) -> Result<Option<Vec<ApplicationLog>>, ViewError>;
+
     /// Initializes a chain in a simple way (used for testing and to create a genesis state).
     ///
     /// # Notes
@@ -553,6 +568,20 @@ impl<S: Storage> ExecutionRuntimeContext for ChainRuntimeContext<S> {
         self.storage.read_network_description().await
     }
 
+    async fn get_confirmation_signatures(
+        &self,
+        block_hash: CryptoHash,
+    ) -> Result<Option<ConfirmationSignatures>, ViewError> {
+        let Some(certificate) = self.storage.read_certificate(block_hash).await? else {
+            return Ok(None);
+        };
+        Ok(Some(ConfirmationSignatures {
+            epoch: certificate.block().header.epoch,
+            round: certificate.round,
+            signatures: certificate.signatures().clone(),
+        }))
+    }
+
     async fn get_or_load_committee_by_hash(
         &self,
         hash: CryptoHash,
@@ -612,7 +641,7 @@ mod tests {
         block::{Block, ConfirmedBlock},
         data_types::{BlockExecutionOutcome, ProposedBlock},
     };
-    use linera_execution::BlobState;
+    use linera_execution::{ApplicationLogLevel, BlobState};
     #[cfg(feature = "dynamodb")]
     use linera_views::dynamo_db::DynamoDbDatabase;
     #[cfg(feature = "scylladb")]
@@ -935,6 +964,35 @@ mod tests {
         Ok(())
     }
 
+    async fn test_storage_application_logs<S: Storage + Sync>(storage: &S) -> Result<(), ViewError>
+    where
+        S::Context: Send + Sync,
+    {
+        let confirmed_hash = CryptoHash::test_hash("confirmed block");
+        let other_hash = CryptoHash::test_hash("other block at the same height");
+        let logs = vec![ApplicationLog {
+            transaction_index: 0,
+            application_id: ApplicationId::new(CryptoHash::test_hash("application")),
+            level: ApplicationLogLevel::Info,
+            message: "hello".to_string(),
+        }];
+
+        assert!(storage
+            .read_application_logs(confirmed_hash)
+            .await?
+            .is_none());
+        storage
+            .write_application_logs(confirmed_hash, &logs)
+            .await?;
+        assert_eq!(
+            storage.read_application_logs(confirmed_hash).await?,
+            Some(logs)
+        );
+        // Logs are keyed by block hash, not by height.
+        assert!(storage.read_application_logs(other_hash).await?.is_none());
+        Ok(())
+    }
+
     /// Generic test function to test Storage trait features
     #[test_case(DbStorage::<MemoryDatabase, _>::make_test_storage(None).await; "memory")]
     #[cfg_attr(feature = "dynamodb", test_case(DbStorage::<DynamoDbDatabase, _>::make_test_storage(None).await; "dynamo_db"))]
@@ -949,6 +1007,7 @@ mod tests {
         test_storage_certificate(&storage).await?;
         test_storage_event(&storage).await?;
         test_storage_network_description(&storage).await?;
+        test_storage_application_logs(&storage).await?;
         Ok(())
     }
 }
@@ -121,6 +121,6 @@ pub use generic_array;
 pub use sha3;
 pub use views::{
     bucket_queue_view, collection_view, hashable_wrapper, historical_hash_wrapper,
-    key_value_store_view, lazy_register_view, log_view, map_view, queue_view,
+    key_value_store_view, lazy_register_view, log_view, map_view, proof, queue_view,
     reentrant_collection_view, register_view, set_view,
 };
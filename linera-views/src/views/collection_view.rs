@@ -23,7 +23,10 @@ use crate::{
     hashable_wrapper::WrappedHashableContainerView,
     historical_hash_wrapper::HistoricallyHashableView,
     store::ReadableKeyValueStore as _,
-    views::{ClonableView, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG},
+    views::{
+        proof::{MerkleTree, ProvableView},
+        ClonableView, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG,
+    },
 };
 
 #[cfg(with_metrics)]
@@ -1744,6 +1747,40 @@ where
     }
 }
 
+impl<W: View + ProvableView> ProvableView for ByteCollectionView<W::Context, W> {
+    /// The value of each entry is the Merkle root of the subview, so that proofs about a
+    /// subview can be chained with a proof of its entry in the collection.
+    async fn merkle_entries(&self) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ViewError> {
+        let mut entries = Vec::new();
+        for key in self.keys().await? {
+            let view = self.try_load_entry(&key).await?.ok_or_else(|| {
+                ViewError::MissingEntries("ByteCollectionView::merkle_entries".into())
+            })?;
+            let root = MerkleTree::from_view(&*view).await?.root();
+            entries.push((key, root.as_bytes().to_vec()));
+        }
+        Ok(entries)
+    }
+}
+
+impl<I, W: View + ProvableView> ProvableView for CollectionView<W::Context, I, W>
+where
+    I: Send + Sync + Serialize + DeserializeOwned,
+{
+    async fn merkle_entries(&self) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ViewError> {
+        self.collection.merkle_entries().await
+    }
+}
+
+impl<I, W: View + ProvableView> ProvableView for CustomCollectionView<W::Context, I, W>
+where
+    Self: View,
+{
+    async fn merkle_entries(&self) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ViewError> {
+        self.collection.merkle_entries().await
+    }
+}
+
 /// Type wrapping `ByteCollectionView` while memoizing the hash.
 pub type HashedByteCollectionView<C, W> =
     WrappedHashableContainerView<C, ByteCollectionView<C, W>, HasherOutput>;
@@ -214,15 +214,15 @@ where
 
 impl<W: View> HistoricallyHashableView<W::Context, W> {
     /// Obtains a hash of the history of the changes in the view.
-    pub async fn historical_hash(&mut self) -> Result<HasherOutput, ViewError> {
-        if let Some(hash) = self.hash.get_mut().unwrap() {
-            return Ok(*hash);
+    pub async fn historical_hash(&self) -> Result<HasherOutput, ViewError> {
+        if let Some(hash) = *self.hash.lock().unwrap() {
+            return Ok(hash);
         }
         let mut batch = Batch::new();
         self.inner.pre_save(&mut batch)?;
         let hash = Self::make_hash(self.stored_hash, &batch)?;
         // Remember the hash that we just computed.
-        *self.hash.get_mut().unwrap() = Some(hash);
+        *self.hash.lock().unwrap() = Some(hash);
         Ok(hash)
     }
 }
@@ -287,7 +287,7 @@ mod tests {
     #[tokio::test]
     async fn test_historically_hashable_view_initial_state() -> Result<(), ViewError> {
         let context = MemoryContext::new_for_testing(());
-        let mut view =
+        let view =
             HistoricallyHashableView::<_, RegisterView<_, u32>>::load(context.clone()).await?;
 
         // Initially should have no pending changes
@@ -353,7 +353,7 @@ mod tests {
         let hash_after_flush = view.historical_hash().await?;
 
         // Reload the view
-        let mut view2 =
+        let view2 =
             HistoricallyHashableView::<_, RegisterView<_, u32>>::load(context.clone()).await?;
 
         // Hash should be the same (loaded from storage)
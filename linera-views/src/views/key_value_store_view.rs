@@ -32,7 +32,10 @@ use crate::{
     historical_hash_wrapper::HistoricallyHashableView,
     map_view::ByteMapView,
     store::ReadableKeyValueStore,
-    views::{ClonableView, HashableView, Hasher, ReplaceContext, View, ViewError, MIN_VIEW_TAG},
+    views::{
+        proof::{IncrementallyProvableView, MerkleChanges, ProvableView},
+        ClonableView, HashableView, Hasher, ReplaceContext, View, ViewError, MIN_VIEW_TAG,
+    },
 };
 
 #[cfg(with_metrics)]
@@ -1211,6 +1214,45 @@ impl<C: Context> HashableView for KeyValueStoreView<C> {
     }
 }
 
+impl<C: Context> ProvableView for KeyValueStoreView<C> {
+    async fn merkle_entries(&self) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ViewError> {
+        self.index_values().await
+    }
+}
+
+impl<C: Context> IncrementallyProvableView for KeyValueStoreView<C> {
+    async fn merkle_changes(&self) -> Result<MerkleChanges, ViewError> {
+        let mut entries = BTreeMap::new();
+        if !self.deletion_set.delete_storage_first {
+            for prefix in &self.deletion_set.deleted_prefixes {
+                let key_prefix = self
+                    .context
+                    .base_key()
+                    .base_tag_index(KeyTag::Index as u8, prefix);
+                for suffix in self
+                    .context
+                    .store()
+                    .find_keys_by_prefix(&key_prefix)
+                    .await?
+                {
+                    entries.insert([prefix.as_slice(), &suffix].concat(), None);
+                }
+            }
+        }
+        for (index, update) in &self.updates {
+            let value = match update {
+                Update::Removed => None,
+                Update::Set(value) => Some(value.clone()),
+            };
+            entries.insert(index.clone(), value);
+        }
+        Ok(MerkleChanges {
+            cleared: self.deletion_set.delete_storage_first,
+            entries: entries.into_iter().collect(),
+        })
+    }
+}
+
 /// Type wrapping `KeyValueStoreView` while memoizing the hash.
 pub type HashedKeyValueStoreView<C> =
     WrappedHashableContainerView<C, KeyValueStoreView<C>, HasherOutput>;
@@ -56,7 +56,10 @@ use crate::{
     hashable_wrapper::WrappedHashableContainerView,
     historical_hash_wrapper::HistoricallyHashableView,
     store::ReadableKeyValueStore as _,
-    views::{ClonableView, HashableView, Hasher, ReplaceContext, View, ViewError},
+    views::{
+        proof::{IncrementallyProvableView, MerkleChanges, ProvableView},
+        ClonableView, HashableView, Hasher, ReplaceContext, View, ViewError,
+    },
 };
 
 /// A view that supports inserting and removing values indexed by `Vec<u8>`.
@@ -993,6 +996,59 @@ where
     }
 }
 
+impl<C, V> ProvableView for ByteMapView<C, V>
+where
+    C: Context,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    async fn merkle_entries(&self) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ViewError> {
+        let mut entries = Vec::new();
+        self.for_each_key_value_or_bytes(
+            |index, value| {
+                entries.push((index.to_vec(), value.into_bytes()?));
+                Ok(())
+            },
+            Vec::new(),
+        )
+        .await?;
+        Ok(entries)
+    }
+}
+
+impl<C, V> IncrementallyProvableView for ByteMapView<C, V>
+where
+    C: Context,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    async fn merkle_changes(&self) -> Result<MerkleChanges, ViewError> {
+        let mut entries = BTreeMap::new();
+        if !self.deletion_set.delete_storage_first {
+            for prefix in &self.deletion_set.deleted_prefixes {
+                let key_prefix = self.context.base_key().base_index(prefix);
+                for suffix in self
+                    .context
+                    .store()
+                    .find_keys_by_prefix(&key_prefix)
+                    .await?
+                {
+                    entries.insert([prefix.as_slice(), &suffix].concat(), None);
+                }
+            }
+        }
+        for (index, update) in &self.updates {
+            let value = match update {
+                Update::Removed => None,
+                Update::Set(value) => Some(bcs::to_bytes(value)?),
+            };
+            entries.insert(index.clone(), value);
+        }
+        Ok(MerkleChanges {
+            cleared: self.deletion_set.delete_storage_first,
+            entries: entries.into_iter().collect(),
+        })
+    }
+}
+
 /// A `View` that has a type for keys. The ordering of the entries
 /// is determined by the serialization of the context.
 #[derive(Debug, Allocative)]
@@ -1576,6 +1632,26 @@ where
     }
 }
 
+impl<C, I, V> ProvableView for MapView<C, I, V>
+where
+    Self: View,
+    ByteMapView<C, V>: ProvableView,
+{
+    async fn merkle_entries(&self) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ViewError> {
+        self.map.merkle_entries().await
+    }
+}
+
+impl<C, I, V> IncrementallyProvableView for MapView<C, I, V>
+where
+    Self: View,
+    ByteMapView<C, V>: IncrementallyProvableView,
+{
+    async fn merkle_changes(&self) -> Result<MerkleChanges, ViewError> {
+        self.map.merkle_changes().await
+    }
+}
+
 /// A map view that uses custom serialization
 #[derive(Debug, Allocative)]
 #[allocative(bound = "C, I, V: Allocative")]
@@ -2133,6 +2209,26 @@ where
     }
 }
 
+impl<C, I, V> ProvableView for CustomMapView<C, I, V>
+where
+    Self: View,
+    ByteMapView<C, V>: ProvableView,
+{
+    async fn merkle_entries(&self) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ViewError> {
+        self.map.merkle_entries().await
+    }
+}
+
+impl<C, I, V> IncrementallyProvableView for CustomMapView<C, I, V>
+where
+    Self: View,
+    ByteMapView<C, V>: IncrementallyProvableView,
+{
+    async fn merkle_changes(&self) -> Result<MerkleChanges, ViewError> {
+        self.map.merkle_changes().await
+    }
+}
+
 /// Type wrapping `ByteMapView` while memoizing the hash.
 pub type HashedByteMapView<C, V> = WrappedHashableContainerView<C, ByteMapView<C, V>, HasherOutput>;
 
@@ -51,6 +51,9 @@ pub mod hashable_wrapper;
 /// Wrapping a view to compute hash based on the history of modifications to the view.
 pub mod historical_hash_wrapper;
 
+/// Merkle proofs of the entries of a view.
+pub mod proof;
+
 /// The minimum value for the view tags. Values in `0..MIN_VIEW_TAG` are used for other purposes.
 pub const MIN_VIEW_TAG: u8 = 1;
 
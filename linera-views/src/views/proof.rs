@@ -0,0 +1,657 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Merkle proofs of the entries of a view.
+//!
+//! The entries of a view are the leaves of a sparse binary Merkle tree, where the path of an
+//! entry is given by the bits of the hash of its key, starting from the most significant one.
+//! A [`KeyProof`] shows that a key has a given value, or that it is absent, relative to the
+//! root of this tree.
+//!
+//! The tree only hashes 32-byte inputs with Keccak-256, so that proofs are cheap to check in
+//! Wasm applications as well as in EVM contracts:
+//! * a leaf is `keccak256(0x00 ++ keccak256(key) ++ keccak256(value))`,
+//! * an inner node is `keccak256(0x01 ++ left ++ right)`,
+//! * the hash of an empty subtree is zero,
+//! * a subtree with a single entry is the leaf of that entry, so leaves are stored at the
+//!   first depth where their path is not shared with another entry.
+//!
+//! The tree only depends on the entries, so that it can be built at once with [`MerkleTree`],
+//! or maintained with [`MerkleTreeView`], where changing an entry only reads and writes the
+//! nodes on its path.
+//!
+//! Note that these roots are independent of the hashes computed by [`HashableView`], which
+//! cover all the entries of a view at once and cannot be used for proofs. Users that need
+//! proofs under a commitment, like the state hash of a block, include the roots in it.
+//!
+//! [`HashableView`]: crate::views::HashableView
+
+use allocative::Allocative;
+use linera_base::{crypto::CryptoHash, ensure};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest as _, Keccak256};
+use thiserror::Error;
+
+use crate::{
+    batch::Batch,
+    context::Context,
+    map_view::ByteMapView,
+    views::{ClonableView, ReplaceContext, View},
+    ViewError,
+};
+
+/// The tag of the leaves of the tree.
+const LEAF_TAG: u8 = 0;
+/// The tag of the inner nodes of the tree.
+const NODE_TAG: u8 = 1;
+/// The maximal depth of the tree, i.e. the number of bits of a hash.
+const MAX_DEPTH: usize = 256;
+
+/// A view whose entries can be committed to in a [`MerkleTree`].
+#[cfg_attr(not(web), trait_variant::make(Send))]
+pub trait ProvableView {
+    /// Returns the entries of the view, ordered by key, with their serialized values.
+    async fn merkle_entries(&self) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ViewError>;
+}
+
+/// A view that can list the entries that changed since it was last saved, so that a
+/// [`MerkleTreeView`] of its entries can be updated without reading the other ones.
+#[cfg_attr(not(web), trait_variant::make(Send))]
+pub trait IncrementallyProvableView: ProvableView {
+    /// Returns the entries that changed since the view was last saved.
+    async fn merkle_changes(&self) -> Result<MerkleChanges, ViewError>;
+}
+
+/// The entries of a view that changed since it was last saved.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MerkleChanges {
+    /// Whether all the saved entries were removed first.
+    pub cleared: bool,
+    /// The changed entries, with their new serialized values, or `None` if they were removed.
+    pub entries: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+}
+
+/// An error when checking a [`KeyProof`].
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ProofError {
+    /// The proof is about a different key.
+    #[error("the proof is about a different key")]
+    WrongKey,
+    /// The proof leads to a different root.
+    #[error("the proof does not match the root hash")]
+    WrongRoot,
+    /// The entries or the number of hashes in the proof are inconsistent.
+    #[error("the proof is malformed")]
+    Malformed,
+}
+
+/// The hashes that a leaf of the tree is computed from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Allocative)]
+pub struct LeafHashes {
+    /// The hash of the key of the entry, which is also its path in the tree.
+    pub key_hash: CryptoHash,
+    /// The hash of the serialized value of the entry.
+    pub value_hash: CryptoHash,
+}
+
+/// A proof that a key is present in a view, with its value, or that it is absent.
+///
+/// The path of the key ends with the leaf of the key, with an empty subtree, or with the
+/// leaf of another entry whose path starts the same way.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyProof {
+    /// The serialized value of the key, if it is present.
+    pub value: Option<Vec<u8>>,
+    /// The other entry at the end of the path, if the key is absent and the subtree there
+    /// is not empty.
+    pub other: Option<LeafHashes>,
+    /// The hashes of the siblings on the path of the key, starting from the root.
+    pub siblings: Vec<CryptoHash>,
+}
+
+linera_base::doc_scalar!(
+    KeyProof,
+    "A proof that a key is present in a view, with its value, or that it is absent"
+);
+
+impl KeyProof {
+    /// Checks the proof against the `root` of the tree, and returns the value of `key`, or
+    /// `None` if the key is absent.
+    pub fn verify(&self, root: &CryptoHash, key: &[u8]) -> Result<Option<&[u8]>, ProofError> {
+        ensure!(self.siblings.len() <= MAX_DEPTH, ProofError::Malformed);
+        let key_hash = keccak(key);
+        let mut hash = match (&self.value, &self.other) {
+            (Some(value), None) => LeafHashes::new(key_hash, value).hash(),
+            (None, Some(other)) => {
+                ensure!(
+                    other.key_hash != key_hash
+                        && common_prefix_len(&other.key_hash, &key_hash) >= self.siblings.len(),
+                    ProofError::WrongKey
+                );
+                other.hash()
+            }
+            (None, None) => empty_root(),
+            (Some(_), Some(_)) => return Err(ProofError::Malformed),
+        };
+        for (depth, sibling) in self.siblings.iter().enumerate().rev() {
+            hash = if bit(&key_hash, depth) {
+                node_hash(sibling, &hash)
+            } else {
+                node_hash(&hash, sibling)
+            };
+        }
+        ensure!(hash == *root, ProofError::WrongRoot);
+        Ok(self.value.as_deref())
+    }
+}
+
+impl LeafHashes {
+    fn new(key_hash: CryptoHash, value: &[u8]) -> Self {
+        Self {
+            key_hash,
+            value_hash: keccak(value),
+        }
+    }
+
+    fn hash(&self) -> CryptoHash {
+        let mut hasher = Keccak256::new();
+        hasher.update([LEAF_TAG]);
+        hasher.update(self.key_hash.as_bytes());
+        hasher.update(self.value_hash.as_bytes());
+        CryptoHash::from(<[u8; 32]>::from(hasher.finalize()))
+    }
+}
+
+/// An entry of a [`MerkleTree`]: its leaf, key and value.
+type Entry = (LeafHashes, Vec<u8>, Vec<u8>);
+
+/// A Merkle tree over the entries of a view, built in memory.
+#[derive(Clone, Debug)]
+pub struct MerkleTree {
+    /// The entries, ordered by the hashes of their keys.
+    entries: Vec<Entry>,
+}
+
+impl MerkleTree {
+    /// Builds the tree over the given entries, which must have distinct keys.
+    pub fn new(entries: Vec<(Vec<u8>, Vec<u8>)>) -> Self {
+        let mut entries = entries
+            .into_iter()
+            .map(|(key, value)| (LeafHashes::new(keccak(&key), &value), key, value))
+            .collect::<Vec<_>>();
+        entries.sort_by(|(a, _, _), (b, _, _)| a.key_hash.as_bytes().cmp(b.key_hash.as_bytes()));
+        debug_assert!(entries
+            .windows(2)
+            .all(|pair| pair[0].0.key_hash != pair[1].0.key_hash));
+        Self { entries }
+    }
+
+    /// Builds the tree over the entries of the view.
+    pub async fn from_view(view: &impl ProvableView) -> Result<Self, ViewError> {
+        Ok(Self::new(view.merkle_entries().await?))
+    }
+
+    /// Returns the root of the tree.
+    pub fn root(&self) -> CryptoHash {
+        Self::subtree_hash(&self.entries, 0)
+    }
+
+    /// Returns the value of the entry with the given key, if any.
+    pub fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        let key_hash = keccak(key);
+        self.entries
+            .iter()
+            .find(|(leaf, _, _)| leaf.key_hash == key_hash)
+            .map(|(_, _, value)| value.as_slice())
+    }
+
+    /// Returns a proof that the key is present with its value, or that it is absent.
+    pub fn prove(&self, key: &[u8]) -> KeyProof {
+        let key_hash = keccak(key);
+        let mut entries = self.entries.as_slice();
+        let mut siblings = Vec::new();
+        while entries.len() > 1 {
+            let depth = siblings.len();
+            let (left, right) = Self::split(entries, depth);
+            let (path, sibling) = if bit(&key_hash, depth) {
+                (right, left)
+            } else {
+                (left, right)
+            };
+            siblings.push(Self::subtree_hash(sibling, depth + 1));
+            entries = path;
+        }
+        match entries.first() {
+            Some((leaf, _, value)) if leaf.key_hash == key_hash => KeyProof {
+                value: Some(value.clone()),
+                other: None,
+                siblings,
+            },
+            other => KeyProof {
+                value: None,
+                other: other.map(|(leaf, _, _)| *leaf),
+                siblings,
+            },
+        }
+    }
+
+    /// Splits entries whose paths share their first `depth` bits according to the next one.
+    fn split(entries: &[Entry], depth: usize) -> (&[Entry], &[Entry]) {
+        let index = entries.partition_point(|(leaf, _, _)| !bit(&leaf.key_hash, depth));
+        entries.split_at(index)
+    }
+
+    /// Returns the hash of the subtree of entries whose paths share their first `depth` bits.
+    fn subtree_hash(entries: &[Entry], depth: usize) -> CryptoHash {
+        match entries {
+            [] => empty_root(),
+            [(leaf, _, _)] => leaf.hash(),
+            _ => {
+                let (left, right) = Self::split(entries, depth);
+                node_hash(
+                    &Self::subtree_hash(left, depth + 1),
+                    &Self::subtree_hash(right, depth + 1),
+                )
+            }
+        }
+    }
+}
+
+/// A node of a [`MerkleTreeView`].
+#[derive(Clone, Debug, Serialize, Deserialize, Allocative)]
+enum Node {
+    /// An inner node, with the hashes of its children.
+    Inner { left: CryptoHash, right: CryptoHash },
+    /// The only entry of a subtree.
+    Leaf(LeafHashes),
+}
+
+impl Node {
+    fn hash(&self) -> CryptoHash {
+        match self {
+            Node::Inner { left, right } => node_hash(left, right),
+            Node::Leaf(leaf) => leaf.hash(),
+        }
+    }
+}
+
+/// A subtree of a [`MerkleTreeView`] being updated.
+enum Subtree {
+    Empty,
+    Leaf(LeafHashes),
+    Inner(Node),
+}
+
+impl Subtree {
+    fn hash(&self) -> CryptoHash {
+        match self {
+            Subtree::Empty => empty_root(),
+            Subtree::Leaf(leaf) => leaf.hash(),
+            Subtree::Inner(node) => node.hash(),
+        }
+    }
+}
+
+/// A Merkle tree over entries, kept in storage so that changing an entry only reads and
+/// writes the nodes on its path. Only the hashes of the entries are stored.
+#[derive(Debug, Allocative)]
+#[allocative(bound = "C")]
+pub struct MerkleTreeView<C> {
+    /// The nodes of the tree, indexed by their depth and their path.
+    nodes: ByteMapView<C, Node>,
+}
+
+impl<C: Context, C2: Context> ReplaceContext<C2> for MerkleTreeView<C> {
+    type Target = MerkleTreeView<C2>;
+
+    async fn with_context(
+        &mut self,
+        ctx: impl FnOnce(&Self::Context) -> C2 + Clone,
+    ) -> Self::Target {
+        MerkleTreeView {
+            nodes: self.nodes.with_context(ctx).await,
+        }
+    }
+}
+
+impl<C: Context> View for MerkleTreeView<C> {
+    const NUM_INIT_KEYS: usize = ByteMapView::<C, Node>::NUM_INIT_KEYS;
+
+    type Context = C;
+
+    fn context(&self) -> C {
+        self.nodes.context()
+    }
+
+    fn pre_load(context: &C) -> Result<Vec<Vec<u8>>, ViewError> {
+        ByteMapView::<C, Node>::pre_load(context)
+    }
+
+    fn post_load(context: C, values: &[Option<Vec<u8>>]) -> Result<Self, ViewError> {
+        Ok(MerkleTreeView {
+            nodes: ByteMapView::post_load(context, values)?,
+        })
+    }
+
+    fn rollback(&mut self) {
+        self.nodes.rollback()
+    }
+
+    async fn has_pending_changes(&self) -> bool {
+        self.nodes.has_pending_changes().await
+    }
+
+    fn pre_save(&self, batch: &mut Batch) -> Result<bool, ViewError> {
+        self.nodes.pre_save(batch)
+    }
+
+    fn post_save(&mut self) {
+        self.nodes.post_save()
+    }
+
+    fn clear(&mut self) {
+        self.nodes.clear()
+    }
+}
+
+impl<C: Context> ClonableView for MerkleTreeView<C> {
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        Ok(MerkleTreeView {
+            nodes: self.nodes.clone_unchecked()?,
+        })
+    }
+}
+
+impl<C: Context> MerkleTreeView<C> {
+    /// Returns the root of the tree.
+    pub async fn root(&self) -> Result<CryptoHash, ViewError> {
+        let root = self.nodes.get(&position(0, &empty_root())).await?;
+        Ok(root.map_or_else(empty_root, |node| node.hash()))
+    }
+
+    /// Sets the serialized value of `key`, or removes the entry if `value` is `None`.
+    pub async fn set(&mut self, key: &[u8], value: Option<&[u8]>) -> Result<(), ViewError> {
+        let key_hash = keccak(key);
+        // The inner nodes on the path of the key, down to an empty subtree or a leaf.
+        let mut path = Vec::new();
+        let bottom = loop {
+            match self.nodes.get(&position(path.len(), &key_hash)).await? {
+                Some(Node::Inner { left, right }) if path.len() < MAX_DEPTH => {
+                    path.push((left, right))
+                }
+                Some(Node::Inner { .. }) => return Err(ViewError::InconsistentEntries),
+                node => break node,
+            }
+        };
+        let depth = path.len();
+        let leaf = value.map(|value| LeafHashes::new(key_hash, value));
+        let mut subtree = match (bottom, leaf) {
+            (Some(Node::Leaf(other)), Some(leaf)) if other.key_hash != key_hash => {
+                self.split(depth, other, leaf)
+            }
+            (Some(Node::Leaf(other)), None) if other.key_hash != key_hash => return Ok(()),
+            (None, None) => return Ok(()),
+            (_, Some(leaf)) => Subtree::Leaf(leaf),
+            (_, None) => Subtree::Empty,
+        };
+        self.write(depth, &key_hash, &subtree);
+        for (depth, (left, right)) in path.into_iter().enumerate().rev() {
+            let is_right = bit(&key_hash, depth);
+            let sibling = if is_right { left } else { right };
+            subtree = match subtree {
+                // A subtree with at most one entry and no sibling moves up.
+                Subtree::Empty | Subtree::Leaf(_) if sibling == empty_root() => {
+                    self.nodes.remove(position(depth + 1, &key_hash));
+                    subtree
+                }
+                // A leaf without sibling moves up.
+                Subtree::Empty => {
+                    let sibling_path = flip(&key_hash, depth);
+                    let sibling_position = position(depth + 1, &sibling_path);
+                    match self.nodes.get(&sibling_position).await? {
+                        Some(Node::Leaf(leaf)) => {
+                            self.nodes.remove(sibling_position);
+                            Subtree::Leaf(leaf)
+                        }
+                        _ => Subtree::Inner(Self::inner(is_right, empty_root(), sibling)),
+                    }
+                }
+                subtree => Subtree::Inner(Self::inner(is_right, subtree.hash(), sibling)),
+            };
+            self.write(depth, &key_hash, &subtree);
+        }
+        Ok(())
+    }
+
+    /// Applies the changes of the entries of a view.
+    pub async fn apply(&mut self, changes: MerkleChanges) -> Result<(), ViewError> {
+        if changes.cleared {
+            self.clear();
+        }
+        for (key, value) in changes.entries {
+            self.set(&key, value.as_deref()).await?;
+        }
+        Ok(())
+    }
+
+    /// Returns a proof that `key` has the serialized `value`, or that it is absent if `value`
+    /// is `None`. The value must be the one of the entry in the tree.
+    pub async fn prove(&self, key: &[u8], value: Option<Vec<u8>>) -> Result<KeyProof, ViewError> {
+        let key_hash = keccak(key);
+        let mut siblings = Vec::new();
+        let bottom = loop {
+            match self.nodes.get(&position(siblings.len(), &key_hash)).await? {
+                Some(Node::Inner { left, right }) if siblings.len() < MAX_DEPTH => {
+                    siblings.push(if bit(&key_hash, siblings.len()) {
+                        left
+                    } else {
+                        right
+                    })
+                }
+                Some(Node::Inner { .. }) => return Err(ViewError::InconsistentEntries),
+                Some(Node::Leaf(leaf)) => break Some(leaf),
+                None => break None,
+            }
+        };
+        match (bottom, value) {
+            (Some(leaf), Some(value)) if leaf == LeafHashes::new(key_hash, &value) => {
+                Ok(KeyProof {
+                    value: Some(value),
+                    other: None,
+                    siblings,
+                })
+            }
+            (other, None) if other.is_none_or(|leaf| leaf.key_hash != key_hash) => Ok(KeyProof {
+                value: None,
+                other,
+                siblings,
+            }),
+            _ => Err(ViewError::InconsistentEntries),
+        }
+    }
+
+    /// Replaces the leaf `other` at `depth` with the subtree of `other` and `leaf`, writing
+    /// the nodes below `depth`.
+    fn split(&mut self, depth: usize, other: LeafHashes, leaf: LeafHashes) -> Subtree {
+        let split_depth = common_prefix_len(&other.key_hash, &leaf.key_hash);
+        self.nodes.insert(
+            position(split_depth + 1, &other.key_hash),
+            Node::Leaf(other),
+        );
+        self.nodes
+            .insert(position(split_depth + 1, &leaf.key_hash), Node::Leaf(leaf));
+        let is_right = bit(&leaf.key_hash, split_depth);
+        let mut node = Self::inner(is_right, leaf.hash(), other.hash());
+        for depth in (depth..split_depth).rev() {
+            self.nodes
+                .insert(position(depth + 1, &leaf.key_hash), node.clone());
+            node = Self::inner(bit(&leaf.key_hash, depth), node.hash(), empty_root());
+        }
+        Subtree::Inner(node)
+    }
+
+    /// Returns the inner node with the given child on the given side, and its sibling.
+    fn inner(is_right: bool, child: CryptoHash, sibling: CryptoHash) -> Node {
+        if is_right {
+            Node::Inner {
+                left: sibling,
+                right: child,
+            }
+        } else {
+            Node::Inner {
+                left: child,
+                right: sibling,
+            }
+        }
+    }
+
+    /// Stores the subtree at the given depth of the path.
+    fn write(&mut self, depth: usize, path: &CryptoHash, subtree: &Subtree) {
+        let position = position(depth, path);
+        match subtree {
+            Subtree::Empty => self.nodes.remove(position),
+            Subtree::Leaf(leaf) => self.nodes.insert(position, Node::Leaf(*leaf)),
+            Subtree::Inner(node) => self.nodes.insert(position, node.clone()),
+        }
+    }
+}
+
+/// Returns the root of a tree without entries.
+pub fn empty_root() -> CryptoHash {
+    CryptoHash::from([0u8; 32])
+}
+
+fn keccak(bytes: &[u8]) -> CryptoHash {
+    CryptoHash::from(<[u8; 32]>::from(Keccak256::digest(bytes)))
+}
+
+fn node_hash(left: &CryptoHash, right: &CryptoHash) -> CryptoHash {
+    let mut hasher = Keccak256::new();
+    hasher.update([NODE_TAG]);
+    hasher.update(left.as_bytes());
+    hasher.update(right.as_bytes());
+    CryptoHash::from(<[u8; 32]>::from(hasher.finalize()))
+}
+
+/// Returns the bit of the path at the given depth, `true` meaning right.
+fn bit(path: &CryptoHash, depth: usize) -> bool {
+    path.as_bytes()[depth / 8] & (0x80 >> (depth % 8)) != 0
+}
+
+/// Returns the path with the bit at the given depth flipped.
+fn flip(path: &CryptoHash, depth: usize) -> CryptoHash {
+    let mut bytes = path.as_bytes().0;
+    bytes[depth / 8] ^= 0x80 >> (depth % 8);
+    CryptoHash::from(bytes)
+}
+
+/// Returns the number of leading bits that the paths have in common.
+fn common_prefix_len(a: &CryptoHash, b: &CryptoHash) -> usize {
+    a.as_bytes()
+        .iter()
+        .zip(b.as_bytes())
+        .position(|(a, b)| a != b)
+        .map_or(MAX_DEPTH, |index| {
+            8 * index + (a.as_bytes()[index] ^ b.as_bytes()[index]).leading_zeros() as usize
+        })
+}
+
+/// Returns the storage key of the node at the given depth of the path: the depth, then the
+/// bytes of the path that hold its first `depth` bits, the others being zero.
+fn position(depth: usize, path: &CryptoHash) -> Vec<u8> {
+    let mut key = (depth as u16).to_be_bytes().to_vec();
+    key.extend(path.as_bytes().iter().take(depth.div_ceil(8)));
+    if depth % 8 != 0 {
+        *key.last_mut().unwrap() &= 0xff << (8 - depth % 8);
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{context::MemoryContext, store::WritableKeyValueStore as _};
+
+    fn entries(num_entries: u8) -> Vec<(Vec<u8>, Vec<u8>)> {
+        (0..num_entries)
+            .map(|i| (vec![2 * i], vec![i; i as usize]))
+            .collect()
+    }
+
+    #[test]
+    fn proofs_of_present_and_absent_keys() {
+        for num_entries in 0..=9 {
+            let tree = MerkleTree::new(entries(num_entries));
+            let root = tree.root();
+            for key in 0..=2 * num_entries {
+                let proof = tree.prove(&[key]);
+                let value = proof.verify(&root, &[key]).unwrap();
+                assert_eq!(value, tree.get(&[key]));
+                assert_eq!(value.is_some(), key % 2 == 0 && key < 2 * num_entries);
+            }
+        }
+    }
+
+    #[test]
+    fn proofs_do_not_transfer_to_other_keys_or_trees() {
+        let tree = MerkleTree::new(entries(5));
+        let proof = tree.prove(&[4]);
+        assert_eq!(proof.verify(&tree.root(), &[6]), Err(ProofError::WrongRoot));
+        assert_eq!(
+            proof.verify(&MerkleTree::new(entries(6)).root(), &[4]),
+            Err(ProofError::WrongRoot)
+        );
+        // Claiming that a present key is absent requires another entry on its path.
+        let forged = KeyProof {
+            value: None,
+            ..proof.clone()
+        };
+        assert_eq!(
+            forged.verify(&tree.root(), &[4]),
+            Err(ProofError::WrongRoot)
+        );
+        let forged = KeyProof {
+            value: None,
+            other: Some(LeafHashes::new(keccak(&[4]), &[2, 2])),
+            ..proof
+        };
+        assert_eq!(forged.verify(&tree.root(), &[4]), Err(ProofError::WrongKey));
+    }
+
+    #[tokio::test]
+    async fn stored_tree_matches_the_tree_built_in_memory() -> Result<(), ViewError> {
+        let context = MemoryContext::new_for_testing(());
+        let mut view = MerkleTreeView::load(context.clone()).await?;
+        let mut entries = std::collections::BTreeMap::new();
+        for step in 0u8..60 {
+            let key = vec![step % 23];
+            if step % 3 == 2 {
+                entries.remove(&key);
+                view.set(&key, None).await?;
+            } else {
+                entries.insert(key.clone(), vec![step]);
+                view.set(&key, Some(&[step])).await?;
+            }
+            if step % 10 == 9 {
+                let mut batch = Batch::new();
+                view.pre_save(&mut batch)?;
+                context.store().write_batch(batch).await?;
+                view.post_save();
+                view = MerkleTreeView::load(context.clone()).await?;
+            }
+            let tree = MerkleTree::new(entries.clone().into_iter().collect());
+            assert_eq!(view.root().await?, tree.root());
+            for key in 0..23 {
+                let value = entries.get(&vec![key]).cloned();
+                let proof = view.prove(&[key], value).await?;
+                assert_eq!(proof, tree.prove(&[key]));
+            }
+        }
+        for key in entries.keys() {
+            view.set(key, None).await?;
+        }
+        assert_eq!(view.root().await?, empty_root());
+        assert!(view.nodes.keys().await?.is_empty());
+        Ok(())
+    }
+}
@@ -24,7 +24,10 @@ use crate::{
     hashable_wrapper::WrappedHashableContainerView,
     historical_hash_wrapper::HistoricallyHashableView,
     store::ReadableKeyValueStore as _,
-    views::{ClonableView, HashableView, Hasher, ReplaceContext, View, ViewError, MIN_VIEW_TAG},
+    views::{
+        proof::{MerkleTree, ProvableView},
+        ClonableView, HashableView, Hasher, ReplaceContext, View, ViewError, MIN_VIEW_TAG,
+    },
 };
 
 #[cfg(with_metrics)]
@@ -926,6 +929,27 @@ impl<W: View> ReentrantByteCollectionView<W::Context, W> {
         Ok(keys)
     }
 
+    /// Returns the keys of the entries that were loaded for writing, reset or removed since
+    /// the collection was last saved, or `None` if all the saved entries are removed first.
+    /// The other entries have no pending changes.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::reentrant_collection_view::ReentrantByteCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: ReentrantByteCollectionView<_, RegisterView<_, String>> =
+    ///     ReentrantByteCollectionView::load(context).await.unwrap();
+    /// view.try_load_entry_mut(&[0, 1]).await.unwrap();
+    /// view.remove_entry(vec![0, 2]);
+    /// assert_eq!(view.updated_keys(), Some(vec![vec![0, 1], vec![0, 2]]));
+    /// # })
+    /// ```
+    pub fn updated_keys(&self) -> Option<Vec<Vec<u8>>> {
+        (!self.delete_storage_first).then(|| self.updates.keys().cloned().collect())
+    }
+
     /// Returns the number of indices of the collection.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -1594,6 +1618,33 @@ where
         Ok(indices)
     }
 
+    /// Returns the indices of the entries that were loaded for writing, reset or removed
+    /// since the collection was last saved, or `None` if all the saved entries are removed
+    /// first. The other entries have no pending changes.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::reentrant_collection_view::ReentrantCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: ReentrantCollectionView<_, u64, RegisterView<_, String>> =
+    ///     ReentrantCollectionView::load(context).await.unwrap();
+    /// view.try_load_entry_mut(&23).await.unwrap();
+    /// assert_eq!(view.updated_indices().unwrap(), Some(vec![23]));
+    /// # })
+    /// ```
+    pub fn updated_indices(&self) -> Result<Option<Vec<I>>, ViewError> {
+        let Some(keys) = self.collection.updated_keys() else {
+            return Ok(None);
+        };
+        let indices = keys
+            .iter()
+            .map(|key| BaseKey::deserialize_value(key))
+            .collect::<Result<_, _>>()?;
+        Ok(Some(indices))
+    }
+
     /// Returns the number of indices in the collection.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -2268,6 +2319,40 @@ where
     }
 }
 
+impl<W: View + ProvableView> ProvableView for ReentrantByteCollectionView<W::Context, W> {
+    /// The value of each entry is the Merkle root of the subview, so that proofs about a
+    /// subview can be chained with a proof of its entry in the collection.
+    async fn merkle_entries(&self) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ViewError> {
+        let mut entries = Vec::new();
+        for key in self.keys().await? {
+            let view = self.try_load_entry(&key).await?.ok_or_else(|| {
+                ViewError::MissingEntries("ReentrantByteCollectionView::merkle_entries".into())
+            })?;
+            let root = MerkleTree::from_view(&*view).await?.root();
+            entries.push((key, root.as_bytes().to_vec()));
+        }
+        Ok(entries)
+    }
+}
+
+impl<I, W: View + ProvableView> ProvableView for ReentrantCollectionView<W::Context, I, W>
+where
+    I: Send + Sync + Serialize + DeserializeOwned,
+{
+    async fn merkle_entries(&self) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ViewError> {
+        self.collection.merkle_entries().await
+    }
+}
+
+impl<I, W: View + ProvableView> ProvableView for ReentrantCustomCollectionView<W::Context, I, W>
+where
+    Self: View,
+{
+    async fn merkle_entries(&self) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ViewError> {
+        self.collection.merkle_entries().await
+    }
+}
+
 /// Type wrapping `ReentrantByteCollectionView` while memoizing the hash.
 pub type HashedReentrantByteCollectionView<C, W> =
     WrappedHashableContainerView<C, ReentrantByteCollectionView<C, W>, HasherOutput>;
@@ -11,7 +11,7 @@ use crate::{
     common::{from_bytes_option_or_default, HasherOutput},
     context::Context,
     hashable_wrapper::WrappedHashableContainerView,
-    views::{ClonableView, HashableView, Hasher, ReplaceContext, View},
+    views::{proof::ProvableView, ClonableView, HashableView, Hasher, ReplaceContext, View},
     ViewError,
 };
 
@@ -247,6 +247,17 @@ where
     }
 }
 
+impl<C, T> ProvableView for RegisterView<C, T>
+where
+    C: Context,
+    T: Clone + Default + Send + Sync + Serialize + DeserializeOwned,
+{
+    /// A register has a single entry, with an empty key.
+    async fn merkle_entries(&self) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ViewError> {
+        Ok(vec![(Vec::new(), bcs::to_bytes(self.get())?)])
+    }
+}
+
 /// Type wrapping `RegisterView` while memoizing the hash.
 pub type HashedRegisterView<C, T> =
     WrappedHashableContainerView<C, RegisterView<C, T>, HasherOutput>;
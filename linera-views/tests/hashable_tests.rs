@@ -4,10 +4,14 @@
 use anyhow::Result;
 use linera_base::crypto::CryptoHash;
 use linera_views::{
+    collection_view::CollectionView,
     common::HasherOutput,
     context::MemoryContext,
     hashable_wrapper::WrappedHashableContainerView,
     historical_hash_wrapper::HistoricallyHashableView,
+    key_value_store_view::KeyValueStoreView,
+    map_view::MapView,
+    proof::{IncrementallyProvableView as _, MerkleTree, MerkleTreeView, ProofError},
     register_view::{HashedRegisterView, RegisterView},
     views::{HashableView, RootView, View},
 };
@@ -79,3 +83,106 @@ async fn check_hashable_not_overwriting_field() -> Result<()> {
 
     Ok(())
 }
+
+#[derive(RootView)]
+struct ProvableType<C> {
+    pub register: RegisterView<C, u64>,
+    pub map: MapView<C, u32, String>,
+    pub store: KeyValueStoreView<C>,
+    pub collection: CollectionView<C, u8, KeyValueStoreView<C>>,
+}
+
+#[tokio::test]
+async fn check_merkle_proofs() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut view = ProvableType::load(context.clone()).await?;
+    view.register.set(7);
+    view.map.insert(&1, "one".to_string())?;
+    view.map.insert(&3, "three".to_string())?;
+    view.store.insert(vec![1, 2], vec![3]).await?;
+    view.collection
+        .load_entry_mut(&5)
+        .await?
+        .insert(vec![4], vec![2])
+        .await?;
+    view.save().await?;
+    let view = ProvableType::load(context).await?;
+
+    let tree = MerkleTree::from_view(&view.register).await?;
+    let proof = tree.prove(&[]);
+    assert_eq!(
+        proof.verify(&tree.root(), &[])?,
+        Some(bcs::to_bytes(&7u64)?.as_slice())
+    );
+
+    let tree = MerkleTree::from_view(&view.map).await?;
+    let key = bcs::to_bytes(&3u32)?;
+    let proof = tree.prove(&key);
+    assert_eq!(
+        proof.verify(&tree.root(), &key)?,
+        Some(bcs::to_bytes("three")?.as_slice())
+    );
+    let key = bcs::to_bytes(&2u32)?;
+    assert_eq!(tree.prove(&key).verify(&tree.root(), &key)?, None);
+
+    let tree = MerkleTree::from_view(&view.store).await?;
+    let proof = tree.prove(&[1, 2]);
+    assert_eq!(proof.verify(&tree.root(), &[1, 2])?, Some([3].as_slice()));
+    assert_eq!(
+        proof.verify(&tree.root(), &[1, 3]),
+        Err(ProofError::WrongRoot)
+    );
+
+    // A proof about a subview is chained with the proof of its root in the collection.
+    let tree = MerkleTree::from_view(&view.collection).await?;
+    let key = bcs::to_bytes(&5u8)?;
+    let proof = tree.prove(&key);
+    let subview_root = proof.verify(&tree.root(), &key)?.unwrap();
+    let subview = view.collection.try_load_entry(&5).await?.unwrap();
+    let subview_tree = MerkleTree::from_view(&*subview).await?;
+    assert_eq!(subview_root, subview_tree.root().as_bytes().as_slice());
+    let proof = subview_tree.prove(&[4]);
+    assert_eq!(
+        proof.verify(&subview_tree.root(), &[4])?,
+        Some([2].as_slice())
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_incremental_merkle_trees() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut view = ProvableType::load(context.clone()).await?;
+    let mut map_tree = MerkleTreeView::load(MemoryContext::new_for_testing(())).await?;
+    let mut store_tree = MerkleTreeView::load(MemoryContext::new_for_testing(())).await?;
+    for round in 0u8..4 {
+        for i in 0..10u32 {
+            view.map
+                .insert(&(i * u32::from(round)), format!("{round}"))?;
+            view.store
+                .insert(vec![round, i as u8], vec![round; i as usize])
+                .await?;
+        }
+        view.map.remove(&3)?;
+        view.store.remove(vec![0, 4]).await?;
+        if round == 2 {
+            view.store.remove_by_prefix(vec![1]).await?;
+        }
+        if round == 3 {
+            view.map.clear();
+        }
+        map_tree.apply(view.map.merkle_changes().await?).await?;
+        store_tree.apply(view.store.merkle_changes().await?).await?;
+        assert_eq!(
+            map_tree.root().await?,
+            MerkleTree::from_view(&view.map).await?.root()
+        );
+        assert_eq!(
+            store_tree.root().await?,
+            MerkleTree::from_view(&view.store).await?.root()
+        );
+        view.save().await?;
+        view = ProvableType::load(context.clone()).await?;
+    }
+    Ok(())
+}
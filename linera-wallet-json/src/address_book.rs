@@ -0,0 +1,238 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Human-readable names for chains, owners and applications.
+//!
+//! The address book is stored in the wallet. Wherever an ID is expected, its name in the
+//! address book can be used instead.
+
+use std::{collections::BTreeMap, fmt, path::Path, str::FromStr};
+
+use anyhow::{bail, ensure, Context as _};
+use linera_base::identifiers::{Account, AccountOwner, ApplicationId, ChainId};
+use serde::{Deserialize, Serialize};
+
+/// The maximum length of a name in the address book.
+const MAX_NAME_LEN: usize = 64;
+
+/// What kind of ID a name stands for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressKind {
+    Chain,
+    Owner,
+    Application,
+}
+
+impl fmt::Display for AddressKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AddressKind::Chain => write!(f, "chain"),
+            AddressKind::Owner => write!(f, "owner"),
+            AddressKind::Application => write!(f, "application"),
+        }
+    }
+}
+
+impl FromStr for AddressKind {
+    type Err = anyhow::Error;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        match string.to_ascii_lowercase().as_str() {
+            "chain" => Ok(AddressKind::Chain),
+            "owner" => Ok(AddressKind::Owner),
+            "application" => Ok(AddressKind::Application),
+            _ => bail!("unknown kind of address `{string}`: expected chain, owner or application"),
+        }
+    }
+}
+
+/// An ID that a name of the address book stands for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Address {
+    Chain(ChainId),
+    Owner(AccountOwner),
+    Application(ApplicationId),
+}
+
+impl Address {
+    /// Parses an ID of the given kind.
+    pub fn parse(kind: AddressKind, id: &str) -> anyhow::Result<Self> {
+        Ok(match kind {
+            AddressKind::Chain => Address::Chain(id.parse()?),
+            AddressKind::Owner => Address::Owner(id.parse()?),
+            AddressKind::Application => Address::Application(id.parse()?),
+        })
+    }
+
+    /// Parses an ID of any kind. This fails if the ID is of more than one kind: e.g. chain
+    /// and application IDs have the same form.
+    pub fn infer(id: &str) -> anyhow::Result<Self> {
+        let mut addresses = [
+            AddressKind::Chain,
+            AddressKind::Owner,
+            AddressKind::Application,
+        ]
+        .into_iter()
+        .filter_map(|kind| Self::parse(kind, id).ok());
+        match (addresses.next(), addresses.next()) {
+            (Some(address), None) => Ok(address),
+            (Some(first), Some(second)) => {
+                bail!("`{id}` could be a {} or {} ID", first.kind(), second.kind())
+            }
+            (None, _) => bail!("`{id}` is not a chain, owner or application ID"),
+        }
+    }
+
+    /// Returns the kind of the ID.
+    pub fn kind(&self) -> AddressKind {
+        match self {
+            Address::Chain(_) => AddressKind::Chain,
+            Address::Owner(_) => AddressKind::Owner,
+            Address::Application(_) => AddressKind::Application,
+        }
+    }
+
+    /// Returns the ID in the form that it is parsed from.
+    pub fn id(&self) -> String {
+        match self {
+            Address::Chain(chain_id) => chain_id.to_string(),
+            Address::Owner(owner) => owner.to_string(),
+            Address::Application(application_id) => application_id.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.kind(), self.id())
+    }
+}
+
+/// Names for chains, owners and applications.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressBook(BTreeMap<String, Address>);
+
+impl AddressBook {
+    /// Reads the address book of the wallet at the given path, without locking the wallet.
+    pub fn read_from_wallet(path: &Path) -> anyhow::Result<Self> {
+        #[derive(Deserialize)]
+        struct Wallet {
+            #[serde(default)]
+            address_book: AddressBook,
+        }
+
+        let file = fs_err::File::open(path)?;
+        let wallet: Wallet = serde_json::from_reader(std::io::BufReader::new(file))
+            .with_context(|| format!("reading the address book of {}", path.display()))?;
+        Ok(wallet.address_book)
+    }
+
+    /// Checks that the name can be used in the address book.
+    pub fn check_name(name: &str) -> anyhow::Result<()> {
+        ensure!(
+            name.len() <= MAX_NAME_LEN,
+            "the name `{name}` is longer than {MAX_NAME_LEN} characters"
+        );
+        ensure!(
+            name.starts_with(|c: char| c.is_ascii_alphabetic())
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')),
+            "invalid name `{name}`: names start with a letter, and only contain letters, \
+             digits, `-`, `_` and `.`"
+        );
+        ensure!(
+            name.parse::<ChainId>().is_err(),
+            "invalid name `{name}`: it is an ID"
+        );
+        Ok(())
+    }
+
+    /// Adds or replaces a name, and returns the address that it stood for before.
+    pub fn insert(&mut self, name: String, address: Address) -> anyhow::Result<Option<Address>> {
+        Self::check_name(&name)?;
+        Ok(self.0.insert(name, address))
+    }
+
+    /// Removes a name, and returns the address that it stood for.
+    pub fn remove(&mut self, name: &str) -> Option<Address> {
+        self.0.remove(name)
+    }
+
+    /// Returns the address that the name stands for.
+    pub fn get(&self, name: &str) -> Option<Address> {
+        self.0.get(name).copied()
+    }
+
+    /// Returns the names and their addresses, ordered by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Address)> {
+        self.0.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Parses a chain ID, or resolves the name of a chain.
+    pub fn resolve_chain_id(&self, value: &str) -> anyhow::Result<ChainId> {
+        self.resolve(value, AddressKind::Chain, |address| match address {
+            Address::Chain(chain_id) => Some(chain_id),
+            _ => None,
+        })
+    }
+
+    /// Parses an owner, or resolves the name of an owner.
+    pub fn resolve_owner(&self, value: &str) -> anyhow::Result<AccountOwner> {
+        self.resolve(value, AddressKind::Owner, |address| match address {
+            Address::Owner(owner) => Some(owner),
+            _ => None,
+        })
+    }
+
+    /// Parses an application ID, or resolves the name of an application.
+    pub fn resolve_application_id(&self, value: &str) -> anyhow::Result<ApplicationId> {
+        self.resolve(value, AddressKind::Application, |address| match address {
+            Address::Application(application_id) => Some(application_id),
+            _ => None,
+        })
+    }
+
+    /// Parses an account formatted as `chain` or `owner@chain`, where the chain and the
+    /// owner are IDs or names.
+    pub fn resolve_account(&self, value: &str) -> anyhow::Result<Account> {
+        if let Ok(account) = value.parse() {
+            return Ok(account);
+        }
+        match value.rsplit_once('@') {
+            Some((owner, chain_id)) => Ok(Account::new(
+                self.resolve_chain_id(chain_id)?,
+                self.resolve_owner(owner)?,
+            )),
+            None => Ok(Account::chain(self.resolve_chain_id(value)?)),
+        }
+    }
+
+    fn resolve<T: FromStr>(
+        &self,
+        value: &str,
+        kind: AddressKind,
+        select: impl FnOnce(Address) -> Option<T>,
+    ) -> anyhow::Result<T> {
+        if let Ok(id) = value.parse() {
+            return Ok(id);
+        }
+        match self.get(value) {
+            Some(address) => match select(address) {
+                Some(id) => Ok(id),
+                None => bail!("`{value}` is the name of {address}, not of a {kind}"),
+            },
+            None => bail!("`{value}` is neither a valid {kind} ID nor a name in the address book"),
+        }
+    }
+}
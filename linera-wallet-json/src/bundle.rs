@@ -0,0 +1,249 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Portable bundles of chains and keys, to move identities between wallets, e.g. from a
+//! laptop to CI.
+//!
+//! A [`WalletBundle`] contains chains of a wallet and a reference to the genesis
+//! configuration of their network. The secret keys of the chains' owners can be included,
+//! encrypted with a passphrase.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    num::NonZeroU32,
+};
+
+use anyhow::{anyhow, ensure, Context as _};
+use linera_base::{
+    crypto::{AccountSecretKey, CryptoHash},
+    hex,
+    identifiers::{AccountOwner, ChainId},
+};
+use linera_client::config::GenesisConfig;
+use linera_core::wallet::Chain;
+use ring::{
+    aead, pbkdf2,
+    rand::{SecureRandom as _, SystemRandom},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::PersistentWallet;
+
+/// The version of the bundle format.
+const BUNDLE_VERSION: u32 = 1;
+
+/// The default number of PBKDF2 iterations to derive the encryption key from a passphrase.
+pub const DEFAULT_KEY_DERIVATION_ITERATIONS: u32 = 600_000;
+
+/// The maximum number of PBKDF2 iterations. Bundles may come from untrusted sources, so this
+/// bounds the time it takes to try a passphrase.
+pub const MAX_KEY_DERIVATION_ITERATIONS: u32 = 10_000_000;
+
+/// The length of the random salt of the key derivation, in bytes.
+const SALT_LEN: usize = 16;
+
+/// Identifies the network that the chains of a bundle belong to.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisReference {
+    /// The name of the network.
+    pub network_name: String,
+    /// The ID of the admin chain.
+    pub admin_chain_id: ChainId,
+    /// The hash of the genesis configuration.
+    pub hash: CryptoHash,
+}
+
+impl From<&GenesisConfig> for GenesisReference {
+    fn from(genesis_config: &GenesisConfig) -> Self {
+        Self {
+            network_name: genesis_config.network_name.clone(),
+            admin_chain_id: genesis_config.admin_chain_id(),
+            hash: genesis_config.hash(),
+        }
+    }
+}
+
+/// Secret keys, encrypted with ChaCha20-Poly1305 under a key that is derived from a
+/// passphrase with PBKDF2-HMAC-SHA256.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EncryptedKeys {
+    /// The number of PBKDF2 iterations.
+    pub iterations: u32,
+    /// The salt of the key derivation, as hex.
+    pub salt: String,
+    /// The nonce of the encryption, as hex.
+    pub nonce: String,
+    /// The encrypted JSON list of secret keys, as hex.
+    pub ciphertext: String,
+}
+
+/// Chains exported from a wallet, with the encrypted keys of their owners if requested.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WalletBundle {
+    /// The version of the bundle format.
+    pub version: u32,
+    /// The network of the chains.
+    pub genesis: GenesisReference,
+    /// The default chain of the exported wallet, if it is in the bundle.
+    pub default: Option<ChainId>,
+    /// The chains.
+    pub chains: BTreeMap<ChainId, Chain>,
+    /// The secret keys of the chains' owners.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keys: Option<EncryptedKeys>,
+}
+
+impl WalletBundle {
+    /// Creates a bundle with the given chains of the wallet, or all of them, without keys.
+    pub fn new(wallet: &PersistentWallet, chain_ids: Option<&[ChainId]>) -> anyhow::Result<Self> {
+        let chains = match chain_ids {
+            None => wallet.items().into_iter().collect::<BTreeMap<_, _>>(),
+            Some(chain_ids) => chain_ids
+                .iter()
+                .map(|chain_id| {
+                    let chain = wallet
+                        .get(*chain_id)
+                        .ok_or_else(|| anyhow!("nonexistent chain `{chain_id}`"))?;
+                    Ok((*chain_id, chain))
+                })
+                .collect::<anyhow::Result<_>>()?,
+        };
+        let default = wallet
+            .default_chain()
+            .filter(|chain_id| chains.contains_key(chain_id));
+        Ok(Self {
+            version: BUNDLE_VERSION,
+            genesis: wallet.genesis_config().into(),
+            default,
+            chains,
+            keys: None,
+        })
+    }
+
+    /// Parses a bundle from JSON.
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let bundle = serde_json::from_str::<Self>(json).context("invalid wallet bundle")?;
+        ensure!(
+            bundle.version == BUNDLE_VERSION,
+            "unsupported wallet bundle version {}",
+            bundle.version
+        );
+        Ok(bundle)
+    }
+
+    /// Returns the bundle as JSON.
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Returns the owners of the chains in the bundle.
+    pub fn owners(&self) -> BTreeSet<AccountOwner> {
+        self.chains
+            .values()
+            .filter_map(|chain| chain.owner)
+            .collect()
+    }
+
+    /// Checks that the chains belong to the network with the given genesis configuration.
+    pub fn check_genesis(&self, genesis_config: &GenesisConfig) -> anyhow::Result<()> {
+        let expected = GenesisReference::from(genesis_config);
+        ensure!(
+            self.genesis == expected,
+            "the bundle is for network {:?} with genesis configuration {}, but the wallet is \
+             for network {:?} with genesis configuration {}",
+            self.genesis.network_name,
+            self.genesis.hash,
+            expected.network_name,
+            expected.hash,
+        );
+        Ok(())
+    }
+
+    /// Adds the secret keys to the bundle, encrypted with the passphrase.
+    pub fn encrypt_keys(
+        &mut self,
+        keys: &[AccountSecretKey],
+        passphrase: &str,
+        iterations: u32,
+    ) -> anyhow::Result<()> {
+        let random = SystemRandom::new();
+        let mut salt = [0; SALT_LEN];
+        let mut nonce = [0; aead::NONCE_LEN];
+        random
+            .fill(&mut salt)
+            .and_then(|()| random.fill(&mut nonce))
+            .map_err(|_| anyhow!("failed to generate random bytes"))?;
+        let key = derive_key(passphrase, &salt, iterations)?;
+        let mut data = serde_json::to_vec(keys)?;
+        key.seal_in_place_append_tag(
+            aead::Nonce::assume_unique_for_key(nonce),
+            aead::Aad::empty(),
+            &mut data,
+        )
+        .map_err(|_| anyhow!("failed to encrypt the keys"))?;
+        self.keys = Some(EncryptedKeys {
+            iterations,
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(data),
+        });
+        Ok(())
+    }
+
+    /// Returns the secret keys of the bundle, decrypted with the passphrase.
+    pub fn decrypt_keys(&self, passphrase: &str) -> anyhow::Result<Vec<AccountSecretKey>> {
+        let Some(keys) = &self.keys else {
+            return Ok(Vec::new());
+        };
+        let salt = hex::decode(&keys.salt).context("invalid salt")?;
+        let mut nonce = [0; aead::NONCE_LEN];
+        hex::decode_to_slice(&keys.nonce, &mut nonce).context("invalid nonce")?;
+        let mut data = hex::decode(&keys.ciphertext).context("invalid ciphertext")?;
+        let key = derive_key(passphrase, &salt, keys.iterations)?;
+        let plaintext = key
+            .open_in_place(
+                aead::Nonce::assume_unique_for_key(nonce),
+                aead::Aad::empty(),
+                &mut data,
+            )
+            .map_err(|_| anyhow!("failed to decrypt the keys: wrong passphrase?"))?;
+        Ok(serde_json::from_slice(plaintext)?)
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> anyhow::Result<aead::LessSafeKey> {
+    let iterations =
+        NonZeroU32::new(iterations).context("the number of iterations must not be zero")?;
+    ensure!(
+        iterations.get() <= MAX_KEY_DERIVATION_ITERATIONS,
+        "the number of iterations must not exceed {MAX_KEY_DERIVATION_ITERATIONS}"
+    );
+    let mut key = [0; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    let key = aead::UnboundKey::new(&aead::CHACHA20_POLY1305, &key)
+        .map_err(|_| anyhow!("invalid encryption key"))?;
+    Ok(aead::LessSafeKey::new(key))
+}
+
+/// What merging a bundle into a wallet did.
+#[derive(Debug, Default)]
+pub struct MergeReport {
+    /// The chains that were added to the wallet.
+    pub added: Vec<ChainId>,
+    /// The chains that the wallet had, and that were updated with a newer state or an owner.
+    pub updated: Vec<ChainId>,
+    /// The chains that the wallet had, and that were left as they were.
+    pub unchanged: Vec<ChainId>,
+    /// The chains whose owner in the bundle has no key in the keystore. New chains among them
+    /// were added as followed chains.
+    pub without_keys: Vec<ChainId>,
+    /// The chains that have a different owner in the wallet than in the bundle. The wallet's
+    /// owner was kept.
+    pub conflicts: Vec<ChainId>,
+}
@@ -87,6 +87,10 @@ impl ChainDetails {
         if self.user_chain.pending_fast_proposal.is_some() {
             println!("{:<20}  present", "Pending fast proposal:");
         }
+
+        if self.user_chain.inbox_policy.is_some() {
+            println!("{:<20}  custom", "Inbox policy:");
+        }
     }
 }
 
@@ -1,10 +1,13 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::path::Path;
+use std::{collections::BTreeSet, path::Path};
 
 use linera_base::{
-    crypto::{AccountPublicKey, AccountSignature, CryptoHash, InMemorySigner, Signer},
+    crypto::{
+        hd::{HdKeyScheme, HdSeed},
+        AccountPublicKey, AccountSecretKey, AccountSignature, CryptoHash, InMemorySigner, Signer,
+    },
     identifiers::AccountOwner,
 };
 use linera_persistent::{self as persistent, Persist as _};
@@ -45,25 +48,78 @@ impl Keystore {
         })?))
     }
 
+    /// Creates a new keystore at `path`, whose keys are derived from `seed`.
+    pub fn create_from_seed(path: &Path, seed: HdSeed) -> Result<Self, persistent::file::Error> {
+        Ok(Self(persistent::File::read_or_create(path, || {
+            Ok(InMemorySigner::from_seed(seed))
+        })?))
+    }
+
+    /// Returns whether the keys are derived from a seed.
+    pub fn has_seed(&self) -> bool {
+        self.0.has_seed()
+    }
+
+    /// Derives the next key of the given scheme from the keystore's seed. The keystore must
+    /// be saved afterwards.
+    pub fn derive_next(
+        &mut self,
+        scheme: HdKeyScheme,
+    ) -> Result<AccountPublicKey, <InMemorySigner as Signer>::Error> {
+        self.0.derive_next(scheme)
+    }
+
+    /// Derives the key of the given scheme with the given index from the keystore's seed.
+    /// The keystore must be saved afterwards.
+    pub fn derive_key(
+        &mut self,
+        scheme: HdKeyScheme,
+        index: u32,
+    ) -> Result<AccountPublicKey, <InMemorySigner as Signer>::Error> {
+        self.0.derive_key(scheme, index)
+    }
+
+    /// Returns the public key of the given scheme with the given index, derived from the
+    /// keystore's seed, without adding it to the keystore.
+    pub fn derived_public_key(
+        &self,
+        scheme: HdKeyScheme,
+        index: u32,
+    ) -> Result<AccountPublicKey, <InMemorySigner as Signer>::Error> {
+        self.0.derived_public_key(scheme, index)
+    }
+
     /// Generates a new key pair, persists the keystore, and returns the public key.
-    pub async fn generate_key(&mut self) -> Result<AccountPublicKey, persistent::file::Error> {
-        let key = self.0.generate_new();
+    pub async fn generate_key(&mut self) -> anyhow::Result<AccountPublicKey> {
+        let key = self.0.generate_new()?;
         self.0.persist().await?;
         Ok(key)
     }
 
     /// Generates `count` new key pairs, persists the keystore, and returns the public keys.
-    pub async fn generate_keys(
-        &mut self,
-        count: usize,
-    ) -> Result<Vec<AccountPublicKey>, persistent::file::Error> {
-        let keys: Vec<_> = std::iter::repeat_with(|| self.0.generate_new())
+    pub async fn generate_keys(&mut self, count: usize) -> anyhow::Result<Vec<AccountPublicKey>> {
+        let keys = std::iter::repeat_with(|| self.0.generate_new())
             .take(count)
-            .collect();
+            .collect::<Result<Vec<_>, _>>()?;
         self.0.persist().await?;
         Ok(keys)
     }
 
+    /// Returns the secret keys of the given owners, if the keystore has them.
+    pub fn secret_keys(&self, owners: &BTreeSet<AccountOwner>) -> Vec<AccountSecretKey> {
+        self.0
+            .keys()
+            .into_iter()
+            .filter(|(owner, _)| owners.contains(owner))
+            .filter_map(|(_, bytes)| serde_json::from_slice(&bytes).ok())
+            .collect()
+    }
+
+    /// Adds a secret key and returns its public key. The keystore must be saved afterwards.
+    pub fn add_key(&mut self, secret: AccountSecretKey) -> AccountPublicKey {
+        self.0.add_key(secret)
+    }
+
     /// Saves the keystore to disk.
     pub async fn save(&mut self) -> Result<(), persistent::file::Error> {
         self.0.persist().await
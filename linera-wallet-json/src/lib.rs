@@ -1,10 +1,14 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod address_book;
+pub mod bundle;
 pub mod display;
 pub mod keystore;
 pub mod paths;
 pub mod wallet;
 
+pub use address_book::AddressBook;
+pub use bundle::WalletBundle;
 pub use keystore::Keystore;
 pub use wallet::PersistentWallet;
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
+    collections::BTreeSet,
     iter::IntoIterator,
     sync::{Arc, RwLock},
 };
@@ -12,11 +13,18 @@ use linera_client::config::GenesisConfig;
 use linera_core::wallet::*;
 use linera_persistent::{self as persistent};
 
+use crate::{
+    address_book::AddressBook,
+    bundle::{MergeReport, WalletBundle},
+};
+
 #[derive(serde::Serialize, serde::Deserialize)]
 pub(crate) struct Data {
     pub chains: Memory,
     pub default: Arc<RwLock<Option<ChainId>>>,
     pub genesis_config: GenesisConfig,
+    #[serde(default)]
+    pub address_book: Arc<RwLock<AddressBook>>,
 }
 
 pub struct PersistentWallet(persistent::File<Data>);
@@ -133,6 +141,7 @@ impl PersistentWallet {
                 chains: Memory::default(),
                 default: Arc::new(RwLock::new(None)),
                 genesis_config,
+                address_book: Arc::default(),
             },
         )?))
     }
@@ -159,6 +168,24 @@ impl PersistentWallet {
         self.0.save()
     }
 
+    /// Returns the names of chains, owners and applications.
+    pub fn address_book(&self) -> AddressBook {
+        self.0.address_book.read().unwrap().clone()
+    }
+
+    /// Changes the address book, and saves the wallet if that succeeds.
+    pub fn update_address_book<R>(
+        &self,
+        update: impl FnOnce(&mut AddressBook) -> anyhow::Result<R>,
+    ) -> anyhow::Result<R> {
+        let outcome = {
+            let mut address_book = self.0.address_book.write().unwrap();
+            update(&mut address_book)?
+        };
+        self.0.save()?;
+        Ok(outcome)
+    }
+
     pub fn mutate<R>(
         &self,
         chain_id: ChainId,
@@ -176,6 +203,16 @@ impl PersistentWallet {
             .ok_or_else(|| anyhow::anyhow!("keypair not found for chain `{chain_id}`"))
     }
 
+    pub fn set_inbox_policy(
+        &self,
+        chain_id: ChainId,
+        policy: Option<InboxPolicy>,
+    ) -> anyhow::Result<()> {
+        self.mutate(chain_id, |chain| chain.inbox_policy = policy.clone())
+            .ok_or_else(|| anyhow::anyhow!("nonexistent chain `{chain_id}`"))??;
+        Ok(())
+    }
+
     pub fn forget_chain(&self, chain_id: ChainId) -> anyhow::Result<Chain> {
         let chain = self
             .0
@@ -186,6 +223,77 @@ impl PersistentWallet {
         Ok(chain)
     }
 
+    /// Merges the chains of a bundle into the wallet, and saves it.
+    ///
+    /// The owner of a chain is only taken from the bundle if its key is among
+    /// `owners_with_keys`. For chains that the wallet already has, the more recent state is
+    /// kept, and the wallet's owner takes precedence over the bundle's.
+    pub fn merge(
+        &self,
+        bundle: &WalletBundle,
+        owners_with_keys: &BTreeSet<AccountOwner>,
+    ) -> Result<MergeReport, persistent::file::Error> {
+        let mut report = MergeReport::default();
+        for (chain_id, chain) in &bundle.chains {
+            let mut chain = chain.clone();
+            if chain
+                .owner
+                .is_some_and(|owner| !owners_with_keys.contains(&owner))
+            {
+                chain.owner = None;
+                report.without_keys.push(*chain_id);
+            }
+            let Some(mut existing) = self.0.chains.get(*chain_id) else {
+                self.0.chains.insert(*chain_id, chain);
+                report.added.push(*chain_id);
+                continue;
+            };
+            let mut changed = false;
+            if chain.next_block_height > existing.next_block_height {
+                existing.block_hash = chain.block_hash;
+                existing.next_block_height = chain.next_block_height;
+                existing.timestamp = chain.timestamp;
+                existing.epoch = chain.epoch;
+                existing.pending_fast_proposal = chain.pending_fast_proposal;
+                changed = true;
+            }
+            match (existing.owner, chain.owner) {
+                (None, Some(owner)) => {
+                    existing.owner = Some(owner);
+                    changed = true;
+                }
+                (Some(current), Some(owner)) if current != owner => {
+                    report.conflicts.push(*chain_id);
+                }
+                _ => {}
+            }
+            if existing.inbox_policy.is_none() && chain.inbox_policy.is_some() {
+                existing.inbox_policy = chain.inbox_policy;
+                changed = true;
+            }
+            if changed {
+                self.0.chains.insert(*chain_id, existing);
+                report.updated.push(*chain_id);
+            } else {
+                report.unchanged.push(*chain_id);
+            }
+        }
+        let owned_chain_ids = bundle
+            .default
+            .into_iter()
+            .chain(report.added.iter().chain(&report.updated).copied())
+            .filter(|chain_id| {
+                self.get(*chain_id)
+                    .is_some_and(|chain| chain.owner.is_some())
+            })
+            .collect::<Vec<_>>();
+        for chain_id in owned_chain_ids {
+            self.try_set_default(chain_id);
+        }
+        self.0.save()?;
+        Ok(report)
+    }
+
     pub fn save(&self) -> Result<(), persistent::file::Error> {
         self.0.save()
     }
@@ -98,8 +98,11 @@ impl Chain {
 
     /// Gets the balance of the default chain.
     ///
+    /// If the client was created with the `lightClient` option, the chain's blocks are not
+    /// executed: the balance after the latest block is proven by a validator instead.
+    ///
     /// # Errors
-    /// If the chain couldn't be established.
+    /// If the chain couldn't be established, or no validator could prove the balance.
     pub async fn balance(&self) -> JsResult<String> {
         Ok(self.chain_client.query_balance().await?.to_string())
     }